@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use ts_rs::TS;
+
+use super::transcript;
+use crate::project::store;
+
+/// A human note attached to one canvas node, e.g. "this assumption was wrong".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct Annotation {
+    pub id: String,
+    pub node_id: String,
+    pub text: String,
+    /// Optional marker shown on the node: "wrong", "important", "question", …
+    pub flag: Option<String>,
+    /// Unix milliseconds, last edit.
+    #[ts(type = "number")]
+    pub updated_at: i64,
+}
+
+/// Next to the transcript: `<ticket_id>/<agent_id>.annotations.json`.
+pub fn path_for(agent_id: &str, ticket_id: &str) -> PathBuf {
+    transcript::path_for(agent_id, ticket_id).with_extension("annotations.json")
+}
+
+pub fn load(agent_id: &str, ticket_id: &str) -> Result<Vec<Annotation>> {
+    store::read_json(&path_for(agent_id, ticket_id))
+}
+
+fn save(agent_id: &str, ticket_id: &str, annotations: &[Annotation]) -> Result<()> {
+    store::write_json(&path_for(agent_id, ticket_id), &annotations)
+}
+
+/// Add a note, or replace the text/flag of the one with `id`.
+pub fn upsert(
+    agent_id: &str,
+    ticket_id: &str,
+    id: Option<String>,
+    node_id: &str,
+    text: String,
+    flag: Option<String>,
+) -> Result<Annotation> {
+    let mut all = load(agent_id, ticket_id)?;
+    let updated_at = chrono::Utc::now().timestamp_millis();
+    let annotation = match id {
+        Some(id) => {
+            let existing = all
+                .iter_mut()
+                .find(|a| a.id == id)
+                .with_context(|| format!("annotation '{}' not found", id))?;
+            existing.text = text;
+            existing.flag = flag;
+            existing.updated_at = updated_at;
+            existing.clone()
+        }
+        None => {
+            let annotation = Annotation {
+                id: uuid::Uuid::new_v4().to_string(),
+                node_id: node_id.to_string(),
+                text,
+                flag,
+                updated_at,
+            };
+            all.push(annotation.clone());
+            annotation
+        }
+    };
+    save(agent_id, ticket_id, &all)?;
+    Ok(annotation)
+}
+
+pub fn delete(agent_id: &str, ticket_id: &str, id: &str) -> Result<()> {
+    let mut all = load(agent_id, ticket_id)?;
+    let before = all.len();
+    all.retain(|a| a.id != id);
+    if all.len() == before {
+        anyhow::bail!("annotation '{}' not found", id);
+    }
+    save(agent_id, ticket_id, &all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_beside_the_transcript() {
+        let path = path_for("agent-1", "t-42");
+        assert!(path.ends_with("transcripts/t-42/agent-1.annotations.json"));
+    }
+}
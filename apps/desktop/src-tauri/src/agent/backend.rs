@@ -0,0 +1,621 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use super::limits::{self, ResourceLimits};
+use super::process::{CLAUDE_SETTINGS_FILE, MCP_CONFIG_FILE};
+use crate::platform::{exec, paths};
+use crate::project::settings::ProjectSettings;
+use ts_rs::TS;
+
+/// Where the claude CLI runs for a project's agents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExecutionBackend {
+    /// Directly on this machine (or in WSL on Windows).
+    #[default]
+    Host,
+    /// Inside the repo's `.devcontainer`, via the `devcontainer` CLI.
+    /// The container must be able to reach the host's MCP server at
+    /// `host.docker.internal` (automatic on Docker Desktop; needs
+    /// `--add-host=host.docker.internal:host-gateway` in `runArgs` on Linux).
+    Devcontainer,
+    /// In a throwaway container from `image`. Only the worktree and the repo's
+    /// git dir are mounted (at their host paths, so no path translation), and
+    /// the container shares the host network to reach the MCP server.
+    Docker {
+        /// Image with claude and the project toolchain installed.
+        image: String,
+        /// Extra `docker run` flags, e.g. `["--cpus", "4"]`.
+        #[serde(default)]
+        run_args: Vec<String>,
+    },
+    /// On a remote machine over SSH. The remote host needs its own clone of
+    /// the repo; each run pushes the worktree's HEAD there, runs claude in a
+    /// remote worktree with the MCP port reverse-tunnelled, and fetches the
+    /// result back into the local worktree.
+    Ssh {
+        /// `user@host`, or an alias from `~/.ssh/config`.
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+        /// Private key to authenticate with, if not the ssh default.
+        #[serde(default)]
+        identity_file: Option<String>,
+        /// Absolute path of the repo's clone on the remote host.
+        remote_repo_root: String,
+    },
+}
+
+/// A backend that has been brought up and is ready to run claude.
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// Host the agent should use to reach the MCP server.
+    pub mcp_host: String,
+    pub mcp_port: u16,
+    /// Host-side repo root and its path as seen by the agent, for translating
+    /// worktree paths. `None` when the agent sees host paths unchanged.
+    path_map: Option<(PathBuf, PathBuf)>,
+}
+
+impl Target {
+    fn host(mcp_port: u16) -> Self {
+        Target {
+            mcp_host: "127.0.0.1".to_string(),
+            mcp_port,
+            path_map: None,
+        }
+    }
+
+    /// SSE endpoint of the MCP server as reachable from the agent.
+    pub fn mcp_url(&self) -> String {
+        format!("http://{}:{}/sse", self.mcp_host, self.mcp_port)
+    }
+
+    /// Endpoint the run's guard hook asks about tool calls on.
+    pub fn hook_url(&self) -> String {
+        format!("http://{}:{}/hook", self.mcp_host, self.mcp_port)
+    }
+
+    /// Translate a host path into the path the agent process sees.
+    pub fn agent_path(&self, host_path: &Path) -> String {
+        match &self.path_map {
+            Some((host_root, agent_root)) => match host_path.strip_prefix(host_root) {
+                Ok(rel) if rel.as_os_str().is_empty() => agent_root.to_string_lossy().to_string(),
+                Ok(rel) => format!(
+                    "{}/{}",
+                    agent_root.to_string_lossy().trim_end_matches('/'),
+                    paths::to_slash(rel)
+                ),
+                Err(_) => host_path.to_string_lossy().to_string(),
+            },
+            None => host_path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// The main checkout a worktree belongs to, resolved from git's common dir.
+/// Falls back to the worktree itself for plain checkouts and non-git dirs.
+pub fn main_repo_root(worktree: &Path) -> PathBuf {
+    let mut cmd = std::process::Command::new("git");
+    cmd.args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+        .current_dir(worktree);
+    exec::output_blocking(&mut cmd, exec::LOCAL)
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| {
+            // git for Windows prints `//wsl.localhost/…` for repos inside WSL
+            let common = paths::normalize(Path::new(String::from_utf8_lossy(&o.stdout).trim()));
+            common.parent().map(Path::to_path_buf)
+        })
+        .unwrap_or_else(|| worktree.to_path_buf())
+}
+
+/// Wrap a string in POSIX single quotes for safe embedding in a shell script.
+/// Single quotes prevent ALL shell interpretation (globs, parameter expansion, etc.).
+/// A single quote inside is handled by: end quote → escaped apostrophe → reopen quote.
+pub fn sh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// The shared git dir of the repo a worktree belongs to.
+fn git_common_dir(worktree: &Path) -> PathBuf {
+    main_repo_root(worktree).join(".git")
+}
+
+/// Env vars forwarded into isolated backends on top of the run's own env, so
+/// the CLI can authenticate without mounting the host's `~/.claude`.
+const FORWARDED_ENV: &[&str] = &["ANTHROPIC_API_KEY", "ANTHROPIC_BASE_URL"];
+
+/// Settings of the project that owns `worktree`, for runs that only know
+/// their working dir (e.g. resumes).
+pub fn project_settings_for(worktree: &Path) -> ProjectSettings {
+    crate::project::settings::load_project(&main_repo_root(worktree)).unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevcontainerUp {
+    outcome: String,
+    remote_workspace_folder: Option<String>,
+}
+
+/// Parse the JSON result line `devcontainer up` prints last on stdout.
+fn parse_devcontainer_up(stdout: &str) -> Result<DevcontainerUp> {
+    let line = stdout
+        .lines()
+        .rev()
+        .find(|l| l.trim_start().starts_with('{'))
+        .context("devcontainer up printed no result")?;
+    let up: DevcontainerUp =
+        serde_json::from_str(line).context("failed to parse devcontainer up result")?;
+    if up.outcome != "success" {
+        anyhow::bail!("devcontainer up failed: {}", line);
+    }
+    Ok(up)
+}
+
+// ── SSH helpers ─────────────────────────────────────────────────────────────
+
+fn ssh_options(port: Option<u16>, identity_file: Option<&str>) -> Vec<String> {
+    let mut opts = vec!["-o".to_string(), "BatchMode=yes".to_string()];
+    if let Some(port) = port {
+        opts.push("-p".to_string());
+        opts.push(port.to_string());
+    }
+    if let Some(key) = identity_file {
+        opts.push("-i".to_string());
+        opts.push(key.to_string());
+    }
+    opts
+}
+
+/// `GIT_SSH_COMMAND` carrying the same port/key options as our ssh calls.
+fn git_ssh_command(port: Option<u16>, identity_file: Option<&str>) -> String {
+    let opts: Vec<String> = ssh_options(port, identity_file)
+        .iter()
+        .map(|o| sh_quote(o))
+        .collect();
+    format!("ssh {}", opts.join(" "))
+}
+
+/// Where a local worktree lives on the remote host.
+fn remote_worktree(remote_repo_root: &str, working_dir: &Path) -> String {
+    let name = working_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{}/.worktrees/{}", remote_repo_root.trim_end_matches('/'), name)
+}
+
+/// Paths the run writes into the worktree that must not be synced as changes.
+const SYNC_EXCLUDES: &str = "-- . ':(exclude).claude' ':(exclude).poietai-mcp.json'";
+
+/// Run a command to completion, failing with its stderr if it exits non-zero.
+async fn run_checked(cmd: &mut Command, what: &str) -> Result<Vec<u8>> {
+    let output = exec::output(cmd, exec::NETWORK)
+        .await
+        .with_context(|| format!("failed to run {}", what))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{} failed: {}", what, stderr.trim());
+    }
+    Ok(output.stdout)
+}
+
+/// Push the local worktree's HEAD to the remote clone and (re)create the
+/// remote worktree on the same branch.
+async fn ssh_prepare(
+    host: &str,
+    port: Option<u16>,
+    identity_file: Option<&str>,
+    remote_repo_root: &str,
+    working_dir: &Path,
+) -> Result<String> {
+    let remote_wt = remote_worktree(remote_repo_root, working_dir);
+    let name = working_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .context("worktree path has no directory name")?;
+    // A private ref, so the push never collides with a checked-out branch.
+    let staging = format!("refs/poietai/{}", name);
+
+    let branch = run_checked(
+        Command::new("git")
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .current_dir(working_dir),
+        "git rev-parse",
+    )
+    .await?;
+    let branch = String::from_utf8_lossy(&branch).trim().to_string();
+
+    run_checked(
+        Command::new("git")
+            .arg("push")
+            .arg("--force")
+            .arg(format!("{}:{}", host, remote_repo_root))
+            .arg(format!("HEAD:{}", staging))
+            .env("GIT_SSH_COMMAND", git_ssh_command(port, identity_file))
+            .current_dir(working_dir),
+        "git push to remote host",
+    )
+    .await?;
+
+    let root = sh_quote(remote_repo_root);
+    let wt = sh_quote(&remote_wt);
+    let script = format!(
+        "git -C {root} worktree remove --force {wt} 2>/dev/null; \
+         git -C {root} worktree prune && \
+         git -C {root} worktree add -f -B {branch} {wt} {staging}",
+        root = root,
+        wt = wt,
+        branch = sh_quote(&branch),
+        staging = sh_quote(&staging),
+    );
+    run_checked(
+        Command::new("ssh")
+            .args(ssh_options(port, identity_file))
+            .arg(host)
+            .arg(script),
+        "remote worktree setup",
+    )
+    .await?;
+
+    Ok(remote_wt)
+}
+
+/// Bash script fed to the remote shell on stdin: recreate the MCP config
+/// files, export the run's env, and exec claude. Going through stdin keeps
+/// tokens and prompts out of both machines' process lists.
+fn ssh_script(
+    remote_wt: &str,
+    working_dir: &Path,
+    claude_args: &[String],
+    env: &[(String, String)],
+) -> String {
+    let mut script = format!("set -e\ncd {}\nmkdir -p .claude\n", sh_quote(remote_wt));
+    for file in [CLAUDE_SETTINGS_FILE, MCP_CONFIG_FILE] {
+        let contents = std::fs::read_to_string(working_dir.join(file)).unwrap_or_default();
+        script.push_str(&format!(
+            "cat > {} <<'POIETAI_EOF'\n{}\nPOIETAI_EOF\n",
+            sh_quote(file),
+            contents.trim_end()
+        ));
+    }
+    script.push_str(&launch_lines(claude_args, env));
+    script
+}
+
+/// Shell lines that export `env` and exec claude, for backends that read
+/// their launch script from stdin so secrets stay out of the process list.
+fn launch_lines(claude_args: &[String], env: &[(String, String)]) -> String {
+    let mut lines = String::new();
+    for (key, value) in env {
+        lines.push_str(&format!("export {}={}\n", key, sh_quote(value)));
+    }
+    let args: Vec<String> = claude_args.iter().map(|a| sh_quote(a)).collect();
+    lines.push_str(&format!("exec claude {}\n", args.join(" ")));
+    lines
+}
+
+/// Bring the agent's commits and uncommitted edits back from the remote
+/// worktree into the local one.
+async fn ssh_finish(
+    host: &str,
+    port: Option<u16>,
+    identity_file: Option<&str>,
+    remote_wt: &str,
+    working_dir: &Path,
+) -> Result<()> {
+    run_checked(
+        Command::new("git")
+            .arg("fetch")
+            .arg(format!("{}:{}", host, remote_wt))
+            .arg("HEAD")
+            .env("GIT_SSH_COMMAND", git_ssh_command(port, identity_file))
+            .current_dir(working_dir),
+        "git fetch from remote worktree",
+    )
+    .await?;
+    run_checked(
+        Command::new("git")
+            .args(["reset", "--hard", "FETCH_HEAD"])
+            .current_dir(working_dir),
+        "git reset to remote HEAD",
+    )
+    .await?;
+
+    // `add -N` makes new files show up in the diff without staging them.
+    let diff_script = format!(
+        "cd {} && git add -A -N {excl} && git diff HEAD --binary {excl}",
+        sh_quote(remote_wt),
+        excl = SYNC_EXCLUDES,
+    );
+    let diff = run_checked(
+        Command::new("ssh")
+            .args(ssh_options(port, identity_file))
+            .arg(host)
+            .arg(diff_script),
+        "remote git diff",
+    )
+    .await?;
+    if diff.is_empty() {
+        return Ok(());
+    }
+
+    // From a file rather than stdin so the apply runs under a time limit too
+    let patch = std::env::temp_dir().join(format!("poietai-sync-{}.patch", uuid::Uuid::new_v4()));
+    tokio::fs::write(&patch, &diff)
+        .await
+        .context("failed to write remote changes")?;
+    let applied = run_checked(
+        Command::new("git")
+            .args(["apply", "--binary"])
+            .arg(&patch)
+            .current_dir(working_dir),
+        "applying remote changes",
+    )
+    .await;
+    let _ = tokio::fs::remove_file(&patch).await;
+    applied.map(|_| ())
+}
+
+// ── Backend lifecycle ───────────────────────────────────────────────────────
+
+/// Bring the backend up for a run in `working_dir`.
+pub async fn prepare(
+    backend: &ExecutionBackend,
+    working_dir: &Path,
+    mcp_port: u16,
+) -> Result<Target> {
+    match backend {
+        ExecutionBackend::Host | ExecutionBackend::Docker { .. } => Ok(Target::host(mcp_port)),
+        ExecutionBackend::Devcontainer => {
+            // Mount the main checkout so the worktree's `.git` link resolves.
+            let repo_root = main_repo_root(working_dir);
+            let mut up = Command::new("devcontainer");
+            up.arg("up").arg("--workspace-folder").arg(&repo_root);
+            let output = exec::output(&mut up, exec::NETWORK)
+                .await
+                .context("failed to run devcontainer up — is @devcontainers/cli installed?")?;
+            let up = parse_devcontainer_up(&String::from_utf8_lossy(&output.stdout))?;
+            let agent_root = up
+                .remote_workspace_folder
+                .context("devcontainer up did not report remoteWorkspaceFolder")?;
+            Ok(Target {
+                mcp_host: "host.docker.internal".to_string(),
+                mcp_port,
+                path_map: Some((repo_root, PathBuf::from(agent_root))),
+            })
+        }
+        ExecutionBackend::Ssh {
+            host,
+            port,
+            identity_file,
+            remote_repo_root,
+        } => {
+            let remote_wt =
+                ssh_prepare(host, *port, identity_file.as_deref(), remote_repo_root, working_dir)
+                    .await?;
+            // The reverse tunnel makes the MCP server local to the remote host.
+            Ok(Target {
+                mcp_host: "127.0.0.1".to_string(),
+                mcp_port,
+                path_map: Some((working_dir.to_path_buf(), PathBuf::from(remote_wt))),
+            })
+        }
+    }
+}
+
+/// Sync results back after the run. A no-op for backends that share the
+/// worktree with the host.
+pub async fn finish(backend: &ExecutionBackend, target: &Target, working_dir: &Path) -> Result<()> {
+    match backend {
+        ExecutionBackend::Ssh {
+            host,
+            port,
+            identity_file,
+            ..
+        } => {
+            let remote_wt = target.agent_path(working_dir);
+            ssh_finish(host, *port, identity_file.as_deref(), &remote_wt, working_dir).await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Build the command that runs `claude <args>` in `working_dir` on the backend,
+/// plus a script to write to its stdin, if the backend needs one.
+/// The caller still sets the host-side working dir and env on the result.
+pub fn command(
+    backend: &ExecutionBackend,
+    target: &Target,
+    working_dir: &Path,
+    claude_args: &[String],
+    env: &[(String, String)],
+    limits: &ResourceLimits,
+) -> (Command, Option<String>) {
+    match backend {
+        ExecutionBackend::Host => {
+            let mut c = Command::new("claude");
+            c.args(claude_args);
+            (c, None)
+        }
+        ExecutionBackend::Devcontainer => {
+            let mut c = Command::new("devcontainer");
+            c.arg("exec")
+                .arg("--workspace-folder")
+                .arg(main_repo_root(working_dir))
+                .args(["sh", "-s"]);
+            let script = format!(
+                "set -e\ncd {}\n{}",
+                sh_quote(&target.agent_path(working_dir)),
+                launch_lines(claude_args, env)
+            );
+            (c, Some(script))
+        }
+        ExecutionBackend::Docker { image, run_args } => {
+            let wt = working_dir.to_string_lossy().to_string();
+            let git_dir = git_common_dir(working_dir).to_string_lossy().to_string();
+            let mut c = Command::new("docker");
+            c.args(["run", "--rm", "-i", "--network", "host"])
+                .arg("-v")
+                .arg(format!("{}:{}", wt, wt))
+                .arg("-v")
+                .arg(format!("{}:{}", git_dir, git_dir))
+                .arg("-w")
+                .arg(&wt);
+            // `-e NAME` without a value makes docker copy it from its own
+            // environment, keeping tokens out of the process list.
+            for (key, _) in env {
+                c.arg("-e").arg(key);
+            }
+            for key in FORWARDED_ENV {
+                if std::env::var_os(key).is_some() {
+                    c.arg("-e").arg(key);
+                }
+            }
+            c.args(limits::docker_args(limits))
+                .args(run_args)
+                .arg(image)
+                .arg("claude")
+                .args(claude_args);
+            (c, None)
+        }
+        ExecutionBackend::Ssh {
+            host,
+            port,
+            identity_file,
+            ..
+        } => {
+            let mut c = Command::new("ssh");
+            c.args(ssh_options(*port, identity_file.as_deref()))
+                .arg("-R")
+                .arg(format!("{}:127.0.0.1:{}", target.mcp_port, target.mcp_port))
+                .arg(host)
+                .args(["bash", "-l", "-s"]);
+            let script = ssh_script(&target.agent_path(working_dir), working_dir, claude_args, env);
+            (c, Some(script))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_defaults_to_host() {
+        let backend: ExecutionBackend = serde_json::from_str(r#"{"kind":"host"}"#).unwrap();
+        assert_eq!(backend, ExecutionBackend::Host);
+        assert_eq!(ExecutionBackend::default(), ExecutionBackend::Host);
+    }
+
+    #[test]
+    fn docker_mounts_worktree_and_passes_env_by_name() {
+        let backend = ExecutionBackend::Docker {
+            image: "ghcr.io/acme/agent:latest".to_string(),
+            run_args: vec!["--cpus".to_string(), "2".to_string()],
+        };
+        let wt = std::env::temp_dir();
+        let env = vec![("GH_TOKEN".to_string(), "secret".to_string())];
+        let (cmd, _) = command(&backend, &Target::host(8080), &wt, &["--print".to_string()], &env, &ResourceLimits::default());
+        let args: Vec<String> = cmd
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+
+        let wt = wt.to_string_lossy().to_string();
+        assert!(args.contains(&format!("{}:{}", wt, wt)));
+        assert!(args.contains(&"GH_TOKEN".to_string()));
+        assert!(!args.iter().any(|a| a.contains("secret")));
+        let image_pos = args.iter().position(|a| a == "ghcr.io/acme/agent:latest").unwrap();
+        assert_eq!(args[image_pos - 1], "2");
+        assert_eq!(args[image_pos + 1], "claude");
+    }
+
+    #[test]
+    fn devcontainer_sends_env_on_stdin() {
+        let wt = std::env::temp_dir();
+        let env = vec![("ANTHROPIC_API_KEY".to_string(), "sk-secret".to_string())];
+        let (cmd, script) = command(&ExecutionBackend::Devcontainer, &Target::host(8080), &wt, &["--print".to_string()], &env, &ResourceLimits::default());
+        let args: Vec<String> = cmd
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+
+        assert!(!args.iter().any(|a| a.contains("sk-secret")));
+        let script = script.unwrap();
+        assert!(script.contains("export ANTHROPIC_API_KEY='sk-secret'"));
+        assert!(script.ends_with("exec claude '--print'\n"));
+    }
+
+    #[test]
+    fn devcontainer_paths_are_translated() {
+        let target = Target {
+            mcp_host: "host.docker.internal".to_string(),
+            mcp_port: 8080,
+            path_map: Some((PathBuf::from("/home/u/repo"), PathBuf::from("/workspaces/repo"))),
+        };
+        assert_eq!(
+            target.agent_path(Path::new("/home/u/repo/.worktrees/t1")),
+            "/workspaces/repo/.worktrees/t1"
+        );
+        assert_eq!(target.agent_path(Path::new("/home/u/repo")), "/workspaces/repo");
+        assert_eq!(target.agent_path(Path::new("/elsewhere")), "/elsewhere");
+    }
+
+    #[test]
+    fn parses_devcontainer_up_output() {
+        let stdout = "[1 ms] @devcontainers/cli 0.60.0\n{\"outcome\":\"success\",\"containerId\":\"abc\",\"remoteUser\":\"node\",\"remoteWorkspaceFolder\":\"/workspaces/repo\"}\n";
+        let up = parse_devcontainer_up(stdout).unwrap();
+        assert_eq!(up.remote_workspace_folder.as_deref(), Some("/workspaces/repo"));
+
+        let failed = "{\"outcome\":\"error\",\"message\":\"no config\"}";
+        assert!(parse_devcontainer_up(failed).is_err());
+    }
+
+    #[test]
+    fn sh_quote_escapes_single_quotes() {
+        assert_eq!(sh_quote("it's fine"), r"'it'\''s fine'");
+    }
+
+    #[test]
+    fn ssh_options_include_port_and_key() {
+        let opts = ssh_options(Some(2222), Some("/home/u/.ssh/build"));
+        assert_eq!(opts, vec!["-o", "BatchMode=yes", "-p", "2222", "-i", "/home/u/.ssh/build"]);
+        assert_eq!(
+            git_ssh_command(None, None),
+            "ssh '-o' 'BatchMode=yes'"
+        );
+    }
+
+    #[test]
+    fn ssh_script_keeps_secrets_quoted() {
+        let wt = std::env::temp_dir().join(format!("poietai-ssh-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(wt.join(".claude")).unwrap();
+        std::fs::write(wt.join(MCP_CONFIG_FILE), "{\"mcpServers\":{}}").unwrap();
+
+        let env = vec![("GH_TOKEN".to_string(), "tok'en".to_string())];
+        let args = vec!["--print".to_string(), "fix the bug; rm -rf /".to_string()];
+        let script = ssh_script("/srv/repo/.worktrees/t1", &wt, &args, &env);
+
+        assert!(script.contains("cd '/srv/repo/.worktrees/t1'"));
+        assert!(script.contains(r"export GH_TOKEN='tok'\''en'"));
+        assert!(script.contains("{\"mcpServers\":{}}"));
+        assert!(script.ends_with("exec claude '--print' 'fix the bug; rm -rf /'\n"));
+        let _ = std::fs::remove_dir_all(&wt);
+    }
+
+    #[test]
+    fn remote_worktree_mirrors_local_name() {
+        assert_eq!(
+            remote_worktree("/srv/repo/", Path::new("/home/u/repo/.worktrees/ticket-1")),
+            "/srv/repo/.worktrees/ticket-1"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::guard;
+use super::state::AgentStatus;
+use crate::project::store;
+use crate::text::truncate_chars;
+
+/// A kind of tool call a human signs off on before the agent may make it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct Checkpoint {
+    /// Shown to the user, e.g. "Database migrations".
+    pub name: String,
+    /// Tools it applies to, e.g. ["Bash"] or ["Edit", "Write"].
+    pub tools: Vec<String>,
+    /// Text that, found in the call's command or the path it writes, stops
+    /// the run.
+    pub patterns: Vec<String>,
+}
+
+/// A run stopped at a checkpoint, awaiting sign-off.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct CheckpointHit {
+    pub id: String,
+    pub agent_id: String,
+    pub ticket_id: String,
+    /// The checkpoint's name.
+    pub checkpoint: String,
+    pub tool_name: String,
+    /// The command or path that matched.
+    pub detail: String,
+    /// The session an approval resumes.
+    pub session_id: Option<String>,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub reached_at: i64,
+    /// Approved checkpoints don't stop the agent again on this ticket.
+    pub approved: bool,
+}
+
+/// Checkpoints offered when setting up a project.
+pub fn presets() -> Vec<Checkpoint> {
+    let checkpoint = |name: &str, tools: &[&str], patterns: &[&str]| Checkpoint {
+        name: name.to_string(),
+        tools: tools.iter().map(|t| t.to_string()).collect(),
+        patterns: patterns.iter().map(|p| p.to_string()).collect(),
+    };
+    vec![
+        checkpoint(
+            "Install scripts",
+            &["Bash"],
+            &[
+                "npm install",
+                "npm i ",
+                "npm ci",
+                "pnpm install",
+                "pnpm add",
+                "yarn add",
+                "yarn install",
+                "pip install",
+                "cargo install",
+            ],
+        ),
+        checkpoint(
+            "Database migrations",
+            &["Bash", "Edit", "Write"],
+            &[
+                "migrate",
+                "migrations/",
+                "diesel migration",
+                "alembic upgrade",
+            ],
+        ),
+        checkpoint(
+            "Force-push",
+            &["Bash"],
+            &["push --force", "push -f", "push --force-with-lease"],
+        ),
+    ]
+}
+
+impl Checkpoint {
+    /// Whether a tool call stops at this checkpoint. Patterns are matched
+    /// against the call's [`detail`]: a command or a path, never the content
+    /// being written.
+    pub fn matches(&self, tool_name: &str, detail: &str) -> bool {
+        self.tools.iter().any(|t| t == tool_name)
+            && self.patterns.iter().any(|p| !p.is_empty() && detail.contains(p.as_str()))
+    }
+}
+
+/// The command or path a tool call acts on, for checkpoints to match and the
+/// user to judge: a Bash call's command, the repo-relative path an edit
+/// writes, or else the call's input. `cwd` and `root` are as the agent sees
+/// them.
+pub fn detail(tool_name: &str, input: &serde_json::Value, cwd: &Path, root: &Path) -> String {
+    if let Some(command) = input.get("command").and_then(|c| c.as_str()) {
+        return command.to_string();
+    }
+    if let Some(path) = guard::write_paths(tool_name, input, cwd, root).into_iter().next() {
+        return path.as_str().to_string();
+    }
+    truncate_chars(&input.to_string(), 300).to_string()
+}
+
+// Serializes read-modify-write cycles on the hits file.
+static HITS: Mutex<()> = Mutex::new(());
+
+/// `$HOME/.poietai/checkpoints.json`.
+fn path() -> PathBuf {
+    store::app_dir().join("checkpoints.json")
+}
+
+fn edit<R>(f: impl FnOnce(&mut Vec<CheckpointHit>) -> R) -> Result<R> {
+    let _guard = HITS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut hits: Vec<CheckpointHit> = store::read_json(&path())?;
+    let result = f(&mut hits);
+    store::write_json(&path(), &hits)?;
+    Ok(result)
+}
+
+fn load() -> Vec<CheckpointHit> {
+    let _guard = HITS.lock().unwrap_or_else(|e| e.into_inner());
+    store::read_json(&path()).unwrap_or_default()
+}
+
+/// Record a run stopping at a checkpoint.
+pub fn reach(hit: CheckpointHit) -> Result<()> {
+    edit(|hits| {
+        hits.retain(|h| {
+            h.approved
+                || !(h.agent_id == hit.agent_id
+                    && h.ticket_id == hit.ticket_id
+                    && h.checkpoint == hit.checkpoint)
+        });
+        hits.push(hit);
+    })
+}
+
+/// Checkpoints awaiting sign-off, oldest first.
+pub fn pending() -> Vec<CheckpointHit> {
+    load().into_iter().filter(|h| !h.approved).collect()
+}
+
+/// Whether the user signed off on `checkpoint` for `agent_id` on `ticket_id`.
+pub fn is_approved(agent_id: &str, ticket_id: &str, checkpoint: &str) -> bool {
+    load().iter().any(|h| {
+        h.approved && h.agent_id == agent_id && h.ticket_id == ticket_id && h.checkpoint == checkpoint
+    })
+}
+
+/// What a run of `agent_id` on `ticket_id` that started at `since` (Unix
+/// seconds) and ended with an error leaves the agent as: waiting when that
+/// run stopped for sign-off, at a checkpoint or a protected path; blocked
+/// otherwise. Requests left over from earlier runs don't count.
+pub fn status_after_failure(agent_id: &str, ticket_id: &str, since: i64) -> AgentStatus {
+    let stopped_at_checkpoint = pending()
+        .iter()
+        .any(|h| h.agent_id == agent_id && h.ticket_id == ticket_id && h.reached_at >= since);
+    let stopped_at_protected_path = guard::pending_edits()
+        .unwrap_or_default()
+        .iter()
+        .any(|e| e.agent_id == agent_id && e.ticket_id == ticket_id && e.requested_at >= since);
+    if stopped_at_checkpoint || stopped_at_protected_path {
+        AgentStatus::WaitingForUser
+    } else {
+        AgentStatus::Blocked
+    }
+}
+
+/// Sign off on a pending checkpoint, returning it.
+pub fn approve(id: &str) -> Result<Option<CheckpointHit>> {
+    edit(|hits| {
+        let hit = hits.iter_mut().find(|h| h.id == id && !h.approved)?;
+        hit.approved = true;
+        Some(hit.clone())
+    })
+}
+
+/// Drop a pending checkpoint, returning it.
+pub fn reject(id: &str) -> Result<Option<CheckpointHit>> {
+    edit(|hits| {
+        let index = hits.iter().position(|h| h.id == id && !h.approved)?;
+        Some(hits.remove(index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn presets_match_their_tool_calls() {
+        let presets = presets();
+        let (install, migrations, force_push) = (&presets[0], &presets[1], &presets[2]);
+        let root = Path::new("/wt");
+        let detail = |tool: &str, input: serde_json::Value| detail(tool, &input, root, root);
+
+        assert!(install.matches("Bash", &detail("Bash", json!({"command": "npm install left-pad"}))));
+        assert!(!install.matches("Bash", &detail("Bash", json!({"command": "npm test"}))));
+        let migration = detail("Write", json!({"file_path": "/wt/db/migrations/002.sql", "content": ""}));
+        assert_eq!(migration, "db/migrations/002.sql");
+        assert!(migrations.matches("Write", &migration));
+        assert!(!migrations.matches("Read", &migration));
+        assert!(force_push.matches("Bash", &detail("Bash", json!({"command": "git push -f origin HEAD"}))));
+        assert!(!force_push.matches("Bash", &detail("Bash", json!({"command": "git push origin HEAD"}))));
+    }
+
+    #[test]
+    fn written_content_and_paths_around_the_call_are_not_matched() {
+        let migrations = &presets()[1];
+        // A worktree path containing a pattern mustn't trip every call
+        let root = Path::new("/home/dev/migrate-tool");
+        let edit = json!({
+            "file_path": "/home/dev/migrate-tool/README.md",
+            "old_string": "a",
+            "new_string": "run the migrate command first"
+        });
+        let detail = detail("Edit", &edit, root, root);
+        assert_eq!(detail, "README.md");
+        assert!(!migrations.matches("Edit", &detail));
+        assert!(migrations.matches("Bash", "diesel migration run"));
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::Instant;
+use ts_rs::TS;
+
+use super::events::AgentEvent;
+
+/// Merge runs of Text (or Thinking) events into one canvas node.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct CoalesceConfig {
+    /// How long to hold an event for followers to merge into it. Also the
+    /// most a merged event is delayed.
+    #[ts(type = "number")]
+    pub window_ms: u64,
+}
+
+impl Default for CoalesceConfig {
+    fn default() -> Self {
+        CoalesceConfig { window_ms: 1500 }
+    }
+}
+
+/// Holds the latest Text/Thinking event while consecutive ones of the same
+/// kind arrive within the window, appending their text to it.
+pub struct Coalescer {
+    window: Option<Duration>,
+    held: Option<(AgentEvent, Instant)>,
+}
+
+impl Coalescer {
+    /// A coalescer that passes everything straight through when `config` is None.
+    pub fn new(config: Option<&CoalesceConfig>) -> Self {
+        Coalescer {
+            window: config.map(|c| Duration::from_millis(c.window_ms)),
+            held: None,
+        }
+    }
+
+    /// The events ready to publish once `event` arrives at `now`, in order.
+    pub fn push(&mut self, event: AgentEvent, now: Instant) -> Vec<AgentEvent> {
+        let Some(window) = self.window else {
+            return vec![event];
+        };
+        let mut ready = Vec::new();
+        if let Some((mut held, since)) = self.held.take() {
+            if now.duration_since(since) <= window && merge(&mut held, &event) {
+                self.held = Some((held, since));
+                return ready;
+            }
+            ready.push(held);
+        }
+        if matches!(event, AgentEvent::Text { .. } | AgentEvent::Thinking { .. }) {
+            self.held = Some((event, now));
+        } else {
+            ready.push(event);
+        }
+        ready
+    }
+
+    /// When the held event must be published, if one is held.
+    pub fn deadline(&self) -> Option<Instant> {
+        Some(self.held.as_ref()?.1 + self.window?)
+    }
+
+    /// Release the held event.
+    pub fn flush(&mut self) -> Option<AgentEvent> {
+        self.held.take().map(|(event, _)| event)
+    }
+}
+
+/// Append `next` to `held` when they're the same kind of prose.
+fn merge(held: &mut AgentEvent, next: &AgentEvent) -> bool {
+    match (held, next) {
+        (AgentEvent::Text { text }, AgentEvent::Text { text: more })
+        | (AgentEvent::Thinking { thinking: text }, AgentEvent::Thinking { thinking: more }) => {
+            text.push_str("\n\n");
+            text.push_str(more);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> AgentEvent {
+        AgentEvent::Text { text: s.to_string() }
+    }
+
+    #[test]
+    fn merges_consecutive_text_within_the_window() {
+        let mut c = Coalescer::new(Some(&CoalesceConfig::default()));
+        let start = Instant::now();
+        assert!(c.push(text("one"), start).is_empty());
+        assert!(c.push(text("two"), start + Duration::from_millis(100)).is_empty());
+        assert_eq!(c.deadline(), Some(start + Duration::from_millis(1500)));
+
+        let tool = AgentEvent::ToolUse {
+            id: "tu_1".to_string(),
+            tool_name: "Read".to_string(),
+            tool_input: serde_json::json!({}),
+            summary: None,
+        };
+        let ready = c.push(tool, start + Duration::from_millis(200));
+        assert!(matches!(&ready[0], AgentEvent::Text { text } if text == "one\n\ntwo"));
+        assert!(matches!(ready[1], AgentEvent::ToolUse { .. }));
+        assert!(c.flush().is_none());
+    }
+
+    #[test]
+    fn starts_a_new_node_after_the_window_or_a_kind_change() {
+        let mut c = Coalescer::new(Some(&CoalesceConfig { window_ms: 50 }));
+        let start = Instant::now();
+        c.push(text("one"), start);
+        assert_eq!(c.push(text("late"), start + Duration::from_millis(80)).len(), 1);
+        let thinking = AgentEvent::Thinking { thinking: "hmm".to_string() };
+        assert_eq!(c.push(thinking, start + Duration::from_millis(90)).len(), 1);
+        assert!(matches!(c.flush(), Some(AgentEvent::Thinking { .. })));
+    }
+
+    #[test]
+    fn passes_through_when_disabled() {
+        let mut c = Coalescer::new(None);
+        assert_eq!(c.push(text("one"), Instant::now()).len(), 1);
+        assert_eq!(c.deadline(), None);
+    }
+}
@@ -0,0 +1,289 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use ts_rs::TS;
+
+use super::history::{local_day, DateRange};
+use crate::project::{settings, store};
+
+/// One finished run's spend, appended to `$HOME/.poietai/costs.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct CostEntry {
+    /// Unix seconds the run finished.
+    #[ts(type = "number")]
+    pub at: i64,
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub cost_usd: f64,
+}
+
+/// Spending caps across all projects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct BudgetConfig {
+    pub daily_usd: Option<f64>,
+    /// Weeks start Monday, local time.
+    pub weekly_usd: Option<f64>,
+    /// Also stop multi-phase runs at their next phase boundary once over
+    /// budget, instead of letting them finish the ticket.
+    pub pause_running: bool,
+}
+
+/// Payload for `budget-exceeded`.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct BudgetExceededPayload {
+    /// "daily" or "weekly".
+    pub period: String,
+    pub spent_usd: f64,
+    pub cap_usd: f64,
+}
+
+impl BudgetExceededPayload {
+    pub fn message(&self) -> String {
+        format!(
+            "{} budget of ${:.2} exceeded (${:.2} spent) — new runs are paused",
+            self.period, self.cap_usd, self.spent_usd
+        )
+    }
+}
+
+/// Where a crossed cap is announced; set once at startup.
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Serializes recording a run against the budget check that follows it.
+static LEDGER: Mutex<()> = Mutex::new(());
+
+/// Announce crossed caps through `app`. Until this is called they are only
+/// logged.
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+pub fn ledger_path() -> PathBuf {
+    store::app_dir().join("costs.jsonl")
+}
+
+/// Every recorded run, oldest first. Unparseable lines are skipped.
+pub fn load() -> Result<Vec<CostEntry>> {
+    let contents = match std::fs::read_to_string(ledger_path()) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).context("failed to read cost ledger"),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn period_starts(now: DateTime<Local>) -> (i64, i64) {
+    let midnight = now.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default();
+    let monday = midnight - Duration::days(now.weekday().num_days_from_monday() as i64);
+    let local = |naive| {
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|dt| dt.timestamp())
+            .unwrap_or(0)
+    };
+    (local(midnight), local(monday))
+}
+
+/// The first cap `entries` exceed as of `now`, daily before weekly.
+pub fn check(
+    config: &BudgetConfig,
+    entries: &[CostEntry],
+    now: DateTime<Local>,
+) -> Option<BudgetExceededPayload> {
+    let (day_start, week_start) = period_starts(now);
+    let spent_since = |start: i64| -> f64 {
+        entries.iter().filter(|e| e.at >= start).map(|e| e.cost_usd).sum()
+    };
+    [("daily", config.daily_usd, day_start), ("weekly", config.weekly_usd, week_start)]
+        .into_iter()
+        .find_map(|(period, cap, start)| {
+            let cap = cap?;
+            let spent = spent_since(start);
+            (spent >= cap).then(|| BudgetExceededPayload {
+                period: period.to_string(),
+                spent_usd: spent,
+                cap_usd: cap,
+            })
+        })
+}
+
+fn budget() -> Option<BudgetConfig> {
+    settings::load_app().ok().and_then(|s| s.budget)
+}
+
+/// Whether a cap is currently exceeded. New runs should be refused when Some.
+pub fn over_budget() -> Option<BudgetExceededPayload> {
+    let config = budget()?;
+    check(&config, &load().unwrap_or_default(), Local::now())
+}
+
+/// Like [`over_budget`], but only when the user asked for running tickets to
+/// stop at their next phase boundary too.
+pub fn should_pause() -> Option<BudgetExceededPayload> {
+    budget().filter(|c| c.pause_running)?;
+    over_budget()
+}
+
+/// Append a finished run — an agent run or a side run such as an estimate —
+/// to the ledger. When it's the one that pushes spend over a cap, emit
+/// `budget-exceeded` and raise an OS notification, once.
+pub fn record(entry: CostEntry) {
+    let exceeded = {
+        // Runs finishing together must each see the other's spend, or the
+        // cap is announced twice or not at all
+        let _guard = LEDGER.lock().unwrap_or_else(|e| e.into_inner());
+        let before = load().unwrap_or_default();
+        if let Err(e) = store::append_jsonl(&ledger_path(), &entry) {
+            warn!("[costs::record] failed to record run cost: {:#}", e);
+        }
+        crossed(&before, entry)
+    };
+    let Some(exceeded) = exceeded else {
+        return;
+    };
+    warn!("[costs::record] {}", exceeded.message());
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let _ = app.emit("budget-exceeded", &exceeded);
+    let shown = app
+        .notification()
+        .builder()
+        .title("Budget exceeded")
+        .body(exceeded.message())
+        .show();
+    if let Err(e) = shown {
+        warn!("[costs::record] failed to show notification: {}", e);
+    }
+}
+
+/// The cap `entry` pushes spend over, if it wasn't over already.
+fn crossed(before: &[CostEntry], entry: CostEntry) -> Option<BudgetExceededPayload> {
+    let config = budget()?;
+    let now = Local::now();
+    let was_over = check(&config, before, now).is_some();
+    let mut after = before.to_vec();
+    after.push(entry);
+    check(&config, &after, now).filter(|_| !was_over)
+}
+
+/// Row key for `export_costs`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Agent,
+    Ticket,
+    Day,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Spend in `range`, by the local day each run finished, as CSV rows of
+/// `group_by` key, run count and total cost. Rows are sorted by key.
+pub fn to_csv(entries: &[CostEntry], range: &DateRange, group_by: GroupBy) -> Result<String> {
+    let (from, to) = range.days()?;
+    let mut groups: BTreeMap<String, (u32, f64)> = BTreeMap::new();
+    for entry in entries {
+        let day = local_day(entry.at);
+        if !(from..=to).contains(&day) {
+            continue;
+        }
+        let key = match group_by {
+            GroupBy::Agent => entry.agent_id.clone(),
+            GroupBy::Ticket => entry.ticket_id.clone(),
+            GroupBy::Day => day.format("%Y-%m-%d").to_string(),
+        };
+        let group = groups.entry(key).or_default();
+        group.0 += 1;
+        group.1 += entry.cost_usd;
+    }
+    let header = match group_by {
+        GroupBy::Agent => "agent_id",
+        GroupBy::Ticket => "ticket_id",
+        GroupBy::Day => "date",
+    };
+    let mut csv = format!("{},runs,cost_usd\n", header);
+    for (key, (runs, cost)) in groups {
+        csv.push_str(&format!("{},{},{:.4}\n", csv_field(&key), runs, cost));
+    }
+    Ok(csv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spent(at: DateTime<Local>, cost_usd: f64) -> CostEntry {
+        CostEntry {
+            at: at.timestamp(),
+            agent_id: "a".to_string(),
+            ticket_id: "t".to_string(),
+            cost_usd,
+        }
+    }
+
+    #[test]
+    fn daily_cap_counts_only_today() {
+        let now = Local::now();
+        let config = BudgetConfig {
+            daily_usd: Some(5.0),
+            ..Default::default()
+        };
+        let yesterday = spent(now - Duration::days(1) - Duration::hours(1), 10.0);
+        assert_eq!(check(&config, std::slice::from_ref(&yesterday), now), None);
+
+        let exceeded = check(&config, &[yesterday, spent(now, 5.5)], now).unwrap();
+        assert_eq!(exceeded.period, "daily");
+        assert_eq!(exceeded.spent_usd, 5.5);
+    }
+
+    #[test]
+    fn weekly_cap_sums_the_week() {
+        let now = Local::now();
+        let config = BudgetConfig {
+            daily_usd: Some(100.0),
+            weekly_usd: Some(3.0),
+            ..Default::default()
+        };
+        let entries = vec![spent(now, 2.0), spent(now, 1.5)];
+        assert_eq!(check(&config, &entries, now).unwrap().period, "weekly");
+    }
+
+    #[test]
+    fn csv_groups_spend_within_the_range() {
+        let day = |d: u32, h: u32| Local.with_ymd_and_hms(2026, 3, d, h, 0, 0).unwrap();
+        let mut entries = vec![spent(day(2, 9), 1.0), spent(day(2, 15), 0.25), spent(day(9, 9), 7.0)];
+        entries[1].ticket_id = "fix, \"now\"".to_string();
+        let range = DateRange {
+            from: "2026-03-01".to_string(),
+            to: "2026-03-05".to_string(),
+        };
+        assert_eq!(to_csv(&entries, &range, GroupBy::Agent).unwrap(), "agent_id,runs,cost_usd\na,2,1.2500\n");
+        assert_eq!(
+            to_csv(&entries, &range, GroupBy::Ticket).unwrap(),
+            "ticket_id,runs,cost_usd\n\"fix, \"\"now\"\"\",1,0.2500\nt,1,1.0000\n"
+        );
+        assert_eq!(to_csv(&entries, &range, GroupBy::Day).unwrap(), "date,runs,cost_usd\n2026-03-02,2,1.2500\n");
+    }
+}
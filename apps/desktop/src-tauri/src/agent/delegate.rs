@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::session;
+use super::state::AgentState;
+use crate::project::store;
+
+/// Offer `ask_human` questions to a senior agent before interrupting the human.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct DelegationConfig {
+    /// The agent (normally a staff engineer) that gets first look at questions.
+    pub senior_agent_id: String,
+    /// Turn cap for the consult run.
+    pub max_turns: u32,
+    /// Give up and escalate to the human after this long.
+    #[ts(type = "number")]
+    pub timeout_secs: u64,
+}
+
+impl Default for DelegationConfig {
+    fn default() -> Self {
+        DelegationConfig {
+            senior_agent_id: String::new(),
+            max_turns: 8,
+            timeout_secs: 180,
+        }
+    }
+}
+
+/// What the senior agent said, parsed from the final line of its reply.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct SeniorAnswer {
+    pub confident: bool,
+    pub answer: String,
+}
+
+/// Payload for `agent-question-delegated`, emitted when a senior agent
+/// answered in place of the human. Also appended to the project's
+/// `.poietai/delegated-answers.jsonl`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DelegatedAnswerPayload {
+    pub agent_id: String,
+    pub senior_agent_id: String,
+    pub question: String,
+    pub answer: String,
+}
+
+fn senior_system_prompt(senior: &AgentState) -> String {
+    format!(
+        "You are {}, a staff engineer on this project. A teammate is blocked on a question \
+         and you get the first chance to answer it before it goes to a human. You can read \
+         the repository but must not change anything.\n\n\
+         Only answer when the codebase, its docs, or its history settle the question. \
+         Product decisions, priorities, credentials, and anything the user would want a say \
+         in are not yours to decide — mark those not confident.\n\n\
+         End your reply with a single line of JSON and nothing after it:\n\
+         {{\"confident\": true|false, \"answer\": \"<the answer for your teammate>\"}}",
+        senior.name
+    )
+}
+
+fn senior_prompt(asker: &str, question: &str) -> String {
+    format!("{} asks:\n\n{}", asker, question)
+}
+
+/// Pull the verdict out of the senior's final message; anything unparseable
+/// counts as not confident.
+pub fn parse_reply(text: &str) -> Option<SeniorAnswer> {
+    serde_json::from_str(session::json_object(text)?).ok()
+}
+
+/// Run a short read-only claude session as `senior` in `working_dir` and
+/// return its verdict. Timeouts and failures are errors — the caller
+/// escalates to the human either way.
+pub async fn consult(
+    config: &DelegationConfig,
+    senior: &AgentState,
+    asker: &str,
+    working_dir: &Path,
+    question: &str,
+) -> Result<Option<SeniorAnswer>> {
+    info!(
+        "[delegate::consult] senior={} asker={} dir={:?}",
+        senior.id, asker, working_dir
+    );
+    let text = session::ask_read_only(
+        &senior.id,
+        "",
+        working_dir,
+        &senior_system_prompt(senior),
+        &senior_prompt(asker, question),
+        config.max_turns,
+        Duration::from_secs(config.timeout_secs),
+    )
+    .await
+    .context("senior agent run failed")?;
+    Ok(parse_reply(&text))
+}
+
+/// Record a delegated answer so the human can audit what was decided for them.
+pub fn log_answer(project_root: &Path, payload: &DelegatedAnswerPayload) -> Result<()> {
+    store::append_jsonl(
+        &store::project_dir(project_root).join("delegated-answers.jsonl"),
+        payload,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trailing_verdict() {
+        let text = "Checked src/db.rs — we use sqlx everywhere.\n\
+                    {\"confident\": true, \"answer\": \"Use sqlx, see src/db.rs\"}";
+        assert_eq!(
+            parse_reply(text),
+            Some(SeniorAnswer {
+                confident: true,
+                answer: "Use sqlx, see src/db.rs".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_fields_in_any_order() {
+        let text = "{\"answer\": \"Ask the user\", \"confident\": false}";
+        assert_eq!(parse_reply(text).map(|a| a.confident), Some(false));
+    }
+
+    #[test]
+    fn missing_or_broken_verdict_is_none() {
+        assert_eq!(parse_reply("I think it's fine?"), None);
+        assert_eq!(parse_reply("{\"confident\": tru"), None);
+    }
+}
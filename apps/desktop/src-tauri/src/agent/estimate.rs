@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::session;
+use crate::project::store;
+use crate::project::tickets::TicketRecord;
+
+const MAX_TURNS: u32 = 12;
+const TIMEOUT: Duration = Duration::from_secs(240);
+
+const SYSTEM_PROMPT: &str = "You are estimating a ticket before anyone starts on it. Read enough \
+    of the repository to judge the work, but do not plan it in detail and do not change anything.\n\n\
+    End your reply with a single JSON object and nothing after it:\n\
+    {\"complexity\": <1-10>, \"files\": [\"<path likely touched>\", ...], \
+    \"risks\": [\"<risk or unknown>\", ...], \"summary\": \"<one or two sentences>\"}";
+
+/// Result of a quick read-only analysis of a ticket, shown on the board.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct TicketEstimate {
+    pub ticket_id: String,
+    /// 1 (trivial) to 10 (very large).
+    pub complexity: u32,
+    pub files: Vec<String>,
+    pub risks: Vec<String>,
+    pub summary: String,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub estimated_at: i64,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    complexity: u32,
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(default)]
+    risks: Vec<String>,
+    #[serde(default)]
+    summary: String,
+}
+
+/// `<project>/.poietai/estimates.json`, keyed by ticket id.
+pub fn path(project_root: &Path) -> PathBuf {
+    store::project_dir(project_root).join("estimates.json")
+}
+
+pub fn load_all(project_root: &Path) -> Result<HashMap<String, TicketEstimate>> {
+    store::read_json(&path(project_root))
+}
+
+fn save(project_root: &Path, estimate: &TicketEstimate) -> Result<()> {
+    let mut all = load_all(project_root)?;
+    all.insert(estimate.ticket_id.clone(), estimate.clone());
+    store::write_json(&path(project_root), &all)
+}
+
+pub fn parse_reply(ticket_id: &str, text: &str) -> Result<TicketEstimate> {
+    let json = session::json_object(text).context("estimate reply had no JSON")?;
+    let reply: Reply = serde_json::from_str(json).context("estimate reply was malformed")?;
+    Ok(TicketEstimate {
+        ticket_id: ticket_id.to_string(),
+        complexity: reply.complexity.clamp(1, 10),
+        files: reply.files,
+        risks: reply.risks,
+        summary: reply.summary,
+        estimated_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// Analyse `ticket` against the project's main checkout and store the result.
+pub async fn run(project_root: &Path, ticket: &TicketRecord) -> Result<TicketEstimate> {
+    info!("[estimate::run] ticket={} root={:?}", ticket.id, project_root);
+    let prompt = format!("Estimate this ticket:\n\n{}", ticket.as_prompt());
+    let text = session::ask_read_only("", &ticket.id, project_root, SYSTEM_PROMPT, &prompt, MAX_TURNS, TIMEOUT)
+        .await
+        .context("estimate run failed")?;
+    let estimate = parse_reply(&ticket.id, &text)?;
+    save(project_root, &estimate)?;
+    Ok(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trailing_estimate() {
+        let text = "Looked at src/billing.\n{\"complexity\": 14, \"files\": [\"src/billing/retry.rs\"], \
+                    \"risks\": [\"no tests for backoff\"], \"summary\": \"Small change.\"}";
+        let estimate = parse_reply("t-1", text).unwrap();
+        assert_eq!(estimate.complexity, 10);
+        assert_eq!(estimate.files, vec!["src/billing/retry.rs"]);
+        assert_eq!(estimate.risks, vec!["no tests for backoff"]);
+        assert!(parse_reply("t-1", "no idea").is_err());
+    }
+}
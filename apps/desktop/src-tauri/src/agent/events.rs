@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::text::truncate_chars;
 
 /// The semantic events we surface to the React canvas.
 /// These are extracted from the nested stream-json wire format.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum AgentEvent {
     /// Agent internal reasoning (extended thinking).
@@ -14,20 +18,45 @@ pub enum AgentEvent {
         id: String,
         tool_name: String,
         tool_input: serde_json::Value,
+        /// What the call does, from [`summarize`]. None in transcripts
+        /// recorded before summaries existed.
+        #[serde(default)]
+        summary: Option<ToolSummary>,
     },
     /// Tool result returned to the agent.
     ToolResult {
         tool_use_id: String,
         content: serde_json::Value,
         is_error: Option<bool>,
+        /// Set when the content was too large to carry: its full size in
+        /// bytes. `content` is then a text preview; `fetch_full_content`
+        /// returns the original.
+        #[serde(default)]
+        #[ts(type = "number | null")]
+        full_size: Option<u64>,
     },
     /// The agent run completed.
     Result {
         result: Option<String>,
         session_id: Option<String>,
+        /// What the run cost, as reported by claude.
+        cost_usd: Option<f64>,
     },
 }
 
+/// The gist of a tool call, so the frontend needn't parse each tool's input.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct ToolSummary {
+    /// One line for the canvas: a path, a command, a pattern.
+    pub label: String,
+    pub file_path: Option<String>,
+    /// 1-based line range read, when the call was limited to one.
+    #[ts(type = "[number, number | null] | null")]
+    pub lines: Option<(u64, Option<u64>)>,
+    pub command: Option<String>,
+}
+
 // ── Wire format types (deserialization only) ─────────────────────────────────
 //
 // claude --output-format stream-json --verbose emits lines shaped like:
@@ -47,6 +76,7 @@ enum StreamLine {
     Result {
         result: Option<String>,
         session_id: Option<String>,
+        total_cost_usd: Option<f64>,
     },
     #[serde(other)]
     Ignored,
@@ -92,6 +122,71 @@ enum UserBlock {
 
 // ── Public API ────────────────────────────────────────────────────────────────
 
+/// Longest label before it's cut.
+const MAX_LABEL_CHARS: usize = 120;
+
+fn clip(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > MAX_LABEL_CHARS || line.len() < text.trim_end().len() {
+        format!("{}…", truncate_chars(line, MAX_LABEL_CHARS))
+    } else {
+        line.to_string()
+    }
+}
+
+/// Summarize a tool call. Unknown tools are labeled with their name.
+pub fn summarize(tool_name: &str, input: &serde_json::Value) -> ToolSummary {
+    let str_field = |key: &str| input.get(key).and_then(|v| v.as_str()).map(String::from);
+    let file_path = str_field("file_path").or_else(|| str_field("notebook_path"));
+    let mut summary = ToolSummary {
+        label: tool_name.to_string(),
+        file_path: file_path.clone(),
+        ..Default::default()
+    };
+    match tool_name {
+        "Read" => {
+            let offset = input.get("offset").and_then(|v| v.as_u64());
+            let limit = input.get("limit").and_then(|v| v.as_u64());
+            if offset.is_some() || limit.is_some() {
+                let start = offset.unwrap_or(1).max(1);
+                summary.lines = Some((start, limit.map(|n| start + n.saturating_sub(1))));
+            }
+            if let Some(path) = file_path {
+                summary.label = match summary.lines {
+                    Some((start, Some(end))) => format!("{}:{}-{}", path, start, end),
+                    Some((start, None)) => format!("{}:{}-", path, start),
+                    None => path,
+                };
+            }
+        }
+        "Bash" => {
+            if let Some(command) = str_field("command") {
+                summary.label = clip(&command);
+                summary.command = Some(command);
+            }
+        }
+        "Glob" | "Grep" => {
+            if let Some(pattern) = str_field("pattern") {
+                summary.label = match str_field("path") {
+                    Some(path) => clip(&format!("{} in {}", pattern, path)),
+                    None => clip(&pattern),
+                };
+            }
+        }
+        "WebFetch" => {
+            if let Some(url) = str_field("url") {
+                summary.label = clip(&url);
+            }
+        }
+        _ => {
+            if let Some(path) = summary.file_path.clone() {
+                summary.label = path;
+            }
+        }
+    }
+    summary
+}
+
 /// Parse a single JSONL line into zero or more AgentEvents.
 /// Returns an empty vec for lines we don't recognise (system events, rate
 /// limits, etc.) — the caller should simply skip those lines.
@@ -109,6 +204,7 @@ pub fn parse_events(line: &str) -> Vec<AgentEvent> {
                 AssistantBlock::Thinking { thinking } => Some(AgentEvent::Thinking { thinking }),
                 AssistantBlock::Text { text } => Some(AgentEvent::Text { text }),
                 AssistantBlock::ToolUse { id, name, input } => Some(AgentEvent::ToolUse {
+                    summary: Some(summarize(&name, &input)),
                     id,
                     tool_name: name,
                     tool_input: input,
@@ -129,19 +225,36 @@ pub fn parse_events(line: &str) -> Vec<AgentEvent> {
                     tool_use_id,
                     content,
                     is_error,
+                    full_size: None,
                 }),
                 UserBlock::Unknown => None,
             })
             .collect(),
 
-        StreamLine::Result { result, session_id } => {
-            vec![AgentEvent::Result { result, session_id }]
-        }
+        StreamLine::Result {
+            result,
+            session_id,
+            total_cost_usd,
+        } => vec![AgentEvent::Result {
+            result,
+            session_id,
+            cost_usd: total_cost_usd,
+        }],
 
         StreamLine::Ignored => vec![],
     }
 }
 
+/// The session id from claude's `system/init` line, which arrives before any
+/// work — unlike the one on `result`, which only comes at the end.
+pub fn init_session_id(line: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    if value.get("type")?.as_str()? != "system" || value.get("subtype")?.as_str()? != "init" {
+        return None;
+    }
+    Some(value.get("session_id")?.as_str()?.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,19 +303,35 @@ mod tests {
 
     #[test]
     fn parses_result_event() {
-        let line = r#"{"type":"result","result":"Done. PR opened at #42.","session_id":"sess_abc"}"#;
+        let line = r#"{"type":"result","result":"Done. PR opened at #42.","session_id":"sess_abc","total_cost_usd":0.42}"#;
         let events = parse_events(line);
         assert_eq!(events.len(), 1);
         assert!(matches!(events[0], AgentEvent::Result { .. }));
-        if let AgentEvent::Result { ref session_id, .. } = events[0] {
+        if let AgentEvent::Result { ref session_id, cost_usd, .. } = events[0] {
             assert_eq!(session_id.as_deref(), Some("sess_abc"));
+            assert_eq!(cost_usd, Some(0.42));
         }
     }
 
+    #[test]
+    fn summarizes_known_tools() {
+        let read = summarize("Read", &serde_json::json!({"file_path": "src/a.rs", "offset": 10, "limit": 51}));
+        assert_eq!(read.label, "src/a.rs:10-60");
+        assert_eq!(read.lines, Some((10, Some(60))));
+        let bash = summarize("Bash", &serde_json::json!({"command": "cargo test\necho done"}));
+        assert_eq!(bash.label, "cargo test…");
+        assert_eq!(bash.command.as_deref(), Some("cargo test\necho done"));
+        let edit = summarize("Edit", &serde_json::json!({"file_path": "src/b.rs", "old_string": "", "new_string": ""}));
+        assert_eq!((edit.label.as_str(), edit.file_path.as_deref()), ("src/b.rs", Some("src/b.rs")));
+        assert_eq!(summarize("Grep", &serde_json::json!({"pattern": "TODO", "path": "src"})).label, "TODO in src");
+        assert_eq!(summarize("TodoWrite", &serde_json::json!({})).label, "TodoWrite");
+    }
+
     #[test]
     fn ignores_system_events() {
         let line = r#"{"type":"system","subtype":"init","session_id":"abc","tools":[]}"#;
         assert!(parse_events(line).is_empty());
+        assert_eq!(init_session_id(line).as_deref(), Some("abc"));
     }
 
     #[test]
@@ -0,0 +1,323 @@
+use serde::Deserialize;
+use serde_json::Value;
+use ts_rs::TS;
+
+use super::annotations::Annotation;
+use super::events::AgentEvent;
+use super::transcript::TranscriptEntry;
+
+/// Output format for `export_transcript`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+}
+
+/// Lines of a tool result kept in the export; the rest is elided.
+const RESULT_PREVIEW_LINES: usize = 20;
+
+/// A rendering-neutral piece of the document.
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    /// Collapsed by default: summary line, body.
+    Collapsed(String, String),
+    /// One-line tool call summary.
+    Tool(String, String),
+    Diff(String),
+    /// Human annotation on the preceding node: flag, text.
+    Note(Option<String>, String),
+}
+
+fn result_text(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        // Content blocks: [{"type":"text","text":"..."}]
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    }
+}
+
+fn preview(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= RESULT_PREVIEW_LINES {
+        return text.to_string();
+    }
+    format!(
+        "{}\n… {} more lines",
+        lines[..RESULT_PREVIEW_LINES].join("\n"),
+        lines.len() - RESULT_PREVIEW_LINES
+    )
+}
+
+fn edit_diff(old: &str, new: &str) -> String {
+    old.lines()
+        .map(|l| format!("-{}", l))
+        .chain(new.lines().map(|l| format!("+{}", l)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Summarise a tool call as (tool, detail), plus a diff for edits.
+fn tool_blocks(tool_name: &str, input: &Value) -> Vec<Block> {
+    let str_field = |key: &str| input[key].as_str().unwrap_or_default().to_string();
+    match tool_name {
+        "Read" | "Write" | "Edit" | "MultiEdit" => {
+            let mut blocks = vec![Block::Tool(tool_name.to_string(), str_field("file_path"))];
+            if tool_name == "Edit" {
+                blocks.push(Block::Diff(edit_diff(
+                    input["old_string"].as_str().unwrap_or_default(),
+                    input["new_string"].as_str().unwrap_or_default(),
+                )));
+            }
+            blocks
+        }
+        "Bash" => vec![Block::Tool(tool_name.to_string(), str_field("command"))],
+        "Grep" | "Glob" => vec![Block::Tool(tool_name.to_string(), str_field("pattern"))],
+        _ => vec![Block::Tool(tool_name.to_string(), input.to_string())],
+    }
+}
+
+fn blocks(title: &str, entries: &[TranscriptEntry], notes: &[Annotation]) -> Vec<Block> {
+    let mut out = vec![Block::Heading(title.to_string())];
+    for entry in entries {
+        match &entry.kind {
+            AgentEvent::Thinking { thinking } => {
+                out.push(Block::Collapsed("Thinking".to_string(), thinking.clone()))
+            }
+            AgentEvent::Text { text } => out.push(Block::Paragraph(text.clone())),
+            AgentEvent::ToolUse {
+                tool_name,
+                tool_input,
+                ..
+            } => out.extend(tool_blocks(tool_name, tool_input)),
+            AgentEvent::ToolResult {
+                content, is_error, ..
+            } => {
+                let label = if *is_error == Some(true) { "Tool error" } else { "Tool output" };
+                out.push(Block::Collapsed(label.to_string(), preview(&result_text(content))));
+            }
+            AgentEvent::Result {
+                result, cost_usd, ..
+            } => {
+                let mut text = format!("**Result:** {}", result.as_deref().unwrap_or("(none)"));
+                if let Some(cost) = cost_usd {
+                    text.push_str(&format!(" _(cost ${:.2})_", cost));
+                }
+                out.push(Block::Paragraph(text));
+            }
+        }
+        out.extend(
+            notes
+                .iter()
+                .filter(|n| n.node_id == entry.node_id)
+                .map(|n| Block::Note(n.flag.clone(), n.text.clone())),
+        );
+    }
+    out
+}
+
+fn fence(text: &str) -> String {
+    // Longer than any backtick run inside, so embedded fences survive
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn to_markdown(blocks: &[Block]) -> String {
+    let parts: Vec<String> = blocks
+        .iter()
+        .map(|b| match b {
+            Block::Heading(t) => format!("# {}", t),
+            Block::Paragraph(t) => t.clone(),
+            Block::Collapsed(summary, body) => {
+                let f = fence(body);
+                format!(
+                    "<details><summary>{}</summary>\n\n{}\n{}\n{}\n\n</details>",
+                    summary, f, body, f
+                )
+            }
+            Block::Tool(tool, detail) => format!("**{}** `{}`", tool, detail.replace('`', "'")),
+            Block::Diff(diff) => {
+                let f = fence(diff);
+                format!("{}diff\n{}\n{}", f, diff, f)
+            }
+            Block::Note(flag, text) => {
+                let label = flag.as_deref().map(|f| format!(" ({})", f)).unwrap_or_default();
+                let quoted: Vec<String> = text.lines().map(|l| format!("> {}", l)).collect();
+                format!("> **Note{}:**\n{}", label, quoted.join("\n"))
+            }
+        })
+        .collect();
+    parts.join("\n\n") + "\n"
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:60rem;margin:2rem auto;\
+line-height:1.5;color:#1f2328}pre{background:#f6f8fa;padding:.75rem;overflow-x:auto}\
+details{margin:.5rem 0}summary{cursor:pointer;color:#57606a}.tool code{background:#eef1f4;\
+padding:0 .25rem}.note{border-left:3px solid #bf8700;background:#fff8c5;padding:.25rem .75rem}.add{color:#116329;background:#dafbe1}.del{color:#82071e;background:#ffebe9}";
+
+fn to_html(blocks: &[Block]) -> String {
+    let mut body = String::new();
+    let mut title = String::new();
+    for b in blocks {
+        match b {
+            Block::Heading(t) => {
+                title = escape(t);
+                body.push_str(&format!("<h1>{}</h1>\n", title));
+            }
+            Block::Paragraph(t) => body.push_str(&format!("<p>{}</p>\n", escape(t))),
+            Block::Collapsed(summary, text) => body.push_str(&format!(
+                "<details><summary>{}</summary><pre>{}</pre></details>\n",
+                escape(summary),
+                escape(text)
+            )),
+            Block::Tool(tool, detail) => body.push_str(&format!(
+                "<p class=\"tool\"><strong>{}</strong> <code>{}</code></p>\n",
+                escape(tool),
+                escape(detail)
+            )),
+            Block::Diff(diff) => {
+                let lines: Vec<String> = diff
+                    .lines()
+                    .map(|l| {
+                        let class = match l.chars().next() {
+                            Some('+') => "add",
+                            Some('-') => "del",
+                            _ => "",
+                        };
+                        format!("<span class=\"{}\">{}</span>", class, escape(l))
+                    })
+                    .collect();
+                body.push_str(&format!("<pre>{}</pre>\n", lines.join("\n")));
+            }
+            Block::Note(flag, text) => {
+                let label = flag.as_deref().map(|f| format!(" ({})", escape(f))).unwrap_or_default();
+                body.push_str(&format!(
+                    "<blockquote class=\"note\"><strong>Note{}:</strong> {}</blockquote>\n",
+                    label,
+                    escape(text)
+                ));
+            }
+        }
+    }
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title>\
+         <style>{}</style></head>\n<body>\n{}</body></html>\n",
+        title, HTML_STYLE, body
+    )
+}
+
+/// Render a transcript as a standalone, shareable document, with each node's
+/// annotations right after it.
+pub fn render(
+    title: &str,
+    entries: &[TranscriptEntry],
+    notes: &[Annotation],
+    format: ExportFormat,
+) -> String {
+    let blocks = blocks(title, entries, notes);
+    match format {
+        ExportFormat::Markdown => to_markdown(&blocks),
+        ExportFormat::Html => to_html(&blocks),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(kind: AgentEvent) -> TranscriptEntry {
+        TranscriptEntry {
+            at: 0,
+            node_id: "n".to_string(),
+            kind,
+            group_id: None,
+        }
+    }
+
+    fn sample() -> Vec<TranscriptEntry> {
+        vec![
+            entry(AgentEvent::Thinking {
+                thinking: "look at retry.rs".to_string(),
+            }),
+            entry(AgentEvent::ToolUse {
+                id: "t1".to_string(),
+                tool_name: "Edit".to_string(),
+                tool_input: json!({
+                    "file_path": "src/retry.rs",
+                    "old_string": "let n = 3;",
+                    "new_string": "let n = 5;"
+                }),
+                summary: None,
+            }),
+            entry(AgentEvent::Result {
+                result: Some("Done".to_string()),
+                session_id: None,
+                cost_usd: Some(0.5),
+            }),
+        ]
+    }
+
+    #[test]
+    fn markdown_collapses_thinking_and_shows_diffs() {
+        let md = render("Ticket 42", &sample(), &[], ExportFormat::Markdown);
+        assert!(md.starts_with("# Ticket 42"));
+        assert!(md.contains("<details><summary>Thinking</summary>"));
+        assert!(md.contains("**Edit** `src/retry.rs`"));
+        assert!(md.contains("-let n = 3;\n+let n = 5;"));
+        assert!(md.contains("_(cost $0.50)_"));
+    }
+
+    #[test]
+    fn html_escapes_and_highlights() {
+        let mut entries = sample();
+        entries.push(entry(AgentEvent::Text {
+            text: "<script>".to_string(),
+        }));
+        let html = render("Ticket 42", &entries, &[], ExportFormat::Html);
+        assert!(html.contains("<span class=\"add\">+let n = 5;</span>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn annotations_follow_their_node() {
+        let mut entries = sample();
+        entries[1].node_id = "edit".to_string();
+        let note = Annotation {
+            id: "a1".to_string(),
+            node_id: "edit".to_string(),
+            text: "5 retries was wrong".to_string(),
+            flag: Some("wrong".to_string()),
+            updated_at: 0,
+        };
+        let md = render("Ticket 42", &entries, &[note], ExportFormat::Markdown);
+        let diff = md.find("+let n = 5;").unwrap();
+        let note = md.find("> **Note (wrong):**\n> 5 retries was wrong").unwrap();
+        assert!(note > diff && note < md.find("**Result:**").unwrap());
+    }
+
+    #[test]
+    fn long_tool_output_is_previewed() {
+        let long = (0..100).map(|i| i.to_string()).collect::<Vec<_>>().join("\n");
+        assert!(preview(&long).ends_with("… 80 more lines"));
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use ts_rs::TS;
+
+use super::events::AgentEvent;
+
+/// Which agent events reach the canvas and the transcript. Everything is
+/// kept by default; `result` always is, since resume depends on it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct EventFilter {
+    pub hide_thinking: bool,
+    pub hide_text: bool,
+    pub hide_tool_use: bool,
+    pub hide_tool_results: bool,
+}
+
+impl EventFilter {
+    pub fn allows(&self, event: &AgentEvent) -> bool {
+        match event {
+            AgentEvent::Thinking { .. } => !self.hide_thinking,
+            AgentEvent::Text { .. } => !self.hide_text,
+            AgentEvent::ToolUse { .. } => !self.hide_tool_use,
+            AgentEvent::ToolResult { .. } => !self.hide_tool_results,
+            AgentEvent::Result { .. } => true,
+        }
+    }
+}
+
+/// The filter in effect, shared with running agents so changes apply mid-run.
+pub type FilterStore = Arc<RwLock<EventFilter>>;
+
+pub fn new_store(filter: EventFilter) -> FilterStore {
+    Arc::new(RwLock::new(filter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hides_only_the_chosen_kinds() {
+        let filter = EventFilter {
+            hide_thinking: true,
+            ..Default::default()
+        };
+        assert!(!filter.allows(&AgentEvent::Thinking { thinking: "hmm".to_string() }));
+        assert!(filter.allows(&AgentEvent::Text { text: "hi".to_string() }));
+        let result = AgentEvent::Result {
+            result: None,
+            session_id: None,
+            cost_usd: None,
+        };
+        assert!(EventFilter {
+            hide_thinking: true,
+            hide_text: true,
+            hide_tool_use: true,
+            hide_tool_results: true,
+        }
+        .allows(&result));
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use ts_rs::TS;
+
+/// Seconds the connectivity test waits for the gateway.
+const CHECK_TIMEOUT_SECS: u32 = 10;
+
+/// An LLM gateway (LiteLLM, an internal proxy) the project's model traffic
+/// must go through.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct GatewayConfig {
+    /// Sent as ANTHROPIC_BASE_URL, e.g. "https://llm.internal.example.com".
+    pub base_url: String,
+    /// Extra request headers as "Name: value", e.g. a tenant or cost-center tag.
+    pub headers: Vec<String>,
+    /// Further env for the claude process, e.g. ANTHROPIC_MODEL pinned to a
+    /// model the gateway serves.
+    pub env: BTreeMap<String, String>,
+}
+
+impl GatewayConfig {
+    /// Env vars that route the CLI through the gateway.
+    pub fn env(&self) -> Vec<(String, String)> {
+        let mut env = vec![(
+            "ANTHROPIC_BASE_URL".to_string(),
+            self.base_url.trim_end_matches('/').to_string(),
+        )];
+        if !self.headers.is_empty() {
+            env.push(("ANTHROPIC_CUSTOM_HEADERS".to_string(), self.headers.join("\n")));
+        }
+        env.extend(self.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        env
+    }
+}
+
+/// Result of `test_gateway`.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct GatewayCheck {
+    /// The gateway answered and accepted the credentials.
+    pub ok: bool,
+    /// HTTP status, when the gateway answered at all.
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+fn interpret(status: u16) -> GatewayCheck {
+    let message = match status {
+        200..=299 => "Gateway reachable and credentials accepted".to_string(),
+        401 | 403 => "Gateway reachable, but it rejected the credentials".to_string(),
+        404 => "Gateway reachable, but it doesn't serve the Anthropic API at this URL".to_string(),
+        other => format!("Gateway answered with HTTP {}", other),
+    };
+    GatewayCheck {
+        ok: (200..300).contains(&status),
+        status: Some(status),
+        message,
+    }
+}
+
+/// List models through the gateway as the CLI would reach it. Headers go to
+/// curl on stdin so the key stays out of the process list. Blocking.
+pub fn check(config: &GatewayConfig, api_key: Option<&str>) -> Result<GatewayCheck> {
+    if config.base_url.trim().is_empty() {
+        anyhow::bail!("no gateway URL configured");
+    }
+    let url = format!("{}/v1/models", config.base_url.trim_end_matches('/'));
+    let mut headers = vec!["anthropic-version: 2023-06-01".to_string()];
+    if let Some(key) = api_key.filter(|k| !k.is_empty()) {
+        headers.push(format!("x-api-key: {}", key));
+    }
+    headers.extend(config.headers.iter().cloned());
+
+    let mut child = Command::new("curl")
+        .args(["-sS", "-w", "\n%{http_code}", "--max-time"])
+        .arg(CHECK_TIMEOUT_SECS.to_string())
+        .args(["-H", "@-", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(headers.join("\n").as_bytes())
+            .context("failed to pass headers to curl")?;
+    }
+    let output = child.wait_with_output().context("failed to run curl")?;
+    Ok(answer(
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+    ))
+}
+
+/// Read curl's output: the body, discarded, then the status code `-w` adds
+/// on a line of its own. There's no null device on every platform to send
+/// the body to instead.
+fn answer(stdout: &str, stderr: &str) -> GatewayCheck {
+    let status = stdout.rsplit('\n').next().unwrap_or_default();
+    let code = status.trim().parse::<u16>().unwrap_or(0);
+    if code == 0 {
+        return GatewayCheck {
+            ok: false,
+            status: None,
+            message: format!("Gateway unreachable: {}", stderr.trim()),
+        };
+    }
+    interpret(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_carries_url_headers_and_extras() {
+        let config = GatewayConfig {
+            base_url: "https://llm.example.com/".to_string(),
+            headers: vec!["X-Tenant: web".to_string(), "X-Cost-Center: 42".to_string()],
+            env: BTreeMap::from([("ANTHROPIC_MODEL".to_string(), "claude-sonnet".to_string())]),
+        };
+        assert_eq!(
+            config.env(),
+            [
+                ("ANTHROPIC_BASE_URL".to_string(), "https://llm.example.com".to_string()),
+                ("ANTHROPIC_CUSTOM_HEADERS".to_string(), "X-Tenant: web\nX-Cost-Center: 42".to_string()),
+                ("ANTHROPIC_MODEL".to_string(), "claude-sonnet".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_success_statuses_pass() {
+        assert!(interpret(200).ok);
+        let rejected = interpret(401);
+        assert!(!rejected.ok);
+        assert!(rejected.message.contains("rejected"));
+        assert_eq!(interpret(502).status, Some(502));
+    }
+
+    #[test]
+    fn status_follows_the_body() {
+        let check = answer("{\"data\": []}\n200", "");
+        assert_eq!(check.status, Some(200));
+        let down = answer("\n000", "Could not resolve host");
+        assert!(!down.ok);
+        assert!(down.message.contains("Could not resolve host"));
+    }
+}
@@ -0,0 +1,581 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use crate::platform::paths;
+use crate::project::store;
+
+/// Paths a role may edit, as globs relative to the repo root
+/// (`*` within a segment, `**` across segments, `?` one character).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct PathRule {
+    /// When non-empty, edits must match one of these.
+    pub allow: Vec<String>,
+    /// Edits matching any of these are forbidden, even when allowed.
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Strictness {
+    /// Emit `path-violation` and let the run continue.
+    #[default]
+    Warn,
+    /// Refuse the write before it happens, emit `path-violation` and stop
+    /// the run.
+    Interrupt,
+}
+
+/// Per-role path restrictions for a project. Checked by a PreToolUse hook
+/// before each write, which needs `curl` wherever the agent runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct RolePaths {
+    /// Role (e.g. "frontend-engineer") → its rule. Roles without one are unrestricted.
+    pub roles: HashMap<String, PathRule>,
+    pub strictness: Strictness,
+}
+
+/// Payload for `path-violation`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PathViolationPayload {
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub role: String,
+    pub tool_name: String,
+    pub path: String,
+    /// Whether the run was stopped.
+    pub interrupted: bool,
+}
+
+impl RolePaths {
+    pub fn rule_for(&self, role: &str) -> Option<&PathRule> {
+        self.roles.get(role)
+    }
+}
+
+/// Tools that write files, and the input key holding the path.
+const WRITE_TOOLS: &[(&str, &str)] = &[
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("Write", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
+fn segment_matches(pattern: &[char], text: &[char]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            segment_matches(&pattern[1..], text)
+                || (!text.is_empty() && segment_matches(pattern, &text[1..]))
+        }
+        (Some('?'), Some(_)) => segment_matches(&pattern[1..], &text[1..]),
+        (Some(p), Some(t)) if p == t => segment_matches(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            segments_match(&pattern[1..], path) || (!path.is_empty() && segments_match(pattern, &path[1..]))
+        }
+        (Some(p), Some(s)) => {
+            let (p, s): (Vec<char>, Vec<char>) = (p.chars().collect(), s.chars().collect());
+            segment_matches(&p, &s) && segments_match(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+/// Whether a repo-relative `path` matches `pattern`. A trailing `/` means
+/// everything below that directory.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = match pattern.strip_suffix('/') {
+        Some(dir) => format!("{}/**", dir),
+        None => pattern.to_string(),
+    };
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    segments_match(&pattern, &path)
+}
+
+impl PathRule {
+    pub fn permits(&self, path: &str) -> bool {
+        if self.deny.iter().any(|p| glob_match(p, path)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|p| glob_match(p, path))
+    }
+}
+
+/// A path a tool call writes, resolved against the worktree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WritePath {
+    /// Repo-relative, with `.` and `..` folded away.
+    Inside(String),
+    /// Outside the worktree, absolute.
+    Outside(String),
+}
+
+impl WritePath {
+    pub fn as_str(&self) -> &str {
+        match self {
+            WritePath::Inside(path) | WritePath::Outside(path) => path,
+        }
+    }
+
+    /// Scratch locations any run may write: temp dirs and devices.
+    fn is_scratch(&self) -> bool {
+        match self {
+            WritePath::Inside(_) => false,
+            WritePath::Outside(path) => {
+                ["/tmp/", "/var/tmp/", "/dev/"].iter().any(|dir| path.starts_with(dir))
+                    || Path::new(path).starts_with(std::env::temp_dir())
+            }
+        }
+    }
+}
+
+/// `path` with `.` and `..` folded away, without touching the disk.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// `path` with symlinks resolved as far as it exists on this machine.
+/// Paths the agent sees inside a container or on a remote host don't exist
+/// here and stay as they are.
+fn real_path(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(real) = std::fs::canonicalize(existing) {
+            return rest.iter().rev().fold(real, |p, c| p.join(c));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Resolve `raw` as the agent would from `cwd`, relative to the worktree at
+/// `root`. Both are paths as the agent sees them.
+pub fn resolve(raw: &str, cwd: &Path, root: &Path) -> WritePath {
+    let path = normalize(&cwd.join(raw));
+    let root = normalize(root);
+    let (path, root) = match std::fs::canonicalize(&root) {
+        Ok(real_root) => (real_path(&path), real_root),
+        Err(_) => (path, root),
+    };
+    match path.strip_prefix(&root) {
+        Ok(relative) => WritePath::Inside(paths::to_slash(relative)),
+        Err(_) => WritePath::Outside(path.to_string_lossy().to_string()),
+    }
+}
+
+/// A shell command split into words, redirection operators and command
+/// separators.
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Redirect(String),
+    Separator,
+}
+
+fn tokenize(command: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    // Quoted empty strings are still words
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+    let end_word = |tokens: &mut Vec<Token>, word: &mut String, in_word: &mut bool| {
+        if *in_word {
+            tokens.push(Token::Word(std::mem::take(word)));
+            *in_word = false;
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                    in_word = true;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                for q in chars.by_ref() {
+                    if q == '\'' {
+                        break;
+                    }
+                    word.push(q);
+                }
+            }
+            '"' => {
+                in_word = true;
+                while let Some(q) = chars.next() {
+                    match q {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        q => word.push(q),
+                    }
+                }
+            }
+            '>' | '<' => {
+                // A number right before the operator is a file descriptor
+                if in_word && word.chars().all(|c| c.is_ascii_digit()) {
+                    word.clear();
+                    in_word = false;
+                }
+                end_word(&mut tokens, &mut word, &mut in_word);
+                let mut op = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if matches!(next, '>' | '<' | '&' | '|') {
+                        op.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Redirect(op));
+            }
+            '&' if chars.peek() == Some(&'>') => {
+                end_word(&mut tokens, &mut word, &mut in_word);
+                let mut op = "&".to_string();
+                while let Some(&next) = chars.peek() {
+                    if next == '>' {
+                        op.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Redirect(op));
+            }
+            ';' | '&' | '|' | '\n' | '(' | ')' | '`' => {
+                end_word(&mut tokens, &mut word, &mut in_word);
+                tokens.push(Token::Separator);
+            }
+            c if c.is_whitespace() => end_word(&mut tokens, &mut word, &mut in_word),
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    end_word(&mut tokens, &mut word, &mut in_word);
+    tokens
+}
+
+/// Shell words that introduce a command rather than being one.
+const PREFIX_WORDS: &[&str] = &[
+    "if", "then", "else", "elif", "do", "while", "until", "!", "{", "}", "sudo", "env", "command",
+    "exec", "time", "nohup", "xargs",
+];
+
+/// Files a shell command writes, joined onto the directory each part of it
+/// runs in: redirections, `tee`, the common file commands and `sed -i`, and
+/// the same inside `bash -c`. Best effort — a path built at run time from
+/// variables or `$(...)` isn't seen.
+fn shell_targets(command: &str, cwd: &Path) -> Vec<PathBuf> {
+    let mut targets = Vec::new();
+    let mut cwd = cwd.to_path_buf();
+    let tokens = tokenize(command);
+    for part in tokens.split(|t| *t == Token::Separator) {
+        let mut words: Vec<&str> = Vec::new();
+        let mut iter = part.iter();
+        while let Some(token) = iter.next() {
+            match token {
+                Token::Word(word) => words.push(word),
+                Token::Redirect(op) => {
+                    let Some(Token::Word(file)) = iter.next() else {
+                        continue;
+                    };
+                    let duplicates_fd = op.ends_with('&') && (file == "-" || file.parse::<u32>().is_ok());
+                    if op.contains('>') && !duplicates_fd {
+                        targets.push(cwd.join(file));
+                    }
+                }
+                Token::Separator => {}
+            }
+        }
+        let start = words
+            .iter()
+            .position(|w| !PREFIX_WORDS.contains(w) && !is_assignment(w))
+            .unwrap_or(words.len());
+        let Some((program, args)) = words[start..].split_first() else {
+            continue;
+        };
+        let program = program.rsplit('/').next().unwrap_or_default();
+        let files: Vec<&str> = args.iter().copied().filter(|a| !a.starts_with('-')).collect();
+        match program {
+            "cd" => {
+                if let Some(dir) = files.first() {
+                    cwd = cwd.join(dir);
+                }
+            }
+            "tee" | "rm" | "rmdir" | "touch" | "mkdir" | "truncate" | "unlink" | "shred" => {
+                targets.extend(files.iter().map(|f| cwd.join(f)));
+            }
+            "cp" | "mv" | "install" | "ln" | "rsync" if files.len() >= 2 => {
+                targets.push(cwd.join(files[files.len() - 1]));
+            }
+            "chmod" | "chown" => targets.extend(files.iter().skip(1).map(|f| cwd.join(f))),
+            "sed" | "perl" if args.iter().any(|a| a.starts_with("-i") || *a == "--in-place") => {
+                // Without -e the first operand is the script
+                let scripted = args.iter().any(|a| *a == "-e" || *a == "-f");
+                let skip = if scripted { 0 } else { 1 };
+                targets.extend(files.iter().skip(skip).map(|f| cwd.join(f)));
+            }
+            "bash" | "sh" | "zsh" | "dash" => {
+                if let Some(i) = args.iter().position(|a| *a == "-c") {
+                    if let Some(script) = args.get(i + 1) {
+                        targets.extend(shell_targets(script, &cwd));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    targets
+}
+
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'),
+        None => false,
+    }
+}
+
+/// Every path a tool call writes, from the agent's `cwd`, relative to the
+/// worktree at `root`. Write tools name one file; Bash commands are read for
+/// the files they write. Scratch paths are left out.
+pub fn write_paths(tool_name: &str, input: &serde_json::Value, cwd: &Path, root: &Path) -> Vec<WritePath> {
+    let raw: Vec<PathBuf> = if tool_name == "Bash" {
+        let command = input.get("command").and_then(|c| c.as_str()).unwrap_or_default();
+        shell_targets(command, cwd)
+    } else {
+        WRITE_TOOLS
+            .iter()
+            .find(|(name, _)| *name == tool_name)
+            .and_then(|(_, key)| input.get(*key)?.as_str())
+            .map(|path| vec![cwd.join(path)])
+            .unwrap_or_default()
+    };
+    raw.iter()
+        .map(|path| resolve(&path.to_string_lossy(), cwd, root))
+        .filter(|path| !path.is_scratch())
+        .collect()
+}
+
+/// Whether `path` is one of the project's protected paths or below one.
+/// Entries are repo-relative globs; a leading `/` is allowed.
+pub fn is_protected(protected: &[String], path: &str) -> bool {
+    protected.iter().any(|p| {
+        let p = p.trim_end_matches('/');
+        glob_match(p, path) || glob_match(&format!("{}/", p), path)
+    })
+}
+
+/// A write to a protected path, refused before it happened and awaiting a
+/// human.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct ProtectedEdit {
+    pub id: String,
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub tool_name: String,
+    /// Repo-relative path the agent tried to write.
+    pub path: String,
+    /// The session an approval resumes.
+    pub session_id: Option<String>,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub requested_at: i64,
+    /// Approved edits let the agent write the path for the rest of the ticket.
+    pub approved: bool,
+}
+
+// Serializes read-modify-write cycles on the edits file.
+static EDITS: Mutex<()> = Mutex::new(());
+
+/// `$HOME/.poietai/protected-edits.json`.
+fn edits_path() -> PathBuf {
+    store::app_dir().join("protected-edits.json")
+}
+
+fn edit_records<R>(f: impl FnOnce(&mut Vec<ProtectedEdit>) -> R) -> Result<R> {
+    let _guard = EDITS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut edits: Vec<ProtectedEdit> = store::read_json(&edits_path())?;
+    let result = f(&mut edits);
+    store::write_json(&edits_path(), &edits)?;
+    Ok(result)
+}
+
+/// Protected-path writes awaiting approval, oldest first.
+pub fn pending_edits() -> Result<Vec<ProtectedEdit>> {
+    let _guard = EDITS.lock().unwrap_or_else(|e| e.into_inner());
+    let edits: Vec<ProtectedEdit> = store::read_json(&edits_path())?;
+    Ok(edits.into_iter().filter(|e| !e.approved).collect())
+}
+
+/// Record a blocked write, replacing any earlier request for the same path.
+pub fn request_edit(edit: ProtectedEdit) -> Result<()> {
+    edit_records(|edits| {
+        edits.retain(|e| {
+            e.approved || !(e.agent_id == edit.agent_id && e.ticket_id == edit.ticket_id && e.path == edit.path)
+        });
+        edits.push(edit);
+    })
+}
+
+/// Whether a human approved `agent_id` writing `path` on `ticket_id`.
+pub fn is_approved(agent_id: &str, ticket_id: &str, path: &str) -> bool {
+    let _guard = EDITS.lock().unwrap_or_else(|e| e.into_inner());
+    store::read_json::<Vec<ProtectedEdit>>(&edits_path())
+        .unwrap_or_default()
+        .iter()
+        .any(|e| e.approved && e.agent_id == agent_id && e.ticket_id == ticket_id && e.path == path)
+}
+
+/// Approve a pending edit, returning it.
+pub fn approve_edit(id: &str) -> Result<Option<ProtectedEdit>> {
+    edit_records(|edits| {
+        let edit = edits.iter_mut().find(|e| e.id == id && !e.approved)?;
+        edit.approved = true;
+        Some(edit.clone())
+    })
+}
+
+/// Drop a pending edit, returning it.
+pub fn reject_edit(id: &str) -> Result<Option<ProtectedEdit>> {
+    edit_records(|edits| {
+        let index = edits.iter().position(|e| e.id == id && !e.approved)?;
+        Some(edits.remove(index))
+    })
+}
+
+/// The forbidden path a tool call writes to, if any. Writes outside the
+/// worktree are forbidden whatever the rule says.
+pub fn violation(
+    rule: &PathRule,
+    tool_name: &str,
+    input: &serde_json::Value,
+    cwd: &Path,
+    root: &Path,
+) -> Option<String> {
+    write_paths(tool_name, input, cwd, root)
+        .into_iter()
+        .find(|path| match path {
+            WritePath::Inside(relative) => !rule.permits(relative),
+            WritePath::Outside(_) => true,
+        })
+        .map(|path| path.as_str().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn globs_match_segments() {
+        assert!(glob_match("apps/desktop/src-tauri/**", "apps/desktop/src-tauri/src/lib.rs"));
+        assert!(glob_match("src-tauri/", "src-tauri/Cargo.toml"));
+        assert!(glob_match("**/*.sql", "db/migrations/001_init.sql"));
+        assert!(glob_match("src/*.ts", "src/app.ts"));
+        assert!(!glob_match("src/*.ts", "src/lib/app.ts"));
+        assert!(glob_match("docs/?.md", "docs/a.md"));
+    }
+
+    #[test]
+    fn deny_wins_over_allow() {
+        let rule = PathRule {
+            allow: vec!["apps/desktop/src/**".to_string()],
+            deny: vec!["**/generated/**".to_string()],
+        };
+        assert!(rule.permits("apps/desktop/src/App.tsx"));
+        assert!(!rule.permits("apps/desktop/src/types/generated/Foo.ts"));
+        assert!(!rule.permits("apps/desktop/src-tauri/src/lib.rs"));
+    }
+
+    #[test]
+    fn flags_writes_outside_the_rule() {
+        let rule = PathRule {
+            allow: vec![],
+            deny: vec!["backend/**".to_string()],
+        };
+        let worktree = Path::new("/nonexistent/wt");
+        let edit = json!({"file_path": "/nonexistent/wt/backend/api.go", "old_string": "a", "new_string": "b"});
+        assert_eq!(violation(&rule, "Edit", &edit, worktree, worktree), Some("backend/api.go".to_string()));
+        assert_eq!(violation(&rule, "Read", &edit, worktree, worktree), None);
+        let write = json!({"file_path": "web/app.ts", "content": ""});
+        assert_eq!(violation(&rule, "Write", &write, worktree, worktree), None);
+
+        // `..` can't step around a deny rule, and leaving the worktree is never allowed
+        let sneaky = json!({"file_path": "web/../backend/api.go", "content": ""});
+        assert_eq!(violation(&rule, "Write", &sneaky, worktree, worktree), Some("backend/api.go".to_string()));
+        let outside = json!({"file_path": "/nonexistent/other/api.go", "content": ""});
+        assert_eq!(
+            violation(&rule, "Write", &outside, worktree, worktree),
+            Some("/nonexistent/other/api.go".to_string())
+        );
+        let scratch = json!({"file_path": "/tmp/notes.md", "content": ""});
+        assert_eq!(violation(&rule, "Write", &scratch, worktree, worktree), None);
+    }
+
+    #[test]
+    fn shell_commands_are_read_for_the_files_they_write() {
+        let rule = PathRule {
+            allow: vec![],
+            deny: vec!["backend/**".to_string()],
+        };
+        let root = Path::new("/nonexistent/wt");
+        let bash = |command: &str| json!({ "command": command });
+        let check = |command: &str| violation(&rule, "Bash", &bash(command), root, root);
+
+        assert_eq!(check("echo hi > backend/x.go"), Some("backend/x.go".to_string()));
+        assert_eq!(check("cat a >>backend/x.go 2>&1"), Some("backend/x.go".to_string()));
+        assert_eq!(check("cd backend && printf x | tee -a api.go"), Some("backend/api.go".to_string()));
+        assert_eq!(check("cp web/a.ts 'backend/b.go'"), Some("backend/b.go".to_string()));
+        assert_eq!(check("sed -i 's/a/b/' backend/api.go"), Some("backend/api.go".to_string()));
+        assert_eq!(check("bash -c \"rm -rf backend\""), Some("backend".to_string()));
+        assert_eq!(check("cargo test 2>&1 | tail -n 20 > /dev/null"), None);
+        assert_eq!(check("grep -r backend web > web/hits.txt"), None);
+        assert_eq!(check("echo '>' backend/x.go"), None);
+    }
+
+    #[test]
+    fn protected_paths_cover_everything_below() {
+        let protected = vec!["/.github/workflows".to_string(), "infrastructure/prod/".to_string()];
+        assert!(is_protected(&protected, ".github/workflows/ci.yml"));
+        assert!(is_protected(&protected, "infrastructure/prod/main.tf"));
+        assert!(!is_protected(&protected, ".github/CODEOWNERS"));
+        assert!(!is_protected(&protected, "infrastructure/staging/main.tf"));
+        assert!(!is_protected(&[], ".github/workflows/ci.yml"));
+    }
+}
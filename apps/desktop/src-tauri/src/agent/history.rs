@@ -0,0 +1,177 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use ts_rs::TS;
+
+use crate::project::store;
+
+/// Longest range a history query may span.
+const MAX_DAYS: i64 = 400;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Succeeded,
+    Failed,
+}
+
+/// One finished agent process, appended to `$HOME/.poietai/metrics/runs.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct RunRecord {
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub started_at: i64,
+    #[ts(type = "number")]
+    pub finished_at: i64,
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub cost_usd: f64,
+    pub outcome: Outcome,
+}
+
+pub fn path() -> PathBuf {
+    store::app_dir().join("metrics").join("runs.jsonl")
+}
+
+pub fn record(run: &RunRecord) -> Result<()> {
+    store::append_jsonl(&path(), run)
+}
+
+/// Every recorded run, oldest first. Unparseable lines are skipped.
+pub fn load() -> Result<Vec<RunRecord>> {
+    let contents = match std::fs::read_to_string(path()) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).context("failed to read run history"),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Local calendar days, both ends included, as `YYYY-MM-DD`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct DateRange {
+    pub from: String,
+    pub to: String,
+}
+
+impl DateRange {
+    pub fn days(&self) -> Result<(NaiveDate, NaiveDate)> {
+        let parse = |s: &str| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d").with_context(|| format!("invalid date '{}'", s))
+        };
+        let (from, to) = (parse(&self.from)?, parse(&self.to)?);
+        if to < from {
+            anyhow::bail!("range ends before it starts");
+        }
+        if (to - from).num_days() >= MAX_DAYS {
+            anyhow::bail!("range is longer than {} days", MAX_DAYS);
+        }
+        Ok((from, to))
+    }
+}
+
+/// The local calendar day of unix time `at`.
+pub fn local_day(at: i64) -> NaiveDate {
+    Local
+        .timestamp_opt(at, 0)
+        .single()
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default()
+}
+
+/// One day of activity, for a heatmap cell.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct DayBucket {
+    pub date: String,
+    pub runs: u32,
+    pub succeeded: u32,
+    pub failed: u32,
+    #[ts(type = "number")]
+    pub duration_secs: u64,
+    pub cost_usd: f64,
+}
+
+/// Runs bucketed by the local day they started. Every day in the range gets
+/// a bucket, empty or not.
+pub fn by_day(runs: &[RunRecord], range: &DateRange) -> Result<Vec<DayBucket>> {
+    let (from, to) = range.days()?;
+    let mut buckets: Vec<DayBucket> = from
+        .iter_days()
+        .take_while(|d| *d <= to)
+        .map(|d| DayBucket {
+            date: d.format("%Y-%m-%d").to_string(),
+            ..Default::default()
+        })
+        .collect();
+    for run in runs {
+        let day = local_day(run.started_at);
+        if !(from..=to).contains(&day) {
+            continue;
+        }
+        let bucket = &mut buckets[(day - from).num_days() as usize];
+        bucket.runs += 1;
+        match run.outcome {
+            Outcome::Succeeded => bucket.succeeded += 1,
+            Outcome::Failed => bucket.failed += 1,
+        }
+        bucket.duration_secs += (run.finished_at - run.started_at).max(0) as u64;
+        bucket.cost_usd += run.cost_usd;
+    }
+    Ok(buckets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_on(day: &str, hour: u32, secs: i64, outcome: Outcome) -> RunRecord {
+        let start = NaiveDate::parse_from_str(day, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap();
+        let started_at = Local.from_local_datetime(&start).earliest().unwrap().timestamp();
+        RunRecord {
+            started_at,
+            finished_at: started_at + secs,
+            agent_id: "a".to_string(),
+            ticket_id: "t".to_string(),
+            cost_usd: 0.5,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn buckets_every_day_in_range() {
+        let runs = [
+            run_on("2026-03-01", 10, 600, Outcome::Succeeded),
+            run_on("2026-03-03", 9, 60, Outcome::Failed),
+            run_on("2026-03-03", 15, 120, Outcome::Succeeded),
+            run_on("2026-03-05", 9, 60, Outcome::Succeeded),
+        ];
+        let range = DateRange {
+            from: "2026-03-01".to_string(),
+            to: "2026-03-03".to_string(),
+        };
+        let days = by_day(&runs, &range).unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!((days[0].runs, days[0].duration_secs), (1, 600));
+        assert_eq!(days[1], DayBucket { date: "2026-03-02".to_string(), ..Default::default() });
+        assert_eq!((days[2].succeeded, days[2].failed, days[2].cost_usd), (1, 1, 1.0));
+    }
+
+    #[test]
+    fn rejects_backwards_and_oversized_ranges() {
+        let range = |from: &str, to: &str| DateRange { from: from.to_string(), to: to.to_string() };
+        assert!(range("2026-03-02", "2026-03-01").days().is_err());
+        assert!(range("2024-01-01", "2026-01-01").days().is_err());
+        assert!(range("2026-13-01", "2026-13-02").days().is_err());
+    }
+}
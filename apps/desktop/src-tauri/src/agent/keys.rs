@@ -0,0 +1,191 @@
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use crate::project::audit_log::{self, Actor, AuditEntry, AuditKind};
+use crate::project::store;
+
+/// An Anthropic API key, or an enterprise gateway, from the vault. Held in
+/// memory and never written to disk by the backend.
+#[derive(Clone, Deserialize, TS)]
+#[ts(export)]
+pub struct ApiKey {
+    /// Label usage is accounted under, e.g. "team-payments".
+    pub id: String,
+    /// May be empty for gateways that authenticate some other way.
+    #[serde(default)]
+    pub key: String,
+    /// Gateway to send requests through instead of the Anthropic API.
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKey")
+            .field("id", &self.id)
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiKey {
+    /// Env vars that point the CLI at this key.
+    pub fn env(&self) -> Vec<(String, String)> {
+        let mut env = Vec::new();
+        if !self.key.is_empty() {
+            env.push(("ANTHROPIC_API_KEY".to_string(), self.key.clone()));
+        }
+        if let Some(ref url) = self.base_url {
+            env.push(("ANTHROPIC_BASE_URL".to_string(), url.clone()));
+        }
+        env
+    }
+}
+
+/// Runs and spend billed to one key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct KeyUsage {
+    pub key_id: String,
+    pub runs: u32,
+    pub cost_usd: f64,
+    /// Unix seconds the key was last handed to a run.
+    #[ts(type = "number | null")]
+    pub last_used_at: Option<i64>,
+}
+
+/// The vault's keys, as the frontend last pushed them.
+static VAULT: Mutex<Vec<ApiKey>> = Mutex::new(Vec::new());
+
+/// Serializes the read-modify-write of the usage file across concurrent runs.
+static USAGE: Mutex<()> = Mutex::new(());
+
+/// Replace the keys runs rotate among. The frontend calls this whenever it
+/// loads or saves the vault.
+pub fn set(keys: Vec<ApiKey>) {
+    *VAULT.lock().unwrap_or_else(|e| e.into_inner()) = keys;
+}
+
+/// Per-key usage, at `$HOME/.poietai/metrics/keys.json`.
+fn path() -> PathBuf {
+    store::app_dir().join("metrics").join("keys.json")
+}
+
+fn update(f: impl FnOnce(&mut HashMap<String, KeyUsage>)) -> Result<()> {
+    let _guard = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut usage: HashMap<String, KeyUsage> = store::read_json(&path())?;
+    f(&mut usage);
+    store::write_json(&path(), &usage)
+}
+
+/// The key used longest ago, never-used keys first in the order given.
+fn least_recent<'a>(keys: &'a [ApiKey], usage: &HashMap<String, KeyUsage>) -> Option<&'a ApiKey> {
+    keys.iter()
+        .min_by_key(|k| usage.get(&k.id).and_then(|u| u.last_used_at))
+}
+
+/// Pick the key for `agent_id`'s next claude process and count the run
+/// against it. None when no keys are configured, leaving the CLI on its own
+/// login. Blocking.
+pub fn rotate(agent_id: &str, ticket_id: &str) -> Option<ApiKey> {
+    let keys = VAULT.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if keys.is_empty() {
+        return None;
+    }
+    let mut picked = None;
+    let result = update(|usage| {
+        let Some(key) = least_recent(&keys, usage) else { return };
+        let entry = usage.entry(key.id.clone()).or_insert_with(|| KeyUsage {
+            key_id: key.id.clone(),
+            ..Default::default()
+        });
+        entry.runs += 1;
+        entry.last_used_at = Some(chrono::Utc::now().timestamp());
+        picked = Some(key.clone());
+    });
+    if let Err(e) = result {
+        warn!("[keys::rotate] failed to record key usage: {:#}", e);
+    }
+    // Still rotate through the keys in order if the usage file is unwritable
+    let key = picked.or_else(|| keys.first().cloned())?;
+    audit_log::record(
+        AuditEntry::new(Actor::Automation, AuditKind::TokenAccess, "issue_api_key")
+            .agent(agent_id, ticket_id)
+            .detail(format!("API key '{}'", key.id)),
+    );
+    Some(key)
+}
+
+/// Bill a finished run's cost to `key_id`. Blocking.
+pub fn record_cost(key_id: &str, cost_usd: f64) {
+    let result = update(|usage| {
+        usage
+            .entry(key_id.to_string())
+            .or_insert_with(|| KeyUsage {
+                key_id: key_id.to_string(),
+                ..Default::default()
+            })
+            .cost_usd += cost_usd;
+    });
+    if let Err(e) = result {
+        warn!("[keys::record_cost] failed to record key usage: {:#}", e);
+    }
+}
+
+/// Usage of every key that has run, busiest first.
+pub fn usage() -> Result<Vec<KeyUsage>> {
+    let usage: HashMap<String, KeyUsage> = store::read_json(&path())?;
+    let mut usage: Vec<KeyUsage> = usage.into_values().collect();
+    usage.sort_by(|a, b| b.runs.cmp(&a.runs).then(a.key_id.cmp(&b.key_id)));
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(id: &str) -> ApiKey {
+        ApiKey {
+            id: id.to_string(),
+            key: format!("sk-{}", id),
+            base_url: None,
+        }
+    }
+
+    #[test]
+    fn rotates_to_the_least_recently_used_key() {
+        let keys = [key("a"), key("b"), key("c")];
+        let used = |id: &str, at: i64| KeyUsage {
+            key_id: id.to_string(),
+            last_used_at: Some(at),
+            ..Default::default()
+        };
+        let mut usage = HashMap::new();
+        assert_eq!(least_recent(&keys, &usage).unwrap().id, "a");
+        usage.insert("a".to_string(), used("a", 100));
+        assert_eq!(least_recent(&keys, &usage).unwrap().id, "b");
+        usage.insert("b".to_string(), used("b", 50));
+        usage.insert("c".to_string(), used("c", 200));
+        assert_eq!(least_recent(&keys, &usage).unwrap().id, "b");
+    }
+
+    #[test]
+    fn gateway_without_key_sets_only_the_base_url() {
+        let gateway = ApiKey {
+            id: "gw".to_string(),
+            key: String::new(),
+            base_url: Some("https://llm.example.com".to_string()),
+        };
+        assert_eq!(
+            gateway.env(),
+            [("ANTHROPIC_BASE_URL".to_string(), "https://llm.example.com".to_string())]
+        );
+        assert!(!format!("{:?}", key("a")).contains("sk-a"));
+    }
+}
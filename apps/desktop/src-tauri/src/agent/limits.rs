@@ -0,0 +1,361 @@
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+use ts_rs::TS;
+
+use crate::platform::exec;
+
+/// CPU and memory caps for an agent's process tree, so a runaway build can't
+/// lock up the machine.
+///
+/// Where they hold: Linux hosts and WSL distros running systemd (a cgroup
+/// scope), and the Docker backend. macOS only lowers the run's CPU priority
+/// and can't cap memory. Windows hosts and Linux without systemd run
+/// unlimited, with a warning in the log.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Maximum CPU cores, e.g. 2.0 or 0.5.
+    pub cpus: Option<f64>,
+    /// Maximum memory in MiB. The process tree is killed when it exceeds this.
+    #[ts(type = "number | null")]
+    pub memory_mb: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.cpus.is_none() && self.memory_mb.is_none()
+    }
+}
+
+/// Payload for `agent-resource-limit`, emitted when a run hits its caps.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ResourceLimitPayload {
+    pub agent_id: String,
+    pub ticket_id: String,
+    /// "throttled" or "oom_killed".
+    pub kind: String,
+    pub detail: String,
+}
+
+/// `systemd-run` prefix that runs the command in a transient cgroup scope.
+/// `--scope` execs in place, so the spawned PID is the agent itself.
+pub fn systemd_run_prefix(limits: &ResourceLimits, unit: &str) -> Vec<String> {
+    let mut prefix = vec![
+        "systemd-run".to_string(),
+        "--user".to_string(),
+        "--scope".to_string(),
+        "--quiet".to_string(),
+        format!("--unit={}", unit),
+    ];
+    if let Some(mb) = limits.memory_mb {
+        prefix.push("-p".to_string());
+        prefix.push(format!("MemoryMax={}M", mb));
+        // Without this, the kernel swaps instead of enforcing the cap.
+        prefix.push("-p".to_string());
+        prefix.push("MemorySwapMax=0".to_string());
+    }
+    if let Some(cpus) = limits.cpus {
+        prefix.push("-p".to_string());
+        prefix.push(format!("CPUQuota={}%", (cpus * 100.0).round() as u64));
+    }
+    prefix.push("--".to_string());
+    prefix
+}
+
+/// macOS has no cgroups, and doesn't enforce `ulimit -v`: the best it offers
+/// is demoting CPU scheduling with `taskpolicy` when a CPU limit is set.
+/// Memory limits don't apply.
+pub fn macos_prefix(limits: &ResourceLimits) -> Vec<String> {
+    if limits.cpus.is_some() {
+        ["taskpolicy", "-c", "utility"].map(String::from).to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Line for the WSL launch script that sets `$POIETAI_LIMIT` to a
+/// `systemd-run` prefix when the distro runs systemd, or leaves it empty.
+/// Job Objects on the Windows side can't see processes inside the WSL VM, so
+/// limits are applied from within the distro.
+#[cfg(target_os = "windows")]
+pub fn wsl_script_prefix(limits: &ResourceLimits, unit: &str) -> String {
+    if limits.is_unlimited() {
+        return String::new();
+    }
+    format!(
+        "POIETAI_LIMIT=; [ -d /run/systemd/system ] && POIETAI_LIMIT='{}'\n",
+        systemd_run_prefix(limits, unit).join(" ")
+    )
+}
+
+/// `docker run` flags that enforce the limits inside the container.
+pub fn docker_args(limits: &ResourceLimits) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(cpus) = limits.cpus {
+        args.push("--cpus".to_string());
+        args.push(cpus.to_string());
+    }
+    if let Some(mb) = limits.memory_mb {
+        args.push("--memory".to_string());
+        args.push(format!("{}m", mb));
+        args.push("--memory-swap".to_string());
+        args.push(format!("{}m", mb));
+    }
+    args
+}
+
+/// The wrapper prefix for running under limits on this host, if any.
+pub async fn host_prefix(limits: &ResourceLimits, unit: &str) -> Vec<String> {
+    if limits.is_unlimited() {
+        return vec![];
+    }
+    if cfg!(target_os = "macos") {
+        if limits.memory_mb.is_some() {
+            warn!("[limits::host_prefix] macOS can't cap memory — the memory limit is not applied");
+        }
+        return macos_prefix(limits);
+    }
+    if cfg!(target_os = "linux") {
+        let has_systemd = exec::output(Command::new("systemd-run").arg("--version"), exec::LOCAL)
+            .await
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if has_systemd {
+            return systemd_run_prefix(limits, unit);
+        }
+        warn!("[limits::host_prefix] systemd-run not available — running without resource limits");
+    }
+    if cfg!(target_os = "windows") {
+        warn!("[limits::host_prefix] resource limits aren't supported on Windows hosts — running without them");
+    }
+    vec![]
+}
+
+/// Rebuild `cmd` so it runs under `prefix`. Must be called before env and
+/// working dir are set, since only program and args are carried over.
+pub fn wrap(cmd: Command, prefix: &[String]) -> Command {
+    let Some((program, prefix_args)) = prefix.split_first() else {
+        return cmd;
+    };
+    let std_cmd = cmd.as_std();
+    let mut wrapped = Command::new(program);
+    wrapped
+        .args(prefix_args)
+        .arg(std_cmd.get_program())
+        .args(std_cmd.get_args());
+    wrapped
+}
+
+/// Read a counter from a cgroup v2 flat-keyed file such as `cpu.stat`.
+fn read_counter(contents: &str, key: &str) -> Option<u64> {
+    contents.lines().find_map(|line| {
+        let (k, v) = line.split_once(' ')?;
+        if k == key {
+            v.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// The cgroup v2 directory a process belongs to.
+fn cgroup_dir(pid: u32) -> Option<PathBuf> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    let path = contents.lines().find_map(|l| l.strip_prefix("0::"))?;
+    Some(PathBuf::from("/sys/fs/cgroup").join(path.trim_start_matches('/')))
+}
+
+/// Whether `dir` is the scope [`systemd_run_prefix`] made for `unit`, rather
+/// than a cgroup the run happens to share with other processes.
+fn is_scope_of(dir: &Path, unit: &str) -> bool {
+    dir.file_name()
+        .is_some_and(|name| *name == *format!("{}.scope", unit))
+}
+
+fn oom_kills(dir: &Path) -> u64 {
+    std::fs::read_to_string(dir.join("memory.events"))
+        .ok()
+        .and_then(|events| read_counter(&events, "oom_kill"))
+        .unwrap_or(0)
+}
+
+/// A limited run's cgroup under watch.
+pub struct Watch {
+    dir: PathBuf,
+    /// OOM kills seen so far.
+    oom_kills: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+}
+
+impl Watch {
+    /// Watch the scope `pid` runs in, emitting `agent-resource-limit` the
+    /// first time it is CPU-throttled and whenever the OOM killer fires
+    /// inside it. None when `pid` isn't in `unit`'s scope — limits weren't
+    /// applied, or this isn't Linux.
+    pub fn start(
+        app: AppHandle,
+        agent_id: String,
+        ticket_id: String,
+        pid: u32,
+        unit: &str,
+    ) -> Option<Watch> {
+        let dir = cgroup_dir(pid).filter(|dir| is_scope_of(dir, unit))?;
+        let oom_kills = Arc::new(AtomicU64::new(0));
+        let task = tokio::spawn(watch(
+            app,
+            agent_id,
+            ticket_id,
+            dir.clone(),
+            oom_kills.clone(),
+        ));
+        Some(Watch {
+            dir,
+            oom_kills,
+            task,
+        })
+    }
+
+    /// Stop watching once the run exited. Returns whether the memory limit
+    /// tripped at any point in the run.
+    pub fn stop(self) -> bool {
+        self.task.abort();
+        // The scope may already be gone; what the watch saw still counts
+        self.oom_kills
+            .load(Ordering::Relaxed)
+            .max(oom_kills(&self.dir))
+            > 0
+    }
+}
+
+async fn watch(
+    app: AppHandle,
+    agent_id: String,
+    ticket_id: String,
+    dir: PathBuf,
+    seen_kills: Arc<AtomicU64>,
+) {
+    info!("[limits::watch] agent={} cgroup={:?}", agent_id, dir);
+
+    let emit = |kind: &str, detail: String| {
+        let _ = app.emit(
+            "agent-resource-limit",
+            &ResourceLimitPayload {
+                agent_id: agent_id.clone(),
+                ticket_id: ticket_id.clone(),
+                kind: kind.to_string(),
+                detail,
+            },
+        );
+    };
+
+    let mut reported_throttle = false;
+    // The cgroup disappears when the scope exits, which ends the loop.
+    while dir.exists() {
+        if let Ok(stat) = std::fs::read_to_string(dir.join("cpu.stat")) {
+            let throttled = read_counter(&stat, "nr_throttled").unwrap_or(0);
+            if throttled > 0 && !reported_throttle {
+                reported_throttle = true;
+                emit(
+                    "throttled",
+                    "run is being held to its CPU limit".to_string(),
+                );
+            }
+        }
+        let kills = oom_kills(&dir);
+        if kills > seen_kills.load(Ordering::Relaxed) {
+            seen_kills.store(kills, Ordering::Relaxed);
+            emit(
+                "oom_killed",
+                "a process exceeded the memory limit and was killed".to_string(),
+            );
+        }
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn systemd_prefix_sets_cgroup_properties() {
+        let limits = ResourceLimits {
+            cpus: Some(1.5),
+            memory_mb: Some(4096),
+        };
+        let prefix = systemd_run_prefix(&limits, "poietai-t1");
+        assert!(prefix.contains(&"MemoryMax=4096M".to_string()));
+        assert!(prefix.contains(&"CPUQuota=150%".to_string()));
+        assert_eq!(prefix.last().unwrap(), "--");
+    }
+
+    #[test]
+    fn docker_args_match_limits() {
+        let limits = ResourceLimits {
+            cpus: Some(2.0),
+            memory_mb: None,
+        };
+        assert_eq!(docker_args(&limits), vec!["--cpus", "2"]);
+        assert!(docker_args(&ResourceLimits::default()).is_empty());
+    }
+
+    #[test]
+    fn wrap_prepends_prefix() {
+        let mut cmd = Command::new("claude");
+        cmd.arg("--print");
+        let wrapped = wrap(
+            cmd,
+            &[
+                "taskpolicy".to_string(),
+                "-c".to_string(),
+                "utility".to_string(),
+            ],
+        );
+        let std_cmd = wrapped.as_std();
+        assert_eq!(std_cmd.get_program(), "taskpolicy");
+        let args: Vec<_> = std_cmd.get_args().collect();
+        assert_eq!(args, vec!["-c", "utility", "claude", "--print"]);
+    }
+
+    #[test]
+    fn macos_only_lowers_cpu_priority() {
+        let limits = ResourceLimits {
+            cpus: Some(1.0),
+            memory_mb: Some(2048),
+        };
+        assert_eq!(macos_prefix(&limits), vec!["taskpolicy", "-c", "utility"]);
+        let memory_only = ResourceLimits {
+            cpus: None,
+            memory_mb: Some(2048),
+        };
+        assert!(macos_prefix(&memory_only).is_empty());
+    }
+
+    #[test]
+    fn only_the_runs_own_scope_is_watched() {
+        let unit = "poietai-1234";
+        let scope = Path::new("/sys/fs/cgroup/user.slice/user-1000.slice/user@1000.service/app.slice/poietai-1234.scope");
+        assert!(is_scope_of(scope, unit));
+        // Without systemd-run the agent shares the app's own cgroup
+        assert!(!is_scope_of(
+            Path::new("/sys/fs/cgroup/user.slice/user-1000.slice/session-2.scope"),
+            unit
+        ));
+    }
+
+    #[test]
+    fn reads_cgroup_counters() {
+        let stat = "usage_usec 100\nnr_periods 10\nnr_throttled 3\n";
+        assert_eq!(read_counter(stat, "nr_throttled"), Some(3));
+        assert_eq!(read_counter(stat, "oom_kill"), None);
+    }
+}
@@ -0,0 +1,265 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::AbortHandle;
+use ts_rs::TS;
+
+use super::orchestrator::OrchestratorInput;
+
+/// The run holding a ticket's worktree.
+pub struct RunLock {
+    pub agent_id: String,
+    /// Set when the run uses a worktree other than `.worktrees/<ticket-id>`.
+    pub worktree_path: Option<String>,
+    run_id: String,
+    /// The orchestrator task, once spawned.
+    abort: Option<AbortHandle>,
+    /// What the task was started with, so a paused run can be continued.
+    input: Option<Box<OrchestratorInput>>,
+}
+
+/// Runs in progress, keyed by ticket id.
+pub type LockStore = Arc<Mutex<HashMap<String, RunLock>>>;
+
+pub fn new_store() -> LockStore {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Payload for `run-taken-over`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RunTakenOverPayload {
+    pub ticket_id: String,
+    pub previous_agent_id: String,
+    pub agent_id: String,
+}
+
+/// A claimed lock. Dropped without [`Reservation::commit`] — the run failed
+/// to start — it releases the lock again, and hands back any lock it was
+/// taking over whose run is still going.
+pub struct Reservation {
+    store: LockStore,
+    ticket_id: String,
+    run_id: String,
+    committed: bool,
+    /// The lock taken over, keyed by its ticket, until [`Reservation::take_over`].
+    displaced: Option<(String, RunLock)>,
+}
+
+impl Reservation {
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Stop the run this reservation takes over, returning its agent id.
+    /// Call once the new run is sure to start.
+    pub fn take_over(&mut self) -> Option<String> {
+        let (_, lock) = self.displaced.take()?;
+        if let Some(abort) = lock.abort {
+            abort.abort();
+        }
+        Some(lock.agent_id)
+    }
+
+    /// Attach the spawned run so a later takeover can stop it. `input` is
+    /// what a paused run continues from; runs without one can't be.
+    pub fn commit(mut self, abort: AbortHandle, input: Option<OrchestratorInput>) {
+        self.take_over();
+        let mut map = self.store.lock().unwrap();
+        if let Some(lock) = map.get_mut(&self.ticket_id).filter(|l| l.run_id == self.run_id) {
+            lock.abort = Some(abort);
+            lock.input = input.map(Box::new);
+        }
+        self.committed = true;
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        release(&self.store, &self.ticket_id, &self.run_id);
+        if let Some((ticket_id, lock)) = self.displaced.take() {
+            if !lock.abort.as_ref().is_some_and(|a| a.is_finished()) {
+                let mut map = self.store.lock().unwrap();
+                map.entry(ticket_id).or_insert(lock);
+            }
+        }
+    }
+}
+
+/// Claim `ticket_id` (and `worktree_path`, when overridden) for `agent_id`'s
+/// run `run_id`. When another run holds it, fail — or with `force_takeover`,
+/// claim it anyway; that run keeps going until [`Reservation::take_over`].
+pub fn reserve(
+    store: &LockStore,
+    run_id: &str,
+    ticket_id: &str,
+    agent_id: &str,
+    worktree_path: Option<&str>,
+    force_takeover: bool,
+) -> Result<Reservation, String> {
+    let mut map = store.lock().unwrap();
+    let holder = map
+        .iter()
+        .find(|(id, lock)| {
+            id.as_str() == ticket_id
+                || (worktree_path.is_some() && lock.worktree_path.as_deref() == worktree_path)
+        })
+        .map(|(id, _)| id.clone());
+
+    let mut displaced = None;
+    if let Some(held_ticket) = holder {
+        let lock = &map[&held_ticket];
+        if !force_takeover {
+            return Err(match lock.worktree_path {
+                Some(ref path) if held_ticket != ticket_id => format!(
+                    "worktree {} is in use by agent '{}' on ticket '{}' — stop that run or take it over",
+                    path, lock.agent_id, held_ticket
+                ),
+                _ => format!(
+                    "ticket '{}' is already being worked on by agent '{}' — stop that run or take it over",
+                    held_ticket, lock.agent_id
+                ),
+            });
+        }
+        let lock = map.remove(&held_ticket).expect("holder is in the map");
+        displaced = Some((held_ticket, lock));
+    }
+
+    map.insert(
+        ticket_id.to_string(),
+        RunLock {
+            agent_id: agent_id.to_string(),
+            worktree_path: worktree_path.map(String::from),
+            run_id: run_id.to_string(),
+            abort: None,
+            input: None,
+        },
+    );
+    Ok(Reservation {
+        store: store.clone(),
+        ticket_id: ticket_id.to_string(),
+        run_id: run_id.to_string(),
+        committed: false,
+        displaced,
+    })
+}
+
+/// Release the lock if `run_id` still holds it — a takeover may have
+/// replaced it already.
+pub fn release(store: &LockStore, ticket_id: &str, run_id: &str) {
+    let mut map = store.lock().unwrap();
+    if map.get(ticket_id).is_some_and(|l| l.run_id == run_id) {
+        map.remove(ticket_id);
+    }
+}
+
+/// Stop the run holding `ticket_id` and release its lock, returning what
+/// the run was started with.
+/// A run still starting up has nothing to stop yet, and one with no input
+/// couldn't be continued; both are left alone.
+pub fn stop(store: &LockStore, ticket_id: &str) -> Option<OrchestratorInput> {
+    let mut map = store.lock().unwrap();
+    let held = map.get(ticket_id)?;
+    held.abort.as_ref()?;
+    held.input.as_ref()?;
+    let lock = map.remove(ticket_id)?;
+    if let Some(abort) = lock.abort {
+        abort.abort();
+    }
+    lock.input.map(|input| *input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(ticket_id: &str) -> OrchestratorInput {
+        serde_json::from_value(serde_json::json!({
+            "agent_id": "a1",
+            "ticket_id": ticket_id,
+            "ticket_slug": "fix-retry",
+            "prompt": "Fix the retry backoff",
+            "system_prompt": "",
+            "repo_root": "/repo",
+            "gh_token": "",
+            "phase": "build",
+            "worktree_path_override": null,
+            "plan_artifact": null,
+            "group_id": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn second_run_on_a_ticket_is_refused() {
+        let store = new_store();
+        let first = reserve(&store, "r1", "t-1", "a1", None, false).unwrap();
+        let err = reserve(&store, "r2", "t-1", "a2", None, false).err().unwrap();
+        assert!(err.contains("agent 'a1'"));
+        drop(first);
+        assert!(reserve(&store, "r2", "t-1", "a2", None, false).is_ok());
+    }
+
+    #[test]
+    fn overridden_worktree_is_locked_too() {
+        let store = new_store();
+        let _first = reserve(&store, "r1", "t-1", "a1", Some("/wt/t-1"), false).unwrap();
+        let err = reserve(&store, "r2", "t-2", "a2", Some("/wt/t-1"), false).err().unwrap();
+        assert!(err.contains("worktree /wt/t-1"));
+    }
+
+    #[tokio::test]
+    async fn takeover_aborts_the_prior_run() {
+        let store = new_store();
+        let first = reserve(&store, "r1", "t-1", "a1", None, false).unwrap();
+        let first_run = first.run_id().to_string();
+        let task = tokio::spawn(std::future::pending::<()>());
+        first.commit(task.abort_handle(), Some(input("t-1")));
+
+        let mut second = reserve(&store, "r2", "t-1", "a2", None, true).unwrap();
+        tokio::task::yield_now().await;
+        assert!(!task.is_finished());
+        assert_eq!(second.take_over().as_deref(), Some("a1"));
+        assert!(task.await.unwrap_err().is_cancelled());
+        // The aborted run's late release must not drop the new lock
+        release(&store, "t-1", &first_run);
+        assert!(store.lock().unwrap().contains_key("t-1"));
+        drop(second);
+        assert!(store.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn abandoned_takeover_leaves_the_prior_run_its_lock() {
+        let store = new_store();
+        let first = reserve(&store, "r1", "t-1", "a1", None, false).unwrap();
+        let task = tokio::spawn(std::future::pending::<()>());
+        first.commit(task.abort_handle(), Some(input("t-1")));
+
+        // The new run's preflight failed
+        drop(reserve(&store, "r2", "t-1", "a2", None, true).unwrap());
+        tokio::task::yield_now().await;
+        assert!(!task.is_finished());
+        assert!(reserve(&store, "r3", "t-1", "a3", None, false).err().unwrap().contains("agent 'a1'"));
+        task.abort();
+    }
+
+    #[tokio::test]
+    async fn stopping_a_run_hands_back_its_input() {
+        let store = new_store();
+        let run = reserve(&store, "r1", "t-1", "a1", None, false).unwrap();
+        let task = tokio::spawn(std::future::pending::<()>());
+        run.commit(task.abort_handle(), Some(input("t-1")));
+
+        let starting = reserve(&store, "r2", "t-2", "a2", None, false).unwrap();
+        assert!(stop(&store, "t-2").is_none());
+        drop(starting);
+
+        let stopped = stop(&store, "t-1").unwrap();
+        assert_eq!(stopped.ticket_slug, "fix-retry");
+        assert!(task.await.unwrap_err().is_cancelled());
+        assert!(stop(&store, "t-1").is_none());
+    }
+}
@@ -1,5 +1,42 @@
+pub mod annotations;
+pub mod backend;
+pub mod checkpoints;
+pub mod coalesce;
+pub mod costs;
+pub mod delegate;
+pub mod estimate;
 pub mod events;
+pub mod export;
+pub mod filter;
+pub mod gateway;
+pub mod guard;
+pub mod history;
+pub mod keys;
+pub mod limits;
+pub mod locks;
 pub mod orchestrator;
+pub mod overflow;
 pub mod parsers;
+pub mod pinned;
+pub mod policy;
+pub mod postmortem;
+pub mod pr_approval;
+pub mod pr_gate;
 pub mod process;
+pub mod recovery;
+pub mod replay;
+pub mod resume;
+pub mod risk;
+pub mod scheduler;
+pub mod search;
+pub mod session;
+pub mod standup;
 pub mod state;
+pub mod terminal;
+pub mod test_first;
+pub mod tour;
+pub mod tournament;
+pub mod transcript;
+pub mod utilization;
+pub mod verify;
+pub mod voice;
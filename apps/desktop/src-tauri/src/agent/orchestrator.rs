@@ -1,59 +1,85 @@
 use anyhow::{Context, Result};
-use log::info;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::agent::pr_approval::{self, Decision};
 use crate::agent::process::{self, AgentRunConfig};
+use crate::agent::state::AgentStatus;
 use crate::context::builder::{ContextInput, TicketPhase};
 use crate::git;
+use crate::github;
+use crate::platform::exec;
+use crate::project::audit_log::{self, Actor, AuditEntry, AuditKind};
+use crate::text;
 use crate::AppState;
+use ts_rs::TS;
 
 // ── Orchestrator event payloads (emitted to frontend) ──
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct PhaseStartedPayload {
     pub ticket_id: String,
     pub phase: String,
     pub group_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Progress from post-creation worktree setup (LFS pull, bootstrap, ...).
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorktreeSetupPayload {
+    pub ticket_id: String,
+    pub agent_id: String,
+    pub step: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct PhaseCompletedPayload {
     pub ticket_id: String,
     pub phase: String,
     pub artifact_content: Option<String>,
     pub result_summary: Option<serde_json::Value>,
     pub blocked: bool,
+    /// Definition-of-done results for build phases, when the project has a policy.
+    pub policy_report: Option<crate::agent::policy::PolicyReport>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct FanOutPayload {
     pub ticket_id: String,
     pub groups: Vec<FanOutGroup>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct FanOutGroup {
     pub group_id: String,
     pub agent_role: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct FanInPayload {
     pub ticket_id: String,
     pub merge_status: String,
     pub conflict_details: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct OrchestratorBlockedPayload {
     pub ticket_id: String,
     pub reason: String,
     pub details: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct OrchestratorQuestionPayload {
     pub ticket_id: String,
     pub agent_id: String,
@@ -83,7 +109,8 @@ pub struct TaskGroupRun {
 }
 
 /// Configuration passed from the frontend/Tauri command when starting a ticket run.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct OrchestratorInput {
     pub agent_id: String,
     pub ticket_id: String,
@@ -92,12 +119,75 @@ pub struct OrchestratorInput {
     pub system_prompt: String,
     pub repo_root: String,
     pub gh_token: String,
+    /// When set, each phase mints a fresh App installation token in place of
+    /// `gh_token`, so long tickets outlive the token's hour.
+    #[serde(default)]
+    pub github_app: Option<github::app_auth::GitHubAppCredentials>,
     pub phase: String,
     pub worktree_path_override: Option<String>,
     pub plan_artifact: Option<String>,
     /// Optional group ID — set during fan-out builds so agent events carry the
     /// group identifier through to the frontend.
     pub group_id: Option<String>,
+    /// GitLab token for projects configured against a GitLab instance.
+    #[serde(default)]
+    pub gitlab_token: Option<String>,
+    /// Set when agents push to a fork and open cross-repo PRs.
+    #[serde(default)]
+    pub fork: Option<github::fork::ForkTarget>,
+    /// Continue this session instead of starting a fresh one.
+    #[serde(default)]
+    pub resume_session_id: Option<String>,
+    /// Cap on agentic turns for the run.
+    #[serde(default)]
+    pub max_turns: Option<u32>,
+    /// Build in pair-programming mode: this agent drives, another reviews.
+    #[serde(default)]
+    pub pair: Option<PairConfig>,
+    /// Build with several agents in parallel and let the user pick a winner.
+    #[serde(default)]
+    pub tournament: Option<crate::agent::tournament::TournamentConfig>,
+    /// Build test-first: a QA agent writes failing tests, then this agent
+    /// makes them pass.
+    #[serde(default)]
+    pub test_first: Option<crate::agent::test_first::TestFirstConfig>,
+    /// Shell command that must pass before the agent may open a PR.
+    #[serde(default)]
+    pub pr_gate: Option<String>,
+}
+
+/// Pair-programming settings for a build.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PairConfig {
+    /// The agent that critiques each driver turn.
+    pub reviewer_agent_id: String,
+    /// Driver/reviewer exchanges before the pair gives up without approval.
+    #[serde(default = "default_pair_rounds")]
+    pub max_rounds: u32,
+    /// Agentic turns each side gets per round.
+    #[serde(default = "default_pair_turns")]
+    pub turns_per_round: u32,
+}
+
+fn default_pair_rounds() -> u32 {
+    3
+}
+
+fn default_pair_turns() -> u32 {
+    25
+}
+
+/// Emitted after each driver or reviewer turn in pair mode.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PairTurnPayload {
+    pub ticket_id: String,
+    pub round: u32,
+    pub agent_id: String,
+    /// "driver" or "reviewer".
+    pub role: String,
+    pub message: String,
 }
 
 // ── Plan artifact types (deserialized from input.plan_artifact JSON) ────────
@@ -130,8 +220,8 @@ pub struct FileConflictCheck {
 
 /// Return the allowed tool set for a given phase.
 ///
-/// Read-only phases (Validate, Qa, Security) get a minimal set;
-/// all other phases get the full builder tool set.
+/// Read-only phases (Validate, Qa, Security) get a minimal set; Tests can
+/// write but not reach GitHub; all other phases get the full builder tool set.
 pub fn phase_tools(phase: &TicketPhase) -> Vec<String> {
     match phase {
         TicketPhase::Validate | TicketPhase::Qa | TicketPhase::Security => vec![
@@ -140,6 +230,23 @@ pub fn phase_tools(phase: &TicketPhase) -> Vec<String> {
             "Glob".to_string(),
             "Bash(git:*)".to_string(),
         ],
+        TicketPhase::Tests => vec![
+            "Read".to_string(),
+            "Edit".to_string(),
+            "Write".to_string(),
+            "Glob".to_string(),
+            "Grep".to_string(),
+            "Bash(git:*)".to_string(),
+            "Bash(cargo:*)".to_string(),
+            "Bash(npm:*)".to_string(),
+            "Bash(npx:*)".to_string(),
+            "Bash(node:*)".to_string(),
+            "Bash(pnpm:*)".to_string(),
+            "Bash(yarn:*)".to_string(),
+            "Bash(ls:*)".to_string(),
+            "Bash(mkdir:*)".to_string(),
+            "Bash(cat:*)".to_string(),
+        ],
         TicketPhase::Brief
         | TicketPhase::Design
         | TicketPhase::Plan
@@ -174,26 +281,30 @@ pub fn phase_tools(phase: &TicketPhase) -> Vec<String> {
 /// Tries `git diff HEAD~1` first. If that fails (e.g. only one commit on
 /// the branch), falls back to `git diff --cached` to catch staged changes.
 pub async fn get_worktree_diff(worktree_path: &str) -> Result<String> {
-    let output = tokio::process::Command::new("git")
-        .args(["diff", "HEAD~1"])
-        .current_dir(worktree_path)
-        .output()
-        .await
-        .context("failed to run git diff HEAD~1")?;
+    let output = exec::output(
+        tokio::process::Command::new("git")
+            .args(["diff", "HEAD~1"])
+            .current_dir(worktree_path),
+        exec::LOCAL,
+    )
+    .await
+    .context("failed to run git diff HEAD~1")?;
 
     if output.status.success() && !output.stdout.is_empty() {
-        return String::from_utf8(output.stdout).context("git diff output was not valid UTF-8");
+        return Ok(text::lossy(output.stdout));
     }
 
     // Fallback: diff cached changes against HEAD
-    let fallback = tokio::process::Command::new("git")
-        .args(["diff", "--cached"])
-        .current_dir(worktree_path)
-        .output()
-        .await
-        .context("failed to run git diff --cached")?;
-
-    String::from_utf8(fallback.stdout).context("git diff fallback output was not valid UTF-8")
+    let fallback = exec::output(
+        tokio::process::Command::new("git")
+            .args(["diff", "--cached"])
+            .current_dir(worktree_path),
+        exec::LOCAL,
+    )
+    .await
+    .context("failed to run git diff --cached")?;
+
+    Ok(text::lossy(fallback.stdout))
 }
 
 /// Run the build phase using parallel fan-out across task groups.
@@ -260,25 +371,29 @@ pub async fn run_fan_out_build(
 
         // Clean up any stale worktree at this path
         if child_path.exists() {
-            let _ = tokio::process::Command::new("git")
-                .args(["worktree", "remove", "--force"])
-                .arg(&child_path)
-                .current_dir(&repo_root)
-                .output()
-                .await;
+            let _ = exec::output(
+                tokio::process::Command::new("git")
+                    .args(["worktree", "remove", "--force"])
+                    .arg(&child_path)
+                    .current_dir(&repo_root),
+                exec::LOCAL,
+            )
+            .await;
             let _ = tokio::fs::remove_dir_all(&child_path).await;
         }
 
         // Create child worktree branching from parent branch
-        let add_output = tokio::process::Command::new("git")
-            .args(["worktree", "add", "-B"])
-            .arg(&child_branch)
-            .arg(&child_path)
-            .arg(&parent_branch)
-            .current_dir(&repo_root)
-            .output()
-            .await
-            .context("failed to run git worktree add for child")?;
+        let add_output = exec::output(
+            tokio::process::Command::new("git")
+                .args(["worktree", "add", "-B"])
+                .arg(&child_branch)
+                .arg(&child_path)
+                .arg(&parent_branch)
+                .current_dir(&repo_root),
+            exec::LOCAL,
+        )
+        .await
+        .context("failed to run git worktree add for child")?;
 
         if !add_output.status.success() {
             let stderr = String::from_utf8_lossy(&add_output.stderr);
@@ -308,10 +423,19 @@ pub async fn run_fan_out_build(
             system_prompt: input.system_prompt.clone(),
             repo_root: input.repo_root.clone(),
             gh_token: input.gh_token.clone(),
+            github_app: input.github_app.clone(),
             phase: "build".to_string(),
             worktree_path_override: Some(child_path.to_string_lossy().to_string()),
             plan_artifact: input.plan_artifact.clone(),
             group_id: Some(group.group_id.clone()),
+            gitlab_token: input.gitlab_token.clone(),
+            fork: input.fork.clone(),
+            resume_session_id: None,
+            max_turns: input.max_turns,
+            pair: None,
+            tournament: None,
+            test_first: None,
+            pr_gate: input.pr_gate.clone(),
         };
 
         let group_id = group.group_id.clone();
@@ -340,7 +464,7 @@ pub async fn run_fan_out_build(
 
     while let Some(join_result) = join_set.join_next().await {
         match join_result {
-            Ok((group_id, Ok((completed, _session_id)))) => {
+            Ok((group_id, Ok((completed, _outcome)))) => {
                 if completed.blocked {
                     all_succeeded = false;
                     failure_details.push(format!("Group {} was blocked", group_id));
@@ -385,20 +509,24 @@ pub async fn run_fan_out_build(
         let child_branch = format!("{}-{}", parent_branch, group.group_id);
         let child_path = worktrees_dir.join(format!("{}-{}", input.ticket_id, group.group_id));
 
-        let merge_output = tokio::process::Command::new("git")
-            .args(["merge", "--no-ff", &child_branch])
-            .current_dir(&parent_wt_path)
-            .output()
-            .await
-            .context("failed to run git merge")?;
+        let merge_output = exec::output(
+            tokio::process::Command::new("git")
+                .args(["merge", "--no-ff", &child_branch])
+                .current_dir(&parent_wt_path),
+            exec::LOCAL,
+        )
+        .await
+        .context("failed to run git merge")?;
 
         if !merge_output.status.success() {
             // Merge conflict — abort and report
-            let _ = tokio::process::Command::new("git")
-                .args(["merge", "--abort"])
-                .current_dir(&parent_wt_path)
-                .output()
-                .await;
+            let _ = exec::output(
+                tokio::process::Command::new("git")
+                    .args(["merge", "--abort"])
+                    .current_dir(&parent_wt_path),
+                exec::LOCAL,
+            )
+            .await;
 
             let stderr = String::from_utf8_lossy(&merge_output.stderr).to_string();
 
@@ -428,13 +556,21 @@ pub async fn run_fan_out_build(
             return Ok(true);
         }
 
+        audit_log::record(
+            AuditEntry::new(Actor::Automation, AuditKind::Merge, "merge_group_branch")
+                .agent(&input.agent_id, &input.ticket_id)
+                .detail(format!("{} into {}", child_branch, parent_branch)),
+        );
+
         // Clean merge — remove child worktree
-        let _ = tokio::process::Command::new("git")
-            .args(["worktree", "remove", "--force"])
-            .arg(&child_path)
-            .current_dir(&repo_root)
-            .output()
-            .await;
+        let _ = exec::output(
+            tokio::process::Command::new("git")
+                .args(["worktree", "remove", "--force"])
+                .arg(&child_path)
+                .current_dir(&repo_root),
+            exec::LOCAL,
+        )
+        .await;
     }
 
     // All merges succeeded
@@ -455,6 +591,170 @@ pub async fn run_fan_out_build(
     Ok(false)
 }
 
+/// Stop a multi-phase run at a phase boundary when the budget is exhausted
+/// and the user asked for running tickets to pause. Emits
+/// `orchestrator-blocked`; the ticket can be resumed once there's budget.
+pub(crate) fn budget_pause(input: &OrchestratorInput, app: &AppHandle) -> bool {
+    let Some(exceeded) = crate::agent::costs::should_pause() else {
+        return false;
+    };
+    info!(
+        "[orchestrator::budget_pause] ticket={} paused: {}",
+        input.ticket_id,
+        exceeded.message()
+    );
+    let _ = app.emit(
+        "orchestrator-blocked",
+        &OrchestratorBlockedPayload {
+            ticket_id: input.ticket_id.clone(),
+            reason: "Spending cap reached".to_string(),
+            details: Some(exceeded.message()),
+        },
+    );
+    true
+}
+
+/// Marker the reviewer ends with once nothing blocking is left.
+const PAIR_APPROVED: &str = "PAIR: APPROVED";
+
+/// Prompt for the reviewer's turn: the driver's report plus the current diff.
+fn reviewer_prompt(round: u32, driver_message: &str, diff: &str) -> String {
+    format!(
+        "You are pair-programming as the reviewer (round {round}). The driver just finished a \
+         turn and reported:\n\n{driver_message}\n\n## Current diff\n\n```diff\n{diff}\n```\n\n\
+         Critique the work so far: bugs, missing pieces, deviations from the ticket, anything the \
+         driver should change next. Be specific and point at files and lines. You do not edit code. \
+         If nothing CRITICAL or WARNING is left, end with the line `{PAIR_APPROVED}`.",
+    )
+}
+
+/// Prompt for a driver turn after the first: the reviewer's critique.
+fn driver_followup_prompt(round: u32, reviewer_message: &str) -> String {
+    format!(
+        "Round {round}. Your pair reviewer says:\n\n{reviewer_message}\n\n\
+         Address the feedback, then finish your turn with a short summary of what you changed \
+         and anything you disagree with."
+    )
+}
+
+/// Run the build phase as a pair: the input's agent drives, the configured
+/// reviewer critiques, in alternating turns on the same worktree. Each side
+/// keeps its own session across rounds; the backend passes the other side's
+/// last message in as the next prompt. Returns `blocked = true` when the
+/// reviewer hasn't approved after `max_rounds`.
+pub async fn run_pair_build(
+    input: &OrchestratorInput,
+    pair: &PairConfig,
+    app: &AppHandle,
+    mcp_port: u16,
+) -> Result<bool> {
+    let repo_root = PathBuf::from(&input.repo_root);
+    let worktree_path = input
+        .worktree_path_override
+        .clone()
+        .unwrap_or_else(|| {
+            git::worktree::Worktree::path_for(&repo_root, &input.ticket_id)
+                .to_string_lossy()
+                .to_string()
+        });
+
+    info!(
+        "[orchestrator::run_pair_build] ticket={} driver={} reviewer={} max_rounds={}",
+        input.ticket_id, input.agent_id, pair.reviewer_agent_id, pair.max_rounds
+    );
+
+    let emit_turn = |round: u32, agent_id: &str, role: &str, message: &str| {
+        let _ = app.emit(
+            "pair-turn",
+            &PairTurnPayload {
+                ticket_id: input.ticket_id.clone(),
+                round,
+                agent_id: agent_id.to_string(),
+                role: role.to_string(),
+                message: message.to_string(),
+            },
+        );
+    };
+
+    let reviewer_name =
+        crate::agent::state::get_agent(&app.state::<AppState>().agents, &pair.reviewer_agent_id).await
+            .map(|a| a.name)
+            .unwrap_or_else(|| pair.reviewer_agent_id.clone());
+
+    let mut driver_session: Option<String> = None;
+    let mut reviewer_session: Option<String> = None;
+    let mut reviewer_message = String::new();
+
+    for round in 1..=pair.max_rounds {
+        if round > 1 && budget_pause(input, app) {
+            return Ok(true);
+        }
+
+        // ── Driver turn ──
+        let driver_input = if round == 1 {
+            OrchestratorInput {
+                prompt: format!(
+                    "{}\n\nYou are pair-programming: {} reviews your work after each turn. \
+                     Work in reviewable increments and end each turn with a short summary of \
+                     what you did and what's next.",
+                    input.prompt, reviewer_name
+                ),
+                max_turns: Some(pair.turns_per_round),
+                pair: None,
+                ..input.clone()
+            }
+        } else {
+            OrchestratorInput {
+                prompt: driver_followup_prompt(round, &reviewer_message),
+                worktree_path_override: Some(worktree_path.clone()),
+                resume_session_id: driver_session.clone(),
+                max_turns: Some(pair.turns_per_round),
+                pair: None,
+                ..input.clone()
+            }
+        };
+        let (_, driver_outcome) = run_phase(&driver_input, app, mcp_port).await?;
+        driver_session = driver_outcome.session_id.or(driver_session);
+        let driver_message = driver_outcome.result.unwrap_or_default();
+        emit_turn(round, &input.agent_id, "driver", &driver_message);
+
+        // ── Reviewer turn ──
+        let diff = get_worktree_diff(&worktree_path).await.unwrap_or_default();
+        let reviewer_input = OrchestratorInput {
+            agent_id: pair.reviewer_agent_id.clone(),
+            prompt: reviewer_prompt(round, &driver_message, &diff),
+            phase: "qa".to_string(),
+            worktree_path_override: Some(worktree_path.clone()),
+            resume_session_id: reviewer_session.clone(),
+            max_turns: Some(pair.turns_per_round),
+            pair: None,
+            ..input.clone()
+        };
+        let (_, reviewer_outcome) = run_phase(&reviewer_input, app, mcp_port).await?;
+        reviewer_session = reviewer_outcome.session_id.or(reviewer_session);
+        reviewer_message = reviewer_outcome.result.unwrap_or_default();
+        emit_turn(round, &pair.reviewer_agent_id, "reviewer", &reviewer_message);
+
+        if reviewer_message.contains(PAIR_APPROVED) {
+            info!(
+                "[orchestrator::run_pair_build] ticket={} approved in round {}",
+                input.ticket_id, round
+            );
+            return Ok(false);
+        }
+    }
+
+    let _ = app.emit(
+        "orchestrator-blocked",
+        &OrchestratorBlockedPayload {
+            ticket_id: input.ticket_id.clone(),
+            reason: format!("Pair reviewer did not approve after {} rounds", pair.max_rounds),
+            details: Some(reviewer_message),
+        },
+    );
+    Ok(true)
+}
+
 /// Run a single phase to completion.
 ///
 /// 1. Emits `orchestrator-phase-started`
@@ -462,12 +762,12 @@ pub async fn run_fan_out_build(
 /// 3. Builds the system prompt with the phase-specific section appended
 /// 4. Calls `process::run` and waits for it to finish
 /// 5. Emits `orchestrator-phase-completed`
-/// 6. Returns the completed payload and optional session_id
+/// 6. Returns the completed payload and the run outcome (session id, final message)
 pub async fn run_phase(
     input: &OrchestratorInput,
     app: &AppHandle,
     mcp_port: u16,
-) -> Result<(PhaseCompletedPayload, Option<String>)> {
+) -> Result<(PhaseCompletedPayload, process::RunOutcome)> {
     // Parse the phase string into the enum
     let phase: TicketPhase = serde_json::from_str(&format!("\"{}\"", input.phase))
         .unwrap_or_default();
@@ -489,34 +789,125 @@ pub async fn run_phase(
 
     // Resolve agent state for worktree config
     let app_state = app.state::<AppState>();
-    let agent = crate::agent::state::get_agent(&app_state.agents, &input.agent_id);
+    let agent = crate::agent::state::get_agent(&app_state.agents, &input.agent_id).await;
     let agent_name = agent.as_ref().map(|a| a.name.clone()).unwrap_or_else(|| "Agent".to_string());
     let agent_role = agent.as_ref().map(|a| a.role.clone()).unwrap_or_else(|| "engineer".to_string());
 
+    let repo_root = PathBuf::from(&input.repo_root);
+    let settings = crate::project::settings::load_project(&repo_root).unwrap_or_default();
+    let wt_config = git::worktree::WorktreeConfig {
+        repo_root: repo_root.clone(),
+        ticket_id: input.ticket_id.clone(),
+        ticket_slug: input.ticket_slug.clone(),
+        agent_name: agent_name.clone(),
+        agent_email: format!("{}@poietai.ai", agent_role),
+        init_submodules: !settings.skip_submodule_init,
+        cache: settings.cache.clone(),
+    };
+    // The agent's identity and tokens, whether the worktree is new or one it
+    // continues in — a resumed build still has to push and open its PR
+    let gh_token = github_token(input, app).await?;
+    let mut env = git::worktree::agent_env(&wt_config, &gh_token);
+    // Point glab at the project's GitLab instance, if one is configured
+    env.extend(crate::project::settings::gitlab_env(
+        &settings,
+        input.gitlab_token.as_deref(),
+    ));
+    if let Some(ref fork) = input.fork {
+        env.extend(github::fork::fork_env(fork));
+    }
+
     // Create worktree or use override
-    let (working_dir, env) = if let Some(ref override_path) = input.worktree_path_override {
+    let working_dir = if let Some(ref override_path) = input.worktree_path_override {
         info!("[orchestrator::run_phase] using worktree override at {}", override_path);
-        (PathBuf::from(override_path), vec![])
+        PathBuf::from(override_path)
     } else {
-        let repo_root = PathBuf::from(&input.repo_root);
-        let wt_config = git::worktree::WorktreeConfig {
-            repo_root: repo_root.clone(),
-            ticket_id: input.ticket_id.clone(),
-            ticket_slug: input.ticket_slug.clone(),
-            agent_name: agent_name.clone(),
-            agent_email: format!("{}@poietai.ai", agent_role),
-        };
         let worktree = git::worktree::create(&wt_config)
+            .await
             .context("failed to create worktree for phase")?;
-        let env = git::worktree::agent_env(&wt_config, &input.gh_token);
+
+        // Replace LFS pointer files with real content before the agent starts
+        if git::lfs::uses_lfs(&worktree.path) {
+            let progress_app = app.clone();
+            let ticket_id = input.ticket_id.clone();
+            let agent_id = input.agent_id.clone();
+            let path = worktree.path.clone();
+            tokio::task::spawn_blocking(move || {
+                git::lfs::pull(&path, |line| {
+                    let _ = progress_app.emit(
+                        "worktree-setup-progress",
+                        &WorktreeSetupPayload {
+                            ticket_id: ticket_id.clone(),
+                            agent_id: agent_id.clone(),
+                            step: "lfs".to_string(),
+                            message: line.to_string(),
+                        },
+                    );
+                })
+            })
+            .await
+            .context("git lfs pull task panicked")??;
+        }
+
+        // Install dependencies so the agent doesn't spend turns doing it
+        if let Some(bootstrap) = settings.bootstrap.clone() {
+            let progress_app = app.clone();
+            let ticket_id = input.ticket_id.clone();
+            let agent_id = input.agent_id.clone();
+            let root = repo_root.clone();
+            let path = worktree.path.clone();
+            let bootstrap_env = env.clone();
+            tokio::task::spawn_blocking(move || {
+                git::bootstrap::run(&bootstrap, &root, &path, &bootstrap_env, |line| {
+                    let _ = progress_app.emit(
+                        "worktree-setup-progress",
+                        &WorktreeSetupPayload {
+                            ticket_id: ticket_id.clone(),
+                            agent_id: agent_id.clone(),
+                            step: "bootstrap".to_string(),
+                            message: line.to_string(),
+                        },
+                    );
+                })
+            })
+            .await
+            .context("bootstrap task panicked")??;
+        }
 
         // Save worktree path to agent state
-        if let Some(mut a) = crate::agent::state::get_agent(&app_state.agents, &input.agent_id) {
+        crate::agent::state::update_agent(&app_state.agents, &input.agent_id, |a| {
             a.worktree_path = Some(worktree.path.to_string_lossy().to_string());
-            crate::agent::state::upsert_agent(&app_state.agents, a);
-        }
+        }).await;
 
-        (worktree.path, env)
+        worktree.path
+    };
+
+    let project_settings =
+        crate::project::settings::load_project(Path::new(&input.repo_root)).unwrap_or_default();
+    let dod = project_settings
+        .definition_of_done
+        .clone()
+        .filter(|_| phase == TicketPhase::Build);
+    // Builds check the ticket's acceptance criteria when they go to open the PR
+    let verify = project_settings
+        .verify_acceptance
+        .clone()
+        .filter(|_| phase == TicketPhase::Build);
+
+    let tour_section = crate::agent::tour::section_for(Path::new(&input.repo_root), &phase).await;
+    // What just landed, so the agent doesn't redo it or build on replaced code
+    let repo_root = PathBuf::from(&input.repo_root);
+    let recent = tokio::task::spawn_blocking(move || github::recent::merged(&repo_root)).await;
+    let recent_section = match recent {
+        Ok(Ok(prs)) => crate::context::builder::recent_changes_section(&prs).unwrap_or_default(),
+        Ok(Err(e)) => {
+            info!("[run_phase] no recent PRs for the prompt: {:#}", e);
+            String::new()
+        }
+        Err(e) => {
+            warn!("[run_phase] recent PR task panicked: {}", e);
+            String::new()
+        }
     };
 
     // Append phase-specific instruction section to the system prompt
@@ -534,13 +925,28 @@ pub async fn run_phase(
             agent_id: "",
         };
         let phase_section = dummy.phase_prompt_section(&phase);
-        if phase_section.is_empty() {
-            input.system_prompt.clone()
-        } else {
-            format!("{}\n\n{}", input.system_prompt, phase_section)
-        }
+        let dod_section = dod.as_ref().map(crate::agent::policy::prompt_section).unwrap_or_default();
+        let security_section = crate::project::audit::load_tracked(Path::new(&input.repo_root))
+            .map(|tracked| crate::project::audit::prompt_section(&tracked, &input.ticket_id))
+            .unwrap_or_default();
+        let conventions_section =
+            crate::context::conventions::load(Path::new(&input.repo_root)).unwrap_or_default();
+        [
+            input.system_prompt.as_str(),
+            &tour_section,
+            &conventions_section,
+            &recent_section,
+            &phase_section,
+            &dod_section,
+            &security_section,
+        ]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
     };
 
+
     let run_config = AgentRunConfig {
         agent_id: input.agent_id.clone(),
         ticket_id: input.ticket_id.clone(),
@@ -549,22 +955,116 @@ pub async fn run_phase(
         allowed_tools: phase_tools(&phase),
         working_dir: working_dir.clone(),
         env,
-        resume_session_id: None,
+        resume_session_id: input.resume_session_id.clone(),
         mcp_port,
         group_id: input.group_id.clone(),
+        backend: project_settings.execution,
+        limits: project_settings.limits,
+        max_turns: input.max_turns,
+        pr_gate: input.pr_gate.clone(),
+        verify_before_pr: verify.is_some(),
     };
 
-    // Run the agent process and wait for completion
-    let session_id = process::run(run_config, app.clone())
+    // Run the agent process and wait for completion. A PR held for the
+    // acceptance check or the user pauses the phase until they're done, so
+    // later phases don't run without it.
+    let mut waiter = pr_approval::wait(&app_state.pr_decisions, &input.agent_id, &input.ticket_id);
+    let mut outcome = process::run_capturing(run_config.clone(), app.clone())
         .await
         .context("agent process failed during phase")?;
+    let mut verify_before_pr = run_config.verify_before_pr;
+    loop {
+        let held = outcome.verify_held || outcome.pr_held;
+        let Some(session_id) = outcome.session_id.clone().filter(|_| held) else {
+            break;
+        };
+        let prompt = if outcome.verify_held {
+            // Checked once per phase; the resumed run opens the PR either way
+            verify_before_pr = false;
+            let report = match verify {
+                Some(ref config) => verify_acceptance(input, config, &working_dir, app).await,
+                None => None,
+            };
+            crate::agent::verify::resume_prompt(report.as_ref())
+        } else {
+            let waiting = AgentStatus::WaitingForUser;
+            crate::agent::state::set_status(&app_state.agents, &input.agent_id, waiting).await;
+            match waiter.next().await {
+                Some(Decision::Approved) => pr_approval::APPROVED_PROMPT.to_string(),
+                Some(Decision::Rejected(Some(feedback))) => pr_approval::rejected_prompt(&feedback),
+                // Turned down outright: the phase ends without its PR
+                _ => break,
+            }
+        };
+        let after = if outcome.verify_held {
+            "acceptance check"
+        } else {
+            "PR decision"
+        };
+        info!(
+            "[orchestrator::run_phase] resuming ticket={} after the {}",
+            input.ticket_id, after
+        );
+        crate::agent::state::set_status(&app_state.agents, &input.agent_id, AgentStatus::Working).await;
+        // The decision can take longer than an App token lasts
+        let mut env = run_config.env.clone();
+        let gh_token = github_token(input, app).await?;
+        env.retain(|(key, _)| key != "GH_TOKEN");
+        env.push(("GH_TOKEN".to_string(), gh_token));
+        let resumed = AgentRunConfig {
+            prompt,
+            // --resume replays the original session context
+            system_prompt: String::new(),
+            env,
+            resume_session_id: Some(session_id),
+            verify_before_pr,
+            ..run_config.clone()
+        };
+        outcome = process::run_capturing(resumed, app.clone())
+            .await
+            .context("agent process failed during phase")?;
+    }
+    drop(waiter);
+    if !outcome.pr_held {
+        watch_opened_pr(input, app, &working_dir).await;
+    }
+
+    // Check the finished build against the project's definition of done
+    let policy_report = match dod {
+        Some(policy) => {
+            let dir = working_dir.clone();
+            match tokio::task::spawn_blocking(move || crate::agent::policy::check(&policy, &dir))
+                .await
+            {
+                Ok(Ok(report)) => {
+                    info!(
+                        "[orchestrator::run_phase] definition of done passed={} for ticket={}",
+                        report.passed(),
+                        input.ticket_id
+                    );
+                    Some(report)
+                }
+                Ok(Err(e)) => {
+                    info!("[orchestrator::run_phase] policy check failed: {:#}", e);
+                    None
+                }
+                Err(e) => {
+                    info!("[orchestrator::run_phase] policy check panicked: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
 
     let completed = PhaseCompletedPayload {
         ticket_id: input.ticket_id.clone(),
         phase: input.phase.clone(),
         artifact_content: None,
-        result_summary: None,
-        blocked: false,
+        result_summary: (outcome.pr_held || outcome.verify_held)
+            .then(|| "The phase stopped without the PR it asked to open.".to_string()),
+        blocked: outcome.pr_held || outcome.verify_held,
+        policy_report,
     };
 
     let _ = app.emit("orchestrator-phase-completed", &completed);
@@ -574,16 +1074,259 @@ pub async fn run_phase(
         input.phase, input.ticket_id
     );
 
-    Ok((completed, session_id))
+    Ok((completed, outcome))
 }
 
-/// Main entry point: run the requested phase, then auto-chain review phases
-/// if the initial phase was Build.
-///
-/// After a successful Build:
+/// The GitHub token for a phase: a fresh App installation token when the
+/// run authenticates as an App, else the one it was started with.
+async fn github_token(input: &OrchestratorInput, app: &AppHandle) -> Result<String> {
+    let Some(creds) = input.github_app.clone() else {
+        return Ok(input.gh_token.clone());
+    };
+    let cache = app.state::<AppState>().github_app_tokens.clone();
+    let installation = creds.installation_id.clone();
+    let token = tokio::task::spawn_blocking(move || github::app_auth::installation_token(&cache, &creds))
+        .await
+        .context("GitHub App token task panicked")??;
+    audit_log::record(
+        AuditEntry::new(Actor::Automation, AuditKind::TokenAccess, "issue_github_app_token")
+            .agent(&input.agent_id, &input.ticket_id)
+            .detail(format!("installation {}", installation)),
+    );
+    Ok(token)
+}
+
+/// Check the ticket's acceptance criteria in a build that went to open its
+/// PR, and emit `acceptance-verified` so the report is shown before the PR.
+/// Failures are logged and return None; they never fail the run.
+async fn verify_acceptance(
+    input: &OrchestratorInput,
+    config: &crate::agent::verify::VerifyConfig,
+    worktree: &Path,
+    app: &AppHandle,
+) -> Option<crate::agent::verify::AcceptanceReport> {
+    let repo_root = Path::new(&input.repo_root);
+    let report = match crate::project::tickets::locate(repo_root, &input.ticket_id) {
+        Ok((project_root, ticket)) => {
+            crate::agent::verify::run(config, &project_root, &ticket, worktree).await
+        }
+        Err(e) => Err(e),
+    };
+    match report {
+        Ok(report) => {
+            info!(
+                "[orchestrator::verify_acceptance] ticket={} all_passed={}",
+                input.ticket_id,
+                report.all_passed()
+            );
+            let _ = app.emit("acceptance-verified", &report);
+            Some(report)
+        }
+        Err(e) => {
+            info!(
+                "[orchestrator::verify_acceptance] skipped for ticket={}: {:#}",
+                input.ticket_id, e
+            );
+            None
+        }
+    }
+}
+
+/// Score the finished build's diff and emit `diff-risk-scored`, so the risk
+/// is on screen before the PR goes up. Failures are logged.
+async fn score_risk(input: &OrchestratorInput, worktree: &Path, app: &AppHandle) {
+    let config = crate::project::settings::load_project(Path::new(&input.repo_root))
+        .unwrap_or_default()
+        .risk;
+    let dir = worktree.to_path_buf();
+    match tokio::task::spawn_blocking(move || crate::agent::risk::assess(&config, &dir)).await {
+        Ok(Ok(report)) => {
+            info!(
+                "[orchestrator::score_risk] ticket={} score={}",
+                input.ticket_id, report.score
+            );
+            let _ = app.emit(
+                "diff-risk-scored",
+                &crate::agent::risk::DiffRiskPayload {
+                    agent_id: input.agent_id.clone(),
+                    ticket_id: input.ticket_id.clone(),
+                    report,
+                },
+            );
+        }
+        Ok(Err(e)) => info!(
+            "[orchestrator::score_risk] skipped for ticket={}: {:#}",
+            input.ticket_id, e
+        ),
+        Err(e) => info!("[orchestrator::score_risk] panicked: {}", e),
+    }
+}
+
+/// Review a finished build in its worktree:
 ///   1. Get the worktree diff
 ///   2. Run Validate → Qa → Security in order
 ///   3. If any review phase reports blocked, emit `orchestrator-blocked` and stop
+async fn run_reviews(input: &OrchestratorInput, app: &AppHandle, mcp_port: u16) -> Result<()> {
+    let worktree_path = input
+        .worktree_path_override
+        .clone()
+        .unwrap_or_else(|| {
+            let repo_root = PathBuf::from(&input.repo_root);
+            git::worktree::Worktree::path_for(&repo_root, &input.ticket_id)
+                .to_string_lossy()
+                .to_string()
+        });
+    score_risk(input, Path::new(&worktree_path), app).await;
+
+    let review_phases = ["validate", "qa", "security"];
+
+    for review_phase in &review_phases {
+        if budget_pause(input, app) {
+            break;
+        }
+
+        // Get the current diff for the review agent to inspect
+        let diff = get_worktree_diff(&worktree_path).await.unwrap_or_else(|e| {
+            info!(
+                "[orchestrator::run_reviews] failed to get diff for {}: {}",
+                review_phase, e
+            );
+            String::new()
+        });
+
+        // Build a review prompt that includes the plan artifact and the diff
+        let review_prompt = {
+            let plan_section = input
+                .plan_artifact
+                .as_deref()
+                .map(|plan| format!("## Approved Plan\n\n{}\n\n", plan))
+                .unwrap_or_default();
+
+            format!(
+                "{}## Code Changes (git diff)\n\n```diff\n{}\n```",
+                plan_section, diff
+            )
+        };
+
+        let review_input = OrchestratorInput {
+            agent_id: input.agent_id.clone(),
+            ticket_id: input.ticket_id.clone(),
+            ticket_slug: input.ticket_slug.clone(),
+            prompt: review_prompt,
+            system_prompt: input.system_prompt.clone(),
+            repo_root: input.repo_root.clone(),
+            gh_token: input.gh_token.clone(),
+            github_app: input.github_app.clone(),
+            phase: review_phase.to_string(),
+            worktree_path_override: Some(worktree_path.clone()),
+            plan_artifact: input.plan_artifact.clone(),
+            group_id: None,
+            gitlab_token: input.gitlab_token.clone(),
+            fork: input.fork.clone(),
+            resume_session_id: None,
+            max_turns: None,
+            pair: None,
+            tournament: None,
+            test_first: None,
+            pr_gate: input.pr_gate.clone(),
+        };
+
+        let (review_completed, _) = run_phase(&review_input, app, mcp_port).await?;
+
+        if review_completed.blocked {
+            let _ = app.emit(
+                "orchestrator-blocked",
+                &OrchestratorBlockedPayload {
+                    ticket_id: input.ticket_id.clone(),
+                    reason: format!("{} phase reported issues", review_phase),
+                    details: review_completed.artifact_content,
+                },
+            );
+            info!(
+                "[orchestrator::run_reviews] blocked at {} for ticket={}",
+                review_phase, input.ticket_id
+            );
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The branch checked out in `dir`, unless HEAD is detached.
+async fn current_branch(dir: &Path) -> Option<String> {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.args(["rev-parse", "--abbrev-ref", "HEAD"]).current_dir(dir);
+    let output = exec::output(&mut cmd, exec::LOCAL).await.ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(branch).filter(|b| output.status.success() && !b.is_empty() && b != "HEAD")
+}
+
+/// Start watching the PR the agent opened from its branch, if it opened one
+/// and it isn't watched already. Reviews, CI triage, labels and latency
+/// tracking all hang off that watch.
+async fn watch_opened_pr(input: &OrchestratorInput, app: &AppHandle, working_dir: &Path) {
+    let dir = working_dir.to_path_buf();
+    let Ok(Some(repo)) = tokio::task::spawn_blocking(move || github::token::repo_slug_for(&dir)).await else {
+        return;
+    };
+    let Some(branch) = current_branch(working_dir).await else {
+        return;
+    };
+    // The input token may have expired by now for GitHub App runs
+    let gh_token = match github_token(input, app).await {
+        Ok(token) => Some(token).filter(|t| !t.is_empty()),
+        Err(e) => {
+            warn!("[orchestrator::watch_opened_pr] no GitHub token for {}: {:#}", branch, e);
+            return;
+        }
+    };
+    let client = github::client::GitHubClient::new(gh_token.as_deref());
+    let pr_number = match client.open_pr_for_branch(&repo, &branch).await {
+        Ok(Some(number)) => number,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("[orchestrator::watch_opened_pr] no PR lookup for {}: {:#}", branch, e);
+            return;
+        }
+    };
+    let agents = &app.state::<AppState>().agents;
+    let watched = crate::agent::state::get_agent(agents, &input.agent_id)
+        .await
+        .is_some_and(|agent| agent.pr_number == Some(pr_number));
+    if watched {
+        return;
+    }
+    info!(
+        "[orchestrator::watch_opened_pr] agent={} opened PR #{} on {}",
+        input.agent_id, pr_number, repo
+    );
+    audit_log::command(
+        Actor::Automation,
+        "start_pr_poll",
+        serde_json::json!({
+            "agent_id": input.agent_id,
+            "ticket_id": input.ticket_id,
+            "repo": repo,
+            "pr_number": pr_number,
+        }),
+        Ok(()),
+    );
+    crate::watch_pr(
+        app.clone(),
+        input.agent_id.clone(),
+        input.ticket_id.clone(),
+        repo,
+        pr_number,
+        gh_token,
+    )
+    .await;
+}
+
+/// Main entry point: run the requested phase, then auto-chain review phases
+/// if the initial phase was Build.
+///
+/// After a successful Build, or for the `review` phase, run the review chain
+/// (see [`run_reviews`]).
 pub async fn run_ticket(input: OrchestratorInput, app: AppHandle, mcp_port: u16) -> Result<()> {
     info!(
         "[orchestrator::run_ticket] starting ticket={} phase={}",
@@ -595,11 +1338,24 @@ pub async fn run_ticket(input: OrchestratorInput, app: AppHandle, mcp_port: u16)
         .plan_artifact
         .as_ref()
         .and_then(|json| serde_json::from_str::<PlanArtifact>(json).ok())
-        .filter(|plan| plan.parallel_safe && plan.task_groups.len() > 1);
+        .filter(|plan| plan.parallel_safe && plan.task_groups.len() > 1)
+        // A resumed build continues its one session
+        .filter(|_| input.resume_session_id.is_none());
 
     if input.phase == "build" {
-        // Either fan-out across parallel task groups or run a single build phase
-        let blocked = if let Some(plan) = use_fan_out {
+        if let Some(ref tournament) = input.tournament {
+            // The user picks the winner; review phases run on it afterwards.
+            crate::agent::tournament::run(&input, tournament, &app, mcp_port).await?;
+            return Ok(());
+        }
+
+        // Pair up, write tests first, fan out across parallel task groups, or
+        // run a single build phase
+        let blocked = if let Some(ref pair) = input.pair {
+            run_pair_build(&input, pair, &app, mcp_port).await?
+        } else if let Some(ref test_first) = input.test_first {
+            crate::agent::test_first::run(&input, test_first, &app, mcp_port).await?
+        } else if let Some(plan) = use_fan_out {
             run_fan_out_build(&input, plan, &app, mcp_port).await?
         } else {
             let (result, _) = run_phase(&input, &app, mcp_port).await?;
@@ -607,76 +1363,11 @@ pub async fn run_ticket(input: OrchestratorInput, app: AppHandle, mcp_port: u16)
         };
 
         if !blocked {
-            // Auto-chain through review phases after a successful build
-            let worktree_path = input
-                .worktree_path_override
-                .clone()
-                .unwrap_or_else(|| {
-                    let repo_root = PathBuf::from(&input.repo_root);
-                    git::worktree::Worktree::path_for(&repo_root, &input.ticket_id)
-                        .to_string_lossy()
-                        .to_string()
-                });
-
-            let review_phases = ["validate", "qa", "security"];
-
-            for review_phase in &review_phases {
-                // Get the current diff for the review agent to inspect
-                let diff = get_worktree_diff(&worktree_path).await.unwrap_or_else(|e| {
-                    info!(
-                        "[orchestrator::run_ticket] failed to get diff for {}: {}",
-                        review_phase, e
-                    );
-                    String::new()
-                });
-
-                // Build a review prompt that includes the plan artifact and the diff
-                let review_prompt = {
-                    let plan_section = input
-                        .plan_artifact
-                        .as_deref()
-                        .map(|plan| format!("## Approved Plan\n\n{}\n\n", plan))
-                        .unwrap_or_default();
-
-                    format!(
-                        "{}## Code Changes (git diff)\n\n```diff\n{}\n```",
-                        plan_section, diff
-                    )
-                };
-
-                let review_input = OrchestratorInput {
-                    agent_id: input.agent_id.clone(),
-                    ticket_id: input.ticket_id.clone(),
-                    ticket_slug: input.ticket_slug.clone(),
-                    prompt: review_prompt,
-                    system_prompt: input.system_prompt.clone(),
-                    repo_root: input.repo_root.clone(),
-                    gh_token: input.gh_token.clone(),
-                    phase: review_phase.to_string(),
-                    worktree_path_override: Some(worktree_path.clone()),
-                    plan_artifact: input.plan_artifact.clone(),
-                    group_id: None,
-                };
-
-                let (review_completed, _) = run_phase(&review_input, &app, mcp_port).await?;
-
-                if review_completed.blocked {
-                    let _ = app.emit(
-                        "orchestrator-blocked",
-                        &OrchestratorBlockedPayload {
-                            ticket_id: input.ticket_id.clone(),
-                            reason: format!("{} phase reported issues", review_phase),
-                            details: review_completed.artifact_content,
-                        },
-                    );
-                    info!(
-                        "[orchestrator::run_ticket] blocked at {} for ticket={}",
-                        review_phase, input.ticket_id
-                    );
-                    break;
-                }
-            }
+            run_reviews(&input, &app, mcp_port).await?;
         }
+    } else if input.phase == "review" {
+        // A finished build picked up elsewhere, e.g. a tournament winner
+        run_reviews(&input, &app, mcp_port).await?;
     } else {
         // Non-build phase: run as-is
         run_phase(&input, &app, mcp_port).await?;
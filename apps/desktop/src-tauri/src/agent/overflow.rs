@@ -0,0 +1,161 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::events::AgentEvent;
+use super::transcript::sanitize;
+use crate::project::store;
+use crate::text::truncate_chars;
+
+/// Tool results larger than this, serialized, are moved out of the event.
+pub const MAX_INLINE_BYTES: usize = 32 * 1024;
+/// Characters of text kept in the event as a preview.
+const PREVIEW_CHARS: usize = 2_000;
+/// The store is pruned back under this size, oldest files first.
+pub const MAX_STORE_BYTES: u64 = 512 * 1024 * 1024;
+
+fn dir() -> PathBuf {
+    store::app_dir().join("overflow")
+}
+
+/// `$HOME/.poietai/overflow/<node_id>.json` — the full content of a
+/// truncated tool result.
+pub fn path_for(node_id: &str) -> PathBuf {
+    dir().join(format!("{}.json", sanitize(node_id)))
+}
+
+/// Readable text of a tool result. Non-text blocks (images, documents) are
+/// named rather than inlined, so base64 payloads never reach the preview.
+fn text_of(content: &Value) -> String {
+    match content {
+        Value::String(s) => s.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .map(|block| match block["text"].as_str() {
+                Some(text) => text.to_string(),
+                None => format!("[{}]", block["type"].as_str().unwrap_or("binary content")),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    }
+}
+
+/// The start of the content's text, cut on a character boundary.
+pub fn preview(content: &Value, full_size: usize) -> String {
+    let text = text_of(content);
+    let mut head = truncate_chars(&text, PREVIEW_CHARS).to_string();
+    if head.len() < text.len() {
+        head.push_str(&format!("\n… truncated ({} KiB in full)", full_size / 1024));
+    }
+    head
+}
+
+/// Move an oversized tool result's content to the overflow store, leaving a
+/// preview in the event. Other events are untouched.
+pub fn spill(event: &mut AgentEvent, node_id: &str) -> Result<()> {
+    let AgentEvent::ToolResult {
+        content, full_size, ..
+    } = event
+    else {
+        return Ok(());
+    };
+    let raw = serde_json::to_string(content).context("failed to serialize tool result")?;
+    if raw.len() <= MAX_INLINE_BYTES {
+        return Ok(());
+    }
+    store::write_atomic(&path_for(node_id), &raw)?;
+    *content = Value::String(preview(content, raw.len()));
+    *full_size = Some(raw.len() as u64);
+    Ok(())
+}
+
+/// The original content of a truncated tool result.
+pub fn load(node_id: &str) -> Result<Value> {
+    let path = path_for(node_id);
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("no stored content for node {} (it may have been pruned)", node_id))?;
+    serde_json::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Delete the oldest stored results until the store fits in `max_bytes`.
+/// Returns how many were deleted. Blocking.
+pub fn prune(max_bytes: u64) -> Result<usize> {
+    prune_dir(&dir(), max_bytes)
+}
+
+fn prune_dir(dir: &Path, max_bytes: u64) -> Result<usize> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some((meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), meta.len(), entry.path()))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort();
+    let mut deleted = 0;
+    for (_, len, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        std::fs::remove_file(&path).with_context(|| format!("failed to delete {}", path.display()))?;
+        total -= len;
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn small_results_stay_inline() {
+        let mut event = AgentEvent::ToolResult {
+            tool_use_id: "tu_1".to_string(),
+            content: json!("short"),
+            is_error: None,
+            full_size: None,
+        };
+        spill(&mut event, "never-written").unwrap();
+        assert!(matches!(event, AgentEvent::ToolResult { full_size: None, .. }));
+    }
+
+    #[test]
+    fn pruning_drops_the_oldest_files_first() {
+        let dir = std::env::temp_dir().join(format!("poietai-overflow-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["old", "mid", "new"] {
+            std::fs::write(dir.join(format!("{}.json", name)), vec![b'x'; 100]).unwrap();
+            // Distinct modification times
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(prune_dir(&dir, 250).unwrap(), 1);
+        assert!(!dir.join("old.json").exists());
+        assert!(dir.join("new.json").exists());
+        assert_eq!(prune_dir(&dir, 250).unwrap(), 0);
+        assert_eq!(prune_dir(&dir.join("missing"), 0).unwrap(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn previews_cut_on_char_boundaries_and_name_binary_blocks() {
+        let content = json!([
+            {"type": "text", "text": "é".repeat(PREVIEW_CHARS + 10)},
+            {"type": "image", "source": {"type": "base64", "data": "AAAA"}},
+        ]);
+        let text = preview(&content, 40 * 1024);
+        assert!(text.starts_with(&"é".repeat(PREVIEW_CHARS)));
+        assert!(text.ends_with("truncated (40 KiB in full)"));
+        assert!(!text.contains("AAAA"));
+        assert_eq!(text_of(&json!([{"type": "image"}])), "[image]");
+    }
+}
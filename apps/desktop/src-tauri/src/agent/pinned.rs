@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use crate::project::store;
+
+/// A standing correction for one agent, e.g. "never touch the legacy module".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct PinnedNote {
+    pub id: String,
+    pub text: String,
+}
+
+type PinnedNotes = HashMap<String, Vec<PinnedNote>>;
+
+/// Serializes read-modify-writes of `pinned-notes.json`.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// `$HOME/.poietai/pinned-notes.json`, keyed by agent id.
+pub fn path() -> PathBuf {
+    store::app_dir().join("pinned-notes.json")
+}
+
+pub fn load(agent_id: &str) -> Result<Vec<PinnedNote>> {
+    let all: PinnedNotes = store::read_json(&path())?;
+    Ok(all.get(agent_id).cloned().unwrap_or_default())
+}
+
+pub fn add(agent_id: &str, text: String) -> Result<PinnedNote> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut all: PinnedNotes = store::read_json(&path())?;
+    let note = PinnedNote {
+        id: uuid::Uuid::new_v4().to_string(),
+        text,
+    };
+    all.entry(agent_id.to_string()).or_default().push(note.clone());
+    store::write_json(&path(), &all)?;
+    Ok(note)
+}
+
+pub fn remove(agent_id: &str, id: &str) -> Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut all: PinnedNotes = store::read_json(&path())?;
+    let notes = all
+        .get_mut(agent_id)
+        .filter(|notes| notes.iter().any(|n| n.id == id))
+        .with_context(|| format!("pinned note '{}' not found", id))?;
+    notes.retain(|n| n.id != id);
+    if notes.is_empty() {
+        all.remove(agent_id);
+    }
+    store::write_json(&path(), &all)
+}
+
+/// `prompt` with the notes listed ahead of it, so they apply to every turn.
+pub fn prepend(notes: &[PinnedNote], prompt: &str) -> String {
+    if notes.is_empty() {
+        return prompt.to_string();
+    }
+    let list: Vec<String> = notes.iter().map(|n| format!("- {}", n.text)).collect();
+    format!(
+        "Pinned notes from the user (always follow these):\n{}\n\n{}",
+        list.join("\n"),
+        prompt
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepend_lists_notes_before_prompt() {
+        let notes = vec![PinnedNote {
+            id: "1".to_string(),
+            text: "never touch the legacy module".to_string(),
+        }];
+        assert_eq!(
+            prepend(&notes, "continue"),
+            "Pinned notes from the user (always follow these):\n\
+             - never touch the legacy module\n\ncontinue"
+        );
+        assert_eq!(prepend(&[], "continue"), "continue");
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+use crate::git;
+use crate::platform::exec::{self, ExecError};
+
+/// A project's definition of done for build runs. Each rule is both stated
+/// in the agent's prompt and checked against the worktree afterwards.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct DodPolicy {
+    /// The change must add or modify tests.
+    pub tests_added: bool,
+    /// The change must touch documentation.
+    pub docs_updated: bool,
+    /// The change must not add TODO/FIXME comments.
+    pub no_new_todos: bool,
+    /// Must exit 0 in the worktree, e.g. "cargo clippy -- -D warnings".
+    pub lint_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct PolicyCheck {
+    /// "tests_added", "docs_updated", "no_new_todos" or "lint_clean".
+    pub rule: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// How a build run measured up to the project's definition of done.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct PolicyReport {
+    pub checks: Vec<PolicyCheck>,
+}
+
+impl PolicyReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+}
+
+/// Instructions appended to the build phase's system prompt.
+pub fn prompt_section(policy: &DodPolicy) -> String {
+    let mut rules = Vec::new();
+    if policy.tests_added {
+        rules.push("Add or update tests that cover the change.".to_string());
+    }
+    if policy.docs_updated {
+        rules.push("Update the documentation (README, docs/) to match the change.".to_string());
+    }
+    if policy.no_new_todos {
+        rules.push("Do not leave TODO or FIXME comments — finish the work or ask.".to_string());
+    }
+    if let Some(ref lint) = policy.lint_command {
+        rules.push(format!("`{}` must pass before you finish.", lint));
+    }
+    if rules.is_empty() {
+        return String::new();
+    }
+    format!(
+        "## Definition of Done\n\nThis project checks every change against these rules \
+         after you finish:\n{}",
+        rules.iter().map(|r| format!("- {}", r)).collect::<Vec<_>>().join("\n")
+    )
+}
+
+/// Paths touched by a unified diff, from its `+++ b/<path>` headers.
+fn diff_paths(diff: &str) -> impl Iterator<Item = &str> {
+    diff.lines().filter_map(|l| l.strip_prefix("+++ b/"))
+}
+
+fn added_lines(diff: &str) -> impl Iterator<Item = &str> {
+    diff.lines()
+        .filter(|l| l.starts_with('+') && !l.starts_with("+++"))
+        .map(|l| &l[1..])
+}
+
+pub(crate) fn is_test_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    lower.split('/').any(|dir| matches!(dir, "test" | "tests" | "__tests__" | "spec"))
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.starts_with("test_")
+        || name.contains("_test.")
+}
+
+fn is_doc_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.starts_with("docs/")
+        || lower.contains("/docs/")
+        || [".md", ".mdx", ".rst", ".adoc"].iter().any(|ext| lower.ends_with(ext))
+}
+
+/// Check the diff-based rules. `untracked` are new files not yet in the diff.
+pub fn check_diff(policy: &DodPolicy, diff: &str, untracked: &[String]) -> Vec<PolicyCheck> {
+    let paths: Vec<&str> = diff_paths(diff)
+        .chain(untracked.iter().map(String::as_str))
+        .collect();
+    let mut checks = Vec::new();
+    if policy.tests_added {
+        let tests: Vec<&str> = paths.iter().copied().filter(|p| is_test_path(p)).collect();
+        // Rust keeps unit tests inline, so a new #[test] counts too
+        let inline = added_lines(diff).any(|l| l.trim_start().starts_with("#[test]"));
+        checks.push(PolicyCheck {
+            rule: "tests_added".to_string(),
+            passed: !tests.is_empty() || inline,
+            detail: if !tests.is_empty() {
+                tests.join(", ")
+            } else if inline {
+                "new #[test] functions".to_string()
+            } else {
+                "no test files changed".to_string()
+            },
+        });
+    }
+    if policy.docs_updated {
+        let docs: Vec<&str> = paths.iter().copied().filter(|p| is_doc_path(p)).collect();
+        checks.push(PolicyCheck {
+            rule: "docs_updated".to_string(),
+            passed: !docs.is_empty(),
+            detail: if docs.is_empty() {
+                "no documentation changed".to_string()
+            } else {
+                docs.join(", ")
+            },
+        });
+    }
+    if policy.no_new_todos {
+        let todos: Vec<&str> = added_lines(diff)
+            .filter(|l| l.contains("TODO") || l.contains("FIXME"))
+            .map(str::trim)
+            .collect();
+        checks.push(PolicyCheck {
+            rule: "no_new_todos".to_string(),
+            passed: todos.is_empty(),
+            detail: if todos.is_empty() {
+                "none added".to_string()
+            } else {
+                todos.join("\n")
+            },
+        });
+    }
+    checks
+}
+
+/// Run every check against the worktree. A lint command that outlasts
+/// [`exec::LOCAL`] fails its check. Blocking — call from spawn_blocking.
+pub fn check(policy: &DodPolicy, worktree: &Path) -> Result<PolicyReport> {
+    let (diff, untracked) = git::diff::worktree_changes(worktree)?;
+    let mut checks = check_diff(policy, &diff, &untracked);
+    if let Some(ref lint) = policy.lint_command {
+        let mut cmd = git::bootstrap::shell_command(lint);
+        cmd.current_dir(worktree);
+        let (passed, detail) = match exec::output_blocking(&mut cmd, exec::LOCAL) {
+            Ok(output) => {
+                let text = String::from_utf8_lossy(&output.stdout).to_string();
+                let tail: Vec<&str> = text.lines().rev().take(20).collect();
+                (
+                    output.status.success(),
+                    tail.into_iter().rev().collect::<Vec<_>>().join("\n"),
+                )
+            }
+            Err(ExecError::TimedOut { limit, .. }) => {
+                (false, format!("`{}` timed out after {:?}", lint, limit))
+            }
+            Err(ExecError::Spawn { error, .. }) => {
+                (false, format!("failed to run `{}`: {}", lint, error))
+            }
+        };
+        checks.push(PolicyCheck {
+            rule: "lint_clean".to_string(),
+            passed,
+            detail,
+        });
+    }
+    Ok(PolicyReport { checks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/retry.rs b/src/retry.rs
+--- a/src/retry.rs
++++ b/src/retry.rs
+@@ -1,2 +1,3 @@
+ fn retry() {
++    // TODO: jitter
+ }
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1,2 @@
+ # App
++Retries now back off.
+";
+
+    fn all_rules() -> DodPolicy {
+        DodPolicy {
+            tests_added: true,
+            docs_updated: true,
+            no_new_todos: true,
+            lint_command: None,
+        }
+    }
+
+    #[test]
+    fn flags_missing_tests_and_new_todos() {
+        let checks = check_diff(&all_rules(), DIFF, &[]);
+        let passed: Vec<(&str, bool)> = checks.iter().map(|c| (c.rule.as_str(), c.passed)).collect();
+        assert_eq!(
+            passed,
+            vec![("tests_added", false), ("docs_updated", true), ("no_new_todos", false)]
+        );
+        assert_eq!(checks[2].detail, "// TODO: jitter");
+    }
+
+    #[test]
+    fn untracked_test_files_count() {
+        let checks = check_diff(&all_rules(), DIFF, &["web/retry.test.ts".to_string()]);
+        assert!(checks[0].passed);
+        assert!(is_test_path("crates/core/tests/retry.rs"));
+        assert!(!is_test_path("src/contest.rs"));
+    }
+
+    #[test]
+    fn prompt_lists_enabled_rules_only() {
+        let section = prompt_section(&DodPolicy {
+            no_new_todos: true,
+            ..Default::default()
+        });
+        assert!(section.contains("TODO or FIXME"));
+        assert!(!section.contains("tests"));
+        assert_eq!(prompt_section(&DodPolicy::default()), "");
+    }
+}
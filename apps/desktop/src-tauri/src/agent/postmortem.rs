@@ -0,0 +1,383 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::events::AgentEvent;
+use super::session;
+use super::transcript::{self, TranscriptEntry};
+use crate::github::{ci::gh, latency};
+use crate::project::store;
+use crate::text::truncate_chars;
+
+const MAX_TURNS: u32 = 8;
+const TIMEOUT: Duration = Duration::from_secs(300);
+/// Transcript text given to the analysis, from the end of the run backwards.
+const DIGEST_CHARS: usize = 24_000;
+
+const SYSTEM_PROMPT: &str = "You are writing a blameless post-mortem of an agent's failed attempt \
+    at a ticket, so the team can change how the next attempt is set up. You get a digest of the \
+    agent's transcript and any PR review history. You may read the repository to check a theory, \
+    but do not change anything.\n\n\
+    Focus on causes the team controls: the prompt and ticket wording, the ticket's scope, the \
+    tools and environment the agent had. Name concrete moments from the transcript.\n\n\
+    End your reply with a single JSON object and nothing after it:\n\
+    {\"summary\": \"<what happened, 2-3 sentences>\", \
+    \"causes\": [\"<what went wrong>\", ...], \
+    \"changes\": [{\"area\": \"prompt|scope|tooling|other\", \"suggestion\": \"<what to change>\"}]}";
+
+/// Something to do differently on the next attempt.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct PostmortemChange {
+    /// "prompt", "scope", "tooling" or "other".
+    pub area: String,
+    pub suggestion: String,
+}
+
+/// What went wrong on a failed run or a PR closed unmerged, and what to
+/// change before trying again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct Postmortem {
+    pub ticket_id: String,
+    pub agent_id: String,
+    pub summary: String,
+    pub causes: Vec<String>,
+    pub changes: Vec<PostmortemChange>,
+    /// The PR the review history came from, if any.
+    pub pr_number: Option<u32>,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub generated_at: i64,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    summary: String,
+    #[serde(default)]
+    causes: Vec<String>,
+    #[serde(default)]
+    changes: Vec<PostmortemChange>,
+}
+
+/// A ticket's stored post-mortems. Files written before tickets kept more
+/// than one hold a single post-mortem.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Stored {
+    Many(Vec<Postmortem>),
+    One(Box<Postmortem>),
+}
+
+/// `<project>/.poietai/postmortems.json`, keyed by ticket id.
+pub fn path(project_root: &Path) -> PathBuf {
+    store::project_dir(project_root).join("postmortems.json")
+}
+
+/// Every ticket's post-mortems, oldest first.
+pub fn load_all(project_root: &Path) -> Result<HashMap<String, Vec<Postmortem>>> {
+    let stored: HashMap<String, Stored> = store::read_json(&path(project_root))?;
+    Ok(stored
+        .into_iter()
+        .map(|(ticket_id, stored)| match stored {
+            Stored::Many(all) => (ticket_id, all),
+            Stored::One(one) => (ticket_id, vec![*one]),
+        })
+        .collect())
+}
+
+/// Serializes read-modify-writes of the post-mortem store.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Add a post-mortem after the ticket's earlier ones.
+fn save(project_root: &Path, postmortem: &Postmortem) -> Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut all = load_all(project_root)?;
+    all.entry(postmortem.ticket_id.clone())
+        .or_default()
+        .push(postmortem.clone());
+    store::write_json(&path(project_root), &all)
+}
+
+/// The transcript as plain lines — narration, tool calls, errors, results —
+/// keeping the end of the run when it's longer than `max_chars`.
+fn digest(entries: &[TranscriptEntry], max_chars: usize) -> String {
+    let lines: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| match &entry.kind {
+            AgentEvent::Text { text } => Some(format!("agent: {}", text.trim())),
+            AgentEvent::ToolUse {
+                tool_name, summary, ..
+            } => Some(match summary {
+                Some(s) if !s.label.is_empty() => format!("tool {}: {}", tool_name, s.label),
+                _ => format!("tool {}", tool_name),
+            }),
+            AgentEvent::ToolResult {
+                content,
+                is_error: Some(true),
+                ..
+            } => {
+                let text = content
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| content.to_string());
+                Some(format!("tool error: {}", truncate_chars(&text, 500)))
+            }
+            AgentEvent::Result { result, .. } => Some(format!(
+                "run ended: {}",
+                result.as_deref().unwrap_or("(no final message)")
+            )),
+            _ => None,
+        })
+        .collect();
+    let mut kept = Vec::new();
+    let mut used = 0;
+    for line in lines.iter().rev() {
+        used += line.len() + 1;
+        if used > max_chars {
+            kept.push("[earlier events omitted]".to_string());
+            break;
+        }
+        kept.push(line.clone());
+    }
+    kept.reverse();
+    kept.join("\n")
+}
+
+#[derive(Deserialize)]
+struct Author {
+    #[serde(default)]
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct Feedback {
+    author: Option<Author>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct PrHistory {
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    reviews: Vec<Feedback>,
+    #[serde(default)]
+    comments: Vec<Feedback>,
+}
+
+/// Reviews and comments on a PR, one per line.
+fn review_history(json: &[u8]) -> Result<String> {
+    let pr: PrHistory = serde_json::from_slice(json).context("failed to parse PR")?;
+    let mut lines = vec![format!("PR state: {}", pr.state)];
+    for feedback in pr.reviews.iter().chain(&pr.comments) {
+        if feedback.body.trim().is_empty() && feedback.state.is_empty() {
+            continue;
+        }
+        let who = feedback
+            .author
+            .as_ref()
+            .map_or("someone", |a| a.login.as_str());
+        let state = if feedback.state.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", feedback.state.to_lowercase())
+        };
+        lines.push(format!("{}{}: {}", who, state, feedback.body.trim()));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// The agent's latest tracked PR on the ticket and its review history, if any.
+fn pr_for(agent_id: &str, ticket_id: &str) -> Option<(u32, String)> {
+    let timing = latest(
+        latency::load()
+            .ok()?
+            .into_iter()
+            .filter(|t| t.agent_id == agent_id && t.ticket_id == ticket_id),
+    )?;
+    let history = gh(&[
+        "pr",
+        "view",
+        &timing.pr_number.to_string(),
+        "--repo",
+        &timing.repo,
+        "--json",
+        "state,reviews,comments",
+    ])
+    .and_then(|json| review_history(&json));
+    match history {
+        Ok(history) => Some((timing.pr_number, history)),
+        Err(e) => {
+            warn!(
+                "[postmortem::pr_for] {}#{}: {:#}",
+                timing.repo, timing.pr_number, e
+            );
+            None
+        }
+    }
+}
+
+/// The most recently opened PR. One not yet refreshed from GitHub has no
+/// open time, and was tracked after the rest.
+fn latest(prs: impl Iterator<Item = latency::PrTiming>) -> Option<latency::PrTiming> {
+    prs.max_by(|a, b| {
+        (a.opened_at.is_none(), &a.opened_at, a.pr_number).cmp(&(
+            b.opened_at.is_none(),
+            &b.opened_at,
+            b.pr_number,
+        ))
+    })
+}
+
+pub fn parse_reply(
+    agent_id: &str,
+    ticket_id: &str,
+    pr_number: Option<u32>,
+    text: &str,
+) -> Result<Postmortem> {
+    let json = session::json_object(text).context("post-mortem reply had no JSON")?;
+    let reply: Reply = serde_json::from_str(json).context("post-mortem reply was malformed")?;
+    Ok(Postmortem {
+        ticket_id: ticket_id.to_string(),
+        agent_id: agent_id.to_string(),
+        summary: reply.summary,
+        causes: reply.causes,
+        changes: reply.changes,
+        pr_number,
+        generated_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// Analyse the agent's transcript on `ticket_id` and its PR's reviews, from
+/// the ticket's project, and add the post-mortem to the ticket's others.
+pub async fn run(project_root: &Path, agent_id: &str, ticket_id: &str) -> Result<Postmortem> {
+    info!("[postmortem::run] agent={} ticket={}", agent_id, ticket_id);
+    let entries = transcript::load(agent_id, ticket_id)?;
+    if entries.is_empty() {
+        anyhow::bail!(
+            "no transcript for agent '{}' on ticket '{}'",
+            agent_id,
+            ticket_id
+        );
+    }
+    let (agent, ticket) = (agent_id.to_string(), ticket_id.to_string());
+    let pr = tokio::task::spawn_blocking(move || pr_for(&agent, &ticket))
+        .await
+        .context("PR history task panicked")?;
+
+    let ticket = crate::project::tickets::find(project_root, ticket_id)?;
+    let mut prompt = format!(
+        "Write the post-mortem for this ticket:\n\n{}\n\n## Transcript digest\n\n{}",
+        ticket.as_prompt(),
+        digest(&entries, DIGEST_CHARS)
+    );
+    if let Some((number, ref history)) = pr {
+        prompt.push_str(&format!(
+            "\n\n## Review history of PR #{}\n\n{}",
+            number, history
+        ));
+    }
+    let text = session::ask_read_only(
+        agent_id,
+        ticket_id,
+        project_root,
+        SYSTEM_PROMPT,
+        &prompt,
+        MAX_TURNS,
+        TIMEOUT,
+    )
+    .await
+    .context("post-mortem run failed")?;
+    let postmortem = parse_reply(agent_id, ticket_id, pr.map(|(number, _)| number), &text)?;
+    save(project_root, &postmortem)?;
+    Ok(postmortem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: AgentEvent) -> TranscriptEntry {
+        TranscriptEntry {
+            at: 0,
+            node_id: "n".to_string(),
+            kind,
+            group_id: None,
+        }
+    }
+
+    #[test]
+    fn digest_keeps_the_end_of_the_run() {
+        let entries = vec![
+            entry(AgentEvent::Text {
+                text: "Starting on the retry logic".to_string(),
+            }),
+            entry(AgentEvent::Thinking {
+                thinking: "hidden".to_string(),
+            }),
+            entry(AgentEvent::ToolResult {
+                tool_use_id: "1".to_string(),
+                content: serde_json::json!("cargo: command not found"),
+                is_error: Some(true),
+                full_size: None,
+            }),
+            entry(AgentEvent::Result {
+                result: Some("Blocked: no cargo".to_string()),
+                session_id: None,
+                cost_usd: None,
+            }),
+        ];
+        assert_eq!(
+            digest(&entries, 10_000),
+            "agent: Starting on the retry logic\ntool error: cargo: command not found\nrun ended: Blocked: no cargo"
+        );
+        assert_eq!(
+            digest(&entries, 30),
+            "[earlier events omitted]\nrun ended: Blocked: no cargo"
+        );
+    }
+
+    #[test]
+    fn renders_review_history_and_parses_reply() {
+        let json = br#"{"state":"CLOSED","reviews":[{"author":{"login":"dana"},"state":"CHANGES_REQUESTED",
+            "body":"This rewrites the whole client"}],"comments":[{"author":{"login":"lee"},"body":"Closing, out of scope"}]}"#;
+        assert_eq!(
+            review_history(json).unwrap(),
+            "PR state: CLOSED\ndana (changes_requested): This rewrites the whole client\nlee: Closing, out of scope"
+        );
+
+        let text = "Done.\n{\"summary\": \"Scope crept.\", \"causes\": [\"Ticket said 'clean up client'\"], \
+                    \"changes\": [{\"area\": \"scope\", \"suggestion\": \"Split the ticket\"}]}";
+        let postmortem = parse_reply("a-1", "t-1", Some(7), text).unwrap();
+        assert_eq!(postmortem.changes[0].area, "scope");
+        assert_eq!(postmortem.pr_number, Some(7));
+        assert!(parse_reply("a-1", "t-1", None, "nothing").is_err());
+    }
+
+    #[test]
+    fn latest_pr_is_the_last_opened() {
+        let pr = |pr_number, opened_at: Option<&str>| latency::PrTiming {
+            pr_number,
+            opened_at: opened_at.map(String::from),
+            ..Default::default()
+        };
+        let prs = vec![
+            pr(4, Some("2026-03-02T10:00:00Z")),
+            pr(9, Some("2026-03-09T10:00:00Z")),
+            pr(2, Some("2026-03-01T10:00:00Z")),
+        ];
+        assert_eq!(latest(prs.clone().into_iter()).unwrap().pr_number, 9);
+        let mut prs = prs;
+        prs.push(pr(12, None));
+        assert_eq!(latest(prs.into_iter()).unwrap().pr_number, 12);
+    }
+}
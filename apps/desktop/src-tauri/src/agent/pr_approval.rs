@@ -0,0 +1,273 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use ts_rs::TS;
+
+use crate::git::diff;
+use crate::platform::exec;
+use crate::project::store;
+
+/// Appended to the system prompt of runs that need approval to open a PR.
+pub const PROMPT_SECTION: &str = "## Opening the PR\n\n\
+    A human approves every PR on this project before it's opened. When the work is committed \
+    and pushed, run `gh pr create` as usual: it will be refused while the request goes to the \
+    user. Then stop and end your turn with a short summary of the change. You will be resumed \
+    once they decide.";
+
+/// What the PreToolUse hook tells the agent when it holds back `gh pr create`.
+/// Single-quoted in the hook, so it must not contain `'`.
+const HOLD_MESSAGE: &str = "Opening a PR on this project needs approval from a human, and the \
+    request has been sent. Stop here and end your turn with a short summary of the change.";
+
+/// What an approved run is told when it's resumed.
+pub const APPROVED_PROMPT: &str = "The user approved your PR. Open it now with `gh pr create`.";
+
+/// What a run is told when the user turns its PR down with feedback.
+pub fn rejected_prompt(feedback: &str) -> String {
+    format!(
+        "The user didn't approve opening the PR yet:\n\n{}\n\nAddress this, then run `gh pr create` again.",
+        feedback.trim()
+    )
+}
+
+/// The user's answer to a PR request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    Approved,
+    /// Turned down, with what to change before asking again.
+    Rejected(Option<String>),
+}
+
+type Waiting = HashMap<(String, String), watch::Sender<Option<Decision>>>;
+
+/// Orchestrator phases waiting on their PR request, keyed by agent and
+/// ticket. A decision nobody is waiting on resumes the session instead.
+pub type DecisionStore = Arc<Mutex<Waiting>>;
+
+pub fn new_store() -> DecisionStore {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// A phase's claim on the decisions about its PR. Dropping it — the phase
+/// ended — hands later decisions back to [`decide`]'s caller.
+pub struct Waiter {
+    store: DecisionStore,
+    key: (String, String),
+    decisions: watch::Receiver<Option<Decision>>,
+}
+
+impl Waiter {
+    /// The next decision, once the user makes one.
+    pub async fn next(&mut self) -> Option<Decision> {
+        self.decisions.changed().await.ok()?;
+        self.decisions.borrow_and_update().clone()
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        self.store.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+    }
+}
+
+/// Take the decisions about `agent_id`'s PR on `ticket_id` for the rest of a
+/// phase, so they continue it rather than start a separate run.
+pub fn wait(store: &DecisionStore, agent_id: &str, ticket_id: &str) -> Waiter {
+    let key = (agent_id.to_string(), ticket_id.to_string());
+    let (sender, decisions) = watch::channel(None);
+    store.lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone(), sender);
+    Waiter {
+        store: store.clone(),
+        key,
+        decisions,
+    }
+}
+
+/// Hand `decision` to the phase waiting on it, or back when none is.
+pub fn decide(
+    store: &DecisionStore,
+    agent_id: &str,
+    ticket_id: &str,
+    decision: Decision,
+) -> Result<(), Decision> {
+    let waiting = store.lock().unwrap_or_else(|e| e.into_inner());
+    match waiting.get(&(agent_id.to_string(), ticket_id.to_string())) {
+        Some(sender) => {
+            sender.send_replace(Some(decision));
+            Ok(())
+        }
+        None => Err(decision),
+    }
+}
+
+/// An agent's request to open a PR, held until the user decides.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct PrRequest {
+    pub id: String,
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub worktree_path: String,
+    /// The session approval resumes.
+    pub session_id: Option<String>,
+    /// From the agent's `gh pr create --title`, when it gave one.
+    pub title: Option<String>,
+    /// `git diff --stat` against the worktree's base.
+    pub diff_summary: String,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub requested_at: i64,
+    /// Approved requests let the agent open its PR for the rest of the ticket.
+    pub approved: bool,
+}
+
+// Serializes read-modify-write cycles on the requests file.
+static REQUESTS: Mutex<()> = Mutex::new(());
+
+/// `$HOME/.poietai/pr-requests.json`.
+fn path() -> PathBuf {
+    store::app_dir().join("pr-requests.json")
+}
+
+fn edit<R>(f: impl FnOnce(&mut Vec<PrRequest>) -> R) -> Result<R> {
+    let _guard = REQUESTS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut requests: Vec<PrRequest> = store::read_json(&path())?;
+    let result = f(&mut requests);
+    store::write_json(&path(), &requests)?;
+    Ok(result)
+}
+
+fn load() -> Vec<PrRequest> {
+    let _guard = REQUESTS.lock().unwrap_or_else(|e| e.into_inner());
+    store::read_json(&path()).unwrap_or_default()
+}
+
+/// Whether a Bash command opens a PR.
+pub fn opens_pr(command: &str) -> bool {
+    command.contains("gh pr create")
+}
+
+/// The PreToolUse hook command that refuses `gh pr create` with exit 2,
+/// handing [`HOLD_MESSAGE`] back to the agent.
+pub fn hold_hook() -> String {
+    format!(
+        "case \"$(cat)\" in *'gh pr create'*) echo '{}' >&2; exit 2;; esac",
+        HOLD_MESSAGE
+    )
+}
+
+/// The `--title`/`-t` value of a `gh pr create` command, if it's quoted or a
+/// single word.
+pub fn requested_title(command: &str) -> Option<String> {
+    let rest = [" --title ", " --title=", " -t "]
+        .iter()
+        .find_map(|flag| command.split_once(flag).map(|(_, rest)| rest.trim_start()))?;
+    let title = match rest.chars().next()? {
+        quote @ ('"' | '\'') => rest[1..].split(quote).next()?,
+        _ => rest.split_whitespace().next()?,
+    };
+    Some(title.to_string()).filter(|t| !t.is_empty())
+}
+
+/// `git diff --stat` of the worktree against its base. Blocking.
+pub fn diff_summary(worktree: &Path) -> Result<String> {
+    let base = diff::base_commit(worktree)?;
+    let mut cmd = Command::new("git");
+    cmd.args(["diff", "--stat", "--no-color", &base])
+        .current_dir(worktree);
+    let output =
+        exec::output_blocking(&mut cmd, exec::LOCAL).context("failed to run git diff --stat")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git diff --stat failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Record a request, replacing any earlier pending one on the same ticket.
+pub fn request(pr: PrRequest) -> Result<()> {
+    edit(|requests| {
+        requests.retain(|r| r.approved || !(r.agent_id == pr.agent_id && r.ticket_id == pr.ticket_id));
+        requests.push(pr);
+    })
+}
+
+/// Requests awaiting a decision, oldest first.
+pub fn pending() -> Vec<PrRequest> {
+    load().into_iter().filter(|r| !r.approved).collect()
+}
+
+/// Whether the user let `agent_id` open its PR on `ticket_id`.
+pub fn is_approved(agent_id: &str, ticket_id: &str) -> bool {
+    load()
+        .iter()
+        .any(|r| r.approved && r.agent_id == agent_id && r.ticket_id == ticket_id)
+}
+
+/// Approve a pending request, returning it.
+pub fn approve(id: &str) -> Result<Option<PrRequest>> {
+    edit(|requests| {
+        let pr = requests.iter_mut().find(|r| r.id == id && !r.approved)?;
+        pr.approved = true;
+        Some(pr.clone())
+    })
+}
+
+/// Drop a pending request, returning it.
+pub fn reject(id: &str) -> Result<Option<PrRequest>> {
+    edit(|requests| {
+        let index = requests.iter().position(|r| r.id == id && !r.approved)?;
+        Some(requests.remove(index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_requested_title() {
+        assert_eq!(
+            requested_title("gh pr create --title \"Fix retry backoff\" --body-file pr.md").as_deref(),
+            Some("Fix retry backoff")
+        );
+        assert_eq!(
+            requested_title("gh pr create -t 'Add tour' --fill").as_deref(),
+            Some("Add tour")
+        );
+        assert_eq!(requested_title("gh pr create --title=WIP").as_deref(), Some("WIP"));
+        assert_eq!(requested_title("gh pr create --fill"), None);
+    }
+
+    #[test]
+    fn hold_hook_only_refuses_pr_creation() {
+        let hook = hold_hook();
+        assert!(hook.starts_with("case \"$(cat)\" in *'gh pr create'*)"));
+        assert!(hook.ends_with("exit 2;; esac"));
+        assert!(!HOLD_MESSAGE.contains('\''));
+        assert!(opens_pr("git push && gh pr create --fill"));
+        assert!(!opens_pr("gh pr view 12"));
+    }
+
+    #[tokio::test]
+    async fn decisions_go_to_the_waiting_phase() {
+        let store = new_store();
+        assert_eq!(decide(&store, "a1", "t1", Decision::Approved), Err(Decision::Approved));
+
+        let mut waiter = wait(&store, "a1", "t1");
+        let rejected = Decision::Rejected(Some("split the migration".to_string()));
+        assert_eq!(decide(&store, "a1", "t1", rejected.clone()), Ok(()));
+        assert_eq!(waiter.next().await, Some(rejected));
+        assert_eq!(decide(&store, "a1", "t1", Decision::Approved), Ok(()));
+        assert_eq!(waiter.next().await, Some(Decision::Approved));
+
+        drop(waiter);
+        assert_eq!(decide(&store, "a1", "t1", Decision::Approved), Err(Decision::Approved));
+    }
+}
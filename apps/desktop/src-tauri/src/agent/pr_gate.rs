@@ -0,0 +1,33 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::project::store;
+
+// Serializes read-modify-write cycles on the gates file.
+static GATES: Mutex<()> = Mutex::new(());
+
+/// `$HOME/.poietai/pr-gates.json`, keyed by ticket id.
+fn path() -> PathBuf {
+    store::app_dir().join("pr-gates.json")
+}
+
+/// Record the gate a ticket's PR must pass, so runs that resume the ticket
+/// keep it.
+pub fn remember(ticket_id: &str, gate: &str) -> Result<()> {
+    let _guard = GATES.lock().unwrap_or_else(|e| e.into_inner());
+    let mut gates: HashMap<String, String> = store::read_json(&path())?;
+    if gates.get(ticket_id).map(String::as_str) == Some(gate) {
+        return Ok(());
+    }
+    gates.insert(ticket_id.to_string(), gate.to_string());
+    store::write_json(&path(), &gates)
+}
+
+/// The gate recorded for `ticket_id`, if it has one.
+pub fn for_ticket(ticket_id: &str) -> Option<String> {
+    let _guard = GATES.lock().unwrap_or_else(|e| e.into_inner());
+    let mut gates: HashMap<String, String> = store::read_json(&path()).ok()?;
+    gates.remove(ticket_id)
+}
@@ -2,14 +2,40 @@ use anyhow::{Context, Result};
 use log::{error, info, warn};
 use serde::Serialize;
 use std::path::PathBuf;
-use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+#[cfg(target_os = "windows")]
 use tokio::process::Command;
 
-use super::events::{parse_events, AgentEvent};
+use super::backend::{self, ExecutionBackend};
+use super::checkpoints::{self, Checkpoint, CheckpointHit};
+use super::coalesce::Coalescer;
+use super::costs;
+use super::events::{init_session_id, parse_events, AgentEvent};
+use super::guard::{self, PathRule, PathViolationPayload, ProtectedEdit, Strictness, WritePath};
+use super::history;
+use super::keys;
+use super::limits::{self, ResourceLimits};
+use super::overflow;
+use super::pinned;
+use super::pr_approval::{self, PrRequest};
+use super::pr_gate;
+use super::recovery;
+use super::state::AgentStatus;
+use super::transcript::{self, TranscriptEntry};
+use super::verify;
+use crate::mcp::{RunToken, ToolCheck};
+use crate::text::{lossy, truncate_chars};
+use ts_rs::TS;
+
+/// Claude settings file written into the working dir before each run.
+pub const CLAUDE_SETTINGS_FILE: &str = ".claude/settings.json";
+/// MCP config file passed to claude via --mcp-config.
+pub const MCP_CONFIG_FILE: &str = ".poietai-mcp.json";
 
 /// Payload sent to the React frontend for each canvas node.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct CanvasNodePayload {
     pub node_id: String,
     pub agent_id: String,
@@ -19,7 +45,8 @@ pub struct CanvasNodePayload {
 }
 
 /// Payload emitted when the agent run completes.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct AgentResultPayload {
     pub agent_id: String,
     pub ticket_id: String,
@@ -27,6 +54,7 @@ pub struct AgentResultPayload {
 }
 
 /// Configuration for running an agent against a ticket.
+#[derive(Clone)]
 pub struct AgentRunConfig {
     pub agent_id: String,
     pub ticket_id: String,
@@ -47,50 +75,156 @@ pub struct AgentRunConfig {
     /// Optional group ID for fan-out builds — passed through to CanvasNodePayload
     /// so the frontend can associate events with a specific task group.
     pub group_id: Option<String>,
+    /// Where claude runs — on the host or inside the project's dev environment.
+    pub backend: ExecutionBackend,
+    /// CPU/memory caps for the claude process tree.
+    pub limits: ResourceLimits,
+    /// Cap on agentic turns for this run (claude --max-turns), for bounded steps
+    /// like pair-programming turns.
+    pub max_turns: Option<u32>,
+    /// Shell command that must pass before the agent may run `gh pr create`.
+    pub pr_gate: Option<String>,
+    /// Hold `gh pr create` back until the orchestrator has checked the
+    /// ticket's acceptance criteria.
+    pub verify_before_pr: bool,
 }
 
-/// Wrap a string in POSIX single quotes for safe embedding in a shell script.
-/// Single quotes prevent ALL shell interpretation (globs, parameter expansion, etc.).
-/// A single quote inside is handled by: end quote → escaped apostrophe → reopen quote.
-#[cfg(target_os = "windows")]
-fn sh_quote(s: &str) -> String {
-    format!("'{}'", s.replace('\'', r"'\''"))
+/// What a finished run leaves behind.
+#[derive(Debug, Clone, Default)]
+pub struct RunOutcome {
+    pub session_id: Option<String>,
+    /// The agent's final message, from the stream's result line.
+    pub result: Option<String>,
+    /// The run asked to open a PR and was held until the user decides.
+    pub pr_held: bool,
+    /// The run asked to open a PR and was held for the acceptance check.
+    pub verify_held: bool,
 }
 
-/// On Windows, convert a UNC WSL path like
-/// `\\wsl.localhost\Ubuntu\home\user\repo` to a Linux path `/home/user/repo`.
-/// Falls back to the original string if it doesn't match the expected format.
+/// Build the claude CLI arguments (everything after the program name).
+///
+/// Argument order matters: --allowedTools is variadic (<tools...>) and greedily
+/// consumes following non-flag args. We place it BEFORE --append-system-prompt
+/// so the flag interrupts the variadic and the prompt arrives as intended.
+///
+/// Order: --allowedTools "..." --mcp-config <file> [--resume "..."] --append-system-prompt "..." "PROMPT"
+#[cfg(not(target_os = "windows"))]
+fn claude_args(config: &AgentRunConfig, mcp_config_path: &str) -> Vec<String> {
+    let mut args = vec![
+        "--print".to_string(),
+        "--verbose".to_string(),
+        "--output-format".to_string(),
+        "stream-json".to_string(),
+        "--allowedTools".to_string(),
+        config.allowed_tools.join(","),
+        "--mcp-config".to_string(),
+        mcp_config_path.to_string(),
+    ];
+    if let Some(ref session_id) = config.resume_session_id {
+        args.push("--resume".to_string());
+        args.push(session_id.clone());
+    }
+    if let Some(max_turns) = config.max_turns {
+        args.push("--max-turns".to_string());
+        args.push(max_turns.to_string());
+    }
+    args.push("--append-system-prompt".to_string());
+    args.push(config.system_prompt.clone());
+    args.push(config.prompt.clone());
+    args
+}
+
+#[cfg(target_os = "windows")]
+use super::backend::sh_quote;
 #[cfg(target_os = "windows")]
-fn wsl_to_linux_path(path: &PathBuf) -> String {
-    let s = path.to_string_lossy();
-    // Matches \\wsl.localhost\<distro>\rest  or  \\wsl$\<distro>\rest
-    if s.starts_with("\\\\wsl") {
-        let mut parts = s.splitn(5, '\\');
-        parts.next(); // ""
-        parts.next(); // ""
-        parts.next(); // "wsl.localhost" or "wsl$"
-        parts.next(); // distro name, e.g. "Ubuntu"
-        if let Some(rest) = parts.next() {
-            return format!("/{}", rest.replace('\\', "/"));
+use crate::platform::{paths, wsl};
+
+/// Tools the guard hook is always asked about: every one that can write a file.
+const GUARDED_TOOLS: &[&str] = &["Bash", "Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// The guard hook's matcher: the tools that write files, and any others a
+/// checkpoint names.
+fn guard_matcher(checkpoints: &[Checkpoint]) -> String {
+    let mut tools: Vec<&str> = GUARDED_TOOLS.to_vec();
+    for tool in checkpoints.iter().flat_map(|c| &c.tools) {
+        if !tools.contains(&tool.as_str()) {
+            tools.push(tool);
         }
     }
-    s.into_owned()
+    tools.join("|")
 }
 
-/// On Windows, extract `\\wsl.localhost\Ubuntu` (or `\\wsl$\Ubuntu`) from a
-/// full UNC WSL path. Used to build paths into the WSL filesystem from Windows.
-#[cfg(target_os = "windows")]
-fn wsl_distro_root(path: &PathBuf) -> Option<String> {
-    let s = path.to_string_lossy();
-    if s.starts_with("\\\\wsl") {
-        let mut parts = s.splitn(5, '\\');
-        parts.next(); // ""
-        parts.next(); // ""
-        let server = parts.next()?; // "wsl.localhost" or "wsl$"
-        let distro = parts.next()?; // e.g. "Ubuntu"
-        return Some(format!("\\\\{}\\{}", server, distro));
-    }
-    None
+/// How long Claude waits on the guard hook. The app answers at once; this
+/// only matters when it can't be reached.
+const GUARD_HOOK_TIMEOUT_SECS: u64 = 120;
+
+/// How long Claude lets the PR gate run. Its default of 60s is too short for
+/// most test suites.
+const PR_GATE_TIMEOUT_SECS: u64 = 30 * 60;
+
+/// Where a run's guard hook asks about tool calls, and which ones.
+struct GuardHook {
+    matcher: String,
+    url: String,
+}
+
+/// PreToolUse hook command that asks the app whether a tool call may go
+/// ahead, before it runs. The app answers with nothing, or with the refusal
+/// the agent sees; a failed request refuses too, so an app that can't be
+/// reached doesn't wave calls through.
+fn guard_hook(url: &str) -> String {
+    format!(
+        "out=$(curl -fsS --max-time {} -H 'Content-Type: application/json' --data-binary @- {}) \
+         || {{ echo 'Could not check this tool call with poietai. Stop here and end your turn.' >&2; exit 2; }}; \
+         [ -z \"$out\" ] || {{ printf '%s\\n' \"$out\" >&2; exit 2; }}",
+        GUARD_HOOK_TIMEOUT_SECS - 10,
+        backend::sh_quote(url)
+    )
+}
+
+/// Hooks for a run's `.claude/settings.json`. With a PR gate, a PreToolUse
+/// hook runs the gate before any Bash call that opens a PR; exit code 2
+/// rejects the call and hands the failing output back to the agent. With a
+/// `hold` hook, `gh pr create` is refused outright until the user approves
+/// the PR or the acceptance check has run, and the gate waits for the run
+/// after that: hooks on one matcher run in parallel, so both would run the
+/// suite for nothing. The guard hook lets the app refuse tool calls before
+/// they happen.
+fn run_hooks(pr_gate: Option<&str>, hold: Option<&str>, guard: Option<&GuardHook>) -> serde_json::Value {
+    let mut hooks = Vec::new();
+    if let Some(hold) = hold {
+        hooks.push(serde_json::json!({ "type": "command", "command": hold }));
+    } else if let Some(gate) = pr_gate.filter(|g| !g.trim().is_empty()) {
+        let command = format!(
+            "case \"$(cat)\" in *'gh pr create'*) \
+             out=$( ({}) 2>&1 ) || {{ echo 'Tests must pass before you open a PR. Fix them and try again:' >&2; \
+             printf '%s\\n' \"$out\" | tail -n 60 >&2; exit 2; }};; esac",
+            gate
+        );
+        hooks.push(serde_json::json!({
+            "type": "command",
+            "command": command,
+            "timeout": PR_GATE_TIMEOUT_SECS
+        }));
+    }
+    let mut pre_tool_use = Vec::new();
+    if !hooks.is_empty() {
+        pre_tool_use.push(serde_json::json!({ "matcher": "Bash", "hooks": hooks }));
+    }
+    if let Some(guard) = guard {
+        pre_tool_use.push(serde_json::json!({
+            "matcher": guard.matcher,
+            "hooks": [{
+                "type": "command",
+                "command": guard_hook(&guard.url),
+                "timeout": GUARD_HOOK_TIMEOUT_SECS
+            }]
+        }));
+    }
+    if pre_tool_use.is_empty() {
+        return serde_json::json!({});
+    }
+    serde_json::json!({ "PreToolUse": pre_tool_use })
 }
 
 /// Run the agent and stream events to the React frontend.
@@ -101,12 +235,133 @@ fn wsl_distro_root(path: &PathBuf) -> Option<String> {
 /// Emits two event types to React:
 /// - "agent-event": one per parsed JSONL line, with the canvas node payload
 /// - "agent-result": once at the end, with the session ID (for pause/resume)
+///
+/// Returns the session ID; use [`run_capturing`] to also get the final message.
 pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String>> {
+    run_capturing(config, app).await.map(|outcome| outcome.session_id)
+}
+
+/// Like [`run`], but also returns the agent's final message.
+pub async fn run_capturing(mut config: AgentRunConfig, app: AppHandle) -> Result<RunOutcome> {
+    // Resumed sessions get the agent's pinned notes on every prompt so
+    // standing corrections survive the whole ticket.
+    if config.resume_session_id.is_some() {
+        match pinned::load(&config.agent_id) {
+            Ok(notes) => config.prompt = pinned::prepend(&notes, &config.prompt),
+            Err(e) => warn!("[process::run] failed to load pinned notes: {:#}", e),
+        }
+    }
+
+    // The gate belongs to the ticket, so every run on it keeps the gate it started with
+    if !config.ticket_id.is_empty() {
+        match config.pr_gate.as_deref().filter(|g| !g.trim().is_empty()) {
+            Some(gate) => {
+                if let Err(e) = pr_gate::remember(&config.ticket_id, gate) {
+                    warn!("[process::run] failed to record PR gate: {:#}", e);
+                }
+            }
+            None => config.pr_gate = pr_gate::for_ticket(&config.ticket_id),
+        }
+    }
+
+    let started_at = chrono::Utc::now().timestamp();
     info!(
         "[process::run] agent={} ticket={} working_dir={:?}",
         config.agent_id, config.ticket_id, config.working_dir
     );
 
+    // Role-scoped path restrictions, checked by the guard hook before each write
+    let role = match app.try_state::<crate::AppState>() {
+        Some(state) => super::state::get_agent(&state.agents, &config.agent_id)
+            .await
+            .map(|a| a.role)
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+    let project_settings = backend::project_settings_for(&config.working_dir);
+    // Model traffic goes through the project's gateway, and each claude
+    // process bills to the next vault key in turn. A key's own gateway, when
+    // it has one, wins over the project's.
+    if let Some(ref gateway) = project_settings.gateway {
+        config.env.extend(gateway.env());
+    }
+    let (agent_id, ticket_id) = (config.agent_id.clone(), config.ticket_id.clone());
+    let api_key = tokio::task::spawn_blocking(move || keys::rotate(&agent_id, &ticket_id))
+        .await
+        .context("key rotation task panicked")?;
+    if let Some(ref key) = api_key {
+        config.env.extend(key.env());
+    }
+    // Held PRs wait for the user; an approval lets the rest of the ticket through.
+    // The acceptance check comes first, so approval is asked for after it.
+    let hold_prs = project_settings.require_pr_approval
+        && !config.verify_before_pr
+        && !pr_approval::is_approved(&config.agent_id, &config.ticket_id);
+    if config.verify_before_pr {
+        config.system_prompt = format!("{}\n\n{}", config.system_prompt, verify::PROMPT_SECTION)
+            .trim_start()
+            .to_string();
+    }
+    if hold_prs {
+        // Resumed sessions have no system prompt of their own
+        config.system_prompt = format!("{}\n\n{}", config.system_prompt, pr_approval::PROMPT_SECTION)
+            .trim_start()
+            .to_string();
+    }
+    let hold_hook = if config.verify_before_pr {
+        Some(verify::hold_hook())
+    } else {
+        hold_prs.then(pr_approval::hold_hook)
+    };
+    let mut pr_requested = false;
+    let mut verify_requested = false;
+    // Checkpoints the user hasn't yet signed off on for this ticket
+    let signoffs: Vec<Checkpoint> = project_settings
+        .checkpoints
+        .into_iter()
+        .filter(|c| !c.tools.is_empty() && c.patterns.iter().any(|p| !p.is_empty()))
+        .filter(|c| !checkpoints::is_approved(&config.agent_id, &config.ticket_id, &c.name))
+        .collect();
+    let role_paths = project_settings.role_paths;
+    let path_rule = role_paths
+        .as_ref()
+        .and_then(|paths| paths.rule_for(&role).cloned().map(|rule| (rule, paths.strictness.clone())));
+    let protected_paths = project_settings.protected_paths;
+
+    let target = backend::prepare(&config.backend, &config.working_dir, config.mcp_port)
+        .await
+        .context("failed to prepare execution backend")?;
+    // The server identifies this run's calls by its token, not by what the agent claims
+    let mut run_token = app
+        .try_state::<crate::AppState>()
+        .map(|state| state.mcp.register_run(&config.agent_id));
+    let mcp_url = format!(
+        "{}?token={}",
+        target.mcp_url(),
+        run_token.as_ref().map(|t| t.as_str()).unwrap_or_default()
+    );
+    // Transient cgroup scope name when running under limits
+    let limit_unit = format!("poietai-{}", uuid::Uuid::new_v4());
+
+    // The worktree as the agent sees it, for resolving the paths its tools write
+    #[cfg(not(target_os = "windows"))]
+    let agent_root = PathBuf::from(target.agent_path(&config.working_dir));
+    #[cfg(target_os = "windows")]
+    let agent_root = PathBuf::from(paths::to_linux(&config.working_dir));
+    let guards = Guards {
+        agent_id: config.agent_id.clone(),
+        ticket_id: config.ticket_id.clone(),
+        role,
+        path_rule,
+        protected_paths,
+        root: agent_root,
+        signoffs,
+    };
+    let guard = run_token.as_ref().filter(|_| guards.is_active()).map(|token| GuardHook {
+        matcher: guard_matcher(&guards.signoffs),
+        url: format!("{}?token={}", target.hook_url(), token.as_str()),
+    });
+
     // Write .claude/settings.json so Claude discovers the MCP server
     {
         let claude_dir = config.working_dir.join(".claude");
@@ -118,16 +373,16 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
             "mcpServers": {
                 "poietai": {
                     "type": "sse",
-                    "url": format!("http://127.0.0.1:{}/sse", config.mcp_port)
+                    "url": mcp_url
                 }
             },
-            // Empty hooks overrides global hooks — prevents the SessionStart:startup
-            // hook from injecting interactive-session skills into headless agent runs.
-            "hooks": {}
+            // Replaces global hooks — prevents the SessionStart:startup hook from
+            // injecting interactive-session skills into headless agent runs.
+            "hooks": run_hooks(config.pr_gate.as_deref(), hold_hook.as_deref(), guard.as_ref())
         });
 
         tokio::fs::write(
-            claude_dir.join("settings.json"),
+            config.working_dir.join(CLAUDE_SETTINGS_FILE),
             serde_json::to_string_pretty(&settings).unwrap(),
         )
         .await
@@ -143,7 +398,7 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
     //
     // We write a small bash script directly to the WSL filesystem via its UNC
     // path (e.g. \\wsl.localhost\Ubuntu\tmp\poietai-<uuid>.sh), then execute
-    // it with `wsl --exec /bin/bash -l <script>`.
+    // it with `wsl -d <distro> --exec /bin/bash <script>`.
     //
     // This sidesteps every argument-passing problem we hit with -c "...":
     //  - Windows CreateProcessW quoting of multi-line / double-quote-containing strings
@@ -153,12 +408,24 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
     // The script file lives on the Linux filesystem so bash reads it directly.
     // POSIX single-quoting inside the script handles any special chars in the
     // system prompt, prompt, or tool names.
-    // -l loads the login profile so nvm / claude are on PATH.
+    // claude is started by absolute path under the PATH of whichever shell
+    // profile found it (see `wsl::resolve_claude`), so no login shell is
+    // needed and fish/zsh-only installs work too.
     #[cfg(target_os = "windows")]
-    let (mut cmd, temp_script) = {
-        let linux_dir = wsl_to_linux_path(&config.working_dir);
+    let (mut cmd, temp_script, stdin_script) = {
+        if config.backend != ExecutionBackend::Host {
+            anyhow::bail!("only the host execution backend is supported on Windows");
+        }
+        let linux_dir = paths::to_linux(&config.working_dir);
+        let distro = wsl::choose(project_settings.wsl_distro.as_deref(), &config.working_dir)?;
+        let claude = {
+            let distro = distro.clone();
+            tokio::task::spawn_blocking(move || wsl::resolve_claude(&distro))
+                .await
+                .context("claude lookup panicked")??
+        };
 
-        let distro_root = wsl_distro_root(&config.working_dir).ok_or_else(|| {
+        let distro_root = paths::distro_root(&config.working_dir).ok_or_else(|| {
             anyhow::anyhow!(
                 "cannot determine WSL distro root from path: {:?}",
                 config.working_dir
@@ -170,30 +437,38 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
             .as_deref()
             .map(|sid| format!("--resume {}", sh_quote(sid)))
             .unwrap_or_default();
+        let max_turns_part = config
+            .max_turns
+            .map(|n| format!("--max-turns {}", n))
+            .unwrap_or_default();
 
         // --allowedTools is variadic and must come BEFORE --mcp-config
         // so the flag interrupts the variadic.
-        let linux_mcp_path = wsl_to_linux_path(&mcp_config_path);
+        let linux_mcp_path = paths::to_linux(&mcp_config_path);
         let script_content = format!(
             "#!/bin/bash\n\
-             exec claude --print --verbose --output-format stream-json \\\n  \
+             export PATH={}\n\
+             {}exec $POIETAI_LIMIT {} --print --verbose --output-format stream-json \\\n  \
              --allowedTools {} \\\n  \
              --mcp-config {} \\\n  \
-             {} \\\n  \
+             {} {} \\\n  \
              --append-system-prompt {} \\\n  \
              {}\n",
+            sh_quote(&claude.path),
+            limits::wsl_script_prefix(&config.limits, &limit_unit),
+            sh_quote(&claude.binary),
             sh_quote(&config.allowed_tools.join(",")),
             sh_quote(&linux_mcp_path),
             resume_part,
+            max_turns_part,
             sh_quote(&config.system_prompt),
             sh_quote(&config.prompt),
         );
 
         // Write the script to WSL's /tmp/ via the UNC path.
         let script_name = format!("poietai-{}.sh", uuid::Uuid::new_v4());
-        let script_win_path =
-            PathBuf::from(format!("{}\\tmp\\{}", distro_root, script_name));
         let script_linux_path = format!("/tmp/{}", script_name);
+        let script_win_path = paths::to_unc(&distro_root, &script_linux_path);
 
         std::fs::write(&script_win_path, script_content.as_bytes())
             .with_context(|| format!("failed to write agent script to {:?}", script_win_path))?;
@@ -204,26 +479,27 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
         );
 
         let mut c = Command::new("wsl");
-        c.arg("--cd")
+        c.arg("-d")
+            .arg(&distro)
+            .arg("--cd")
             .arg(&linux_dir)
             .arg("--exec")
             .arg("/bin/bash")
-            .arg("-l")
             .arg(&script_linux_path);
 
-        (c, Some(script_win_path))
+        (c, Some(script_win_path), None::<String>)
     };
 
     // Write MCP config to a temp file for --mcp-config.
     // Inline JSON strings hit variadic arg parsing issues with claude CLI.
     // A file path is unambiguous and reliable.
-    let mcp_config_path = config.working_dir.join(".poietai-mcp.json");
+    let mcp_config_path = config.working_dir.join(MCP_CONFIG_FILE);
     {
         let mcp_config = serde_json::json!({
             "mcpServers": {
                 "poietai": {
                     "type": "sse",
-                    "url": format!("http://127.0.0.1:{}/sse", config.mcp_port)
+                    "url": mcp_url
                 }
             }
         });
@@ -235,31 +511,24 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
         .with_context(|| "failed to write MCP config file")?;
     }
 
-    // On Linux/macOS, run claude directly with separate args — no shell involved.
-    //
-    // Argument order matters: --allowedTools is variadic (<tools...>) and greedily
-    // consumes following non-flag args. We place it BEFORE --append-system-prompt
-    // so the flag interrupts the variadic and the prompt arrives as intended.
-    //
-    // Order: --allowedTools "..." --mcp-config <file> [--resume "..."] --append-system-prompt "..." "PROMPT"
+    // On Linux/macOS, run claude with separate args — no shell involved on the
+    // host; other backends wrap the same args in their own exec command.
     #[cfg(not(target_os = "windows"))]
-    let (mut cmd, temp_script) = {
-        let mut c = Command::new("claude");
-        c.arg("--print")
-            .arg("--verbose")
-            .arg("--output-format")
-            .arg("stream-json")
-            .arg("--allowedTools")
-            .arg(config.allowed_tools.join(","))
-            .arg("--mcp-config")
-            .arg(&mcp_config_path);
-        if let Some(ref session_id) = config.resume_session_id {
-            c.arg("--resume").arg(session_id);
+    let (mut cmd, temp_script, stdin_script) = {
+        let args = claude_args(&config, &target.agent_path(&mcp_config_path));
+        let (mut c, stdin_script) = backend::command(
+            &config.backend,
+            &target,
+            &config.working_dir,
+            &args,
+            &config.env,
+            &config.limits,
+        );
+        // Docker applies limits itself; other remote backends don't support them.
+        if config.backend == ExecutionBackend::Host {
+            c = limits::wrap(c, &limits::host_prefix(&config.limits, &limit_unit).await);
         }
-        c.arg("--append-system-prompt")
-            .arg(&config.system_prompt)
-            .arg(&config.prompt);
-        (c, None::<PathBuf>)
+        (c, None::<PathBuf>, stdin_script)
     };
 
     // On Linux/macOS, set the working directory directly on the process.
@@ -277,52 +546,199 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
     // and avoid a pipe-buffer deadlock if claude emits large error output.
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::inherit());
+    // A run aborted by a takeover must not leave claude running
+    cmd.kill_on_drop(true);
+    if stdin_script.is_some() {
+        cmd.stdin(std::process::Stdio::piped());
+    }
 
     let mut child = cmd.spawn().context("failed to spawn claude process")?;
     info!("[process::run] claude spawned pid={:?}", child.id());
 
+    // Report throttling / OOM kills from the run's cgroup
+    let limit_watch = match child.id() {
+        Some(pid) if !config.limits.is_unlimited() => limits::Watch::start(
+            app.clone(),
+            config.agent_id.clone(),
+            config.ticket_id.clone(),
+            pid,
+            &limit_unit,
+        ),
+        _ => None,
+    };
+
+    // Remote backends read their launch script from stdin
+    if let Some(script) = stdin_script {
+        let mut stdin = child.stdin.take().expect("stdin was not piped");
+        stdin
+            .write_all(script.as_bytes())
+            .await
+            .context("failed to send launch script")?;
+    }
+
     let stdout = child.stdout.take().expect("stdout was not piped");
-    let mut lines = BufReader::new(stdout).lines();
+    // Split on bytes, not lines(): one stray non-UTF-8 byte from a build tool
+    // would otherwise end the stream with an error
+    let mut lines = BufReader::new(stdout).split(b'\n');
 
     let mut node_sequence: u32 = 0;
+    // Sequences restart every run, so the run id keeps node ids unique per ticket
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let mut transcript = transcript::Writer::for_run(&config.agent_id, &config.ticket_id);
     let mut last_session_id: Option<String> = None;
+    // Known from the init line, before any result carries it
+    let mut run_session_id: Option<String> = None;
+    let mut last_result: Option<String> = None;
+    let mut last_cost: Option<f64> = None;
+
+    let event_filter = app.try_state::<crate::AppState>().map(|s| s.event_filter.clone());
+    let coalesce = crate::project::settings::load_app().ok().and_then(|s| s.coalesce_events);
+    let mut coalescer = Coalescer::new(coalesce.as_ref());
+
+    // Number, persist, and emit one canvas event
+    let mut publish = |transcript: &mut transcript::Writer, mut event: AgentEvent| {
+        node_sequence += 1;
+        let node_id = node_id(&config.agent_id, &config.ticket_id, &run_id, node_sequence);
+        if let Err(e) = overflow::spill(&mut event, &node_id) {
+            warn!("[process::run] failed to store oversized tool result: {:#}", e);
+        }
+
+        let payload = CanvasNodePayload {
+            node_id,
+            agent_id: config.agent_id.clone(),
+            ticket_id: config.ticket_id.clone(),
+            kind: event,
+            group_id: config.group_id.clone(),
+        };
+
+        let entry = TranscriptEntry {
+            at: chrono::Utc::now().timestamp_millis(),
+            node_id: payload.node_id.clone(),
+            kind: payload.kind.clone(),
+            group_id: payload.group_id.clone(),
+        };
+        transcript.record(entry);
+
+        let _ = app.emit("agent-event", &payload);
+    };
 
     // Read JSONL lines as they arrive — loops until claude exits
-    while let Some(line) = lines
-        .next_line()
-        .await
-        .context("error reading claude output")?
-    {
-        let line = line.trim().to_string();
+    loop {
+        // Hold the stream while the transcript is far behind on disk
+        transcript.catch_up().await;
+        let next = tokio::select! {
+            line = lines.next_segment() => line,
+            Some(check) = next_check(&mut run_token) => {
+                let refusal = guards.review(&check, run_session_id.as_deref(), &app).await;
+                let stop = refusal.is_some();
+                let _ = check.reply.send(refusal);
+                if stop {
+                    // Refused before it ran; the stream ends once claude exits
+                    let _ = child.start_kill();
+                }
+                continue;
+            }
+            // Don't sit on held text while claude goes quiet
+            _ = until(coalescer.deadline()) => {
+                for event in coalescer.flush() {
+                    publish(&mut transcript, event);
+                }
+                continue;
+            }
+        };
+        let Some(line) = next.context("error reading claude output")? else {
+            break;
+        };
+        let line = lossy(line).trim().to_string();
         if line.is_empty() {
             continue;
         }
 
-        info!(
-            "[process::run] line: {}",
-            line.chars().take(200).collect::<String>()
-        );
+        info!("[process::run] line: {}", truncate_chars(&line, 200));
+
+        if let Some(session_id) = init_session_id(&line) {
+            let journaled =
+                recovery::set_session(&config.ticket_id, &config.agent_id, &config.working_dir, &session_id);
+            if let Err(e) = journaled {
+                warn!("[process::run] failed to journal session: {:#}", e);
+            }
+            run_session_id = Some(session_id);
+        }
 
         for event in parse_events(&line) {
+            if let AgentEvent::ToolUse { ref tool_name, ref tool_input, .. } = event {
+                let command = tool_input.get("command").and_then(|c| c.as_str()).unwrap_or_default();
+                if config.verify_before_pr && tool_name == "Bash" && pr_approval::opens_pr(command) {
+                    verify_requested = true;
+                }
+                if hold_prs && !pr_requested && tool_name == "Bash" && pr_approval::opens_pr(command) {
+                    pr_requested = true;
+                    let request = PrRequest {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        agent_id: config.agent_id.clone(),
+                        ticket_id: config.ticket_id.clone(),
+                        worktree_path: config.working_dir.to_string_lossy().to_string(),
+                        session_id: run_session_id.clone(),
+                        title: pr_approval::requested_title(command),
+                        diff_summary: String::new(),
+                        requested_at: chrono::Utc::now().timestamp(),
+                        approved: false,
+                    };
+                    let app = app.clone();
+                    tokio::spawn(async move {
+                        let worktree = PathBuf::from(&request.worktree_path);
+                        let summary = tokio::task::spawn_blocking(move || pr_approval::diff_summary(&worktree)).await;
+                        let request = match summary {
+                            Ok(Ok(diff_summary)) => PrRequest { diff_summary, ..request },
+                            Ok(Err(e)) => {
+                                warn!("[process::run] no diff summary for PR request: {:#}", e);
+                                request
+                            }
+                            Err(e) => {
+                                warn!("[process::run] diff summary panicked: {}", e);
+                                request
+                            }
+                        };
+                        info!(
+                            "[process::run] agent={} ticket={} asked to open a PR",
+                            request.agent_id, request.ticket_id
+                        );
+                        if let Err(e) = pr_approval::request(request.clone()) {
+                            error!("[process::run] failed to record PR request: {:#}", e);
+                        }
+                        let _ = app.emit("pr-approval-requested", &request);
+                    });
+                }
+            }
+
             // Capture session_id from Result events for pause/resume
-            if let AgentEvent::Result { ref session_id, .. } = event {
+            if let AgentEvent::Result { ref session_id, ref result, cost_usd } = event {
                 last_session_id = session_id.clone();
+                last_result = result.clone();
+                last_cost = cost_usd;
             }
 
-            node_sequence += 1;
-            let node_id = format!("{}-{}-{}", config.agent_id, config.ticket_id, node_sequence);
-
-            let payload = CanvasNodePayload {
-                node_id,
-                agent_id: config.agent_id.clone(),
-                ticket_id: config.ticket_id.clone(),
-                kind: event,
-                group_id: config.group_id.clone(),
-            };
-
-            let _ = app.emit("agent-event", &payload);
+            if event_filter.as_ref().is_some_and(|f| !f.read().unwrap().allows(&event)) {
+                continue;
+            }
+            for event in coalescer.push(event, tokio::time::Instant::now()) {
+                publish(&mut transcript, event);
+            }
         }
     }
+    for event in coalescer.flush() {
+        publish(&mut transcript, event);
+    }
+
+    if let Err(e) = transcript.finish().await {
+        error!("[process::run] failed to persist transcript: {:#}", e);
+    }
+    // Keep the overflow store from growing without bound
+    tokio::task::spawn_blocking(|| {
+        if let Err(e) = overflow::prune(overflow::MAX_STORE_BYTES) {
+            warn!("[process::run] failed to prune overflow store: {:#}", e);
+        }
+    });
 
     // Wait for the process to exit cleanly
     let status = child
@@ -335,11 +751,79 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
         status, config.agent_id, config.ticket_id
     );
 
+    let oom_killed = limit_watch.is_some_and(limits::Watch::stop);
+    #[cfg(unix)]
+    let killed = {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal() == Some(9)
+    };
+    #[cfg(not(unix))]
+    let killed = false;
+    // SIGKILL is the OOM killer taking out the agent itself only when the
+    // cgroup recorded a kill; a stop or takeover kills it too
+    if killed && oom_killed {
+        if let Some(mb) = config.limits.memory_mb {
+            warn!("[process::run] agent={} killed at memory limit {} MiB", config.agent_id, mb);
+            let _ = app.emit(
+                "agent-resource-limit",
+                &limits::ResourceLimitPayload {
+                    agent_id: config.agent_id.clone(),
+                    ticket_id: config.ticket_id.clone(),
+                    kind: "oom_killed".to_string(),
+                    detail: format!("the agent run was killed after exceeding {} MiB", mb),
+                },
+            );
+        }
+    }
+
     // Clean up the temp script file (Windows only; None on other platforms)
     if let Some(ref path) = temp_script {
         let _ = std::fs::remove_file(path);
     }
 
+    // Bring remote results back before anyone looks at the worktree
+    let synced = backend::finish(&config.backend, &target, &config.working_dir).await;
+    if let Err(ref e) = synced {
+        error!("[process::run] failed to sync results for agent={}: {:#}", config.agent_id, e);
+    }
+
+    // Record spend and announce the run that crosses a budget cap
+    if let Some(cost_usd) = last_cost {
+        let key_id = api_key.as_ref().map(|k| k.id.clone());
+        let entry = costs::CostEntry {
+            at: chrono::Utc::now().timestamp(),
+            agent_id: config.agent_id.clone(),
+            ticket_id: config.ticket_id.clone(),
+            cost_usd,
+        };
+        let recorded = tokio::task::spawn_blocking(move || {
+            if let Some(key_id) = key_id {
+                keys::record_cost(&key_id, cost_usd);
+            }
+            costs::record(entry);
+        })
+        .await;
+        if let Err(e) = recorded {
+            warn!("[process::run] cost recording task panicked: {}", e);
+        }
+    }
+
+    let run = history::RunRecord {
+        started_at,
+        finished_at: chrono::Utc::now().timestamp(),
+        agent_id: config.agent_id.clone(),
+        ticket_id: config.ticket_id.clone(),
+        cost_usd: last_cost.unwrap_or(0.0),
+        outcome: if status.success() && synced.is_ok() {
+            history::Outcome::Succeeded
+        } else {
+            history::Outcome::Failed
+        },
+    };
+    if let Err(e) = history::record(&run) {
+        warn!("[process::run] failed to record run history: {:#}", e);
+    }
+
     // Emit the completion event regardless of exit status
     // React uses this to show the ask-user overlay if needed
     let _ = app.emit(
@@ -351,17 +835,203 @@ pub async fn run(config: AgentRunConfig, app: AppHandle) -> Result<Option<String
         },
     );
 
+    synced.context("failed to sync results back from execution backend")?;
     if !status.success() {
         anyhow::bail!("claude process exited with status: {}", status);
     }
 
-    Ok(last_session_id)
+    Ok(RunOutcome {
+        session_id: last_session_id,
+        result: last_result,
+        pr_held: pr_requested,
+        verify_held: verify_requested,
+    })
+}
+
+/// What a run's guard hook checks tool calls against.
+struct Guards {
+    agent_id: String,
+    ticket_id: String,
+    role: String,
+    /// The agent's role rule, when its role has one.
+    path_rule: Option<(PathRule, Strictness)>,
+    protected_paths: Vec<String>,
+    /// The worktree as the agent sees it.
+    root: PathBuf,
+    /// Checkpoints the user hasn't yet signed off on for this ticket.
+    signoffs: Vec<Checkpoint>,
+}
+
+impl Guards {
+    fn is_active(&self) -> bool {
+        self.path_rule.is_some() || !self.protected_paths.is_empty() || !self.signoffs.is_empty()
+    }
+
+    /// Decide a tool call before it runs: `None` lets it go ahead, otherwise
+    /// the refusal the agent sees. A refused call stops the run.
+    async fn review(&self, check: &ToolCheck, session_id: Option<&str>, app: &AppHandle) -> Option<String> {
+        let cwd = check.cwd.clone().unwrap_or_else(|| self.root.clone());
+        let targets = guard::write_paths(&check.tool_name, &check.tool_input, &cwd, &self.root);
+
+        // Protected paths wait for a human, whatever the role allows
+        let protected = targets.iter().find_map(|target| match target {
+            WritePath::Inside(path)
+                if guard::is_protected(&self.protected_paths, path)
+                    && !guard::is_approved(&self.agent_id, &self.ticket_id, path) =>
+            {
+                Some(path.clone())
+            }
+            _ => None,
+        });
+        if let Some(path) = protected {
+            warn!(
+                "[process::run] agent={} tried to write protected path {} ({}), stopping for approval",
+                self.agent_id, path, check.tool_name
+            );
+            let edit = ProtectedEdit {
+                id: uuid::Uuid::new_v4().to_string(),
+                agent_id: self.agent_id.clone(),
+                ticket_id: self.ticket_id.clone(),
+                tool_name: check.tool_name.clone(),
+                path: path.clone(),
+                session_id: session_id.map(String::from),
+                requested_at: chrono::Utc::now().timestamp(),
+                approved: false,
+            };
+            if let Err(e) = guard::request_edit(edit.clone()) {
+                error!("[process::run] failed to record protected edit: {:#}", e);
+            }
+            if let Some(state) = app.try_state::<crate::AppState>() {
+                super::state::set_status(&state.agents, &self.agent_id, AgentStatus::WaitingForUser).await;
+            }
+            let _ = app.emit("protected-path-blocked", &edit);
+            return Some(format!(
+                "{} is protected: writing it needs approval from the user, and the request has been sent. \
+                 Stop here and end your turn.",
+                path
+            ));
+        }
+
+        let detail = checkpoints::detail(&check.tool_name, &check.tool_input, &cwd, &self.root);
+        if let Some(checkpoint) = self.signoffs.iter().find(|c| c.matches(&check.tool_name, &detail)) {
+            let hit = CheckpointHit {
+                id: uuid::Uuid::new_v4().to_string(),
+                agent_id: self.agent_id.clone(),
+                ticket_id: self.ticket_id.clone(),
+                checkpoint: checkpoint.name.clone(),
+                tool_name: check.tool_name.clone(),
+                detail,
+                session_id: session_id.map(String::from),
+                reached_at: chrono::Utc::now().timestamp(),
+                approved: false,
+            };
+            info!(
+                "[process::run] agent={} paused at checkpoint '{}': {}",
+                self.agent_id, hit.checkpoint, hit.detail
+            );
+            if let Err(e) = checkpoints::reach(hit.clone()) {
+                error!("[process::run] failed to record checkpoint: {:#}", e);
+            }
+            if let Some(state) = app.try_state::<crate::AppState>() {
+                super::state::set_status(&state.agents, &self.agent_id, AgentStatus::WaitingForUser).await;
+            }
+            let _ = app.emit("checkpoint-reached", &hit);
+            return Some(format!(
+                "Checkpoint \"{}\": this needs sign-off from the user, and the request has been sent. \
+                 Stop here and end your turn.",
+                checkpoint.name
+            ));
+        }
+
+        let (rule, strictness) = self.path_rule.as_ref()?;
+        let path = guard::violation(rule, &check.tool_name, &check.tool_input, &cwd, &self.root)?;
+        let interrupted = *strictness == Strictness::Interrupt;
+        warn!(
+            "[process::run] agent={} role={} tried to write outside its paths: {} ({})",
+            self.agent_id, self.role, path, check.tool_name
+        );
+        let _ = app.emit(
+            "path-violation",
+            &PathViolationPayload {
+                agent_id: self.agent_id.clone(),
+                ticket_id: self.ticket_id.clone(),
+                role: self.role.clone(),
+                tool_name: check.tool_name.clone(),
+                path: path.clone(),
+                interrupted,
+            },
+        );
+        interrupted.then(|| format!("As {}, you may not write {}. The run is being stopped.", self.role, path))
+    }
+}
+
+/// A canvas node's id: unique across every run an agent makes on a ticket,
+/// since annotations and stored tool results are keyed by it.
+fn node_id(agent_id: &str, ticket_id: &str, run_id: &str, sequence: u32) -> String {
+    format!("{}-{}-{}-{}", agent_id, ticket_id, run_id, sequence)
+}
+
+/// The next tool call the run's guard hook asks about; never, for runs
+/// without a token.
+async fn next_check(token: &mut Option<RunToken>) -> Option<ToolCheck> {
+    match token {
+        Some(token) => token.next_check().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Sleep until `deadline`, or forever without one.
+async fn until(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn pr_gate_hook_only_when_set() {
+        assert_eq!(run_hooks(None, None, None), serde_json::json!({}));
+        assert_eq!(run_hooks(Some("  "), None, None), serde_json::json!({}));
+        let hooks = run_hooks(Some("cargo test"), None, None);
+        assert_eq!(hooks["PreToolUse"][0]["matcher"], "Bash");
+        let command = hooks["PreToolUse"][0]["hooks"][0]["command"].as_str().unwrap();
+        assert!(command.contains("*'gh pr create'*) out=$( (cargo test) 2>&1 )"));
+        assert!(command.contains("exit 2"));
+        assert_eq!(hooks["PreToolUse"][0]["hooks"][0]["timeout"], PR_GATE_TIMEOUT_SECS);
+
+        let held = run_hooks(Some("cargo test"), Some(&pr_approval::hold_hook()), None);
+        let hooks = held["PreToolUse"][0]["hooks"].as_array().unwrap();
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks[0]["command"], pr_approval::hold_hook());
+    }
+
+    #[test]
+    fn guard_hook_covers_checkpoint_tools() {
+        assert_eq!(guard_matcher(&[]), "Bash|Edit|MultiEdit|Write|NotebookEdit");
+        let mut checkpoints = checkpoints::presets();
+        checkpoints[0].tools.push("WebFetch".to_string());
+        assert_eq!(guard_matcher(&checkpoints), "Bash|Edit|MultiEdit|Write|NotebookEdit|WebFetch");
+    }
+
+    #[test]
+    fn guard_hook_refuses_unless_the_app_allows() {
+        let guard = GuardHook {
+            matcher: guard_matcher(&[]),
+            url: "http://127.0.0.1:9/hook?token=t".to_string(),
+        };
+        let hooks = run_hooks(None, None, Some(&guard));
+        assert_eq!(hooks["PreToolUse"][0]["matcher"], guard.matcher);
+        assert_eq!(hooks["PreToolUse"][0]["hooks"][0]["timeout"], GUARD_HOOK_TIMEOUT_SECS);
+        let command = hooks["PreToolUse"][0]["hooks"][0]["command"].as_str().unwrap();
+        assert!(command.contains("--data-binary @- 'http://127.0.0.1:9/hook?token=t'"));
+        // Both an unreachable app and a refusal exit 2
+        assert_eq!(command.matches("exit 2").count(), 2);
+    }
+
     #[test]
     fn allowed_tools_join_format() {
         let tools = vec![
@@ -376,8 +1046,8 @@ mod tests {
 
     #[test]
     fn node_id_format() {
-        let node_id = format!("{}-{}-{}", "agent-1", "ticket-42", 3);
-        assert_eq!(node_id, "agent-1-ticket-42-3");
+        assert_eq!(node_id("agent-1", "ticket-42", "r1", 3), "agent-1-ticket-42-r1-3");
+        assert_ne!(node_id("agent-1", "ticket-42", "r1", 3), node_id("agent-1", "ticket-42", "r2", 3));
     }
 
     #[cfg(target_os = "windows")]
@@ -398,28 +1068,4 @@ mod tests {
         assert!(quoted.ends_with('\''));
         assert!(quoted.contains("--title"));
     }
-
-    #[cfg(target_os = "windows")]
-    #[test]
-    fn wsl_distro_root_localhost() {
-        let path = PathBuf::from(r"\\wsl.localhost\Ubuntu\home\keenan\repo");
-        assert_eq!(
-            super::wsl_distro_root(&path),
-            Some(r"\\wsl.localhost\Ubuntu".to_string())
-        );
-    }
-
-    #[cfg(target_os = "windows")]
-    #[test]
-    fn wsl_path_conversion_wsl_localhost() {
-        let path = PathBuf::from(r"\\wsl.localhost\Ubuntu\home\keenan\github\repo");
-        assert_eq!(wsl_to_linux_path(&path), "/home/keenan/github/repo");
-    }
-
-    #[cfg(target_os = "windows")]
-    #[test]
-    fn wsl_path_conversion_wsl_dollar() {
-        let path = PathBuf::from(r"\\wsl$\Ubuntu\home\keenan\github\repo");
-        assert_eq!(wsl_to_linux_path(&path), "/home/keenan/github/repo");
-    }
 }
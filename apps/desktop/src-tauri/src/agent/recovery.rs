@@ -0,0 +1,386 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::orchestrator::OrchestratorInput;
+use crate::github::app_auth::GitHubAppCredentials;
+use crate::project::store;
+use crate::StartAgentPayload;
+
+/// What a resumed run is told when it picks up after a crash.
+pub const RESUME_PROMPT: &str = "The app closed while you were working on this ticket. \
+    Check the worktree's state and pick up where you left off.";
+
+/// A run that was queued or in flight, journaled so a crash doesn't lose it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct RunMarker {
+    pub run_id: String,
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub worktree_path: String,
+    /// Unix seconds the run was started or queued.
+    #[ts(type = "number")]
+    pub started_at: i64,
+    /// The claude session, once it reported one — what a resume continues.
+    pub session_id: Option<String>,
+    /// Local RFC 3339 time a deferred run was waiting for. None once running.
+    pub queued_until: Option<String>,
+    /// The ticket run this is part of — a pair reviewer, fan-out group or
+    /// tournament entry. Journaled once it reports a session.
+    pub parent_run_id: Option<String>,
+    /// What an in-flight run was started with, without its tokens.
+    #[ts(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<OrchestratorInput>,
+    /// What a queued run will start with, without its tokens.
+    #[ts(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<StartAgentPayload>,
+}
+
+impl RunMarker {
+    pub fn new(run_id: &str, agent_id: &str, ticket_id: &str, worktree_path: &str) -> Self {
+        RunMarker {
+            run_id: run_id.to_string(),
+            agent_id: agent_id.to_string(),
+            ticket_id: ticket_id.to_string(),
+            worktree_path: worktree_path.to_string(),
+            started_at: chrono::Utc::now().timestamp(),
+            session_id: None,
+            queued_until: None,
+            parent_run_id: None,
+            input: None,
+            payload: None,
+        }
+    }
+
+    /// Journal `input` so the run can be replayed. Tokens stay off disk;
+    /// [`Secrets::restore`] puts fresh ones back.
+    pub fn with_input(self, input: &OrchestratorInput) -> Self {
+        RunMarker {
+            input: Some(OrchestratorInput {
+                gh_token: String::new(),
+                gitlab_token: None,
+                github_app: input.github_app.as_ref().map(without_key),
+                ..input.clone()
+            }),
+            ..self
+        }
+    }
+
+    /// Journal a queued run's `payload`, tokens removed as for [`RunMarker::with_input`].
+    pub fn with_payload(self, payload: &StartAgentPayload) -> Self {
+        RunMarker {
+            payload: Some(StartAgentPayload {
+                gh_token: String::new(),
+                gitlab_token: None,
+                github_app: payload.github_app.as_ref().map(without_key),
+                ..payload.clone()
+            }),
+            ..self
+        }
+    }
+}
+
+fn without_key(app: &GitHubAppCredentials) -> GitHubAppCredentials {
+    GitHubAppCredentials {
+        private_key_pem: String::new(),
+        ..app.clone()
+    }
+}
+
+/// Tokens for replaying a journaled run, from the vault.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+pub struct Secrets {
+    pub gh_token: String,
+    #[serde(default)]
+    pub gitlab_token: Option<String>,
+    #[serde(default)]
+    pub github_app: Option<GitHubAppCredentials>,
+}
+
+impl Secrets {
+    pub fn restore(self, input: OrchestratorInput) -> OrchestratorInput {
+        OrchestratorInput {
+            gh_token: self.gh_token,
+            gitlab_token: self.gitlab_token,
+            github_app: self.github_app,
+            ..input
+        }
+    }
+
+    pub fn restore_payload(self, payload: StartAgentPayload) -> StartAgentPayload {
+        StartAgentPayload {
+            gh_token: self.gh_token,
+            gitlab_token: self.gitlab_token,
+            github_app: self.github_app,
+            ..payload
+        }
+    }
+}
+
+/// `$HOME/.poietai/runs.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Journal {
+    /// Runs this process is responsible for.
+    active: Vec<RunMarker>,
+    /// Runs a previous process died holding, awaiting resume or dismissal.
+    interrupted: Vec<RunMarker>,
+}
+
+impl Journal {
+    /// A started run replaces any other running on its ticket — one holding
+    /// the ticket's lock means the previous run was taken over.
+    fn begin(&mut self, marker: RunMarker) {
+        let starting = marker.queued_until.is_none();
+        self.active.retain(|m| {
+            m.run_id != marker.run_id
+                && !(starting && m.queued_until.is_none() && m.ticket_id == marker.ticket_id)
+        });
+        self.active.push(marker);
+    }
+
+    /// Everything still active belonged to a process that is gone.
+    fn recover(&mut self) -> usize {
+        let orphaned = self.active.len();
+        self.interrupted.append(&mut self.active);
+        orphaned
+    }
+
+    /// Attach a session to the run it belongs to. A session from another
+    /// agent or worktree on a running ticket is one of its sub-runs, and
+    /// gets a marker of its own under that run.
+    fn set_session(
+        &mut self,
+        ticket_id: &str,
+        agent_id: &str,
+        working_dir: &Path,
+        session_id: &str,
+    ) {
+        let worktree = working_dir.to_string_lossy();
+        let running = |m: &RunMarker| m.ticket_id == ticket_id && m.queued_until.is_none();
+        if let Some(marker) = self
+            .active
+            .iter_mut()
+            .find(|m| running(m) && m.agent_id == agent_id && m.worktree_path == worktree)
+        {
+            marker.session_id = Some(session_id.to_string());
+            return;
+        }
+        let Some(parent) = self
+            .active
+            .iter()
+            .find(|m| running(m) && m.parent_run_id.is_none())
+        else {
+            return;
+        };
+        let marker = RunMarker {
+            session_id: Some(session_id.to_string()),
+            parent_run_id: Some(parent.run_id.clone()),
+            ..RunMarker::new(
+                &uuid::Uuid::new_v4().to_string(),
+                agent_id,
+                ticket_id,
+                &worktree,
+            )
+        };
+        self.active.push(marker);
+    }
+
+    /// Drop `run_id` and its sub-runs.
+    fn finish(&mut self, run_id: &str) {
+        self.active
+            .retain(|m| m.run_id != run_id && m.parent_run_id.as_deref() != Some(run_id));
+    }
+}
+
+// Serializes read-modify-write cycles on the journal file.
+static JOURNAL: Mutex<()> = Mutex::new(());
+
+fn path() -> PathBuf {
+    store::app_dir().join("runs.json")
+}
+
+fn edit<R>(f: impl FnOnce(&mut Journal) -> R) -> Result<R> {
+    let _guard = JOURNAL.lock().unwrap_or_else(|e| e.into_inner());
+    let mut journal: Journal = store::read_json(&path())?;
+    let result = f(&mut journal);
+    store::write_json(&path(), &journal)?;
+    Ok(result)
+}
+
+/// Record a run as queued or started.
+pub fn begin(marker: RunMarker) -> Result<()> {
+    edit(|j| j.begin(marker))
+}
+
+/// Drop a run that finished — successfully or not — or was replaced.
+pub fn finish(run_id: &str) -> Result<()> {
+    edit(|j| j.finish(run_id))
+}
+
+/// Note the session `agent_id` is running on `ticket_id` in `working_dir`,
+/// so a crash can resume it.
+pub fn set_session(
+    ticket_id: &str,
+    agent_id: &str,
+    working_dir: &Path,
+    session_id: &str,
+) -> Result<()> {
+    edit(|j| j.set_session(ticket_id, agent_id, working_dir, session_id))
+}
+
+/// The run `agent_id` has in flight on `ticket_id`, if journaled.
+pub fn active_run(ticket_id: &str, agent_id: &str) -> Result<Option<RunMarker>> {
+    let _guard = JOURNAL.lock().unwrap_or_else(|e| e.into_inner());
+    let journal: Journal = store::read_json(&path())?;
+    Ok(journal.active.into_iter().find(|m| {
+        m.ticket_id == ticket_id
+            && m.agent_id == agent_id
+            && m.queued_until.is_none()
+            && m.parent_run_id.is_none()
+    }))
+}
+
+/// Mark the runs a previous process left behind as interrupted. Call once
+/// at startup, before anything new is journaled.
+pub fn recover() -> Result<usize> {
+    edit(Journal::recover)
+}
+
+/// Runs awaiting resume, oldest first.
+pub fn interrupted() -> Result<Vec<RunMarker>> {
+    let _guard = JOURNAL.lock().unwrap_or_else(|e| e.into_inner());
+    let journal: Journal = store::read_json(&path())?;
+    Ok(journal.interrupted)
+}
+
+/// Remove an interrupted run, returning it.
+pub fn take_interrupted(run_id: &str) -> Result<Option<RunMarker>> {
+    edit(|j| {
+        let index = j.interrupted.iter().position(|m| m.run_id == run_id)?;
+        Some(j.interrupted.remove(index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(run_id: &str, agent_id: &str) -> RunMarker {
+        RunMarker::new(run_id, agent_id, "t-1", "/repo/.worktrees/t-1")
+    }
+
+    #[test]
+    fn sessions_attach_to_the_running_agent() {
+        let mut journal = Journal {
+            active: vec![marker("r1", "driver"), marker("r2", "reviewer")],
+            interrupted: vec![],
+        };
+        journal.set_session("t-1", "driver", Path::new("/repo/.worktrees/t-1"), "sess-1");
+        assert_eq!(journal.active[0].session_id.as_deref(), Some("sess-1"));
+        assert_eq!(journal.active[1].session_id, None);
+    }
+
+    #[test]
+    fn journaled_input_carries_no_tokens() {
+        let input: OrchestratorInput = serde_json::from_value(serde_json::json!({
+            "agent_id": "a",
+            "ticket_id": "t-1",
+            "ticket_slug": "fix-retry",
+            "prompt": "Fix the retry backoff",
+            "system_prompt": "",
+            "repo_root": "/repo",
+            "gh_token": "ghp_secret",
+            "gitlab_token": "glpat-secret",
+            "github_app": { "app_id": "1", "installation_id": "2", "private_key_pem": "-----BEGIN KEY" },
+            "phase": "build",
+            "worktree_path_override": null,
+            "plan_artifact": null,
+            "group_id": null
+        }))
+        .unwrap();
+        let marker = marker("r1", "a").with_input(&input);
+        let text = serde_json::to_string(&marker).unwrap();
+        assert!(
+            !text.contains("ghp_secret")
+                && !text.contains("glpat-secret")
+                && !text.contains("BEGIN KEY")
+        );
+
+        let secrets = Secrets {
+            gh_token: "ghp_fresh".to_string(),
+            ..Secrets::default()
+        };
+        let restored = secrets.restore(marker.input.unwrap());
+        assert_eq!(restored.gh_token, "ghp_fresh");
+        assert_eq!(restored.prompt, "Fix the retry backoff");
+    }
+
+    #[test]
+    fn sub_runs_are_journaled_under_their_ticket_run() {
+        let mut journal = Journal {
+            active: vec![marker("r1", "driver")],
+            interrupted: vec![],
+        };
+        let group = Path::new("/repo/.worktrees/t-1-group-a");
+        journal.set_session("t-1", "driver", group, "sess-a");
+        journal.set_session("t-1", "driver", group, "sess-a2");
+        journal.set_session(
+            "t-1",
+            "reviewer",
+            Path::new("/repo/.worktrees/t-1"),
+            "sess-r",
+        );
+        journal.set_session("t-2", "other", Path::new("/repo/.worktrees/t-2"), "sess-x");
+
+        assert_eq!(journal.active.len(), 3);
+        assert_eq!(journal.active[0].session_id, None);
+        assert_eq!(
+            journal.active[1].worktree_path,
+            "/repo/.worktrees/t-1-group-a"
+        );
+        assert_eq!(journal.active[1].session_id.as_deref(), Some("sess-a2"));
+        assert_eq!(journal.active[2].agent_id, "reviewer");
+        assert!(journal.active[1..]
+            .iter()
+            .all(|m| m.parent_run_id.as_deref() == Some("r1")));
+
+        journal.finish("r1");
+        assert!(journal.active.is_empty());
+    }
+
+    #[test]
+    fn a_started_run_replaces_the_one_it_took_over() {
+        let mut journal = Journal::default();
+        journal.begin(RunMarker {
+            queued_until: Some("2026-01-05T09:00:00+00:00".to_string()),
+            ..marker("queued", "a")
+        });
+        journal.begin(marker("r1", "a"));
+        journal.begin(marker("r2", "b"));
+        let ids: Vec<&str> = journal.active.iter().map(|m| m.run_id.as_str()).collect();
+        assert_eq!(ids, ["queued", "r2"]);
+    }
+
+    #[test]
+    fn recovery_moves_active_runs_to_interrupted() {
+        let mut journal = Journal {
+            active: vec![marker("r2", "a")],
+            interrupted: vec![marker("r1", "a")],
+        };
+        assert_eq!(journal.recover(), 1);
+        assert!(journal.active.is_empty());
+        let ids: Vec<&str> = journal
+            .interrupted
+            .iter()
+            .map(|m| m.run_id.as_str())
+            .collect();
+        assert_eq!(ids, ["r1", "r2"]);
+    }
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+use super::process::CanvasNodePayload;
+use super::transcript::TranscriptEntry;
+
+/// Longest pause between two replayed events, after speed-up. Agents sit
+/// idle for minutes waiting on builds or answers; nobody wants to watch that.
+const MAX_GAP_MS: u64 = 3_000;
+
+/// Payload for `replay-event`: a recorded canvas node, re-emitted on its
+/// original schedule. Kept apart from `agent-event` so replays never touch
+/// live run state.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ReplayEventPayload {
+    pub replay_id: String,
+    pub node: CanvasNodePayload,
+}
+
+/// Payload for `replay-finished`, sent after the last event or when stopped.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ReplayFinishedPayload {
+    pub replay_id: String,
+    pub stopped: bool,
+}
+
+/// Replays in progress, by id.
+pub type ReplayStore = Arc<Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>>;
+
+pub fn new_store() -> ReplayStore {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Milliseconds to wait before each entry: the recorded gap divided by
+/// `speed`, capped at `MAX_GAP_MS`. The first entry plays immediately.
+fn delays(entries: &[TranscriptEntry], speed: f64) -> Vec<u64> {
+    let mut previous = entries.first().map_or(0, |e| e.at);
+    entries
+        .iter()
+        .map(|entry| {
+            let gap = (entry.at - previous).max(0) as f64 / speed;
+            previous = entry.at;
+            (gap as u64).min(MAX_GAP_MS)
+        })
+        .collect()
+}
+
+/// Start re-emitting a recorded run at `speed` times its original pace.
+/// Returns the replay's id; events arrive as `replay-event`.
+pub fn start(
+    store: &ReplayStore,
+    app: AppHandle,
+    agent_id: &str,
+    ticket_id: &str,
+    entries: Vec<TranscriptEntry>,
+    speed: f64,
+) -> Result<String, String> {
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(format!("replay speed must be positive, got {}", speed));
+    }
+    let replay_id = uuid::Uuid::new_v4().to_string();
+    let delays = delays(&entries, speed);
+    let (id, agent_id, ticket_id, replays) =
+        (replay_id.clone(), agent_id.to_string(), ticket_id.to_string(), store.clone());
+    // Held until the task is stored, so a replay that ends at once can't
+    // try to remove itself before it's there
+    let mut running = store.lock().unwrap();
+    let task = tauri::async_runtime::spawn(async move {
+        for (entry, delay) in entries.into_iter().zip(delays) {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            let node = CanvasNodePayload {
+                node_id: entry.node_id,
+                agent_id: agent_id.clone(),
+                ticket_id: ticket_id.clone(),
+                kind: entry.kind,
+                group_id: entry.group_id,
+            };
+            let _ = app.emit("replay-event", &ReplayEventPayload { replay_id: id.clone(), node });
+        }
+        replays.lock().unwrap().remove(&id);
+        let _ = app.emit("replay-finished", &ReplayFinishedPayload { replay_id: id, stopped: false });
+    });
+    running.insert(replay_id.clone(), task);
+    Ok(replay_id)
+}
+
+/// Stop a replay early. Returns false if it had already finished.
+pub fn stop(store: &ReplayStore, app: &AppHandle, replay_id: &str) -> bool {
+    let Some(task) = store.lock().unwrap().remove(replay_id) else {
+        return false;
+    };
+    task.abort();
+    let _ = app.emit(
+        "replay-finished",
+        &ReplayFinishedPayload {
+            replay_id: replay_id.to_string(),
+            stopped: true,
+        },
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::events::AgentEvent;
+
+    fn at(ms: i64) -> TranscriptEntry {
+        TranscriptEntry {
+            at: ms,
+            node_id: format!("n-{}", ms),
+            kind: AgentEvent::Text { text: String::new() },
+            group_id: None,
+        }
+    }
+
+    #[test]
+    fn delays_follow_recorded_gaps_scaled_and_capped() {
+        let entries = [at(10_000), at(10_400), at(11_400), at(200_000)];
+        assert_eq!(delays(&entries, 1.0), vec![0, 400, 1_000, MAX_GAP_MS]);
+        assert_eq!(delays(&entries, 4.0), vec![0, 100, 250, MAX_GAP_MS]);
+    }
+
+    #[test]
+    fn out_of_order_entries_play_back_to_back() {
+        assert_eq!(delays(&[at(5_000), at(4_000)], 1.0), vec![0, 0]);
+    }
+}
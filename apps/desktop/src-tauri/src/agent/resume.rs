@@ -0,0 +1,188 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::Path;
+use ts_rs::TS;
+
+use super::state::AgentState;
+use crate::git::drift;
+use crate::github::{ci, poller, token};
+
+/// What to put in a resume prompt besides the human's reply.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct ResumeInclude {
+    /// Reviews on the agent's PR that ask for changes or comment.
+    pub reviews: bool,
+    /// Checks failing on the PR's head commit.
+    pub failed_checks: bool,
+    /// Commits that landed on the default branch since the worktree's base.
+    pub new_commits_on_main: bool,
+    /// The human's reply, leading the prompt.
+    pub user_reply: Option<String>,
+}
+
+/// Everything gathered for a resume prompt.
+#[derive(Debug, Default)]
+pub struct ResumeContext {
+    pub reviews: Vec<poller::PrReview>,
+    pub failed_checks: Vec<ci::CheckStatus>,
+    /// Log tails of the failed checks, by check name.
+    pub check_logs: Vec<(String, String)>,
+    pub new_commits: Vec<String>,
+    pub user_reply: Option<String>,
+}
+
+/// Lay the context out as one prompt, most direct input first.
+pub fn compose(pr_number: Option<u32>, context: &ResumeContext) -> String {
+    let mut sections = Vec::new();
+    if let Some(reply) = context.user_reply.as_deref().filter(|r| !r.trim().is_empty()) {
+        sections.push(format!("## Reply from the human\n\n{}", reply.trim()));
+    }
+    let pr = pr_number.map(|n| format!(" on PR #{}", n)).unwrap_or_default();
+    let reviews: Vec<String> = context
+        .reviews
+        .iter()
+        .filter(|r| r.state != "APPROVED" && !r.body.trim().is_empty())
+        .map(|r| format!("- **{}** ({}): {}", r.author, r.state.to_lowercase(), r.body.trim()))
+        .collect();
+    if !reviews.is_empty() {
+        sections.push(format!("## Review feedback{}\n\n{}", pr, reviews.join("\n")));
+    }
+    if !context.failed_checks.is_empty() {
+        let checks: Vec<String> = context
+            .failed_checks
+            .iter()
+            .map(|c| format!("- {} ({})", c.name, c.url))
+            .collect();
+        let mut section = format!("## Failing checks{}\n\n{}", pr, checks.join("\n"));
+        if !context.check_logs.is_empty() {
+            section.push_str(
+                "\n\nFind the cause in the logs below, fix it, and push. If the failure is \
+                 unrelated to your change (infrastructure, a flaky test), say so instead of \
+                 changing code.",
+            );
+            for (name, log) in &context.check_logs {
+                section.push_str(&format!("\n\n### Log: {}\n\n```\n{}\n```", name, log));
+            }
+        }
+        sections.push(section);
+    }
+    if !context.new_commits.is_empty() {
+        sections.push(format!(
+            "## New commits on the default branch\n\nRebase onto them if they touch your change.\n\n{}",
+            context.new_commits.iter().map(|c| format!("- {}", c)).collect::<Vec<_>>().join("\n")
+        ));
+    }
+    if sections.is_empty() {
+        return "Continue where you left off.".to_string();
+    }
+    sections.push("Address the above, then continue where you left off.".to_string());
+    sections.join("\n\n")
+}
+
+/// One-line summaries of the default branch's commits the worktree lacks.
+fn new_commits_on_main(worktree: &Path) -> Result<Vec<String>> {
+    // Best effort: stale refs still say something
+    let _ = drift::git(worktree, &["fetch", "--quiet", "origin"]);
+    let main = drift::origin_default(worktree);
+    let log = drift::git(worktree, &["log", "--oneline", "--no-merges", "-n", "20", &format!("HEAD..{}", main)])?;
+    Ok(log.lines().map(String::from).collect())
+}
+
+/// Gather what `include` asks for about `agent`'s work. Sections that can't
+/// be fetched are left out. Blocking — call from spawn_blocking.
+pub fn gather(agent: &AgentState, include: ResumeInclude) -> ResumeContext {
+    let worktree = agent.worktree_path.as_deref().map(Path::new);
+    let repo = worktree.and_then(token::repo_slug_for);
+    let pr = repo.as_deref().zip(agent.pr_number);
+    let mut context = ResumeContext {
+        user_reply: include.user_reply,
+        ..Default::default()
+    };
+    if let (true, Some((repo, pr))) = (include.reviews, pr) {
+        // gather runs on a blocking thread, so it can wait on the async fetch
+        match tauri::async_runtime::block_on(poller::fetch_reviews(repo, pr)) {
+            Ok(reviews) => context.reviews = reviews,
+            Err(e) => log::warn!("[resume::gather] no reviews for PR #{}: {:#}", pr, e),
+        }
+    }
+    if let (true, Some((repo, pr))) = (include.failed_checks, pr) {
+        match ci::fetch_checks(repo, pr) {
+            Ok((_, checks)) => context.failed_checks = checks.into_iter().filter(|c| c.failed).collect(),
+            Err(e) => log::warn!("[resume::gather] no checks for PR #{}: {:#}", pr, e),
+        }
+    }
+    if let (true, Some(worktree)) = (include.new_commits_on_main, worktree) {
+        match new_commits_on_main(worktree) {
+            Ok(commits) => context.new_commits = commits,
+            Err(e) => log::warn!("[resume::gather] no default-branch log: {:#}", e),
+        }
+    }
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_sections_and_skips_approvals() {
+        let context = ResumeContext {
+            reviews: vec![
+                poller::PrReview {
+                    author: "dana".to_string(),
+                    body: "Please add a test.".to_string(),
+                    state: "CHANGES_REQUESTED".to_string(),
+                    submitted_at: String::new(),
+                },
+                poller::PrReview {
+                    author: "lee".to_string(),
+                    body: "LGTM".to_string(),
+                    state: "APPROVED".to_string(),
+                    submitted_at: String::new(),
+                },
+            ],
+            failed_checks: vec![ci::CheckStatus {
+                name: "test".to_string(),
+                url: "https://ci/1".to_string(),
+                run_id: None,
+                failed: true,
+            }],
+            check_logs: vec![],
+            new_commits: vec!["abc123 Bump deps".to_string()],
+            user_reply: Some("Use the v2 client.".to_string()),
+        };
+        let prompt = compose(Some(7), &context);
+        let order: Vec<usize> = ["Reply from the human", "Review feedback on PR #7", "Failing checks", "New commits"]
+            .iter()
+            .map(|h| prompt.find(h).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+        assert!(prompt.contains("- **dana** (changes_requested): Please add a test."));
+        assert!(!prompt.contains("LGTM"));
+    }
+
+    #[test]
+    fn failing_check_logs_come_with_triage_guidance() {
+        let context = ResumeContext {
+            failed_checks: vec![ci::CheckStatus {
+                name: "test".to_string(),
+                url: "https://ci/1".to_string(),
+                run_id: Some(1),
+                failed: true,
+            }],
+            check_logs: vec![("test".to_string(), "assertion failed".to_string())],
+            ..Default::default()
+        };
+        let prompt = compose(Some(7), &context);
+        assert!(prompt.starts_with("## Failing checks on PR #7\n\n- test (https://ci/1)"));
+        assert!(prompt.contains("say so instead of changing code"));
+        assert!(prompt.contains("### Log: test\n\n```\nassertion failed\n```"));
+    }
+
+    #[test]
+    fn empty_context_just_continues() {
+        assert_eq!(compose(None, &ResumeContext::default()), "Continue where you left off.");
+    }
+}
@@ -0,0 +1,260 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+use super::guard::glob_match;
+use super::policy::is_test_path;
+use crate::git;
+
+/// Marks the risk section in a PR body so a re-score replaces it.
+pub const PR_MARKER: &str = "<!-- poietai:risk -->";
+
+/// What makes a change risky in this project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Globs relative to the repo root, e.g. "src/auth/" or "**/payments/**".
+    pub critical_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct RiskReason {
+    /// "critical_path", "size", "deleted_tests" or "migration".
+    pub kind: String,
+    pub detail: String,
+    pub points: u32,
+}
+
+/// How closely reviewers should look at an agent's change, and where.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct RiskReport {
+    /// 0 (routine) to 100.
+    pub score: u32,
+    /// "low", "medium" or "high".
+    pub level: String,
+    pub reasons: Vec<RiskReason>,
+    pub files_changed: u32,
+    pub lines_changed: u32,
+}
+
+/// Payload for `diff-risk-scored`, emitted once a build finishes.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DiffRiskPayload {
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub report: RiskReport,
+}
+
+/// One file's share of a unified diff.
+#[derive(Debug, Default, PartialEq)]
+struct FileChange {
+    path: String,
+    deleted: bool,
+    added: u32,
+    removed: u32,
+}
+
+fn file_changes(diff: &str) -> Vec<FileChange> {
+    let mut files: Vec<FileChange> = Vec::new();
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git a/") {
+            let path = rest.split_once(" b/").map_or(rest, |(a, _)| a);
+            files.push(FileChange {
+                path: path.to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with("deleted file mode") {
+            file.deleted = true;
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            file.path = path.to_string();
+        } else if line.starts_with('+') && !line.starts_with("+++") {
+            file.added += 1;
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            file.removed += 1;
+        }
+    }
+    files
+}
+
+fn is_migration(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.split('/').any(|dir| matches!(dir, "migrations" | "migrate" | "db_migrations"))
+        || lower.ends_with(".sql")
+}
+
+fn listed(paths: &[&str]) -> String {
+    const SHOWN: usize = 5;
+    let mut text = paths.iter().take(SHOWN).copied().collect::<Vec<_>>().join(", ");
+    if paths.len() > SHOWN {
+        text.push_str(&format!(" and {} more", paths.len() - SHOWN));
+    }
+    text
+}
+
+/// Score a change from its diff and the new untracked files.
+pub fn score(config: &RiskConfig, diff: &str, untracked: &[String]) -> RiskReport {
+    let mut files = file_changes(diff);
+    files.extend(untracked.iter().map(|path| FileChange {
+        path: path.clone(),
+        ..Default::default()
+    }));
+    let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    let lines_changed: u32 = files.iter().map(|f| f.added + f.removed).sum();
+    let mut reasons = Vec::new();
+
+    let critical: Vec<&str> = paths
+        .iter()
+        .copied()
+        .filter(|p| config.critical_paths.iter().any(|c| glob_match(c, p)))
+        .collect();
+    if !critical.is_empty() {
+        reasons.push(RiskReason {
+            kind: "critical_path".to_string(),
+            detail: format!("Touches critical paths: {}", listed(&critical)),
+            points: (25 * critical.len() as u32).min(50),
+        });
+    }
+
+    let size_points = match lines_changed {
+        0..=50 => 0,
+        51..=200 => 5,
+        201..=500 => 15,
+        _ => 25,
+    } + if files.len() > 20 { 10 } else { 0 };
+    if size_points > 0 {
+        reasons.push(RiskReason {
+            kind: "size".to_string(),
+            detail: format!("{} lines changed across {} files", lines_changed, files.len()),
+            points: size_points,
+        });
+    }
+
+    let deleted_tests: Vec<&str> = files
+        .iter()
+        .filter(|f| f.deleted && is_test_path(&f.path))
+        .map(|f| f.path.as_str())
+        .collect();
+    // Test files that mostly lost lines: assertions removed rather than rewritten
+    let gutted_tests: Vec<&str> = files
+        .iter()
+        .filter(|f| !f.deleted && is_test_path(&f.path) && f.removed >= 10 && f.removed > f.added * 2)
+        .map(|f| f.path.as_str())
+        .collect();
+    if !deleted_tests.is_empty() || !gutted_tests.is_empty() {
+        let mut detail = Vec::new();
+        if !deleted_tests.is_empty() {
+            detail.push(format!("Deletes tests: {}", listed(&deleted_tests)));
+        }
+        if !gutted_tests.is_empty() {
+            detail.push(format!("Mostly removes lines from tests: {}", listed(&gutted_tests)));
+        }
+        reasons.push(RiskReason {
+            kind: "deleted_tests".to_string(),
+            detail: detail.join(". "),
+            points: (30 * deleted_tests.len() as u32 + 15 * gutted_tests.len() as u32).min(40),
+        });
+    }
+
+    let migrations: Vec<&str> = paths.iter().copied().filter(|p| is_migration(p)).collect();
+    if !migrations.is_empty() {
+        reasons.push(RiskReason {
+            kind: "migration".to_string(),
+            detail: format!("Touches migrations: {}", listed(&migrations)),
+            points: 20,
+        });
+    }
+
+    let score = reasons.iter().map(|r| r.points).sum::<u32>().min(100);
+    RiskReport {
+        score,
+        level: match score {
+            0..=24 => "low",
+            25..=59 => "medium",
+            _ => "high",
+        }
+        .to_string(),
+        reasons,
+        files_changed: files.len() as u32,
+        lines_changed,
+    }
+}
+
+/// Score everything the worktree changed since its base. Blocking.
+pub fn assess(config: &RiskConfig, worktree: &Path) -> Result<RiskReport> {
+    let (diff, untracked) = git::diff::worktree_changes(worktree)?;
+    Ok(score(config, &diff, &untracked))
+}
+
+/// The report as a PR body section, starting with [`PR_MARKER`].
+pub fn pr_section(report: &RiskReport) -> String {
+    let mut section = format!(
+        "{}\n### Change risk: {} ({}/100)\n\n",
+        PR_MARKER, report.level, report.score
+    );
+    if report.reasons.is_empty() {
+        section.push_str("Nothing stood out: a small change outside critical paths.\n");
+    }
+    for reason in &report.reasons {
+        section.push_str(&format!("- {} (+{})\n", reason.detail, reason.points));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/auth/session.rs b/src/auth/session.rs
+--- a/src/auth/session.rs
++++ b/src/auth/session.rs
+@@ -1,2 +1,3 @@
+ fn refresh() {
++    renew();
+ }
+diff --git a/tests/session_test.rs b/tests/session_test.rs
+deleted file mode 100644
+--- a/tests/session_test.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-#[test]
+-fn renews() {}
+";
+
+    #[test]
+    fn parses_files_and_deletions() {
+        let files = file_changes(DIFF);
+        assert_eq!(files.len(), 2);
+        assert_eq!((files[0].path.as_str(), files[0].added, files[0].removed), ("src/auth/session.rs", 1, 0));
+        assert!(files[1].deleted);
+        assert_eq!(files[1].path, "tests/session_test.rs");
+    }
+
+    #[test]
+    fn critical_paths_deleted_tests_and_migrations_add_up() {
+        let config = RiskConfig {
+            critical_paths: vec!["src/auth/".to_string()],
+        };
+        let report = score(&config, DIFF, &["db/migrations/0042_sessions.sql".to_string()]);
+        let kinds: Vec<(&str, u32)> = report.reasons.iter().map(|r| (r.kind.as_str(), r.points)).collect();
+        assert_eq!(kinds, vec![("critical_path", 25), ("deleted_tests", 30), ("migration", 20)]);
+        assert_eq!((report.score, report.level.as_str()), (75, "high"));
+        assert_eq!(report.files_changed, 3);
+    }
+
+    #[test]
+    fn small_routine_change_is_low_risk() {
+        let report = score(&RiskConfig::default(), DIFF.split("diff --git a/tests").next().unwrap(), &[]);
+        assert_eq!((report.score, report.level.as_str()), (0, "low"));
+        assert!(pr_section(&report).contains("Nothing stood out"));
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use ts_rs::TS;
+
+/// What a paused run is told when it gets its slot back.
+pub const RESUME_PROMPT: &str = "You were paused to make room for an urgent ticket. \
+    Check the worktree's state and pick up where you left off.";
+
+/// How soon a ticket should get a free agent slot.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    /// May pause a lower-priority run when preemption is on.
+    Urgent,
+}
+
+/// Cap on simultaneous ticket runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub max_concurrent: usize,
+    /// Pause the lowest-priority run, keeping its session, when an urgent
+    /// ticket finds every slot taken.
+    pub preempt: bool,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            max_concurrent: 3,
+            preempt: false,
+        }
+    }
+}
+
+/// A run holding, or waiting for, a slot.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct Slot {
+    /// The run holding or waiting for the slot; its lock and journal entry
+    /// share the id.
+    pub run_id: String,
+    pub ticket_id: String,
+    pub agent_id: String,
+    pub priority: Priority,
+}
+
+struct Waiting<T> {
+    slot: Slot,
+    seq: u64,
+    item: T,
+}
+
+/// Running slots and the runs queued behind them. `T` is whatever the
+/// caller needs to start a queued run.
+pub struct Scheduler<T> {
+    running: Vec<Slot>,
+    waiting: Vec<Waiting<T>>,
+    seq: u64,
+}
+
+pub type SharedScheduler<T> = Arc<Mutex<Scheduler<T>>>;
+
+impl<T> Default for Scheduler<T> {
+    fn default() -> Self {
+        Scheduler {
+            running: Vec::new(),
+            waiting: Vec::new(),
+            seq: 0,
+        }
+    }
+}
+
+impl<T> Scheduler<T> {
+    /// Take a slot if one is free.
+    pub fn try_start(&mut self, config: &SchedulerConfig, slot: Slot) -> Result<(), Slot> {
+        if self.running.len() >= config.max_concurrent.max(1) {
+            return Err(slot);
+        }
+        self.running.push(slot);
+        Ok(())
+    }
+
+    pub fn enqueue(&mut self, slot: Slot, item: T) {
+        self.seq += 1;
+        self.waiting.push(Waiting { slot, seq: self.seq, item });
+    }
+
+    /// The run an urgent ticket may pause: the lowest-priority one below
+    /// `priority`, most recently started first.
+    pub fn preemption_candidate(&self, config: &SchedulerConfig, priority: Priority) -> Option<Slot> {
+        if !config.preempt || priority != Priority::Urgent {
+            return None;
+        }
+        self.running
+            .iter()
+            .rev()
+            .filter(|s| s.priority < priority)
+            .min_by_key(|s| s.priority)
+            .cloned()
+    }
+
+    /// Hand the slot of the run `victim` to `slot`.
+    pub fn replace(&mut self, victim: &str, slot: Slot) {
+        self.release(victim);
+        self.running.push(slot);
+    }
+
+    /// Free the slot held by the run `run_id`, if any.
+    pub fn release(&mut self, run_id: &str) {
+        self.running.retain(|s| s.run_id != run_id);
+    }
+
+    /// Free every slot held on `ticket_id`, for a takeover that stops the
+    /// run holding it.
+    pub fn release_ticket(&mut self, ticket_id: &str) {
+        self.running.retain(|s| s.ticket_id != ticket_id);
+    }
+
+    /// The highest-priority queued run, oldest first among equals, once a
+    /// slot is free. It takes the slot.
+    pub fn next(&mut self, config: &SchedulerConfig) -> Option<(Slot, T)> {
+        if self.running.len() >= config.max_concurrent.max(1) {
+            return None;
+        }
+        let index = self
+            .waiting
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, w)| (w.slot.priority, std::cmp::Reverse(w.seq)))
+            .map(|(i, _)| i)?;
+        let Waiting { slot, item, .. } = self.waiting.remove(index);
+        self.running.push(slot.clone());
+        Some((slot, item))
+    }
+
+    /// Queued runs in the order they'll start.
+    pub fn queued(&self) -> Vec<Slot> {
+        let mut waiting: Vec<&Waiting<T>> = self.waiting.iter().collect();
+        waiting.sort_by_key(|w| (std::cmp::Reverse(w.slot.priority), w.seq));
+        waiting.into_iter().map(|w| w.slot.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(ticket_id: &str, priority: Priority) -> Slot {
+        Slot {
+            run_id: format!("run-{}", ticket_id),
+            ticket_id: ticket_id.to_string(),
+            agent_id: format!("agent-{}", ticket_id),
+            priority,
+        }
+    }
+
+    #[test]
+    fn freed_slots_go_to_the_highest_priority_first() {
+        let config = SchedulerConfig { max_concurrent: 1, preempt: false };
+        let mut scheduler = Scheduler::default();
+        scheduler.try_start(&config, slot("t-1", Priority::Normal)).unwrap();
+        for (ticket, priority) in [("t-2", Priority::Low), ("t-3", Priority::High), ("t-4", Priority::High)] {
+            let waiting = scheduler.try_start(&config, slot(ticket, priority)).unwrap_err();
+            scheduler.enqueue(waiting, ticket);
+        }
+        assert!(scheduler.next(&config).is_none());
+        scheduler.release("run-t-1");
+        assert_eq!(scheduler.next(&config).map(|(_, t)| t), Some("t-3"));
+        let order: Vec<String> = scheduler.queued().into_iter().map(|s| s.ticket_id).collect();
+        assert_eq!(order, ["t-4", "t-2"]);
+    }
+
+    #[test]
+    fn only_urgent_tickets_preempt_and_only_when_enabled() {
+        let mut config = SchedulerConfig { max_concurrent: 2, preempt: false };
+        let mut scheduler: Scheduler<()> = Scheduler::default();
+        scheduler.try_start(&config, slot("t-1", Priority::Low)).unwrap();
+        scheduler.try_start(&config, slot("t-2", Priority::Normal)).unwrap();
+        assert_eq!(scheduler.preemption_candidate(&config, Priority::Urgent), None);
+        config.preempt = true;
+        assert_eq!(scheduler.preemption_candidate(&config, Priority::High), None);
+        let victim = scheduler.preemption_candidate(&config, Priority::Urgent).unwrap();
+        assert_eq!(victim.ticket_id, "t-1");
+        scheduler.replace(&victim.run_id, slot("t-3", Priority::Urgent));
+        assert!(scheduler.try_start(&config, slot("t-4", Priority::Low)).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::events::AgentEvent;
+use super::transcript::TranscriptEntry;
+use crate::project::store;
+use crate::text::truncate_bytes;
+
+/// Tool output beyond this is left out of the index — it's mostly file
+/// contents the agent read, which the codebase itself is better for.
+const MAX_INDEXED_RESULT: usize = 4 * 1024;
+
+/// Narrow a transcript search. Empty/None fields don't filter.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SearchFilters {
+    pub agent_id: Option<String>,
+    pub ticket_id: Option<String>,
+    /// Event kinds to include: "text", "thinking", "tool_use", "tool_result", "result".
+    pub kinds: Vec<String>,
+    /// Unix milliseconds, inclusive.
+    #[ts(type = "number | null")]
+    pub since: Option<i64>,
+    #[ts(type = "number | null")]
+    pub until: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+/// One matching event, best matches first.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SearchHit {
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub node_id: String,
+    #[ts(type = "number")]
+    pub at: i64,
+    pub kind: String,
+    /// Matching excerpt with hits wrapped in `[` `]`.
+    pub snippet: String,
+}
+
+pub fn index_path() -> PathBuf {
+    store::app_dir().join("transcripts").join("index.db")
+}
+
+fn open(path: &Path) -> Result<Connection> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let conn =
+        Connection::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS events USING fts5(
+             agent_id UNINDEXED, ticket_id UNINDEXED, node_id UNINDEXED,
+             at UNINDEXED, kind UNINDEXED, body
+         );
+         CREATE TABLE IF NOT EXISTS indexed_files (
+             path TEXT PRIMARY KEY, lines INTEGER NOT NULL
+         );",
+    )
+    .context("failed to initialise transcript index")?;
+    Ok(conn)
+}
+
+/// The searchable text of an event, and its kind tag.
+fn searchable(kind: &AgentEvent) -> (&'static str, String) {
+    match kind {
+        AgentEvent::Thinking { thinking } => ("thinking", thinking.clone()),
+        AgentEvent::Text { text } => ("text", text.clone()),
+        AgentEvent::ToolUse {
+            tool_name,
+            tool_input,
+            ..
+        } => ("tool_use", format!("{} {}", tool_name, tool_input)),
+        AgentEvent::ToolResult { content, .. } => {
+            let mut text = match content {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let cut = truncate_bytes(&text, MAX_INDEXED_RESULT).len();
+            text.truncate(cut);
+            ("tool_result", text)
+        }
+        AgentEvent::Result { result, .. } => ("result", result.clone().unwrap_or_default()),
+    }
+}
+
+/// Index transcript lines added since the last sync. Transcripts are
+/// append-only, so each file only needs reading from where it was left.
+fn sync(conn: &mut Connection, transcripts_dir: &Path) -> Result<()> {
+    let Ok(tickets) = std::fs::read_dir(transcripts_dir) else {
+        return Ok(());
+    };
+    let tx = conn.transaction()?;
+    for ticket_dir in tickets.flatten().filter(|e| e.path().is_dir()) {
+        let ticket_id = ticket_dir.file_name().to_string_lossy().to_string();
+        for file in std::fs::read_dir(ticket_dir.path())?.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+                continue;
+            }
+            let agent_id = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let key = path.to_string_lossy().to_string();
+            let done: usize = tx
+                .query_row(
+                    "SELECT lines FROM indexed_files WHERE path = ?1",
+                    params![key],
+                    |row| row.get::<_, i64>(0),
+                )
+                .map(|n| n as usize)
+                .unwrap_or(0);
+
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            // Only complete lines — a run may be mid-write
+            let lines: Vec<&str> = contents
+                .split_inclusive('\n')
+                .filter(|l| l.ends_with('\n'))
+                .collect();
+            for line in lines.iter().skip(done) {
+                let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) else {
+                    continue;
+                };
+                let (kind, body) = searchable(&entry.kind);
+                tx.execute(
+                    "INSERT INTO events (agent_id, ticket_id, node_id, at, kind, body)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![agent_id, ticket_id, entry.node_id, entry.at, kind, body],
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO indexed_files (path, lines) VALUES (?1, ?2)
+                 ON CONFLICT(path) DO UPDATE SET lines = excluded.lines",
+                params![key, lines.len() as i64],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Turn free text into an FTS5 query that matches all words, so punctuation
+/// in user input can't produce a syntax error.
+pub fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn run_query(conn: &Connection, query: &str, filters: &SearchFilters) -> Result<Vec<SearchHit>> {
+    let mut sql = String::from(
+        "SELECT agent_id, ticket_id, node_id, at, kind,
+                snippet(events, 5, '[', ']', '…', 12)
+         FROM events WHERE events MATCH ?1",
+    );
+    let mut args: Vec<rusqlite::types::Value> = vec![fts_query(query).into()];
+    let mut bind = |clause: &str, value: rusqlite::types::Value, sql: &mut String| {
+        args.push(value);
+        sql.push_str(&format!(" AND {} ?{}", clause, args.len()));
+    };
+    if let Some(ref agent_id) = filters.agent_id {
+        bind("agent_id =", agent_id.clone().into(), &mut sql);
+    }
+    if let Some(ref ticket_id) = filters.ticket_id {
+        bind("ticket_id =", ticket_id.clone().into(), &mut sql);
+    }
+    if let Some(since) = filters.since {
+        bind("at >=", since.into(), &mut sql);
+    }
+    if let Some(until) = filters.until {
+        bind("at <=", until.into(), &mut sql);
+    }
+    if !filters.kinds.is_empty() {
+        let placeholders: Vec<String> = filters
+            .kinds
+            .iter()
+            .map(|k| {
+                args.push(k.clone().into());
+                format!("?{}", args.len())
+            })
+            .collect();
+        sql.push_str(&format!(" AND kind IN ({})", placeholders.join(", ")));
+    }
+    sql.push_str(&format!(
+        " ORDER BY rank LIMIT {}",
+        filters.limit.unwrap_or(50).min(500)
+    ));
+
+    let mut stmt = conn.prepare(&sql).context("failed to prepare search")?;
+    let hits = stmt
+        .query_map(rusqlite::params_from_iter(args), |row| {
+            Ok(SearchHit {
+                agent_id: row.get(0)?,
+                ticket_id: row.get(1)?,
+                node_id: row.get(2)?,
+                at: row.get(3)?,
+                kind: row.get(4)?,
+                snippet: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("search failed")?;
+    Ok(hits)
+}
+
+fn search_in(
+    index: &Path,
+    transcripts_dir: &Path,
+    query: &str,
+    filters: &SearchFilters,
+) -> Result<Vec<SearchHit>> {
+    if query.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let mut conn = open(index)?;
+    sync(&mut conn, transcripts_dir).context("failed to update transcript index")?;
+    run_query(&conn, query, filters)
+}
+
+/// Full-text search across every persisted transcript. Blocking — call from
+/// spawn_blocking.
+pub fn search(query: &str, filters: &SearchFilters) -> Result<Vec<SearchHit>> {
+    let index = index_path();
+    let transcripts_dir = index.parent().map(Path::to_path_buf).unwrap_or_default();
+    search_in(&index, &transcripts_dir, query, filters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_transcript(dir: &Path, ticket: &str, agent: &str, events: &[AgentEvent]) {
+        let path = dir.join(ticket).join(format!("{}.jsonl", agent));
+        for (i, kind) in events.iter().enumerate() {
+            let entry = TranscriptEntry {
+                at: i as i64,
+                node_id: format!("{}-{}", agent, i),
+                kind: kind.clone(),
+                group_id: None,
+            };
+            store::append_jsonl(&path, &entry).unwrap();
+        }
+    }
+
+    #[test]
+    fn fts_query_quotes_terms() {
+        assert_eq!(
+            fts_query("retry  \"logic\" -x"),
+            "\"retry\" \"\"\"logic\"\"\" \"-x\""
+        );
+    }
+
+    #[test]
+    fn finds_events_and_picks_up_new_lines() {
+        let dir = std::env::temp_dir().join(format!("poietai-search-{}", uuid::Uuid::new_v4()));
+        let index = dir.join("index.db");
+        write_transcript(
+            &dir,
+            "t-1",
+            "alice",
+            &[
+                AgentEvent::Text {
+                    text: "Updating the billing retry logic".to_string(),
+                },
+                AgentEvent::Thinking {
+                    thinking: "unrelated".to_string(),
+                },
+            ],
+        );
+
+        let hits = search_in(&index, &dir, "billing retry", &SearchFilters::default()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].agent_id, "alice");
+        assert_eq!(hits[0].ticket_id, "t-1");
+        assert!(hits[0].snippet.contains("[billing]"));
+
+        write_transcript(
+            &dir,
+            "t-2",
+            "bob",
+            &[AgentEvent::Text {
+                text: "billing page styles".to_string(),
+            }],
+        );
+        let filters = SearchFilters {
+            ticket_id: Some("t-2".to_string()),
+            ..Default::default()
+        };
+        let hits = search_in(&index, &dir, "billing", &filters).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].agent_id, "bob");
+
+        // Re-syncing doesn't duplicate already indexed lines
+        let all = search_in(&index, &dir, "billing", &SearchFilters::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+
+use super::backend;
+use super::costs;
+use super::keys;
+use crate::platform::exec;
+
+/// Ask an agent's session a side question without disturbing it: the session
+/// is forked, so the agent's own history never sees the question. Returns the
+/// final reply text. `model` overrides the session's model when set. The
+/// spend is charged to `agent_id` and `ticket_id`.
+pub async fn ask_forked(
+    agent_id: &str,
+    ticket_id: &str,
+    session_id: &str,
+    working_dir: &Path,
+    model: Option<&str>,
+    prompt: &str,
+    timeout: Duration,
+) -> Result<String> {
+    if cfg!(target_os = "windows") {
+        anyhow::bail!("forked session queries are not supported on Windows yet");
+    }
+
+    let mut cmd = Command::new("claude");
+    cmd.args(["--print", "--output-format", "json", "--max-turns", "1"])
+        .args(["--resume", session_id, "--fork-session"]);
+    if let Some(model) = model {
+        cmd.args(["--model", model]);
+    }
+    cmd.arg(prompt)
+        .current_dir(working_dir)
+        .stdin(std::process::Stdio::null());
+    run(
+        cmd,
+        agent_id,
+        ticket_id,
+        working_dir,
+        timeout,
+        "session query",
+    )
+    .await
+}
+
+/// Tools for analysis runs: enough to read the repo, nothing that can change it.
+pub const READ_ONLY_TOOLS: &[&str] = &[
+    "Read",
+    "Grep",
+    "Glob",
+    "Bash(git log:*)",
+    "Bash(git show:*)",
+    "Bash(git diff:*)",
+];
+
+/// Run a fresh, bounded claude session in `working_dir` restricted to
+/// [`READ_ONLY_TOOLS`] and return its final reply text. The spend is charged
+/// to `agent_id` and `ticket_id`; either may be empty for runs with none.
+pub async fn ask_read_only(
+    agent_id: &str,
+    ticket_id: &str,
+    working_dir: &Path,
+    system_prompt: &str,
+    prompt: &str,
+    max_turns: u32,
+    timeout: Duration,
+) -> Result<String> {
+    if cfg!(target_os = "windows") {
+        anyhow::bail!("read-only analysis runs are not supported on Windows yet");
+    }
+
+    let mut cmd = Command::new("claude");
+    cmd.args(["--print", "--output-format", "json", "--max-turns"])
+        .arg(max_turns.to_string())
+        .arg("--allowedTools")
+        .arg(READ_ONLY_TOOLS.join(","))
+        .arg("--append-system-prompt")
+        .arg(system_prompt)
+        .arg(prompt)
+        .current_dir(working_dir)
+        .stdin(std::process::Stdio::null());
+    run(
+        cmd,
+        agent_id,
+        ticket_id,
+        working_dir,
+        timeout,
+        "analysis run",
+    )
+    .await
+}
+
+/// Run `cmd` through the project's gateway on the next vault key, as agent
+/// runs are, and return its final reply text.
+async fn run(
+    mut cmd: Command,
+    agent_id: &str,
+    ticket_id: &str,
+    working_dir: &Path,
+    timeout: Duration,
+    what: &str,
+) -> Result<String> {
+    if let Some(gateway) = backend::project_settings_for(working_dir).gateway {
+        cmd.envs(gateway.env());
+    }
+    // The key and cost bookkeeping read and write files, so stay off the runtime
+    let (agent_id, ticket_id) = (agent_id.to_string(), ticket_id.to_string());
+    let (agent, ticket) = (agent_id.clone(), ticket_id.clone());
+    let key = tokio::task::spawn_blocking(move || keys::rotate(&agent, &ticket))
+        .await
+        .context("key rotation task panicked")?;
+    if let Some(ref key) = key {
+        cmd.envs(key.env());
+    }
+    let output = exec::output(&mut cmd, timeout)
+        .await
+        .with_context(|| format!("{} failed", what))?;
+    let key_id = key.map(|k| k.id);
+    tokio::task::spawn_blocking(move || reply(&output, &agent_id, &ticket_id, key_id.as_deref()))
+        .await
+        .context("reply task panicked")?
+}
+
+/// The final reply text of a `--output-format json` run, recording what the
+/// run cost in the ledger and against `key_id`.
+fn reply(
+    output: &std::process::Output,
+    agent_id: &str,
+    ticket_id: &str,
+    key_id: Option<&str>,
+) -> Result<String> {
+    let result: Option<serde_json::Value> = serde_json::from_slice(&output.stdout).ok();
+    // A failed run is still billed
+    if let Some(cost_usd) = result.as_ref().and_then(|r| r["total_cost_usd"].as_f64()) {
+        if let Some(key_id) = key_id {
+            keys::record_cost(key_id, cost_usd);
+        }
+        costs::record(costs::CostEntry {
+            at: chrono::Utc::now().timestamp(),
+            agent_id: agent_id.to_string(),
+            ticket_id: ticket_id.to_string(),
+            cost_usd,
+        });
+    }
+    if !output.status.success() {
+        anyhow::bail!("claude exited with status {}", output.status);
+    }
+    let result = result.context("failed to parse claude output")?;
+    Ok(result["result"].as_str().unwrap_or_default().to_string())
+}
+
+/// The outermost `{...}` in a reply, for prompts that ask for a JSON answer.
+pub fn json_object(text: &str) -> Option<&str> {
+    match (text.find('{'), text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => Some(&text[start..=end]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_json_object_from_chatter() {
+        assert_eq!(json_object("Sure! {\"a\": {\"b\": 1}} done"), Some("{\"a\": {\"b\": 1}}"));
+        assert_eq!(json_object("no json } here {"), None);
+    }
+}
@@ -0,0 +1,227 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDateTime, NaiveTime};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::session;
+use super::state::{self, AgentState};
+use crate::project::{settings, store};
+
+/// When to run check-ins and which model to spend on them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct StandupConfig {
+    /// Local times of day, "HH:MM".
+    pub times: Vec<String>,
+    /// A cheap model — check-ins only summarise what's already in the session.
+    pub model: String,
+}
+
+impl Default for StandupConfig {
+    fn default() -> Self {
+        StandupConfig {
+            times: vec!["09:00".to_string()],
+            model: "haiku".to_string(),
+        }
+    }
+}
+
+/// One agent's check-in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct StandupEntry {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub ticket_id: Option<String>,
+    pub summary: String,
+    pub blockers: Vec<String>,
+    pub next_step: String,
+    /// Set when the check-in itself failed.
+    pub error: Option<String>,
+}
+
+/// Payload for `standup-report`: every active agent's check-in in one place.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StandupReport {
+    /// RFC 3339 local time the report was generated.
+    pub generated_at: String,
+    pub entries: Vec<StandupEntry>,
+}
+
+const CHECK_IN_PROMPT: &str = "Standup check-in. Do not use any tools or continue the work. \
+    Reply with a single line of JSON and nothing else:\n\
+    {\"summary\": \"<where the ticket stands, one or two sentences>\", \
+    \"blockers\": [\"<anything stopping you>\"], \
+    \"next_step\": \"<what you will do next>\"}";
+
+/// Where the latest report is kept: `$HOME/.poietai/standup.json`.
+pub fn report_path() -> PathBuf {
+    store::app_dir().join("standup.json")
+}
+
+fn parse_slots(times: &[String]) -> Vec<NaiveTime> {
+    times
+        .iter()
+        .filter_map(|t| match NaiveTime::parse_from_str(t.trim(), "%H:%M") {
+            Ok(time) => Some(time),
+            Err(_) => {
+                warn!("[standup::parse_slots] ignoring invalid time {:?}", t);
+                None
+            }
+        })
+        .collect()
+}
+
+/// True if any configured slot falls in `(from, to]`. Checking a window rather
+/// than an exact minute means a slot isn't missed when the machine sleeps
+/// through it or a tick runs late.
+pub fn slot_due(times: &[String], from: NaiveDateTime, to: NaiveDateTime) -> bool {
+    let slots = parse_slots(times);
+    let mut day = from.date();
+    while day <= to.date() {
+        if slots.iter().any(|slot| {
+            let at = day.and_time(*slot);
+            at > from && at <= to
+        }) {
+            return true;
+        }
+        day = match day.succ_opt() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+    false
+}
+
+/// Agents that have a ticket in flight.
+async fn active_agents(agents: &state::StateStore) -> Vec<AgentState> {
+    state::all_agents(agents).await
+        .into_iter()
+        .filter(|a| a.current_ticket_id.is_some())
+        .collect()
+}
+
+/// Ask one agent for its check-in by forking its session with a cheap model,
+/// so the agent's own session is left untouched.
+async fn check_in(agent: &AgentState, model: &str) -> Result<StandupEntry> {
+    let mut entry = StandupEntry {
+        agent_id: agent.id.clone(),
+        agent_name: agent.name.clone(),
+        ticket_id: agent.current_ticket_id.clone(),
+        ..Default::default()
+    };
+    let (Some(session_id), Some(worktree)) = (&agent.session_id, &agent.worktree_path) else {
+        entry.summary = "Not started yet.".to_string();
+        return Ok(entry);
+    };
+    let text = session::ask_forked(
+        &agent.id,
+        agent.current_ticket_id.as_deref().unwrap_or_default(),
+        session_id,
+        Path::new(worktree),
+        Some(model),
+        CHECK_IN_PROMPT,
+        Duration::from_secs(120),
+    )
+    .await?;
+    let json = session::json_object(&text)
+        .with_context(|| format!("check-in reply had no JSON: {}", text))?;
+    let reply: StandupEntry =
+        serde_json::from_str(json).context("failed to parse check-in reply")?;
+    entry.summary = reply.summary;
+    entry.blockers = reply.blockers;
+    entry.next_step = reply.next_step;
+    Ok(entry)
+}
+
+/// Check in with every active agent, save the report, and emit `standup-report`.
+pub async fn run(app: &AppHandle, config: &StandupConfig) -> StandupReport {
+    let agents = active_agents(&app.state::<crate::AppState>().agents).await;
+    info!("[standup::run] checking in with {} agents", agents.len());
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for (i, agent) in agents.into_iter().enumerate() {
+        let model = config.model.clone();
+        tasks.spawn(async move {
+            let entry = match check_in(&agent, &model).await {
+                Ok(entry) => entry,
+                Err(e) => StandupEntry {
+                    agent_id: agent.id.clone(),
+                    agent_name: agent.name.clone(),
+                    ticket_id: agent.current_ticket_id.clone(),
+                    error: Some(format!("{:#}", e)),
+                    ..Default::default()
+                },
+            };
+            (i, entry)
+        });
+    }
+    let mut entries = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(entry) = joined {
+            entries.push(entry);
+        }
+    }
+    // Keep roster order regardless of which check-in finished first
+    entries.sort_by_key(|(i, _)| *i);
+
+    let report = StandupReport {
+        generated_at: Local::now().to_rfc3339(),
+        entries: entries.into_iter().map(|(_, e)| e).collect(),
+    };
+    if let Err(e) = store::write_json(&report_path(), &report) {
+        warn!("[standup::run] failed to save report: {:#}", e);
+    }
+    let _ = app.emit("standup-report", &report);
+    report
+}
+
+/// Background loop: run a standup whenever a configured time passes.
+/// Settings are re-read each tick so edits apply without a restart.
+pub async fn schedule(app: AppHandle) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(30));
+    let mut last = Local::now().naive_local();
+    loop {
+        ticker.tick().await;
+        let now = Local::now().naive_local();
+        let config = settings::load_app().ok().and_then(|s| s.standup);
+        if let Some(config) = config {
+            if slot_due(&config.times, last, now) {
+                run(&app, &config).await;
+            }
+        }
+        last = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, 2).unwrap().and_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn slot_inside_window_is_due() {
+        let times = vec!["09:00".to_string()];
+        assert!(slot_due(&times, at(8, 59), at(9, 0)));
+        assert!(!slot_due(&times, at(9, 0), at(9, 1)));
+        assert!(!slot_due(&times, at(8, 0), at(8, 30)));
+    }
+
+    #[test]
+    fn slot_across_midnight_after_sleep() {
+        let times = vec!["07:30".to_string(), "bogus".to_string()];
+        let next_morning = at(8, 0) + chrono::Duration::days(1);
+        assert!(slot_due(&times, at(23, 0), next_morning));
+    }
+}
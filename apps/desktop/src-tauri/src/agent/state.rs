@@ -1,9 +1,14 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+use ts_rs::TS;
+
+use crate::text::ellipsize;
 
 /// The statuses an agent can be in.
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
 #[serde(rename_all = "snake_case")]
 pub enum AgentStatus {
     Idle,
@@ -14,7 +19,8 @@ pub enum AgentStatus {
 }
 
 /// Everything we know about a running (or idle) agent.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub struct AgentState {
     pub id: String,
     pub name: String,
@@ -35,70 +41,124 @@ pub struct AgentState {
     pub chatting: bool,
     /// Per-agent initiative override: "auto", "ask", "suggest", or "off".
     pub initiative: Option<String>,
+    /// One-line "currently doing X" the agent set through `set_status_note`.
+    /// Cleared when it goes idle.
+    pub status_note: Option<String>,
+}
+
+/// A mutation of the store, broadcast to subscribers so events can be
+/// derived from state changes rather than sprinkled over every call site.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentChange {
+    Updated(String),
+    Removed(String),
+}
+
+impl AgentChange {
+    pub fn agent_id(&self) -> &str {
+        match self {
+            AgentChange::Updated(id) | AgentChange::Removed(id) => id,
+        }
+    }
+}
+
+/// Payload for `agent-state-changed`. `agent` is None once removed.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AgentStateChangedPayload {
+    pub agent_id: String,
+    pub agent: Option<AgentState>,
 }
 
+/// Changes buffered per subscriber before it starts missing them.
+const CHANGE_BUFFER: usize = 256;
+
+/// Longest status note kept; the roster shows it on one line.
+const MAX_NOTE_CHARS: usize = 120;
+
 /// The shared state store.
 ///
 /// Arc = "Atomically Reference Counted" — a smart pointer you can clone cheaply
 /// and share across threads. The data is freed when the last clone is dropped.
 ///
-/// Mutex = mutual exclusion lock. In Rust, the data lives *inside* the Mutex,
-/// not outside it. You can't forget to lock before accessing.
-pub type StateStore = Arc<Mutex<HashMap<String, AgentState>>>;
+/// RwLock = many readers or one writer at a time. tokio's lock is awaited
+/// rather than blocking the thread, so async commands reading the roster
+/// don't stall the executor while a writer holds it.
+#[derive(Clone)]
+pub struct StateStore {
+    agents: Arc<RwLock<HashMap<String, AgentState>>>,
+    changes: broadcast::Sender<AgentChange>,
+}
+
+impl StateStore {
+    /// Receive every change made after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<AgentChange> {
+        self.changes.subscribe()
+    }
+
+    fn notify(&self, change: AgentChange) {
+        // No subscribers is fine
+        let _ = self.changes.send(change);
+    }
+}
 
 /// Create a new empty state store.
 pub fn new_store() -> StateStore {
-    Arc::new(Mutex::new(HashMap::new()))
+    StateStore {
+        agents: Arc::new(RwLock::new(HashMap::new())),
+        changes: broadcast::channel(CHANGE_BUFFER).0,
+    }
 }
 
 /// Insert or update an agent in the store.
-pub fn upsert_agent(store: &StateStore, agent: AgentState) {
-    let mut map = store.lock().unwrap();
-    map.insert(agent.id.clone(), agent);
+pub async fn upsert_agent(store: &StateStore, agent: AgentState) {
+    let id = agent.id.clone();
+    store.agents.write().await.insert(id.clone(), agent);
+    store.notify(AgentChange::Updated(id));
+}
+
+/// Mutate an agent under a single lock acquisition, so concurrent commands
+/// can't clobber each other between a read and the write-back.
+/// Returns the closure's result, or None if the agent ID is not found.
+pub async fn update_agent<R>(store: &StateStore, id: &str, f: impl FnOnce(&mut AgentState) -> R) -> Option<R> {
+    let result = store.agents.write().await.get_mut(id).map(f);
+    if result.is_some() {
+        store.notify(AgentChange::Updated(id.to_string()));
+    }
+    result
 }
 
 /// Get a snapshot of an agent's state.
-pub fn get_agent(store: &StateStore, id: &str) -> Option<AgentState> {
-    let map = store.lock().unwrap();
-    map.get(id).cloned()
+pub async fn get_agent(store: &StateStore, id: &str) -> Option<AgentState> {
+    store.agents.read().await.get(id).cloned()
 }
 
 /// Get all agents as a Vec (for sending to the frontend).
-pub fn all_agents(store: &StateStore) -> Vec<AgentState> {
-    let map = store.lock().unwrap();
-    map.values().cloned().collect()
+pub async fn all_agents(store: &StateStore) -> Vec<AgentState> {
+    store.agents.read().await.values().cloned().collect()
 }
 
 /// Persist the Claude Code session ID on an agent after a successful run.
 /// No-op if the agent ID is not found.
-pub fn save_session_id(store: &StateStore, id: &str, session_id: &str) {
-    let mut map = store.lock().unwrap();
-    if let Some(agent) = map.get_mut(id) {
-        agent.session_id = Some(session_id.to_string());
-    }
+pub async fn save_session_id(store: &StateStore, id: &str, session_id: &str) {
+    update_agent(store, id, |agent| agent.session_id = Some(session_id.to_string())).await;
 }
 
 /// Persist the chat session ID on an agent after a chat run.
 /// No-op if the agent ID is not found.
-pub fn save_chat_session_id(store: &StateStore, id: &str, session_id: &str) {
-    let mut map = store.lock().unwrap();
-    if let Some(agent) = map.get_mut(id) {
-        agent.chat_session_id = Some(session_id.to_string());
-    }
+pub async fn save_chat_session_id(store: &StateStore, id: &str, session_id: &str) {
+    update_agent(store, id, |agent| agent.chat_session_id = Some(session_id.to_string())).await;
 }
 
 /// Set the chatting flag on an agent.
 /// No-op if the agent ID is not found.
-pub fn set_chatting(store: &StateStore, id: &str, chatting: bool) {
-    let mut map = store.lock().unwrap();
-    if let Some(agent) = map.get_mut(id) {
-        agent.chatting = chatting;
-    }
+pub async fn set_chatting(store: &StateStore, id: &str, chatting: bool) {
+    update_agent(store, id, |agent| agent.chatting = chatting).await;
 }
 
 /// Update name, role, and personality of an existing agent.
 /// Returns true if the agent was found, false otherwise.
-pub fn update_agent_fields(
+pub async fn update_agent_fields(
     store: &StateStore,
     id: &str,
     name: Option<String>,
@@ -106,8 +166,7 @@ pub fn update_agent_fields(
     personality: Option<String>,
     initiative: Option<String>,
 ) -> bool {
-    let mut map = store.lock().unwrap();
-    if let Some(agent) = map.get_mut(id) {
+    update_agent(store, id, |agent| {
         if let Some(n) = name {
             agent.name = n;
         }
@@ -119,31 +178,110 @@ pub fn update_agent_fields(
         }
         // initiative can be set to None (clear override) or Some(value)
         agent.initiative = initiative;
-        true
-    } else {
-        false
-    }
+    })
+    .await
+    .is_some()
 }
 
 /// Remove an agent from the store.
 /// Returns true if the agent was found and removed, false otherwise.
-pub fn remove_agent(store: &StateStore, id: &str) -> bool {
-    let mut map = store.lock().unwrap();
-    map.remove(id).is_some()
+pub async fn remove_agent(store: &StateStore, id: &str) -> bool {
+    let removed = store.agents.write().await.remove(id).is_some();
+    if removed {
+        store.notify(AgentChange::Removed(id.to_string()));
+    }
+    removed
 }
 
 /// Update just the status of an agent.
 /// Returns true if the agent was found and updated, false if the ID was not in the store.
-pub fn set_status(store: &StateStore, id: &str, status: AgentStatus) -> bool {
-    let mut map = store.lock().unwrap();
-    if let Some(agent) = map.get_mut(id) {
+pub async fn set_status(store: &StateStore, id: &str, status: AgentStatus) -> bool {
+    update_agent(store, id, |agent| {
+        // The note described the run that just ended
+        if status == AgentStatus::Idle {
+            agent.status_note = None;
+        }
         agent.status = status;
-        true
-    } else {
-        false
+    })
+    .await
+    .is_some()
+}
+
+/// Set the agent's status note to the first line of `note`, cut to fit; a
+/// blank note clears it. Returns false if the agent ID is not found.
+pub async fn set_status_note(store: &StateStore, id: &str, note: &str) -> bool {
+    let line = note.lines().next().unwrap_or_default().trim();
+    let note = (!line.is_empty()).then(|| ellipsize(line, MAX_NOTE_CHARS));
+    update_agent(store, id, |agent| agent.status_note = note).await.is_some()
+}
+
+/// Emit `agent-state-changed` with a fresh snapshot after every mutation,
+/// so the frontend roster follows the store. Runs for the app's lifetime.
+pub async fn emit_changes(store: StateStore, app: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut changes = store.subscribe();
+    loop {
+        let change = match changes.recv().await {
+            Ok(change) => change,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                log::warn!("[state::emit_changes] missed {} agent changes", missed);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let payload = AgentStateChangedPayload {
+            agent_id: change.agent_id().to_string(),
+            agent: get_agent(&store, change.agent_id()).await,
+        };
+        let _ = app.emit("agent-state-changed", &payload);
     }
 }
 
+/// Move `from`'s ticket, worktree and PR onto `to` in one step, so no reader
+/// sees the ticket owned by both or neither. Both sessions are cleared: the
+/// old session belongs to the other agent's persona, and `to` starts fresh
+/// from a handoff summary. Returns the moved (ticket_id, worktree_path).
+pub async fn transfer_ticket(store: &StateStore, from: &str, to: &str) -> Result<(String, String), String> {
+    let mut map = store.agents.write().await;
+    let source = map.get(from).ok_or_else(|| format!("agent '{}' not found", from))?;
+    let target = map.get(to).ok_or_else(|| format!("agent '{}' not found", to))?;
+    if from == to {
+        return Err("cannot hand a ticket off to the same agent".to_string());
+    }
+    if source.status == AgentStatus::Working {
+        return Err(format!("agent '{}' is still working — pause it first", from));
+    }
+    if target.current_ticket_id.is_some() {
+        return Err(format!("agent '{}' already has a ticket", to));
+    }
+    let (Some(ticket_id), Some(worktree_path)) =
+        (source.current_ticket_id.clone(), source.worktree_path.clone())
+    else {
+        return Err(format!("agent '{}' has no ticket in progress", from));
+    };
+    let pr_number = source.pr_number;
+
+    if let Some(source) = map.get_mut(from) {
+        source.current_ticket_id = None;
+        source.worktree_path = None;
+        source.pr_number = None;
+        source.session_id = None;
+        source.status = AgentStatus::Idle;
+    }
+    if let Some(target) = map.get_mut(to) {
+        target.current_ticket_id = Some(ticket_id.clone());
+        target.worktree_path = Some(worktree_path.clone());
+        target.pr_number = pr_number;
+        target.session_id = None;
+        target.status = AgentStatus::Idle;
+    }
+    drop(map);
+    store.notify(AgentChange::Updated(from.to_string()));
+    store.notify(AgentChange::Updated(to.to_string()));
+    Ok((ticket_id, worktree_path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,93 +300,195 @@ mod tests {
             chat_session_id: None,
             chatting: false,
             initiative: None,
+            status_note: None,
         }
     }
 
-    #[test]
-    fn insert_and_retrieve_agent() {
+    #[tokio::test]
+    async fn insert_and_retrieve_agent() {
         let store = new_store();
         let agent = make_agent("agent-1", AgentStatus::Idle);
-        upsert_agent(&store, agent);
+        upsert_agent(&store, agent).await;
 
-        let retrieved = get_agent(&store, "agent-1").expect("agent should exist");
+        let retrieved = get_agent(&store, "agent-1").await.expect("agent should exist");
         assert_eq!(retrieved.id, "agent-1");
         assert_eq!(retrieved.status, AgentStatus::Idle);
     }
 
-    #[test]
-    fn update_agent_status() {
+    #[tokio::test]
+    async fn update_agent_status() {
         let store = new_store();
-        upsert_agent(&store, make_agent("agent-2", AgentStatus::Idle));
-        set_status(&store, "agent-2", AgentStatus::Working);
+        upsert_agent(&store, make_agent("agent-2", AgentStatus::Idle)).await;
+        set_status(&store, "agent-2", AgentStatus::Working).await;
 
-        let agent = get_agent(&store, "agent-2").unwrap();
+        let agent = get_agent(&store, "agent-2").await.unwrap();
         assert_eq!(agent.status, AgentStatus::Working);
     }
 
-    #[test]
-    fn all_agents_returns_all() {
+    #[tokio::test]
+    async fn all_agents_returns_all() {
         let store = new_store();
-        upsert_agent(&store, make_agent("a1", AgentStatus::Idle));
-        upsert_agent(&store, make_agent("a2", AgentStatus::Working));
+        upsert_agent(&store, make_agent("a1", AgentStatus::Idle)).await;
+        upsert_agent(&store, make_agent("a2", AgentStatus::Working)).await;
 
-        let all = all_agents(&store);
+        let all = all_agents(&store).await;
         assert_eq!(all.len(), 2);
     }
 
-    #[test]
-    fn missing_agent_returns_none() {
+    #[tokio::test]
+    async fn missing_agent_returns_none() {
         let store = new_store();
-        assert!(get_agent(&store, "nonexistent").is_none());
+        assert!(get_agent(&store, "nonexistent").await.is_none());
     }
 
-    #[test]
-    fn save_and_retrieve_session_id() {
+    #[tokio::test]
+    async fn save_and_retrieve_session_id() {
         let store = new_store();
-        upsert_agent(&store, make_agent("agent-5", AgentStatus::Idle));
-        save_session_id(&store, "agent-5", "session-abc");
+        upsert_agent(&store, make_agent("agent-5", AgentStatus::Idle)).await;
+        save_session_id(&store, "agent-5", "session-abc").await;
 
-        let agent = get_agent(&store, "agent-5").unwrap();
+        let agent = get_agent(&store, "agent-5").await.unwrap();
         assert_eq!(agent.session_id, Some("session-abc".to_string()));
     }
 
-    #[test]
-    fn save_session_id_no_op_for_missing_agent() {
+    #[tokio::test]
+    async fn save_session_id_no_op_for_missing_agent() {
         let store = new_store();
         // Should not panic — just silently does nothing
-        save_session_id(&store, "nonexistent", "session-xyz");
-        assert!(get_agent(&store, "nonexistent").is_none());
+        save_session_id(&store, "nonexistent", "session-xyz").await;
+        assert!(get_agent(&store, "nonexistent").await.is_none());
     }
 
-    #[test]
-    fn update_agent_fields_partial() {
+    #[tokio::test]
+    async fn update_agent_fields_partial() {
         let store = new_store();
-        upsert_agent(&store, make_agent("agent-u", AgentStatus::Idle));
-        let ok = update_agent_fields(&store, "agent-u", Some("New Name".into()), None, None, None);
+        upsert_agent(&store, make_agent("agent-u", AgentStatus::Idle)).await;
+        let ok = update_agent_fields(&store, "agent-u", Some("New Name".into()), None, None, None).await;
         assert!(ok);
-        let agent = get_agent(&store, "agent-u").unwrap();
+        let agent = get_agent(&store, "agent-u").await.unwrap();
         assert_eq!(agent.name, "New Name");
         assert_eq!(agent.role, "backend-engineer"); // unchanged
     }
 
-    #[test]
-    fn update_agent_fields_missing() {
+    #[tokio::test]
+    async fn update_agent_fields_missing() {
         let store = new_store();
-        let ok = update_agent_fields(&store, "nope", Some("x".into()), None, None, None);
+        let ok = update_agent_fields(&store, "nope", Some("x".into()), None, None, None).await;
         assert!(!ok);
     }
 
-    #[test]
-    fn remove_agent_works() {
+    #[tokio::test]
+    async fn remove_agent_works() {
         let store = new_store();
-        upsert_agent(&store, make_agent("agent-r", AgentStatus::Idle));
-        assert!(remove_agent(&store, "agent-r"));
-        assert!(get_agent(&store, "agent-r").is_none());
+        upsert_agent(&store, make_agent("agent-r", AgentStatus::Idle)).await;
+        assert!(remove_agent(&store, "agent-r").await);
+        assert!(get_agent(&store, "agent-r").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn remove_agent_missing() {
+        let store = new_store();
+        assert!(!remove_agent(&store, "nonexistent").await);
+    }
+
+    #[tokio::test]
+    async fn transfer_ticket_moves_work_and_clears_sessions() {
+        let store = new_store();
+        let mut from = make_agent("fe", AgentStatus::Blocked);
+        from.current_ticket_id = Some("t-1".to_string());
+        from.worktree_path = Some("/wt/t-1".to_string());
+        from.session_id = Some("s-1".to_string());
+        from.pr_number = Some(7);
+        upsert_agent(&store, from).await;
+        upsert_agent(&store, make_agent("be", AgentStatus::Idle)).await;
+
+        let moved = transfer_ticket(&store, "fe", "be").await.unwrap();
+        assert_eq!(moved, ("t-1".to_string(), "/wt/t-1".to_string()));
+
+        let from = get_agent(&store, "fe").await.unwrap();
+        assert!(from.current_ticket_id.is_none() && from.session_id.is_none());
+        let to = get_agent(&store, "be").await.unwrap();
+        assert_eq!(to.current_ticket_id.as_deref(), Some("t-1"));
+        assert_eq!(to.pr_number, Some(7));
+        assert!(to.session_id.is_none());
     }
 
-    #[test]
-    fn remove_agent_missing() {
+    #[tokio::test]
+    async fn transfer_ticket_refuses_busy_agents() {
         let store = new_store();
-        assert!(!remove_agent(&store, "nonexistent"));
+        let mut from = make_agent("fe", AgentStatus::Working);
+        from.current_ticket_id = Some("t-1".to_string());
+        from.worktree_path = Some("/wt/t-1".to_string());
+        upsert_agent(&store, from).await;
+        upsert_agent(&store, make_agent("be", AgentStatus::Idle)).await;
+        assert!(transfer_ticket(&store, "fe", "be").await.is_err());
+        assert_eq!(get_agent(&store, "fe").await.unwrap().current_ticket_id.as_deref(), Some("t-1"));
+    }
+
+    #[tokio::test]
+    async fn update_agent_returns_none_for_missing_agent() {
+        let store = new_store();
+        assert_eq!(update_agent(&store, "ghost", |a| a.chatting = true).await, None);
+        upsert_agent(&store, make_agent("a1", AgentStatus::Idle)).await;
+        let ticket = update_agent(&store, "a1", |a| {
+            a.status = AgentStatus::Working;
+            a.current_ticket_id = Some("t-1".to_string());
+            a.current_ticket_id.clone()
+        }).await;
+        assert_eq!(ticket, Some(Some("t-1".to_string())));
+        assert_eq!(get_agent(&store, "a1").await.unwrap().status, AgentStatus::Working);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_updates_do_not_clobber_each_other() {
+        let store = new_store();
+        upsert_agent(&store, make_agent("a1", AgentStatus::Idle)).await;
+        let tasks: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    for _ in 0..100 {
+                        update_agent(&store, "a1", |a| a.name.push(char::from(b'a' + i))).await;
+                    }
+                    // A field written by another task in between must survive
+                    set_chatting(&store, "a1", true).await;
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let agent = get_agent(&store, "a1").await.unwrap();
+        assert_eq!(agent.name.len(), "Test Agent".len() + 800);
+        assert!(agent.chatting);
+    }
+
+    #[tokio::test]
+    async fn mutations_are_broadcast() {
+        let store = new_store();
+        let mut changes = store.subscribe();
+        upsert_agent(&store, make_agent("a1", AgentStatus::Idle)).await;
+        set_status(&store, "ghost", AgentStatus::Working).await;
+        remove_agent(&store, "a1").await;
+        assert_eq!(changes.recv().await.unwrap(), AgentChange::Updated("a1".to_string()));
+        assert_eq!(changes.recv().await.unwrap(), AgentChange::Removed("a1".to_string()));
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn status_notes_are_one_line_and_cleared_when_idle() {
+        let store = new_store();
+        upsert_agent(&store, make_agent("a1", AgentStatus::Working)).await;
+        assert!(set_status_note(&store, "a1", "  Running the migration tests\nthen lint").await);
+        let note = get_agent(&store, "a1").await.unwrap().status_note;
+        assert_eq!(note.as_deref(), Some("Running the migration tests"));
+
+        set_status_note(&store, "a1", &"é".repeat(500)).await;
+        let note = get_agent(&store, "a1").await.unwrap().status_note.unwrap();
+        assert_eq!(note.chars().count(), MAX_NOTE_CHARS + 1);
+
+        set_status(&store, "a1", AgentStatus::Idle).await;
+        assert_eq!(get_agent(&store, "a1").await.unwrap().status_note, None);
+        assert!(!set_status_note(&store, "ghost", "hi").await);
     }
 }
@@ -0,0 +1,214 @@
+use anyhow::{Context, Result};
+use log::info;
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+/// Payload for `terminal-output`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TerminalOutputPayload {
+    pub session_id: String,
+    pub data: String,
+}
+
+/// Payload for `terminal-exit`, sent once the shell has exited.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TerminalExitPayload {
+    pub session_id: String,
+    pub exit_code: Option<u32>,
+}
+
+/// An open shell. Dropping it closes the pty.
+pub struct TerminalSession {
+    pub agent_id: String,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+}
+
+pub type TerminalStore = Arc<Mutex<HashMap<String, TerminalSession>>>;
+
+pub fn new_store() -> TerminalStore {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+fn default_shell() -> String {
+    if cfg!(target_os = "windows") {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())
+    } else {
+        std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string())
+    }
+}
+
+/// Split `buf` into the longest valid UTF-8 prefix and the bytes of a
+/// character cut off at the end of the read, which are kept for the next one.
+/// Invalid bytes in the middle are replaced rather than held back.
+fn take_utf8(buf: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(buf) {
+        Ok(_) => buf.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => {
+            let text = String::from_utf8_lossy(buf).to_string();
+            buf.clear();
+            return text;
+        }
+    };
+    let rest = buf.split_off(valid);
+    let text = String::from_utf8_lossy(buf).to_string();
+    *buf = rest;
+    text
+}
+
+/// Start the user's shell in `worktree` and stream its output as
+/// `terminal-output` events. Returns the session id.
+pub fn open(
+    store: &TerminalStore,
+    app: AppHandle,
+    agent_id: &str,
+    worktree: &Path,
+    cols: u16,
+    rows: u16,
+) -> Result<String> {
+    let pair = native_pty_system()
+        .openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .context("failed to open pty")?;
+    let mut cmd = CommandBuilder::new(default_shell());
+    cmd.cwd(worktree);
+    let mut child = pair.slave.spawn_command(cmd).context("failed to start shell")?;
+    // The child holds its own handle; ours would keep the pty open after exit
+    drop(pair.slave);
+
+    let killer = child.clone_killer();
+    let mut reader = pair.master.try_clone_reader().context("failed to read from pty")?;
+    let writer = pair.master.take_writer().context("failed to write to pty")?;
+    let session_id = uuid::Uuid::new_v4().to_string();
+    info!("[terminal::open] agent={} session={}", agent_id, session_id);
+
+    // Registered before the reader starts, so a shell that exits at once
+    // still gets removed
+    store.lock().unwrap().insert(
+        session_id.clone(),
+        TerminalSession {
+            agent_id: agent_id.to_string(),
+            master: pair.master,
+            writer,
+            killer,
+        },
+    );
+
+    let id = session_id.clone();
+    let sessions = store.clone();
+    std::thread::spawn(move || {
+        let mut chunk = [0u8; 8192];
+        let mut pending = Vec::new();
+        while let Ok(n) = reader.read(&mut chunk) {
+            if n == 0 {
+                break;
+            }
+            pending.extend_from_slice(&chunk[..n]);
+            let data = take_utf8(&mut pending);
+            if !data.is_empty() {
+                let _ = app.emit(
+                    "terminal-output",
+                    TerminalOutputPayload {
+                        session_id: id.clone(),
+                        data,
+                    },
+                );
+            }
+        }
+        let exit_code = child.wait().ok().map(|status| status.exit_code());
+        sessions.lock().unwrap().remove(&id);
+        let _ = app.emit(
+            "terminal-exit",
+            TerminalExitPayload {
+                session_id: id,
+                exit_code,
+            },
+        );
+    });
+    Ok(session_id)
+}
+
+fn with_session<T>(
+    store: &TerminalStore,
+    session_id: &str,
+    f: impl FnOnce(&mut TerminalSession) -> Result<T>,
+) -> Result<T> {
+    let mut map = store.lock().unwrap();
+    let session = map
+        .get_mut(session_id)
+        .with_context(|| format!("terminal '{}' is not open", session_id))?;
+    f(session)
+}
+
+/// Send keystrokes (or pasted text) to the shell.
+pub fn write(store: &TerminalStore, session_id: &str, data: &str) -> Result<()> {
+    with_session(store, session_id, |s| {
+        s.writer.write_all(data.as_bytes())?;
+        s.writer.flush().context("failed to write to terminal")
+    })
+}
+
+pub fn resize(store: &TerminalStore, session_id: &str, cols: u16, rows: u16) -> Result<()> {
+    with_session(store, session_id, |s| {
+        s.master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .context("failed to resize terminal")
+    })
+}
+
+/// Kill the shell. `terminal-exit` follows once it has gone.
+pub fn close(store: &TerminalStore, session_id: &str) -> Result<()> {
+    with_session(store, session_id, |s| {
+        s.killer.kill().context("failed to stop shell")
+    })
+}
+
+/// Close every shell opened for an agent, e.g. when it's deleted.
+pub fn close_for_agent(store: &TerminalStore, agent_id: &str) {
+    let mut map = store.lock().unwrap();
+    for session in map.values_mut().filter(|s| s.agent_id == agent_id) {
+        let _ = session.killer.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_characters_wait_for_the_next_read() {
+        // "é" is 0xC3 0xA9
+        let mut buf = vec![b'o', b'k', 0xC3];
+        assert_eq!(take_utf8(&mut buf), "ok");
+        assert_eq!(buf, vec![0xC3]);
+        buf.push(0xA9);
+        assert_eq!(take_utf8(&mut buf), "é");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn invalid_bytes_are_replaced() {
+        let mut buf = vec![b'a', 0xFF, b'b'];
+        assert_eq!(take_utf8(&mut buf), "a\u{FFFD}b");
+        assert!(buf.is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+use super::orchestrator::{self, OrchestratorBlockedPayload, OrchestratorInput};
+use super::tournament::run_tests;
+use crate::git;
+
+/// Test-first settings: who writes the tests and how they're run.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TestFirstConfig {
+    /// Writes failing tests for the acceptance criteria before any code.
+    pub qa_agent_id: String,
+    /// Shell command run in the worktree between phases, e.g. "cargo test".
+    pub test_command: String,
+}
+
+/// Emitted after each phase of a test-first build with the suite's result.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TestFirstStatusPayload {
+    pub ticket_id: String,
+    /// "tests_written" after the QA run, "implemented" after the build.
+    pub stage: String,
+    pub passed: bool,
+    /// The last lines of the test command's output.
+    pub output: String,
+}
+
+fn tests_prompt(title: &str, criteria: &[String]) -> String {
+    let criteria = if criteria.is_empty() {
+        "(none listed — derive them from the ticket description)".to_string()
+    } else {
+        criteria.iter().map(|c| format!("- {}", c)).collect::<Vec<_>>().join("\n")
+    };
+    format!(
+        "You are writing tests first for \"{}\". Another agent implements it afterwards.\n\n\
+         ## Acceptance criteria\n\n{}\n\n\
+         Write tests that pin down each criterion, following the repo's test layout and style. \
+         Do not implement the feature: the new tests must fail until someone does. Commit the \
+         tests, and end with a short list of which test covers which criterion.",
+        title, criteria
+    )
+}
+
+fn implement_prompt(prompt: &str, qa_summary: &str, output: &str) -> String {
+    format!(
+        "{}\n\n## Tests to make pass\n\n\
+         A QA agent has already committed failing tests for this ticket:\n\n{}\n\n\
+         Latest test run:\n\n```\n{}\n```\n\n\
+         Implement the ticket until they pass. Don't weaken, skip or delete these tests; \
+         if one looks wrong, ask before touching it.",
+        prompt, qa_summary, output
+    )
+}
+
+fn block(input: &OrchestratorInput, app: &AppHandle, reason: &str, output: String) {
+    let _ = app.emit(
+        "orchestrator-blocked",
+        &OrchestratorBlockedPayload {
+            ticket_id: input.ticket_id.clone(),
+            reason: reason.to_string(),
+            details: Some(output),
+        },
+    );
+}
+
+/// Build in two phases: the QA agent writes failing tests, then the
+/// implementing agent works until they pass. The suite runs after each phase
+/// and `test-first-status` reports it. Returns `blocked = true` when the new
+/// tests already pass (they pin nothing down) or still fail at the end.
+pub async fn run(
+    input: &OrchestratorInput,
+    config: &TestFirstConfig,
+    app: &AppHandle,
+    mcp_port: u16,
+) -> Result<bool> {
+    let repo_root = PathBuf::from(&input.repo_root);
+    let worktree_path = input.worktree_path_override.clone().unwrap_or_else(|| {
+        git::worktree::Worktree::path_for(&repo_root, &input.ticket_id)
+            .to_string_lossy()
+            .to_string()
+    });
+    let (title, criteria) = match crate::project::tickets::locate(&repo_root, &input.ticket_id) {
+        Ok((_, ticket)) => (ticket.title, ticket.acceptance_criteria),
+        Err(_) => (input.ticket_slug.clone(), vec![]),
+    };
+    info!(
+        "[test_first::run] ticket={} qa={} implementer={}",
+        input.ticket_id, config.qa_agent_id, input.agent_id
+    );
+
+    let report = |stage: &str, passed: bool, output: &str| {
+        let _ = app.emit(
+            "test-first-status",
+            &TestFirstStatusPayload {
+                ticket_id: input.ticket_id.clone(),
+                stage: stage.to_string(),
+                passed,
+                output: output.to_string(),
+            },
+        );
+    };
+
+    // ── Tests first ──
+    let tests_input = OrchestratorInput {
+        agent_id: config.qa_agent_id.clone(),
+        prompt: tests_prompt(&title, &criteria),
+        phase: "tests".to_string(),
+        test_first: None,
+        ..input.clone()
+    };
+    let (_, qa_outcome) = orchestrator::run_phase(&tests_input, app, mcp_port).await?;
+    let (passed, output) = run_tests(&config.test_command, Path::new(&worktree_path)).await;
+    report("tests_written", passed, &output);
+    if passed {
+        block(input, app, "Tests written first already pass, so they don't cover the ticket", output);
+        return Ok(true);
+    }
+    if orchestrator::budget_pause(input, app) {
+        return Ok(true);
+    }
+
+    // ── Implementation ──
+    let build_input = OrchestratorInput {
+        prompt: implement_prompt(&input.prompt, &qa_outcome.result.unwrap_or_default(), &output),
+        worktree_path_override: Some(worktree_path.clone()),
+        test_first: None,
+        ..input.clone()
+    };
+    let (completed, _) = orchestrator::run_phase(&build_input, app, mcp_port).await?;
+    let (passed, output) = run_tests(&config.test_command, Path::new(&worktree_path)).await;
+    report("implemented", passed, &output);
+    if !passed {
+        block(input, app, "Tests written first still fail after the build", output);
+        return Ok(true);
+    }
+    Ok(completed.blocked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_prompt_lists_criteria_and_forbids_implementing() {
+        let prompt = tests_prompt("Retry uploads", &["Retries 3 times".to_string(), "Backs off".to_string()]);
+        assert!(prompt.contains("- Retries 3 times\n- Backs off"));
+        assert!(prompt.contains("Do not implement the feature"));
+        assert!(tests_prompt("Retry uploads", &[]).contains("derive them from the ticket description"));
+    }
+
+    #[test]
+    fn implement_prompt_carries_the_failing_run() {
+        let prompt = implement_prompt("Fix #7", "retry_test covers criterion 1", "1 failed");
+        assert!(prompt.starts_with("Fix #7\n\n## Tests to make pass"));
+        assert!(prompt.contains("retry_test covers criterion 1"));
+        assert!(prompt.contains("```\n1 failed\n```"));
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::session;
+use crate::context::builder::TicketPhase;
+use crate::platform::exec;
+use crate::project::store;
+
+const MAX_TURNS: u32 = 40;
+const TIMEOUT: Duration = Duration::from_secs(900);
+
+/// Commits the repo may move past a tour before it's left out of prompts.
+const STALE_AFTER_COMMITS: u32 = 200;
+
+/// Longest tour section a prompt gets; the rest stays in `tour.json`.
+const MAX_SECTION_CHARS: usize = 8_000;
+
+const SYSTEM_PROMPT: &str = "You are writing an orientation tour of this repository for engineers \
+    who will work in it next and have never seen it. Explore it: manifests, READMEs, the main \
+    entry points, the largest modules, how a request or command moves through the code. Do not \
+    change anything.\n\n\
+    Be concrete: real paths, real type and function names. Skip what anyone would assume.\n\n\
+    End your reply with a single JSON object and nothing after it:\n\
+    {\"summary\": \"<what the project is and how it's built, 2-3 sentences>\", \
+    \"entry_points\": [{\"path\": \"<file>\", \"description\": \"<what starts here>\"}], \
+    \"modules\": [{\"path\": \"<dir or file>\", \"description\": \"<what it owns>\"}], \
+    \"data_flow\": \"<how data moves through the main path, one paragraph>\", \
+    \"conventions\": [\"<pattern new code should follow>\", ...]}";
+
+/// A path worth knowing and why.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct TourStop {
+    pub path: String,
+    pub description: String,
+}
+
+/// A structured orientation to a repository, written once by a read-only
+/// agent and given to later runs that work in the code so they don't start
+/// cold.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct CodebaseTour {
+    pub summary: String,
+    pub entry_points: Vec<TourStop>,
+    pub modules: Vec<TourStop>,
+    pub data_flow: String,
+    pub conventions: Vec<String>,
+    /// Commit the tour was written at; later commits may have moved things.
+    pub commit: Option<String>,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub generated_at: i64,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    summary: String,
+    #[serde(default)]
+    entry_points: Vec<TourStop>,
+    #[serde(default)]
+    modules: Vec<TourStop>,
+    #[serde(default)]
+    data_flow: String,
+    #[serde(default)]
+    conventions: Vec<String>,
+}
+
+/// `<repo>/.poietai/tour.json`.
+pub fn path(repo_root: &Path) -> PathBuf {
+    store::project_dir(repo_root).join("tour.json")
+}
+
+pub fn load(repo_root: &Path) -> Result<Option<CodebaseTour>> {
+    store::read_json(&path(repo_root))
+}
+
+pub fn parse_reply(text: &str, commit: Option<String>) -> Result<CodebaseTour> {
+    let json = session::json_object(text).context("tour reply had no JSON")?;
+    let reply: Reply = serde_json::from_str(json).context("tour reply was malformed")?;
+    Ok(CodebaseTour {
+        summary: reply.summary,
+        entry_points: reply.entry_points,
+        modules: reply.modules,
+        data_flow: reply.data_flow,
+        conventions: reply.conventions,
+        commit,
+        generated_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// The tour as a system prompt section, cut to [`MAX_SECTION_CHARS`] at a
+/// line break. `commits_since` notes how far the repo has moved on.
+pub fn prompt_section(tour: &CodebaseTour, commits_since: u32) -> String {
+    let stops = |stops: &[TourStop]| {
+        stops
+            .iter()
+            .map(|s| format!("- `{}`: {}", s.path, s.description))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let mut section = String::from("## Codebase tour\n\n");
+    if commits_since > 0 {
+        section.push_str(&format!(
+            "Written {} commit{} ago; check paths before relying on them.\n\n",
+            commits_since,
+            if commits_since == 1 { "" } else { "s" }
+        ));
+    }
+    section.push_str(&tour.summary);
+    if !tour.entry_points.is_empty() {
+        section.push_str(&format!("\n\n### Entry points\n{}", stops(&tour.entry_points)));
+    }
+    if !tour.modules.is_empty() {
+        section.push_str(&format!("\n\n### Key modules\n{}", stops(&tour.modules)));
+    }
+    if !tour.data_flow.is_empty() {
+        section.push_str(&format!("\n\n### Data flow\n{}", tour.data_flow));
+    }
+    if !tour.conventions.is_empty() {
+        let conventions = tour.conventions.iter().map(|c| format!("- {}", c)).collect::<Vec<_>>();
+        section.push_str(&format!("\n\n### Conventions\n{}", conventions.join("\n")));
+    }
+    if section.len() > MAX_SECTION_CHARS {
+        let mut end = MAX_SECTION_CHARS;
+        while !section.is_char_boundary(end) {
+            end -= 1;
+        }
+        section.truncate(section[..end].rfind('\n').unwrap_or(end));
+        section.push_str("\n\n(Tour cut short; the rest is in .poietai/tour.json.)");
+    }
+    section
+}
+
+/// Phases that work in the code and start from the tour. Reviews and checks
+/// look at a diff instead.
+fn wanted(phase: &TicketPhase) -> bool {
+    matches!(
+        phase,
+        TicketPhase::Design | TicketPhase::Plan | TicketPhase::Tests | TicketPhase::Build
+    )
+}
+
+/// Commits on HEAD since the tour's commit. None when the tour has no
+/// commit or git no longer knows it.
+async fn commits_since(repo_root: &Path, commit: &str) -> Option<u32> {
+    let output = exec::output(
+        tokio::process::Command::new("git")
+            .args(["rev-list", "--count", &format!("{}..HEAD", commit)])
+            .current_dir(repo_root),
+        exec::LOCAL,
+    )
+    .await
+    .ok()
+    .filter(|o| o.status.success())?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// The tour section for a `phase` run on `repo_root`. Empty when the phase
+/// doesn't use it, or there's no tour or it's stale.
+pub async fn section_for(repo_root: &Path, phase: &TicketPhase) -> String {
+    if !wanted(phase) {
+        return String::new();
+    }
+    let Some(tour) = load(repo_root).ok().flatten() else {
+        return String::new();
+    };
+    let Some(commit) = tour.commit.as_deref() else {
+        return String::new();
+    };
+    match commits_since(repo_root, commit).await {
+        Some(n) if n <= STALE_AFTER_COMMITS => prompt_section(&tour, n),
+        _ => {
+            info!(
+                "[tour::section_for] tour of {:?} is stale, leaving it out",
+                repo_root
+            );
+            String::new()
+        }
+    }
+}
+
+/// Tour `repo_root` with a read-only agent and store the result.
+pub async fn generate(repo_root: &Path) -> Result<CodebaseTour> {
+    info!("[tour::generate] root={:?}", repo_root);
+    let commit = exec::output(
+        tokio::process::Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(repo_root),
+        exec::LOCAL,
+    )
+    .await
+    .ok()
+    .filter(|o| o.status.success())
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    let text = session::ask_read_only(
+        "",
+        "",
+        repo_root,
+        SYSTEM_PROMPT,
+        "Write the tour of this repository.",
+        MAX_TURNS,
+        TIMEOUT,
+    )
+    .await
+    .context("tour run failed")?;
+    let tour = parse_reply(&text, commit)?;
+    store::write_json(&path(repo_root), &tour)?;
+    Ok(tour)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trailing_tour_and_renders_it() {
+        let text = "Explored the repo.\n{\"summary\": \"A Tauri app.\", \
+                    \"entry_points\": [{\"path\": \"src/main.rs\", \"description\": \"starts the app\"}], \
+                    \"modules\": [], \"data_flow\": \"Commands call the store.\", \
+                    \"conventions\": [\"Errors via anyhow\"]}";
+        let tour = parse_reply(text, Some("abc123".to_string())).unwrap();
+        assert_eq!(tour.entry_points[0].path, "src/main.rs");
+        assert_eq!(tour.commit.as_deref(), Some("abc123"));
+
+        let section = prompt_section(&tour, 0);
+        assert!(section.starts_with("## Codebase tour\n\nA Tauri app."));
+        assert!(section.contains("### Entry points\n- `src/main.rs`: starts the app"));
+        assert!(!section.contains("### Key modules"));
+        assert!(section.ends_with("### Conventions\n- Errors via anyhow"));
+        assert!(prompt_section(&tour, 3).contains("Written 3 commits ago"));
+        assert!(parse_reply("no json", None).is_err());
+    }
+
+    #[test]
+    fn long_tours_are_cut_at_a_line() {
+        let tour = CodebaseTour {
+            summary: "A big repo.".to_string(),
+            entry_points: vec![],
+            modules: (0..500)
+                .map(|i| TourStop {
+                    path: format!("src/module_{}.rs", i),
+                    description: "does its part — ünïcode too".to_string(),
+                })
+                .collect(),
+            data_flow: String::new(),
+            conventions: vec![],
+            commit: None,
+            generated_at: 0,
+        };
+        let section = prompt_section(&tour, 0);
+        let (kept, note) = section.rsplit_once("\n\n").unwrap();
+        assert!(kept.len() <= MAX_SECTION_CHARS);
+        assert!(kept.ends_with("ünïcode too"));
+        assert!(note.contains("tour.json"));
+    }
+}
@@ -0,0 +1,423 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use super::orchestrator::{self, OrchestratorInput};
+use super::session;
+use crate::git;
+use crate::platform::exec;
+use crate::AppState;
+
+/// Tournament settings: who competes and how entries are compared.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TournamentConfig {
+    /// One attempt per agent, each in its own worktree.
+    pub agent_ids: Vec<String>,
+    /// Shell command run in each worktree afterwards, e.g. "cargo test".
+    #[serde(default)]
+    pub test_command: Option<String>,
+    /// Agent that reads every diff and ranks the attempts.
+    #[serde(default)]
+    pub judge_agent_id: Option<String>,
+}
+
+/// One contestant's result.
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+pub struct TournamentEntry {
+    pub agent_id: String,
+    pub worktree_path: String,
+    pub branch: String,
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+    /// None when no test command is configured.
+    pub tests_passed: Option<bool>,
+    /// Tail of the test command's output.
+    pub test_output: String,
+    /// Set when the attempt itself failed.
+    pub error: Option<String>,
+    /// 1 is best.
+    pub rank: u32,
+}
+
+/// Payload for `tournament-results`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TournamentPayload {
+    pub ticket_id: String,
+    pub entries: Vec<TournamentEntry>,
+    pub judge_rationale: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JudgeVerdict {
+    ranking: Vec<String>,
+    rationale: String,
+}
+
+/// Tournaments waiting on the user to pick a winner, keyed by ticket id.
+/// Holds each one's run input so the winner can go on to the review phases.
+pub type PendingStore = Arc<Mutex<HashMap<String, OrchestratorInput>>>;
+
+pub fn new_store() -> PendingStore {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// The input that continues `winner`'s attempt through the review phases,
+/// or None when no tournament on `ticket_id` is waiting (e.g. after a restart).
+pub fn winner_input(store: &PendingStore, ticket_id: &str, winner: &str) -> Option<OrchestratorInput> {
+    let input = store.lock().unwrap_or_else(|e| e.into_inner()).remove(ticket_id)?;
+    let path = entry_path(Path::new(&input.repo_root), ticket_id, winner);
+    Some(OrchestratorInput {
+        agent_id: winner.to_string(),
+        phase: "review".to_string(),
+        worktree_path_override: Some(path.to_string_lossy().to_string()),
+        group_id: None,
+        tournament: None,
+        ..input
+    })
+}
+
+/// Where a contestant's attempt lives: a sibling of the ticket's own worktree.
+pub fn entry_path(repo_root: &Path, ticket_id: &str, agent_id: &str) -> PathBuf {
+    git::worktree::Worktree::path_for(repo_root, &format!("{}-{}", ticket_id, agent_id))
+}
+
+pub fn entry_branch(ticket_slug: &str, agent_id: &str) -> String {
+    format!("{}-{}", git::worktree::Worktree::branch_for(ticket_slug), agent_id)
+}
+
+/// A fresh worktree off HEAD for `suffix` (a contestant, or the judge),
+/// at [`entry_path`] on [`entry_branch`].
+async fn create_worktree(input: &OrchestratorInput, suffix: &str) -> Result<PathBuf> {
+    let repo_root = PathBuf::from(&input.repo_root);
+    let settings = crate::project::settings::load_project(&repo_root).unwrap_or_default();
+    let config = git::worktree::WorktreeConfig {
+        repo_root,
+        ticket_id: format!("{}-{}", input.ticket_id, suffix),
+        ticket_slug: format!("{}-{}", input.ticket_slug, suffix),
+        // Identity is set per phase, not on the worktree
+        agent_name: String::new(),
+        agent_email: String::new(),
+        init_submodules: !settings.skip_submodule_init,
+        cache: settings.cache,
+    };
+    let worktree = git::worktree::create(&config)
+        .await
+        .with_context(|| format!("failed to create the {} worktree", suffix))?;
+    Ok(worktree.path)
+}
+
+/// Delete `suffix`'s worktree and branch, if they exist.
+async fn remove_worktree(repo_root: &Path, ticket_id: &str, ticket_slug: &str, suffix: &str) -> Result<()> {
+    let path = entry_path(repo_root, ticket_id, suffix);
+    if path.exists() {
+        git::worktree::remove(repo_root, &path)
+            .await
+            .with_context(|| format!("failed to remove the {} worktree", suffix))?;
+    }
+    let branch = entry_branch(ticket_slug, suffix);
+    let exists = git_output(repo_root, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch)])
+        .await
+        .is_ok();
+    if exists {
+        git_output(repo_root, &["branch", "-D", &branch]).await?;
+    }
+    Ok(())
+}
+
+/// Parse `git diff --shortstat`, e.g.
+/// " 3 files changed, 10 insertions(+), 2 deletions(-)".
+pub fn parse_shortstat(line: &str) -> (u32, u32, u32) {
+    let mut stats = (0, 0, 0);
+    for part in line.split(',') {
+        let mut words = part.split_whitespace();
+        let (Some(n), Some(kind)) = (words.next(), words.next()) else {
+            continue;
+        };
+        let n: u32 = n.parse().unwrap_or(0);
+        if kind.starts_with("file") {
+            stats.0 = n;
+        } else if kind.starts_with("insertion") {
+            stats.1 = n;
+        } else if kind.starts_with("deletion") {
+            stats.2 = n;
+        }
+    }
+    stats
+}
+
+/// Rank entries in place. A judge's ranking wins; agents it left out (and
+/// all agents when there's no judge) are ordered by: no error, tests passing,
+/// then the smallest change.
+pub fn rank(entries: &mut [TournamentEntry], judge_order: Option<&[String]>) {
+    let judged = |id: &str| {
+        judge_order
+            .and_then(|order| order.iter().position(|a| a == id))
+            .unwrap_or(usize::MAX)
+    };
+    entries.sort_by_key(|e| {
+        (
+            judged(&e.agent_id),
+            e.error.is_some(),
+            e.tests_passed == Some(false),
+            e.insertions + e.deletions,
+        )
+    });
+    for (i, entry) in entries.iter_mut().enumerate() {
+        entry.rank = i as u32 + 1;
+    }
+}
+
+async fn git_output(dir: &Path, args: &[&str]) -> Result<String> {
+    let mut cmd = tokio::process::Command::new("git");
+    cmd.args(args).current_dir(dir);
+    let output = exec::output(&mut cmd, exec::LOCAL).await?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run the test command in a worktree; returns (passed, output tail). A run
+/// that outlasts [`exec::LOCAL`] is killed and counts as failing.
+pub(crate) async fn run_tests(command: &str, dir: &Path) -> (bool, String) {
+    let mut cmd = git::bootstrap::shell_command(command);
+    cmd.current_dir(dir);
+    match tokio::task::spawn_blocking(move || exec::output_blocking(&mut cmd, exec::LOCAL)).await {
+        Ok(Ok(output)) => {
+            let text = String::from_utf8_lossy(&output.stdout).to_string();
+            let tail: Vec<&str> = text.lines().rev().take(40).collect();
+            let tail = tail.into_iter().rev().collect::<Vec<_>>().join("\n");
+            (output.status.success(), tail)
+        }
+        Ok(Err(e)) => (false, e.to_string()),
+        Err(e) => (false, format!("test task panicked: {}", e)),
+    }
+}
+
+/// Ask the judge to rank the attempts from their diffs.
+async fn judge(
+    input: &OrchestratorInput,
+    judge_agent_id: &str,
+    entries: &[TournamentEntry],
+    base: &str,
+    app: &AppHandle,
+    mcp_port: u16,
+) -> Result<JudgeVerdict> {
+    let mut prompt = format!(
+        "{}\n\nSeveral engineers attempted the ticket above independently. Compare their \
+         attempts for correctness, completeness, and fit with the codebase, and rank them.\n",
+        input.prompt
+    );
+    for entry in entries {
+        let diff = git_output(Path::new(&entry.worktree_path), &["diff", base])
+            .await
+            .unwrap_or_default();
+        prompt.push_str(&format!(
+            "\n## Attempt by {}\nTests: {}\n\n```diff\n{}\n```\n",
+            entry.agent_id,
+            match entry.tests_passed {
+                Some(true) => "passing",
+                Some(false) => "failing",
+                None => "not run",
+            },
+            diff
+        ));
+    }
+    prompt.push_str(
+        "\nEnd your reply with a single line of JSON: \
+         {\"ranking\": [\"<agent id, best first>\", ...], \"rationale\": \"<why>\"}",
+    );
+
+    // The judge only reads, but a run still writes its hook settings into its
+    // working directory — give it a scratch worktree rather than the user's checkout
+    let scratch = create_worktree(input, "judge").await?;
+    let judge_input = OrchestratorInput {
+        agent_id: judge_agent_id.to_string(),
+        prompt,
+        phase: "qa".to_string(),
+        worktree_path_override: Some(scratch.to_string_lossy().to_string()),
+        group_id: Some("tournament-judge".to_string()),
+        resume_session_id: None,
+        tournament: None,
+        pair: None,
+        ..input.clone()
+    };
+    let result = orchestrator::run_phase(&judge_input, app, mcp_port).await;
+    let repo_root = Path::new(&input.repo_root);
+    if let Err(e) = remove_worktree(repo_root, &input.ticket_id, &input.ticket_slug, "judge").await {
+        warn!("[tournament::judge] failed to clean up the judge's worktree: {:#}", e);
+    }
+    let text = result?.1.result.unwrap_or_default();
+    let json = session::json_object(&text).context("judge reply had no JSON verdict")?;
+    serde_json::from_str(json).context("failed to parse judge verdict")
+}
+
+/// Run every contestant on the ticket in parallel, compare the results, and
+/// emit `tournament-results`. Worktrees are left in place until the user
+/// picks a winner with [`finish`]; the input waits in the pending store for
+/// [`winner_input`].
+pub async fn run(
+    input: &OrchestratorInput,
+    config: &TournamentConfig,
+    app: &AppHandle,
+    mcp_port: u16,
+) -> Result<TournamentPayload> {
+    let repo_root = PathBuf::from(&input.repo_root);
+    let base = git_output(&repo_root, &["rev-parse", "HEAD"]).await?;
+    info!(
+        "[tournament::run] ticket={} contestants={} base={}",
+        input.ticket_id,
+        config.agent_ids.len(),
+        base
+    );
+
+    let mut join_set = tokio::task::JoinSet::new();
+    for agent_id in &config.agent_ids {
+        let branch = entry_branch(&input.ticket_slug, agent_id);
+        let path = create_worktree(input, agent_id).await?;
+        let path_str = path.to_string_lossy().to_string();
+
+        let entry_input = OrchestratorInput {
+            agent_id: agent_id.clone(),
+            worktree_path_override: Some(path_str.clone()),
+            group_id: Some(format!("tournament-{}", agent_id)),
+            tournament: None,
+            pair: None,
+            ..input.clone()
+        };
+        let app = app.clone();
+        let agent_id = agent_id.clone();
+        join_set.spawn(async move {
+            let result = orchestrator::run_phase(&entry_input, &app, mcp_port).await;
+            TournamentEntry {
+                agent_id,
+                worktree_path: path_str,
+                branch,
+                error: result.err().map(|e| format!("{:#}", e)),
+                ..Default::default()
+            }
+        });
+    }
+
+    let mut entries = Vec::new();
+    while let Some(joined) = join_set.join_next().await {
+        match joined {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!("[tournament::run] contestant task failed: {}", e),
+        }
+    }
+
+    // ── Compare ──
+    for entry in &mut entries {
+        let dir = PathBuf::from(&entry.worktree_path);
+        // Stage new files so they count toward the stats
+        let _ = git_output(&dir, &["add", "-N", "."]).await;
+        let stat = git_output(&dir, &["diff", "--shortstat", &base]).await.unwrap_or_default();
+        (entry.files_changed, entry.insertions, entry.deletions) = parse_shortstat(&stat);
+        if let Some(ref command) = config.test_command {
+            let (passed, output) = run_tests(command, &dir).await;
+            entry.tests_passed = Some(passed);
+            entry.test_output = output;
+        }
+    }
+
+    let verdict = match config.judge_agent_id {
+        Some(ref judge_id) => match judge(input, judge_id, &entries, &base, app, mcp_port).await {
+            Ok(v) => Some(v),
+            Err(e) => {
+                warn!("[tournament::run] judge failed: {:#}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    rank(&mut entries, verdict.as_ref().map(|v| v.ranking.as_slice()));
+
+    let payload = TournamentPayload {
+        ticket_id: input.ticket_id.clone(),
+        entries,
+        judge_rationale: verdict.map(|v| v.rationale),
+    };
+    app.state::<AppState>()
+        .tournaments
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(input.ticket_id.clone(), input.clone());
+    let _ = app.emit("tournament-results", &payload);
+    Ok(payload)
+}
+
+/// Keep the winner's attempt and delete everyone else's worktree and branch.
+pub async fn finish(
+    repo_root: &Path,
+    ticket_id: &str,
+    ticket_slug: &str,
+    winner: &str,
+    agent_ids: &[String],
+) -> Result<()> {
+    for agent_id in agent_ids.iter().filter(|a| a.as_str() != winner) {
+        remove_worktree(repo_root, ticket_id, ticket_slug, agent_id)
+            .await
+            .with_context(|| format!("failed to clean up {}'s attempt", agent_id))?;
+    }
+    info!("[tournament::finish] ticket={} winner={}", ticket_id, winner);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, tests: Option<bool>, lines: u32) -> TournamentEntry {
+        TournamentEntry {
+            agent_id: id.to_string(),
+            tests_passed: tests,
+            insertions: lines,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_shortstat() {
+        assert_eq!(
+            parse_shortstat(" 3 files changed, 10 insertions(+), 2 deletions(-)"),
+            (3, 10, 2)
+        );
+        assert_eq!(parse_shortstat(" 1 file changed, 1 deletion(-)"), (1, 0, 1));
+        assert_eq!(parse_shortstat(""), (0, 0, 0));
+    }
+
+    #[test]
+    fn ranks_passing_small_changes_first() {
+        let mut entries = vec![
+            entry("big", Some(true), 500),
+            entry("failing", Some(false), 10),
+            entry("small", Some(true), 40),
+        ];
+        rank(&mut entries, None);
+        let order: Vec<_> = entries.iter().map(|e| e.agent_id.as_str()).collect();
+        assert_eq!(order, vec!["small", "big", "failing"]);
+        assert_eq!(entries[0].rank, 1);
+    }
+
+    #[test]
+    fn judge_ranking_takes_precedence() {
+        let mut entries = vec![entry("a", Some(true), 10), entry("b", Some(true), 900)];
+        rank(&mut entries, Some(&["b".to_string()]));
+        assert_eq!(entries[0].agent_id, "b");
+        assert_eq!(entries[1].rank, 2);
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use ts_rs::TS;
+
+use super::events::AgentEvent;
+use crate::project::store;
+
+/// One persisted canvas event.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TranscriptEntry {
+    /// Unix milliseconds the event was received.
+    #[ts(type = "number")]
+    pub at: i64,
+    pub node_id: String,
+    pub kind: AgentEvent,
+    pub group_id: Option<String>,
+}
+
+/// Keep ids usable as path components.
+pub(crate) fn sanitize(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// `$HOME/.poietai/transcripts/<ticket_id>/<agent_id>.jsonl` — every run an
+/// agent makes on a ticket, in order.
+pub fn path_for(agent_id: &str, ticket_id: &str) -> PathBuf {
+    store::app_dir()
+        .join("transcripts")
+        .join(sanitize(ticket_id))
+        .join(format!("{}.jsonl", sanitize(agent_id)))
+}
+
+// ── Writer ──────────────────────────────────────────────────────────────────
+
+/// Entries in flight to the writer before new ones are held back.
+const CHANNEL_CAPACITY: usize = 1024;
+/// Entries held in memory once the channel is full. Past this, the run's
+/// stream waits for the disk in [`Writer::catch_up`].
+const MAX_BACKLOG: usize = 8192;
+/// Entries written per batch.
+const BATCH: usize = 256;
+/// How often buffered writes are fsynced while a run is streaming.
+const SYNC_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Persists a run's transcript on a blocking thread, so disk latency doesn't
+/// hold up the event stream. When the writer falls behind, entries wait in
+/// memory, up to [`MAX_BACKLOG`] — none are dropped. A writer dropped
+/// without [`Writer::finish`], as when its run is aborted, still writes
+/// everything it was given.
+pub struct Writer {
+    tx: mpsc::Sender<TranscriptEntry>,
+    /// Entries that didn't fit in the channel, oldest first.
+    backlog: VecDeque<TranscriptEntry>,
+    /// Taken by [`Writer::finish`].
+    task: Option<JoinHandle<Result<()>>>,
+}
+
+impl Writer {
+    pub fn for_run(agent_id: &str, ticket_id: &str) -> Self {
+        Self::open(path_for(agent_id, ticket_id))
+    }
+
+    pub fn open(path: PathBuf) -> Self {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let task = tokio::task::spawn_blocking(move || write_all(&path, rx));
+        Writer {
+            tx,
+            backlog: VecDeque::new(),
+            task: Some(task),
+        }
+    }
+
+    /// Queue an entry. Never blocks.
+    pub fn record(&mut self, entry: TranscriptEntry) {
+        self.backlog.push_back(entry);
+        while !self.backlog.is_empty() {
+            match self.tx.try_reserve() {
+                Ok(permit) => permit.send(self.backlog.pop_front().expect("backlog is non-empty")),
+                Err(mpsc::error::TrySendError::Full(())) => break,
+                // The writer failed; finish() reports why
+                Err(mpsc::error::TrySendError::Closed(())) => self.backlog.clear(),
+            }
+        }
+    }
+
+    /// Wait until the backlog is within [`MAX_BACKLOG`]. Returns at once
+    /// unless the disk is that far behind.
+    pub async fn catch_up(&mut self) {
+        while self.backlog.len() > MAX_BACKLOG {
+            let entry = self.backlog.pop_front().expect("backlog is non-empty");
+            if self.tx.send(entry).await.is_err() {
+                self.backlog.clear();
+            }
+        }
+    }
+
+    /// Write everything still queued and sync the file.
+    pub async fn finish(mut self) -> Result<()> {
+        while let Some(entry) = self.backlog.pop_front() {
+            if self.tx.send(entry).await.is_err() {
+                self.backlog.clear();
+            }
+        }
+        let task = self.task.take().expect("finish runs once");
+        // Closes the channel, which ends the writer
+        drop(self);
+        task.await.context("transcript writer panicked")?
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        if self.backlog.is_empty() {
+            return;
+        }
+        // Can't await here; a thread hands the rest over as the writer drains
+        let backlog = std::mem::take(&mut self.backlog);
+        let tx = self.tx.clone();
+        std::thread::spawn(move || {
+            for entry in backlog {
+                if tx.blocking_send(entry).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+fn write_all(path: &Path, mut rx: mpsc::Receiver<TranscriptEntry>) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let mut out = std::io::BufWriter::new(file);
+    let mut last_sync = Instant::now();
+    let mut batch = Vec::with_capacity(BATCH);
+
+    while rx.blocking_recv().map(|entry| batch.push(entry)).is_some() {
+        while batch.len() < BATCH {
+            match rx.try_recv() {
+                Ok(entry) => batch.push(entry),
+                Err(_) => break,
+            }
+        }
+        for entry in batch.drain(..) {
+            serde_json::to_writer(&mut out, &entry).context("failed to serialize transcript entry")?;
+            out.write_all(b"\n")
+                .with_context(|| format!("failed to append to {}", path.display()))?;
+        }
+        if last_sync.elapsed() >= SYNC_INTERVAL {
+            out.flush().with_context(|| format!("failed to append to {}", path.display()))?;
+            out.get_ref().sync_data().context("failed to sync transcript")?;
+            last_sync = Instant::now();
+        }
+    }
+    out.flush().with_context(|| format!("failed to append to {}", path.display()))?;
+    out.get_ref().sync_all().context("failed to sync transcript")
+}
+
+/// Every event recorded for an agent on a ticket. Missing transcripts are empty.
+pub fn load(agent_id: &str, ticket_id: &str) -> Result<Vec<TranscriptEntry>> {
+    load_from(&path_for(agent_id, ticket_id))
+}
+
+/// Every event in the transcript file at `path`. A missing file is empty.
+pub fn load_from(path: &Path) -> Result<Vec<TranscriptEntry>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// A window of a transcript, for virtualized canvases that load older
+/// nodes on demand.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct EventPage {
+    pub entries: Vec<TranscriptEntry>,
+    /// Pass back as `cursor` for the entries before these; None at the start.
+    #[ts(type = "number | null")]
+    pub cursor: Option<u64>,
+    /// Entries in the whole transcript.
+    #[ts(type = "number")]
+    pub total: u64,
+}
+
+/// Up to `limit` entries ending just before `cursor`, or the latest ones
+/// when no cursor is given.
+pub fn page(entries: Vec<TranscriptEntry>, cursor: Option<u64>, limit: usize) -> EventPage {
+    let total = entries.len();
+    let end = cursor.map_or(total, |c| (c as usize).min(total));
+    let start = end.saturating_sub(limit);
+    EventPage {
+        entries: entries.into_iter().skip(start).take(end - start).collect(),
+        cursor: (start > 0).then_some(start as u64),
+        total: total as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_components_are_sanitized() {
+        let path = path_for("agent/1", "../t-42");
+        assert!(path.ends_with("transcripts/___t-42/agent_1.jsonl"));
+    }
+
+    #[test]
+    fn pages_walk_backwards_from_the_latest() {
+        let entries: Vec<TranscriptEntry> = (0..5)
+            .map(|i| TranscriptEntry {
+                at: i,
+                node_id: format!("n-{}", i),
+                kind: AgentEvent::Text { text: String::new() },
+                group_id: None,
+            })
+            .collect();
+        let latest = page(entries.clone(), None, 2);
+        assert_eq!(latest.entries.iter().map(|e| e.at).collect::<Vec<_>>(), [3, 4]);
+        assert_eq!((latest.cursor, latest.total), (Some(3), 5));
+        let older = page(entries.clone(), latest.cursor, 2);
+        assert_eq!(older.entries.iter().map(|e| e.at).collect::<Vec<_>>(), [1, 2]);
+        let first = page(entries, older.cursor, 2);
+        assert_eq!(first.entries.iter().map(|e| e.at).collect::<Vec<_>>(), [0]);
+        assert_eq!(first.cursor, None);
+    }
+
+    #[tokio::test]
+    async fn writer_keeps_every_entry_in_order_under_bursts() {
+        let path = std::env::temp_dir().join(format!("poietai-transcript-{}.jsonl", uuid::Uuid::new_v4()));
+        let mut writer = Writer::open(path.clone());
+        let total = CHANNEL_CAPACITY * 3;
+        for i in 0..total {
+            writer.record(TranscriptEntry {
+                at: i as i64,
+                node_id: format!("n-{}", i),
+                kind: AgentEvent::Text { text: "x".to_string() },
+                group_id: None,
+            });
+        }
+        writer.finish().await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<TranscriptEntry> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(entries.len(), total);
+        assert!(entries.iter().enumerate().all(|(i, e)| e.at == i as i64));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn dropped_writer_still_writes_its_backlog() {
+        let path = std::env::temp_dir().join(format!("poietai-transcript-{}.jsonl", uuid::Uuid::new_v4()));
+        let mut writer = Writer::open(path.clone());
+        let total = CHANNEL_CAPACITY * 3;
+        for i in 0..total {
+            writer.record(TranscriptEntry {
+                at: i as i64,
+                node_id: format!("n-{}", i),
+                kind: AgentEvent::Text { text: "x".to_string() },
+                group_id: None,
+            });
+        }
+        // An aborted run drops its writer mid-stream
+        drop(writer);
+
+        let mut written = 0;
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            written = std::fs::read_to_string(&path).map(|c| c.lines().count()).unwrap_or(0);
+            if written == total {
+                break;
+            }
+        }
+        assert_eq!(written, total);
+        let _ = std::fs::remove_file(path);
+    }
+}
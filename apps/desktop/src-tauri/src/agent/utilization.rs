@@ -0,0 +1,245 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tokio::sync::broadcast;
+use ts_rs::TS;
+
+use super::history::{Outcome, RunRecord};
+use super::state::{all_agents, get_agent, AgentChange, AgentStatus, StateStore};
+use crate::project::store;
+
+/// How often a running app notes it's still up, bounding the time a span can
+/// run on after the app quits.
+const HEARTBEAT_SECS: i64 = 300;
+const APP_STARTED: &str = "";
+const HEARTBEAT: &str = "*";
+
+/// A change of an agent's status, appended to `$HOME/.poietai/metrics/status.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Transition {
+    at: i64,
+    /// An agent id, [`APP_STARTED`] — which ends every agent's earlier
+    /// status — or [`HEARTBEAT`].
+    agent_id: String,
+    /// None once the agent is removed, or while the app was closed.
+    status: Option<AgentStatus>,
+}
+
+/// How one agent's time split over a window.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct AgentUtilization {
+    pub agent_id: String,
+    /// Distinct tickets with a successful run in the window.
+    pub tickets_completed: u32,
+    /// Working or reviewing.
+    #[ts(type = "number")]
+    pub working_secs: u64,
+    #[ts(type = "number")]
+    pub waiting_secs: u64,
+    #[ts(type = "number")]
+    pub idle_secs: u64,
+    #[ts(type = "number")]
+    pub blocked_secs: u64,
+    /// This agent's fraction of all agents' working time, 0–1.
+    pub work_share: f64,
+}
+
+fn path() -> PathBuf {
+    store::app_dir().join("metrics").join("status.jsonl")
+}
+
+fn append(agent_id: &str, status: Option<AgentStatus>) {
+    let transition = Transition {
+        at: chrono::Utc::now().timestamp(),
+        agent_id: agent_id.to_string(),
+        status,
+    };
+    if let Err(e) = store::append_jsonl(&path(), &transition) {
+        log::warn!("[utilization::append] failed to record status change: {:#}", e);
+    }
+}
+
+fn load() -> Result<Vec<Transition>> {
+    let contents = match std::fs::read_to_string(path()) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).context("failed to read status history"),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Record every agent status change for the app's lifetime.
+pub async fn track(store: StateStore) {
+    let mut changes = store.subscribe();
+    let mut heartbeat = tokio::time::interval(std::time::Duration::from_secs(HEARTBEAT_SECS as u64));
+    append(APP_STARTED, None);
+    let mut last: HashMap<String, AgentStatus> = HashMap::new();
+    for agent in all_agents(&store).await {
+        append(&agent.id, Some(agent.status.clone()));
+        last.insert(agent.id, agent.status);
+    }
+    loop {
+        let received = tokio::select! {
+            received = changes.recv() => received,
+            _ = heartbeat.tick() => {
+                append(HEARTBEAT, None);
+                continue;
+            }
+        };
+        let change = match received {
+            Ok(change) => change,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                log::warn!("[utilization::track] missed {} agent changes", missed);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let id = change.agent_id().to_string();
+        let status = match change {
+            AgentChange::Updated(_) => get_agent(&store, &id).await.map(|a| a.status),
+            AgentChange::Removed(_) => None,
+        };
+        if status.as_ref() == last.get(&id) {
+            continue;
+        }
+        append(&id, status.clone());
+        match status {
+            Some(status) => last.insert(id, status),
+            None => last.remove(&id),
+        };
+    }
+}
+
+fn add_span(entry: &mut AgentUtilization, status: &AgentStatus, start: i64, end: i64, since: i64) {
+    let secs = (end - start.max(since)).max(0) as u64;
+    match status {
+        AgentStatus::Working | AgentStatus::Reviewing => entry.working_secs += secs,
+        AgentStatus::WaitingForUser => entry.waiting_secs += secs,
+        AgentStatus::Idle => entry.idle_secs += secs,
+        AgentStatus::Blocked => entry.blocked_secs += secs,
+    }
+}
+
+/// Per-agent time split and completed tickets between `since` and `now`,
+/// busiest first.
+fn summarize(transitions: &[Transition], runs: &[RunRecord], since: i64, now: i64) -> Vec<AgentUtilization> {
+    let mut stats: HashMap<String, AgentUtilization> = HashMap::new();
+    let mut current: HashMap<String, (AgentStatus, i64)> = HashMap::new();
+    let close = |stats: &mut HashMap<String, AgentUtilization>, id: &str, span: Option<(AgentStatus, i64)>, end: i64| {
+        if let Some((status, start)) = span {
+            let entry = stats.entry(id.to_string()).or_insert_with(|| AgentUtilization {
+                agent_id: id.to_string(),
+                ..Default::default()
+            });
+            add_span(entry, &status, start, end.min(now), since);
+        }
+    };
+    let mut last_seen = i64::MIN;
+    for t in transitions.iter().filter(|t| t.at <= now) {
+        match t.agent_id.as_str() {
+            // The previous run of the app was last seen alive at its final record
+            APP_STARTED => {
+                let end = t.at.min(last_seen.saturating_add(HEARTBEAT_SECS));
+                for (id, span) in current.drain() {
+                    close(&mut stats, &id, Some(span), end);
+                }
+                last_seen = t.at;
+                continue;
+            }
+            HEARTBEAT => {
+                last_seen = t.at;
+                continue;
+            }
+            _ => last_seen = t.at,
+        }
+        close(&mut stats, &t.agent_id, current.remove(&t.agent_id), t.at);
+        if let Some(ref status) = t.status {
+            current.insert(t.agent_id.clone(), (status.clone(), t.at));
+        }
+    }
+    for (id, span) in current.drain() {
+        close(&mut stats, &id, Some(span), now);
+    }
+
+    let mut completed: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for run in runs.iter().filter(|r| r.outcome == Outcome::Succeeded && (since..=now).contains(&r.finished_at)) {
+        completed.entry(&run.agent_id).or_default().insert(&run.ticket_id);
+    }
+    for (agent_id, tickets) in completed {
+        stats
+            .entry(agent_id.to_string())
+            .or_insert_with(|| AgentUtilization {
+                agent_id: agent_id.to_string(),
+                ..Default::default()
+            })
+            .tickets_completed = tickets.len() as u32;
+    }
+
+    let total_work: u64 = stats.values().map(|s| s.working_secs).sum();
+    let mut result: Vec<AgentUtilization> = stats
+        .into_values()
+        .map(|mut s| {
+            if total_work > 0 {
+                s.work_share = s.working_secs as f64 / total_work as f64;
+            }
+            s
+        })
+        .collect();
+    result.sort_by(|a, b| b.working_secs.cmp(&a.working_secs).then(a.agent_id.cmp(&b.agent_id)));
+    result
+}
+
+/// Utilization over the last `days` days.
+pub fn over_days(days: u32) -> Result<Vec<AgentUtilization>> {
+    let now = chrono::Utc::now().timestamp();
+    let since = now - i64::from(days) * 86_400;
+    Ok(summarize(&load()?, &super::history::load()?, since, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(at: i64, agent_id: &str, status: Option<AgentStatus>) -> Transition {
+        Transition {
+            at,
+            agent_id: agent_id.to_string(),
+            status,
+        }
+    }
+
+    #[test]
+    fn splits_time_by_status_and_stops_at_app_restarts() {
+        let transitions = [
+            at(0, "a", Some(AgentStatus::Idle)),
+            at(100, "a", Some(AgentStatus::Working)),
+            at(400, "a", Some(AgentStatus::WaitingForUser)),
+            at(500, "b", Some(AgentStatus::Working)),
+            at(550, HEARTBEAT, None),
+            // The app quit soon after the last heartbeat and came back much later
+            at(5000, APP_STARTED, None),
+            at(5000, "a", Some(AgentStatus::Idle)),
+        ];
+        let runs = [RunRecord {
+            started_at: 100,
+            finished_at: 400,
+            agent_id: "a".to_string(),
+            ticket_id: "t-1".to_string(),
+            cost_usd: 0.0,
+            outcome: Outcome::Succeeded,
+        }];
+        let stats = summarize(&transitions, &runs, 50, 5100);
+        let a = stats.iter().find(|s| s.agent_id == "a").unwrap();
+        assert_eq!((a.idle_secs, a.working_secs, a.waiting_secs), (50 + 100, 300, 450));
+        assert_eq!(a.tickets_completed, 1);
+        let b = stats.iter().find(|s| s.agent_id == "b").unwrap();
+        assert_eq!(b.working_secs, 350);
+        assert_eq!(stats[0].agent_id, "b");
+        assert!((a.work_share - 300.0 / 650.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use ts_rs::TS;
+
+use super::orchestrator::get_worktree_diff;
+use super::session;
+use super::tournament::run_tests;
+use crate::project::store;
+use crate::project::tickets::TicketRecord;
+
+/// Check acceptance criteria after a successful build, before the PR.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct VerifyConfig {
+    /// Run in the worktree first; its result and output tail go to the checker.
+    pub test_command: Option<String>,
+    pub max_turns: u32,
+    #[ts(type = "number")]
+    pub timeout_secs: u64,
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        VerifyConfig {
+            test_command: None,
+            max_turns: 15,
+            timeout_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct CriterionResult {
+    pub criterion: String,
+    pub passed: bool,
+    /// Where in the diff or test output the verdict comes from.
+    pub evidence: String,
+}
+
+/// Per-criterion verdicts for a ticket. Emitted as `acceptance-verified`
+/// and stored in `<project>/.poietai/acceptance.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct AcceptanceReport {
+    pub ticket_id: String,
+    pub criteria: Vec<CriterionResult>,
+    /// None when no test command is configured.
+    pub tests_passed: Option<bool>,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub verified_at: i64,
+}
+
+impl AcceptanceReport {
+    pub fn all_passed(&self) -> bool {
+        self.tests_passed != Some(false) && self.criteria.iter().all(|c| c.passed)
+    }
+}
+
+const SYSTEM_PROMPT: &str = "You are verifying finished work against its acceptance criteria \
+    before a pull request is opened. Check each criterion against the diff, the test results, \
+    and the code itself. Be strict: a criterion passes only if the change clearly satisfies it. \
+    Do not change anything.\n\n\
+    End your reply with a single JSON object and nothing after it:\n\
+    {\"criteria\": [{\"criterion\": \"<as given>\", \"passed\": true|false, \
+    \"evidence\": \"<file/test that shows it, or what is missing>\"}]}";
+
+/// Appended to the system prompt of builds whose PR waits on verification.
+pub const PROMPT_SECTION: &str = "## Opening the PR\n\n\
+    The ticket's acceptance criteria are checked before its PR is opened. When the work is \
+    committed and pushed, run `gh pr create` as usual: it will be refused while the check runs. \
+    Then stop and end your turn with a short summary of the change. You will be resumed with \
+    the result.";
+
+/// What the PreToolUse hook tells the agent when it holds back `gh pr create`.
+/// Single-quoted in the hook, so it must not contain `'`.
+const HOLD_MESSAGE: &str = "The acceptance criteria are checked before the PR is opened, and \
+    the check has been started. Stop here and end your turn with a short summary of the change.";
+
+/// The PreToolUse hook command that refuses `gh pr create` with exit 2 until
+/// the criteria have been checked.
+pub fn hold_hook() -> String {
+    format!(
+        "case \"$(cat)\" in *'gh pr create'*) echo '{}' >&2; exit 2;; esac",
+        HOLD_MESSAGE
+    )
+}
+
+/// What a build held for verification is told when it's resumed. Without a
+/// report — verification off or it couldn't run — the PR goes ahead.
+pub fn resume_prompt(report: Option<&AcceptanceReport>) -> String {
+    let Some(report) = report.filter(|r| !r.all_passed()) else {
+        return "The acceptance check is done. Open the PR now with `gh pr create`.".to_string();
+    };
+    let mut unmet: Vec<String> = report
+        .criteria
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| format!("- {} ({})", c.criterion, c.evidence))
+        .collect();
+    if report.tests_passed == Some(false) {
+        unmet.push("- the test suite fails".to_string());
+    }
+    format!(
+        "The acceptance check found unmet criteria:\n\n{}\n\nAddress them and push, then open \
+         the PR with `gh pr create`.",
+        unmet.join("\n")
+    )
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    criteria: Vec<CriterionResult>,
+}
+
+/// Serializes read-modify-writes of `acceptance.json`.
+static LOCK: Mutex<()> = Mutex::new(());
+
+pub fn path(project_root: &Path) -> PathBuf {
+    store::project_dir(project_root).join("acceptance.json")
+}
+
+pub fn load_all(project_root: &Path) -> Result<HashMap<String, AcceptanceReport>> {
+    store::read_json(&path(project_root))
+}
+
+fn save(project_root: &Path, report: &AcceptanceReport) -> Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut all = load_all(project_root)?;
+    all.insert(report.ticket_id.clone(), report.clone());
+    store::write_json(&path(project_root), &all)
+}
+
+fn prompt(ticket: &TicketRecord, diff: &str, tests: Option<&(bool, String)>) -> String {
+    let tests = match tests {
+        Some((passed, tail)) => format!(
+            "## Test results: {}\n\n```\n{}\n```",
+            if *passed { "passed" } else { "FAILED" },
+            tail
+        ),
+        None => "## Test results\n\nNo test command is configured.".to_string(),
+    };
+    format!(
+        "{}\n\n## Code Changes (git diff)\n\n```diff\n{}\n```\n\n{}",
+        ticket.as_prompt(),
+        diff,
+        tests
+    )
+}
+
+/// Criteria the checker skipped count as failed, so the report always
+/// covers the whole ticket.
+pub fn parse_reply(ticket: &TicketRecord, text: &str) -> Result<Vec<CriterionResult>> {
+    let json = session::json_object(text).context("verification reply had no JSON")?;
+    let reply: Reply = serde_json::from_str(json).context("verification reply was malformed")?;
+    Ok(ticket
+        .acceptance_criteria
+        .iter()
+        .enumerate()
+        .map(|(i, criterion)| {
+            reply
+                .criteria
+                .iter()
+                .find(|r| r.criterion.trim() == criterion.trim())
+                .or_else(|| reply.criteria.get(i))
+                .map(|r| CriterionResult {
+                    criterion: criterion.clone(),
+                    ..r.clone()
+                })
+                .unwrap_or_else(|| CriterionResult {
+                    criterion: criterion.clone(),
+                    passed: false,
+                    evidence: "not checked".to_string(),
+                })
+        })
+        .collect())
+}
+
+/// Verify `ticket`'s acceptance criteria in `worktree` and store the report
+/// under `project_root`.
+pub async fn run(
+    config: &VerifyConfig,
+    project_root: &Path,
+    ticket: &TicketRecord,
+    worktree: &Path,
+) -> Result<AcceptanceReport> {
+    if ticket.acceptance_criteria.is_empty() {
+        anyhow::bail!("ticket '{}' has no acceptance criteria", ticket.id);
+    }
+    info!("[verify::run] ticket={} worktree={:?}", ticket.id, worktree);
+
+    let tests = match config.test_command {
+        Some(ref command) if !command.trim().is_empty() => Some(run_tests(command, worktree).await),
+        _ => None,
+    };
+    let diff = get_worktree_diff(&worktree.to_string_lossy()).await.unwrap_or_default();
+    let text = session::ask_read_only(
+        "",
+        &ticket.id,
+        worktree,
+        SYSTEM_PROMPT,
+        &prompt(ticket, &diff, tests.as_ref()),
+        config.max_turns,
+        Duration::from_secs(config.timeout_secs),
+    )
+    .await
+    .context("verification run failed")?;
+
+    let report = AcceptanceReport {
+        ticket_id: ticket.id.clone(),
+        criteria: parse_reply(ticket, &text)?,
+        tests_passed: tests.map(|(passed, _)| passed),
+        verified_at: chrono::Utc::now().timestamp(),
+    };
+    save(project_root, &report)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket() -> TicketRecord {
+        TicketRecord {
+            id: "t-1".to_string(),
+            acceptance_criteria: vec!["retries 5 times".to_string(), "logs each retry".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unchecked_criteria_fail() {
+        let text = "{\"criteria\": [{\"criterion\": \"retries 5 times\", \"passed\": true, \
+                    \"evidence\": \"src/retry.rs:12\"}]}";
+        let results = parse_reply(&ticket(), text).unwrap();
+        assert!(results[0].passed);
+        assert_eq!(results[0].evidence, "src/retry.rs:12");
+        assert!(!results[1].passed);
+        assert_eq!(results[1].evidence, "not checked");
+    }
+
+    #[test]
+    fn failing_tests_fail_the_report() {
+        let report = AcceptanceReport {
+            ticket_id: "t-1".to_string(),
+            criteria: vec![],
+            tests_passed: Some(false),
+            verified_at: 0,
+        };
+        assert!(!report.all_passed());
+    }
+
+    #[test]
+    fn resume_prompt_lists_unmet_criteria() {
+        let mut report = AcceptanceReport {
+            ticket_id: "t-1".to_string(),
+            criteria: parse_reply(&ticket(), "{\"criteria\": []}").unwrap(),
+            tests_passed: Some(true),
+            verified_at: 0,
+        };
+        let prompt = resume_prompt(Some(&report));
+        assert!(prompt.contains("- retries 5 times (not checked)"));
+        assert!(!prompt.contains("test suite"));
+
+        report.criteria.iter_mut().for_each(|c| c.passed = true);
+        assert!(resume_prompt(Some(&report)).starts_with("The acceptance check is done."));
+        assert!(resume_prompt(None).contains("gh pr create"));
+    }
+}
@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use ts_rs::TS;
+
+/// Seconds a transcription API call may take.
+const API_TIMEOUT_SECS: u32 = 120;
+
+/// How voice-memo answers to agent questions are turned into text.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranscriptionConfig {
+    /// whisper.cpp on this machine. The audio must be a format the binary
+    /// reads: 16 kHz WAV, or anything when it was built with ffmpeg.
+    Whisper {
+        /// The `whisper-cli` binary; looked up on PATH when bare.
+        binary: String,
+        /// Absolute path of a ggml model, e.g. "/opt/whisper/ggml-base.en.bin".
+        model: String,
+        /// Spoken language, e.g. "en"; auto-detected when unset.
+        language: Option<String>,
+    },
+    /// An OpenAI-compatible `/audio/transcriptions` endpoint.
+    Api {
+        /// e.g. "https://api.openai.com/v1".
+        base_url: String,
+        /// e.g. "whisper-1".
+        model: String,
+    },
+}
+
+/// Transcript lines joined into one reply, without whisper's non-speech
+/// markers like "[BLANK_AUDIO]".
+fn clean(raw: &str) -> String {
+    raw.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !(l.starts_with('[') && l.ends_with(']')))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split curl's output, written with `-w "\n%{http_code}"`, into the body,
+/// failing on anything but a 2xx.
+fn api_reply<'a>(stdout: &'a str, stderr: &str) -> Result<&'a str> {
+    let (body, code) = stdout.rsplit_once('\n').unwrap_or(("", stdout));
+    match code.trim().parse::<u16>().unwrap_or(0) {
+        200..=299 => Ok(body),
+        0 => anyhow::bail!("transcription API unreachable: {}", stderr.trim()),
+        status => anyhow::bail!("transcription API answered HTTP {}: {}", status, body.trim()),
+    }
+}
+
+fn whisper(binary: &str, model: &str, language: Option<&str>, audio: &Path) -> Result<String> {
+    let mut cmd = Command::new(binary);
+    cmd.args(["-nt", "-np", "-m", model, "-f"]).arg(audio);
+    if let Some(language) = language {
+        cmd.args(["-l", language]);
+    }
+    let output = cmd.output().with_context(|| format!("failed to run {}", binary))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            binary,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Upload through curl; the key goes on stdin to stay out of the process list.
+fn api(base_url: &str, model: &str, api_key: Option<&str>, audio: &Path) -> Result<String> {
+    let url = format!("{}/audio/transcriptions", base_url.trim_end_matches('/'));
+    let mut child = Command::new("curl")
+        .args(["-sS", "-w", "\n%{http_code}", "--max-time"])
+        .arg(API_TIMEOUT_SECS.to_string())
+        .args(["-H", "@-", "-F", "response_format=text", "-F"])
+        .arg(format!("model={}", model))
+        .arg("-F")
+        .arg(format!("file=@{}", audio.display()))
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let header = api_key
+            .filter(|k| !k.is_empty())
+            .map(|k| format!("Authorization: Bearer {}", k))
+            .unwrap_or_default();
+        stdin
+            .write_all(header.as_bytes())
+            .context("failed to pass headers to curl")?;
+    }
+    let output = child.wait_with_output().context("failed to run curl")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    api_reply(&stdout, &String::from_utf8_lossy(&output.stderr)).map(str::to_string)
+}
+
+/// The spoken text of a voice memo, ready to send as a reply. Blocking.
+pub fn transcribe(config: &TranscriptionConfig, audio: &Path, api_key: Option<&str>) -> Result<String> {
+    if !audio.is_file() {
+        anyhow::bail!("no audio file at {}", audio.display());
+    }
+    let raw = match config {
+        TranscriptionConfig::Whisper { binary, model, language } => {
+            whisper(binary, model, language.as_deref(), audio)?
+        }
+        TranscriptionConfig::Api { base_url, model } => api(base_url, model, api_key, audio)?,
+    };
+    let text = clean(&raw);
+    if text.is_empty() {
+        anyhow::bail!("no speech recognised in {}", audio.display());
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_joins_lines_and_drops_markers() {
+        let raw = "\n [BLANK_AUDIO]\n Yes, use the v2 endpoint.\n\n  And skip the migration.  \n";
+        assert_eq!(clean(raw), "Yes, use the v2 endpoint. And skip the migration.");
+        assert_eq!(clean("[BLANK_AUDIO]\n"), "");
+    }
+
+    #[test]
+    fn api_reply_requires_success_status() {
+        assert_eq!(api_reply("Ship it.\n\n200", "").unwrap(), "Ship it.\n");
+        let err = api_reply("{\"error\":\"bad key\"}\n401", "").unwrap_err();
+        assert!(err.to_string().contains("HTTP 401"));
+        let err = api_reply("000", "Could not resolve host").unwrap_err();
+        assert!(err.to_string().contains("unreachable: Could not resolve host"));
+    }
+}
@@ -1,4 +1,5 @@
-#[derive(Debug, Clone, PartialEq, serde::Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, Default, ts_rs::TS)]
+#[ts(export)]
 #[serde(rename_all = "lowercase")]
 pub enum TicketPhase {
     Brief,
@@ -7,6 +8,8 @@ pub enum TicketPhase {
     #[default]
     Build,
     Plan,
+    /// Failing tests written ahead of a test-first build.
+    Tests,
     Validate,
     Qa,
     Security,
@@ -76,6 +79,15 @@ Rules:
 - Run the test cases specified in the plan and confirm they pass
 - Open a PR when all tasks are complete and tests pass".to_string(),
 
+            TicketPhase::Tests => "\
+## Your Task: TESTS Phase
+Write failing tests for this ticket before anyone implements it.
+Rules:
+- Only add or change tests — do NOT touch the implementation
+- Follow the repo's existing test layout and style
+- Run the new tests and confirm they fail for the missing behavior, not a typo
+- Commit the tests, but do NOT push or open a PR — the build agent does that".to_string(),
+
             TicketPhase::Validate => "\
 ## Your Task: VALIDATE Phase
 You are an independent validator. You have not seen the build agent's reasoning.
@@ -171,6 +183,72 @@ fn role_description(role: &str) -> &'static str {
     }
 }
 
+/// Prompt section describing how the agent may push, based on the
+/// repository access pre-flight. `fork` is `(upstream, fork_owner)` when the
+/// run uses the fork-based workflow.
+pub fn repo_access_section(default_branch: &str, protected: bool, fork: Option<(&str, &str)>) -> String {
+    let mut lines = vec!["## Repository Access".to_string()];
+    if let Some((upstream, fork_owner)) = fork {
+        lines.push(format!(
+            "You do not push to {upstream} directly. Push your branch to the `fork` remote \
+             (`git push fork <branch>`) and open a cross-repo PR with \
+             `gh pr create --repo {upstream} --head {fork_owner}:<branch>`.",
+            upstream = upstream,
+            fork_owner = fork_owner,
+        ));
+    }
+    if protected {
+        lines.push(format!(
+            "`{}` is a protected branch. Never push to it or rewrite its history — \
+             all changes go through a PR that needs the required reviews and checks.",
+            default_branch
+        ));
+    } else {
+        lines.push(format!(
+            "Never push directly to `{}` — open a PR from your feature branch.",
+            default_branch
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Library snippets attached to the run, as a prompt section.
+pub fn snippets_section(snippets: &[super::snippets::Snippet]) -> Option<String> {
+    if snippets.is_empty() {
+        return None;
+    }
+    let blocks: Vec<String> = snippets
+        .iter()
+        .map(|s| format!("### {}\n{}", s.name, s.body.trim()))
+        .collect();
+    Some(format!("## Team Instructions\n\n{}", blocks.join("\n\n")))
+}
+
+/// The repo's latest merged PRs as a prompt section, so agents build on what
+/// just landed instead of redoing or fighting it.
+pub fn recent_changes_section(prs: &[crate::github::recent::RecentPr]) -> Option<String> {
+    if prs.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = prs
+        .iter()
+        .map(|pr| {
+            let date = pr.merged_at.get(..10).unwrap_or(&pr.merged_at);
+            if pr.summary.is_empty() {
+                format!("- #{} {} (merged {})", pr.number, pr.title, date)
+            } else {
+                format!("- #{} {} (merged {}): {}", pr.number, pr.title, date, pr.summary)
+            }
+        })
+        .collect();
+    Some(format!(
+        "## Recent Changes\n\
+         Recently merged into this repo, newest first. Don't redo this work, and build on \
+         any refactors here rather than the code they replaced.\n\n{}",
+        lines.join("\n")
+    ))
+}
+
 /// Build the full system prompt string for a single agent run.
 pub fn build(input: &ContextInput, phase: &TicketPhase) -> String {
     let acceptance_criteria = if input.ticket_acceptance_criteria.is_empty() {
@@ -375,6 +453,46 @@ mod tests {
         assert!(prompt.contains("Problem statement"));
     }
 
+    #[test]
+    fn snippets_section_lists_each_block() {
+        let snippet = |name: &str, body: &str| crate::context::snippets::Snippet {
+            name: name.to_string(),
+            body: body.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(snippets_section(&[]), None);
+        let section = snippets_section(&[snippet("Security checklist", "- no secrets in logs\n")]).unwrap();
+        assert_eq!(section, "## Team Instructions\n\n### Security checklist\n- no secrets in logs");
+    }
+
+    #[test]
+    fn recent_changes_section_lists_merged_prs() {
+        let pr = |number: u32, summary: &str| crate::github::recent::RecentPr {
+            number,
+            title: format!("PR {}", number),
+            summary: summary.to_string(),
+            merged_at: "2026-10-14T09:30:00Z".to_string(),
+        };
+        assert_eq!(recent_changes_section(&[]), None);
+        let section = recent_changes_section(&[pr(42, "Moves retries into the worker."), pr(41, "")]).unwrap();
+        assert!(section.starts_with("## Recent Changes\n"));
+        assert!(section.ends_with(
+            "- #42 PR 42 (merged 2026-10-14): Moves retries into the worker.\n- #41 PR 41 (merged 2026-10-14)"
+        ));
+    }
+
+    #[test]
+    fn repo_access_section_mentions_fork_and_protection() {
+        let section = repo_access_section("main", true, Some(("rust-lang/rust", "agent-bot")));
+        assert!(section.contains("`fork` remote"));
+        assert!(section.contains("--head agent-bot:<branch>"));
+        assert!(section.contains("`main` is a protected branch"));
+
+        let direct = repo_access_section("trunk", false, None);
+        assert!(!direct.contains("fork"));
+        assert!(direct.contains("`trunk`"));
+    }
+
     #[test]
     fn review_and_ship_phases_produce_no_phase_section() {
         let criteria = default_criteria();
@@ -396,4 +514,26 @@ mod tests {
         assert!(!review_prompt.contains("## Your Task: REVIEW Phase"));
         assert!(!ship_prompt.contains("## Your Task: SHIP Phase"));
     }
+
+    #[test]
+    fn tests_phase_forbids_implementing_and_opening_a_pr() {
+        let criteria = default_criteria();
+        let input = ContextInput {
+            role: "qa-engineer",
+            personality: "pragmatic",
+            project_name: "RRP API",
+            project_stack: "Go 1.23, PostgreSQL, pgx",
+            project_context: "",
+            ticket_number: 1,
+            ticket_title: "Test",
+            ticket_description: "Test description.",
+            ticket_acceptance_criteria: &criteria,
+            agent_id: "test-agent-123",
+        };
+        let prompt = build(&input, &TicketPhase::Tests);
+        assert!(prompt.contains("## Your Task: TESTS Phase"));
+        assert!(prompt.contains("do NOT touch the implementation"));
+        assert!(prompt.contains("do NOT push or open a PR"));
+        assert!(!prompt.contains("Open a PR"));
+    }
 }
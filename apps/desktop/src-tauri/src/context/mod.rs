@@ -1 +1,3 @@
 pub mod builder;
+pub mod conventions;
+pub mod snippets;
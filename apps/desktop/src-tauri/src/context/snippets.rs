@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use crate::project::store;
+
+/// A reusable block of instructions, e.g. "security checklist".
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct Snippet {
+    /// Assigned on first save.
+    pub id: String,
+    pub name: String,
+    pub body: String,
+    /// Roles whose runs always get this snippet.
+    pub roles: Vec<String>,
+    /// Tickets whose runs get this snippet, whatever the role.
+    pub tickets: Vec<String>,
+}
+
+impl Snippet {
+    pub fn applies_to(&self, role: &str, ticket_id: &str) -> bool {
+        self.roles.iter().any(|r| r == role) || self.tickets.iter().any(|t| t == ticket_id)
+    }
+}
+
+/// Serializes read-modify-writes of the snippet libraries.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// `$HOME/.poietai/snippets.json`, or `<project>/.poietai/snippets.json`
+/// for a project's own library.
+pub fn path(project_root: Option<&Path>) -> PathBuf {
+    match project_root {
+        Some(root) => store::project_dir(root).join("snippets.json"),
+        None => store::app_dir().join("snippets.json"),
+    }
+}
+
+pub fn load(project_root: Option<&Path>) -> Result<Vec<Snippet>> {
+    store::read_json(&path(project_root))
+}
+
+/// Insert or replace a snippet by id, returning it as stored.
+pub fn save(project_root: Option<&Path>, mut snippet: Snippet) -> Result<Snippet> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut all = load(project_root)?;
+    if snippet.id.is_empty() {
+        snippet.id = uuid::Uuid::new_v4().to_string();
+    }
+    match all.iter_mut().find(|s| s.id == snippet.id) {
+        Some(existing) => *existing = snippet.clone(),
+        None => all.push(snippet.clone()),
+    }
+    store::write_json(&path(project_root), &all)?;
+    Ok(snippet)
+}
+
+pub fn delete(project_root: Option<&Path>, id: &str) -> Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut all = load(project_root)?;
+    let index = all
+        .iter()
+        .position(|s| s.id == id)
+        .with_context(|| format!("snippet '{}' not found", id))?;
+    all.remove(index);
+    store::write_json(&path(project_root), &all)
+}
+
+/// Snippets attached to a run's role or ticket. A project snippet shadows a
+/// global one of the same name.
+pub fn attached(global: Vec<Snippet>, project: Vec<Snippet>, role: &str, ticket_id: &str) -> Vec<Snippet> {
+    let mut chosen: Vec<Snippet> = global
+        .into_iter()
+        .filter(|g| !project.iter().any(|p| p.name == g.name))
+        .collect();
+    chosen.extend(project);
+    chosen.retain(|s| s.applies_to(role, ticket_id));
+    chosen
+}
+
+/// The snippets for a run on `ticket_id` in `repo_root`. Unreadable
+/// libraries are skipped.
+pub fn for_run(repo_root: &Path, role: &str, ticket_id: &str) -> Vec<Snippet> {
+    let read = |root: Option<&Path>| {
+        load(root).unwrap_or_else(|e| {
+            log::warn!("[snippets::for_run] skipping {}: {:#}", path(root).display(), e);
+            Vec::new()
+        })
+    };
+    attached(read(None), read(Some(repo_root)), role, ticket_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, roles: &[&str], tickets: &[&str]) -> Snippet {
+        Snippet {
+            id: name.to_string(),
+            name: name.to_string(),
+            body: format!("{} body", name),
+            roles: roles.iter().map(|s| s.to_string()).collect(),
+            tickets: tickets.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn attaches_by_role_or_ticket_with_project_shadowing() {
+        let global = vec![
+            snippet("security checklist", &["backend-engineer"], &[]),
+            snippet("api rules", &["backend-engineer"], &[]),
+        ];
+        let project = vec![
+            snippet("api rules", &[], &["t-9"]),
+            snippet("migrations", &[], &["t-1"]),
+        ];
+        let names: Vec<String> = attached(global, project, "backend-engineer", "t-1")
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, ["security checklist", "migrations"]);
+    }
+
+    #[test]
+    fn save_assigns_ids_and_replaces_by_id() {
+        let root = std::env::temp_dir().join(format!("poietai-snippets-{}", uuid::Uuid::new_v4()));
+        let saved = save(Some(&root), Snippet { id: String::new(), ..snippet("api rules", &[], &[]) }).unwrap();
+        assert!(!saved.id.is_empty());
+        save(Some(&root), Snippet { body: "v2".to_string(), ..saved.clone() }).unwrap();
+        let all = load(Some(&root)).unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].body, "v2");
+        delete(Some(&root), &saved.id).unwrap();
+        assert!(load(Some(&root)).unwrap().is_empty());
+        assert!(delete(Some(&root), &saved.id).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::project::cache;
+use crate::project::settings::BootstrapConfig;
+
+/// Build the shell invocation for a bootstrap command, with stderr folded
+/// into stdout so progress from both streams arrives in order.
+pub(crate) fn shell_command(command: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", &format!("{} 2>&1", command)]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &format!("exec 2>&1\n{}", command)]);
+        cmd
+    }
+}
+
+/// Environment for the bootstrap command's cache sharing options.
+pub fn bootstrap_env(config: &BootstrapConfig, repo_root: &Path) -> Vec<(String, String)> {
+    let mut env = Vec::new();
+    if config.share_pnpm_store {
+        env.push((
+            "npm_config_store_dir".to_string(),
+            cache::pnpm_store_dir(repo_root).to_string_lossy().to_string(),
+        ));
+    }
+    env
+}
+
+/// Point `<worktree>/target` at the main checkout's `target/` so cargo reuses
+/// already-built dependencies. Skipped when the worktree already has one.
+fn link_target_dir(repo_root: &Path, worktree_path: &Path) -> Result<()> {
+    let shared = repo_root.join("target");
+    let link = worktree_path.join("target");
+    if link.exists() || link.symlink_metadata().is_ok() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(&shared)
+        .with_context(|| format!("failed to create {}", shared.display()))?;
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&shared, &link)
+        .with_context(|| format!("failed to link {}", link.display()))?;
+    #[cfg(windows)]
+    std::os::windows::fs::symlink_dir(&shared, &link)
+        .with_context(|| format!("failed to link {}", link.display()))?;
+
+    Ok(())
+}
+
+/// Prepare a fresh worktree for the agent: link shared caches, then run the
+/// project's bootstrap command (e.g. `pnpm install --frozen-lockfile`).
+///
+/// `env` is the agent environment (identity, shared caches) so installs land
+/// where the agent's builds will look. `on_progress` receives each line of
+/// command output.
+///
+/// Blocking — call via `spawn_blocking` from async contexts.
+pub fn run(
+    config: &BootstrapConfig,
+    repo_root: &Path,
+    worktree_path: &Path,
+    env: &[(String, String)],
+    mut on_progress: impl FnMut(&str),
+) -> Result<()> {
+    if config.link_target_dir {
+        link_target_dir(repo_root, worktree_path)?;
+    }
+
+    let command = config.command.trim();
+    if command.is_empty() {
+        return Ok(());
+    }
+
+    let mut child = shell_command(command)
+        .current_dir(worktree_path)
+        .envs(env.iter().cloned())
+        .envs(bootstrap_env(config, repo_root))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn bootstrap command `{}`", command))?;
+
+    let stdout = child.stdout.take().context("bootstrap command has no stdout")?;
+    let mut last_line = String::new();
+    for line in BufReader::new(stdout).lines() {
+        let line = line.context("failed to read bootstrap output")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        on_progress(&line);
+        last_line = line;
+    }
+
+    let status = child.wait().context("failed to wait for bootstrap command")?;
+    if !status.success() {
+        anyhow::bail!("bootstrap command `{}` failed ({}): {}", command, status, last_line);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("poietai-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_streams_output_and_reports_failure() {
+        let dir = temp_dir("bootstrap");
+        let mut config = BootstrapConfig {
+            command: "echo one; echo two >&2".to_string(),
+            ..Default::default()
+        };
+
+        let mut lines = Vec::new();
+        run(&config, &dir, &dir, &[], |l| lines.push(l.to_string())).unwrap();
+        assert_eq!(lines, vec!["one", "two"]);
+
+        config.command = "echo broken; exit 3".to_string();
+        let err = run(&config, &dir, &dir, &[], |_| {}).unwrap_err();
+        assert!(err.to_string().contains("broken"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_shared_target_dir() {
+        let root = temp_dir("bootstrap-root");
+        let worktree = root.join(".worktrees").join("t1");
+        std::fs::create_dir_all(&worktree).unwrap();
+
+        link_target_dir(&root, &worktree).unwrap();
+        assert_eq!(std::fs::read_link(worktree.join("target")).unwrap(), root.join("target"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
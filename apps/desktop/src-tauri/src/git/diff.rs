@@ -0,0 +1,321 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Component, Path};
+use std::process::Command;
+use ts_rs::TS;
+
+use super::drift;
+use crate::platform::exec;
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+    /// Line number on the base side (None for added lines).
+    pub old_line: Option<u32>,
+    /// Line number in the worktree (None for removed lines).
+    pub new_line: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Text after the `@@ … @@` marker, usually the enclosing function.
+    pub section: String,
+    pub lines: Vec<DiffLine>,
+}
+
+/// One file's changes between the base branch and the worktree, including
+/// uncommitted edits.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct FileDiff {
+    pub path: String,
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Reject absolute paths and `..` so callers can't read outside the worktree.
+pub(crate) fn check_path(path: &str) -> Result<()> {
+    let escapes = Path::new(path)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if path.is_empty() || escapes {
+        anyhow::bail!("'{}' is not a path inside the worktree", path);
+    }
+    Ok(())
+}
+
+fn git(worktree: &Path, args: &[&str]) -> Result<std::process::Output> {
+    let mut cmd = Command::new("git");
+    cmd.args(args).current_dir(worktree);
+    exec::output_blocking(&mut cmd, exec::LOCAL)
+        .with_context(|| format!("failed to run git {}", args.join(" ")))
+}
+
+/// The commit the worktree branched from: the merge base with the repo's
+/// default branch, remote or local, or HEAD when there's neither to compare
+/// against.
+pub fn base_commit(worktree: &Path) -> Result<String> {
+    let remote = drift::origin_default(worktree);
+    let local = remote.trim_start_matches("origin/").to_string();
+    for branch in [remote, local] {
+        let output = git(worktree, &["merge-base", &branch, "HEAD"])?;
+        let base = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !base.is_empty() {
+            return Ok(base);
+        }
+    }
+    let output = git(worktree, &["rev-parse", "HEAD"])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git rev-parse HEAD failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `path`'s contents at the base commit, or None if it didn't exist there.
+pub fn file_at_base(worktree: &Path, path: &str) -> Result<Option<String>> {
+    check_path(path)?;
+    let base = base_commit(worktree)?;
+    let output = git(worktree, &["show", &format!("{}:{}", base, path)])?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
+/// Structured diff of `path` from the base commit to the worktree as it is
+/// now. Untracked files show as entirely added.
+pub fn file_diff(worktree: &Path, path: &str) -> Result<FileDiff> {
+    check_path(path)?;
+    let base = base_commit(worktree)?;
+    let output = git(worktree, &["diff", "--no-color", &base, "--", path])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    if text.is_empty() && is_untracked(worktree, path)? {
+        // Exits 1 when the files differ, so the status isn't checked
+        let untracked = git(
+            worktree,
+            &["diff", "--no-color", "--no-index", "--", "/dev/null", path],
+        )?;
+        text = String::from_utf8_lossy(&untracked.stdout).to_string();
+    }
+    Ok(parse(path, &text))
+}
+
+/// Whether `path` is a new file git doesn't track yet (and doesn't ignore).
+fn is_untracked(worktree: &Path, path: &str) -> Result<bool> {
+    let output = git(
+        worktree,
+        &["ls-files", "--others", "--exclude-standard", "--", path],
+    )?;
+    Ok(!output.stdout.trim_ascii().is_empty())
+}
+
+/// Everything the worktree changed since its base: the unified diff of
+/// tracked files (committed or not) and the paths of new untracked files.
+pub fn worktree_changes(worktree: &Path) -> Result<(String, Vec<String>)> {
+    let base = base_commit(worktree)?;
+    let diff = git(worktree, &["diff", "--no-color", &base])?;
+    if !diff.status.success() {
+        anyhow::bail!("git diff failed: {}", String::from_utf8_lossy(&diff.stderr));
+    }
+    let untracked = git(worktree, &["ls-files", "--others", "--exclude-standard"])?;
+    Ok((
+        String::from_utf8_lossy(&diff.stdout).to_string(),
+        String::from_utf8_lossy(&untracked.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+    ))
+}
+
+/// `@@ -a,b +c,d @@ section` → (a, b, c, d, section). Counts default to 1.
+fn parse_hunk_header(line: &str) -> Option<(u32, u32, u32, u32, String)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |r: &str| -> Option<(u32, u32)> {
+        match r.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((r.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+    Some((
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section.trim().to_string(),
+    ))
+}
+
+/// Parse `git diff` output for a single file into hunks.
+pub fn parse(path: &str, diff: &str) -> FileDiff {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut binary = false;
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in diff.lines() {
+        if let Some((old_start, old_lines, new_start, new_lines, section)) = parse_hunk_header(line)
+        {
+            (old_line, new_line) = (old_start, new_start);
+            hunks.push(DiffHunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                section,
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            // File header lines before the first hunk
+            binary |= line.starts_with("Binary files ");
+            continue;
+        };
+        let (kind, content) = match line.split_at_checked(1) {
+            Some(("+", rest)) => (DiffLineKind::Added, rest),
+            Some(("-", rest)) => (DiffLineKind::Removed, rest),
+            Some((" ", rest)) => (DiffLineKind::Context, rest),
+            // "\ No newline at end of file" and anything unexpected
+            _ => continue,
+        };
+        let (old, new) = match kind {
+            DiffLineKind::Added => (None, Some(new_line)),
+            DiffLineKind::Removed => (Some(old_line), None),
+            DiffLineKind::Context => (Some(old_line), Some(new_line)),
+        };
+        old_line += old.is_some() as u32;
+        new_line += new.is_some() as u32;
+        hunk.lines.push(DiffLine {
+            kind,
+            content: content.to_string(),
+            old_line: old,
+            new_line: new,
+        });
+    }
+    FileDiff {
+        path: path.to_string(),
+        binary,
+        hunks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "diff --git a/src/retry.rs b/src/retry.rs
+index 1111111..2222222 100644
+--- a/src/retry.rs
++++ b/src/retry.rs
+@@ -10,3 +10,4 @@ fn backoff() {
+     let base = 2;
+-    let n = 3;
++    let n = 5;
++    let jitter = true;
+ }
+";
+
+    #[test]
+    fn parses_hunks_with_line_numbers() {
+        let diff = parse("src/retry.rs", SAMPLE);
+        assert!(!diff.binary);
+        assert_eq!(diff.hunks.len(), 1);
+        let hunk = &diff.hunks[0];
+        assert_eq!(
+            (
+                hunk.old_start,
+                hunk.old_lines,
+                hunk.new_start,
+                hunk.new_lines
+            ),
+            (10, 3, 10, 4)
+        );
+        assert_eq!(hunk.section, "fn backoff() {");
+        let kinds: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.old_line, l.new_line))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Some(10), Some(10)),
+                (Some(11), None),
+                (None, Some(11)),
+                (None, Some(12)),
+                (Some(12), Some(13))
+            ]
+        );
+        assert_eq!(hunk.lines[2].content, "    let n = 5;");
+    }
+
+    #[test]
+    fn hunk_header_counts_default_to_one() {
+        assert_eq!(
+            parse_hunk_header("@@ -1 +1,2 @@"),
+            Some((1, 1, 1, 2, String::new()))
+        );
+    }
+
+    #[test]
+    fn binary_files_are_flagged() {
+        let diff = parse(
+            "logo.png",
+            "diff --git a/logo.png b/logo.png\nBinary files a/logo.png and b/logo.png differ\n",
+        );
+        assert!(diff.binary && diff.hunks.is_empty());
+    }
+
+    #[test]
+    fn unchanged_tracked_files_have_an_empty_diff() {
+        let dir = std::env::temp_dir().join(format!("poietai-diff-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let run = |args: &[&str]| assert!(git(&dir, args).unwrap().status.success());
+        run(&["init", "-q"]);
+        std::fs::write(dir.join("kept.txt"), "same\n").unwrap();
+        run(&["add", "kept.txt"]);
+        let identity = ["-c", "user.name=t", "-c", "user.email=t@t"];
+        run(&[&identity[..], &["commit", "-qm", "x"]].concat());
+        std::fs::write(dir.join("new.txt"), "fresh\n").unwrap();
+
+        assert!(file_diff(&dir, "kept.txt").unwrap().hunks.is_empty());
+        let added = file_diff(&dir, "new.txt").unwrap();
+        assert_eq!(added.hunks.len(), 1);
+        assert_eq!(added.hunks[0].lines[0].kind, DiffLineKind::Added);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_worktree() {
+        assert!(check_path("../secrets").is_err());
+        assert!(check_path("/etc/passwd").is_err());
+        assert!(check_path("src/lib.rs").is_ok());
+    }
+}
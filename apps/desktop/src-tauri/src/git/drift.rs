@@ -0,0 +1,242 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use crate::agent::state;
+use crate::platform::exec;
+
+/// How often active worktrees are checked against origin.
+const CHECK_INTERVAL_SECS: u64 = 10 * 60;
+
+/// When a worktree counts as too far behind origin's default branch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct DriftConfig {
+    /// Commits on origin the worktree's branch doesn't have.
+    pub max_commits: u32,
+    /// Days since the oldest of those commits landed.
+    pub max_days: u32,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            max_commits: 50,
+            max_days: 7,
+        }
+    }
+}
+
+/// Where a worktree stands against origin's default branch.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct WorktreeDrift {
+    /// e.g. "origin/main".
+    pub base: String,
+    pub behind: u32,
+    pub days_behind: u32,
+    pub stale: bool,
+    /// Shell command that brings the worktree up to date.
+    pub rebase_command: String,
+}
+
+/// Payload for `worktree-stale`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorktreeStalePayload {
+    pub agent_id: String,
+    pub ticket_id: Option<String>,
+    pub worktree_path: String,
+    pub drift: WorktreeDrift,
+}
+
+/// Run git in `worktree` for its trimmed stdout. Blocking.
+pub(crate) fn git(worktree: &Path, args: &[&str]) -> Result<String> {
+    let mut cmd = Command::new("git");
+    cmd.args(args).current_dir(worktree);
+    let output = exec::output_blocking(&mut cmd, exec::NETWORK)?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `origin/<default branch>`, from origin's HEAD when git knows it,
+/// `origin/main` otherwise.
+pub fn origin_default(repo: &Path) -> String {
+    git(repo, &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"])
+        .ok()
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "origin/main".to_string())
+}
+
+fn measure(
+    config: &DriftConfig,
+    base: &str,
+    worktree: &Path,
+    behind: u32,
+    oldest: Option<i64>,
+    now: i64,
+) -> WorktreeDrift {
+    let days_behind = oldest.map_or(0, |at| ((now - at).max(0) / 86_400) as u32);
+    WorktreeDrift {
+        base: base.to_string(),
+        behind,
+        days_behind,
+        stale: behind > 0 && (behind > config.max_commits || days_behind > config.max_days),
+        rebase_command: format!(
+            "git -C '{}' fetch origin && git -C '{}' rebase {}",
+            worktree.display(),
+            worktree.display(),
+            base
+        ),
+    }
+}
+
+/// Fetch origin and measure how far the worktree's branch has fallen behind
+/// its default branch. Blocking.
+pub fn check(config: &DriftConfig, worktree: &Path) -> Result<WorktreeDrift> {
+    let base = origin_default(worktree);
+    let branch = base.strip_prefix("origin/").unwrap_or(&base);
+    git(worktree, &["fetch", "--quiet", "origin", branch])?;
+    let range = format!("HEAD..{}", base);
+    let behind: u32 = git(worktree, &["rev-list", "--count", &range])?
+        .parse()
+        .context("unexpected git rev-list output")?;
+    let oldest = git(worktree, &["log", "--reverse", "--format=%ct", &range])?
+        .lines()
+        .next()
+        .and_then(|t| t.parse().ok());
+    Ok(measure(
+        config,
+        &base,
+        worktree,
+        behind,
+        oldest,
+        chrono::Utc::now().timestamp(),
+    ))
+}
+
+/// Rebase a worktree onto origin's default branch, aborting on conflicts so
+/// the worktree is left as it was.
+pub fn rebase(worktree: &Path) -> Result<()> {
+    let base = origin_default(worktree);
+    let branch = base.strip_prefix("origin/").unwrap_or(&base);
+    git(worktree, &["fetch", "--quiet", "origin", branch])?;
+    if let Err(e) = git(worktree, &["rebase", &base]) {
+        let _ = git(worktree, &["rebase", "--abort"]);
+        return Err(e.context(format!(
+            "rebase onto {} hit conflicts and was aborted",
+            base
+        )));
+    }
+    Ok(())
+}
+
+/// Background loop: check every active worktree and emit `worktree-stale`
+/// when one goes stale, and again each time it doubles how far behind it is.
+pub async fn schedule(app: AppHandle) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+    // agent id → how far behind it was when last warned
+    let mut warned: HashMap<String, u32> = HashMap::new();
+    loop {
+        ticker.tick().await;
+        let agents = state::all_agents(&app.state::<crate::AppState>().agents).await;
+        for agent in agents.into_iter().filter(|a| a.current_ticket_id.is_some()) {
+            let Some(worktree) = agent.worktree_path.clone() else {
+                continue;
+            };
+            let drift = tokio::task::spawn_blocking(move || {
+                let path = Path::new(&worktree);
+                let config = crate::agent::backend::project_settings_for(path).drift;
+                check(&config, path).map(|drift| (worktree, drift))
+            })
+            .await;
+            let (worktree, drift) = match drift {
+                Ok(Ok(found)) => found,
+                Ok(Err(e)) => {
+                    warn!("[drift::schedule] agent={}: {:#}", agent.id, e);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("[drift::schedule] check panicked: {}", e);
+                    continue;
+                }
+            };
+            if !drift.stale {
+                warned.remove(&agent.id);
+                continue;
+            }
+            if warned
+                .get(&agent.id)
+                .is_some_and(|&at| drift.behind < at * 2)
+            {
+                continue;
+            }
+            info!(
+                "[drift::schedule] agent={} is {} commits / {} days behind {}",
+                agent.id, drift.behind, drift.days_behind, drift.base
+            );
+            warned.insert(agent.id.clone(), drift.behind);
+            let _ = app.emit(
+                "worktree-stale",
+                &WorktreeStalePayload {
+                    agent_id: agent.id,
+                    ticket_id: agent.current_ticket_id,
+                    worktree_path: worktree,
+                    drift,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    #[test]
+    fn stale_past_either_threshold() {
+        let config = DriftConfig::default();
+        let path = Path::new("/repo/.worktrees/t-1");
+        let now = 100 * DAY;
+
+        let fresh = measure(&config, "origin/main", path, 12, Some(now - 2 * DAY), now);
+        assert_eq!(
+            (fresh.behind, fresh.days_behind, fresh.stale),
+            (12, 2, false)
+        );
+        assert!(measure(&config, "origin/main", path, 51, Some(now - DAY), now).stale);
+        assert!(measure(&config, "origin/main", path, 3, Some(now - 8 * DAY), now).stale);
+        assert!(!measure(&config, "origin/main", path, 0, None, now).stale);
+    }
+
+    #[test]
+    fn rebase_command_targets_the_worktree() {
+        let drift = measure(
+            &DriftConfig::default(),
+            "origin/develop",
+            Path::new("/r/.worktrees/t-1"),
+            1,
+            None,
+            0,
+        );
+        assert_eq!(
+            drift.rebase_command,
+            "git -C '/r/.worktrees/t-1' fetch origin && git -C '/r/.worktrees/t-1' rebase origin/develop"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::platform::exec;
+
+/// Whether the checkout tracks any paths with Git LFS, judged by
+/// `filter=lfs` entries in the top-level `.gitattributes`.
+pub fn uses_lfs(path: &Path) -> bool {
+    std::fs::read_to_string(path.join(".gitattributes"))
+        .map(|contents| contents.lines().any(is_lfs_attribute))
+        .unwrap_or(false)
+}
+
+fn is_lfs_attribute(line: &str) -> bool {
+    let line = line.trim();
+    !line.starts_with('#') && line.split_whitespace().any(|attr| attr == "filter=lfs")
+}
+
+/// Split a chunk of `git lfs` progress output into messages. LFS redraws its
+/// progress line with `\r`, so both `\r` and `\n` end a message.
+fn progress_lines(chunk: &str) -> impl Iterator<Item = &str> {
+    chunk.split(['\r', '\n']).map(str::trim).filter(|l| !l.is_empty())
+}
+
+/// Install the LFS hooks for the repo and download LFS objects for the
+/// worktree's checkout, replacing pointer files with real content.
+///
+/// `on_progress` receives each progress line `git lfs pull` prints.
+///
+/// Blocking — call via `spawn_blocking` from async contexts.
+pub fn pull(worktree_path: &Path, mut on_progress: impl FnMut(&str)) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["lfs", "install", "--local"])
+        .current_dir(worktree_path);
+    let install = exec::output_blocking(&mut cmd, exec::LOCAL)
+        .context("failed to run git lfs install — is git-lfs installed?")?;
+    if !install.status.success() {
+        let stderr = String::from_utf8_lossy(&install.stderr);
+        anyhow::bail!("git lfs install failed: {}", stderr.trim());
+    }
+
+    let mut child = Command::new("git")
+        .args(["lfs", "pull"])
+        .current_dir(worktree_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to spawn git lfs pull")?;
+
+    let mut stderr = child.stderr.take().context("git lfs pull has no stderr")?;
+    let mut last_line = String::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stderr.read(&mut buf).context("failed to read git lfs output")?;
+        if n == 0 {
+            break;
+        }
+        for line in progress_lines(&String::from_utf8_lossy(&buf[..n])) {
+            on_progress(line);
+            last_line = line.to_string();
+        }
+    }
+
+    let status = child.wait().context("failed to wait for git lfs pull")?;
+    if !status.success() {
+        anyhow::bail!("git lfs pull failed: {}", last_line);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_lfs_attributes() {
+        assert!(is_lfs_attribute("*.psd filter=lfs diff=lfs merge=lfs -text"));
+        assert!(!is_lfs_attribute("# *.psd filter=lfs"));
+        assert!(!is_lfs_attribute("*.rs text eol=lf"));
+    }
+
+    #[test]
+    fn splits_carriage_return_progress() {
+        let chunk = "Downloading LFS objects:  10% (1/10)\rDownloading LFS objects:  20% (2/10)\r\n";
+        let lines: Vec<&str> = progress_lines(chunk).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("20%"));
+    }
+}
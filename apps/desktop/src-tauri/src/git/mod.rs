@@ -1,2 +1,7 @@
+pub mod bootstrap;
+pub mod diff;
+pub mod drift;
+pub mod lfs;
 pub mod scan;
+pub mod watch;
 pub mod worktree;
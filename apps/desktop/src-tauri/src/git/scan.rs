@@ -2,7 +2,12 @@ use serde::Serialize;
 use std::path::Path;
 use std::process::Command;
 
-#[derive(Serialize, Debug)]
+use crate::platform::{exec, paths};
+use crate::project::settings::ProviderHost;
+use ts_rs::TS;
+
+#[derive(Serialize, Debug, TS)]
+#[ts(export)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum FolderScanResult {
     SingleRepo {
@@ -10,6 +15,10 @@ pub enum FolderScanResult {
         repo_root: String,
         remote_url: Option<String>,
         provider: Option<String>,
+        #[serde(flatten)]
+        status: RepoStatus,
+        #[serde(flatten)]
+        layout: RepoLayout,
     },
     MultiRepo {
         repos: Vec<RepoInfo>,
@@ -18,12 +27,131 @@ pub enum FolderScanResult {
     NoRepo,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, TS)]
+#[ts(export)]
 pub struct RepoInfo {
     pub name: String,
     pub repo_root: String,
     pub remote_url: Option<String>,
     pub provider: Option<String>,
+    #[serde(flatten)]
+    pub status: RepoStatus,
+    #[serde(flatten)]
+    pub layout: RepoLayout,
+}
+
+/// Repositories-within-the-repository that need special handling.
+#[derive(Serialize, Debug, Default, PartialEq, TS)]
+#[ts(export)]
+pub struct RepoLayout {
+    /// Submodule paths declared in `.gitmodules`. Fresh worktrees need
+    /// `git submodule update --init` before these contain any code.
+    pub submodules: Vec<String>,
+    /// Top-level directories that are independent git repos but not
+    /// submodules. These never appear in worktrees.
+    pub nested_repos: Vec<String>,
+    /// Tracks files with Git LFS — worktrees need a `git lfs pull`.
+    pub uses_lfs: bool,
+}
+
+/// Extract submodule paths from the contents of a `.gitmodules` file.
+pub fn parse_gitmodules(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            if key.trim() == "path" {
+                Some(value.trim().to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Detect submodules and nested (non-submodule) repos one level below `path`.
+pub fn get_layout(path: &Path) -> RepoLayout {
+    let submodules = std::fs::read_to_string(path.join(".gitmodules"))
+        .map(|c| parse_gitmodules(&c))
+        .unwrap_or_default();
+
+    let mut nested_repos = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" || name == ".worktrees" || submodules.contains(&name) {
+                continue;
+            }
+            let sub = entry.path();
+            if sub.is_dir() && sub.join(".git").exists() {
+                nested_repos.push(name);
+            }
+        }
+    }
+    nested_repos.sort();
+
+    RepoLayout {
+        submodules,
+        nested_repos,
+        uses_lfs: super::lfs::uses_lfs(path),
+    }
+}
+
+/// Working-copy state, so the UI can warn about dirty or stale repos before
+/// agents branch from them.
+#[derive(Serialize, Debug, Default, PartialEq, TS)]
+#[ts(export)]
+pub struct RepoStatus {
+    /// Current branch; None when HEAD is detached.
+    pub branch: Option<String>,
+    /// Upstream tracking branch, e.g. "origin/main".
+    pub upstream: Option<String>,
+    /// Commits on the branch not yet on upstream.
+    pub ahead: u32,
+    /// Commits on upstream not yet on the branch.
+    pub behind: u32,
+    /// Modified, staged, or conflicted tracked files.
+    pub has_uncommitted_changes: bool,
+    pub has_untracked_files: bool,
+}
+
+/// Parse `git status --porcelain=v2 --branch` output.
+pub fn parse_status(porcelain: &str) -> RepoStatus {
+    let mut status = RepoStatus::default();
+    for line in porcelain.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            if head != "(detached)" {
+                status.branch = Some(head.to_string());
+            }
+        } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if line.starts_with("1 ") || line.starts_with("2 ") || line.starts_with("u ") {
+            status.has_uncommitted_changes = true;
+        } else if line.starts_with("? ") {
+            status.has_untracked_files = true;
+        }
+    }
+    status
+}
+
+/// Read branch, ahead/behind, and dirty state for a repo.
+/// Ahead/behind reflect the last fetch — no network access happens here.
+pub fn get_status(path: &Path) -> RepoStatus {
+    let mut cmd = Command::new("git");
+    cmd.args(["status", "--porcelain=v2", "--branch"]).current_dir(path);
+    exec::output_blocking(&mut cmd, exec::LOCAL)
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_status(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
 }
 
 pub fn detect_provider(remote_url: &str) -> Option<&'static str> {
@@ -40,18 +168,47 @@ pub fn detect_provider(remote_url: &str) -> Option<&'static str> {
     }
 }
 
+/// Extract the hostname from an HTTPS or SCP-style SSH remote URL.
+pub fn remote_host(remote_url: &str) -> Option<&str> {
+    let rest = match remote_url.split_once("://") {
+        Some((_, rest)) => rest,
+        // scp-style: git@host:owner/repo.git
+        None => remote_url.split_once(':')?.0,
+    };
+    let authority = rest.split('/').next()?;
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+/// Like `detect_provider`, but consults the user-defined host table first so
+/// self-hosted instances (e.g. git.example.com → gitlab) are recognised.
+pub fn detect_provider_with(remote_url: &str, hosts: &[ProviderHost]) -> Option<String> {
+    if let Some(host) = remote_host(remote_url) {
+        if let Some(mapping) = hosts.iter().find(|h| h.host.eq_ignore_ascii_case(host)) {
+            return Some(mapping.provider.clone());
+        }
+    }
+    detect_provider(remote_url).map(String::from)
+}
+
 pub fn get_remote_url(path: &Path) -> Option<String> {
-    Command::new("git")
-        .args(["remote", "get-url", "origin"])
-        .current_dir(path)
-        .output()
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "get-url", "origin"]).current_dir(path);
+    exec::output_blocking(&mut cmd, exec::LOCAL)
         .ok()
         .filter(|o| o.status.success())
         .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
         .filter(|s| !s.is_empty())
 }
 
-pub fn scan_folder(path: &Path) -> FolderScanResult {
+pub fn scan_folder(path: &Path, hosts: &[ProviderHost]) -> FolderScanResult {
+    // One spelling, so the repo roots it reports match worktree and git paths
+    let path = &paths::normalize(path);
     // Case 1: path itself is a git repo
     if path.join(".git").exists() {
         let name = path
@@ -61,13 +218,14 @@ pub fn scan_folder(path: &Path) -> FolderScanResult {
         let remote_url = get_remote_url(path);
         let provider = remote_url
             .as_deref()
-            .and_then(detect_provider)
-            .map(String::from);
+            .and_then(|url| detect_provider_with(url, hosts));
         return FolderScanResult::SingleRepo {
             name,
             repo_root: path.to_string_lossy().to_string(),
             remote_url,
             provider,
+            status: get_status(path),
+            layout: get_layout(path),
         };
     }
 
@@ -83,13 +241,14 @@ pub fn scan_folder(path: &Path) -> FolderScanResult {
                 let remote_url = get_remote_url(&sub);
                 let provider = remote_url
                     .as_deref()
-                    .and_then(detect_provider)
-                    .map(String::from);
+                    .and_then(|url| detect_provider_with(url, hosts));
                 repos.push(RepoInfo {
                     name,
                     repo_root: sub.to_string_lossy().to_string(),
                     remote_url,
                     provider,
+                    status: get_status(&sub),
+                    layout: get_layout(&sub),
                 });
             }
         }
@@ -157,4 +316,68 @@ mod tests {
     fn returns_none_for_unknown_host() {
         assert_eq!(detect_provider("https://custom-git.company.com/repo"), None);
     }
+
+    #[test]
+    fn remote_host_parses_https_and_ssh() {
+        assert_eq!(remote_host("https://git.example.com/team/repo.git"), Some("git.example.com"));
+        assert_eq!(remote_host("git@git.example.com:team/repo.git"), Some("git.example.com"));
+        assert_eq!(remote_host("ssh://git@git.example.com:2222/team/repo"), Some("git.example.com"));
+    }
+
+    #[test]
+    fn user_host_table_maps_self_hosted_gitlab() {
+        let hosts = vec![ProviderHost {
+            host: "custom-git.company.com".to_string(),
+            provider: "gitlab".to_string(),
+        }];
+        assert_eq!(
+            detect_provider_with("git@custom-git.company.com:team/repo.git", &hosts),
+            Some("gitlab".to_string())
+        );
+        assert_eq!(
+            detect_provider_with("https://github.com/user/repo", &hosts),
+            Some("github".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_clean_tracking_branch() {
+        let out = "# branch.oid abc123\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +2 -5\n";
+        let status = parse_status(out);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!(status.ahead, 2);
+        assert_eq!(status.behind, 5);
+        assert!(!status.has_uncommitted_changes);
+        assert!(!status.has_untracked_files);
+    }
+
+    #[test]
+    fn parses_dirty_detached_head() {
+        let out = "# branch.oid abc123\n# branch.head (detached)\n1 .M N... 100644 100644 100644 a b src/lib.rs\n? notes.txt\n";
+        let status = parse_status(out);
+        assert_eq!(status.branch, None);
+        assert!(status.has_uncommitted_changes);
+        assert!(status.has_untracked_files);
+    }
+
+    #[test]
+    fn parses_gitmodules_paths() {
+        let contents = "[submodule \"vendor/lib\"]\n\tpath = vendor/lib\n\turl = https://github.com/x/lib.git\n[submodule \"docs\"]\n\tpath = docs\n\turl = ../docs.git\n";
+        assert_eq!(parse_gitmodules(contents), vec!["vendor/lib", "docs"]);
+    }
+
+    #[test]
+    fn layout_reports_nested_repos_but_not_submodules() {
+        let root = std::env::temp_dir().join(format!("poietai-layout-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("docs").join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("tools").join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join(".gitmodules"), "[submodule \"docs\"]\n\tpath = docs\n").unwrap();
+
+        let layout = get_layout(&root);
+        assert_eq!(layout.submodules, vec!["docs"]);
+        assert_eq!(layout.nested_repos, vec!["tools"]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
 }
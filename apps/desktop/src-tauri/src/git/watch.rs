@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+use crate::platform::paths;
+
+/// Changes arriving within this window are sent as one event — an agent
+/// rewriting a file produces a burst of create/modify/rename notifications.
+const BATCH_WINDOW: Duration = Duration::from_millis(200);
+
+/// Directories whose churn isn't the agent's edits.
+const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target", ".claude"];
+
+/// Payload for `worktree-file-changed`.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct WorktreeFileChangedPayload {
+    pub agent_id: String,
+    pub worktree_path: String,
+    /// Paths relative to the worktree, sorted and deduplicated.
+    pub paths: Vec<String>,
+}
+
+/// Active watchers keyed by agent id. Dropping a watcher stops it.
+pub type WatcherStore = Arc<Mutex<HashMap<String, RecommendedWatcher>>>;
+
+pub fn new_store() -> WatcherStore {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// The worktree-relative path of a change, or None if it should be ignored.
+fn relevant(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    let ignored = rel
+        .components()
+        .any(|c| IGNORED_DIRS.contains(&c.as_os_str().to_string_lossy().as_ref()));
+    if ignored || rel.as_os_str().is_empty() {
+        return None;
+    }
+    Some(paths::to_slash(rel))
+}
+
+/// Start emitting `worktree-file-changed` for `worktree`. Replaces any
+/// watcher already running for the agent.
+pub fn watch(store: &WatcherStore, app: AppHandle, agent_id: &str, worktree: PathBuf) -> Result<()> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        match res {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                let _ = tx.send(event.paths);
+            }
+            Ok(_) => {}
+            Err(e) => warn!("[watch] watcher error: {}", e),
+        }
+    })
+    .context("failed to create file watcher")?;
+    watcher
+        .watch(&worktree, RecursiveMode::Recursive)
+        .with_context(|| format!("failed to watch {}", worktree.display()))?;
+
+    let agent = agent_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        // Ends when the watcher (and with it the sender) is dropped
+        while let Some(first) = rx.recv().await {
+            let mut changed = BTreeSet::new();
+            let mut add = |paths: Vec<PathBuf>| {
+                changed.extend(paths.iter().filter_map(|p| relevant(&worktree, p)));
+            };
+            add(first);
+            let deadline = tokio::time::Instant::now() + BATCH_WINDOW;
+            while let Ok(Some(paths)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+                add(paths);
+            }
+            if changed.is_empty() {
+                continue;
+            }
+            let _ = app.emit(
+                "worktree-file-changed",
+                WorktreeFileChangedPayload {
+                    agent_id: agent.clone(),
+                    worktree_path: worktree.to_string_lossy().to_string(),
+                    paths: changed.into_iter().collect(),
+                },
+            );
+        }
+    });
+
+    info!("[watch] agent={} watching worktree", agent_id);
+    store.lock().unwrap().insert(agent_id.to_string(), watcher);
+    Ok(())
+}
+
+/// Stop watching the agent's worktree. Returns false if none was watched.
+pub fn unwatch(store: &WatcherStore, agent_id: &str) -> bool {
+    store.lock().unwrap().remove(agent_id).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_git_and_build_dirs() {
+        let root = Path::new("/wt");
+        assert_eq!(relevant(root, Path::new("/wt/src/lib.rs")), Some("src/lib.rs".to_string()));
+        assert_eq!(relevant(root, Path::new("/wt/.git/index")), None);
+        assert_eq!(relevant(root, Path::new("/wt/web/node_modules/x.js")), None);
+        assert_eq!(relevant(root, Path::new("/wt")), None);
+        assert_eq!(relevant(root, Path::new("/elsewhere/a")), None);
+    }
+}
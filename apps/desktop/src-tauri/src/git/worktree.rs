@@ -1,6 +1,15 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::Output;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::platform::{exec, paths};
+use crate::project::cache::{self, CacheConfig};
+
+/// Longest a submodule checkout, which fetches every submodule, may take.
+const SUBMODULE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
 
 /// Configuration for a new worktree.
 pub struct WorktreeConfig {
@@ -14,6 +23,10 @@ pub struct WorktreeConfig {
     pub agent_name: String,
     /// Agent email for git commits.
     pub agent_email: String,
+    /// Run `git submodule update --init --recursive` after creating the worktree.
+    pub init_submodules: bool,
+    /// Shared build caches to point the agent's toolchains at.
+    pub cache: CacheConfig,
 }
 
 /// A created worktree, ready for agent use.
@@ -34,52 +47,60 @@ impl Worktree {
     /// The worktree directory path.
     /// Format: <repo_root>/.worktrees/<ticket-id>
     pub fn path_for(repo_root: &Path, ticket_id: &str) -> PathBuf {
-        repo_root.join(".worktrees").join(ticket_id)
+        paths::normalize(repo_root).join(".worktrees").join(ticket_id)
     }
 }
 
+/// Run git in `dir` and wait for it, killing it once `limit` passes or the
+/// caller stops waiting.
+async fn git<I, S>(dir: &Path, args: I, limit: Duration) -> Result<Output>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut cmd = Command::new("git");
+    cmd.args(args).current_dir(dir);
+    Ok(exec::output(&mut cmd, limit).await?)
+}
+
 /// Create a new git worktree for a ticket.
 ///
 /// Uses `-B` so the branch is created or reset if it already exists
 /// (e.g. from a previous failed attempt). Prunes any stale worktree
 /// at the target path before adding.
-pub fn create(config: &WorktreeConfig) -> Result<Worktree> {
+pub async fn create(config: &WorktreeConfig) -> Result<Worktree> {
     let branch = Worktree::branch_for(&config.ticket_slug);
     let path = Worktree::path_for(&config.repo_root, &config.ticket_id);
+    let repo_root = config.repo_root.as_path();
 
     // If the path already exists, clean it up so git can create a fresh worktree.
     // We try two things:
     //  1. `git worktree remove --force` — deregisters it and deletes the dir if
     //     git knows about it.
-    //  2. `std::fs::remove_dir_all` — handles the case where the directory is
+    //  2. `remove_dir_all` — handles the case where the directory is
     //     orphaned (not in git's registry, e.g. left over from a previous run on
     //     a different OS/path), which causes `git worktree add` to fail with
     //     "fatal: '...' already exists".
     if path.exists() {
-        let _ = Command::new("git")
-            .args(["worktree", "remove", "--force"])
-            .arg(&path)
-            .current_dir(&config.repo_root)
-            .output();
+        let remove = [
+            OsStr::new("worktree"),
+            OsStr::new("remove"),
+            OsStr::new("--force"),
+            path.as_os_str(),
+        ];
+        let _ = git(repo_root, remove, exec::LOCAL).await;
         // Remove the directory regardless — git may not have cleaned it up if
         // the path wasn't in its registry.
-        let _ = std::fs::remove_dir_all(&path);
+        let _ = tokio::fs::remove_dir_all(&path).await;
     }
 
     // Prune any stale entries left in .git/worktrees/.
-    let _ = Command::new("git")
-        .args(["worktree", "prune"])
-        .current_dir(&config.repo_root)
-        .output();
+    let _ = git(repo_root, ["worktree", "prune"], exec::LOCAL).await;
 
     // If another worktree has the target branch checked out (from a previous run
     // with a different ticket UUID but the same slug), remove it first.
     // `git worktree add -B` won't work if the branch is checked out elsewhere.
-    if let Ok(list_out) = Command::new("git")
-        .args(["worktree", "list", "--porcelain"])
-        .current_dir(&config.repo_root)
-        .output()
-    {
+    if let Ok(list_out) = git(repo_root, ["worktree", "list", "--porcelain"], exec::LOCAL).await {
         let list_text = String::from_utf8_lossy(&list_out.stdout);
         let mut current_wt_path: Option<String> = None;
         for line in list_text.lines() {
@@ -89,10 +110,8 @@ pub fn create(config: &WorktreeConfig) -> Result<Worktree> {
                 if b == branch {
                     // This worktree has our branch — remove it
                     if let Some(ref wt_path) = current_wt_path {
-                        let _ = Command::new("git")
-                            .args(["worktree", "remove", "--force", wt_path])
-                            .current_dir(&config.repo_root)
-                            .output();
+                        let remove = ["worktree", "remove", "--force", wt_path.as_str()];
+                        let _ = git(repo_root, remove, exec::LOCAL).await;
                     }
                 }
             } else if line.is_empty() {
@@ -101,21 +120,27 @@ pub fn create(config: &WorktreeConfig) -> Result<Worktree> {
         }
     }
 
-    let output = Command::new("git")
-        .arg("worktree")
-        .arg("add")
-        .arg(&path)
-        .arg("-B")       // reset branch if it already exists
-        .arg(&branch)
-        .current_dir(&config.repo_root)
-        .output()
-        .context("failed to run git worktree add")?;
+    // -B resets the branch if it already exists
+    let add = [
+        OsStr::new("worktree"),
+        OsStr::new("add"),
+        path.as_os_str(),
+        OsStr::new("-B"),
+        OsStr::new(&branch),
+    ];
+    let output = git(repo_root, add, exec::LOCAL).await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         anyhow::bail!("git worktree add failed: {}", stderr);
     }
 
+    // Worktrees start with empty submodule directories — populate them so the
+    // agent can actually build the code.
+    if config.init_submodules && path.join(".gitmodules").exists() {
+        init_submodules(&path).await?;
+    }
+
     Ok(Worktree {
         path,
         branch,
@@ -123,18 +148,30 @@ pub fn create(config: &WorktreeConfig) -> Result<Worktree> {
     })
 }
 
+/// Initialise and check out all submodules (recursively) in a worktree.
+pub async fn init_submodules(worktree_path: &Path) -> Result<()> {
+    let args = ["submodule", "update", "--init", "--recursive"];
+    let output = git(worktree_path, args, SUBMODULE_TIMEOUT).await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git submodule update --init failed: {}", stderr);
+    }
+
+    Ok(())
+}
+
 /// Remove a worktree after the ticket is done.
 ///
 /// Equivalent to: git worktree remove <path> --force
-pub fn remove(repo_root: &Path, worktree_path: &Path) -> Result<()> {
-    let output = Command::new("git")
-        .arg("worktree")
-        .arg("remove")
-        .arg(worktree_path)
-        .arg("--force")
-        .current_dir(repo_root)
-        .output()
-        .context("failed to run git worktree remove")?;
+pub async fn remove(repo_root: &Path, worktree_path: &Path) -> Result<()> {
+    let args = [
+        OsStr::new("worktree"),
+        OsStr::new("remove"),
+        worktree_path.as_os_str(),
+        OsStr::new("--force"),
+    ];
+    let output = git(repo_root, args, exec::LOCAL).await?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -145,9 +182,10 @@ pub fn remove(repo_root: &Path, worktree_path: &Path) -> Result<()> {
 }
 
 /// Build the environment variables to inject into the agent process.
-/// Sets git author identity so commits show the agent's name.
+/// Sets git author identity so commits show the agent's name, plus any
+/// shared build cache locations.
 pub fn agent_env(config: &WorktreeConfig, gh_token: &str) -> Vec<(String, String)> {
-    vec![
+    let mut env = vec![
         ("GIT_AUTHOR_NAME".to_string(), config.agent_name.clone()),
         ("GIT_AUTHOR_EMAIL".to_string(), config.agent_email.clone()),
         ("GIT_COMMITTER_NAME".to_string(), config.agent_name.clone()),
@@ -156,7 +194,9 @@ pub fn agent_env(config: &WorktreeConfig, gh_token: &str) -> Vec<(String, String
             config.agent_email.clone(),
         ),
         ("GH_TOKEN".to_string(), gh_token.to_string()),
-    ]
+    ];
+    env.extend(cache::cache_env(&config.cache, &config.repo_root));
+    env
 }
 
 #[cfg(test)]
@@ -187,6 +227,8 @@ mod tests {
             ticket_slug: "fix-thing".to_string(),
             agent_name: "Staff Engineer".to_string(),
             agent_email: "staff-engineer@poietai.ai".to_string(),
+            init_submodules: true,
+            cache: CacheConfig::default(),
         };
         let env = agent_env(&config, "gh_token_abc");
 
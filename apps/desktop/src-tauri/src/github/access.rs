@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::gh;
+
+/// How agents should get their commits into the repository.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum PushMode {
+    /// Push feature branches to origin and open PRs there.
+    Direct,
+    /// No push rights — push to a fork and open a cross-repo PR.
+    Fork,
+}
+
+/// Result of the repository permission pre-flight.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RepoAccess {
+    pub repo: String,
+    pub default_branch: String,
+    /// True when the default branch has protection rules (required reviews,
+    /// status checks, no direct pushes, ...).
+    pub default_branch_protected: bool,
+    pub can_push: bool,
+    pub push_mode: PushMode,
+    /// Things the user should know before the run starts.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct Permissions {
+    #[serde(default)]
+    push: bool,
+}
+
+#[derive(Deserialize)]
+struct RepoResponse {
+    default_branch: String,
+    #[serde(default)]
+    permissions: Option<Permissions>,
+}
+
+#[derive(Deserialize)]
+struct BranchResponse {
+    #[serde(default)]
+    protected: bool,
+}
+
+fn gh_api(path: &str, token: &str) -> Result<Vec<u8>> {
+    gh::run_blocking(&["api", path], Some(token)).with_context(|| format!("gh api {} failed", path))
+}
+
+/// Combine repo + branch facts into a push mode and user-facing warnings.
+fn assess(repo: &str, default_branch: String, protected: bool, can_push: bool) -> RepoAccess {
+    let mut warnings = Vec::new();
+    let push_mode = if can_push {
+        PushMode::Direct
+    } else {
+        warnings.push(format!(
+            "You can't push to {} — agents will work from a fork and open cross-repo PRs.",
+            repo
+        ));
+        PushMode::Fork
+    };
+    if protected {
+        warnings.push(format!(
+            "'{}' is protected — agent PRs will need the required reviews/checks before merging.",
+            default_branch
+        ));
+    }
+    RepoAccess {
+        repo: repo.to_string(),
+        default_branch,
+        default_branch_protected: protected,
+        can_push,
+        push_mode,
+        warnings,
+    }
+}
+
+/// Inspect push rights and default-branch protection for `owner/repo`.
+///
+/// Uses the branch endpoint's `protected` flag rather than the protection
+/// endpoint, since the latter requires admin rights to read.
+///
+/// Blocking — call via `spawn_blocking` from async contexts.
+pub fn check_repo_access(repo: &str, token: &str) -> Result<RepoAccess> {
+    let repo_info: RepoResponse = serde_json::from_slice(&gh_api(&format!("repos/{}", repo), token)?)
+        .context("failed to parse repo response")?;
+
+    let branch: BranchResponse = serde_json::from_slice(&gh_api(
+        &format!("repos/{}/branches/{}", repo, repo_info.default_branch),
+        token,
+    )?)
+    .context("failed to parse branch response")?;
+
+    // App installation tokens have no per-user permissions block; their
+    // access is scoped by the installation itself.
+    let can_push = repo_info
+        .permissions
+        .as_ref()
+        .map(|p| p.push)
+        .unwrap_or(true);
+
+    Ok(assess(repo, repo_info.default_branch, branch.protected, can_push))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_rights_use_direct_mode() {
+        let access = assess("o/r", "main".to_string(), false, true);
+        assert_eq!(access.push_mode, PushMode::Direct);
+        assert!(access.warnings.is_empty());
+    }
+
+    #[test]
+    fn no_push_rights_switch_to_fork_mode() {
+        let access = assess("o/r", "main".to_string(), false, false);
+        assert_eq!(access.push_mode, PushMode::Fork);
+        assert_eq!(access.warnings.len(), 1);
+    }
+
+    #[test]
+    fn protected_default_branch_warns() {
+        let access = assess("o/r", "main".to_string(), true, true);
+        assert!(access.warnings[0].contains("protected"));
+    }
+
+    #[test]
+    fn repo_response_without_permissions_parses() {
+        let parsed: RepoResponse = serde_json::from_str(r#"{"default_branch":"trunk"}"#).unwrap();
+        assert_eq!(parsed.default_branch, "trunk");
+        assert!(parsed.permissions.is_none());
+    }
+}
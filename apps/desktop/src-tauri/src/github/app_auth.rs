@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+use super::gh;
+
+/// GitHub App credentials, read from the vault by the frontend and passed in
+/// per run. The private key never touches disk on the backend side.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GitHubAppCredentials {
+    pub app_id: String,
+    pub installation_id: String,
+    /// PEM-encoded RSA private key downloaded from the App settings page.
+    pub private_key_pem: String,
+}
+
+/// A minted installation access token.
+#[derive(Debug, Clone)]
+pub struct InstallationToken {
+    pub token: String,
+    /// Local deadline after which we mint a fresh token. GitHub tokens live
+    /// for an hour; we refresh well before that.
+    pub refresh_at: Instant,
+}
+
+#[derive(Serialize)]
+struct AppClaims {
+    iat: u64,
+    exp: u64,
+    iss: String,
+}
+
+#[derive(Deserialize)]
+struct AccessTokenResponse {
+    token: String,
+}
+
+/// Installation tokens last 60 minutes. Each phase takes a token from the
+/// cache, so refresh after 30 to leave a phase at least half an hour.
+const TOKEN_REFRESH_AFTER: Duration = Duration::from_secs(30 * 60);
+
+/// Build the short-lived RS256 JWT that authenticates as the App itself.
+///
+/// `iat` is backdated 60s to tolerate clock drift, and `exp` stays under
+/// GitHub's 10-minute maximum.
+pub fn mint_jwt(creds: &GitHubAppCredentials, now_secs: u64) -> Result<String> {
+    let claims = AppClaims {
+        iat: now_secs.saturating_sub(60),
+        exp: now_secs + 9 * 60,
+        iss: creds.app_id.clone(),
+    };
+    let key = jsonwebtoken::EncodingKey::from_rsa_pem(creds.private_key_pem.as_bytes())
+        .context("GitHub App private key is not a valid RSA PEM")?;
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256),
+        &claims,
+        &key,
+    )
+    .context("failed to sign GitHub App JWT")
+}
+
+/// Exchange an App JWT for an installation access token via `gh api`.
+///
+/// Blocking — call via `spawn_blocking` from async contexts.
+pub fn request_installation_token(creds: &GitHubAppCredentials) -> Result<InstallationToken> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system clock is before the Unix epoch")?
+        .as_secs();
+    let jwt = mint_jwt(creds, now)?;
+
+    let stdout = gh::run_blocking(
+        &[
+            "api",
+            "--method",
+            "POST",
+            "-H",
+            &format!("Authorization: Bearer {}", jwt),
+            &format!("app/installations/{}/access_tokens", creds.installation_id),
+        ],
+        // gh refuses to run unauthenticated; the explicit header above wins.
+        Some(&jwt),
+    )
+    .context("GitHub App token exchange failed")?;
+
+    let parsed: AccessTokenResponse = serde_json::from_slice(&stdout)
+        .context("failed to parse installation token response")?;
+
+    Ok(InstallationToken {
+        token: parsed.token,
+        refresh_at: Instant::now() + TOKEN_REFRESH_AFTER,
+    })
+}
+
+/// Cache of installation tokens keyed by installation ID, so concurrent runs
+/// share one token and only re-mint when it's close to expiry.
+pub type TokenCache = Arc<Mutex<HashMap<String, InstallationToken>>>;
+
+pub fn new_cache() -> TokenCache {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// Return a valid installation token, minting a new one if the cached token
+/// is missing or due for refresh.
+///
+/// Blocking — call via `spawn_blocking` from async contexts.
+pub fn installation_token(cache: &TokenCache, creds: &GitHubAppCredentials) -> Result<String> {
+    {
+        let map = cache.lock().unwrap();
+        if let Some(cached) = map.get(&creds.installation_id) {
+            if Instant::now() < cached.refresh_at {
+                return Ok(cached.token.clone());
+            }
+        }
+    }
+
+    let fresh = request_installation_token(creds)?;
+    let token = fresh.token.clone();
+    cache
+        .lock()
+        .unwrap()
+        .insert(creds.installation_id.clone(), fresh);
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mint_jwt_rejects_invalid_key() {
+        let creds = GitHubAppCredentials {
+            app_id: "12345".to_string(),
+            installation_id: "678".to_string(),
+            private_key_pem: "not a key".to_string(),
+        };
+        let err = mint_jwt(&creds, 1_700_000_000).unwrap_err();
+        assert!(format!("{:#}", err).contains("RSA PEM"));
+    }
+
+    #[test]
+    fn cached_token_is_reused_until_refresh() {
+        let cache = new_cache();
+        cache.lock().unwrap().insert(
+            "678".to_string(),
+            InstallationToken {
+                token: "ghs_cached".to_string(),
+                refresh_at: Instant::now() + Duration::from_secs(60),
+            },
+        );
+        let creds = GitHubAppCredentials {
+            app_id: "12345".to_string(),
+            installation_id: "678".to_string(),
+            private_key_pem: String::new(),
+        };
+        assert_eq!(installation_token(&cache, &creds).unwrap(), "ghs_cached");
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::gh as gh_cli;
+
+/// Resume the agent with failing CI logs when its PR's checks fail.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct CiTriageConfig {
+    /// Auto-fix attempts per PR before leaving it to a human.
+    pub max_attempts: u32,
+    /// Lines kept from the end of each failing run's log.
+    pub log_tail_lines: usize,
+}
+
+impl Default for CiTriageConfig {
+    fn default() -> Self {
+        CiTriageConfig {
+            max_attempts: 2,
+            log_tail_lines: 150,
+        }
+    }
+}
+
+/// A completed check on the PR's head commit.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct CheckStatus {
+    pub name: String,
+    pub url: String,
+    /// Actions run id, when the check is a GitHub Actions job.
+    #[ts(type = "number | null")]
+    pub run_id: Option<u64>,
+    pub failed: bool,
+}
+
+/// Payload for `ci-failed`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct CiFailedPayload {
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub pr_number: u32,
+    /// The failed checks.
+    pub checks: Vec<CheckStatus>,
+    /// 1-based auto-fix attempt, or None once attempts are used up.
+    pub attempt: Option<u32>,
+}
+
+/// One entry of `statusCheckRollup`: a CheckRun or a legacy StatusContext.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RollupItem {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    context: Option<String>,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    details_url: Option<String>,
+    #[serde(default)]
+    target_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChecksView {
+    head_ref_oid: String,
+    #[serde(default)]
+    status_check_rollup: Vec<RollupItem>,
+}
+
+/// `https://github.com/o/r/actions/runs/123/job/456` → 123.
+fn run_id_from_url(url: &str) -> Option<u64> {
+    let (_, rest) = url.split_once("/actions/runs/")?;
+    rest.split('/').next()?.parse().ok()
+}
+
+/// Parse `gh pr view --json headRefOid,statusCheckRollup` into the head sha
+/// and the checks that have finished on it. Pending checks are left out.
+pub fn parse_checks(json: &[u8]) -> Result<(String, Vec<CheckStatus>)> {
+    let view: ChecksView = serde_json::from_slice(json).context("failed to parse PR checks")?;
+    let completed = view
+        .status_check_rollup
+        .into_iter()
+        .filter_map(|item| {
+            let outcome = item.conclusion.as_deref().or(item.state.as_deref()).unwrap_or_default();
+            let failed = match outcome {
+                "FAILURE" | "ERROR" | "TIMED_OUT" | "STARTUP_FAILURE" => true,
+                "SUCCESS" => false,
+                // Pending, skipped, neutral, cancelled
+                _ => return None,
+            };
+            let url = item.details_url.or(item.target_url).unwrap_or_default();
+            Some(CheckStatus {
+                name: item.name.or(item.context).unwrap_or_default(),
+                run_id: run_id_from_url(&url),
+                url,
+                failed,
+            })
+        })
+        .collect();
+    Ok((view.head_ref_oid, completed))
+}
+
+pub(crate) fn gh(args: &[&str]) -> Result<Vec<u8>> {
+    Ok(gh_cli::run_blocking(args, None)?)
+}
+
+/// The PR's head sha and its completed checks.
+pub fn fetch_checks(repo: &str, pr_number: u32) -> Result<(String, Vec<CheckStatus>)> {
+    let stdout = gh(&[
+        "pr",
+        "view",
+        &pr_number.to_string(),
+        "--repo",
+        repo,
+        "--json",
+        "headRefOid,statusCheckRollup",
+    ])?;
+    parse_checks(&stdout)
+}
+
+/// Keep the last `lines` lines — test runners print the failure summary last.
+pub fn tail(log: &str, lines: usize) -> String {
+    let all: Vec<&str> = log.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    let mut text = all[start..].join("\n");
+    if start > 0 {
+        text = format!("… {} earlier lines omitted\n{}", start, text);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_failed_check_runs_and_statuses() {
+        let json = br#"{"headRefOid":"abc123","statusCheckRollup":[
+            {"__typename":"CheckRun","name":"test","status":"COMPLETED","conclusion":"FAILURE",
+             "detailsUrl":"https://github.com/o/r/actions/runs/42/job/7"},
+            {"__typename":"CheckRun","name":"lint","status":"COMPLETED","conclusion":"SUCCESS",
+             "detailsUrl":"https://github.com/o/r/actions/runs/42/job/8"},
+            {"__typename":"StatusContext","context":"ci/jenkins","state":"ERROR",
+             "targetUrl":"https://jenkins.example.com/1"}]}"#;
+        let (sha, checks) = parse_checks(json).unwrap();
+        assert_eq!(sha, "abc123");
+        let failed: Vec<&CheckStatus> = checks.iter().filter(|c| c.failed).collect();
+        assert_eq!(checks.len(), 3);
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].run_id, Some(42));
+        assert_eq!(failed[1].name, "ci/jenkins");
+        assert_eq!(failed[1].run_id, None);
+    }
+
+    #[test]
+    fn tail_keeps_the_end() {
+        let log = (1..=5).map(|i| i.to_string()).collect::<Vec<_>>().join("\n");
+        assert_eq!(tail(&log, 2), "… 3 earlier lines omitted\n4\n5");
+        assert_eq!(tail("a\nb", 5), "a\nb");
+    }
+}
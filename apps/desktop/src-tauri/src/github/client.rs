@@ -0,0 +1,372 @@
+use anyhow::{Context, Result};
+use octocrab::Octocrab;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use super::ci::{self, CheckStatus};
+use super::gh;
+use super::poller::PrReview;
+
+/// Items per page for paginated lists, GitHub's maximum.
+const PER_PAGE: usize = 100;
+
+/// Pages read from one list before giving up on the rest.
+const MAX_PAGES: usize = 50;
+
+/// A PR's head sha and check rollup, shaped like `gh pr view --json
+/// headRefOid,statusCheckRollup` so [`ci::parse_checks`] reads both.
+const CHECKS_QUERY: &str = "query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      headRefOid
+      commits(last: 1) { nodes { commit { statusCheckRollup { contexts(first: 100) { nodes {
+        __typename
+        ... on CheckRun { name conclusion detailsUrl }
+        ... on StatusContext { context state targetUrl }
+      } } } } } }
+    }
+  }
+}";
+
+/// An open issue, for importing as a ticket.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct GitHubIssue {
+    pub number: u32,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    pub milestone: Option<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMethod {
+    Merge,
+    #[default]
+    Squash,
+    Rebase,
+}
+
+/// How the app talks to GitHub: the REST and GraphQL APIs directly when it
+/// has a token, the `gh` CLI and whatever it's logged in as otherwise. Both
+/// return the same API JSON, so callers don't care which they got.
+pub enum GitHubClient {
+    Api(Octocrab),
+    Cli,
+}
+
+impl GitHubClient {
+    /// An API client for `token`, falling back to gh when there's no token
+    /// or the client can't be built.
+    pub fn new(token: Option<&str>) -> Self {
+        let Some(token) = token.filter(|t| !t.trim().is_empty()) else {
+            return GitHubClient::Cli;
+        };
+        match Octocrab::builder()
+            .personal_token(token.to_string())
+            .build()
+        {
+            Ok(octocrab) => GitHubClient::Api(octocrab),
+            Err(e) => {
+                log::warn!("[client::new] falling back to gh: {}", e);
+                GitHubClient::Cli
+            }
+        }
+    }
+
+    /// `GET <route>`, e.g. `repos/o/r/pulls/1/reviews`.
+    async fn get(&self, route: &str) -> Result<Value> {
+        match self {
+            GitHubClient::Api(octocrab) => octocrab
+                .get(format!("/{}", route), None::<&()>)
+                .await
+                .with_context(|| format!("GET {} failed", route)),
+            GitHubClient::Cli => parse(&gh::run(&["api", route], None).await?),
+        }
+    }
+
+    /// Every item of a paginated list at `route`, which must already carry a
+    /// query string. `field` names the array in responses that wrap it in an
+    /// object, e.g. `jobs`.
+    async fn get_all(&self, route: &str, field: Option<&str>) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let mut listed = self
+                .get(&format!("{}&per_page={}&page={}", route, PER_PAGE, page))
+                .await?;
+            if let Some(field) = field {
+                listed = listed[field].take();
+            }
+            let Value::Array(batch) = listed else {
+                break;
+            };
+            let done = batch.len() < PER_PAGE;
+            items.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// `GET <route>` for a plain-text body, following a redirect to where
+    /// the content is hosted, as log downloads do.
+    async fn get_text(&self, route: &str) -> Result<String> {
+        match self {
+            GitHubClient::Api(octocrab) => {
+                let response = octocrab
+                    ._get(format!("/{}", route))
+                    .await
+                    .with_context(|| format!("GET {} failed", route))?;
+                let response = octocrab
+                    .follow_location_to_data(response)
+                    .await
+                    .with_context(|| format!("GET {} failed", route))?;
+                let status = response.status();
+                let body = octocrab
+                    .body_to_string(response)
+                    .await
+                    .with_context(|| format!("GET {} failed", route))?;
+                if !status.is_success() {
+                    anyhow::bail!("GET {} failed with {}: {}", route, status, body.trim());
+                }
+                Ok(body)
+            }
+            GitHubClient::Cli => {
+                Ok(String::from_utf8_lossy(&gh::run(&["api", route], None).await?).to_string())
+            }
+        }
+    }
+
+    /// `PUT <route>` with a flat JSON object body.
+    async fn put(&self, route: &str, body: &Value) -> Result<Value> {
+        match self {
+            GitHubClient::Api(octocrab) => octocrab
+                .put(format!("/{}", route), Some(body))
+                .await
+                .with_context(|| format!("PUT {} failed", route)),
+            GitHubClient::Cli => {
+                let fields = field_args(body)?;
+                let mut args = vec!["api", "--method", "PUT", route];
+                args.extend(fields.iter().map(String::as_str));
+                parse(&gh::run(&args, None).await?)
+            }
+        }
+    }
+
+    /// A GraphQL query's `data`, with flat variables.
+    async fn graphql(&self, query: &str, variables: &Value) -> Result<Value> {
+        let response: Value = match self {
+            GitHubClient::Api(octocrab) => octocrab
+                .graphql(&serde_json::json!({ "query": query, "variables": variables }))
+                .await
+                .context("GraphQL query failed")?,
+            GitHubClient::Cli => {
+                let query = format!("query={}", query);
+                let fields = field_args(variables)?;
+                let mut args = vec!["api", "graphql", "-f", query.as_str()];
+                args.extend(fields.iter().map(String::as_str));
+                parse(&gh::run(&args, None).await?)?
+            }
+        };
+        if let Some(errors) = response.get("errors").filter(|e| !e.is_null()) {
+            anyhow::bail!("GraphQL query failed: {}", errors);
+        }
+        Ok(response["data"].clone())
+    }
+
+    /// Every review on a PR, oldest first.
+    pub async fn reviews(&self, repo: &str, pr_number: u32) -> Result<Vec<PrReview>> {
+        let reviews = self
+            .get_all(
+                &format!("repos/{}/pulls/{}/reviews?", repo, pr_number),
+                None,
+            )
+            .await?;
+        Ok(reviews.iter().map(review).collect())
+    }
+
+    /// The open PR whose head is `branch` on the repo itself, if any.
+    pub async fn open_pr_for_branch(&self, repo: &str, branch: &str) -> Result<Option<u32>> {
+        let owner = repo
+            .split_once('/')
+            .map(|(owner, _)| owner)
+            .with_context(|| format!("'{}' isn't owner/name", repo))?;
+        let listed = self
+            .get(&format!(
+                "repos/{}/pulls?state=open&head={}:{}",
+                repo, owner, branch
+            ))
+            .await?;
+        Ok(listed
+            .as_array()
+            .and_then(|prs| prs.first())
+            .and_then(|pr| pr["number"].as_u64())
+            .map(|n| n as u32))
+    }
+
+    /// Logs of a workflow run's failed jobs, each trimmed to its last
+    /// `lines` lines.
+    pub async fn failed_logs(&self, repo: &str, run_id: u64, lines: usize) -> Result<String> {
+        let jobs = self
+            .get_all(
+                &format!("repos/{}/actions/runs/{}/jobs?filter=latest", repo, run_id),
+                Some("jobs"),
+            )
+            .await?;
+        let mut logs = Vec::new();
+        for job in jobs.iter().filter(|j| j["conclusion"] == "failure") {
+            let Some(id) = job["id"].as_u64() else {
+                continue;
+            };
+            let log = self
+                .get_text(&format!("repos/{}/actions/jobs/{}/logs", repo, id))
+                .await?;
+            logs.push(format!(
+                "{}\n{}",
+                job["name"].as_str().unwrap_or("job"),
+                ci::tail(&log, lines)
+            ));
+        }
+        Ok(logs.join("\n\n"))
+    }
+
+    /// The PR's head sha and its completed checks.
+    pub async fn checks(&self, repo: &str, pr_number: u32) -> Result<(String, Vec<CheckStatus>)> {
+        let (owner, name) = repo
+            .split_once('/')
+            .with_context(|| format!("'{}' isn't owner/name", repo))?;
+        let data = self
+            .graphql(
+                CHECKS_QUERY,
+                &serde_json::json!({ "owner": owner, "name": name, "number": pr_number }),
+            )
+            .await?;
+        let pr = &data["repository"]["pullRequest"];
+        let rollup = &pr["commits"]["nodes"][0]["commit"]["statusCheckRollup"]["contexts"]["nodes"];
+        let view = serde_json::json!({
+            "headRefOid": pr["headRefOid"],
+            "statusCheckRollup": if rollup.is_array() { rollup.clone() } else { Value::Array(vec![]) },
+        });
+        ci::parse_checks(view.to_string().as_bytes())
+    }
+
+    /// Open issues, newest first. Pull requests, which the issues API also
+    /// lists, are left out.
+    pub async fn issues(&self, repo: &str) -> Result<Vec<GitHubIssue>> {
+        let listed = self
+            .get_all(&format!("repos/{}/issues?state=open", repo), None)
+            .await?;
+        Ok(listed
+            .iter()
+            .filter(|i| i.get("pull_request").is_none())
+            .map(issue)
+            .collect())
+    }
+
+    /// Merge a PR, returning the merge commit's sha.
+    pub async fn merge(&self, repo: &str, pr_number: u32, method: MergeMethod) -> Result<String> {
+        let method = match method {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        };
+        let merged = self
+            .put(
+                &format!("repos/{}/pulls/{}/merge", repo, pr_number),
+                &serde_json::json!({ "merge_method": method }),
+            )
+            .await
+            .with_context(|| format!("failed to merge PR #{}", pr_number))?;
+        Ok(merged["sha"].as_str().unwrap_or_default().to_string())
+    }
+}
+
+fn parse(stdout: &[u8]) -> Result<Value> {
+    serde_json::from_slice(stdout).context("failed to parse gh api output")
+}
+
+/// `gh api` field flags for a flat JSON object: `-f` for strings, `-F` for
+/// numbers and booleans, which gh sends typed.
+fn field_args(body: &Value) -> Result<Vec<String>> {
+    let Some(fields) = body.as_object() else {
+        anyhow::bail!("gh api fields must be a JSON object");
+    };
+    let mut args = Vec::new();
+    for (key, value) in fields {
+        match value {
+            Value::String(s) => args.extend(["-f".to_string(), format!("{}={}", key, s)]),
+            Value::Number(_) | Value::Bool(_) => {
+                args.extend(["-F".to_string(), format!("{}={}", key, value)])
+            }
+            _ => anyhow::bail!("gh api can't send nested field '{}'", key),
+        }
+    }
+    Ok(args)
+}
+
+/// A REST review object in the shape the poller emits.
+fn review(json: &Value) -> PrReview {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    PrReview {
+        author: text(&json["user"]["login"]),
+        body: text(&json["body"]),
+        state: text(&json["state"]),
+        submitted_at: text(&json["submitted_at"]),
+    }
+}
+
+fn issue(json: &Value) -> GitHubIssue {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    GitHubIssue {
+        number: json["number"].as_u64().unwrap_or_default() as u32,
+        title: text(&json["title"]),
+        body: text(&json["body"]),
+        labels: json["labels"]
+            .as_array()
+            .map(|labels| labels.iter().map(|l| text(&l["name"])).collect())
+            .unwrap_or_default(),
+        milestone: json["milestone"]["title"].as_str().map(String::from),
+        url: text(&json["html_url"]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_rest_reviews_and_issues() {
+        let r = review(&json!({
+            "user": {"login": "ci-claude[bot]"}, "body": "LGTM", "state": "APPROVED",
+            "submitted_at": "2026-02-20T10:00:00Z"
+        }));
+        assert_eq!(
+            (r.author.as_str(), r.state.as_str()),
+            ("ci-claude[bot]", "APPROVED")
+        );
+
+        let i = issue(&json!({
+            "number": 12, "title": "Crash on empty repo", "body": null,
+            "labels": [{"name": "bug"}], "milestone": {"title": "v1"},
+            "html_url": "https://github.com/o/r/issues/12"
+        }));
+        assert_eq!(i.number, 12);
+        assert_eq!(i.body, "");
+        assert_eq!(i.labels, vec!["bug".to_string()]);
+        assert_eq!(i.milestone.as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn gh_fields_are_typed_and_flat() {
+        let args = field_args(&json!({"merge_method": "squash", "number": 3})).unwrap();
+        assert_eq!(args, vec!["-f", "merge_method=squash", "-F", "number=3"]);
+        assert!(field_args(&json!({"nested": {"a": 1}})).is_err());
+        assert!(field_args(&json!([1])).is_err());
+    }
+}
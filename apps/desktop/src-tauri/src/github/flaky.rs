@@ -0,0 +1,161 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::ci::CheckStatus;
+use crate::project::store;
+
+/// Payload for `flaky-check`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FlakyCheckPayload {
+    pub repo: String,
+    pub pr_number: u32,
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub check_name: String,
+}
+
+/// How long a check stays marked flaky; one that is fixed upstream gets
+/// auto-fixed again after this. A fresh fail-then-pass marks it again.
+const FLAKY_SECS: i64 = 7 * 24 * 3600;
+
+/// PRs not polled for this long are dropped from the outcomes.
+const PR_SECS: i64 = 7 * 24 * 3600;
+
+/// The last outcome seen for a check on a commit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+struct LastOutcome {
+    sha: String,
+    failed_on_sha: bool,
+}
+
+/// One PR's check outcomes.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PrOutcomes {
+    /// Unix seconds of the last poll.
+    polled_at: i64,
+    /// Check name → last outcome.
+    checks: HashMap<String, LastOutcome>,
+}
+
+/// Check outcomes across polls, at `$HOME/.poietai/metrics/checks.json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckMetrics {
+    /// "owner/repo#pr" → outcomes, for PRs polled in the last [`PR_SECS`].
+    prs: HashMap<String, PrOutcomes>,
+    /// Checks seen failing then passing on the same commit, per repo, with
+    /// when that was last seen (unix seconds).
+    flaky_since: HashMap<String, HashMap<String, i64>>,
+}
+
+// Serializes read-modify-write cycles on the metrics file; every PR poller
+// records into it.
+static CHECKS: Mutex<()> = Mutex::new(());
+
+pub fn path() -> PathBuf {
+    store::app_dir().join("metrics").join("checks.json")
+}
+
+impl CheckMetrics {
+    /// Load the metrics, let `f` change them, and save them. Blocking.
+    pub fn update<R>(f: impl FnOnce(&mut CheckMetrics) -> R) -> Result<(R, CheckMetrics)> {
+        let _guard = CHECKS.lock().unwrap_or_else(|e| e.into_inner());
+        let mut metrics: CheckMetrics = store::read_json(&path())?;
+        let result = f(&mut metrics);
+        store::write_json(&path(), &metrics)?;
+        Ok((result, metrics))
+    }
+
+    pub fn is_flaky(&self, repo: &str, check: &str) -> bool {
+        self.flaky_since.get(repo).is_some_and(|names| names.contains_key(check))
+    }
+
+    /// Record a poll of the PR's checks at `sha`. Returns checks newly found
+    /// flaky: failed, then passed on a re-run with no new commits. Flaky
+    /// marks and PRs past their time are dropped first.
+    pub fn record(&mut self, repo: &str, pr_number: u32, sha: &str, checks: &[CheckStatus], now: i64) -> Vec<String> {
+        self.prs.retain(|_, pr| now - pr.polled_at < PR_SECS);
+        for names in self.flaky_since.values_mut() {
+            names.retain(|_, since| now - *since < FLAKY_SECS);
+        }
+        self.flaky_since.retain(|_, names| !names.is_empty());
+
+        let pr = self.prs.entry(format!("{}#{}", repo, pr_number)).or_default();
+        pr.polled_at = now;
+        let outcomes = &mut pr.checks;
+        let mut newly_flaky = Vec::new();
+        for check in checks {
+            let last = outcomes.entry(check.name.clone()).or_default();
+            if last.sha != sha {
+                *last = LastOutcome {
+                    sha: sha.to_string(),
+                    failed_on_sha: check.failed,
+                };
+                continue;
+            }
+            if check.failed {
+                last.failed_on_sha = true;
+            } else if last.failed_on_sha {
+                last.failed_on_sha = false;
+                let names = self.flaky_since.entry(repo.to_string()).or_default();
+                if names.insert(check.name.clone(), now).is_none() {
+                    newly_flaky.push(check.name.clone());
+                }
+            }
+        }
+        newly_flaky
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, failed: bool) -> CheckStatus {
+        CheckStatus {
+            name: name.to_string(),
+            url: String::new(),
+            run_id: None,
+            failed,
+        }
+    }
+
+    #[test]
+    fn fail_then_pass_on_same_commit_is_flaky() {
+        let mut metrics = CheckMetrics::default();
+        assert!(metrics.record("o/r", 1, "a", &[check("test", true)], 0).is_empty());
+        assert_eq!(metrics.record("o/r", 1, "a", &[check("test", false)], 0), vec!["test"]);
+        assert!(metrics.is_flaky("o/r", "test"));
+        // Already known: not reported again
+        metrics.record("o/r", 2, "b", &[check("test", true)], 0);
+        assert!(metrics.record("o/r", 2, "b", &[check("test", false)], 0).is_empty());
+    }
+
+    #[test]
+    fn fix_pushed_in_a_new_commit_is_not_flaky() {
+        let mut metrics = CheckMetrics::default();
+        metrics.record("o/r", 1, "a", &[check("test", true)], 0);
+        assert!(metrics.record("o/r", 1, "b", &[check("test", false)], 0).is_empty());
+        assert!(!metrics.is_flaky("o/r", "test"));
+    }
+
+    #[test]
+    fn flaky_marks_and_old_prs_expire() {
+        let mut metrics = CheckMetrics::default();
+        metrics.record("o/r", 1, "a", &[check("test", true)], 0);
+        metrics.record("o/r", 1, "a", &[check("test", false)], 0);
+        metrics.record("o/r", 2, "b", &[], FLAKY_SECS - 1);
+        assert!(metrics.is_flaky("o/r", "test"));
+        assert_eq!(metrics.prs.len(), 2);
+
+        metrics.record("o/r", 2, "b", &[], FLAKY_SECS);
+        assert!(!metrics.is_flaky("o/r", "test"));
+        assert!(metrics.flaky_since.is_empty());
+        assert_eq!(metrics.prs.keys().collect::<Vec<_>>(), ["o/r#2"]);
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+use super::gh;
+use crate::platform::exec;
+use ts_rs::TS;
+
+/// Name of the git remote agents push to in fork mode.
+pub const FORK_REMOTE: &str = "fork";
+
+/// Where an agent pushes and opens its PR when working from a fork.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ForkTarget {
+    /// The upstream `owner/repo` the PR targets.
+    pub upstream: String,
+    /// The fork's `owner/repo`.
+    pub full_name: String,
+    /// Login of the fork owner — used as the `--head owner:branch` prefix.
+    pub owner: String,
+    pub clone_url: String,
+}
+
+#[derive(Deserialize)]
+struct ForkOwner {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct ForkResponse {
+    full_name: String,
+    clone_url: String,
+    owner: ForkOwner,
+}
+
+/// Fork `upstream` into the token owner's account, or return the existing fork.
+///
+/// `POST /repos/{upstream}/forks` is idempotent — GitHub returns the existing
+/// fork if there is one.
+///
+/// Blocking — call via `spawn_blocking` from async contexts.
+pub fn ensure_fork(upstream: &str, token: &str) -> Result<ForkTarget> {
+    let stdout = gh::run_blocking(
+        &["api", "--method", "POST", &format!("repos/{}/forks", upstream)],
+        Some(token),
+    )
+    .with_context(|| format!("forking {} failed", upstream))?;
+
+    let fork: ForkResponse =
+        serde_json::from_slice(&stdout).context("failed to parse fork response")?;
+
+    Ok(ForkTarget {
+        upstream: upstream.to_string(),
+        full_name: fork.full_name,
+        owner: fork.owner.login,
+        clone_url: fork.clone_url,
+    })
+}
+
+/// Add (or repoint) the `fork` remote in the repo. Remotes are shared by all
+/// worktrees of a repo, so this only needs to run once per repo root.
+pub fn add_fork_remote(repo_root: &Path, fork: &ForkTarget) -> Result<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "get-url", FORK_REMOTE])
+        .current_dir(repo_root);
+    let existing =
+        exec::output_blocking(&mut cmd, exec::LOCAL).context("failed to run git remote get-url")?;
+
+    let verb = if existing.status.success() { "set-url" } else { "add" };
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", verb, FORK_REMOTE, &fork.clone_url])
+        .current_dir(repo_root);
+    let output = exec::output_blocking(&mut cmd, exec::LOCAL)
+        .with_context(|| format!("failed to run git remote {}", verb))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git remote {} failed: {}", verb, stderr.trim());
+    }
+    Ok(())
+}
+
+/// Environment for a fork-mode run. `GH_REPO` makes `gh pr create` target
+/// upstream even though the branch lives on the fork.
+pub fn fork_env(fork: &ForkTarget) -> Vec<(String, String)> {
+    vec![
+        ("GH_REPO".to_string(), fork.upstream.clone()),
+        ("POIETAI_PUSH_REMOTE".to_string(), FORK_REMOTE.to_string()),
+        ("POIETAI_FORK_OWNER".to_string(), fork.owner.clone()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ForkTarget {
+        ForkTarget {
+            upstream: "rust-lang/rust".to_string(),
+            full_name: "agent-bot/rust".to_string(),
+            owner: "agent-bot".to_string(),
+            clone_url: "https://github.com/agent-bot/rust.git".to_string(),
+        }
+    }
+
+    #[test]
+    fn fork_env_targets_upstream() {
+        let env = fork_env(&sample());
+        assert!(env.contains(&("GH_REPO".to_string(), "rust-lang/rust".to_string())));
+        assert!(env.contains(&("POIETAI_PUSH_REMOTE".to_string(), "fork".to_string())));
+    }
+
+    #[test]
+    fn fork_response_parses() {
+        let json = r#"{"full_name":"agent-bot/rust","clone_url":"https://github.com/agent-bot/rust.git","owner":{"login":"agent-bot"},"private":false}"#;
+        let parsed: ForkResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.owner.login, "agent-bot");
+    }
+}
@@ -0,0 +1,184 @@
+use std::fmt;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use crate::platform::exec::{self, ExecError};
+
+/// gh processes allowed at once across the app. Pollers, pre-flights and
+/// commands all share these, so bursts don't trip GitHub's secondary rate
+/// limits.
+const MAX_CONCURRENT: usize = 4;
+
+/// Tries for a rate-limited call before giving up.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry; doubled for each one after.
+const FIRST_BACKOFF: Duration = Duration::from_secs(2);
+
+static PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT);
+
+/// Why a gh call failed, sorted from its stderr.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GhError {
+    /// gh couldn't be started, usually because it isn't installed.
+    NotInstalled(String),
+    /// No token, or one GitHub rejected.
+    Unauthenticated(String),
+    /// Still rate limited after every retry.
+    RateLimited(String),
+    NotFound(String),
+    /// Killed after running longer than [`exec::NETWORK`]; holds the
+    /// subcommand.
+    TimedOut(String),
+    /// Anything else: the subcommand and what gh printed.
+    Failed {
+        command: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for GhError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GhError::NotInstalled(e) => {
+                write!(f, "failed to run gh — is the GitHub CLI installed? ({})", e)
+            }
+            GhError::Unauthenticated(m) => write!(f, "GitHub rejected the credentials: {}", m),
+            GhError::RateLimited(m) => write!(f, "GitHub rate limit hit, retries exhausted: {}", m),
+            GhError::NotFound(m) => write!(f, "not found on GitHub: {}", m),
+            GhError::TimedOut(command) => {
+                write!(f, "gh {} timed out after {:?}", command, exec::NETWORK)
+            }
+            GhError::Failed { command, message } => write!(f, "gh {} failed: {}", command, message),
+        }
+    }
+}
+
+impl std::error::Error for GhError {}
+
+/// Sort a failed call by what gh printed.
+fn classify(command: &str, message: &str) -> GhError {
+    let lower = message.to_lowercase();
+    let message = message.to_string();
+    if lower.contains("http 429") || lower.contains("rate limit") {
+        GhError::RateLimited(message)
+    } else if lower.contains("http 401")
+        || lower.contains("bad credentials")
+        || lower.contains("gh auth login")
+    {
+        GhError::Unauthenticated(message)
+    } else if lower.contains("http 404") || lower.contains("could not resolve to a") {
+        GhError::NotFound(message)
+    } else {
+        GhError::Failed {
+            command: command.to_string(),
+            message,
+        }
+    }
+}
+
+/// The first two args, e.g. "pr view", for error messages without the
+/// values (tokens, bodies) later args may carry.
+fn command_name(args: &[&str]) -> String {
+    args.iter().take(2).copied().collect::<Vec<_>>().join(" ")
+}
+
+async fn attempt(args: &[&str], token: Option<&str>) -> Result<Vec<u8>, GhError> {
+    let _permit = PERMITS
+        .acquire()
+        .await
+        .expect("gh semaphore is never closed");
+    let mut cmd = Command::new("gh");
+    cmd.args(args);
+    if let Some(token) = token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = exec::output(&mut cmd, exec::NETWORK)
+        .await
+        .map_err(|e| match e {
+            ExecError::Spawn { error, .. } => GhError::NotInstalled(error.to_string()),
+            ExecError::TimedOut { .. } => GhError::TimedOut(command_name(args)),
+        })?;
+    if output.status.success() {
+        return Ok(output.stdout);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // `gh api -i` puts the error response on stdout
+    let message = match stderr.trim() {
+        "" => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        s => s.to_string(),
+    };
+    Err(classify(&command_name(args), &message))
+}
+
+/// Run `gh <args>`, as `token` when given, and return its stdout. Waits its
+/// turn for one of the shared slots and backs off on rate limits.
+pub async fn run(args: &[&str], token: Option<&str>) -> Result<Vec<u8>, GhError> {
+    let mut backoff = FIRST_BACKOFF;
+    let mut tries = 1;
+    loop {
+        match attempt(args, token).await {
+            Err(GhError::RateLimited(m)) if tries < MAX_ATTEMPTS => {
+                log::warn!(
+                    "[gh::run] gh {} rate limited, retrying in {:?}: {}",
+                    command_name(args),
+                    backoff,
+                    m
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                tries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// [`run`] for synchronous code. Blocking — call via `spawn_blocking` from
+/// async contexts.
+pub fn run_blocking(args: &[&str], token: Option<&str>) -> Result<Vec<u8>, GhError> {
+    tauri::async_runtime::block_on(run(args, token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_failures_by_stderr() {
+        let limited = classify(
+            "api",
+            "gh: You have exceeded a secondary rate limit (HTTP 403)",
+        );
+        assert!(matches!(limited, GhError::RateLimited(_)));
+        assert!(matches!(
+            classify("api", "HTTP 429: Too Many Requests"),
+            GhError::RateLimited(_)
+        ));
+        assert!(matches!(
+            classify("api", "HTTP 401: Bad credentials"),
+            GhError::Unauthenticated(_)
+        ));
+        assert!(matches!(
+            classify(
+                "pr view",
+                "GraphQL: Could not resolve to a PullRequest with the number of 9."
+            ),
+            GhError::NotFound(_)
+        ));
+        assert_eq!(
+            classify("pr edit", "no changes").to_string(),
+            "gh pr edit failed: no changes"
+        );
+    }
+
+    #[test]
+    fn error_names_leave_out_values() {
+        assert_eq!(
+            command_name(&["pr", "edit", "12", "--body", "secret"]),
+            "pr edit"
+        );
+        assert_eq!(command_name(&["api"]), "api");
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::ci::gh;
+use crate::project::store;
+
+/// How long an agent PR waited on people. Times are RFC 3339, from GitHub.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct PrTiming {
+    pub repo: String,
+    pub pr_number: u32,
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub opened_at: Option<String>,
+    /// The first review by someone other than a bot.
+    pub first_review_at: Option<String>,
+    pub merged_at: Option<String>,
+    /// Closed without merging; no longer refreshed.
+    pub closed: bool,
+}
+
+impl PrTiming {
+    fn settled(&self) -> bool {
+        self.merged_at.is_some() || self.closed
+    }
+}
+
+/// Review latency across tracked PRs.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct LatencyStats {
+    #[ts(type = "number | null")]
+    pub median_to_first_review_secs: Option<u64>,
+    #[ts(type = "number | null")]
+    pub p90_to_first_review_secs: Option<u64>,
+    #[ts(type = "number | null")]
+    pub median_to_merge_secs: Option<u64>,
+    #[ts(type = "number | null")]
+    pub p90_to_merge_secs: Option<u64>,
+    /// Open PRs nobody has reviewed yet.
+    pub awaiting_review: u32,
+    pub prs: Vec<PrTiming>,
+}
+
+// Serializes read-modify-write cycles on the tracking file; every PR watch
+// tracks into it while a refresh may be writing it back.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Tracked PRs, at `$HOME/.poietai/metrics/reviews.json`, keyed "owner/repo#pr".
+pub fn path() -> PathBuf {
+    store::app_dir().join("metrics").join("reviews.json")
+}
+
+/// Start tracking an agent's PR. A PR already tracked is left as is.
+pub fn track(repo: &str, pr_number: u32, agent_id: &str, ticket_id: &str) -> Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut prs: HashMap<String, PrTiming> = store::read_json(&path())?;
+    prs.entry(format!("{}#{}", repo, pr_number)).or_insert_with(|| PrTiming {
+        repo: repo.to_string(),
+        pr_number,
+        agent_id: agent_id.to_string(),
+        ticket_id: ticket_id.to_string(),
+        ..Default::default()
+    });
+    store::write_json(&path(), &prs)
+}
+
+#[derive(Deserialize)]
+struct Author {
+    #[serde(default)]
+    login: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Review {
+    author: Option<Author>,
+    submitted_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrView {
+    created_at: Option<String>,
+    merged_at: Option<String>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    reviews: Vec<Review>,
+}
+
+/// Fill `timing` from `gh pr view --json createdAt,mergedAt,state,reviews`.
+fn apply_view(timing: &mut PrTiming, json: &[u8]) -> Result<()> {
+    let view: PrView = serde_json::from_slice(json).context("failed to parse PR")?;
+    timing.opened_at = view.created_at.or(timing.opened_at.take());
+    timing.merged_at = view.merged_at.filter(|m| !m.is_empty());
+    timing.closed = view.state == "CLOSED";
+    timing.first_review_at = view
+        .reviews
+        .into_iter()
+        .filter(|r| r.author.as_ref().is_some_and(|a| !a.login.ends_with("[bot]")))
+        .filter_map(|r| r.submitted_at)
+        .min();
+    Ok(())
+}
+
+/// Every tracked PR, as last refreshed.
+pub fn load() -> Result<Vec<PrTiming>> {
+    let prs: HashMap<String, PrTiming> = store::read_json(&path())?;
+    Ok(prs.into_values().collect())
+}
+
+/// Re-read every PR not yet merged or closed from GitHub, returning all
+/// tracked PRs. PRs GitHub can't answer for keep their last known state.
+/// Blocking.
+pub fn refresh() -> Result<Vec<PrTiming>> {
+    // Fetch without the lock so PRs tracked meanwhile aren't held up, then
+    // merge into the file as it is now so they aren't lost either
+    let mut fetched: HashMap<String, PrTiming> = store::read_json(&path())?;
+    fetched.retain(|_, t| !t.settled());
+    for timing in fetched.values_mut() {
+        let fetched = gh(&[
+            "pr",
+            "view",
+            &timing.pr_number.to_string(),
+            "--repo",
+            &timing.repo,
+            "--json",
+            "createdAt,mergedAt,state,reviews",
+        ])
+        .and_then(|json| apply_view(timing, &json));
+        if let Err(e) = fetched {
+            warn!("[latency::refresh] {}#{}: {:#}", timing.repo, timing.pr_number, e);
+        }
+    }
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut prs: HashMap<String, PrTiming> = store::read_json(&path())?;
+    for (key, timing) in fetched {
+        if let Some(tracked) = prs.get_mut(&key) {
+            *tracked = timing;
+        }
+    }
+    store::write_json(&path(), &prs)?;
+    let mut prs: Vec<PrTiming> = prs.into_values().collect();
+    prs.sort_by(|a, b| b.opened_at.cmp(&a.opened_at));
+    Ok(prs)
+}
+
+fn secs_between(from: &Option<String>, to: &Option<String>) -> Option<u64> {
+    let from = DateTime::parse_from_rfc3339(from.as_deref()?).ok()?;
+    let to = DateTime::parse_from_rfc3339(to.as_deref()?).ok()?;
+    u64::try_from((to - from).num_seconds()).ok()
+}
+
+/// Nearest-rank percentile of `values`, which must be sorted.
+fn percentile(values: &[u64], pct: usize) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    let rank = (pct * values.len()).div_ceil(100).max(1);
+    values.get(rank - 1).copied()
+}
+
+pub fn stats(prs: Vec<PrTiming>) -> LatencyStats {
+    let sorted = |f: &dyn Fn(&PrTiming) -> Option<u64>| {
+        let mut values: Vec<u64> = prs.iter().filter_map(f).collect();
+        values.sort_unstable();
+        values
+    };
+    let to_review = sorted(&|p| secs_between(&p.opened_at, &p.first_review_at));
+    let to_merge = sorted(&|p| secs_between(&p.opened_at, &p.merged_at));
+    LatencyStats {
+        median_to_first_review_secs: percentile(&to_review, 50),
+        p90_to_first_review_secs: percentile(&to_review, 90),
+        median_to_merge_secs: percentile(&to_merge, 50),
+        p90_to_merge_secs: percentile(&to_merge, 90),
+        awaiting_review: prs
+            .iter()
+            .filter(|p| !p.settled() && p.first_review_at.is_none())
+            .count() as u32,
+        prs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_review_skips_bots() {
+        let json = br#"{"createdAt":"2026-03-02T09:00:00Z","mergedAt":null,"state":"OPEN","reviews":[
+            {"author":{"login":"ci-claude[bot]"},"submittedAt":"2026-03-02T09:05:00Z"},
+            {"author":{"login":"dana"},"submittedAt":"2026-03-02T13:00:00Z"}]}"#;
+        let mut timing = PrTiming::default();
+        apply_view(&mut timing, json).unwrap();
+        assert_eq!(timing.first_review_at.as_deref(), Some("2026-03-02T13:00:00Z"));
+        assert_eq!(timing.merged_at, None);
+        assert!(!timing.settled());
+    }
+
+    #[test]
+    fn stats_take_percentiles_over_known_durations() {
+        let pr = |review_hours: Option<u32>, merged: bool| PrTiming {
+            opened_at: Some("2026-03-02T00:00:00Z".to_string()),
+            first_review_at: review_hours.map(|h| format!("2026-03-02T{:02}:00:00Z", h)),
+            merged_at: merged.then(|| "2026-03-02T20:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let result = stats(vec![pr(Some(1), true), pr(Some(2), false), pr(Some(9), false), pr(None, false)]);
+        assert_eq!(result.median_to_first_review_secs, Some(2 * 3600));
+        assert_eq!(result.p90_to_first_review_secs, Some(9 * 3600));
+        assert_eq!(result.median_to_merge_secs, Some(20 * 3600));
+        assert_eq!(result.awaiting_review, 1);
+        assert_eq!(stats(vec![]).median_to_merge_secs, None);
+    }
+}
@@ -1 +1,14 @@
+pub mod access;
+pub mod app_auth;
+pub mod ci;
+pub mod client;
+pub mod flaky;
+pub mod fork;
+pub mod gh;
+pub mod latency;
 pub mod poller;
+pub mod pr_meta;
+pub mod projects;
+pub mod recent;
+pub mod release_notes;
+pub mod token;
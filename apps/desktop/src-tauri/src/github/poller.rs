@@ -1,12 +1,22 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use log::{info, warn};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::time::interval;
+use ts_rs::TS;
+
+use super::ci::{CiFailedPayload, CiTriageConfig};
+use super::client::GitHubClient;
+use super::flaky::{CheckMetrics, FlakyCheckPayload};
+use super::gh;
+use crate::agent::state::{self, AgentStatus};
+use crate::project::audit_log::{self, Actor};
+use crate::project::hours::WorkingHours;
 
 /// A single PR review from GitHub.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
 pub struct PrReview {
     pub author: String,
     pub body: String,
@@ -15,7 +25,8 @@ pub struct PrReview {
 }
 
 /// Payload emitted to React when a new CI review arrives.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 pub struct ReviewPayload {
     pub agent_id: String,
     pub ticket_id: String,
@@ -30,9 +41,9 @@ struct GhPrViewOutput {
 }
 
 /// Fetch current reviews for a PR using the `gh` CLI.
-pub fn fetch_reviews(repo: &str, pr_number: u32) -> Result<Vec<PrReview>> {
-    let output = Command::new("gh")
-        .args([
+pub async fn fetch_reviews(repo: &str, pr_number: u32) -> Result<Vec<PrReview>> {
+    let stdout = gh::run(
+        &[
             "pr",
             "view",
             &pr_number.to_string(),
@@ -40,25 +51,198 @@ pub fn fetch_reviews(repo: &str, pr_number: u32) -> Result<Vec<PrReview>> {
             repo,
             "--json",
             "reviews",
-        ])
-        .output()
-        .context("failed to run gh pr view")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("gh pr view failed: {}", stderr);
-    }
+        ],
+        None,
+    )
+    .await?;
 
     let parsed: GhPrViewOutput =
-        serde_json::from_slice(&output.stdout).context("failed to parse gh pr view output")?;
+        serde_json::from_slice(&stdout).context("failed to parse gh pr view output")?;
 
     Ok(parsed.reviews)
 }
 
+/// Polls in a row a failing commit's logs may fail to download before it's
+/// left to a human.
+const LOG_FETCH_TRIES: u32 = 3;
+
+/// Per-PR CI triage progress across polls.
+#[derive(Default)]
+struct CiTriage {
+    /// Head commit whose failures were already handled.
+    handled_sha: Option<String>,
+    attempts: u32,
+    /// Failed log downloads for the current head commit.
+    log_failures: u32,
+}
+
+/// Payload for `pr-poll-stopped`: a PR or MR is no longer watched although
+/// it's still open, so later reviews and CI failures won't reach the agent.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PollStoppedPayload {
+    pub agent_id: String,
+    pub ticket_id: String,
+    pub pr_number: u32,
+    pub polls: u32,
+}
+
+/// When the PR's head commit has failed checks, resume the agent with the
+/// failing logs — up to `config.max_attempts` times, then just report.
+/// Only a resume spends an attempt: without a session to resume, or when the
+/// logs can't be downloaded, the failure is reported and left to a human.
+/// Check outcomes are recorded on every poll; checks that pass on a re-run
+/// of the same commit are marked flaky and no longer trigger a resume.
+#[allow(clippy::too_many_arguments)]
+async fn triage_ci(
+    app: &AppHandle,
+    client: &GitHubClient,
+    repo: &str,
+    pr_number: u32,
+    agent_id: &str,
+    ticket_id: &str,
+    config: &CiTriageConfig,
+    triage: &mut CiTriage,
+) {
+    let (sha, checks) = match client.checks(repo, pr_number).await {
+        Ok(result) => result,
+        Err(e) => {
+            warn!("[poller::triage_ci] PR #{}: {:#}", pr_number, e);
+            return;
+        }
+    };
+    let repo_owned = repo.to_string();
+    let (sha, checks, newly_flaky, metrics) = match tokio::task::spawn_blocking(move || {
+        let now = chrono::Utc::now().timestamp();
+        let (newly_flaky, metrics) = CheckMetrics::update(|metrics| {
+            metrics.record(&repo_owned, pr_number, &sha, &checks, now)
+        })?;
+        anyhow::Ok((sha, checks, newly_flaky, metrics))
+    })
+    .await
+    {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            warn!("[poller::triage_ci] PR #{}: {:#}", pr_number, e);
+            return;
+        }
+        Err(e) => {
+            warn!("[poller::triage_ci] PR #{}: check task panicked: {}", pr_number, e);
+            return;
+        }
+    };
+    for check_name in newly_flaky {
+        info!("[poller::triage_ci] {} check '{}' is flaky", repo, check_name);
+        let _ = app.emit(
+            "flaky-check",
+            &FlakyCheckPayload {
+                repo: repo.to_string(),
+                pr_number,
+                agent_id: agent_id.to_string(),
+                ticket_id: ticket_id.to_string(),
+                check_name,
+            },
+        );
+    }
+    let failed: Vec<_> = checks
+        .into_iter()
+        .filter(|c| c.failed && !metrics.is_flaky(repo, &c.name))
+        .collect();
+    if failed.is_empty() || triage.handled_sha.as_deref() == Some(sha.as_str()) {
+        return;
+    }
+    let app_state = app.state::<crate::AppState>();
+    let Some(agent) = state::get_agent(&app_state.agents, agent_id).await else {
+        return;
+    };
+    if agent.status == AgentStatus::Working {
+        // Pick the failure up once the current run ends
+        return;
+    }
+    let report = |attempt: Option<u32>| {
+        let _ = app.emit(
+            "ci-failed",
+            &CiFailedPayload {
+                agent_id: agent_id.to_string(),
+                ticket_id: ticket_id.to_string(),
+                pr_number,
+                checks: failed.clone(),
+                attempt,
+            },
+        );
+    };
+    let session_id = match agent.session_id {
+        Some(session_id) if triage.attempts < config.max_attempts => session_id,
+        _ => {
+            triage.handled_sha = Some(sha);
+            report(None);
+            info!("[poller::triage_ci] PR #{} failed CI — leaving it to a human", pr_number);
+            return;
+        }
+    };
+
+    let mut run_ids: Vec<(String, u64)> = failed
+        .iter()
+        .filter_map(|c| Some((c.name.clone(), c.run_id?)))
+        .collect();
+    run_ids.dedup_by_key(|(_, id)| *id);
+    let mut logs = Vec::new();
+    for (name, id) in run_ids {
+        match client.failed_logs(repo, id, config.log_tail_lines).await {
+            Ok(log) => logs.push((name, log)),
+            Err(e) => {
+                // Try again next poll; a resume without the logs would only guess
+                triage.log_failures += 1;
+                warn!(
+                    "[poller::triage_ci] PR #{}: no logs for run {} ({}/{}): {:#}",
+                    pr_number, id, triage.log_failures, LOG_FETCH_TRIES, e
+                );
+                if triage.log_failures >= LOG_FETCH_TRIES {
+                    triage.handled_sha = Some(sha);
+                    triage.log_failures = 0;
+                    report(None);
+                }
+                return;
+            }
+        }
+    }
+    triage.handled_sha = Some(sha);
+    triage.log_failures = 0;
+    triage.attempts += 1;
+    let attempt = triage.attempts;
+    report(Some(attempt));
+
+    info!(
+        "[poller::triage_ci] PR #{} failed CI — auto-fix attempt {}/{}",
+        pr_number, attempt, config.max_attempts
+    );
+    let context = crate::agent::resume::ResumeContext {
+        failed_checks: failed.clone(),
+        check_logs: logs,
+        ..Default::default()
+    };
+    let prompt = crate::agent::resume::compose(Some(pr_number), &context);
+    let result = crate::resume_session(app, &app_state, agent_id.to_string(), session_id, prompt).await;
+    if let Err(ref e) = result {
+        warn!("[poller::triage_ci] failed to resume agent {}: {}", agent_id, e);
+    }
+    audit_log::command(
+        Actor::Automation,
+        "triage_ci",
+        serde_json::json!({ "agent_id": agent_id, "ticket_id": ticket_id, "pr_number": pr_number, "attempt": attempt }),
+        result.as_ref().map(|_| ()).map_err(String::as_str),
+    );
+}
+
 /// Poll a PR for new CI reviews, emitting a Tauri event when one arrives.
+/// With `ci` set, failed checks also resume the agent with their logs.
 ///
+/// Talks to the API as `gh_token` when given, through the gh CLI otherwise.
 /// Runs in a background tokio task. Stops when the PR is approved or after
-/// max_polls attempts.
+/// max_polls attempts, emitting `pr-poll-stopped` for the latter. Outside
+/// `hours` the poller idles without spending polls, so an overnight review
+/// is still picked up in the morning.
+#[allow(clippy::too_many_arguments)]
 pub async fn poll_pr(
     app: AppHandle,
     repo: String,
@@ -66,34 +250,35 @@ pub async fn poll_pr(
     agent_id: String,
     ticket_id: String,
     poll_interval_secs: u64,
+    hours: Option<WorkingHours>,
+    ci: Option<CiTriageConfig>,
+    gh_token: Option<String>,
 ) {
+    let client = GitHubClient::new(gh_token.as_deref());
     let mut ticker = interval(Duration::from_secs(poll_interval_secs));
     let mut seen_count = 0usize;
     let max_polls = 120; // 60 minutes at 30s intervals
+    let mut polls = 0;
+    let mut triage = CiTriage::default();
 
-    for _ in 0..max_polls {
+    while polls < max_polls {
         ticker.tick().await;
-
-        // spawn_blocking runs the synchronous gh CLI call on tokio's blocking
-        // thread pool, so the async executor thread isn't stalled during the
-        // subprocess wait.
-        let repo_clone = repo.clone();
-        let reviews = match tokio::task::spawn_blocking(move || {
-            fetch_reviews(&repo_clone, pr_number)
-        })
-        .await
-        {
-            Ok(Ok(r)) => r,
-            Ok(Err(e)) => {
-                eprintln!(
-                    "poller: error fetching reviews for PR #{}: {}",
-                    pr_number, e
-                );
+        if let Some(ref hours) = hours {
+            if hours.is_closed(chrono::Local::now().naive_local()) {
                 continue;
             }
+        }
+        polls += 1;
+
+        if let Some(config) = ci.as_ref().filter(|_| crate::project::flags::is_enabled("ci_triage")) {
+            triage_ci(&app, &client, &repo, pr_number, &agent_id, &ticket_id, config, &mut triage).await;
+        }
+
+        let reviews = match client.reviews(&repo, pr_number).await {
+            Ok(r) => r,
             Err(e) => {
                 eprintln!(
-                    "poller: spawn_blocking panicked for PR #{}: {}",
+                    "poller: error fetching reviews for PR #{}: {:#}",
                     pr_number, e
                 );
                 continue;
@@ -120,9 +305,15 @@ pub async fn poll_pr(
         }
     }
 
-    eprintln!(
-        "poller: max polls ({}) reached for PR #{}",
-        max_polls, pr_number
+    warn!("[poller::poll_pr] max polls ({}) reached for PR #{}", max_polls, pr_number);
+    let _ = app.emit(
+        "pr-poll-stopped",
+        &PollStoppedPayload {
+            agent_id,
+            ticket_id,
+            pr_number,
+            polls: max_polls,
+        },
     );
 }
 
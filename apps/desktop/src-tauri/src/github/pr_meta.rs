@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::ci::gh;
+
+#[derive(Deserialize)]
+struct Named {
+    #[serde(alias = "title")]
+    name: String,
+}
+
+fn repo_labels(repo: &str) -> Result<Vec<String>> {
+    let stdout = gh(&["label", "list", "--repo", repo, "--json", "name", "--limit", "500"])?;
+    let labels: Vec<Named> = serde_json::from_slice(&stdout).context("failed to parse labels")?;
+    Ok(labels.into_iter().map(|l| l.name).collect())
+}
+
+fn repo_milestones(repo: &str) -> Result<Vec<String>> {
+    let stdout = gh(&["api", &format!("repos/{}/milestones?state=open&per_page=100", repo)])?;
+    let milestones: Vec<Named> =
+        serde_json::from_slice(&stdout).context("failed to parse milestones")?;
+    Ok(milestones.into_iter().map(|m| m.name).collect())
+}
+
+/// The repo's spelling of each wanted name that exists there, compared
+/// case-insensitively. `gh pr edit` fails outright on unknown labels.
+pub fn matching(wanted: &[String], existing: &[String]) -> Vec<String> {
+    let mut found: Vec<String> = wanted
+        .iter()
+        .filter_map(|w| existing.iter().find(|e| e.eq_ignore_ascii_case(w.trim())))
+        .cloned()
+        .collect();
+    found.dedup();
+    found
+}
+
+/// `value` as one entry of a gh list flag. gh reads each value as CSV, so
+/// a label with a comma or quote has to be quoted to stay whole.
+fn flag_value(value: &str) -> String {
+    if value.contains([',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `gh pr edit` arguments for the labels and milestone, or None when there
+/// is nothing to apply.
+pub fn edit_args(pr_number: u32, repo: &str, labels: &[String], milestone: Option<&str>) -> Option<Vec<String>> {
+    if labels.is_empty() && milestone.is_none() {
+        return None;
+    }
+    let mut args = vec![
+        "pr".to_string(),
+        "edit".to_string(),
+        pr_number.to_string(),
+        "--repo".to_string(),
+        repo.to_string(),
+    ];
+    for label in labels {
+        args.push("--add-label".to_string());
+        args.push(flag_value(label));
+    }
+    if let Some(milestone) = milestone {
+        args.push("--milestone".to_string());
+        args.push(milestone.to_string());
+    }
+    Some(args)
+}
+
+/// Put the ticket's labels and milestone on its PR, skipping any the repo
+/// doesn't have. Returns what was applied.
+pub fn apply(
+    repo: &str,
+    pr_number: u32,
+    labels: &[String],
+    milestone: Option<&str>,
+) -> Result<(Vec<String>, Option<String>)> {
+    let labels = if labels.is_empty() {
+        Vec::new()
+    } else {
+        matching(labels, &repo_labels(repo)?)
+    };
+    let milestone = match milestone {
+        Some(m) if !m.trim().is_empty() => {
+            matching(&[m.to_string()], &repo_milestones(repo)?).into_iter().next()
+        }
+        _ => None,
+    };
+    if let Some(args) = edit_args(pr_number, repo, &labels, milestone.as_deref()) {
+        gh(&args.iter().map(String::as_str).collect::<Vec<_>>())?;
+    }
+    Ok((labels, milestone))
+}
+
+/// `body` with `section` in place of the old section starting with `marker`
+/// (which runs to the end of the body), or appended when there is none.
+pub fn with_section(body: &str, marker: &str, section: &str) -> String {
+    let kept = body.split_once(marker).map_or(body, |(before, _)| before).trim_end();
+    if kept.is_empty() {
+        section.to_string()
+    } else {
+        format!("{}\n\n{}", kept, section)
+    }
+}
+
+/// Add or replace the section starting with `marker` at the end of the PR body.
+pub fn set_body_section(repo: &str, pr_number: u32, marker: &str, section: &str) -> Result<()> {
+    let number = pr_number.to_string();
+    let stdout = gh(&["pr", "view", &number, "--repo", repo, "--json", "body"])?;
+    let view: serde_json::Value = serde_json::from_slice(&stdout).context("failed to parse PR body")?;
+    let body = with_section(view["body"].as_str().unwrap_or_default(), marker, section);
+    gh(&["pr", "edit", &number, "--repo", repo, "--body", &body])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_existing_labels_case_insensitively() {
+        let found = matching(&names(&["Bug", "area: billing", "nope"]), &names(&["bug", "area: billing"]));
+        assert_eq!(found, names(&["bug", "area: billing"]));
+    }
+
+    #[test]
+    fn builds_edit_args() {
+        let args = edit_args(7, "o/r", &names(&["bug", "area: a, b"]), Some("v1.2")).unwrap();
+        assert_eq!(
+            args,
+            names(&[
+                "pr", "edit", "7", "--repo", "o/r", "--add-label", "bug", "--add-label", "\"area: a, b\"",
+                "--milestone", "v1.2"
+            ])
+        );
+        assert!(edit_args(7, "o/r", &[], None).is_none());
+    }
+
+    #[test]
+    fn body_section_is_replaced_not_repeated() {
+        let once = with_section("Fixes #7", "<!-- m -->", "<!-- m -->\nrisk: low");
+        assert_eq!(once, "Fixes #7\n\n<!-- m -->\nrisk: low");
+        let twice = with_section(&once, "<!-- m -->", "<!-- m -->\nrisk: high");
+        assert_eq!(twice, "Fixes #7\n\n<!-- m -->\nrisk: high");
+        assert_eq!(with_section("", "<!-- m -->", "<!-- m -->"), "<!-- m -->");
+    }
+}
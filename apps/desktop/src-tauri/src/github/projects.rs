@@ -0,0 +1,258 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::ci::gh;
+use crate::project::store;
+
+/// Mirror ticket status changes to a GitHub Projects (v2) board.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct ProjectsSyncConfig {
+    /// User or organization that owns the board.
+    pub owner: String,
+    /// The number in the board's URL: github.com/orgs/<owner>/projects/<number>.
+    pub number: u32,
+    /// Single-select field holding the column.
+    pub status_field: String,
+    /// Ticket status → option name, overriding the Todo/In Progress/Done
+    /// defaults. Statuses with no option aren't mirrored.
+    pub status_map: HashMap<String, String>,
+}
+
+impl Default for ProjectsSyncConfig {
+    fn default() -> Self {
+        ProjectsSyncConfig {
+            owner: String::new(),
+            number: 0,
+            status_field: "Status".to_string(),
+            status_map: HashMap::new(),
+        }
+    }
+}
+
+impl ProjectsSyncConfig {
+    /// The board option name for a ticket status, if it has one.
+    pub fn option_name(&self, status: &str) -> Option<String> {
+        if let Some(name) = self.status_map.get(status) {
+            return Some(name.clone());
+        }
+        let name = match status {
+            "backlog" | "refined" | "assigned" => "Todo",
+            "in_progress" | "in_review" | "blocked" => "In Progress",
+            "shipped" => "Done",
+            _ => return None,
+        };
+        Some(name.to_string())
+    }
+}
+
+const BOARD_QUERY: &str = "query($owner: String!, $number: Int!, $field: String!) {
+  repositoryOwner(login: $owner) {
+    ... on ProjectV2Owner {
+      projectV2(number: $number) {
+        id
+        field(name: $field) {
+          ... on ProjectV2SingleSelectField { id options { id name } }
+        }
+      }
+    }
+  }
+}";
+
+const RESOURCE: &str = "query($url: URI!) {
+  resource(url: $url) { ... on Issue { id } ... on PullRequest { id } }
+}";
+
+const ADD_ITEM: &str = "mutation($project: ID!, $content: ID!) {
+  addProjectV2ItemById(input: {projectId: $project, contentId: $content}) { item { id } }
+}";
+
+const ADD_DRAFT: &str = "mutation($project: ID!, $title: String!) {
+  addProjectV2DraftIssue(input: {projectId: $project, title: $title}) { projectItem { id } }
+}";
+
+const SET_STATUS: &str = "mutation($project: ID!, $item: ID!, $field: ID!, $option: String!) {
+  updateProjectV2ItemFieldValue(input: {projectId: $project, itemId: $item, fieldId: $field,
+    value: {singleSelectOptionId: $option}}) { projectV2Item { id } }
+}";
+
+#[derive(Debug, Deserialize)]
+struct SelectOption {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusField {
+    id: String,
+    options: Vec<SelectOption>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Board {
+    id: String,
+    field: Option<StatusField>,
+}
+
+/// The board's id, its status field id, and the option id for `option`.
+fn parse_board(json: &[u8], option: &str) -> Result<(String, String, String)> {
+    let value: serde_json::Value = serde_json::from_slice(json).context("failed to parse board")?;
+    let board: Board = value
+        .pointer("/data/repositoryOwner/projectV2")
+        .filter(|v| !v.is_null())
+        .map(|v| serde_json::from_value(v.clone()))
+        .transpose()
+        .context("failed to parse board")?
+        .context("project board not found")?;
+    let field = board.field.context("status field not found or not single-select")?;
+    let option_id = field
+        .options
+        .iter()
+        .find(|o| o.name.eq_ignore_ascii_case(option))
+        .map(|o| o.id.clone())
+        .with_context(|| format!("board has no '{}' option", option))?;
+    Ok((board.id, field.id, option_id))
+}
+
+/// Serializes read-modify-writes of `github-project.json`.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Ticket id → board item id, at `<project>/.poietai/github-project.json`.
+pub fn path(project_root: &Path) -> PathBuf {
+    store::project_dir(project_root).join("github-project.json")
+}
+
+/// Put the ticket on the board: the issue it was imported from when it has
+/// one — adding an issue already there returns its card — else a draft.
+fn add_item(board_id: &str, title: &str, source_url: Option<&str>) -> Result<String> {
+    let parse = |stdout: &[u8], pointer: &str| -> Result<Option<String>> {
+        let value: serde_json::Value = serde_json::from_slice(stdout).context("failed to parse board item")?;
+        Ok(value.pointer(pointer).and_then(|v| v.as_str()).map(String::from))
+    };
+    if let Some(url) = source_url.filter(|u| u.starts_with("https://github.com/")) {
+        let stdout = gh(&[
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", RESOURCE),
+            "-f",
+            &format!("url={}", url),
+        ])?;
+        if let Some(content_id) = parse(&stdout, "/data/resource/id")? {
+            let stdout = gh(&[
+                "api",
+                "graphql",
+                "-f",
+                &format!("query={}", ADD_ITEM),
+                "-f",
+                &format!("project={}", board_id),
+                "-f",
+                &format!("content={}", content_id),
+            ])?;
+            return parse(&stdout, "/data/addProjectV2ItemById/item/id")?
+                .with_context(|| format!("{} was not added to the board", url));
+        }
+    }
+    let stdout = gh(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={}", ADD_DRAFT),
+        "-f",
+        &format!("project={}", board_id),
+        "-f",
+        &format!("title={}", title),
+    ])?;
+    parse(&stdout, "/data/addProjectV2DraftIssue/projectItem/id")?.context("draft issue was not created")
+}
+
+/// Move the ticket's card to the column for `status`, adding the ticket to
+/// the board the first time (see [`add_item`]). Statuses without a column
+/// are an error rather than a guess. Blocking — call from spawn_blocking.
+pub fn sync_ticket(
+    config: &ProjectsSyncConfig,
+    project_root: &Path,
+    ticket_id: &str,
+    title: &str,
+    source_url: Option<&str>,
+    status: &str,
+) -> Result<()> {
+    let option = config
+        .option_name(status)
+        .with_context(|| format!("no board column for status '{}' — add it to the status map", status))?;
+    let stdout = gh(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={}", BOARD_QUERY),
+        "-f",
+        &format!("owner={}", config.owner),
+        "-F",
+        &format!("number={}", config.number),
+        "-f",
+        &format!("field={}", config.status_field),
+    ])?;
+    let (board_id, field_id, option_id) = parse_board(&stdout, &option)?;
+
+    let item_id = {
+        // Held across adding the item too, so a ticket synced twice at once
+        // doesn't land on the board twice
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut items: HashMap<String, String> = store::read_json(&path(project_root))?;
+        match items.get(ticket_id) {
+            Some(id) => id.clone(),
+            None => {
+                let id = add_item(&board_id, title, source_url)?;
+                items.insert(ticket_id.to_string(), id.clone());
+                store::write_json(&path(project_root), &items)?;
+                id
+            }
+        }
+    };
+    gh(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={}", SET_STATUS),
+        "-f",
+        &format!("project={}", board_id),
+        "-f",
+        &format!("item={}", item_id),
+        "-f",
+        &format!("field={}", field_id),
+        "-f",
+        &format!("option={}", option_id),
+    ])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_statuses_with_overrides() {
+        let mut config = ProjectsSyncConfig::default();
+        assert_eq!(config.option_name("refined").as_deref(), Some("Todo"));
+        assert_eq!(config.option_name("in_review").as_deref(), Some("In Progress"));
+        assert_eq!(config.option_name("shipped").as_deref(), Some("Done"));
+        assert_eq!(config.option_name("archived"), None);
+        config.status_map.insert("in_review".to_string(), "Review".to_string());
+        assert_eq!(config.option_name("in_review").as_deref(), Some("Review"));
+    }
+
+    #[test]
+    fn finds_board_field_and_option() {
+        let json = br#"{"data":{"repositoryOwner":{"projectV2":{"id":"PVT_1",
+            "field":{"id":"PVTSSF_1","options":[{"id":"a","name":"Todo"},{"id":"b","name":"In Progress"}]}}}}}"#;
+        let (board, field, option) = parse_board(json, "in progress").unwrap();
+        assert_eq!((board.as_str(), field.as_str(), option.as_str()), ("PVT_1", "PVTSSF_1", "b"));
+        assert!(parse_board(json, "Done").is_err());
+        assert!(parse_board(br#"{"data":{"repositoryOwner":{"projectV2":null}}}"#, "Todo").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::ci::gh;
+use super::token;
+use crate::project::store;
+
+/// Merged PRs given to each run.
+pub const LIMIT: usize = 10;
+/// How long a fetched list is reused before asking GitHub again.
+const TTL_SECS: i64 = 15 * 60;
+/// Longest PR summary kept, in characters.
+const SUMMARY_CHARS: usize = 240;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecentPr {
+    pub number: u32,
+    pub title: String,
+    /// The first paragraph of the PR body, shortened.
+    pub summary: String,
+    pub merged_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    /// Unix seconds.
+    fetched_at: i64,
+    prs: Vec<RecentPr>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListedPr {
+    number: u32,
+    title: String,
+    #[serde(default)]
+    body: String,
+    merged_at: String,
+}
+
+fn cache_path(repo_root: &Path) -> PathBuf {
+    store::project_dir(repo_root).join("recent-prs.json")
+}
+
+/// First paragraph of a PR body that isn't a heading, HTML comment or
+/// checklist, cut to [`SUMMARY_CHARS`].
+fn summarize(body: &str) -> String {
+    let paragraph = body
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| {
+            !p.is_empty() && !p.starts_with('#') && !p.starts_with("<!--") && !p.starts_with("- [")
+        })
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if paragraph.chars().count() <= SUMMARY_CHARS {
+        return paragraph;
+    }
+    let cut: String = paragraph.chars().take(SUMMARY_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn fetch(repo: &str) -> Result<Vec<RecentPr>> {
+    let stdout = gh(&[
+        "pr",
+        "list",
+        "--repo",
+        repo,
+        "--state",
+        "merged",
+        "--json",
+        "number,title,body,mergedAt",
+        "--limit",
+        &LIMIT.to_string(),
+    ])?;
+    let listed: Vec<ListedPr> =
+        serde_json::from_slice(&stdout).context("failed to parse merged PRs")?;
+    Ok(listed
+        .into_iter()
+        .map(|pr| RecentPr {
+            number: pr.number,
+            title: pr.title,
+            summary: summarize(&pr.body),
+            merged_at: pr.merged_at,
+        })
+        .collect())
+}
+
+/// The repo's most recently merged PRs, newest first. Served from
+/// `<repo>/.poietai/recent-prs.json` while fresh; a stale list beats none
+/// when GitHub can't be reached. Blocking.
+pub fn merged(repo_root: &Path) -> Result<Vec<RecentPr>> {
+    let path = cache_path(repo_root);
+    let cache: Cache = store::read_json(&path).unwrap_or_default();
+    let now = chrono::Utc::now().timestamp();
+    if now - cache.fetched_at < TTL_SECS {
+        return Ok(cache.prs);
+    }
+    let repo = token::repo_slug_for(repo_root).context("repo has no GitHub origin remote")?;
+    match fetch(&repo) {
+        Ok(prs) => {
+            let cache = Cache {
+                fetched_at: now,
+                prs: prs.clone(),
+            };
+            // The list is still good for this run; the next one just fetches again
+            if let Err(e) = store::write_json(&path, &cache) {
+                warn!("[recent::merged] failed to cache PRs for {}: {:#}", repo, e);
+            }
+            Ok(prs)
+        }
+        Err(e) if !cache.prs.is_empty() => {
+            warn!("[recent::merged] using cached PRs for {}: {:#}", repo, e);
+            Ok(cache.prs)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_skips_boilerplate_and_truncates() {
+        let body = "<!-- template -->\n\n## Summary\n\nMoves retries\ninto the queue worker.\n\n- [x] Tests";
+        assert_eq!(summarize(body), "Moves retries into the queue worker.");
+        assert_eq!(summarize(""), "");
+        let long = summarize(&"word ".repeat(100));
+        assert!(long.ends_with('…'));
+        assert!(long.chars().count() <= SUMMARY_CHARS + 1);
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use super::ci::gh;
+use super::token;
+use crate::git::worktree::Worktree;
+use crate::platform::exec;
+use crate::project::tickets::{self, TicketRecord};
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedPr {
+    pub number: u32,
+    pub title: String,
+    author: Author,
+    pub head_ref_name: String,
+}
+
+/// Release note sections, in output order. A ticket lands in the first
+/// section one of its tags maps to.
+const SECTIONS: &[(&str, &[&str])] = &[
+    ("Features", &["feature", "feat", "enhancement"]),
+    ("Fixes", &["bug", "fix", "bugfix"]),
+    ("Documentation", &["docs", "documentation"]),
+    ("Maintenance", &["chore", "refactor", "deps", "dependencies", "ci"]),
+];
+const OTHER: &str = "Other changes";
+
+/// The branch slug the board derives from a ticket title.
+fn ticket_slug(title: &str) -> String {
+    let mut slug = String::new();
+    let mut in_space = false;
+    for c in title.to_lowercase().chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                slug.push('-');
+            }
+            in_space = true;
+        } else {
+            slug.push(c);
+            in_space = false;
+        }
+    }
+    slug.chars().take(50).collect()
+}
+
+fn section(ticket: Option<&TicketRecord>) -> &'static str {
+    let Some(ticket) = ticket else {
+        return OTHER;
+    };
+    SECTIONS
+        .iter()
+        .find(|(_, tags)| {
+            ticket
+                .tags
+                .iter()
+                .any(|t| tags.contains(&t.trim().to_lowercase().as_str()))
+        })
+        .map(|(name, _)| *name)
+        .unwrap_or(OTHER)
+}
+
+/// Group agent PRs — those on `feat/<ticket-slug>` branches — into a
+/// Markdown changelog draft.
+pub fn render(since_tag: &str, prs: &[MergedPr], tickets: &[TicketRecord]) -> String {
+    let mut groups: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for pr in prs {
+        let Some(slug) = pr.head_ref_name.strip_prefix("feat/") else {
+            continue;
+        };
+        let ticket = tickets
+            .iter()
+            .find(|t| Worktree::branch_for(&ticket_slug(&t.title)) == pr.head_ref_name);
+        let name = section(ticket);
+        let order = SECTIONS
+            .iter()
+            .position(|(n, _)| *n == name)
+            .unwrap_or(SECTIONS.len());
+        let ticket_ref = ticket
+            .map(|t| format!(", ticket #{}", t.number))
+            .unwrap_or_else(|| format!(", branch `{}`", slug));
+        groups.entry(order).or_default().push(format!(
+            "- {} (#{}{}) — @{}",
+            pr.title, pr.number, ticket_ref, pr.author.login
+        ));
+    }
+
+    let mut notes = format!("## Changes since {}\n", since_tag);
+    if groups.is_empty() {
+        notes.push_str("\nNo agent pull requests were merged.\n");
+    }
+    for (order, lines) in groups {
+        let name = SECTIONS.get(order).map(|(n, _)| *n).unwrap_or(OTHER);
+        notes.push_str(&format!("\n### {}\n\n{}\n", name, lines.join("\n")));
+    }
+    notes
+}
+
+/// Commit date of `tag` in the local checkout, ISO 8601.
+fn tag_date(repo_root: &Path, tag: &str) -> Result<String> {
+    let mut cmd = Command::new("git");
+    cmd.args(["log", "-1", "--format=%cI", tag, "--"]).current_dir(repo_root);
+    let output = exec::output_blocking(&mut cmd, exec::LOCAL).context("failed to run git log")?;
+    if !output.status.success() {
+        anyhow::bail!("tag '{}' not found: {}", tag, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// PRs merged into the repo after `since` (ISO 8601).
+fn merged_prs(repo: &str, since: &str) -> Result<Vec<MergedPr>> {
+    let stdout = gh(&[
+        "pr",
+        "list",
+        "--repo",
+        repo,
+        "--state",
+        "merged",
+        "--search",
+        &format!("merged:>{}", since),
+        "--json",
+        "number,title,author,headRefName",
+        "--limit",
+        "500",
+    ])?;
+    serde_json::from_slice(&stdout).context("failed to parse merged PRs")
+}
+
+/// Draft release notes for the agent PRs merged since `since_tag`.
+/// Blocking — call from spawn_blocking.
+pub fn generate(repo_root: &Path, since_tag: &str) -> Result<String> {
+    let repo = token::repo_slug_for(repo_root).context("repo has no GitHub origin remote")?;
+    let since = tag_date(repo_root, since_tag)?;
+    let prs = merged_prs(&repo, &since)?;
+    // Repos of a multi-repo project sit below the root that owns the board
+    let tickets = repo_root
+        .ancestors()
+        .find_map(|root| tickets::load_all(root).ok().filter(|t| !t.is_empty()))
+        .unwrap_or_default();
+    Ok(render(since_tag, &prs, &tickets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(number: u32, title: &str, branch: &str) -> MergedPr {
+        MergedPr {
+            number,
+            title: title.to_string(),
+            author: Author {
+                login: "poietai-bot".to_string(),
+            },
+            head_ref_name: branch.to_string(),
+        }
+    }
+
+    #[test]
+    fn slug_matches_the_board() {
+        assert_eq!(ticket_slug("Retry  billing\tcharges"), "retry-billing-charges");
+        assert_eq!(ticket_slug(&"a".repeat(80)).len(), 50);
+    }
+
+    #[test]
+    fn groups_agent_prs_by_ticket_tags() {
+        let tickets = vec![
+            TicketRecord {
+                number: 3,
+                title: "Retry billing".to_string(),
+                tags: vec!["Bug".to_string()],
+                ..Default::default()
+            },
+            TicketRecord {
+                number: 4,
+                title: "Dark mode".to_string(),
+                tags: vec!["feature".to_string()],
+                ..Default::default()
+            },
+        ];
+        let prs = vec![
+            pr(10, "Retry failed charges", "feat/retry-billing"),
+            pr(11, "Add dark mode", "feat/dark-mode"),
+            pr(12, "Hand-written fix", "fix/typo"),
+            pr(13, "Old ticket", "feat/gone"),
+        ];
+        let notes = render("v1.0.0", &prs, &tickets);
+        assert_eq!(
+            notes,
+            "## Changes since v1.0.0\n\
+             \n### Features\n\n- Add dark mode (#11, ticket #4) — @poietai-bot\n\
+             \n### Fixes\n\n- Retry failed charges (#10, ticket #3) — @poietai-bot\n\
+             \n### Other changes\n\n- Old ticket (#13, branch `gone`) — @poietai-bot\n"
+        );
+    }
+}
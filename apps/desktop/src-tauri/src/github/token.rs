@@ -0,0 +1,183 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+use super::gh;
+
+/// What a token is able to do against one repository.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct TokenCheck {
+    /// Classic token scopes from `X-OAuth-Scopes`; `None` for fine-grained
+    /// tokens and GitHub App tokens, which don't report scopes.
+    pub scopes: Option<Vec<String>>,
+    /// Whether the token can push branches (and therefore open PRs from them).
+    pub can_push: bool,
+    /// Human-readable scope problems that will make the run fail. Empty = OK.
+    pub problems: Vec<String>,
+}
+
+impl TokenCheck {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct RepoPermissions {
+    #[serde(default)]
+    push: bool,
+}
+
+#[derive(Deserialize)]
+struct RepoResponse {
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    permissions: Option<RepoPermissions>,
+}
+
+/// Extract `owner/repo` from a GitHub remote URL (HTTPS or SSH).
+pub fn repo_slug(remote_url: &str) -> Option<String> {
+    let (_, path) = remote_url.split_once("github.com")?;
+    let path = path.trim_start_matches([':', '/']);
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let mut parts = path.splitn(3, '/');
+    let owner = parts.next().filter(|s| !s.is_empty())?;
+    let repo = parts.next().filter(|s| !s.is_empty())?;
+    Some(format!("{}/{}", owner, repo))
+}
+
+/// Resolve the GitHub `owner/repo` slug for a local checkout via its origin remote.
+pub fn repo_slug_for(repo_root: &Path) -> Option<String> {
+    crate::git::scan::get_remote_url(repo_root).and_then(|url| repo_slug(&url))
+}
+
+/// Parse the `X-OAuth-Scopes` header out of `gh api -i` output.
+fn parse_scopes_header(response: &str) -> Option<Vec<String>> {
+    response
+        .lines()
+        .take_while(|l| !l.trim().is_empty())
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim().eq_ignore_ascii_case("x-oauth-scopes") {
+                Some(
+                    value
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect(),
+                )
+            } else {
+                None
+            }
+        })
+}
+
+/// Split `gh api -i` output into (status line + headers, body).
+fn split_response(response: &str) -> (&str, &str) {
+    let sep = if response.contains("\r\n\r\n") { "\r\n\r\n" } else { "\n\n" };
+    response.split_once(sep).unwrap_or((response, ""))
+}
+
+/// Evaluate scopes and permissions against what an agent run needs:
+/// read + push to the repo, and the ability to open a PR.
+fn evaluate(scopes: Option<Vec<String>>, repo: &RepoResponse) -> TokenCheck {
+    let mut problems = Vec::new();
+
+    if let Some(ref scopes) = scopes {
+        let has_repo = scopes.iter().any(|s| s == "repo");
+        let has_public = scopes.iter().any(|s| s == "public_repo");
+        let sufficient = has_repo || (has_public && !repo.private);
+        if !sufficient {
+            problems.push(format!(
+                "token is missing the '{}' scope (has: {})",
+                if repo.private { "repo" } else { "repo' or 'public_repo" },
+                if scopes.is_empty() { "none".to_string() } else { scopes.join(", ") }
+            ));
+        }
+    }
+
+    // Only a reported push permission counts. Missing push rights aren't
+    // fatal: the access pre-flight routes those runs through a fork instead.
+    let can_push = repo.permissions.as_ref().map(|p| p.push).unwrap_or(false);
+
+    TokenCheck {
+        scopes,
+        can_push,
+        problems,
+    }
+}
+
+/// Check a token against a repository using `gh api`.
+///
+/// Blocking — call via `spawn_blocking` from async contexts.
+pub fn check_token(repo: &str, token: &str) -> Result<TokenCheck> {
+    let stdout = gh::run_blocking(&["api", "-i", &format!("repos/{}", repo)], Some(token))
+        .with_context(|| format!("token cannot access {}", repo))?;
+    let stdout = String::from_utf8_lossy(&stdout);
+
+    let (head, body) = split_response(&stdout);
+    let parsed: RepoResponse =
+        serde_json::from_str(body).context("failed to parse gh api repo response")?;
+
+    Ok(evaluate(parse_scopes_header(head), &parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_slug_from_https_and_ssh() {
+        assert_eq!(repo_slug("https://github.com/poietai-ai/app.git"), Some("poietai-ai/app".to_string()));
+        assert_eq!(repo_slug("git@github.com:poietai-ai/app.git"), Some("poietai-ai/app".to_string()));
+        assert_eq!(repo_slug("https://gitlab.com/poietai-ai/app"), None);
+    }
+
+    #[test]
+    fn parses_scopes_header() {
+        let resp = "HTTP/2.0 200 OK\r\nX-Oauth-Scopes: repo, workflow\r\n\r\n{}";
+        let (head, _) = split_response(resp);
+        assert_eq!(parse_scopes_header(head), Some(vec!["repo".to_string(), "workflow".to_string()]));
+    }
+
+    #[test]
+    fn fine_grained_token_has_no_scopes_header() {
+        let resp = "HTTP/2.0 200 OK\nContent-Type: application/json\n\n{}";
+        let (head, _) = split_response(resp);
+        assert_eq!(parse_scopes_header(head), None);
+    }
+
+    #[test]
+    fn missing_repo_scope_is_reported() {
+        let repo = RepoResponse { private: true, permissions: Some(RepoPermissions { push: true }) };
+        let check = evaluate(Some(vec!["read:org".to_string()]), &repo);
+        assert!(!check.is_ok());
+        assert!(check.problems[0].contains("'repo'"));
+    }
+
+    #[test]
+    fn public_repo_scope_is_enough_for_public_repos() {
+        let repo = RepoResponse { private: false, permissions: Some(RepoPermissions { push: true }) };
+        let check = evaluate(Some(vec!["public_repo".to_string()]), &repo);
+        assert!(check.is_ok());
+    }
+
+    #[test]
+    fn missing_permissions_block_is_not_push_access() {
+        let repo = RepoResponse { private: true, permissions: None };
+        let check = evaluate(None, &repo);
+        assert!(check.is_ok());
+        assert!(!check.can_push);
+    }
+
+    #[test]
+    fn no_push_permission_is_not_fatal() {
+        let repo = RepoResponse { private: false, permissions: Some(RepoPermissions { push: false }) };
+        let check = evaluate(None, &repo);
+        assert!(!check.can_push);
+        assert!(check.is_ok());
+    }
+}
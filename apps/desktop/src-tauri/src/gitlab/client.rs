@@ -0,0 +1,232 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::process::Command;
+use ts_rs::TS;
+
+use crate::github::poller::PrReview;
+use crate::platform::exec;
+use crate::project::settings::GitLabConfig;
+
+/// An open issue, for importing as a ticket. Shaped like a GitHub issue so
+/// both import the same way.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct GitLabIssue {
+    /// The project-scoped issue number (`iid`).
+    pub number: u32,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+    pub milestone: Option<String>,
+    pub url: String,
+}
+
+/// Talks to a (possibly self-hosted) GitLab instance through `glab api`,
+/// as the vault token when given and glab's own login otherwise.
+pub struct GitLabClient {
+    config: GitLabConfig,
+    token: Option<String>,
+    /// URL-encoded project path, e.g. "team%2Fapp".
+    project: String,
+}
+
+impl GitLabClient {
+    pub fn new(config: GitLabConfig, token: Option<&str>, project_path: &str) -> Self {
+        GitLabClient {
+            config,
+            token: token.filter(|t| !t.trim().is_empty()).map(String::from),
+            project: project_path.replace('/', "%2F"),
+        }
+    }
+
+    /// `GET projects/<project>/<route>`, every page of it.
+    async fn get(&self, route: &str) -> Result<Vec<Value>> {
+        let route = format!("projects/{}/{}", self.project, route);
+        let mut cmd = Command::new("glab");
+        cmd.args(["api", "--paginate", &route])
+            .env("GITLAB_HOST", self.config.host());
+        if let Some(ref token) = self.token {
+            cmd.env("GITLAB_TOKEN", token);
+        }
+        let output = exec::output(&mut cmd, exec::NETWORK)
+            .await
+            .context("failed to run glab — is the GitLab CLI installed?")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "glab api {} failed: {}",
+                route,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        parse_pages(&output.stdout)
+    }
+
+    /// Open issues, for importing as tickets.
+    pub async fn issues(&self) -> Result<Vec<GitLabIssue>> {
+        let listed = self.get("issues?state=opened&per_page=100").await?;
+        Ok(listed.iter().map(issue).collect())
+    }
+
+    /// "opened", "merged", "closed" or "locked".
+    pub async fn merge_request_state(&self, iid: u32) -> Result<String> {
+        let mr = self.get(&format!("merge_requests/{}", iid)).await?;
+        Ok(mr
+            .first()
+            .and_then(|m| m["state"].as_str())
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// Discussion on a merge request, oldest first, as reviews. System notes
+    /// ("added 1 commit") are left out.
+    pub async fn notes(&self, iid: u32) -> Result<Vec<PrReview>> {
+        let notes = self
+            .get(&format!("merge_requests/{}/notes?sort=asc&order_by=created_at&per_page=100", iid))
+            .await?;
+        Ok(notes
+            .iter()
+            .filter(|n| !n["system"].as_bool().unwrap_or(false))
+            .map(note)
+            .collect())
+    }
+
+    /// Who has approved a merge request; empty until someone does.
+    pub async fn approvers(&self, iid: u32) -> Result<Vec<String>> {
+        let approvals = self.get(&format!("merge_requests/{}/approvals", iid)).await?;
+        Ok(approvals
+            .first()
+            .and_then(|a| a["approved_by"].as_array())
+            .map(|by| {
+                by.iter()
+                    .filter_map(|a| a["user"]["username"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+/// The project path in a remote URL on `config`'s instance, e.g.
+/// "team/sub/app" from "git@git.example.com:team/sub/app.git".
+pub fn project_path(remote_url: &str, config: &GitLabConfig) -> Option<String> {
+    let host = crate::git::scan::remote_host(remote_url)?;
+    if !host.eq_ignore_ascii_case(config.host()) {
+        return None;
+    }
+    let path = match remote_url.split_once("://") {
+        // https://host[:port]/path or ssh://git@host[:port]/path
+        Some((_, rest)) => rest.split_once('/')?.1,
+        // scp-style: git@host:path
+        None => remote_url.split_once(':')?.1,
+    };
+    // An instance served under a prefix, e.g. https://host/gitlab/team/app
+    let prefix = config
+        .base_url
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(&config.base_url)
+        .split_once('/')
+        .map(|(_, prefix)| prefix.trim_matches('/'))
+        .unwrap_or_default();
+    let path = path.trim_matches('/');
+    let path = path.strip_prefix(&format!("{}/", prefix)).unwrap_or(path);
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+/// `glab api --paginate` prints one JSON document per page; flatten the
+/// pages' arrays into one list.
+fn parse_pages(stdout: &[u8]) -> Result<Vec<Value>> {
+    let mut items = Vec::new();
+    for page in serde_json::Deserializer::from_slice(stdout).into_iter::<Value>() {
+        match page.context("failed to parse glab api output")? {
+            Value::Array(page) => items.extend(page),
+            other => items.push(other),
+        }
+    }
+    Ok(items)
+}
+
+fn note(json: &Value) -> PrReview {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    PrReview {
+        author: text(&json["author"]["username"]),
+        body: text(&json["body"]),
+        state: "COMMENTED".to_string(),
+        submitted_at: text(&json["created_at"]),
+    }
+}
+
+fn issue(json: &Value) -> GitLabIssue {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    GitLabIssue {
+        number: json["iid"].as_u64().unwrap_or_default() as u32,
+        title: text(&json["title"]),
+        body: text(&json["description"]),
+        labels: json["labels"]
+            .as_array()
+            .map(|labels| labels.iter().map(text).collect())
+            .unwrap_or_default(),
+        milestone: json["milestone"]["title"].as_str().map(String::from),
+        url: text(&json["web_url"]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn instance(base_url: &str) -> GitLabConfig {
+        GitLabConfig {
+            base_url: base_url.to_string(),
+        }
+    }
+
+    #[test]
+    fn finds_the_project_path_in_remotes() {
+        let config = instance("https://git.example.com");
+        assert_eq!(
+            project_path("git@git.example.com:team/sub/app.git", &config).as_deref(),
+            Some("team/sub/app")
+        );
+        assert_eq!(
+            project_path("ssh://git@git.example.com:2222/team/app", &config).as_deref(),
+            Some("team/app")
+        );
+        assert_eq!(project_path("https://github.com/team/app.git", &config), None);
+
+        let prefixed = instance("https://git.example.com/gitlab/");
+        assert_eq!(
+            project_path("https://git.example.com/gitlab/team/app.git", &prefixed).as_deref(),
+            Some("team/app")
+        );
+    }
+
+    #[test]
+    fn flattens_paginated_output() {
+        let items = parse_pages(b"[{\"iid\":1}]\n[{\"iid\":2},{\"iid\":3}]").unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(parse_pages(b"{\"state\":\"opened\"}").unwrap().len(), 1);
+        assert!(parse_pages(b"not json").is_err());
+    }
+
+    #[test]
+    fn maps_notes_and_issues() {
+        let n = note(&json!({
+            "author": {"username": "reviewer"}, "body": "Needs a test",
+            "created_at": "2026-02-20T10:00:00Z", "system": false
+        }));
+        assert_eq!((n.author.as_str(), n.state.as_str()), ("reviewer", "COMMENTED"));
+
+        let i = issue(&json!({
+            "iid": 7, "title": "Crash on empty repo", "description": null,
+            "labels": ["bug"], "milestone": {"title": "v1"},
+            "web_url": "https://git.example.com/team/app/-/issues/7"
+        }));
+        assert_eq!(i.number, 7);
+        assert_eq!(i.body, "");
+        assert_eq!(i.labels, vec!["bug".to_string()]);
+        assert_eq!(i.milestone.as_deref(), Some("v1"));
+    }
+}
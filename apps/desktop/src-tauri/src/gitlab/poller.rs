@@ -0,0 +1,95 @@
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::time::interval;
+
+use super::client::GitLabClient;
+use crate::github::poller::{PollStoppedPayload, PrReview, ReviewPayload};
+use crate::project::hours::WorkingHours;
+
+/// Poll a merge request for review notes and approvals, emitting the same
+/// `pr-review` events as the GitHub poller; the MR's iid stands in for the
+/// PR number.
+///
+/// Runs in a background tokio task. Stops when the MR is approved, merged
+/// or closed, or after max_polls attempts, emitting `pr-poll-stopped` for the
+/// latter. Outside `hours` the poller idles without spending polls.
+pub async fn poll_mr(
+    app: AppHandle,
+    client: GitLabClient,
+    mr_iid: u32,
+    agent_id: String,
+    ticket_id: String,
+    poll_interval_secs: u64,
+    hours: Option<WorkingHours>,
+) {
+    let mut ticker = interval(Duration::from_secs(poll_interval_secs));
+    let mut seen_count = 0usize;
+    let max_polls = 120; // 60 minutes at 30s intervals
+    let mut polls = 0;
+    let emit = |review: PrReview| {
+        let _ = app.emit(
+            "pr-review",
+            &ReviewPayload {
+                agent_id: agent_id.clone(),
+                ticket_id: ticket_id.clone(),
+                pr_number: mr_iid,
+                review,
+            },
+        );
+    };
+
+    while polls < max_polls {
+        ticker.tick().await;
+        if let Some(ref hours) = hours {
+            if hours.is_closed(chrono::Local::now().naive_local()) {
+                continue;
+            }
+        }
+        polls += 1;
+
+        match client.notes(mr_iid).await {
+            Ok(notes) if notes.len() > seen_count => {
+                for note in notes.iter().skip(seen_count) {
+                    emit(note.clone());
+                }
+                seen_count = notes.len();
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("[gitlab::poll_mr] notes for MR !{}: {:#}", mr_iid, e),
+        }
+
+        match client.approvers(mr_iid).await {
+            Ok(approvers) if !approvers.is_empty() => {
+                emit(PrReview {
+                    author: approvers.join(", "),
+                    body: String::new(),
+                    state: "APPROVED".to_string(),
+                    submitted_at: chrono::Utc::now().to_rfc3339(),
+                });
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("[gitlab::poll_mr] approvals for MR !{}: {:#}", mr_iid, e),
+        }
+
+        match client.merge_request_state(mr_iid).await.as_deref() {
+            Ok("merged") | Ok("closed") => {
+                log::info!("[gitlab::poll_mr] MR !{} is no longer open", mr_iid);
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("[gitlab::poll_mr] state of MR !{}: {:#}", mr_iid, e),
+        }
+    }
+
+    log::warn!("[gitlab::poll_mr] max polls ({}) reached for MR !{}", max_polls, mr_iid);
+    let _ = app.emit(
+        "pr-poll-stopped",
+        &PollStoppedPayload {
+            agent_id,
+            ticket_id,
+            pr_number: mr_iid,
+            polls: max_polls,
+        },
+    );
+}
@@ -2,32 +2,69 @@ mod agent;
 mod context;
 mod git;
 mod github;
+mod gitlab;
 mod mcp;
+mod platform;
+mod project;
+mod text;
 
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use std::path::PathBuf;
 use tauri::{Emitter, Manager, State};
-use std::io::Write;
+use tauri_plugin_deep_link::DeepLinkExt;
 
-use log::{error, info};
+use log::{error, info, warn};
 
 use agent::state::{
     all_agents, get_agent, new_store, remove_agent, set_chatting, set_status,
     update_agent_fields, upsert_agent, AgentState, AgentStatus, StateStore,
 };
+use project::audit_log::{Actor, AuditEntry, AuditKind};
+use ts_rs::TS;
 /// Global app state — injected into Tauri commands via State<AppState>.
 pub struct AppState {
     pub agents: StateStore,
     pub mcp: mcp::McpState,
+    /// Cached GitHub App installation tokens, shared across runs.
+    pub github_app_tokens: github::app_auth::TokenCache,
+    /// Live file watchers on agents' worktrees.
+    pub watchers: git::watch::WatcherStore,
+    /// Interactive shells opened in agents' worktrees.
+    pub terminals: agent::terminal::TerminalStore,
+    pub replays: agent::replay::ReplayStore,
+    /// Which run holds each ticket's worktree.
+    pub run_locks: agent::locks::LockStore,
+    /// Event kinds hidden from the canvas, read live by running agents.
+    pub event_filter: agent::filter::FilterStore,
+    /// Ticket runs holding a concurrency slot, and those queued for one.
+    pub scheduler: agent::scheduler::SharedScheduler<QueuedRun>,
+    /// A deep link the frontend hasn't picked up yet.
+    pub pending_link: project::deeplink::PendingLink,
+    /// Phases paused on a PR request, waiting for the user's decision.
+    pub pr_decisions: agent::pr_approval::DecisionStore,
+    /// Tournaments waiting on the user to pick a winner.
+    pub tournaments: agent::tournament::PendingStore,
+    /// Runs waiting for their project's working hours.
+    pub deferred: project::hours::DeferredStore,
+}
+
+/// Record a state-changing command in the audit log once it has run, with
+/// the arguments worth keeping, and pass its result through.
+fn audited<T>(actor: Actor, command: &str, args: serde_json::Value, result: Result<T, String>) -> Result<T, String> {
+    project::audit_log::command(actor, command, args, result.as_ref().map(|_| ()).map_err(String::as_str));
+    result
 }
 
 // ── Agent management commands ─────────────────────────────────────────────────
 
 /// Create a new agent and add it to the roster.
-/// Called from React when the user creates a new agent.
+/// Called from React when the user creates a new agent, and with `restored`
+/// set when the persisted roster is loaded at startup.
 #[tauri::command]
-fn create_agent(
+#[allow(clippy::too_many_arguments)]
+async fn create_agent(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     id: String,
     name: String,
@@ -35,7 +72,10 @@ fn create_agent(
     personality: String,
     chat_session_id: Option<String>,
     initiative: Option<String>,
+    restored: Option<bool>,
 ) -> Result<(), String> {
+    let actor = if restored == Some(true) { Actor::Automation } else { Actor::User };
+    let args = serde_json::json!({ "agent_id": id, "name": name, "role": role });
     let agent = AgentState {
         id: id.clone(),
         name,
@@ -49,21 +89,26 @@ fn create_agent(
         chat_session_id,
         chatting: false,
         initiative,
+        status_note: None,
     };
-    upsert_agent(&state.agents, agent);
-    Ok(())
+    let first = all_agents(&state.agents).await.is_empty();
+    upsert_agent(&state.agents, agent).await;
+    if first {
+        emit_onboarding(&app);
+    }
+    audited(actor, "create_agent", args, Ok(()))
 }
 
 /// Get all agents for the roster panel.
 #[tauri::command]
-fn get_all_agents(state: State<'_, AppState>) -> Vec<AgentState> {
-    all_agents(&state.agents)
+async fn get_all_agents(state: State<'_, AppState>) -> Result<Vec<AgentState>, String> {
+    Ok(all_agents(&state.agents).await)
 }
 
 /// Update an existing agent's name, role, or personality.
 /// Only the provided (Some) fields are changed; None fields are left as-is.
 #[tauri::command]
-fn update_agent(
+async fn update_agent(
     state: State<'_, AppState>,
     id: String,
     name: Option<String>,
@@ -71,39 +116,241 @@ fn update_agent(
     personality: Option<String>,
     initiative: Option<String>,
 ) -> Result<(), String> {
-    if update_agent_fields(&state.agents, &id, name, role, personality, initiative) {
+    let args = serde_json::json!({ "agent_id": id, "name": name, "role": role, "initiative": initiative });
+    let result = if update_agent_fields(&state.agents, &id, name, role, personality, initiative).await {
         Ok(())
     } else {
         Err(format!("agent '{}' not found", id))
-    }
+    };
+    audited(Actor::User, "update_agent", args, result)
+}
+
+/// Standing notes prepended to every resume prompt for an agent.
+#[tauri::command]
+fn get_pinned_notes(agent_id: String) -> Result<Vec<agent::pinned::PinnedNote>, String> {
+    agent::pinned::load(&agent_id).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn add_pinned_note(agent_id: String, text: String) -> Result<agent::pinned::PinnedNote, String> {
+    let args = serde_json::json!({ "agent_id": agent_id, "text": text });
+    let result = if text.trim().is_empty() {
+        Err("pinned note is empty".to_string())
+    } else {
+        agent::pinned::add(&agent_id, text.trim().to_string()).map_err(|e| format!("{:#}", e))
+    };
+    audited(Actor::User, "add_pinned_note", args, result)
+}
+
+#[tauri::command]
+fn remove_pinned_note(agent_id: String, id: String) -> Result<(), String> {
+    let result = agent::pinned::remove(&agent_id, &id).map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "remove_pinned_note", serde_json::json!({ "agent_id": agent_id, "id": id }), result)
+}
+
+/// The global snippet library, or a project's own when `project_root` is given.
+#[tauri::command]
+fn list_snippets(project_root: Option<String>) -> Result<Vec<context::snippets::Snippet>, String> {
+    context::snippets::load(project_root.as_deref().map(std::path::Path::new)).map_err(|e| format!("{:#}", e))
+}
+
+/// Create (empty id) or replace a snippet.
+#[tauri::command]
+fn save_snippet(
+    project_root: Option<String>,
+    snippet: context::snippets::Snippet,
+) -> Result<context::snippets::Snippet, String> {
+    let args = serde_json::json!({ "project_root": project_root, "name": snippet.name });
+    let result = if snippet.name.trim().is_empty() || snippet.body.trim().is_empty() {
+        Err("snippet needs a name and a body".to_string())
+    } else {
+        context::snippets::save(project_root.as_deref().map(std::path::Path::new), snippet).map_err(|e| format!("{:#}", e))
+    };
+    audited(Actor::User, "save_snippet", args, result)
+}
+
+#[tauri::command]
+fn delete_snippet(project_root: Option<String>, id: String) -> Result<(), String> {
+    let result = context::snippets::delete(project_root.as_deref().map(std::path::Path::new), &id)
+        .map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "delete_snippet", serde_json::json!({ "project_root": project_root, "id": id }), result)
 }
 
 /// Delete an agent from the runtime state.
 /// The frontend is responsible for also removing the agent from persisted agents.json.
 #[tauri::command]
-fn delete_agent(
+async fn delete_agent(
     state: State<'_, AppState>,
     id: String,
 ) -> Result<(), String> {
-    if remove_agent(&state.agents, &id) {
+    git::watch::unwatch(&state.watchers, &id);
+    agent::terminal::close_for_agent(&state.terminals, &id);
+    let result = if remove_agent(&state.agents, &id).await {
         Ok(())
     } else {
         Err(format!("agent '{}' not found", id))
-    }
+    };
+    audited(Actor::User, "delete_agent", serde_json::json!({ "agent_id": id }), result)
 }
 
 /// Scan a folder and return git repo information.
 /// Returns SingleRepo, MultiRepo (one level deep), or NoRepo.
+/// Self-hosted hosts are resolved via the user's provider host table.
+#[tauri::command]
+async fn scan_folder(app: tauri::AppHandle, path: String) -> Result<git::scan::FolderScanResult, String> {
+    // Spawns a git per repo found, so keep it off the async runtime
+    tokio::task::spawn_blocking(move || {
+        let settings = project::settings::load_app().map_err(|e| e.to_string())?;
+        let result = git::scan::scan_folder(std::path::Path::new(&path), &settings.provider_hosts);
+        if !matches!(result, git::scan::FolderScanResult::NoRepo) {
+            match project::onboarding::project_scanned() {
+                Ok(true) => emit_onboarding(&app),
+                Ok(false) => {}
+                Err(e) => warn!("[scan_folder] failed to record onboarding progress: {:#}", e),
+            }
+        }
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Everything standing in the way of agent runs on a project's repo, each
+/// with a suggested fix. Empty when healthy. The frontend checks each repo
+/// of a project.
+#[tauri::command]
+async fn check_project_health(
+    state: State<'_, AppState>,
+    project_root: String,
+) -> Result<Vec<project::health::HealthProblem>, String> {
+    let port = state.mcp.port;
+    tokio::task::spawn_blocking(move || {
+        let root = std::path::Path::new(&project_root);
+        let settings = project::settings::load_project(root).unwrap_or_default();
+        project::health::check(root, &settings, port)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Installed WSL distros, default first, for the project's distro picker.
+#[tauri::command]
+async fn list_wsl_distros() -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(platform::wsl::list_distros)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Which of claude, gh and git `distro` has, with their versions.
+#[tauri::command]
+async fn check_wsl_distro(distro: String) -> Result<platform::wsl::WslHealth, String> {
+    tokio::task::spawn_blocking(move || platform::wsl::check(&distro))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+// ── Deep links ────────────────────────────────────────────────────────────────
+
+fn focus_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Bring the window forward and emit `deep-link` for the frontend to
+/// navigate. The link is also held until the frontend takes it, in case it
+/// isn't listening yet.
+fn route_deep_link(app: &tauri::AppHandle, link: project::deeplink::DeepLink) {
+    info!("[route_deep_link] {:?}", link);
+    focus_main_window(app);
+    if let Some(state) = app.try_state::<AppState>() {
+        *state.pending_link.lock().unwrap_or_else(|e| e.into_inner()) = Some(link.clone());
+    }
+    let _ = app.emit("deep-link", &link);
+}
+
+/// Route a `poietai://` link handed over by the frontend.
+#[tauri::command]
+fn open_deep_link(app: tauri::AppHandle, url: String) -> Result<project::deeplink::DeepLink, String> {
+    let link = project::deeplink::parse(&url).map_err(|e| format!("{:#}", e))?;
+    route_deep_link(&app, link.clone());
+    Ok(link)
+}
+
+/// The link the app was opened with, or the last one routed, if the
+/// frontend hasn't taken it yet. Clears it.
+#[tauri::command]
+fn take_deep_link(state: State<'_, AppState>) -> Option<project::deeplink::DeepLink> {
+    state.pending_link.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+// ── Onboarding ────────────────────────────────────────────────────────────────
+
+async fn onboarding_state(agents: &StateStore) -> Result<project::onboarding::OnboardingState, String> {
+    let count = all_agents(agents).await.len();
+    tokio::task::spawn_blocking(move || project::onboarding::state(&project::onboarding::probe(count)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Re-check onboarding in the background and emit `onboarding-state-changed`.
+fn emit_onboarding(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let agents = app.state::<AppState>().agents.clone();
+        match onboarding_state(&agents).await {
+            Ok(state) => {
+                let _ = app.emit("onboarding-state-changed", &state);
+            }
+            Err(e) => warn!("[emit_onboarding] {}", e),
+        }
+    });
+}
+
+/// Where first-run setup stands, from what's actually installed and
+/// configured on this machine.
+#[tauri::command]
+async fn get_onboarding_state(
+    state: State<'_, AppState>,
+) -> Result<project::onboarding::OnboardingState, String> {
+    onboarding_state(&state.agents).await
+}
+
+/// Count an onboarding step as done: one finished where the backend can't
+/// see it (a token saved to the vault), or `done` to close the wizard.
+#[tauri::command]
+async fn mark_onboarding_step(
+    state: State<'_, AppState>,
+    step: project::onboarding::OnboardingStep,
+) -> Result<project::onboarding::OnboardingState, String> {
+    let args = serde_json::json!({ "step": step });
+    let result = project::onboarding::mark(step).map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "mark_onboarding_step", args, result)?;
+    onboarding_state(&state.agents).await
+}
+
+/// Leave an onboarding step for later; it isn't counted as done.
 #[tauri::command]
-fn scan_folder(path: String) -> Result<git::scan::FolderScanResult, String> {
-    Ok(git::scan::scan_folder(std::path::Path::new(&path)))
+async fn skip_onboarding_step(
+    state: State<'_, AppState>,
+    step: project::onboarding::OnboardingStep,
+) -> Result<project::onboarding::OnboardingState, String> {
+    let args = serde_json::json!({ "step": step });
+    let result = project::onboarding::skip(step).map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "skip_onboarding_step", args, result)?;
+    onboarding_state(&state.agents).await
 }
 
 // ── Agent execution commands ──────────────────────────────────────────────────
 
 /// Payload from React to start an agent on a ticket.
 /// Matches the shape React sends via invoke("start_agent", { payload: { ... } }).
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, serde::Serialize, TS)]
+#[ts(export)]
 pub struct StartAgentPayload {
     pub agent_id: String,
     pub ticket_id: String,
@@ -112,6 +359,9 @@ pub struct StartAgentPayload {
     pub system_prompt: String,
     pub repo_root: String,
     pub gh_token: String,
+    /// When set, mint a GitHub App installation token and use it instead of
+    /// `gh_token`, so PRs are attributed to the App's bot identity.
+    pub github_app: Option<github::app_auth::GitHubAppCredentials>,
     pub resume_session_id: Option<String>,
     /// The current ticket phase (e.g. "brief", "design", "plan", "build", etc.).
     /// Defaults to Build if absent or unrecognised.
@@ -122,28 +372,594 @@ pub struct StartAgentPayload {
     /// Optional JSON plan artifact from the Plan phase — used by the orchestrator
     /// to determine whether to fan-out the Build phase across parallel task groups.
     pub plan_artifact: Option<String>,
+    /// GitLab token from the vault — only used when the project has a GitLab config.
+    pub gitlab_token: Option<String>,
+    /// Build in pair-programming mode with a reviewer agent.
+    pub pair: Option<agent::orchestrator::PairConfig>,
+    /// Build with several agents in parallel and compare the results.
+    pub tournament: Option<agent::tournament::TournamentConfig>,
+    /// Build test-first: a QA agent writes failing tests before the build.
+    #[serde(default)]
+    pub test_first: Option<agent::test_first::TestFirstConfig>,
+    /// Shell command that must pass before the agent may open a PR, e.g. the
+    /// `test_command` of a dependency update.
+    #[serde(default)]
+    pub pr_gate: Option<String>,
+    /// Start immediately even outside the project's working hours.
+    #[serde(default)]
+    pub run_now: bool,
+    /// Stop whichever run holds the ticket's worktree instead of failing.
+    #[serde(default)]
+    pub force_takeover: bool,
+    /// Started by the app on its own, e.g. resuming after a restart, rather
+    /// than by the user. Only changes how the start is audited.
+    #[serde(default)]
+    pub automated: bool,
+}
+
+/// Emitted when a run is held until the project's working hours open.
+#[derive(serde::Serialize, TS)]
+#[ts(export)]
+pub struct RunDeferredPayload {
+    pub agent_id: String,
+    pub ticket_id: String,
+    /// Local RFC 3339 time the run will start.
+    pub until: String,
 }
 
 /// Assign a ticket to an agent and start the Claude process.
 ///
 /// Returns immediately — the agent runs in a background tokio task.
 /// Events arrive at React via "agent-event" and "agent-result" Tauri events.
+/// Outside the project's working hours the run is deferred (`run-deferred`)
+/// unless `run_now` is set.
 #[tauri::command]
 async fn start_agent(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     payload: StartAgentPayload,
 ) -> Result<(), String> {
+    let actor = if payload.automated { Actor::Automation } else { Actor::User };
+    let args = serde_json::json!({
+        "agent_id": payload.agent_id,
+        "ticket_id": payload.ticket_id,
+        "repo_root": payload.repo_root,
+        "phase": payload.phase,
+        "run_now": payload.run_now,
+        "force_takeover": payload.force_takeover,
+    });
+    let result = start_or_defer(app, &state, payload).await;
+    audited(actor, "start_agent", args, result)
+}
+
+/// Schedule the run, or hold it until the project's working hours open.
+async fn start_or_defer(app: tauri::AppHandle, state: &AppState, payload: StartAgentPayload) -> Result<(), String> {
+    if payload.tournament.is_some() && !project::flags::is_enabled("tournaments") {
+        return Err("tournaments are turned off — enable them under feature flags".to_string());
+    }
+    let hours = project::settings::load_project(std::path::Path::new(&payload.repo_root))
+        .unwrap_or_default()
+        .working_hours;
+    // A new start supersedes a run still waiting on the ticket
+    cancel_deferred(state, &payload.ticket_id);
+    let now = chrono::Local::now();
+    let open_at = match hours {
+        Some(ref hours) if !payload.run_now => Some(
+            hours
+                .next_open(now.naive_local())
+                .map_err(|e| format!("invalid working hours: {:#}", e))?,
+        ),
+        _ => None,
+    };
+    let Some(open_at) = open_at.filter(|at| *at > now.naive_local()) else {
+        return schedule_ticket(&app, state, payload).await;
+    };
+
+    let wait = (open_at - now.naive_local()).to_std().unwrap_or_default();
+    info!(
+        "[start_or_defer] agent={} outside working hours — deferring {}s",
+        payload.agent_id,
+        wait.as_secs()
+    );
+    let until = (now + chrono::Duration::from_std(wait).unwrap_or_default()).to_rfc3339();
+    // Journal the queued run so it isn't silently lost if the app quits first
+    let queued_id = uuid::Uuid::new_v4().to_string();
+    let marker = agent::recovery::RunMarker {
+        queued_until: Some(until.clone()),
+        ..run_marker(&queued_id, &payload).with_payload(&payload)
+    };
+    if let Err(e) = agent::recovery::begin(marker) {
+        warn!("[start_or_defer] failed to journal deferred run: {:#}", e);
+    }
+    let _ = app.emit(
+        "run-deferred",
+        &RunDeferredPayload {
+            agent_id: payload.agent_id.clone(),
+            ticket_id: payload.ticket_id.clone(),
+            until,
+        },
+    );
+    let ticket_id = payload.ticket_id.clone();
+    let deferred_id = queued_id.clone();
+    // Hold the store while spawning, so the task can't finish before it's listed
+    let mut deferred = state.deferred.lock().unwrap_or_else(|e| e.into_inner());
+    let task = tokio::spawn(async move {
+        tokio::time::sleep(wait).await;
+        let state = app.state::<AppState>();
+        {
+            let mut deferred = state.deferred.lock().unwrap_or_else(|e| e.into_inner());
+            if deferred.get(&payload.ticket_id).is_some_and(|d| d.id == queued_id) {
+                deferred.remove(&payload.ticket_id);
+            }
+        }
+        let _ = agent::recovery::finish(&queued_id);
+        if let Err(e) = schedule_ticket(&app, &state, payload).await {
+            error!("[start_or_defer] deferred run failed to start: {}", e);
+        }
+    });
+    deferred.insert(
+        ticket_id,
+        project::hours::Deferred {
+            id: deferred_id,
+            abort: task.abort_handle(),
+        },
+    );
+    Ok(())
+}
+
+/// Call off a run on `ticket_id` still waiting for working hours, and drop
+/// its journal entry. Returns whether there was one.
+fn cancel_deferred(state: &AppState, ticket_id: &str) -> bool {
+    let removed = state
+        .deferred
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(ticket_id);
+    let Some(deferred) = removed else {
+        return false;
+    };
+    deferred.abort.abort();
+    let _ = agent::recovery::finish(&deferred.id);
+    info!("[cancel_deferred] ticket={} deferred run cancelled", ticket_id);
+    true
+}
+
+/// Cancel a run deferred until working hours. Returns false when the ticket
+/// had none waiting.
+#[tauri::command]
+fn cancel_deferred_run(state: State<'_, AppState>, ticket_id: String) -> bool {
+    let cancelled = cancel_deferred(&state, &ticket_id);
+    if cancelled {
+        project::audit_log::command(Actor::User, "cancel_deferred_run", serde_json::json!({ "ticket_id": ticket_id }), Ok(()));
+    }
+    cancelled
+}
+
+/// A run waiting for a scheduler slot.
+pub enum QueuedRun {
+    Dispatch(Box<StartAgentPayload>),
+    /// A run paused for an urgent ticket, continued from its session by the
+    /// orchestrator. The paused run's journal entry stands until then.
+    Resume {
+        input: Box<agent::orchestrator::OrchestratorInput>,
+        paused_run_id: String,
+    },
+}
+
+/// Start the ticket if a scheduler slot is free, else queue it by the
+/// ticket's priority. An urgent ticket may instead pause the lowest-priority
+/// run when preemption is on.
+async fn schedule_ticket(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    payload: StartAgentPayload,
+) -> Result<(), String> {
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let Some(config) = project::settings::load_app().unwrap_or_default().scheduler else {
+        return dispatch_ticket(app, state, payload, run_id).await;
+    };
+    let priority = project::tickets::locate(std::path::Path::new(&payload.repo_root), &payload.ticket_id)
+        .map(|(_, ticket)| ticket.priority)
+        .unwrap_or_default();
+    let slot = agent::scheduler::Slot {
+        run_id: run_id.clone(),
+        ticket_id: payload.ticket_id.clone(),
+        agent_id: payload.agent_id.clone(),
+        priority,
+    };
+    let (waiting, victim) = {
+        let mut scheduler = state.scheduler.lock().unwrap();
+        // A takeover stops the run holding the ticket, so it reuses that slot
+        if payload.force_takeover {
+            scheduler.release_ticket(&payload.ticket_id);
+        }
+        match scheduler.try_start(&config, slot) {
+            Ok(()) => (None, None),
+            Err(slot) => {
+                let victim = scheduler.preemption_candidate(&config, slot.priority);
+                (Some(slot), victim)
+            }
+        }
+    };
+    if let Some(slot) = waiting {
+        // Only a run with a session can be paused and picked up again
+        let paused = victim.and_then(|v| {
+            let run = agent::recovery::active_run(&v.ticket_id, &v.agent_id).ok().flatten()?;
+            let session_id = run.session_id?;
+            let input = agent::locks::stop(&state.run_locks, &v.ticket_id)?;
+            Some((v, session_id, run.worktree_path, input))
+        });
+        let Some((victim, session_id, worktree_path, input)) = paused else {
+            info!("[schedule_ticket] no free slot — queued ticket={} priority={:?}", slot.ticket_id, slot.priority);
+            state.scheduler.lock().unwrap().enqueue(slot, QueuedRun::Dispatch(Box::new(payload)));
+            emit_run_queue(app, state);
+            return Ok(());
+        };
+        info!("[schedule_ticket] ticket={} preempts ticket={}", slot.ticket_id, victim.ticket_id);
+        set_status(&state.agents, &victim.agent_id, AgentStatus::Idle).await;
+        // Continue the build from its session; the review phases follow as usual
+        let resumed = agent::orchestrator::OrchestratorInput {
+            prompt: agent::scheduler::RESUME_PROMPT.to_string(),
+            worktree_path_override: Some(worktree_path),
+            resume_session_id: Some(session_id),
+            pair: None,
+            tournament: None,
+            test_first: None,
+            ..input
+        };
+        {
+            let mut scheduler = state.scheduler.lock().unwrap();
+            scheduler.replace(&victim.run_id, slot);
+            // The victim's journal entry stays, so a crash while it waits leaves it resumable
+            scheduler.enqueue(
+                agent::scheduler::Slot {
+                    run_id: uuid::Uuid::new_v4().to_string(),
+                    ..victim.clone()
+                },
+                QueuedRun::Resume {
+                    input: Box::new(resumed),
+                    paused_run_id: victim.run_id.clone(),
+                },
+            );
+        }
+        let _ = app.emit("run-preempted", &victim);
+        emit_run_queue(app, state);
+    }
+
+    let result = dispatch_ticket(app, state, payload, run_id.clone()).await;
+    if result.is_err() {
+        release_slot(app, &run_id);
+    }
+    result
+}
+
+fn emit_run_queue(app: &tauri::AppHandle, state: &AppState) {
+    let queued = state.scheduler.lock().unwrap().queued();
+    let _ = app.emit("run-queue-changed", &queued);
+}
+
+/// Free the scheduler slot of the run `run_id` and start whatever queued
+/// runs now fit.
+fn release_slot(app: &tauri::AppHandle, run_id: &str) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    // With the scheduler switched off, drain the queue
+    let config = project::settings::load_app()
+        .unwrap_or_default()
+        .scheduler
+        .unwrap_or(agent::scheduler::SchedulerConfig {
+            max_concurrent: usize::MAX,
+            preempt: false,
+        });
+    let ready: Vec<(agent::scheduler::Slot, QueuedRun)> = {
+        let mut scheduler = state.scheduler.lock().unwrap();
+        scheduler.release(run_id);
+        std::iter::from_fn(|| scheduler.next(&config)).collect()
+    };
+    if ready.is_empty() {
+        return;
+    }
+    emit_run_queue(app, &state);
+    for (slot, run) in ready {
+        tauri::async_runtime::spawn(start_queued(app.clone(), slot, run));
+    }
+}
+
+async fn start_queued(app: tauri::AppHandle, slot: agent::scheduler::Slot, run: QueuedRun) {
+    let state = app.state::<AppState>();
+    info!("[start_queued] starting ticket={} agent={}", slot.ticket_id, slot.agent_id);
+    let result = match run {
+        QueuedRun::Dispatch(payload) => dispatch_ticket(&app, &state, *payload, slot.run_id.clone()).await,
+        QueuedRun::Resume { input, paused_run_id } => {
+            continue_paused(&app, &state, &slot.run_id, *input, &paused_run_id).await
+        }
+    };
+    if let Err(e) = result {
+        error!("[start_queued] ticket={} failed to start: {}", slot.ticket_id, e);
+        release_slot(&app, &slot.run_id);
+    }
+}
+
+/// Pick a preempted run up again as `run_id`. It goes through the
+/// orchestrator, so it holds the ticket's lock and gets the review phases.
+async fn continue_paused(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    run_id: &str,
+    input: agent::orchestrator::OrchestratorInput,
+    paused_run_id: &str,
+) -> Result<(), String> {
+    continue_run(app, state, run_id, input).await?;
+    // The new entry takes over from the paused run's
+    let _ = agent::recovery::finish(paused_run_id);
+    project::telemetry::record(project::telemetry::Feature::RunResumed);
+    Ok(())
+}
+
+/// Start `input` in the worktree it names, under a lock and a journal entry
+/// of its own — for runs that carry on work already done.
+async fn continue_run(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    run_id: &str,
+    input: agent::orchestrator::OrchestratorInput,
+) -> Result<(), String> {
+    refuse_over_budget(app)?;
+    let worktree_path = input.worktree_path_override.clone().unwrap_or_else(|| {
+        git::worktree::Worktree::path_for(std::path::Path::new(&input.repo_root), &input.ticket_id)
+            .to_string_lossy()
+            .to_string()
+    });
+    let reservation = agent::locks::reserve(
+        &state.run_locks,
+        run_id,
+        &input.ticket_id,
+        &input.agent_id,
+        input.worktree_path_override.as_deref(),
+        false,
+    )?;
+    agent::state::update_agent(&state.agents, &input.agent_id, |a| {
+        a.status = AgentStatus::Working;
+        a.current_ticket_id = Some(input.ticket_id.clone());
+        a.worktree_path = Some(worktree_path.clone());
+    })
+    .await;
+
+    let marker = agent::recovery::RunMarker {
+        session_id: input.resume_session_id.clone(),
+        ..agent::recovery::RunMarker::new(run_id, &input.agent_id, &input.ticket_id, &worktree_path)
+            .with_input(&input)
+    };
+    if let Err(e) = agent::recovery::begin(marker) {
+        warn!("[continue_run] failed to journal run: {:#}", e);
+    }
+    spawn_ticket_run(app, state, reservation, input);
+    Ok(())
+}
+
+/// Agent runs per local day over `range`, for the activity calendar.
+#[tauri::command]
+fn get_run_history(range: agent::history::DateRange) -> Result<Vec<agent::history::DayBucket>, String> {
+    let runs = agent::history::load().map_err(|e| format!("{:#}", e))?;
+    agent::history::by_day(&runs, &range).map_err(|e| format!("{:#}", e))
+}
+
+/// Agent spend in `range` as CSV, one row per agent, ticket or day, for
+/// budgeting spreadsheets.
+#[tauri::command]
+fn export_costs(range: agent::history::DateRange, group_by: agent::costs::GroupBy) -> Result<String, String> {
+    let entries = agent::costs::load().map_err(|e| format!("{:#}", e))?;
+    agent::costs::to_csv(&entries, &range, group_by).map_err(|e| format!("{:#}", e))
+}
+
+/// Check that the project's LLM gateway answers and accepts `api_key`, as
+/// an agent run would reach it.
+#[tauri::command]
+async fn test_gateway(
+    project_root: String,
+    api_key: Option<String>,
+) -> Result<agent::gateway::GatewayCheck, String> {
+    tokio::task::spawn_blocking(move || {
+        let settings = project::settings::load_project(std::path::Path::new(&project_root))?;
+        let gateway = settings
+            .gateway
+            .ok_or_else(|| anyhow::anyhow!("project has no gateway configured"))?;
+        agent::gateway::check(&gateway, api_key.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// Hand the backend the vault's Anthropic keys to rotate among. Kept in
+/// memory only; the frontend calls this whenever it loads or saves them.
+#[tauri::command]
+fn set_api_keys(keys: Vec<agent::keys::ApiKey>) {
+    agent::keys::set(keys);
+}
+
+/// Runs and spend billed to each vault API key.
+#[tauri::command]
+fn get_api_key_usage() -> Result<Vec<agent::keys::KeyUsage>, String> {
+    agent::keys::usage().map_err(|e| format!("{:#}", e))
+}
+
+/// How work has spread over the agents in the last `days` days (14 when
+/// unset): time working, waiting and idle, and tickets completed.
+#[tauri::command]
+async fn get_agent_utilization(days: Option<u32>) -> Result<Vec<agent::utilization::AgentUtilization>, String> {
+    tokio::task::spawn_blocking(move || agent::utilization::over_days(days.unwrap_or(14)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Runs waiting for a scheduler slot, in the order they'll start.
+#[tauri::command]
+fn get_run_queue(state: State<'_, AppState>) -> Vec<agent::scheduler::Slot> {
+    state.scheduler.lock().unwrap().queued()
+}
+
+/// The crash-recovery journal entry for a run of `payload`.
+fn run_marker(run_id: &str, payload: &StartAgentPayload) -> agent::recovery::RunMarker {
+    let worktree_path = payload.worktree_path_override.clone().unwrap_or_else(|| {
+        git::worktree::Worktree::path_for(std::path::Path::new(&payload.repo_root), &payload.ticket_id)
+            .to_string_lossy()
+            .to_string()
+    });
+    agent::recovery::RunMarker::new(run_id, &payload.agent_id, &payload.ticket_id, &worktree_path)
+}
+
+/// Refuse to start new work once a spending cap is hit.
+fn refuse_over_budget(app: &tauri::AppHandle) -> Result<(), String> {
+    match agent::costs::over_budget() {
+        Some(exceeded) => {
+            let _ = app.emit("budget-exceeded", &exceeded);
+            Err(exceeded.message())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Mint tokens, run the GitHub pre-flight, and hand the ticket to the
+/// orchestrator as run `run_id`.
+async fn dispatch_ticket(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    payload: StartAgentPayload,
+    run_id: String,
+) -> Result<(), String> {
+    refuse_over_budget(app)?;
     let agents_store = state.agents.clone();
 
     info!("[start_agent] agent={} ticket={} repo={}", payload.agent_id, payload.ticket_id, payload.repo_root);
 
+    // Two runs on one ticket would race on its worktree
+    let mut reservation = agent::locks::reserve(
+        &state.run_locks,
+        &run_id,
+        &payload.ticket_id,
+        &payload.agent_id,
+        payload.worktree_path_override.as_deref(),
+        payload.force_takeover,
+    )?;
+
+    let gh_token = match payload.github_app {
+        Some(ref creds) => {
+            let cache = state.github_app_tokens.clone();
+            let creds = creds.clone();
+            let installation = creds.installation_id.clone();
+            let token = tokio::task::spawn_blocking(move || {
+                github::app_auth::installation_token(&cache, &creds)
+            })
+            .await
+            .map_err(|e| format!("GitHub App token task panicked: {}", e))?
+            .map_err(|e| format!("{:#}", e))?;
+            project::audit_log::record(
+                AuditEntry::new(Actor::Automation, AuditKind::TokenAccess, "issue_github_app_token")
+                    .agent(&payload.agent_id, &payload.ticket_id)
+                    .detail(format!("installation {}", installation)),
+            );
+            token
+        }
+        None => payload.gh_token.clone(),
+    };
+
+    // On Windows the run needs claude, gh and git inside the project's distro
+    if cfg!(target_os = "windows") {
+        if let Err(e) = preflight_wsl(&payload.repo_root).await {
+            error!("[start_agent] WSL preflight failed for agent={}: {}", payload.agent_id, e);
+            let _ = app.emit("agent-preflight-failed", serde_json::json!({
+                "agent_id": payload.agent_id,
+                "ticket_id": payload.ticket_id,
+                "error": e,
+            }));
+            return Err(e);
+        }
+    }
+
+    // Fail fast on tokens that can't open PRs, rather than letting the agent
+    // die mid-run at `gh pr create`, and learn how the agent may push.
+    let access = match preflight_github(&payload.repo_root, &gh_token).await {
+        Ok(access) => access,
+        Err(e) => {
+            error!("[start_agent] preflight failed for agent={}: {}", payload.agent_id, e);
+            let _ = app.emit("agent-preflight-failed", serde_json::json!({
+                "agent_id": payload.agent_id,
+                "ticket_id": payload.ticket_id,
+                "error": e,
+            }));
+            return Err(e);
+        }
+    };
+
+    let mut system_prompt = payload.system_prompt.clone();
+    let mut fork = None;
+    if let Some(ref access) = access {
+        if !access.warnings.is_empty() {
+            let _ = app.emit("repo-access-warning", serde_json::json!({
+                "agent_id": payload.agent_id,
+                "ticket_id": payload.ticket_id,
+                "warnings": access.warnings,
+            }));
+        }
+
+        // Route through a fork when upstream forbids pushes or the project asks for it
+        let settings = project::settings::load_project(std::path::Path::new(&payload.repo_root))
+            .unwrap_or_default();
+        if access.push_mode == github::access::PushMode::Fork || settings.fork_mode {
+            fork = Some(prepare_fork(&payload.repo_root, &access.repo, &gh_token).await?);
+        }
+
+        system_prompt = format!(
+            "{}\n\n{}",
+            system_prompt,
+            context::builder::repo_access_section(
+                &access.default_branch,
+                access.default_branch_protected,
+                fork.as_ref().map(|f| (f.upstream.as_str(), f.owner.as_str())),
+            )
+        );
+    }
+
+    // Library snippets attached to this agent's role or the ticket
+    let role = get_agent(&agents_store, &payload.agent_id)
+        .await
+        .map(|a| a.role)
+        .unwrap_or_default();
+    let snippets = context::snippets::for_run(std::path::Path::new(&payload.repo_root), &role, &payload.ticket_id);
+    if let Some(section) = context::builder::snippets_section(&snippets) {
+        system_prompt = format!("{}\n\n{}", system_prompt, section);
+    }
+    // Earlier runs' answers on this ticket, so the agent doesn't ask again
+    if let Some(answers) = project::qa::load(&payload.ticket_id).ok().and_then(|qa| project::qa::as_prompt(&qa)) {
+        system_prompt = format!("{}\n\n{}", system_prompt, answers);
+    }
+
+    // Preflight passed: only now stop the run being taken over
+    if let Some(previous) = reservation.take_over() {
+        info!("[start_agent] agent={} took over ticket={} from agent={}", payload.agent_id, payload.ticket_id, previous);
+        agent::state::update_agent(&agents_store, &previous, |a| {
+            a.status = AgentStatus::Idle;
+            a.current_ticket_id = None;
+            a.worktree_path = None;
+        }).await;
+        let _ = app.emit(
+            "run-taken-over",
+            &agent::locks::RunTakenOverPayload {
+                ticket_id: payload.ticket_id.clone(),
+                previous_agent_id: previous,
+                agent_id: payload.agent_id.clone(),
+            },
+        );
+    }
+
     // Mark agent as working
-    set_status(&agents_store, &payload.agent_id, AgentStatus::Working);
-    if let Some(mut a) = get_agent(&agents_store, &payload.agent_id) {
+    agent::state::update_agent(&agents_store, &payload.agent_id, |a| {
+        a.status = AgentStatus::Working;
         a.current_ticket_id = Some(payload.ticket_id.clone());
-        upsert_agent(&agents_store, a);
-    }
+    }).await;
 
     // Build the OrchestratorInput from the payload — the orchestrator handles
     // worktree creation, phase prompt appending, and tool set selection internally.
@@ -152,43 +968,257 @@ async fn start_agent(
         .unwrap_or("build")
         .to_string();
 
-    let mcp_port = state.mcp.port;
-
+    let marker = run_marker(reservation.run_id(), &payload);
     let orchestrator_input = agent::orchestrator::OrchestratorInput {
         agent_id: payload.agent_id.clone(),
         ticket_id: payload.ticket_id.clone(),
         ticket_slug: payload.ticket_slug.clone(),
         prompt: payload.prompt.clone(),
-        system_prompt: payload.system_prompt.clone(),
+        system_prompt,
         repo_root: payload.repo_root.clone(),
-        gh_token: payload.gh_token.clone(),
+        gh_token,
+        github_app: payload.github_app.clone(),
         phase: phase_str,
         worktree_path_override: payload.worktree_path_override,
         plan_artifact: payload.plan_artifact,
         group_id: None,
+        gitlab_token: payload.gitlab_token,
+        fork,
+        resume_session_id: None,
+        max_turns: None,
+        pair: payload.pair,
+        tournament: payload.tournament,
+        test_first: payload.test_first,
+        pr_gate: payload.pr_gate,
     };
-
-    let app_clone = app.clone();
-    let agents_store_clone = agents_store.clone();
-    let agent_id = payload.agent_id.clone();
+    if let Err(e) = agent::recovery::begin(marker.with_input(&orchestrator_input)) {
+        warn!("[start_agent] failed to journal run: {:#}", e);
+    }
 
     info!("[start_agent] dispatching to orchestrator for agent={}", payload.agent_id);
+    spawn_ticket_run(app, state, reservation, orchestrator_input);
+    project::telemetry::record(project::telemetry::Feature::RunStarted);
 
-    // Spawn the orchestrator run as a background task — this command returns immediately
-    tokio::spawn(async move {
-        match agent::orchestrator::run_ticket(orchestrator_input, app_clone, mcp_port).await {
+    Ok(())
+}
+
+/// Run `input` through the orchestrator as a background task holding
+/// `reservation`. The run's lock, journal entry and scheduler slot are given
+/// up when it ends.
+fn spawn_ticket_run(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    reservation: agent::locks::Reservation,
+    input: agent::orchestrator::OrchestratorInput,
+) {
+    let app_clone = app.clone();
+    let agents_store = state.agents.clone();
+    let mcp_port = state.mcp.port;
+    let (locks, agent_id, ticket_id, run_id) = (
+        state.run_locks.clone(),
+        input.agent_id.clone(),
+        input.ticket_id.clone(),
+        reservation.run_id().to_string(),
+    );
+
+    // Spawn the orchestrator run as a background task — the command returns immediately
+    let started_at = chrono::Utc::now().timestamp();
+    let run_input = input.clone();
+    let run = tokio::spawn(async move {
+        match agent::orchestrator::run_ticket(run_input, app_clone.clone(), mcp_port).await {
             Ok(()) => {
                 info!("[start_agent] agent={} orchestrator completed", agent_id);
-                set_status(&agents_store_clone, &agent_id, AgentStatus::Idle);
+                set_status(&agents_store, &agent_id, AgentStatus::Idle).await;
             }
             Err(e) => {
                 error!("[start_agent] orchestrator failed: {}", e);
-                set_status(&agents_store_clone, &agent_id, AgentStatus::Blocked);
+                let status = agent::checkpoints::status_after_failure(&agent_id, &ticket_id, started_at);
+                set_status(&agents_store, &agent_id, status).await;
             }
         }
+        agent::locks::release(&locks, &ticket_id, &run_id);
+        let _ = agent::recovery::finish(&run_id);
+        release_slot(&app_clone, &run_id);
     });
+    reservation.commit(run.abort_handle(), Some(input));
+}
 
-    Ok(())
+/// Validate the GitHub token against the repo before a run, then inspect push
+/// rights and default-branch protection.
+/// Skipped (Ok(None)) for non-GitHub remotes and when no token was supplied.
+async fn preflight_github(
+    repo_root: &str,
+    gh_token: &str,
+) -> Result<Option<github::access::RepoAccess>, String> {
+    if gh_token.is_empty() {
+        return Ok(None);
+    }
+    let repo_root = PathBuf::from(repo_root);
+    let token = gh_token.to_string();
+    tokio::task::spawn_blocking(move || {
+        let Some(slug) = github::token::repo_slug_for(&repo_root) else {
+            return Ok(None);
+        };
+        let check = github::token::check_token(&slug, &token).map_err(|e| format!("{:#}", e))?;
+        if !check.is_ok() {
+            return Err(format!(
+                "GitHub token cannot be used for this run: {}",
+                check.problems.join("; ")
+            ));
+        }
+        github::access::check_repo_access(&slug, &token)
+            .map(Some)
+            .map_err(|e| format!("{:#}", e))
+    })
+    .await
+    .map_err(|e| format!("preflight panicked: {}", e))?
+}
+
+/// Check the project's WSL distro holds the repo and has claude, gh and git,
+/// so a broken setup fails here rather than mid-run.
+async fn preflight_wsl(repo_root: &str) -> Result<(), String> {
+    let repo_root = PathBuf::from(repo_root);
+    tokio::task::spawn_blocking(move || {
+        let settings = project::settings::load_project(&repo_root).unwrap_or_default();
+        let distro = platform::wsl::choose(settings.wsl_distro.as_deref(), &repo_root)?;
+        platform::wsl::ensure_ready(&distro)
+    })
+    .await
+    .map_err(|e| format!("WSL preflight panicked: {}", e))?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// Fork the upstream repo (or reuse the existing fork) and register it as the
+/// `fork` remote so the agent can push there.
+async fn prepare_fork(
+    repo_root: &str,
+    upstream: &str,
+    gh_token: &str,
+) -> Result<github::fork::ForkTarget, String> {
+    let repo_root = PathBuf::from(repo_root);
+    let upstream = upstream.to_string();
+    let token = gh_token.to_string();
+    tokio::task::spawn_blocking(move || {
+        let fork = github::fork::ensure_fork(&upstream, &token)?;
+        github::fork::add_fork_remote(&repo_root, &fork)?;
+        info!("[start_agent] using fork {} for upstream {}", fork.full_name, upstream);
+        Ok::<_, anyhow::Error>(fork)
+    })
+    .await
+    .map_err(|e| format!("fork setup panicked: {}", e))?
+    .map_err(|e| format!("fork setup failed: {:#}", e))
+}
+
+/// Inspect push rights and branch protection for a project's GitHub repo.
+/// Returns None when the repo has no GitHub origin remote.
+#[tauri::command]
+async fn check_repo_access(
+    repo_root: String,
+    gh_token: String,
+) -> Result<Option<github::access::RepoAccess>, String> {
+    tokio::task::spawn_blocking(move || {
+        github::token::repo_slug_for(std::path::Path::new(&repo_root))
+            .map(|slug| github::access::check_repo_access(&slug, &gh_token))
+            .transpose()
+    })
+    .await
+    .map_err(|e| format!("access check panicked: {}", e))?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// Check what the given GitHub token can do against a project's repo.
+/// Returns None when the repo has no GitHub origin remote.
+#[tauri::command]
+async fn check_github_token(
+    repo_root: String,
+    gh_token: String,
+) -> Result<Option<github::token::TokenCheck>, String> {
+    tokio::task::spawn_blocking(move || {
+        github::token::repo_slug_for(std::path::Path::new(&repo_root))
+            .map(|slug| github::token::check_token(&slug, &gh_token))
+            .transpose()
+    })
+    .await
+    .map_err(|e| format!("token check panicked: {}", e))?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// The `owner/name` of a project's GitHub origin.
+async fn github_slug(repo_root: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        github::token::repo_slug_for(std::path::Path::new(&repo_root))
+            .ok_or_else(|| format!("{} has no GitHub origin remote", repo_root))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Open issues on a project's GitHub repo, for importing as tickets. Uses
+/// the API when given a token, the gh CLI otherwise.
+#[tauri::command]
+async fn list_github_issues(
+    repo_root: String,
+    gh_token: Option<String>,
+) -> Result<Vec<github::client::GitHubIssue>, String> {
+    let repo = github_slug(repo_root).await?;
+    github::client::GitHubClient::new(gh_token.as_deref())
+        .issues(&repo)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// A client for the project's GitLab instance and its origin remote's
+/// project there.
+async fn gitlab_client(
+    repo_root: String,
+    gitlab_token: Option<String>,
+) -> Result<gitlab::client::GitLabClient, String> {
+    tokio::task::spawn_blocking(move || {
+        let root = std::path::Path::new(&repo_root);
+        let config = project::settings::load_project(root)
+            .map_err(|e| format!("{:#}", e))?
+            .gitlab
+            .ok_or_else(|| format!("{} has no GitLab instance configured", repo_root))?;
+        let project = git::scan::get_remote_url(root)
+            .and_then(|url| gitlab::client::project_path(&url, &config))
+            .ok_or_else(|| format!("{} has no origin remote on {}", repo_root, config.host()))?;
+        Ok(gitlab::client::GitLabClient::new(config, gitlab_token.as_deref(), &project))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Open issues on a project's GitLab project, for importing as tickets.
+#[tauri::command]
+async fn list_gitlab_issues(
+    repo_root: String,
+    gitlab_token: Option<String>,
+) -> Result<Vec<gitlab::client::GitLabIssue>, String> {
+    gitlab_client(repo_root, gitlab_token)
+        .await?
+        .issues()
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Merge a PR on a project's GitHub repo, returning the merge commit's sha.
+#[tauri::command]
+async fn merge_pull_request(
+    repo_root: String,
+    pr_number: u32,
+    method: github::client::MergeMethod,
+    gh_token: Option<String>,
+) -> Result<String, String> {
+    let repo = github_slug(repo_root).await?;
+    let sha = github::client::GitHubClient::new(gh_token.as_deref())
+        .merge(&repo, pr_number, method)
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    project::audit_log::record(
+        AuditEntry::new(Actor::User, AuditKind::Merge, "merge_pull_request")
+            .detail(format!("{}#{} ({:?}) as {}", repo, pr_number, method, sha)),
+    );
+    Ok(sha)
 }
 
 /// Resume a paused agent session with a user reply.
@@ -203,21 +1233,354 @@ async fn resume_agent(
     session_id: String,
     prompt: String,
 ) -> Result<(), String> {
-    let agents_store = state.agents.clone();
+    let args = serde_json::json!({ "agent_id": agent_id, "session_id": session_id });
+    let result = resume_session(&app, &state, agent_id, session_id, prompt).await;
+    audited(Actor::User, "resume_agent", args, result)
+}
 
-    let agent = get_agent(&agents_store, &agent_id)
+/// Build a prompt for resuming `agent_id` from its reply, PR reviews,
+/// failing checks, and new default-branch commits, as `include` selects.
+#[tauri::command]
+async fn compose_resume_prompt(
+    state: State<'_, AppState>,
+    agent_id: String,
+    include: agent::resume::ResumeInclude,
+) -> Result<String, String> {
+    let agent = get_agent(&state.agents, &agent_id)
+        .await
         .ok_or_else(|| format!("agent '{}' not found", agent_id))?;
+    tokio::task::spawn_blocking(move || {
+        let context = agent::resume::gather(&agent, include);
+        agent::resume::compose(agent.pr_number, &context)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
 
-    let worktree_path = agent
-        .worktree_path
-        .as_ref()
-        .ok_or_else(|| format!("agent '{}' has no worktree — cannot resume", agent_id))?;
-
-    let working_dir = PathBuf::from(worktree_path);
+/// Runs that were queued or in flight when the app last quit.
+#[tauri::command]
+fn get_interrupted_runs() -> Result<Vec<agent::recovery::RunMarker>, String> {
+    agent::recovery::interrupted().map_err(|e| format!("{:#}", e))
+}
+
+/// Pick an interrupted run up again. A queued run is started as it was
+/// asked for; one in flight continues from its last session through the
+/// rest of its phases, or starts over when it never reported one. Runs
+/// journaled without their input can only resume their session.
+/// `secrets` replaces the tokens the journal leaves out.
+#[tauri::command]
+async fn resume_interrupted_run(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    run_id: String,
+    secrets: agent::recovery::Secrets,
+) -> Result<(), String> {
+    let args = serde_json::json!({ "run_id": run_id });
+    let result: Result<(), String> = async {
+        let run = agent::recovery::interrupted()
+            .map_err(|e| format!("{:#}", e))?
+            .into_iter()
+            .find(|r| r.run_id == run_id)
+            .ok_or_else(|| format!("no interrupted run '{}'", run_id))?;
+        if let Some(payload) = run.payload {
+            start_or_defer(app.clone(), &state, secrets.restore_payload(payload)).await?;
+            agent::recovery::take_interrupted(&run_id).map_err(|e| format!("{:#}", e))?;
+            return Ok(());
+        }
+        if let Some(input) = run.input {
+            let input = secrets.restore(input);
+            let worktree = std::path::Path::new(&run.worktree_path)
+                .is_dir()
+                .then(|| run.worktree_path.clone());
+            let input = match run.session_id {
+                // The sub-runs have markers of their own to resume
+                Some(session_id) => agent::orchestrator::OrchestratorInput {
+                    prompt: agent::recovery::RESUME_PROMPT.to_string(),
+                    worktree_path_override: worktree,
+                    resume_session_id: Some(session_id),
+                    pair: None,
+                    tournament: None,
+                    test_first: None,
+                    ..input
+                },
+                None => input,
+            };
+            continue_run(&app, &state, &uuid::Uuid::new_v4().to_string(), input).await?;
+            agent::recovery::take_interrupted(&run_id).map_err(|e| format!("{:#}", e))?;
+            return Ok(());
+        }
+        let session_id = run
+            .session_id
+            .clone()
+            .ok_or_else(|| format!("run '{}' never started a session — start the ticket again", run_id))?;
+        // The roster is rebuilt on launch without worktrees; point the agent back at this one
+        agent::state::update_agent(&state.agents, &run.agent_id, |a| {
+            a.worktree_path = Some(run.worktree_path.clone());
+            a.current_ticket_id = Some(run.ticket_id.clone());
+        })
+        .await
+        .ok_or_else(|| format!("agent '{}' not found", run.agent_id))?;
+
+        resume_session(&app, &state, run.agent_id, session_id, agent::recovery::RESUME_PROMPT.to_string()).await?;
+        agent::recovery::take_interrupted(&run_id).map_err(|e| format!("{:#}", e))?;
+        Ok(())
+    }
+    .await;
+    audited(Actor::User, "resume_interrupted_run", args, result)
+}
+
+/// Writes to protected paths that stopped their runs, awaiting a decision.
+#[tauri::command]
+fn get_protected_edits() -> Result<Vec<agent::guard::ProtectedEdit>, String> {
+    agent::guard::pending_edits().map_err(|e| format!("{:#}", e))
+}
+
+/// Let the agent write a protected path for the rest of its ticket and
+/// resume the run the write stopped.
+#[tauri::command]
+async fn approve_protected_edit(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    edit_id: String,
+) -> Result<(), String> {
+    let edit = agent::guard::approve_edit(&edit_id)
+        .map_err(|e| format!("{:#}", e))?
+        .ok_or_else(|| format!("no pending protected edit '{}'", edit_id))?;
+    project::audit_log::record(
+        AuditEntry::new(Actor::User, AuditKind::Approval, "approve_protected_edit")
+            .agent(&edit.agent_id, &edit.ticket_id)
+            .detail(format!("{} {}", edit.tool_name, edit.path)),
+    );
+    info!(
+        "[approve_protected_edit] agent={} ticket={} path={}",
+        edit.agent_id, edit.ticket_id, edit.path
+    );
+    let session_id = edit.session_id.clone().ok_or_else(|| {
+        format!("the run stopped before it started a session — start ticket '{}' again", edit.ticket_id)
+    })?;
+    let prompt = format!(
+        "A human approved your {} of `{}`, a protected path. Make that change and continue where you left off.",
+        edit.tool_name, edit.path
+    );
+    resume_session(&app, &state, edit.agent_id, session_id, prompt).await
+}
+
+/// Refuse a protected-path write. The guard stopped it before it happened,
+/// so there's nothing to undo; the agent is left blocked.
+#[tauri::command]
+async fn reject_protected_edit(state: State<'_, AppState>, edit_id: String) -> Result<(), String> {
+    let edit = agent::guard::reject_edit(&edit_id)
+        .map_err(|e| format!("{:#}", e))?
+        .ok_or_else(|| format!("no pending protected edit '{}'", edit_id))?;
+    project::audit_log::record(
+        AuditEntry::new(Actor::User, AuditKind::Approval, "reject_protected_edit")
+            .agent(&edit.agent_id, &edit.ticket_id)
+            .detail(format!("{} {}", edit.tool_name, edit.path)),
+    );
+    info!(
+        "[reject_protected_edit] agent={} ticket={} path={}",
+        edit.agent_id, edit.ticket_id, edit.path
+    );
+    set_status(&state.agents, &edit.agent_id, AgentStatus::Blocked).await;
+    Ok(())
+}
+
+/// PRs agents asked to open, awaiting the user's decision.
+#[tauri::command]
+fn get_pr_requests() -> Vec<agent::pr_approval::PrRequest> {
+    agent::pr_approval::pending()
+}
+
+/// Let the agent open its PR and resume the run that asked.
+#[tauri::command]
+async fn approve_pr_request(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    request_id: String,
+) -> Result<(), String> {
+    let request = agent::pr_approval::approve(&request_id)
+        .map_err(|e| format!("{:#}", e))?
+        .ok_or_else(|| format!("no pending PR request '{}'", request_id))?;
+    project::audit_log::record(
+        AuditEntry::new(Actor::User, AuditKind::Approval, "approve_pr_request")
+            .agent(&request.agent_id, &request.ticket_id)
+            .detail(request.title.clone().unwrap_or_default()),
+    );
+    info!(
+        "[approve_pr_request] agent={} ticket={}",
+        request.agent_id, request.ticket_id
+    );
+    // A phase waiting on the request carries on itself, through its later phases
+    let decision = agent::pr_approval::Decision::Approved;
+    if agent::pr_approval::decide(&state.pr_decisions, &request.agent_id, &request.ticket_id, decision).is_ok() {
+        return Ok(());
+    }
+    let session_id = request.session_id.clone().ok_or_else(|| {
+        format!("the run stopped before it started a session — start ticket '{}' again", request.ticket_id)
+    })?;
+    // The approval is recorded, so the resumed run's hooks let it through
+    resume_session(
+        &app,
+        &state,
+        request.agent_id,
+        session_id,
+        agent::pr_approval::APPROVED_PROMPT.to_string(),
+    )
+    .await
+}
+
+/// Turn down a PR request. With feedback, the agent is resumed to address it
+/// and ask again; without, it stays stopped.
+#[tauri::command]
+async fn reject_pr_request(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    request_id: String,
+    feedback: Option<String>,
+) -> Result<(), String> {
+    let request = agent::pr_approval::reject(&request_id)
+        .map_err(|e| format!("{:#}", e))?
+        .ok_or_else(|| format!("no pending PR request '{}'", request_id))?;
+    project::audit_log::record(
+        AuditEntry::new(Actor::User, AuditKind::Approval, "reject_pr_request")
+            .agent(&request.agent_id, &request.ticket_id)
+            .detail(request.title.clone().unwrap_or_default()),
+    );
+    info!(
+        "[reject_pr_request] agent={} ticket={}",
+        request.agent_id, request.ticket_id
+    );
+    let feedback = feedback.filter(|f| !f.trim().is_empty());
+    let decision = agent::pr_approval::Decision::Rejected(feedback.clone());
+    if agent::pr_approval::decide(&state.pr_decisions, &request.agent_id, &request.ticket_id, decision).is_ok() {
+        return Ok(());
+    }
+    let (Some(feedback), Some(session_id)) = (feedback, request.session_id) else {
+        return Ok(());
+    };
+    let prompt = agent::pr_approval::rejected_prompt(&feedback);
+    resume_session(&app, &state, request.agent_id, session_id, prompt).await
+}
+
+/// Checkpoint presets to offer when configuring a project.
+#[tauri::command]
+fn get_checkpoint_presets() -> Vec<agent::checkpoints::Checkpoint> {
+    agent::checkpoints::presets()
+}
+
+/// Runs paused at a checkpoint, awaiting sign-off.
+#[tauri::command]
+fn get_checkpoint_hits() -> Vec<agent::checkpoints::CheckpointHit> {
+    agent::checkpoints::pending()
+}
+
+/// Sign off on a checkpoint and resume the run it paused. The agent isn't
+/// stopped at that checkpoint again on this ticket.
+#[tauri::command]
+async fn approve_checkpoint(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    hit_id: String,
+) -> Result<(), String> {
+    let hit = agent::checkpoints::approve(&hit_id)
+        .map_err(|e| format!("{:#}", e))?
+        .ok_or_else(|| format!("no pending checkpoint '{}'", hit_id))?;
+    project::audit_log::record(
+        AuditEntry::new(Actor::User, AuditKind::Approval, "approve_checkpoint")
+            .agent(&hit.agent_id, &hit.ticket_id)
+            .detail(format!("{}: {}", hit.checkpoint, hit.detail)),
+    );
+    info!(
+        "[approve_checkpoint] agent={} ticket={} checkpoint={}",
+        hit.agent_id, hit.ticket_id, hit.checkpoint
+    );
+    let session_id = hit.session_id.clone().ok_or_else(|| {
+        format!("the run stopped before it started a session — start ticket '{}' again", hit.ticket_id)
+    })?;
+    let prompt = format!(
+        "The user signed off on \"{}\" for `{}`. Go ahead with it and continue where you left off.",
+        hit.checkpoint, hit.detail
+    );
+    resume_session(&app, &state, hit.agent_id, session_id, prompt).await
+}
+
+/// Turn down a checkpoint; the run stays stopped.
+#[tauri::command]
+async fn reject_checkpoint(state: State<'_, AppState>, hit_id: String) -> Result<(), String> {
+    let hit = agent::checkpoints::reject(&hit_id)
+        .map_err(|e| format!("{:#}", e))?
+        .ok_or_else(|| format!("no pending checkpoint '{}'", hit_id))?;
+    project::audit_log::record(
+        AuditEntry::new(Actor::User, AuditKind::Approval, "reject_checkpoint")
+            .agent(&hit.agent_id, &hit.ticket_id)
+            .detail(format!("{}: {}", hit.checkpoint, hit.detail)),
+    );
+    info!(
+        "[reject_checkpoint] agent={} ticket={} checkpoint={}",
+        hit.agent_id, hit.ticket_id, hit.checkpoint
+    );
+    set_status(&state.agents, &hit.agent_id, AgentStatus::Blocked).await;
+    Ok(())
+}
+
+/// Privileged actions matching `filter`, newest first.
+#[tauri::command]
+fn get_audit_log(
+    filter: project::audit_log::AuditFilter,
+) -> Result<Vec<project::audit_log::AuditEntry>, String> {
+    project::audit_log::query(&filter).map_err(|e| format!("{:#}", e))
+}
+
+/// Forget an interrupted run without resuming it.
+#[tauri::command]
+fn dismiss_interrupted_run(run_id: String) -> Result<(), String> {
+    let result = agent::recovery::take_interrupted(&run_id)
+        .map(|_| ())
+        .map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "dismiss_interrupted_run", serde_json::json!({ "run_id": run_id }), result)
+}
+
+/// Start a resumed run in the background. Shared by `resume_agent` and
+/// automatic follow-ups such as CI triage.
+async fn resume_session(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    agent_id: String,
+    session_id: String,
+    prompt: String,
+) -> Result<(), String> {
+    refuse_over_budget(app)?;
+    let agents_store = state.agents.clone();
+
+    let agent = get_agent(&agents_store, &agent_id).await
+        .ok_or_else(|| format!("agent '{}' not found", agent_id))?;
+
+    let worktree_path = agent
+        .worktree_path
+        .as_ref()
+        .ok_or_else(|| format!("agent '{}' has no worktree — cannot resume", agent_id))?;
+
+    let working_dir = PathBuf::from(worktree_path);
+    // Resume in the same environment the session was started in.
+    let project_settings = agent::backend::project_settings_for(&working_dir);
+
+    // A resume works in the worktree like any run, so it holds the lock too;
+    // a ticketless agent is locked by its worktree alone
+    let ticket_id = agent.current_ticket_id.clone().unwrap_or_default();
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let lock_key = if ticket_id.is_empty() { worktree_path } else { &ticket_id };
+    let reservation = agent::locks::reserve(
+        &state.run_locks,
+        &run_id,
+        lock_key,
+        &agent_id,
+        Some(worktree_path),
+        false,
+    )?;
 
     let run_config = agent::process::AgentRunConfig {
         agent_id: agent_id.clone(),
-        ticket_id: agent.current_ticket_id.clone().unwrap_or_default(),
+        ticket_id: ticket_id.clone(),
         prompt,
         // No system prompt: --resume replays the original session context from Claude's side.
         system_prompt: String::new(),
@@ -248,35 +1611,365 @@ async fn resume_agent(
         resume_session_id: Some(session_id),
         mcp_port: state.mcp.port,
         group_id: None,
+        backend: project_settings.execution,
+        limits: project_settings.limits,
+        max_turns: None,
+        pr_gate: None,
+        verify_before_pr: false,
     };
 
-    set_status(&agents_store, &agent_id, AgentStatus::Working);
+    set_status(&agents_store, &agent_id, AgentStatus::Working).await;
+    project::telemetry::record(project::telemetry::Feature::RunResumed);
+
+    let marker = agent::recovery::RunMarker {
+        session_id: run_config.resume_session_id.clone(),
+        ..agent::recovery::RunMarker::new(&run_id, &agent_id, &run_config.ticket_id, worktree_path)
+    };
+    if let Err(e) = agent::recovery::begin(marker) {
+        warn!("[resume_agent] failed to journal run: {:#}", e);
+    }
 
     let app_clone = app.clone();
     let agents_store_clone = agents_store.clone();
+    let (locks, lock_key) = (state.run_locks.clone(), lock_key.to_string());
+    let started_at = chrono::Utc::now().timestamp();
 
-    tokio::spawn(async move {
+    let run = tokio::spawn(async move {
         match agent::process::run(run_config, app_clone).await {
             Ok(new_session_id) => {
                 if let Some(sid) = new_session_id {
-                    agent::state::save_session_id(&agents_store_clone, &agent_id, &sid);
+                    agent::state::save_session_id(&agents_store_clone, &agent_id, &sid).await;
                 }
-                set_status(&agents_store_clone, &agent_id, AgentStatus::Idle);
+                set_status(&agents_store_clone, &agent_id, AgentStatus::Idle).await;
             }
             Err(e) => {
                 eprintln!("agent '{}' resume failed: {}", agent_id, e);
-                set_status(&agents_store_clone, &agent_id, AgentStatus::Blocked);
+                let status = agent::checkpoints::status_after_failure(&agent_id, &ticket_id, started_at);
+                set_status(&agents_store_clone, &agent_id, status).await;
             }
         }
+        agent::locks::release(&locks, &lock_key, &run_id);
+        let _ = agent::recovery::finish(&run_id);
     });
+    reservation.commit(run.abort_handle(), None);
+
+    Ok(())
+}
+
+/// Keep the winning tournament attempt and clean up the rest. The winner takes
+/// the ticket with its attempt's worktree and goes on to the review phases.
+#[tauri::command]
+async fn pick_tournament_winner(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    repo_root: String,
+    ticket_id: String,
+    ticket_slug: String,
+    winner_agent_id: String,
+    agent_ids: Vec<String>,
+) -> Result<String, String> {
+    let args = serde_json::json!({ "ticket_id": ticket_id, "winner_agent_id": winner_agent_id, "agent_ids": agent_ids });
+    let result: Result<String, String> = async {
+        let root = PathBuf::from(&repo_root);
+        let winner_path = agent::tournament::entry_path(&root, &ticket_id, &winner_agent_id);
+        agent::tournament::finish(&root, &ticket_id, &ticket_slug, &winner_agent_id, &agent_ids)
+            .await
+            .map_err(|e| format!("{:#}", e))?;
+
+        let worktree_path = winner_path.to_string_lossy().to_string();
+        match agent::tournament::winner_input(&state.tournaments, &ticket_id, &winner_agent_id) {
+            Some(input) => {
+                let run_id = uuid::Uuid::new_v4().to_string();
+                continue_run(&app, &state, &run_id, input).await?;
+            }
+            None => {
+                // The run's input didn't survive a restart; hand over the attempt
+                // and let the user start the reviews
+                warn!(
+                    "[pick_tournament_winner] no pending tournament for ticket={}, skipping reviews",
+                    ticket_id
+                );
+                agent::state::update_agent(&state.agents, &winner_agent_id, |winner| {
+                    winner.current_ticket_id = Some(ticket_id);
+                    winner.worktree_path = Some(worktree_path.clone());
+                }).await;
+            }
+        }
+        Ok(worktree_path)
+    }
+    .await;
+    audited(Actor::User, "pick_tournament_winner", args, result)
+}
+
+// ── Worktree watch commands ───────────────────────────────────────────────────
+
+/// Emit `worktree-file-changed` as the agent edits files, so the file tree
+/// and diff view update live. Call again after the worktree changes.
+#[tauri::command]
+async fn watch_worktree(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    agent_id: String,
+) -> Result<(), String> {
+    let worktree = agent_worktree(&state, &agent_id).await?;
+    git::watch::watch(&state.watchers, app, &agent_id, worktree)
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn unwatch_worktree(state: State<'_, AppState>, agent_id: String) {
+    git::watch::unwatch(&state.watchers, &agent_id);
+}
+
+// ── Terminal commands ─────────────────────────────────────────────────────────
+
+/// Open a shell in the agent's worktree. Output arrives as `terminal-output`
+/// events for the returned session id; `terminal-exit` follows when it ends.
+#[tauri::command]
+async fn open_worktree_shell(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    agent_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<String, String> {
+    let result = match agent_worktree(&state, &agent_id).await {
+        Ok(worktree) => agent::terminal::open(&state.terminals, app, &agent_id, &worktree, cols, rows)
+            .map_err(|e| format!("{:#}", e)),
+        Err(e) => Err(e),
+    };
+    audited(Actor::User, "open_worktree_shell", serde_json::json!({ "agent_id": agent_id }), result)
+}
+
+#[tauri::command]
+fn write_terminal(state: State<'_, AppState>, session_id: String, data: String) -> Result<(), String> {
+    agent::terminal::write(&state.terminals, &session_id, &data).map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn resize_terminal(
+    state: State<'_, AppState>,
+    session_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    agent::terminal::resize(&state.terminals, &session_id, cols, rows)
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+fn close_terminal(state: State<'_, AppState>, session_id: String) -> Result<(), String> {
+    let result = agent::terminal::close(&state.terminals, &session_id).map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "close_terminal", serde_json::json!({ "session_id": session_id }), result)
+}
+
+// ── Transcript commands ───────────────────────────────────────────────────────
+
+/// The full content of a tool result that was truncated for size
+/// (`full_size` set on its event).
+#[tauri::command]
+fn fetch_full_content(node_id: String) -> Result<serde_json::Value, String> {
+    agent::overflow::load(&node_id).map_err(|e| format!("{:#}", e))
+}
+
+/// Default page size for `get_events`.
+const EVENT_PAGE_SIZE: usize = 200;
+
+/// A page of an agent's persisted events on a ticket, newest last. Omit
+/// `cursor` for the latest events; pass the returned cursor for older ones.
+#[tauri::command]
+async fn get_events(
+    agent_id: String,
+    ticket_id: String,
+    cursor: Option<u64>,
+    limit: Option<usize>,
+) -> Result<agent::transcript::EventPage, String> {
+    let entries = tokio::task::spawn_blocking(move || agent::transcript::load(&agent_id, &ticket_id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+    Ok(agent::transcript::page(entries, cursor, limit.unwrap_or(EVENT_PAGE_SIZE).max(1)))
+}
+
+/// Re-emit an agent's recorded run on a ticket with its original pacing,
+/// `speed` times faster, as `replay-event`s. Returns the replay id.
+#[tauri::command]
+async fn replay_run(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    agent_id: String,
+    ticket_id: String,
+    speed: f64,
+) -> Result<String, String> {
+    let (agent, ticket) = (agent_id.clone(), ticket_id.clone());
+    let entries = tokio::task::spawn_blocking(move || agent::transcript::load(&agent, &ticket))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+    if entries.is_empty() {
+        return Err(format!("no transcript for agent '{}' on ticket '{}'", agent_id, ticket_id));
+    }
+    agent::replay::start(&state.replays, app, &agent_id, &ticket_id, entries, speed)
+}
 
+/// Stop a replay before it reaches the end.
+#[tauri::command]
+fn stop_replay(app: tauri::AppHandle, state: State<'_, AppState>, replay_id: String) -> Result<(), String> {
+    if !agent::replay::stop(&state.replays, &app, &replay_id) {
+        return Err(format!("replay '{}' is not running", replay_id));
+    }
     Ok(())
 }
 
+/// Render an agent's run on a ticket as a shareable Markdown or HTML document,
+/// for attaching "how the agent did this" to a PR or design doc.
+#[tauri::command]
+async fn export_transcript(
+    state: State<'_, AppState>,
+    agent_id: String,
+    ticket_id: String,
+    format: agent::export::ExportFormat,
+) -> Result<String, String> {
+    let entries =
+        agent::transcript::load(&agent_id, &ticket_id).map_err(|e| format!("{:#}", e))?;
+    if entries.is_empty() {
+        return Err(format!("no transcript for agent '{}' on ticket '{}'", agent_id, ticket_id));
+    }
+    let agent_name = get_agent(&state.agents, &agent_id).await
+        .map(|a| a.name)
+        .unwrap_or_else(|| agent_id.clone());
+    let notes = agent::annotations::load(&agent_id, &ticket_id).map_err(|e| format!("{:#}", e))?;
+    let title = format!("{} on {}", agent_name, ticket_id);
+    Ok(agent::export::render(&title, &entries, &notes, format))
+}
+
+/// Human notes attached to canvas nodes of an agent's run on a ticket.
+#[tauri::command]
+fn get_annotations(
+    agent_id: String,
+    ticket_id: String,
+) -> Result<Vec<agent::annotations::Annotation>, String> {
+    agent::annotations::load(&agent_id, &ticket_id).map_err(|e| format!("{:#}", e))
+}
+
+/// Create an annotation on `node_id`, or edit the one with `id`.
+#[tauri::command]
+fn save_annotation(
+    agent_id: String,
+    ticket_id: String,
+    node_id: String,
+    id: Option<String>,
+    text: String,
+    flag: Option<String>,
+) -> Result<agent::annotations::Annotation, String> {
+    let args = serde_json::json!({ "agent_id": agent_id, "ticket_id": ticket_id, "node_id": node_id, "id": id, "flag": flag });
+    let result = agent::annotations::upsert(&agent_id, &ticket_id, id, &node_id, text, flag)
+        .map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "save_annotation", args, result)
+}
+
+#[tauri::command]
+fn delete_annotation(agent_id: String, ticket_id: String, id: String) -> Result<(), String> {
+    let result = agent::annotations::delete(&agent_id, &ticket_id, &id).map_err(|e| format!("{:#}", e));
+    let args = serde_json::json!({ "agent_id": agent_id, "ticket_id": ticket_id, "id": id });
+    audited(Actor::User, "delete_annotation", args, result)
+}
+
+/// Full-text search across every agent's persisted transcripts, e.g. to find
+/// where any agent touched the billing retry logic over the past weeks.
+#[tauri::command]
+async fn search_transcripts(
+    query: String,
+    filters: Option<agent::search::SearchFilters>,
+) -> Result<Vec<agent::search::SearchHit>, String> {
+    tokio::task::spawn_blocking(move || {
+        agent::search::search(&query, &filters.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
+
+// ── Handoff command ───────────────────────────────────────────────────────────
+
+const HANDOFF_PROMPT: &str = "You are handing this ticket off to another engineer who has not seen \
+    any of this session. Do not use any tools or continue the work. Write a handoff note in \
+    markdown: what the ticket needs, what you've done so far (files touched, commits, PR), \
+    what's left, open questions, and why it's being handed off if that came up.";
+
+/// Payload for the `agent-handoff` event and the `handoff_ticket` result.
+#[derive(serde::Serialize, TS)]
+#[ts(export)]
+pub struct HandoffPayload {
+    pub from_agent_id: String,
+    pub to_agent_id: String,
+    pub ticket_id: String,
+    pub worktree_path: String,
+    /// Handoff note generated from the outgoing agent's session — include it
+    /// in the prompt when starting the receiving agent.
+    pub summary: String,
+}
+
+/// Hand a ticket from one agent to another (e.g. a frontend ticket that turns
+/// out to need backend work). The worktree, ticket and PR move over; the
+/// receiving agent starts a fresh session from a generated handoff note.
+#[tauri::command]
+async fn handoff_ticket(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    from_agent: String,
+    to_agent: String,
+) -> Result<HandoffPayload, String> {
+    let args = serde_json::json!({ "from_agent": from_agent, "to_agent": to_agent });
+    let result: Result<HandoffPayload, String> = async {
+        let source = get_agent(&state.agents, &from_agent).await
+            .ok_or_else(|| format!("agent '{}' not found", from_agent))?;
+
+        // Summarise before transferring — the transfer clears the session id.
+        let summary = match (&source.session_id, &source.worktree_path) {
+            (Some(session_id), Some(worktree)) => agent::session::ask_forked(
+                &from_agent,
+                source.current_ticket_id.as_deref().unwrap_or_default(),
+                session_id,
+                std::path::Path::new(worktree),
+                None,
+                HANDOFF_PROMPT,
+                std::time::Duration::from_secs(180),
+            )
+            .await
+            .unwrap_or_else(|e| {
+                error!("[handoff_ticket] failed to summarise agent={}: {:#}", from_agent, e);
+                String::new()
+            }),
+            _ => String::new(),
+        };
+
+        let (ticket_id, worktree_path) =
+            agent::state::transfer_ticket(&state.agents, &from_agent, &to_agent).await?;
+        info!(
+            "[handoff_ticket] ticket={} from={} to={}",
+            ticket_id, from_agent, to_agent
+        );
+
+        let payload = HandoffPayload {
+            from_agent_id: from_agent,
+            to_agent_id: to_agent,
+            ticket_id,
+            worktree_path,
+            summary,
+        };
+        let _ = app.emit("agent-handoff", &payload);
+        Ok(payload)
+    }
+    .await;
+    audited(Actor::User, "handoff_ticket", args, result)
+}
+
 // ── Chat agent command ────────────────────────────────────────────────────────
 
 /// Payload from React to start a chat session with an agent.
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+#[ts(export)]
 pub struct ChatAgentPayload {
     pub agent_id: String,
     pub message: String,
@@ -284,6 +1977,10 @@ pub struct ChatAgentPayload {
     pub system_prompt: String,
     /// State deltas injected via --append-system-prompt on resume.
     pub context_update: String,
+    /// Sent by the app on its own, e.g. waking an agent messaged by another,
+    /// rather than by the user. Only changes how the message is audited.
+    #[serde(default)]
+    pub automated: bool,
 }
 
 /// Start or resume a persistent chat session with an agent.
@@ -296,117 +1993,137 @@ async fn chat_agent(
     state: State<'_, AppState>,
     payload: ChatAgentPayload,
 ) -> Result<(), String> {
-    let agents_store = state.agents.clone();
-
-    // Check if already processing a chat message
-    let agent = get_agent(&agents_store, &payload.agent_id)
-        .ok_or_else(|| format!("agent '{}' not found", payload.agent_id))?;
-    if agent.chatting {
-        return Err("agent is already processing a chat message".to_string());
-    }
-
-    set_chatting(&agents_store, &payload.agent_id, true);
-
-    // Determine cold start vs resume
-    let is_cold_start = agent.chat_session_id.is_none();
-
-    // Stable working directory: $HOME/.poietai/chat/<agent_id>/
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-    let working_dir = PathBuf::from(&home)
-        .join(".poietai")
-        .join("chat")
-        .join(&payload.agent_id);
-
-    // Ensure directory exists
-    std::fs::create_dir_all(&working_dir)
-        .map_err(|e| format!("failed to create chat dir: {}", e))?;
-
-    let (system_prompt, resume_session_id) = if is_cold_start {
-        (payload.system_prompt.clone(), None)
-    } else {
-        // On resume, inject context updates (or empty string if none)
-        (payload.context_update.clone(), agent.chat_session_id.clone())
-    };
-
-    // Wrap messages starting with "/" so the CLI doesn't intercept them as slash commands
-    let prompt = if payload.message.starts_with('/') {
-        format!("User message: {}", payload.message)
-    } else {
-        payload.message.clone()
-    };
-
-    let run_config = agent::process::AgentRunConfig {
-        agent_id: payload.agent_id.clone(),
-        ticket_id: "chat".to_string(),
-        prompt,
-        system_prompt,
-        allowed_tools: vec![
-            "Read".to_string(),
-            "Glob".to_string(),
-            "Grep".to_string(),
-            "mcp__poietai__list_tickets".to_string(),
-            "mcp__poietai__get_ticket_details".to_string(),
-            "mcp__poietai__ask_human".to_string(),
-            "mcp__poietai__status_update".to_string(),
-            "mcp__poietai__present_choices".to_string(),
-            "mcp__poietai__confirm_action".to_string(),
-            "mcp__poietai__update_ticket".to_string(),
-            "mcp__poietai__create_ticket".to_string(),
-            "mcp__poietai__complete_phase".to_string(),
-            "mcp__poietai__claim_ticket".to_string(),
-            "mcp__poietai__relay_answer".to_string(),
-            "mcp__poietai__message_agent".to_string(),
-        ],
-        working_dir,
-        env: vec![],
-        resume_session_id,
-        mcp_port: state.mcp.port,
-        group_id: None,
-    };
-
-    let app_clone = app.clone();
-    let agents_store_clone = agents_store.clone();
-    let agent_id = payload.agent_id.clone();
-
-    info!("[chat_agent] agent={} cold_start={}", agent_id, is_cold_start);
-
-    let app_for_error = app.clone();
-    let agent_id_for_error = payload.agent_id.clone();
+    let actor = if payload.automated { Actor::Automation } else { Actor::User };
+    let args = serde_json::json!({ "agent_id": payload.agent_id });
+    let result: Result<(), String> = async {
+        let agents_store = state.agents.clone();
+
+        // Claim the agent for this message in one step, so two messages can't
+        // both find it free
+        let agent = agent::state::update_agent(&agents_store, &payload.agent_id, |a| {
+            if a.chatting {
+                return None;
+            }
+            a.chatting = true;
+            Some(a.clone())
+        })
+        .await
+        .ok_or_else(|| format!("agent '{}' not found", payload.agent_id))?
+        .ok_or_else(|| "agent is already processing a chat message".to_string())?;
+
+        // Determine cold start vs resume
+        let is_cold_start = agent.chat_session_id.is_none();
+
+        // Stable working directory: $HOME/.poietai/chat/<agent_id>/
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        let working_dir = PathBuf::from(&home)
+            .join(".poietai")
+            .join("chat")
+            .join(&payload.agent_id);
+
+        // Ensure directory exists
+        if let Err(e) = std::fs::create_dir_all(&working_dir) {
+            set_chatting(&agents_store, &payload.agent_id, false).await;
+            return Err(format!("failed to create chat dir: {}", e));
+        }
 
-    tokio::spawn(async move {
-        match agent::process::run(run_config, app_clone).await {
-            Ok(session_id) => {
-                info!("[chat_agent] agent={} completed, session={:?}", agent_id, session_id);
-                if let Some(ref sid) = session_id {
-                    agent::state::save_chat_session_id(&agents_store_clone, &agent_id, sid);
+        let (system_prompt, resume_session_id) = if is_cold_start {
+            (payload.system_prompt.clone(), None)
+        } else {
+            // On resume, inject context updates (or empty string if none)
+            (payload.context_update.clone(), agent.chat_session_id.clone())
+        };
+
+        // Wrap messages starting with "/" so the CLI doesn't intercept them as slash commands
+        let prompt = if payload.message.starts_with('/') {
+            format!("User message: {}", payload.message)
+        } else {
+            payload.message.clone()
+        };
+
+        let run_config = agent::process::AgentRunConfig {
+            agent_id: payload.agent_id.clone(),
+            ticket_id: "chat".to_string(),
+            prompt,
+            system_prompt,
+            allowed_tools: vec![
+                "Read".to_string(),
+                "Glob".to_string(),
+                "Grep".to_string(),
+                "mcp__poietai__list_tickets".to_string(),
+                "mcp__poietai__get_ticket_details".to_string(),
+                "mcp__poietai__ask_human".to_string(),
+                "mcp__poietai__status_update".to_string(),
+                "mcp__poietai__set_status_note".to_string(),
+                "mcp__poietai__present_choices".to_string(),
+                "mcp__poietai__confirm_action".to_string(),
+                "mcp__poietai__update_ticket".to_string(),
+                "mcp__poietai__create_ticket".to_string(),
+                "mcp__poietai__complete_phase".to_string(),
+                "mcp__poietai__claim_ticket".to_string(),
+                "mcp__poietai__relay_answer".to_string(),
+                "mcp__poietai__message_agent".to_string(),
+            ],
+            working_dir,
+            env: vec![],
+            resume_session_id,
+            mcp_port: state.mcp.port,
+            group_id: None,
+            // Chat runs read-only in a scratch dir — no project toolchain needed.
+            backend: agent::backend::ExecutionBackend::Host,
+            limits: agent::limits::ResourceLimits::default(),
+            max_turns: None,
+            pr_gate: None,
+            verify_before_pr: false,
+        };
+
+        let app_clone = app.clone();
+        let agents_store_clone = agents_store.clone();
+        let agent_id = payload.agent_id.clone();
+
+        info!("[chat_agent] agent={} cold_start={}", agent_id, is_cold_start);
+
+        let app_for_error = app.clone();
+        let agent_id_for_error = payload.agent_id.clone();
+
+        tokio::spawn(async move {
+            match agent::process::run(run_config, app_clone).await {
+                Ok(session_id) => {
+                    info!("[chat_agent] agent={} completed, session={:?}", agent_id, session_id);
+                    if let Some(ref sid) = session_id {
+                        agent::state::save_chat_session_id(&agents_store_clone, &agent_id, sid).await;
+                    }
+                    // No session_id means claude produced no output — likely a startup failure
+                    if session_id.is_none() {
+                        let _ = app_for_error.emit("agent-chat-error", serde_json::json!({
+                            "agent_id": agent_id_for_error,
+                            "error": "Agent produced no response. Check MCP server connection.",
+                        }));
+                    }
+                    set_chatting(&agents_store_clone, &agent_id, false).await;
                 }
-                // No session_id means claude produced no output — likely a startup failure
-                if session_id.is_none() {
+                Err(e) => {
+                    error!("[chat_agent] agent={} chat failed: {}", agent_id, e);
                     let _ = app_for_error.emit("agent-chat-error", serde_json::json!({
                         "agent_id": agent_id_for_error,
-                        "error": "Agent produced no response. Check MCP server connection.",
+                        "error": format!("{}", e),
                     }));
+                    set_chatting(&agents_store_clone, &agent_id, false).await;
                 }
-                set_chatting(&agents_store_clone, &agent_id, false);
-            }
-            Err(e) => {
-                error!("[chat_agent] agent={} chat failed: {}", agent_id, e);
-                let _ = app_for_error.emit("agent-chat-error", serde_json::json!({
-                    "agent_id": agent_id_for_error,
-                    "error": format!("{}", e),
-                }));
-                set_chatting(&agents_store_clone, &agent_id, false);
             }
-        }
-    });
+        });
 
-    Ok(())
+        Ok(())
+    }
+    .await;
+    audited(actor, "chat_agent", args, result)
 }
 
 // ── GitHub polling command ────────────────────────────────────────────────────
 
-/// Start polling a PR for CI reviews.
-/// Call this after the agent opens a PR (detected from agent-event stream).
+/// Start polling a PR for CI reviews, after putting the ticket's labels and
+/// milestone on it. Runs start this themselves once the agent opens a PR;
+/// this is for a PR opened by hand.
 #[tauri::command]
 async fn start_pr_poll(
     app: tauri::AppHandle,
@@ -414,21 +2131,417 @@ async fn start_pr_poll(
     ticket_id: String,
     repo: String,
     pr_number: u32,
+    gh_token: Option<String>,
+) {
+    project::audit_log::command(
+        Actor::User,
+        "start_pr_poll",
+        serde_json::json!({ "agent_id": agent_id, "ticket_id": ticket_id, "repo": repo, "pr_number": pr_number }),
+        Ok(()),
+    );
+    watch_pr(app, agent_id, ticket_id, repo, pr_number, gh_token).await;
+}
+
+/// Label the agent's PR, attach its risk score, track it for advisories and
+/// review latency, and poll it for reviews and CI in the background.
+pub async fn watch_pr(
+    app: tauri::AppHandle,
+    agent_id: String,
+    ticket_id: String,
+    repo: String,
+    pr_number: u32,
+    gh_token: Option<String>,
 ) {
+    let worktree = agent::state::update_agent(&app.state::<AppState>().agents, &agent_id, |a| {
+        a.pr_number = Some(pr_number);
+        a.worktree_path.clone()
+    })
+    .await
+    .flatten();
+    if let Some(ref worktree) = worktree {
+        {
+            let (worktree, ticket_id, repo) = (PathBuf::from(worktree), ticket_id.clone(), repo.clone());
+            tauri::async_runtime::spawn_blocking(move || {
+                let Ok((_, ticket)) = project::tickets::locate(&worktree, &ticket_id) else {
+                    return;
+                };
+                match github::pr_meta::apply(&repo, pr_number, &ticket.labels, ticket.milestone.as_deref()) {
+                    Ok((labels, milestone)) => info!(
+                        "[start_pr_poll] PR #{} labels={:?} milestone={:?}",
+                        pr_number, labels, milestone
+                    ),
+                    Err(e) => error!("[start_pr_poll] failed to label PR #{}: {:#}", pr_number, e),
+                }
+            });
+        }
+        {
+            let (worktree, ticket_id) = (PathBuf::from(worktree), ticket_id.clone());
+            tauri::async_runtime::spawn_blocking(move || {
+                let repo_root = agent::backend::main_repo_root(&worktree);
+                match project::audit::record_pr(&repo_root, &ticket_id, pr_number) {
+                    Ok(0) => {}
+                    Ok(n) => info!("[start_pr_poll] PR #{} remediates {} advisories", pr_number, n),
+                    Err(e) => warn!("[start_pr_poll] failed to track advisory PR #{}: {:#}", pr_number, e),
+                }
+            });
+        }
+        // Tell reviewers where to look
+        let (worktree, repo) = (PathBuf::from(worktree), repo.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            let config = agent::backend::project_settings_for(&worktree).risk;
+            let attached = agent::risk::assess(&config, &worktree).and_then(|report| {
+                let section = agent::risk::pr_section(&report);
+                github::pr_meta::set_body_section(&repo, pr_number, agent::risk::PR_MARKER, &section)
+            });
+            if let Err(e) = attached {
+                warn!("[start_pr_poll] failed to attach risk score to PR #{}: {:#}", pr_number, e);
+            }
+        });
+    }
+    {
+        let (repo, agent_id, ticket_id) = (repo.clone(), agent_id.clone(), ticket_id.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = github::latency::track(&repo, pr_number, &agent_id, &ticket_id) {
+                warn!("[start_pr_poll] failed to track review latency for PR #{}: {:#}", pr_number, e);
+            }
+        });
+    }
+    let settings = worktree.map(|p| agent::backend::project_settings_for(std::path::Path::new(&p)));
+    let (hours, ci) = settings
+        .map(|s| (s.working_hours, s.ci_triage))
+        .unwrap_or_default();
     tokio::spawn(github::poller::poll_pr(
         app, repo, pr_number, agent_id, ticket_id, 30, // poll every 30 seconds
+        hours, ci, gh_token,
     ));
 }
 
+/// Start polling a GitLab merge request for review notes and approvals.
+/// The GitLab counterpart of `start_pr_poll`; call it once the agent opens
+/// the MR.
+#[tauri::command]
+async fn start_mr_poll(
+    app: tauri::AppHandle,
+    agent_id: String,
+    ticket_id: String,
+    repo_root: String,
+    mr_iid: u32,
+    gitlab_token: Option<String>,
+) -> Result<(), String> {
+    let args = serde_json::json!({ "agent_id": agent_id, "ticket_id": ticket_id, "repo_root": repo_root, "mr_iid": mr_iid });
+    let result: Result<(), String> = async {
+        let client = gitlab_client(repo_root.clone(), gitlab_token).await?;
+        agent::state::update_agent(&app.state::<AppState>().agents, &agent_id, |a| {
+            a.pr_number = Some(mr_iid);
+        })
+        .await;
+        let hours = project::settings::load_project(std::path::Path::new(&repo_root))
+            .unwrap_or_default()
+            .working_hours;
+        tokio::spawn(gitlab::poller::poll_mr(
+            app, client, mr_iid, agent_id, ticket_id, 30, // poll every 30 seconds
+            hours,
+        ));
+        Ok(())
+    }
+    .await;
+    audited(Actor::User, "start_mr_poll", args, result)
+}
+
+/// Score the risk of an agent's uncommitted and committed changes: critical
+/// paths, size, deleted tests and migrations.
+#[tauri::command]
+async fn score_diff_risk(
+    state: State<'_, AppState>,
+    agent_id: String,
+) -> Result<agent::risk::RiskReport, String> {
+    let worktree = get_agent(&state.agents, &agent_id)
+        .await
+        .ok_or_else(|| format!("agent '{}' not found", agent_id))?
+        .worktree_path
+        .ok_or_else(|| format!("agent '{}' has no worktree", agent_id))?;
+    tokio::task::spawn_blocking(move || {
+        let worktree = PathBuf::from(worktree);
+        let config = agent::backend::project_settings_for(&worktree).risk;
+        agent::risk::assess(&config, &worktree)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// Time from PR opened to first human review and to merge, across agent
+/// PRs. Refreshes PRs still open from GitHub first.
+#[tauri::command]
+async fn get_review_latency_stats() -> Result<github::latency::LatencyStats, String> {
+    tokio::task::spawn_blocking(|| github::latency::refresh().map(github::latency::stats))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Draft Markdown release notes from the agent PRs merged since `since_tag`.
+#[tauri::command]
+async fn generate_release_notes(repo_root: String, since_tag: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        github::release_notes::generate(std::path::Path::new(&repo_root), &since_tag)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// A new ticket pre-filled from a template, for the frontend to add to the board.
+#[tauri::command]
+fn create_ticket_from_template(
+    template: project::templates::TicketTemplate,
+    title: String,
+) -> Result<project::templates::TicketDraft, String> {
+    if title.trim().is_empty() {
+        return Err("ticket title is empty".to_string());
+    }
+    Ok(template.draft(&title))
+}
+
+/// Run `cargo audit` and the npm, pnpm or yarn audit in the repo: new
+/// advisories come back as ticket drafts, tracked ones with their
+/// remediation status.
+#[tauri::command]
+async fn audit_dependencies(repo_root: String) -> Result<project::audit::AuditReport, String> {
+    tokio::task::spawn_blocking(move || project::audit::audit(&PathBuf::from(repo_root)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Link advisories to the ticket the frontend added for them, so runs on it
+/// get the security prompt and its PR is tracked against them.
+#[tauri::command]
+fn track_advisories(
+    repo_root: String,
+    advisories: Vec<project::audit::Advisory>,
+    ticket_id: String,
+) -> Result<(), String> {
+    let args = serde_json::json!({ "repo_root": repo_root, "ticket_id": ticket_id, "advisories": advisories.len() });
+    let result = project::audit::track(&PathBuf::from(repo_root), advisories, &ticket_id).map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "track_advisories", args, result)
+}
+
+/// Outdated Cargo and npm dependencies, grouped into tickets for the
+/// frontend to add. Start each with its `test_command` as `pr_gate`.
+#[tauri::command]
+async fn find_dependency_updates(
+    project_root: String,
+) -> Result<Vec<project::deps::DependencyUpdate>, String> {
+    tokio::task::spawn_blocking(move || project::deps::find_updates(&PathBuf::from(project_root)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Check a bulk edit against the board and, when it sets a status, mirror
+/// that to GitHub Projects in one pass. The frontend applies `changes` to
+/// the `updated` tickets.
+#[tauri::command]
+async fn bulk_update_tickets(
+    project_root: String,
+    ids: Vec<String>,
+    changes: project::bulk::TicketChanges,
+) -> Result<project::bulk::BulkUpdate, String> {
+    let args = serde_json::json!({ "project_root": project_root, "ids": ids, "changes": changes });
+    let result: Result<project::bulk::BulkUpdate, String> = async {
+        let root = PathBuf::from(&project_root);
+        let board = project::tickets::load_all(&root).map_err(|e| format!("{:#}", e))?;
+        let result = project::bulk::partition(&board, &ids);
+        let Some(status) = changes.status else {
+            return Ok(result);
+        };
+        let Some(config) = project::settings::load_project(&root)
+            .map_err(|e| format!("{:#}", e))?
+            .github_project
+        else {
+            return Ok(result);
+        };
+        let synced: Vec<_> = board.into_iter().filter(|t| result.updated.contains(&t.id)).collect();
+        tokio::task::spawn_blocking(move || {
+            for t in synced {
+                let source_url = t.source_url.as_deref();
+                if let Err(e) = github::projects::sync_ticket(&config, &root, &t.id, &t.title, source_url, &status) {
+                    warn!("[bulk_update_tickets] board sync failed for ticket={}: {:#}", t.id, e);
+                }
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(result)
+    }
+    .await;
+    audited(Actor::User, "bulk_update_tickets", args, result)
+}
+
+/// Assign tickets to `agent_id`, or with None spread them over the roster,
+/// least-loaded first. The frontend records the returned assignments.
+#[tauri::command]
+async fn bulk_assign(
+    state: State<'_, AppState>,
+    project_root: String,
+    ids: Vec<String>,
+    agent_id: Option<String>,
+) -> Result<Vec<project::bulk::BulkAssignment>, String> {
+    let args = serde_json::json!({ "project_root": project_root, "ids": ids, "agent_id": agent_id });
+    let result: Result<Vec<project::bulk::BulkAssignment>, String> = async {
+        let board = project::tickets::load_all(std::path::Path::new(&project_root)).map_err(|e| format!("{:#}", e))?;
+        let ids = project::bulk::partition(&board, &ids).updated;
+        let agents: Vec<String> = match agent_id {
+            Some(id) => {
+                get_agent(&state.agents, &id)
+                    .await
+                    .ok_or_else(|| format!("agent '{}' not found", id))?;
+                vec![id]
+            }
+            None => {
+                let mut roster: Vec<AgentState> = all_agents(&state.agents)
+                    .await
+                    .into_iter()
+                    .filter(|a| a.status != AgentStatus::Blocked)
+                    .collect();
+                roster.sort_by(|a, b| a.name.cmp(&b.name));
+                roster.into_iter().map(|a| a.id).collect()
+            }
+        };
+        if agents.is_empty() {
+            return Err("no agents available to assign".to_string());
+        }
+        Ok(project::bulk::auto_assign(&board, &ids, &agents))
+    }
+    .await;
+    audited(Actor::User, "bulk_assign", args, result)
+}
+
+/// Mirror a ticket's status to the project's GitHub Projects board.
+/// A no-op when the project has no board configured. `source_url` is the
+/// issue the ticket was imported from, which goes on the board in its place.
+#[tauri::command]
+async fn sync_ticket_status(
+    project_root: String,
+    ticket_id: String,
+    title: String,
+    source_url: Option<String>,
+    status: String,
+) -> Result<(), String> {
+    let args = serde_json::json!({ "project_root": project_root, "ticket_id": ticket_id, "status": status });
+    let result: Result<(), String> = async {
+        let root = PathBuf::from(&project_root);
+        let Some(config) = project::settings::load_project(&root)
+            .map_err(|e| format!("{:#}", e))?
+            .github_project
+        else {
+            return Ok(());
+        };
+        tokio::task::spawn_blocking(move || {
+            github::projects::sync_ticket(&config, &root, &ticket_id, &title, source_url.as_deref(), &status)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+    }
+    .await;
+    audited(Actor::User, "sync_ticket_status", args, result)
+}
+
+/// Re-derive the repo's conventions section from its commits, test layout
+/// and merged PR reviews. New agent runs include it in their system prompt.
+#[tauri::command]
+async fn refresh_conventions(repo_root: String) -> Result<String, String> {
+    let args = serde_json::json!({ "repo_root": repo_root });
+    let result: Result<String, String> = async {
+        tokio::task::spawn_blocking(move || context::conventions::refresh(std::path::Path::new(&repo_root)))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("{:#}", e))
+    }
+    .await;
+    audited(Actor::User, "refresh_conventions", args, result)
+}
+
 /// Deliver a human reply to a waiting ask_human MCP call.
 /// Called from React when the user submits a reply in the AgentQuestionCard.
+/// Without a reply, the answer suggested with the question is sent instead.
 #[tauri::command]
 async fn answer_agent(
     state: State<'_, AppState>,
     agent_id: String,
-    reply: String,
+    reply: Option<String>,
 ) -> Result<(), String> {
-    state.mcp.answer(&agent_id, reply).await
+    let args = serde_json::json!({ "agent_id": agent_id, "reply": reply });
+    let result: Result<(), String> = async {
+        let reply = match reply {
+            Some(reply) => reply,
+            None => state
+                .mcp
+                .suggested_answer(&agent_id)
+                .await
+                .ok_or_else(|| format!("no suggested answer for agent '{}'", agent_id))?,
+        };
+        state.mcp.answer(&agent_id, reply).await?;
+        project::telemetry::record(project::telemetry::Feature::QuestionAnswered);
+        Ok(())
+    }
+    .await;
+    audited(Actor::User, "answer_agent", args, result)
+}
+
+/// Transcribe a voice memo and deliver the text as the reply to a waiting
+/// ask_human call. Returns the text sent, for the question card to show.
+#[tauri::command]
+async fn answer_agent_with_audio(
+    state: State<'_, AppState>,
+    agent_id: String,
+    audio_path: String,
+    api_key: Option<String>,
+) -> Result<String, String> {
+    let args = serde_json::json!({ "agent_id": agent_id, "audio_path": audio_path });
+    let result: Result<String, String> = async {
+        let reply = tokio::task::spawn_blocking(move || {
+            let config = project::settings::load_app()?
+                .transcription
+                .ok_or_else(|| anyhow::anyhow!("no transcription configured"))?;
+            agent::voice::transcribe(&config, std::path::Path::new(&audio_path), api_key.as_deref())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+        state.mcp.answer(&agent_id, reply.clone()).await?;
+        project::telemetry::record(project::telemetry::Feature::QuestionAnswered);
+        Ok(reply)
+    }
+    .await;
+    audited(Actor::User, "answer_agent_with_audio", args, result)
+}
+
+/// Every question agents asked on a ticket, with its answer, oldest first.
+#[tauri::command]
+fn get_ticket_qa(ticket_id: String) -> Result<Vec<project::qa::QaEntry>, String> {
+    project::qa::load(&ticket_id).map_err(|e| format!("{:#}", e))
+}
+
+/// List the questions agents are blocked on, highest effective priority first.
+/// Backed by the same map ask_human replies are delivered through.
+#[tauri::command]
+async fn get_pending_questions(
+    state: State<'_, AppState>,
+) -> Result<Vec<mcp::inbox::QuestionInfo>, String> {
+    Ok(state.mcp.pending_questions().await)
+}
+
+/// Where the MCP server is listening and whether it answers there, for
+/// connectivity diagnostics.
+#[tauri::command]
+async fn get_mcp_endpoint(state: State<'_, AppState>) -> Result<mcp::listen::McpEndpoint, String> {
+    let (listen, port) = (state.mcp.listen.clone(), state.mcp.port);
+    tokio::task::spawn_blocking(move || mcp::listen::endpoint(&listen, port))
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// Deliver ticket data to a waiting list_tickets MCP call.
@@ -445,40 +2558,160 @@ async fn answer_tickets(
 /// Get the git diff for an agent's worktree relative to the base branch.
 /// Returns the diff string for the VALIDATE phase to inspect.
 #[tauri::command]
-fn get_worktree_diff(
+async fn get_worktree_diff(
     state: State<'_, AppState>,
     agent_id: String,
 ) -> Result<String, String> {
-    let agent = get_agent(&state.agents, &agent_id)
+    let agent = get_agent(&state.agents, &agent_id).await
         .ok_or_else(|| format!("agent '{}' not found", agent_id))?;
     let worktree_path = agent
         .worktree_path
         .ok_or_else(|| format!("agent '{}' has no worktree", agent_id))?;
 
-    // Try "git diff main...HEAD" first (works when branched off main)
-    let output = std::process::Command::new("git")
-        .args(["diff", "main...HEAD"])
-        .current_dir(&worktree_path)
-        .output()
-        .map_err(|e| format!("git diff failed: {}", e))?;
+    // Diff from where the branch left the project's default branch first
+    let worktree = PathBuf::from(&worktree_path);
+    let base = tokio::task::spawn_blocking(move || git::diff::base_commit(&worktree))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))?;
+    let output = platform::exec::output(
+        tokio::process::Command::new("git")
+            .args(["diff", &format!("{}..HEAD", base)])
+            .current_dir(&worktree_path),
+        platform::exec::LOCAL,
+    )
+    .await
+    .map_err(|e| format!("git diff failed: {}", e))?;
 
     if output.status.success() && !output.stdout.is_empty() {
-        return String::from_utf8(output.stdout).map_err(|e| e.to_string());
+        return Ok(text::lossy(output.stdout));
     }
 
     // Fallback: diff against the immediate parent commit
-    let fallback = std::process::Command::new("git")
-        .args(["diff", "HEAD~1..HEAD"])
-        .current_dir(&worktree_path)
-        .output()
-        .map_err(|e| format!("git diff fallback failed: {}", e))?;
+    let fallback = platform::exec::output(
+        tokio::process::Command::new("git")
+            .args(["diff", "HEAD~1..HEAD"])
+            .current_dir(&worktree_path),
+        platform::exec::LOCAL,
+    )
+    .await
+    .map_err(|e| format!("git diff fallback failed: {}", e))?;
 
     if !fallback.status.success() {
         let stderr = String::from_utf8_lossy(&fallback.stderr);
         return Err(format!("git diff fallback failed: {}", stderr));
     }
 
-    String::from_utf8(fallback.stdout).map_err(|e| e.to_string())
+    Ok(text::lossy(fallback.stdout))
+}
+
+/// Structured diff of one file in an agent's worktree against its base,
+/// including uncommitted edits, for the side-by-side diff view.
+#[tauri::command]
+async fn get_file_diff(
+    state: State<'_, AppState>,
+    agent_id: String,
+    path: String,
+) -> Result<git::diff::FileDiff, String> {
+    let worktree = agent_worktree(&state, &agent_id).await?;
+    tokio::task::spawn_blocking(move || git::diff::file_diff(&worktree, &path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// How far an agent's worktree has fallen behind origin's default branch.
+#[tauri::command]
+async fn check_worktree_drift(
+    state: State<'_, AppState>,
+    agent_id: String,
+) -> Result<git::drift::WorktreeDrift, String> {
+    let args = serde_json::json!({ "agent_id": agent_id });
+    let result: Result<git::drift::WorktreeDrift, String> = async {
+        let worktree = agent_worktree(&state, &agent_id).await?;
+        tokio::task::spawn_blocking(move || {
+            let config = agent::backend::project_settings_for(&worktree).drift;
+            git::drift::check(&config, &worktree)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+    }
+    .await;
+    audited(Actor::User, "check_worktree_drift", args, result)
+}
+
+/// Rebase an agent's worktree onto origin's default branch, e.g. after a
+/// `worktree-stale` warning. Conflicts abort the rebase and are reported.
+/// Refused while a run is working in the worktree.
+#[tauri::command]
+async fn rebase_worktree(state: State<'_, AppState>, agent_id: String) -> Result<(), String> {
+    let args = serde_json::json!({ "agent_id": agent_id });
+    let result: Result<(), String> = async {
+        let agent = get_agent(&state.agents, &agent_id)
+            .await
+            .ok_or_else(|| format!("agent '{}' not found", agent_id))?;
+        let worktree = agent
+            .worktree_path
+            .clone()
+            .ok_or_else(|| format!("agent '{}' has no worktree", agent_id))?;
+        if agent.status == AgentStatus::Working {
+            return Err(format!("agent '{}' is working — stop its run before rebasing", agent_id));
+        }
+        // Hold the worktree's lock like a run would, so none starts mid-rebase
+        let ticket_id = agent.current_ticket_id.unwrap_or_default();
+        let run_id = uuid::Uuid::new_v4().to_string();
+        let lock_key = if ticket_id.is_empty() { &worktree } else { &ticket_id };
+        let _reservation =
+            agent::locks::reserve(&state.run_locks, &run_id, lock_key, &agent_id, Some(worktree.as_str()), false)?;
+        tokio::task::spawn_blocking(move || git::drift::rebase(std::path::Path::new(&worktree)))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("{:#}", e))
+    }
+    .await;
+    audited(Actor::User, "rebase_worktree", args, result)
+}
+
+/// A file's contents at the commit the agent's branch started from.
+/// `None` when the agent created the file.
+#[tauri::command]
+async fn get_file_at_base(
+    state: State<'_, AppState>,
+    agent_id: String,
+    path: String,
+) -> Result<Option<String>, String> {
+    let worktree = agent_worktree(&state, &agent_id).await?;
+    tokio::task::spawn_blocking(move || git::diff::file_at_base(&worktree, &path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Open an agent's worktree — or one file in it — in the user's editor.
+#[tauri::command]
+async fn open_in_editor(
+    state: State<'_, AppState>,
+    agent_id: String,
+    path: Option<String>,
+) -> Result<(), String> {
+    let worktree = agent_worktree(&state, &agent_id).await?;
+    if let Some(ref path) = path {
+        git::diff::check_path(path).map_err(|e| format!("{:#}", e))?;
+    }
+    let editor = project::settings::load_app()
+        .map_err(|e| format!("{:#}", e))?
+        .editor
+        .unwrap_or_default();
+    project::editor::open(&editor, &worktree, path.as_deref()).map_err(|e| format!("{:#}", e))
+}
+
+async fn agent_worktree(state: &AppState, agent_id: &str) -> Result<PathBuf, String> {
+    get_agent(&state.agents, agent_id).await
+        .ok_or_else(|| format!("agent '{}' not found", agent_id))?
+        .worktree_path
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("agent '{}' has no worktree", agent_id))
 }
 
 // ── Project-scoped file store commands ─────────────────────────────────────────
@@ -487,7 +2720,7 @@ fn get_worktree_diff(
 /// Returns `None` if the file doesn't exist.
 #[tauri::command]
 fn read_project_store(project_root: String, filename: String) -> Result<Option<String>, String> {
-    let path = PathBuf::from(&project_root).join(".poietai").join(&filename);
+    let path = project::store::project_dir(std::path::Path::new(&project_root)).join(&filename);
     match std::fs::read_to_string(&path) {
         Ok(contents) => Ok(Some(contents)),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
@@ -500,32 +2733,300 @@ fn read_project_store(project_root: String, filename: String) -> Result<Option<S
 /// Uses write-to-tmp + rename to prevent corruption on crash.
 #[tauri::command]
 fn write_project_store(project_root: String, filename: String, data: String) -> Result<(), String> {
-    let dir = PathBuf::from(&project_root).join(".poietai");
-    std::fs::create_dir_all(&dir)
-        .map_err(|e| format!("failed to create .poietai dir: {}", e))?;
+    let path = project::store::project_dir(std::path::Path::new(&project_root)).join(&filename);
+    let result = project::store::write_atomic(&path, &data).map_err(|e| format!("{:#}", e));
+    let args = serde_json::json!({ "project_root": project_root, "filename": filename, "bytes": data.len() });
+    audited(Actor::User, "write_project_store", args, result)
+}
+
+// ── Ticket analysis commands ──────────────────────────────────────────────────
+
+/// Run a quick read-only analysis of a board ticket — complexity, files
+/// likely touched, risks — and store it for the board. Also emitted as
+/// `ticket-estimated`.
+#[tauri::command]
+async fn estimate_ticket(
+    app: tauri::AppHandle,
+    project_root: String,
+    ticket_id: String,
+) -> Result<agent::estimate::TicketEstimate, String> {
+    refuse_over_budget(&app)?;
+    let root = PathBuf::from(&project_root);
+    let ticket = project::tickets::find(&root, &ticket_id).map_err(|e| format!("{:#}", e))?;
+    let estimate = agent::estimate::run(&root, &ticket)
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    let _ = app.emit("ticket-estimated", &estimate);
+    Ok(estimate)
+}
+
+/// Have a read-only agent write a tour of the repo — entry points, key
+/// modules, data flow, conventions — and store it so later design, plan,
+/// test and build runs on the repo get it in their system prompt until it
+/// goes stale.
+#[tauri::command]
+async fn generate_codebase_tour(
+    app: tauri::AppHandle,
+    repo_root: String,
+) -> Result<agent::tour::CodebaseTour, String> {
+    refuse_over_budget(&app)?;
+    agent::tour::generate(&PathBuf::from(&repo_root))
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// The repo's stored tour, if one has been generated.
+#[tauri::command]
+fn get_codebase_tour(repo_root: String) -> Result<Option<agent::tour::CodebaseTour>, String> {
+    agent::tour::load(std::path::Path::new(&repo_root)).map_err(|e| format!("{:#}", e))
+}
+
+/// Summarize a failed run or a PR closed unmerged — transcript and review
+/// history — into causes and what to change, stored with the project's
+/// tickets for the next attempt.
+#[tauri::command]
+async fn generate_postmortem(
+    app: tauri::AppHandle,
+    project_root: String,
+    agent_id: String,
+    ticket_id: String,
+) -> Result<agent::postmortem::Postmortem, String> {
+    refuse_over_budget(&app)?;
+    agent::postmortem::run(&PathBuf::from(&project_root), &agent_id, &ticket_id)
+        .await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Stored post-mortems for a project, keyed by ticket id, oldest first.
+#[tauri::command]
+fn get_postmortems(
+    project_root: String,
+) -> Result<std::collections::HashMap<String, Vec<agent::postmortem::Postmortem>>, String> {
+    agent::postmortem::load_all(std::path::Path::new(&project_root)).map_err(|e| format!("{:#}", e))
+}
+
+/// An ICS calendar of expected completion windows and review deadlines
+/// for the project's active tickets, for PMs' calendar apps.
+#[tauri::command]
+fn export_calendar(project_root: String) -> Result<String, String> {
+    project::calendar::export(std::path::Path::new(&project_root)).map_err(|e| format!("{:#}", e))
+}
+
+/// Stored estimates for a project, keyed by ticket id.
+#[tauri::command]
+fn get_ticket_estimates(
+    project_root: String,
+) -> Result<std::collections::HashMap<String, agent::estimate::TicketEstimate>, String> {
+    agent::estimate::load_all(std::path::Path::new(&project_root)).map_err(|e| format!("{:#}", e))
+}
+
+/// Check a ticket's acceptance criteria against an agent's worktree now,
+/// e.g. after fixing up a failed report by hand.
+#[tauri::command]
+async fn verify_acceptance(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    project_root: String,
+    ticket_id: String,
+    agent_id: String,
+) -> Result<agent::verify::AcceptanceReport, String> {
+    refuse_over_budget(&app)?;
+    let worktree = agent_worktree(&state, &agent_id).await?;
+    let root = PathBuf::from(&project_root);
+    let ticket = project::tickets::find(&root, &ticket_id).map_err(|e| format!("{:#}", e))?;
+    let config = project::settings::load_project(&root)
+        .map_err(|e| format!("{:#}", e))?
+        .verify_acceptance
+        .unwrap_or_default();
+    let report = agent::verify::run(&config, &root, &ticket, &worktree)
+        .await
+        .map_err(|e| format!("{:#}", e))?;
+    let _ = app.emit("acceptance-verified", &report);
+    Ok(report)
+}
+
+/// Stored acceptance reports for a project, keyed by ticket id.
+#[tauri::command]
+fn get_acceptance_reports(
+    project_root: String,
+) -> Result<std::collections::HashMap<String, agent::verify::AcceptanceReport>, String> {
+    agent::verify::load_all(std::path::Path::new(&project_root)).map_err(|e| format!("{:#}", e))
+}
 
-    let target = dir.join(&filename);
-    let tmp = dir.join(format!("{}.tmp", filename));
+// ── Settings commands ─────────────────────────────────────────────────────────
 
-    let mut file = std::fs::File::create(&tmp)
-        .map_err(|e| format!("failed to create tmp file: {}", e))?;
-    file.write_all(data.as_bytes())
-        .map_err(|e| format!("failed to write tmp file: {}", e))?;
-    file.sync_all()
-        .map_err(|e| format!("failed to sync tmp file: {}", e))?;
-    drop(file);
+/// Run a standup now, outside the schedule. Uses the configured model, or the
+/// default when standups aren't scheduled.
+#[tauri::command]
+async fn run_standup(app: tauri::AppHandle) -> Result<agent::standup::StandupReport, String> {
+    let config = project::settings::load_app()
+        .map_err(|e| format!("{:#}", e))?
+        .standup
+        .unwrap_or_default();
+    Ok(agent::standup::run(&app, &config).await)
+}
+
+/// The most recent standup report, or an empty one if none has run.
+#[tauri::command]
+fn get_last_standup() -> Result<agent::standup::StandupReport, String> {
+    project::store::read_json(&agent::standup::report_path()).map_err(|e| format!("{:#}", e))
+}
 
-    std::fs::rename(&tmp, &target)
-        .map_err(|e| format!("failed to rename tmp → target: {}", e))?;
+/// This install's release channel and the state of every feature flag.
+#[tauri::command]
+fn get_feature_flags() -> Result<project::flags::FeatureFlags, String> {
+    project::flags::load().map_err(|e| format!("{:#}", e))
+}
 
+/// Force a feature flag on or off, or back to its channel default when
+/// `enabled` is null.
+#[tauri::command]
+fn set_feature_flag(key: String, enabled: Option<bool>) -> Result<project::flags::FeatureFlags, String> {
+    let result = project::flags::set(&key, enabled).map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "set_feature_flag", serde_json::json!({ "key": key, "enabled": enabled }), result)
+}
+
+/// Follow a release channel's flag defaults, or the build's own with null.
+#[tauri::command]
+fn set_update_channel(channel: Option<project::flags::Channel>) -> Result<project::flags::FeatureFlags, String> {
+    let args = serde_json::json!({ "channel": channel });
+    audited(Actor::User, "set_update_channel", args, project::flags::set_channel(channel).map_err(|e| format!("{:#}", e)))
+}
+
+/// Everything telemetry has recorded, and exactly what the next upload
+/// would send.
+#[tauri::command]
+fn get_telemetry() -> Result<project::telemetry::TelemetryReport, String> {
+    project::telemetry::report().map_err(|e| format!("{:#}", e))
+}
+
+/// Delete all recorded usage counts.
+#[tauri::command]
+fn clear_telemetry() -> Result<(), String> {
+    let result = project::telemetry::clear().map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "clear_telemetry", serde_json::json!({}), result)
+}
+
+/// Get the app-wide settings (provider host table, etc.).
+#[tauri::command]
+fn get_app_settings() -> Result<project::settings::AppSettings, String> {
+    project::settings::load_app().map_err(|e| format!("{:#}", e))
+}
+
+/// Replace the app-wide settings.
+#[tauri::command]
+fn save_app_settings(state: State<'_, AppState>, settings: project::settings::AppSettings) -> Result<(), String> {
+    let result = project::settings::save_app(&settings).map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "save_app_settings", serde_json::json!({ "settings": settings }), result)?;
+    *state.event_filter.write().unwrap() = settings.event_filter;
     Ok(())
 }
 
+/// Change which event kinds are emitted and persisted. Applies to running
+/// agents from their next event.
+#[tauri::command]
+fn set_event_filter(state: State<'_, AppState>, filter: agent::filter::EventFilter) -> Result<(), String> {
+    let result = project::settings::load_app().and_then(|mut settings| {
+        settings.event_filter = filter.clone();
+        project::settings::save_app(&settings)
+    });
+    let result = result.map_err(|e| format!("{:#}", e));
+    audited(Actor::User, "set_event_filter", serde_json::json!({ "filter": filter }), result)?;
+    *state.event_filter.write().unwrap() = filter;
+    Ok(())
+}
+
+/// Publish this machine's roster, the given projects' boards and its
+/// transcripts to the sync folder, and return what the other machines last
+/// published. The sync store calls this every minute; empty when sync is off.
+#[tauri::command]
+async fn sync_machines(
+    state: State<'_, AppState>,
+    project_roots: Vec<String>,
+) -> Result<Vec<project::sync::MachineSnapshot>, String> {
+    let Some(config) = project::settings::load_app().map_err(|e| format!("{:#}", e))?.sync else {
+        return Ok(vec![]);
+    };
+    let agents = all_agents(&state.agents).await;
+    let roots: Vec<PathBuf> = project_roots.into_iter().map(PathBuf::from).collect();
+    tokio::task::spawn_blocking(move || {
+        project::sync::publish(&config, agents, &roots)?;
+        project::sync::peers(&config)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// The tickets other machines have on their boards for `project_root`, for
+/// the frontend to fold into its own. Empty when sync is off.
+#[tauri::command]
+async fn get_peer_tickets(project_root: String) -> Result<Vec<serde_json::Value>, String> {
+    let Some(config) = project::settings::load_app().map_err(|e| format!("{:#}", e))?.sync else {
+        return Ok(vec![]);
+    };
+    tokio::task::spawn_blocking(move || {
+        let peers = project::sync::peers(&config)?;
+        Ok::<_, anyhow::Error>(project::sync::board_tickets(&peers, &PathBuf::from(project_root)))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// An agent's transcript from another machine, as it last published it.
+#[tauri::command]
+fn get_peer_transcript(
+    machine_id: String,
+    agent_id: String,
+    ticket_id: String,
+) -> Result<Vec<agent::transcript::TranscriptEntry>, String> {
+    let config = project::settings::load_app()
+        .map_err(|e| format!("{:#}", e))?
+        .sync
+        .ok_or_else(|| "sync is not configured".to_string())?;
+    project::sync::peer_transcript(&config, &machine_id, &agent_id, &ticket_id).map_err(|e| format!("{:#}", e))
+}
+
+/// Get the settings for one project (GitLab instance, etc.).
+#[tauri::command]
+fn get_project_settings(project_root: String) -> Result<project::settings::ProjectSettings, String> {
+    project::settings::load_project(std::path::Path::new(&project_root))
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Replace the settings for one project.
+#[tauri::command]
+fn save_project_settings(
+    project_root: String,
+    settings: project::settings::ProjectSettings,
+) -> Result<(), String> {
+    let result = match settings.working_hours {
+        Some(ref hours) => hours.validate().map_err(|e| format!("invalid working hours: {:#}", e)),
+        None => Ok(()),
+    };
+    let result = result.and_then(|()| {
+        project::settings::save_project(std::path::Path::new(&project_root), &settings)
+            .map_err(|e| format!("{:#}", e))
+    });
+    let args = serde_json::json!({ "project_root": project_root, "settings": settings });
+    audited(Actor::User, "save_project_settings", args, result)
+}
+
 // ── App entry point ───────────────────────────────────────────────────────────
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Before any thread starts, and before anything spawns git, gh or claude
+    platform::shell_path::apply();
+
     tauri::Builder::default()
+        // First, so a second launch hands over its link before starting
+        // anything; the deep-link feature forwards the link to `on_open_url`.
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            focus_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(
             tauri_plugin_log::Builder::new()
                 .level(log::LevelFilter::Info)
@@ -535,6 +3036,7 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_stronghold::Builder::new(|password| {
                 // Derive a 32-byte vault key from the installation key + a fixed app salt.
@@ -548,19 +3050,84 @@ pub fn run() {
         )
         .setup(|app| {
             // Bind synchronously to grab the port before async runtime takes over.
-            let listener = mcp::bind();
-            let port = mcp::bound_port(&listener);
-            let mcp = mcp::McpState::new(port);
+            let listen = project::settings::load_app().unwrap_or_default().mcp;
+            let listener = mcp::listen::bind(&listen);
+            let port = mcp::listen::bound_port(&listener);
+            let mut mcp = mcp::McpState::new(port);
+            mcp.listen = listen;
 
             // Spawn the axum server — it takes clones of the pending Arcs.
             let pending = mcp.pending_questions.clone();
             let pending_tickets = mcp.pending_ticket_queries.clone();
+            let run_tokens = mcp.run_tokens.clone();
             let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(mcp::serve(listener, pending, pending_tickets, app_handle));
+            // The mobile relay serves reply links on the LAN; off unless configured
+            let relay = project::settings::load_app()
+                .unwrap_or_default()
+                .relay
+                .and_then(|config| match mcp::relay::bind(&config) {
+                    Ok(relay_listener) => {
+                        let links = mcp::relay::new_links();
+                        tauri::async_runtime::spawn(mcp::relay::serve(relay_listener, links.clone(), pending.clone()));
+                        Some((config, links))
+                    }
+                    Err(e) => {
+                        warn!("[setup] mobile relay disabled: {:#}", e);
+                        None
+                    }
+                });
+            tauri::async_runtime::spawn(mcp::serve(listener, pending, pending_tickets, run_tokens, relay, app_handle));
+            // Budget caps crossed by any run, side runs included, are announced from here
+            agent::costs::init(app.handle().clone());
+            tauri::async_runtime::spawn(agent::standup::schedule(app.handle().clone()));
+            tauri::async_runtime::spawn(project::telemetry::schedule());
+            tauri::async_runtime::spawn(git::drift::schedule(app.handle().clone()));
+
+            // Runs the last process was still working on can't continue on their own
+            match agent::recovery::recover() {
+                Ok(0) => {}
+                Ok(n) => info!("[setup] {} run(s) were interrupted by the last shutdown", n),
+                Err(e) => warn!("[setup] run recovery failed: {:#}", e),
+            }
+
+            let agents = new_store();
+            tauri::async_runtime::spawn(agent::state::emit_changes(agents.clone(), app.handle().clone()));
+            tauri::async_runtime::spawn(agent::utilization::track(agents.clone()));
 
             app.manage(AppState {
-                agents: new_store(),
+                agents,
                 mcp,
+                github_app_tokens: github::app_auth::new_cache(),
+                watchers: git::watch::new_store(),
+                terminals: agent::terminal::new_store(),
+                replays: agent::replay::new_store(),
+                run_locks: agent::locks::new_store(),
+                event_filter: agent::filter::new_store(
+                    project::settings::load_app().unwrap_or_default().event_filter,
+                ),
+                scheduler: Default::default(),
+                pending_link: std::sync::Arc::new(std::sync::Mutex::new(
+                    project::deeplink::from_args(std::env::args().skip(1)),
+                )),
+                pr_decisions: agent::pr_approval::new_store(),
+                tournaments: agent::tournament::new_store(),
+                deferred: project::hours::new_store(),
+            });
+
+            // Installers register the scheme; Linux and Windows dev builds
+            // have to at runtime
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            if let Err(e) = app.deep_link().register_all() {
+                warn!("[setup] failed to register {}:// links: {}", project::deeplink::SCHEME, e);
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    match project::deeplink::parse(url.as_str()) {
+                        Ok(link) => route_deep_link(&handle, link),
+                        Err(e) => warn!("[setup] ignoring link: {:#}", e),
+                    }
+                }
             });
 
             Ok(())
@@ -568,18 +3135,122 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             create_agent,
             update_agent,
+            get_pinned_notes,
+            add_pinned_note,
+            remove_pinned_note,
+            list_snippets,
+            save_snippet,
+            delete_snippet,
             delete_agent,
             scan_folder,
             get_all_agents,
             get_worktree_diff,
+            get_file_diff,
+            check_worktree_drift,
+            rebase_worktree,
+            get_file_at_base,
+            open_in_editor,
             start_agent,
+            check_github_token,
+            check_repo_access,
             resume_agent,
+            compose_resume_prompt,
+            get_interrupted_runs,
+            resume_interrupted_run,
+            dismiss_interrupted_run,
+            get_protected_edits,
+            approve_protected_edit,
+            reject_protected_edit,
+            get_pr_requests,
+            approve_pr_request,
+            reject_pr_request,
+            get_checkpoint_presets,
+            get_checkpoint_hits,
+            approve_checkpoint,
+            reject_checkpoint,
+            handoff_ticket,
+            watch_worktree,
+            unwatch_worktree,
+            open_worktree_shell,
+            write_terminal,
+            resize_terminal,
+            close_terminal,
+            replay_run,
+            stop_replay,
+            export_transcript,
+            fetch_full_content,
+            get_events,
+            search_transcripts,
+            get_annotations,
+            save_annotation,
+            delete_annotation,
+            pick_tournament_winner,
             chat_agent,
             start_pr_poll,
+            list_github_issues,
+            list_gitlab_issues,
+            start_mr_poll,
+            cancel_deferred_run,
+            merge_pull_request,
+            score_diff_risk,
+            generate_release_notes,
+            create_ticket_from_template,
+            find_dependency_updates,
+            audit_dependencies,
+            track_advisories,
+            bulk_update_tickets,
+            bulk_assign,
+            get_run_queue,
+            get_run_history,
+            export_costs,
+            set_api_keys,
+            get_api_key_usage,
+            test_gateway,
+            get_telemetry,
+            get_onboarding_state,
+            open_deep_link,
+            take_deep_link,
+            check_project_health,
+            list_wsl_distros,
+            check_wsl_distro,
+            mark_onboarding_step,
+            skip_onboarding_step,
+            get_feature_flags,
+            set_feature_flag,
+            set_update_channel,
+            clear_telemetry,
+            get_agent_utilization,
+            get_review_latency_stats,
+            sync_ticket_status,
+            refresh_conventions,
             answer_agent,
+            answer_agent_with_audio,
+            get_pending_questions,
+            get_mcp_endpoint,
+            get_ticket_qa,
+            estimate_ticket,
+            get_ticket_estimates,
+            generate_postmortem,
+            get_postmortems,
+            generate_codebase_tour,
+            get_codebase_tour,
+            export_calendar,
+            verify_acceptance,
+            get_acceptance_reports,
+            run_standup,
+            get_last_standup,
             answer_tickets,
             read_project_store,
             write_project_store,
+            get_app_settings,
+            save_app_settings,
+            set_event_filter,
+            sync_machines,
+            get_peer_transcript,
+            get_peer_tickets,
+            get_project_settings,
+            save_project_settings,
+            get_audit_log,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,218 @@
+// apps/desktop/src-tauri/src/mcp/inbox.rs
+//
+// The pending-question inbox. Each waiting ask_human / present_choices /
+// confirm_action call holds one entry: the reply channel plus what the UI needs
+// to order and show it. Keeping both in the same map means the inbox can never
+// list a question nobody is waiting on, or miss one that is.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Mutex};
+use ts_rs::TS;
+
+use crate::project::qa::SuggestedAnswer;
+
+/// How urgently the agent needs an answer. Agents pass this as a hint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    /// The agent cannot make any progress until answered.
+    Blocking,
+}
+
+impl Priority {
+    /// Parse an agent-supplied hint, falling back to Normal for anything unknown.
+    pub fn from_hint(hint: Option<&str>) -> Self {
+        match hint.map(|h| h.trim().to_ascii_lowercase()).as_deref() {
+            Some("low") => Priority::Low,
+            Some("high") => Priority::High,
+            Some("blocking") => Priority::Blocking,
+            _ => Priority::Normal,
+        }
+    }
+
+    fn bumped(self, levels: u64) -> Self {
+        let rank = (self as u64 + levels).min(Priority::Blocking as u64);
+        match rank {
+            0 => Priority::Low,
+            1 => Priority::Normal,
+            2 => Priority::High,
+            _ => Priority::Blocking,
+        }
+    }
+}
+
+/// Which tool asked — decides how the UI renders the answer controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    Question,
+    Choices,
+    Confirm,
+}
+
+/// A waiting question as shown in the inbox.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct QuestionInfo {
+    pub id: String,
+    pub agent_id: String,
+    pub kind: QuestionKind,
+    pub question: String,
+    /// Choices (present_choices) or action details (confirm_action).
+    #[ts(type = "unknown")]
+    pub details: Option<serde_json::Value>,
+    /// The priority the agent asked with.
+    pub priority: Priority,
+    /// Priority after aging — what the inbox sorts by.
+    pub effective_priority: Priority,
+    /// Unix seconds when the question was asked.
+    #[ts(type = "number")]
+    pub asked_at: u64,
+    #[ts(type = "number")]
+    pub waiting_secs: u64,
+    /// An earlier answer to a similar question on the same project.
+    pub suggested_answer: Option<SuggestedAnswer>,
+}
+
+/// One inbox entry: the reply channel plus its display info.
+pub struct PendingQuestion {
+    pub tx: oneshot::Sender<String>,
+    pub info: QuestionInfo,
+}
+
+/// Pending questions keyed by agent_id — an agent blocks on one at a time.
+pub type PendingQuestions = Arc<Mutex<HashMap<String, PendingQuestion>>>;
+
+/// Waiting this long raises a question's priority by one level, so low
+/// priority questions can't starve behind a stream of high ones.
+pub const AGING_STEP_SECS: u64 = 5 * 60;
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl PendingQuestion {
+    pub fn new(
+        agent_id: &str,
+        kind: QuestionKind,
+        question: String,
+        details: Option<serde_json::Value>,
+        priority: Priority,
+        tx: oneshot::Sender<String>,
+    ) -> Self {
+        PendingQuestion {
+            tx,
+            info: QuestionInfo {
+                id: uuid::Uuid::new_v4().to_string(),
+                agent_id: agent_id.to_string(),
+                kind,
+                question,
+                details,
+                priority,
+                effective_priority: priority,
+                asked_at: now_secs(),
+                waiting_secs: 0,
+                suggested_answer: None,
+            },
+        }
+    }
+}
+
+/// Snapshot the inbox in answering order: highest effective priority first,
+/// then oldest first.
+pub fn ordered(pending: &HashMap<String, PendingQuestion>, now: u64) -> Vec<QuestionInfo> {
+    let mut questions: Vec<QuestionInfo> = pending
+        .values()
+        .map(|p| {
+            let mut info = p.info.clone();
+            info.waiting_secs = now.saturating_sub(info.asked_at);
+            info.effective_priority = info.priority.bumped(info.waiting_secs / AGING_STEP_SECS);
+            info
+        })
+        .collect();
+    questions.sort_by(|a, b| {
+        b.effective_priority
+            .cmp(&a.effective_priority)
+            .then(a.asked_at.cmp(&b.asked_at))
+    });
+    questions
+}
+
+/// Drop an agent's entry if it is still the question with `id` — used when a
+/// wait times out, without clobbering a newer question from the same agent.
+pub async fn remove_if_current(pending: &PendingQuestions, agent_id: &str, id: &str) {
+    let mut map = pending.lock().await;
+    if map.get(agent_id).map(|p| p.info.id == id).unwrap_or(false) {
+        map.remove(agent_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(agent: &str, priority: Priority, asked_at: u64) -> PendingQuestion {
+        let (tx, _rx) = oneshot::channel();
+        let mut p = PendingQuestion::new(agent, QuestionKind::Question, "?".to_string(), None, priority, tx);
+        p.info.asked_at = asked_at;
+        p
+    }
+
+    #[test]
+    fn priority_hint_parsing() {
+        assert_eq!(Priority::from_hint(Some("HIGH")), Priority::High);
+        assert_eq!(Priority::from_hint(Some("urgent-ish")), Priority::Normal);
+        assert_eq!(Priority::from_hint(None), Priority::Normal);
+    }
+
+    #[test]
+    fn orders_by_priority_then_age() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), entry("a", Priority::Normal, 1_000));
+        map.insert("b".to_string(), entry("b", Priority::High, 1_010));
+        map.insert("c".to_string(), entry("c", Priority::Normal, 990));
+
+        let order: Vec<String> = ordered(&map, 1_020).into_iter().map(|q| q.agent_id).collect();
+        assert_eq!(order, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn old_questions_age_up() {
+        let mut map = HashMap::new();
+        map.insert("old".to_string(), entry("old", Priority::Low, 0));
+        map.insert("new".to_string(), entry("new", Priority::High, 3 * AGING_STEP_SECS));
+
+        let now = 3 * AGING_STEP_SECS;
+        let questions = ordered(&map, now);
+        assert_eq!(questions[0].agent_id, "old");
+        assert_eq!(questions[0].effective_priority, Priority::Blocking);
+        assert_eq!(questions[0].waiting_secs, now);
+    }
+
+    #[tokio::test]
+    async fn remove_if_current_keeps_newer_question() {
+        let pending: PendingQuestions = Arc::new(Mutex::new(HashMap::new()));
+        let first = entry("a", Priority::Normal, 0);
+        let first_id = first.info.id.clone();
+        pending.lock().await.insert("a".to_string(), first);
+        pending.lock().await.insert("a".to_string(), entry("a", Priority::Normal, 5));
+
+        remove_if_current(&pending, "a", &first_id).await;
+        assert!(pending.lock().await.contains_key("a"));
+    }
+}
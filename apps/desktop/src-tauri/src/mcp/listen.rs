@@ -0,0 +1,225 @@
+// apps/desktop/src-tauri/src/mcp/listen.rs
+//
+// Where the MCP server listens. By default it takes an OS-assigned loopback
+// port, which some endpoint security tools flag or block; those setups can pin
+// a fixed port (with a few fallbacks if it's taken) and allow-list it once.
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// How long a reachability probe waits for the server to accept and answer.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Interfaces the MCP server listens on. Agents on this machine connect
+/// through 127.0.0.1 either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum McpBind {
+    /// 127.0.0.1 only; nothing off this machine can connect.
+    #[default]
+    Loopback,
+    /// Every interface, for Docker on Linux, where containers reach the host
+    /// through the bridge rather than its loopback. Anyone on the network can
+    /// then reach the server too: `/sse` and `/hook` still need a run token,
+    /// but `/message` only needs a live session id. Use it on trusted networks.
+    AllInterfaces,
+}
+
+impl McpBind {
+    fn ip(self) -> IpAddr {
+        match self {
+            McpBind::Loopback => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            McpBind::AllInterfaces => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        }
+    }
+}
+
+/// Where the MCP server listens. Read at startup; changes take effect after
+/// a restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct McpListenConfig {
+    pub bind: McpBind,
+    /// Fixed port to listen on; OS-assigned when unset.
+    pub port: Option<u16>,
+    /// Ports after `port` to try when it's taken, before falling back to an
+    /// OS-assigned one.
+    pub fallback_ports: u16,
+}
+
+impl Default for McpListenConfig {
+    fn default() -> Self {
+        McpListenConfig {
+            bind: McpBind::Loopback,
+            port: None,
+            fallback_ports: 10,
+        }
+    }
+}
+
+/// Where the MCP server ended up listening, for diagnostics.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct McpEndpoint {
+    /// SSE endpoint agents on this machine connect to. Each run adds its own
+    /// `?token=`; the server refuses this bare URL.
+    pub url: String,
+    pub port: u16,
+    pub bind: McpBind,
+    /// The configured port, when it was taken and another was used.
+    pub requested_port: Option<u16>,
+    /// The server answered an HTTP request on `/sse` just now.
+    pub reachable: bool,
+}
+
+/// Ports to try in order: the configured one and its fallbacks. Empty when
+/// no port is configured.
+fn candidate_ports(config: &McpListenConfig) -> Vec<u16> {
+    let Some(first) = config.port.filter(|p| *p != 0) else {
+        return Vec::new();
+    };
+    (0..=config.fallback_ports)
+        .map_while(|i| first.checked_add(i))
+        .collect()
+}
+
+/// Bind the MCP server's port synchronously, non-blocking so
+/// `tokio::net::TcpListener::from_std()` accepts it. Call this in Tauri's
+/// setup() before the async runtime has full control.
+///
+/// A configured port that's taken falls through to its fallbacks, then to an
+/// OS-assigned port, so a busy port never stops the app from starting.
+pub fn bind(config: &McpListenConfig) -> TcpListener {
+    let ip = config.bind.ip();
+    if config.bind == McpBind::AllInterfaces {
+        warn!("[mcp::bind] listening on every interface; /message is reachable from the network");
+    }
+    let listener = candidate_ports(config)
+        .into_iter()
+        .find_map(|port| match TcpListener::bind((ip, port)) {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                warn!("[mcp::bind] port {} unavailable: {}", port, e);
+                None
+            }
+        })
+        .unwrap_or_else(|| {
+            if let Some(port) = config.port.filter(|p| *p != 0) {
+                warn!(
+                    "[mcp::bind] port {} and its fallbacks are taken, using any free port",
+                    port
+                );
+            }
+            TcpListener::bind((ip, 0)).expect("MCP: failed to bind port")
+        });
+    listener
+        .set_nonblocking(true)
+        .expect("MCP: failed to set non-blocking");
+    listener
+}
+
+pub fn bound_port(listener: &TcpListener) -> u16 {
+    listener.local_addr().expect("MCP: no local addr").port()
+}
+
+/// Whether an HTTP server answers `/sse` on the loopback `port`. Blocking.
+///
+/// The probe has no run token, so the MCP server refuses it; any HTTP
+/// response still shows it's up, where a bare connect only shows that
+/// something holds the port.
+pub fn answers(port: u16) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(PROBE_TIMEOUT));
+    let request = format!(
+        "GET /sse HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+        port
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    let mut head = [0u8; 8];
+    stream.read_exact(&mut head).is_ok() && head.starts_with(b"HTTP/1.")
+}
+
+/// The endpoint for a server on `port` started with `config`. Blocking: it
+/// probes the port.
+pub fn endpoint(config: &McpListenConfig, port: u16) -> McpEndpoint {
+    McpEndpoint {
+        url: format!("http://127.0.0.1:{}/sse", port),
+        port,
+        bind: config.bind,
+        requested_port: config.port.filter(|p| *p != 0 && *p != port),
+        reachable: answers(port),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tries_the_fixed_port_then_its_fallbacks() {
+        assert!(candidate_ports(&McpListenConfig::default()).is_empty());
+        let config = McpListenConfig {
+            port: Some(7823),
+            fallback_ports: 2,
+            ..Default::default()
+        };
+        assert_eq!(candidate_ports(&config), vec![7823, 7824, 7825]);
+        let config = McpListenConfig {
+            port: Some(u16::MAX),
+            fallback_ports: 5,
+            ..Default::default()
+        };
+        assert_eq!(candidate_ports(&config), vec![u16::MAX]);
+    }
+
+    #[test]
+    fn a_taken_port_falls_back_and_is_reported() {
+        let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = taken.local_addr().unwrap().port();
+        let config = McpListenConfig {
+            port: Some(port),
+            fallback_ports: 0,
+            ..Default::default()
+        };
+        let listener = bind(&config);
+        let bound = bound_port(&listener);
+        assert_ne!(bound, port);
+
+        let endpoint = endpoint(&config, bound);
+        assert_eq!(endpoint.requested_port, Some(port));
+        assert_eq!(endpoint.url, format!("http://127.0.0.1:{}/sse", bound));
+    }
+
+    #[test]
+    fn only_an_http_answer_counts_as_reachable() {
+        // Accepts but never speaks HTTP
+        let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(!answers(bound_port(&silent)));
+
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = bound_port(&server);
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = server.accept().unwrap();
+            let mut buf = [0u8; 512];
+            let _ = stream.read(&mut buf);
+            stream
+                .write_all(b"HTTP/1.1 401 Unauthorized\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+        });
+        assert!(answers(port));
+        handle.join().unwrap();
+    }
+}
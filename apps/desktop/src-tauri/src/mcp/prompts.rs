@@ -0,0 +1,192 @@
+// apps/desktop/src-tauri/src/mcp/prompts.rs
+//
+// Curated prompt templates served over MCP `prompts/list` and `prompts/get`.
+// Agents fetch one when they reach the step it covers — writing the PR body,
+// summarising a change — instead of every system prompt carrying them all.
+
+use serde_json::{json, Map, Value};
+
+/// An argument a template fills in from the caller.
+struct PromptArg {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+struct Prompt {
+    name: &'static str,
+    description: &'static str,
+    arguments: &'static [PromptArg],
+    /// Text with `{{name}}` placeholders for the arguments.
+    template: &'static str,
+}
+
+const PROMPTS: &[Prompt] = &[
+    Prompt {
+        name: "write_pr_description",
+        description: "How to write the body of the pull request for your ticket.",
+        arguments: &[
+            PromptArg {
+                name: "ticket_title",
+                description: "Title of the ticket the PR implements",
+                required: true,
+            },
+            PromptArg {
+                name: "base",
+                description: "Branch the PR merges into; defaults to the repo's default branch",
+                required: false,
+            },
+        ],
+        template: "\
+Write the pull request description for \"{{ticket_title}}\".
+
+Run `git log --oneline {{base}}..HEAD` and `git diff --stat {{base}}...HEAD` first, \
+and describe what the branch actually does, not what the ticket asked for.
+
+Structure:
+- Open with one or two plain sentences: what changed and why.
+- **Changes**: one bullet per behaviour change. Leave out file lists; the diff shows them.
+- **Testing**: the commands you ran and what you saw. Name anything you couldn't verify.
+- **Notes for reviewers**: only if something is surprising, risky or deliberately left out.
+
+Keep it under 250 words. Pass it with `gh pr create --body-file` so the markdown survives.",
+    },
+    Prompt {
+        name: "summarize_changes",
+        description: "How to summarise the work on your branch for the user.",
+        arguments: &[PromptArg {
+            name: "base",
+            description: "Branch to compare against; defaults to the repo's default branch",
+            required: false,
+        }],
+        template: "\
+Summarise the changes on this branch since `{{base}}` for the user.
+
+Read `git diff {{base}}...HEAD` rather than working from memory. Lead with the \
+outcome in one sentence, then up to five bullets on what changed in behaviour. \
+Mention anything unfinished, any test you skipped, and any follow-up the user \
+should know about. No file-by-file walkthroughs.",
+    },
+    Prompt {
+        name: "write_commit_message",
+        description: "How to write a commit message for staged changes.",
+        arguments: &[],
+        template: "\
+Write a commit message for the staged changes (`git diff --cached`).
+
+Subject: imperative mood, at most 72 characters, no trailing period, following \
+the repository's existing style in `git log --oneline -20`. Body, when the \
+change needs one: why it was made and anything a reviewer wouldn't see from \
+the diff, wrapped at 72 columns.",
+    },
+    Prompt {
+        name: "review_own_diff",
+        description: "A checklist for reviewing your own diff before asking for review.",
+        arguments: &[PromptArg {
+            name: "base",
+            description: "Branch to compare against; defaults to the repo's default branch",
+            required: false,
+        }],
+        template: "\
+Review `git diff {{base}}...HEAD` as if someone else wrote it. Check for:
+- Debug output, commented-out code, TODOs you meant to resolve
+- Changes unrelated to the ticket
+- Error paths that swallow failures or lose context
+- New behaviour without a test, or tests that can't fail
+- Names and comments that no longer match the code
+
+Fix what you find before opening or updating the PR.",
+    },
+];
+
+/// The `prompts/list` result.
+pub fn list() -> Value {
+    let prompts: Vec<Value> = PROMPTS
+        .iter()
+        .map(|p| {
+            json!({
+                "name": p.name,
+                "description": p.description,
+                "arguments": p.arguments.iter().map(|a| json!({
+                    "name": a.name,
+                    "description": a.description,
+                    "required": a.required,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    json!({ "prompts": prompts })
+}
+
+/// The `prompts/get` result for `name` filled in with `arguments`, or what's
+/// wrong with the request. A `base` the caller leaves out is `default_base`,
+/// the repo's default branch.
+pub fn get(
+    name: &str,
+    arguments: Option<&Map<String, Value>>,
+    default_base: &str,
+) -> Result<Value, String> {
+    let prompt = PROMPTS
+        .iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Unknown prompt: {}", name))?;
+    let mut text = prompt.template.to_string();
+    for arg in prompt.arguments {
+        let given = arguments
+            .and_then(|a| a.get(arg.name))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        let value = match given {
+            Some(v) => v,
+            None if arg.required => return Err(format!("Missing argument: {}", arg.name)),
+            None if arg.name == "base" => default_base,
+            None => "",
+        };
+        text = text.replace(&format!("{{{{{}}}}}", arg.name), value);
+    }
+    Ok(json!({
+        "description": prompt.description,
+        "messages": [{
+            "role": "user",
+            "content": { "type": "text", "text": text }
+        }]
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_placeholder_is_a_declared_argument() {
+        for prompt in PROMPTS {
+            let mut text = prompt.template.to_string();
+            for arg in prompt.arguments {
+                text = text.replace(&format!("{{{{{}}}}}", arg.name), "");
+            }
+            assert!(
+                !text.contains("{{"),
+                "{} has an undeclared placeholder",
+                prompt.name
+            );
+        }
+        let listed = list();
+        assert_eq!(listed["prompts"].as_array().unwrap().len(), PROMPTS.len());
+    }
+
+    #[test]
+    fn get_fills_arguments_and_defaults() {
+        let args = json!({ "ticket_title": "Fix retry backoff" });
+        let result = get("write_pr_description", args.as_object(), "origin/trunk").unwrap();
+        let text = result["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("\"Fix retry backoff\""));
+        assert!(text.contains("git log --oneline origin/trunk..HEAD"));
+
+        assert_eq!(
+            get("write_pr_description", None, "main").unwrap_err(),
+            "Missing argument: ticket_title"
+        );
+        assert!(get("no_such_prompt", None, "main").is_err());
+    }
+}
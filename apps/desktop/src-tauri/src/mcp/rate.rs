@@ -0,0 +1,114 @@
+// apps/desktop/src-tauri/src/mcp/rate.rs
+//
+// Per-agent limits on MCP tool calls, so an agent stuck in a loop can't
+// flood the human with questions. Calls over the limit are refused with a
+// structured error telling the agent to carry on without asking.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+/// Calls allowed per agent, per tool, within the window. Tools not listed
+/// are unlimited.
+const LIMITS: &[(&str, usize, Duration)] = &[
+    ("ask_human", 3, HOUR),
+    ("present_choices", 3, HOUR),
+    ("confirm_action", 5, HOUR),
+    ("status_update", 120, HOUR),
+    ("set_status_note", 120, HOUR),
+];
+
+/// A refused call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimited {
+    pub tool: String,
+    pub limit: usize,
+    pub window: Duration,
+    /// When the oldest counted call leaves the window.
+    pub retry_after: Duration,
+}
+
+impl RateLimited {
+    /// JSON-RPC error body for the refused call.
+    pub fn to_error(&self) -> Value {
+        json!({
+            "code": -32003,
+            "message": format!(
+                "Rate limit reached for {} ({} calls per {} minutes). Do not ask again: \
+                 proceed with your best judgment and list the open question in your final summary.",
+                self.tool,
+                self.limit,
+                self.window.as_secs() / 60
+            ),
+            "data": {
+                "tool": self.tool,
+                "limit": self.limit,
+                "window_secs": self.window.as_secs(),
+                "retry_after_secs": self.retry_after.as_secs(),
+            }
+        })
+    }
+}
+
+/// Recent call times, per (agent, tool).
+#[derive(Default)]
+pub struct RateLimiter {
+    calls: HashMap<(String, String), VecDeque<Instant>>,
+}
+
+pub type SharedRateLimiter = Arc<Mutex<RateLimiter>>;
+
+impl RateLimiter {
+    /// Count a call at `now`, or refuse it when the agent is over the limit.
+    pub fn check(&mut self, agent_id: &str, tool: &str, now: Instant) -> Result<(), RateLimited> {
+        let Some(&(_, limit, window)) = LIMITS.iter().find(|(name, _, _)| *name == tool) else {
+            return Ok(());
+        };
+        let calls = self
+            .calls
+            .entry((agent_id.to_string(), tool.to_string()))
+            .or_default();
+        while calls.front().is_some_and(|at| now.duration_since(*at) >= window) {
+            calls.pop_front();
+        }
+        if calls.len() >= limit {
+            let oldest = *calls.front().expect("over the limit means non-empty");
+            return Err(RateLimited {
+                tool: tool.to_string(),
+                limit,
+                window,
+                retry_after: window.saturating_sub(now.duration_since(oldest)),
+            });
+        }
+        calls.push_back(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_the_fourth_question_within_an_hour() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        for minute in 0..3 {
+            assert!(limiter.check("a1", "ask_human", start + Duration::from_secs(minute * 60)).is_ok());
+        }
+        let refused = limiter.check("a1", "ask_human", start + Duration::from_secs(600)).unwrap_err();
+        assert_eq!(refused.retry_after, Duration::from_secs(3000));
+        assert_eq!(refused.to_error()["data"]["limit"], 3);
+        // Other agents and unlisted tools are unaffected
+        assert!(limiter.check("a2", "ask_human", start).is_ok());
+        assert!(limiter.check("a1", "list_tickets", start).is_ok());
+        // The window slides
+        assert!(limiter.check("a1", "ask_human", start + HOUR).is_ok());
+    }
+}
@@ -0,0 +1,319 @@
+// apps/desktop/src-tauri/src/mcp/relay.rs
+//
+// The mobile-companion relay. When an agent asks a question it is pushed to a
+// ntfy topic with a one-time reply link; the link opens a small form served on
+// the LAN (or VPN) interface, and submitting it delivers the reply through the
+// same inbox the desktop UI answers from.
+
+use std::{
+    collections::HashMap,
+    io::Write,
+    net::TcpListener,
+    process::{Command, Stdio},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Html,
+    routing::get,
+    Form, Router,
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::inbox::PendingQuestions;
+
+/// Seconds a push to the notification endpoint may take.
+const PUSH_TIMEOUT_SECS: u32 = 10;
+
+/// Where agent questions are pushed, and how the phone reaches this machine.
+/// Read at startup; changes take effect after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct RelayConfig {
+    /// A ntfy topic URL, e.g. "https://ntfy.sh/poietai-7f3a" or a self-hosted one.
+    /// Anyone who knows the topic sees the questions, so pick an unguessable name.
+    pub topic_url: String,
+    /// This machine as the phone sees it, e.g. "http://192.168.1.20:7878".
+    pub public_url: String,
+    /// Port the reply form is served on, on all interfaces.
+    pub port: u16,
+}
+
+impl RelayConfig {
+    fn reply_url(&self, token: &str) -> String {
+        format!("{}/reply/{}", self.public_url.trim_end_matches('/'), token)
+    }
+}
+
+/// The question a reply link answers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplyLink {
+    pub agent_id: String,
+    pub question_id: String,
+}
+
+/// Outstanding reply links keyed by their one-time token.
+pub type ReplyLinks = Arc<std::sync::Mutex<HashMap<String, ReplyLink>>>;
+
+pub fn new_links() -> ReplyLinks {
+    Arc::new(std::sync::Mutex::new(HashMap::new()))
+}
+
+/// Bind the reply form's port on all interfaces, non-blocking for tokio.
+pub fn bind(config: &RelayConfig) -> Result<TcpListener> {
+    let listener = TcpListener::bind(("0.0.0.0", config.port))
+        .with_context(|| format!("failed to bind relay port {}", config.port))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// ntfy headers for a question: a title, and a tap and a button that both
+/// open the reply form.
+fn headers(config: &RelayConfig, agent_name: &str, token: &str) -> Vec<String> {
+    let url = config.reply_url(token);
+    vec![
+        format!("Title: {} has a question", agent_name),
+        format!("Click: {}", url),
+        format!("Actions: view, Reply, {}", url),
+        "Tags: question".to_string(),
+    ]
+}
+
+fn send(config: &RelayConfig, headers: &[String], question: &str) -> Result<()> {
+    let mut cmd = Command::new("curl");
+    cmd.args(["-sS", "-f", "-o", "/dev/null", "--max-time"])
+        .arg(PUSH_TIMEOUT_SECS.to_string());
+    for header in headers {
+        cmd.arg("-H").arg(header);
+    }
+    let mut child = cmd
+        .args(["--data-binary", "@-", &config.topic_url])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(question.as_bytes())
+            .context("failed to pass the question to curl")?;
+    }
+    let output = child.wait_with_output().context("failed to run curl")?;
+    if !output.status.success() {
+        anyhow::bail!("push failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Mint a reply link for the question and push it. Links to questions no
+/// longer waiting are dropped first. The push itself runs in the background.
+pub async fn push(
+    config: RelayConfig,
+    links: &ReplyLinks,
+    pending: &PendingQuestions,
+    agent_name: &str,
+    link: ReplyLink,
+    question: String,
+) {
+    let token = uuid::Uuid::new_v4().to_string();
+    {
+        let pending = pending.lock().await;
+        let mut links = links.lock().unwrap();
+        links.retain(|_, l| pending.get(&l.agent_id).is_some_and(|p| p.info.id == l.question_id));
+        links.insert(token.clone(), link);
+    }
+    let headers = headers(&config, agent_name, &token);
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = send(&config, &headers, &question) {
+            warn!("[relay::push] {:#}", e);
+        }
+    });
+}
+
+/// Why a reply link couldn't deliver.
+#[derive(Debug, PartialEq)]
+enum ReplyError {
+    /// Unknown or already used.
+    UnknownLink,
+    /// Answered elsewhere, or the agent stopped waiting.
+    NoLongerWaiting,
+}
+
+/// The question a link answers, if it is still the one its agent waits on.
+async fn question_for(links: &ReplyLinks, pending: &PendingQuestions, token: &str) -> Result<String, ReplyError> {
+    let link = links.lock().unwrap().get(token).cloned().ok_or(ReplyError::UnknownLink)?;
+    let pending = pending.lock().await;
+    match pending.get(&link.agent_id) {
+        Some(p) if p.info.id == link.question_id => Ok(p.info.question.clone()),
+        _ => Err(ReplyError::NoLongerWaiting),
+    }
+}
+
+/// Spend the link on `reply`.
+async fn deliver(
+    links: &ReplyLinks,
+    pending: &PendingQuestions,
+    token: &str,
+    reply: String,
+) -> Result<(), ReplyError> {
+    let link = links.lock().unwrap().remove(token).ok_or(ReplyError::UnknownLink)?;
+    let entry = {
+        let mut pending = pending.lock().await;
+        match pending.get(&link.agent_id) {
+            Some(p) if p.info.id == link.question_id => pending.remove(&link.agent_id),
+            _ => None,
+        }
+    };
+    entry
+        .ok_or(ReplyError::NoLongerWaiting)?
+        .tx
+        .send(reply)
+        .map_err(|_| ReplyError::NoLongerWaiting)
+}
+
+// ── Reply form ────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+struct RelayState {
+    links: ReplyLinks,
+    pending: PendingQuestions,
+}
+
+#[derive(Deserialize)]
+struct ReplyForm {
+    reply: String,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(body: &str) -> Html<String> {
+    Html(format!(
+        "<!doctype html><meta name=viewport content=\"width=device-width\"><title>poietai</title>\
+         <body style=\"font-family:sans-serif;max-width:40em;margin:1em auto;padding:0 1em\">{}</body>",
+        body
+    ))
+}
+
+fn refused(err: ReplyError) -> (StatusCode, Html<String>) {
+    match err {
+        ReplyError::UnknownLink => (StatusCode::NOT_FOUND, page("<p>This reply link is unknown or already used.</p>")),
+        ReplyError::NoLongerWaiting => (StatusCode::GONE, page("<p>The agent is no longer waiting on this question.</p>")),
+    }
+}
+
+async fn form_handler(
+    Path(token): Path<String>,
+    State(state): State<RelayState>,
+) -> Result<Html<String>, (StatusCode, Html<String>)> {
+    let question = question_for(&state.links, &state.pending, &token).await.map_err(refused)?;
+    Ok(page(&format!(
+        "<p style=\"white-space:pre-wrap\">{}</p><form method=post>\
+         <textarea name=reply rows=6 style=\"width:100%\" required autofocus></textarea>\
+         <p><button>Send reply</button></p></form>",
+        escape(&question)
+    )))
+}
+
+async fn reply_handler(
+    Path(token): Path<String>,
+    State(state): State<RelayState>,
+    Form(ReplyForm { reply }): Form<ReplyForm>,
+) -> Result<Html<String>, (StatusCode, Html<String>)> {
+    let reply = reply.trim().to_string();
+    if reply.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, page("<p>The reply was empty.</p>")));
+    }
+    deliver(&state.links, &state.pending, &token, reply).await.map_err(refused)?;
+    Ok(page("<p>Reply sent. The agent is back at work.</p>"))
+}
+
+/// Serve reply forms on the relay listener. Call via tauri::async_runtime::spawn().
+pub async fn serve(listener: TcpListener, links: ReplyLinks, pending: PendingQuestions) {
+    let router = Router::new()
+        .route("/reply/:token", get(form_handler).post(reply_handler))
+        .with_state(RelayState { links, pending });
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+        Ok(l) => l,
+        Err(e) => {
+            warn!("[relay::serve] failed to convert listener: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = axum::serve(listener, router).await {
+        warn!("[relay::serve] stopped: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::inbox::{PendingQuestion, Priority, QuestionKind};
+    use tokio::sync::{oneshot, Mutex};
+
+    fn config() -> RelayConfig {
+        RelayConfig {
+            topic_url: "https://ntfy.sh/poietai-test".to_string(),
+            public_url: "http://10.0.0.5:7878/".to_string(),
+            port: 7878,
+        }
+    }
+
+    async fn waiting(pending: &PendingQuestions, agent: &str) -> (ReplyLink, oneshot::Receiver<String>) {
+        let (tx, rx) = oneshot::channel();
+        let entry = PendingQuestion::new(agent, QuestionKind::Question, "Which DB?".to_string(), None, Priority::Normal, tx);
+        let link = ReplyLink {
+            agent_id: agent.to_string(),
+            question_id: entry.info.id.clone(),
+        };
+        pending.lock().await.insert(agent.to_string(), entry);
+        (link, rx)
+    }
+
+    #[test]
+    fn headers_link_to_the_reply_form() {
+        let headers = headers(&config(), "Ada", "tok");
+        assert_eq!(headers[0], "Title: Ada has a question");
+        assert_eq!(headers[1], "Click: http://10.0.0.5:7878/reply/tok");
+        assert_eq!(headers[2], "Actions: view, Reply, http://10.0.0.5:7878/reply/tok");
+    }
+
+    #[tokio::test]
+    async fn link_delivers_once() {
+        let pending: PendingQuestions = Arc::new(Mutex::new(HashMap::new()));
+        let links = new_links();
+        let (link, rx) = waiting(&pending, "a").await;
+        links.lock().unwrap().insert("tok".to_string(), link);
+
+        assert_eq!(question_for(&links, &pending, "tok").await.unwrap(), "Which DB?");
+        deliver(&links, &pending, "tok", "Postgres".to_string()).await.unwrap();
+        assert_eq!(rx.await.unwrap(), "Postgres");
+        assert_eq!(
+            deliver(&links, &pending, "tok", "again".to_string()).await,
+            Err(ReplyError::UnknownLink)
+        );
+    }
+
+    #[tokio::test]
+    async fn link_to_a_replaced_question_is_refused() {
+        let pending: PendingQuestions = Arc::new(Mutex::new(HashMap::new()));
+        let links = new_links();
+        let (old, _rx) = waiting(&pending, "a").await;
+        links.lock().unwrap().insert("tok".to_string(), old);
+        let (_new, _rx2) = waiting(&pending, "a").await;
+
+        assert_eq!(
+            deliver(&links, &pending, "tok", "late".to_string()).await,
+            Err(ReplyError::NoLongerWaiting)
+        );
+        assert!(pending.lock().await.contains_key("a"));
+    }
+}
@@ -9,29 +9,101 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use log::{info, warn};
 use serde::Deserialize;
 use serde_json::{json, Value};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio_stream::wrappers::ReceiverStream;
 
+use super::inbox::{self, PendingQuestion, PendingQuestions, Priority, QuestionInfo, QuestionKind};
+use super::listen::McpListenConfig;
+use super::prompts;
+use super::rate::{RateLimiter, SharedRateLimiter};
+use super::relay::{self, RelayConfig, ReplyLink, ReplyLinks};
+
 // ── Public types ─────────────────────────────────────────────────────────────
 
 /// State held in AppState — provides `answer()` for the answer_agent command.
 pub struct McpState {
     pub port: u16,
-    pub(crate) pending_questions:
-        Arc<Mutex<HashMap<String, oneshot::Sender<String>>>>,
+    /// The listen settings the port was bound with.
+    pub listen: McpListenConfig,
+    pub(crate) pending_questions: PendingQuestions,
     pub(crate) pending_ticket_queries:
         Arc<Mutex<HashMap<String, oneshot::Sender<String>>>>,
+    pub(crate) run_tokens: RunTokens,
+}
+
+/// Per-run MCP tokens → the run each belongs to.
+pub(crate) type RunTokens = Arc<std::sync::Mutex<HashMap<String, RunEntry>>>;
+
+pub(crate) struct RunEntry {
+    agent_id: String,
+    /// Where the run's guard hook sends the tool calls it asks about.
+    tool_checks: mpsc::Sender<ToolCheck>,
+}
+
+/// A tool call the run's PreToolUse hook asks about before it happens.
+pub struct ToolCheck {
+    pub tool_name: String,
+    pub tool_input: Value,
+    /// The agent's working directory, as the agent sees it.
+    pub cwd: Option<std::path::PathBuf>,
+    /// `None` lets the call go ahead; otherwise the reason it's refused,
+    /// which the agent sees.
+    pub reply: oneshot::Sender<Option<String>>,
+}
+
+/// A run's MCP token, valid until dropped.
+pub struct RunToken {
+    tokens: RunTokens,
+    token: String,
+    tool_checks: mpsc::Receiver<ToolCheck>,
+}
+
+impl RunToken {
+    pub fn as_str(&self) -> &str {
+        &self.token
+    }
+
+    /// The next tool call the run's guard hook asks about.
+    pub async fn next_check(&mut self) -> Option<ToolCheck> {
+        self.tool_checks.recv().await
+    }
+}
+
+impl Drop for RunToken {
+    fn drop(&mut self) {
+        self.tokens.lock().unwrap().remove(&self.token);
+    }
 }
 
 impl McpState {
     pub fn new(port: u16) -> Self {
         Self {
             port,
+            listen: McpListenConfig::default(),
             pending_questions: Arc::new(Mutex::new(HashMap::new())),
             pending_ticket_queries: Arc::new(Mutex::new(HashMap::new())),
+            run_tokens: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Mint the token a run connects with. Calls under it act as `agent_id`,
+    /// whatever the agent claims; dropping the guard revokes it.
+    pub fn register_run(&self, agent_id: &str) -> RunToken {
+        let token = uuid::Uuid::new_v4().to_string();
+        let (tx, tool_checks) = mpsc::channel(8);
+        let entry = RunEntry {
+            agent_id: agent_id.to_string(),
+            tool_checks: tx,
+        };
+        self.run_tokens.lock().unwrap().insert(token.clone(), entry);
+        RunToken {
+            tokens: self.run_tokens.clone(),
+            token,
+            tool_checks,
         }
     }
 
@@ -43,13 +115,27 @@ impl McpState {
             pending.remove(agent_id)
         };
         match tx {
-            Some(sender) => sender
+            Some(pending) => pending
+                .tx
                 .send(reply)
                 .map_err(|_| "agent is no longer waiting".to_string()),
             None => Err(format!("no pending question for agent '{}'", agent_id)),
         }
     }
 
+    /// The earlier answer offered with an agent's waiting question, if any.
+    pub async fn suggested_answer(&self, agent_id: &str) -> Option<String> {
+        let pending = self.pending_questions.lock().await;
+        let suggestion = pending.get(agent_id)?.info.suggested_answer.as_ref()?;
+        Some(suggestion.answer.clone())
+    }
+
+    /// All questions agents are waiting on, in the order they should be answered.
+    pub async fn pending_questions(&self) -> Vec<QuestionInfo> {
+        let pending = self.pending_questions.lock().await;
+        inbox::ordered(&pending, inbox::now_secs())
+    }
+
     /// Deliver ticket data to a waiting list_tickets call.
     pub async fn answer_tickets(&self, request_id: &str, data: String) -> Result<(), String> {
         let tx = {
@@ -69,14 +155,31 @@ impl McpState {
 
 type SseSender = mpsc::Sender<Result<Event, Infallible>>;
 
+/// An open SSE connection and the agent its token identified.
+struct Session {
+    tx: SseSender,
+    agent_id: String,
+}
+
 #[derive(Clone)]
 struct ServerState {
-    sessions: Arc<Mutex<HashMap<String, SseSender>>>,
-    pending_questions: Arc<Mutex<HashMap<String, oneshot::Sender<String>>>>,
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+    run_tokens: RunTokens,
+    pending_questions: PendingQuestions,
     pending_ticket_queries: Arc<Mutex<HashMap<String, oneshot::Sender<String>>>>,
+    rate: SharedRateLimiter,
+    /// Set when the mobile relay's reply form is being served.
+    relay: Option<RelayConfig>,
+    reply_links: ReplyLinks,
     app: tauri::AppHandle,
 }
 
+#[derive(Deserialize)]
+struct ConnectQuery {
+    #[serde(default)]
+    token: String,
+}
+
 #[derive(Deserialize)]
 struct SessionQuery {
     #[serde(rename = "sessionId")]
@@ -89,20 +192,31 @@ struct SessionQuery {
 /// Call via tauri::async_runtime::spawn().
 pub async fn serve(
     listener: std::net::TcpListener,
-    pending_questions: Arc<Mutex<HashMap<String, oneshot::Sender<String>>>>,
+    pending_questions: PendingQuestions,
     pending_ticket_queries: Arc<Mutex<HashMap<String, oneshot::Sender<String>>>>,
+    run_tokens: RunTokens,
+    relay: Option<(RelayConfig, ReplyLinks)>,
     app: tauri::AppHandle,
 ) {
+    let (relay, reply_links) = match relay {
+        Some((config, links)) => (Some(config), links),
+        None => (None, relay::new_links()),
+    };
     let state = ServerState {
         sessions: Arc::new(Mutex::new(HashMap::new())),
+        run_tokens,
         pending_questions,
         pending_ticket_queries,
+        rate: Arc::new(std::sync::Mutex::new(RateLimiter::default())),
+        relay,
+        reply_links,
         app,
     };
 
     let router = Router::new()
         .route("/sse", get(sse_handler))
         .route("/message", post(message_handler))
+        .route("/hook", post(hook_handler))
         .with_state(state);
 
     let tokio_listener = tokio::net::TcpListener::from_std(listener)
@@ -115,13 +229,25 @@ pub async fn serve(
 
 // ── SSE handler ───────────────────────────────────────────────────────────────
 
+/// Open a session for the run holding `token`. Unknown tokens are refused.
 async fn sse_handler(
+    Query(ConnectQuery { token }): Query<ConnectQuery>,
     State(state): State<ServerState>,
-) -> Sse<ReceiverStream<Result<Event, Infallible>>> {
+) -> Result<Sse<ReceiverStream<Result<Event, Infallible>>>, StatusCode> {
+    let Some(agent_id) = state.run_tokens.lock().unwrap().get(&token).map(|r| r.agent_id.clone()) else {
+        warn!("[mcp::sse_handler] refused connection with unknown run token");
+        return Err(StatusCode::UNAUTHORIZED);
+    };
     let session_id = uuid::Uuid::new_v4().to_string();
     let (tx, rx) = mpsc::channel::<Result<Event, Infallible>>(32);
 
-    state.sessions.lock().await.insert(session_id.clone(), tx.clone());
+    state.sessions.lock().await.insert(
+        session_id.clone(),
+        Session {
+            tx: tx.clone(),
+            agent_id,
+        },
+    );
 
     // Tell the client where to POST messages
     let _ = tx
@@ -147,8 +273,53 @@ async fn sse_handler(
             .await;
     });
 
-    Sse::new(ReceiverStream::new(rx))
-        .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+    Ok(Sse::new(ReceiverStream::new(rx))
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(15))))
+}
+
+// ── Hook handler ──────────────────────────────────────────────────────────────
+
+/// What Claude's PreToolUse hook passes on stdin, as far as the guard needs it.
+#[derive(Deserialize)]
+struct HookInput {
+    #[serde(default)]
+    tool_name: String,
+    #[serde(default)]
+    tool_input: Value,
+    #[serde(default)]
+    cwd: Option<String>,
+}
+
+/// Refusal for a tool call whose run stopped listening.
+const RUN_ENDED: &str = "This run has ended. Stop here and end your turn.";
+
+/// A run's PreToolUse hook, asking whether a tool call may go ahead. Answers
+/// with an empty body to allow it, or the reason to refuse it. Unknown tokens
+/// get 401, which the hook treats as a refusal.
+async fn hook_handler(
+    Query(ConnectQuery { token }): Query<ConnectQuery>,
+    State(state): State<ServerState>,
+    Json(input): Json<HookInput>,
+) -> Result<String, StatusCode> {
+    let checks = state.run_tokens.lock().unwrap().get(&token).map(|r| r.tool_checks.clone());
+    let Some(checks) = checks else {
+        warn!("[mcp::hook_handler] refused hook call with unknown run token");
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+    let (reply, verdict) = oneshot::channel();
+    let check = ToolCheck {
+        tool_name: input.tool_name,
+        tool_input: input.tool_input,
+        cwd: input.cwd.map(std::path::PathBuf::from),
+        reply,
+    };
+    if checks.send(check).await.is_err() {
+        return Ok(RUN_ENDED.to_string());
+    }
+    match verdict.await {
+        Ok(refusal) => Ok(refusal.unwrap_or_default()),
+        Err(_) => Ok(RUN_ENDED.to_string()),
+    }
 }
 
 // ── Message handler ───────────────────────────────────────────────────────────
@@ -158,13 +329,17 @@ async fn message_handler(
     State(state): State<ServerState>,
     Json(body): Json<Value>,
 ) -> StatusCode {
+    let Some(agent_id) = state.sessions.lock().await.get(&session_id).map(|s| s.agent_id.clone()) else {
+        return StatusCode::NOT_FOUND;
+    };
     // Respond 202 immediately; send the JSON-RPC response over SSE async.
     tokio::spawn(async move {
-        if let Some(resp) = handle_jsonrpc(&state, body).await {
+        if let Some(resp) = handle_jsonrpc(&state, &agent_id, body).await {
             let data = serde_json::to_string(&resp).unwrap_or_default();
             let sessions = state.sessions.lock().await;
-            if let Some(tx) = sessions.get(&session_id) {
-                let _ = tx
+            if let Some(session) = sessions.get(&session_id) {
+                let _ = session
+                    .tx
                     .send(Ok(Event::default().event("message").data(data)))
                     .await;
             }
@@ -174,23 +349,204 @@ async fn message_handler(
     StatusCode::ACCEPTED
 }
 
+// ── Question history ─────────────────────────────────────────────────────────
+
+/// Root of the project that owns `ticket_id`, found from the agent's worktree
+/// path without touching git, so it still resolves once the worktree is gone.
+/// Falls back to the worktree's main checkout. Blocking.
+fn qa_root(worktree: &str, ticket_id: &str) -> String {
+    let worktree = std::path::Path::new(worktree);
+    let root = match crate::project::tickets::locate(worktree, ticket_id) {
+        Ok((root, _)) => root,
+        Err(_) => crate::agent::backend::main_repo_root(worktree),
+    };
+    root.to_string_lossy().into_owned()
+}
+
+/// Keep a question and its outcome on the asking agent's ticket.
+async fn record_qa(
+    app: &tauri::AppHandle,
+    agent_id: &str,
+    question: &str,
+    answer: Option<&str>,
+    answered_by: Option<&str>,
+) {
+    let agents = &app.state::<crate::AppState>().agents;
+    let Some(agent) = crate::agent::state::get_agent(agents, agent_id).await else {
+        return;
+    };
+    let Some(ticket_id) = agent.current_ticket_id else {
+        return;
+    };
+    let repo_root = match agent.worktree_path {
+        Some(worktree) => {
+            let ticket_id = ticket_id.clone();
+            match tokio::task::spawn_blocking(move || qa_root(&worktree, &ticket_id)).await {
+                Ok(root) => Some(root),
+                Err(e) => {
+                    warn!("[mcp::record_qa] project lookup panicked: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    let entry = crate::project::qa::QaEntry {
+        at: chrono::Utc::now().timestamp(),
+        agent_id: agent_id.to_string(),
+        question: question.to_string(),
+        answer: answer.map(String::from),
+        answered_by: answered_by.map(String::from),
+        repo_root,
+    };
+    if let Err(e) = crate::project::qa::record(&ticket_id, &entry) {
+        warn!("[mcp::record_qa] failed to record question on {}: {:#}", ticket_id, e);
+    }
+}
+
+/// An earlier answer on the asking agent's project to a question like this
+/// one, to offer alongside it.
+async fn suggest_answer(
+    app: &tauri::AppHandle,
+    agent_id: &str,
+    question: &str,
+) -> Option<crate::project::qa::SuggestedAnswer> {
+    let agents = &app.state::<crate::AppState>().agents;
+    let agent = crate::agent::state::get_agent(agents, agent_id).await?;
+    let (worktree, ticket_id) = (agent.worktree_path?, agent.current_ticket_id?);
+    let question = question.to_string();
+    let found = tokio::task::spawn_blocking(move || {
+        let repo_root = qa_root(&worktree, &ticket_id);
+        crate::project::qa::suggest(std::path::Path::new(&repo_root), &question)
+    })
+    .await;
+    match found {
+        Ok(Ok(suggestion)) => suggestion,
+        Ok(Err(e)) => {
+            warn!("[mcp::suggest_answer] failed to search question history: {:#}", e);
+            None
+        }
+        Err(e) => {
+            warn!("[mcp::suggest_answer] search panicked: {}", e);
+            None
+        }
+    }
+}
+
+// ── Delegated answering ───────────────────────────────────────────────────────
+
+/// Offer a question to the project's senior agent, if one is configured.
+/// Returns the reply when the senior answered confidently; `None` means the
+/// question should go to the human.
+async fn try_delegate(app: &tauri::AppHandle, agent_id: &str, question: &str) -> Option<String> {
+    use crate::agent::{backend, delegate, state};
+
+    let agents = &app.state::<crate::AppState>().agents;
+    let asker = state::get_agent(agents, agent_id).await?;
+    let worktree = std::path::PathBuf::from(asker.worktree_path.as_ref()?);
+    let config = backend::project_settings_for(&worktree).delegate_questions?;
+    if config.senior_agent_id == agent_id {
+        return None;
+    }
+    let senior = state::get_agent(agents, &config.senior_agent_id).await?;
+
+    let verdict = delegate::consult(&config, &senior, &asker.name, &worktree, question).await;
+    let answer = match verdict {
+        Ok(Some(v)) if v.confident => v.answer,
+        Ok(_) => {
+            info!("[mcp::try_delegate] senior={} not confident — escalating", senior.id);
+            return None;
+        }
+        Err(e) => {
+            warn!("[mcp::try_delegate] senior={} failed: {:#} — escalating", senior.id, e);
+            return None;
+        }
+    };
+
+    let payload = delegate::DelegatedAnswerPayload {
+        agent_id: agent_id.to_string(),
+        senior_agent_id: senior.id.clone(),
+        question: question.to_string(),
+        answer: answer.clone(),
+    };
+    if let Err(e) = delegate::log_answer(&backend::main_repo_root(&worktree), &payload) {
+        warn!("[mcp::try_delegate] failed to log delegated answer: {:#}", e);
+    }
+    record_qa(app, agent_id, question, Some(&answer), Some(&senior.id)).await;
+    let _ = app.emit("agent-question-delegated", &payload);
+    Some(answer)
+}
+
 // ── JSON-RPC dispatcher ───────────────────────────────────────────────────────
 
-async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
-    let id = body.get("id").cloned();
-    let method = body["method"].as_str()?;
+/// The caller's repo default branch, as `origin/<name>`, for prompts that
+/// compare against it. `main` when the agent has no worktree.
+async fn default_base(app: &tauri::AppHandle, agent_id: &str) -> String {
+    let agents = &app.state::<crate::AppState>().agents;
+    let worktree = crate::agent::state::get_agent(agents, agent_id)
+        .await
+        .and_then(|a| a.worktree_path);
+    let Some(worktree) = worktree else {
+        return "main".to_string();
+    };
+    tokio::task::spawn_blocking(move || {
+        crate::git::drift::origin_default(std::path::Path::new(&worktree))
+    })
+    .await
+    .unwrap_or_else(|_| "main".to_string())
+}
 
-    match method {
+/// Answer the requests that need no server state: `initialize` and the
+/// prompt methods. None for every other method. `default_base` fills a
+/// prompt's `base` when the caller leaves it out.
+fn handle_stateless(body: &Value, default_base: &str) -> Option<Value> {
+    let id = body.get("id").cloned();
+    match body["method"].as_str()? {
         "initialize" => Some(json!({
             "jsonrpc": "2.0",
             "id": id,
             "result": {
                 "protocolVersion": "2024-11-05",
-                "capabilities": { "tools": {} },
+                "capabilities": { "tools": {}, "prompts": {} },
                 "serverInfo": { "name": "poietai", "version": "1.0.0" }
             }
         })),
 
+        "prompts/list" => Some(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": prompts::list()
+        })),
+
+        "prompts/get" => {
+            let name = body["params"]["name"].as_str().unwrap_or("");
+            match prompts::get(name, body["params"]["arguments"].as_object(), default_base) {
+                Ok(result) => Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": result
+                })),
+                Err(message) => Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32602, "message": message }
+                })),
+            }
+        }
+
+        _ => None,
+    }
+}
+
+/// Handle one request from `caller`, the agent the session's token belongs to.
+async fn handle_jsonrpc(state: &ServerState, caller: &str, body: Value) -> Option<Value> {
+    let id = body.get("id").cloned();
+    let method = body["method"].as_str()?;
+
+    match method {
+        "initialize" | "prompts/list" => handle_stateless(&body, ""),
+        "prompts/get" => handle_stateless(&body, &default_base(&state.app, caller).await),
+
         // Client signals ready — no response needed
         "notifications/initialized" => None,
 
@@ -212,6 +568,11 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                                 "agent_id": {
                                     "type": "string",
                                     "description": "Your agent ID, exactly as given in your system prompt"
+                                },
+                                "priority": {
+                                    "type": "string",
+                                    "enum": ["low", "normal", "high", "blocking"],
+                                    "description": "How urgently you need the answer. Use 'blocking' only when you cannot make any progress without it."
                                 }
                             },
                             "required": ["question", "agent_id"]
@@ -235,6 +596,20 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                             "required": ["message", "agent_id"]
                         }
                     },
+                    {
+                        "name": "set_status_note",
+                        "description": "Set the one-line note shown next to your name in the team roster, e.g. \"Running the migration tests\". Update it when you move to a new step; an empty note clears it.",
+                        "inputSchema": {
+                            "type": "object",
+                            "properties": {
+                                "note": {
+                                    "type": "string",
+                                    "description": "What you're doing right now, in a few words"
+                                }
+                            },
+                            "required": ["note"]
+                        }
+                    },
                     {
                         "name": "present_choices",
                         "description": "Present the user with 2-4 labeled options. Use when you see multiple valid approaches and want the user to pick.",
@@ -261,6 +636,11 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                                 "agent_id": {
                                     "type": "string",
                                     "description": "Your agent ID"
+                                },
+                                "priority": {
+                                    "type": "string",
+                                    "enum": ["low", "normal", "high", "blocking"],
+                                    "description": "How urgently you need the answer. Use 'blocking' only when you cannot make any progress without it."
                                 }
                             },
                             "required": ["question", "choices", "agent_id"]
@@ -283,6 +663,11 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                                 "agent_id": {
                                     "type": "string",
                                     "description": "Your agent ID"
+                                },
+                                "priority": {
+                                    "type": "string",
+                                    "enum": ["low", "normal", "high", "blocking"],
+                                    "description": "How urgently you need the answer. Use 'blocking' only when you cannot make any progress without it."
                                 }
                             },
                             "required": ["action", "agent_id"]
@@ -473,7 +858,19 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
         "tools/call" => {
             let params = body.get("params")?;
             let tool_name = params["name"].as_str()?;
-            let args = params.get("arguments").cloned().unwrap_or(json!({}));
+            let mut args = params.get("arguments").cloned().unwrap_or(json!({}));
+            // The token says who is calling; a self-reported agent_id is ignored
+            if let Some(fields) = args.as_object_mut() {
+                fields.insert("agent_id".to_string(), json!(caller));
+            }
+
+            if let Some(agent_id) = args.get("agent_id").and_then(|v| v.as_str()) {
+                let checked = state.rate.lock().unwrap().check(agent_id, tool_name, std::time::Instant::now());
+                if let Err(limited) = checked {
+                    warn!("[mcp::handle_jsonrpc] agent={} rate limited on {}", agent_id, tool_name);
+                    return Some(json!({ "jsonrpc": "2.0", "id": id, "error": limited.to_error() }));
+                }
+            }
 
             match tool_name {
                 "ask_human" => {
@@ -484,20 +881,72 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                         .and_then(|v| v.as_str())?
                         .to_string();
 
+                    if let Some(reply) = try_delegate(&state.app, &agent_id, &question).await {
+                        return Some(json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "result": {
+                                "content": [{ "type": "text", "text": reply }],
+                                "isError": false
+                            }
+                        }));
+                    }
+
+                    let priority = Priority::from_hint(args.get("priority").and_then(|v| v.as_str()));
+
+                    let suggested_answer = suggest_answer(&state.app, &agent_id, &question).await;
+
                     let (tx, rx) = oneshot::channel::<String>();
+                    let mut entry = PendingQuestion::new(
+                        &agent_id,
+                        QuestionKind::Question,
+                        question.clone(),
+                        None,
+                        priority,
+                        tx,
+                    );
+                    entry.info.suggested_answer = suggested_answer.clone();
+                    let question_id = entry.info.id.clone();
                     state
                         .pending_questions
                         .lock()
                         .await
-                        .insert(agent_id.clone(), tx);
+                        .insert(agent_id.clone(), entry);
 
                     let _ = state.app.emit(
                         "agent-question",
-                        json!({ "agent_id": agent_id, "question": question }),
+                        json!({
+                            "agent_id": agent_id,
+                            "question_id": question_id,
+                            "question": question,
+                            "priority": priority,
+                            "suggested_answer": suggested_answer,
+                        }),
                     );
+                    if let Some(config) = state.relay.clone() {
+                        let agents = &state.app.state::<crate::AppState>().agents;
+                        let name = crate::agent::state::get_agent(agents, &agent_id)
+                            .await
+                            .map(|a| a.name)
+                            .unwrap_or_else(|| agent_id.clone());
+                        let link = ReplyLink {
+                            agent_id: agent_id.clone(),
+                            question_id: question_id.clone(),
+                        };
+                        relay::push(config, &state.reply_links, &state.pending_questions, &name, link, question.clone())
+                            .await;
+                    }
 
                     // Block until reply arrives or timeout (10 minutes)
-                    match tokio::time::timeout(Duration::from_secs(600), rx).await {
+                    let outcome = tokio::time::timeout(Duration::from_secs(600), rx).await;
+                    if !matches!(outcome, Ok(Ok(_))) {
+                        inbox::remove_if_current(&state.pending_questions, &agent_id, &question_id).await;
+                    }
+                    match outcome {
+                        Ok(Ok(ref reply)) => record_qa(&state.app, &agent_id, &question, Some(reply), Some("human")).await,
+                        _ => record_qa(&state.app, &agent_id, &question, None, None).await,
+                    }
+                    match outcome {
                         Ok(Ok(reply)) => Some(json!({
                             "jsonrpc": "2.0",
                             "id": id,
@@ -545,6 +994,28 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                     }))
                 }
 
+                "set_status_note" => {
+                    let agent_id = args.get("agent_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    let note = args.get("note")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+
+                    // The agent store polls the roster every 2s, which picks the note up
+                    let agents = &state.app.state::<crate::AppState>().agents;
+                    let found = crate::agent::state::set_status_note(agents, agent_id, note).await;
+                    let text = if found { "Status note set." } else { "No agent to set a note on." };
+                    Some(json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "result": {
+                            "content": [{ "type": "text", "text": text }],
+                            "isError": !found
+                        }
+                    }))
+                }
+
                 "present_choices" => {
                     let question = args.get("question")
                         .and_then(|v| v.as_str())
@@ -558,19 +1029,35 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                         .unwrap_or("")
                         .to_string();
 
+                    let priority = Priority::from_hint(args.get("priority").and_then(|v| v.as_str()));
+
                     let (tx, rx) = oneshot::channel::<String>();
+                    let entry = PendingQuestion::new(
+                        &agent_id,
+                        QuestionKind::Choices,
+                        question.clone(),
+                        Some(choices.clone()),
+                        priority,
+                        tx,
+                    );
+                    let question_id = entry.info.id.clone();
                     {
                         let mut pending = state.pending_questions.lock().await;
-                        pending.insert(agent_id.clone(), tx);
+                        pending.insert(agent_id.clone(), entry);
                     }
 
                     let _ = state.app.emit("agent-choices", json!({
                         "agent_id": agent_id,
                         "question": question,
                         "choices": choices,
+                        "priority": priority,
                     }));
 
-                    match tokio::time::timeout(Duration::from_secs(600), rx).await {
+                    let outcome = tokio::time::timeout(Duration::from_secs(600), rx).await;
+                    if !matches!(outcome, Ok(Ok(_))) {
+                        inbox::remove_if_current(&state.pending_questions, &agent_id, &question_id).await;
+                    }
+                    match outcome {
                         Ok(Ok(reply)) => Some(json!({
                             "jsonrpc": "2.0",
                             "id": id,
@@ -612,19 +1099,35 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                         .unwrap_or("")
                         .to_string();
 
+                    let priority = Priority::from_hint(args.get("priority").and_then(|v| v.as_str()));
+
                     let (tx, rx) = oneshot::channel::<String>();
+                    let entry = PendingQuestion::new(
+                        &agent_id,
+                        QuestionKind::Confirm,
+                        action.clone(),
+                        Some(json!(details)),
+                        priority,
+                        tx,
+                    );
+                    let question_id = entry.info.id.clone();
                     {
                         let mut pending = state.pending_questions.lock().await;
-                        pending.insert(agent_id.clone(), tx);
+                        pending.insert(agent_id.clone(), entry);
                     }
 
                     let _ = state.app.emit("agent-confirm", json!({
                         "agent_id": agent_id,
                         "action": action,
                         "details": details,
+                        "priority": priority,
                     }));
 
-                    match tokio::time::timeout(Duration::from_secs(600), rx).await {
+                    let outcome = tokio::time::timeout(Duration::from_secs(600), rx).await;
+                    if !matches!(outcome, Ok(Ok(_))) {
+                        inbox::remove_if_current(&state.pending_questions, &agent_id, &question_id).await;
+                    }
+                    match outcome {
                         Ok(Ok(reply)) => Some(json!({
                             "jsonrpc": "2.0",
                             "id": id,
@@ -782,8 +1285,8 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
                     };
 
                     let result_text = match tx {
-                        Some(sender) => {
-                            let _ = sender.send(answer);
+                        Some(pending) => {
+                            let _ = pending.tx.send(answer);
                             "Answer relayed to your coding session."
                         }
                         None => "No pending question found — it may have timed out or already been answered."
@@ -852,18 +1355,6 @@ async fn handle_jsonrpc(state: &ServerState, body: Value) -> Option<Value> {
 mod tests {
     use serde_json::json;
 
-    fn initialize_response(id: serde_json::Value) -> serde_json::Value {
-        json!({
-            "jsonrpc": "2.0",
-            "id": id,
-            "result": {
-                "protocolVersion": "2024-11-05",
-                "capabilities": { "tools": {} },
-                "serverInfo": { "name": "poietai", "version": "1.0.0" }
-            }
-        })
-    }
-
     fn tools_list_response(id: serde_json::Value) -> serde_json::Value {
         json!({
             "jsonrpc": "2.0",
@@ -1019,6 +1510,14 @@ mod tests {
         })
     }
 
+    fn initialize_response(id: serde_json::Value) -> serde_json::Value {
+        super::handle_stateless(
+            &json!({ "jsonrpc": "2.0", "id": id, "method": "initialize" }),
+            "",
+        )
+        .unwrap()
+    }
+
     #[test]
     fn initialize_has_correct_protocol_version() {
         let resp = initialize_response(json!(1));
@@ -1031,6 +1530,58 @@ mod tests {
         assert!(resp["result"]["capabilities"]["tools"].is_object());
     }
 
+    #[test]
+    fn prompts_list_and_get_answer_through_the_dispatcher() {
+        let resp = initialize_response(json!(1));
+        assert!(resp["result"]["capabilities"]["prompts"].is_object());
+
+        let resp = super::handle_stateless(
+            &json!({ "jsonrpc": "2.0", "id": 2, "method": "prompts/list" }),
+            "",
+        )
+        .unwrap();
+        assert_eq!(resp["id"], 2);
+        let prompts = resp["result"]["prompts"].as_array().unwrap();
+        assert!(prompts.iter().any(|p| p["name"] == "write_pr_description"));
+
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "prompts/get",
+            "params": { "name": "write_pr_description", "arguments": { "ticket_title": "Fix retry backoff" } }
+        });
+        let resp = super::handle_stateless(&body, "main").unwrap();
+        assert_eq!(resp["id"], 3);
+        let text = resp["result"]["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap();
+        assert!(text.contains("Fix retry backoff"));
+    }
+
+    #[test]
+    fn prompts_get_reports_bad_params_as_invalid_params() {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "prompts/get",
+            "params": { "name": "write_pr_description" }
+        });
+        let resp = super::handle_stateless(&body, "main").unwrap();
+        assert_eq!(resp["error"]["code"], -32602);
+        assert_eq!(resp["error"]["message"], "Missing argument: ticket_title");
+        assert!(resp.get("result").is_none());
+
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "prompts/get",
+            "params": { "name": "no_such_prompt" }
+        });
+        let resp = super::handle_stateless(&body, "main").unwrap();
+        assert_eq!(resp["error"]["code"], -32602);
+        assert_eq!(resp["error"]["message"], "Unknown prompt: no_such_prompt");
+    }
+
     #[test]
     fn tools_list_contains_ask_human() {
         let resp = tools_list_response(json!(2));
@@ -1095,6 +1646,17 @@ mod tests {
         assert!(required_strs.contains(&"agent_id"));
     }
 
+    #[test]
+    fn run_tokens_are_revoked_when_dropped() {
+        let state = super::McpState::new(9999);
+        let token = state.register_run("agent-1");
+        let key = token.as_str().to_string();
+        let agent_id = state.run_tokens.lock().unwrap().get(&key).map(|r| r.agent_id.clone());
+        assert_eq!(agent_id.as_deref(), Some("agent-1"));
+        drop(token);
+        assert!(state.run_tokens.lock().unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn mcp_state_answer_returns_err_when_no_pending() {
         let state = super::McpState::new(9999);
@@ -1108,15 +1670,25 @@ mod tests {
         use tokio::sync::oneshot;
         let state = super::McpState::new(9999);
         let (tx, rx) = oneshot::channel::<String>();
+        let entry = super::PendingQuestion::new(
+            "agent-1",
+            super::QuestionKind::Question,
+            "which approach?".to_string(),
+            None,
+            super::Priority::Normal,
+            tx,
+        );
         state
             .pending_questions
             .lock()
             .await
-            .insert("agent-1".to_string(), tx);
+            .insert("agent-1".to_string(), entry);
+        assert_eq!(state.pending_questions().await.len(), 1);
         let result = state.answer("agent-1", "use approach A".to_string()).await;
         assert!(result.is_ok());
         let received = rx.await.unwrap();
         assert_eq!(received, "use approach A");
+        assert!(state.pending_questions().await.is_empty());
     }
 
     #[test]
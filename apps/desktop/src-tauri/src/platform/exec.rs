@@ -0,0 +1,170 @@
+use std::fmt;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Limit for commands that only touch the local disk: status, worktree add.
+pub const LOCAL: Duration = Duration::from_secs(120);
+
+/// Limit for commands that talk to a remote: fetch, ls-remote, gh.
+pub const NETWORK: Duration = Duration::from_secs(5 * 60);
+
+/// Longest a blocking wait sleeps between checks on the child.
+const MAX_POLL: Duration = Duration::from_millis(50);
+
+/// Why an external command produced no output. A command that ran and
+/// exited non-zero isn't an error here; callers read its status.
+#[derive(Debug)]
+pub enum ExecError {
+    /// The program couldn't be started, usually because it isn't installed.
+    Spawn { command: String, error: io::Error },
+    /// Killed after running longer than its limit.
+    TimedOut { command: String, limit: Duration },
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecError::Spawn { command, error } => {
+                write!(f, "failed to run {}: {}", command, error)
+            }
+            ExecError::TimedOut { command, limit } => {
+                write!(f, "{} timed out after {:?}", command, limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExecError::Spawn { error, .. } => Some(error),
+            ExecError::TimedOut { .. } => None,
+        }
+    }
+}
+
+/// The program and its first two args, e.g. "git worktree add", for errors
+/// without the values (paths, tokens) later args may carry.
+fn describe(cmd: &Command) -> String {
+    let program = cmd.get_program();
+    let program = Path::new(program)
+        .file_stem()
+        .unwrap_or(program)
+        .to_string_lossy();
+    std::iter::once(program)
+        .chain(cmd.get_args().take(2).map(|a| a.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run `cmd` and collect its output, killing it once `limit` passes or the
+/// caller stops waiting.
+pub async fn output(
+    cmd: &mut tokio::process::Command,
+    limit: Duration,
+) -> Result<Output, ExecError> {
+    let command = describe(cmd.as_std());
+    let output = cmd.kill_on_drop(true).output();
+    match tokio::time::timeout(limit, output).await {
+        Ok(result) => result.map_err(|error| ExecError::Spawn { command, error }),
+        Err(_) => Err(ExecError::TimedOut { command, limit }),
+    }
+}
+
+/// [`output`] for synchronous code. Blocks the calling thread, so call it
+/// via `spawn_blocking` from async contexts.
+pub fn output_blocking(cmd: &mut Command, limit: Duration) -> Result<Output, ExecError> {
+    let command = describe(cmd);
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| ExecError::Spawn {
+            command: command.clone(),
+            error,
+        })?;
+    // Drain the pipes alongside, or a chatty child fills them and never exits
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + limit;
+    let mut pause = Duration::from_millis(1);
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                return Ok(Output {
+                    status,
+                    stdout: stdout.join().unwrap_or_default(),
+                    stderr: stderr.join().unwrap_or_default(),
+                })
+            }
+            Ok(None) if Instant::now() < deadline => {
+                thread::sleep(pause);
+                pause = (pause * 2).min(MAX_POLL);
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(ExecError::TimedOut { command, limit });
+            }
+            Err(error) => return Err(ExecError::Spawn { command, error }),
+        }
+    }
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_leave_out_values() {
+        let mut cmd = Command::new("/usr/bin/git");
+        cmd.args(["worktree", "add", "/secret/path"]);
+        assert_eq!(describe(&cmd), "git worktree add");
+        assert_eq!(describe(&Command::new("gh")), "gh");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn blocking_run_collects_output_and_kills_on_timeout() {
+        let mut echo = Command::new("sh");
+        echo.args(["-c", "echo out; echo err >&2; exit 3"]);
+        let output = output_blocking(&mut echo, LOCAL).unwrap();
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+        assert_eq!(output.status.code(), Some(3));
+
+        let mut hang = Command::new("sleep");
+        hang.arg("30");
+        let started = Instant::now();
+        let err = output_blocking(&mut hang, Duration::from_millis(100)).unwrap_err();
+        assert!(matches!(err, ExecError::TimedOut { .. }));
+        assert_eq!(err.to_string(), "sleep 30 timed out after 100ms");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn async_run_kills_on_timeout() {
+        let mut hang = tokio::process::Command::new("sleep");
+        hang.arg("30");
+        let err = output(&mut hang, Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ExecError::TimedOut { .. }));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod exec;
+pub mod paths;
+pub mod shell_path;
+pub mod wsl;
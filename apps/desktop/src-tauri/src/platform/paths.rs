@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+// On Windows the repos live inside WSL: the app reaches them through UNC
+// paths like `\\wsl.localhost\Ubuntu\home\user\repo`, while claude, git and
+// the launch script inside WSL see `/home/user/repo`. Everything here is
+// plain string work so it behaves, and is tested, the same on every platform.
+
+/// Server names Windows exposes WSL distros under.
+const WSL_SERVERS: &[&str] = &["wsl.localhost", "wsl$"];
+
+/// A path inside a WSL distro, as reached from Windows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WslPath {
+    /// `wsl.localhost` or `wsl$`, as written.
+    pub server: String,
+    /// e.g. `Ubuntu`.
+    pub distro: String,
+    /// The same path as seen inside the distro, e.g. `/home/user/repo`.
+    pub linux: String,
+}
+
+impl WslPath {
+    /// `\\wsl.localhost\Ubuntu`: the UNC root of the distro's filesystem.
+    pub fn distro_root(&self) -> String {
+        format!("\\\\{}\\{}", self.server, self.distro)
+    }
+}
+
+/// Split a WSL UNC path, with either separator, into its parts.
+pub fn parse_wsl(path: &str) -> Option<WslPath> {
+    let path = path.replace('/', "\\");
+    let rest = path.strip_prefix("\\\\")?;
+    let mut parts = rest.split('\\').filter(|p| !p.is_empty());
+    let server = parts.next()?;
+    if !WSL_SERVERS.iter().any(|s| s.eq_ignore_ascii_case(server)) {
+        return None;
+    }
+    let distro = parts.next()?;
+    let linux = parts.collect::<Vec<_>>().join("/");
+    Some(WslPath {
+        server: server.to_string(),
+        distro: distro.to_string(),
+        linux: format!("/{}", linux),
+    })
+}
+
+/// The path as seen inside WSL: `\\wsl.localhost\Ubuntu\home\u\repo` →
+/// `/home/u/repo`. Anything that isn't a WSL UNC path comes back unchanged.
+pub fn to_linux(path: &Path) -> String {
+    let s = path.to_string_lossy();
+    match parse_wsl(&s) {
+        Some(wsl) => wsl.linux,
+        None => s.into_owned(),
+    }
+}
+
+/// `\\wsl.localhost\Ubuntu` for a path inside a distro, None otherwise.
+pub fn distro_root(path: &Path) -> Option<String> {
+    parse_wsl(&path.to_string_lossy()).map(|wsl| wsl.distro_root())
+}
+
+/// The Windows path of `linux` inside the distro at `distro_root`:
+/// (`\\wsl.localhost\Ubuntu`, `/tmp/x.sh`) → `\\wsl.localhost\Ubuntu\tmp\x.sh`.
+pub fn to_unc(distro_root: &str, linux: &str) -> PathBuf {
+    let rest = linux
+        .split('/')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\\");
+    let root = distro_root.trim_end_matches(['\\', '/']);
+    if rest.is_empty() {
+        return PathBuf::from(root);
+    }
+    PathBuf::from(format!("{}\\{}", root, rest))
+}
+
+/// One spelling for a Windows path: backslashes, no repeated or trailing
+/// separators, the UNC prefix kept. `C:` stays a root as `C:\`.
+pub fn normalize_windows(path: &str) -> String {
+    let path = path.replace('/', "\\");
+    let prefix = if path.starts_with("\\\\") {
+        "\\\\"
+    } else if path.starts_with('\\') {
+        "\\"
+    } else {
+        ""
+    };
+    let joined = path
+        .split('\\')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\\");
+    let normalized = format!("{}{}", prefix, joined);
+    if normalized.ends_with(':') {
+        return format!("{}\\", normalized);
+    }
+    normalized
+}
+
+/// One spelling for a POSIX path: no repeated or trailing slashes.
+pub fn normalize_posix(path: &str) -> String {
+    let joined = path
+        .split('/')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    if path.starts_with('/') {
+        format!("/{}", joined)
+    } else if joined.is_empty() {
+        ".".to_string()
+    } else {
+        joined
+    }
+}
+
+/// A host path in this platform's one spelling, so paths from the folder
+/// picker, git output and stored settings compare equal.
+pub fn normalize(path: &Path) -> PathBuf {
+    let s = path.to_string_lossy();
+    if s.is_empty() {
+        return path.to_path_buf();
+    }
+    if cfg!(target_os = "windows") {
+        PathBuf::from(normalize_windows(&s))
+    } else {
+        PathBuf::from(normalize_posix(&s))
+    }
+}
+
+/// A relative path with `/` separators, as git and the frontend expect.
+pub fn to_slash(relative: &Path) -> String {
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_servers_and_separators() {
+        for path in [
+            r"\\wsl.localhost\Ubuntu\home\keenan\github\repo",
+            r"\\wsl$\Ubuntu\home\keenan\github\repo",
+            "//wsl.localhost/Ubuntu/home/keenan/github/repo",
+            r"\\WSL.LOCALHOST\Ubuntu\home\keenan\github\repo\",
+        ] {
+            let wsl = parse_wsl(path).unwrap();
+            assert_eq!(wsl.distro, "Ubuntu");
+            assert_eq!(wsl.linux, "/home/keenan/github/repo", "{}", path);
+        }
+        assert_eq!(parse_wsl(r"\\wsl$\Ubuntu").unwrap().linux, "/");
+        assert_eq!(parse_wsl(r"\\fileserver\share\repo"), None);
+        assert_eq!(parse_wsl(r"C:\Users\keenan"), None);
+        assert_eq!(parse_wsl("/home/keenan"), None);
+        assert_eq!(parse_wsl(r"\\wsl.localhost"), None);
+    }
+
+    #[test]
+    fn converts_to_linux() {
+        let path = PathBuf::from(r"\\wsl.localhost\Ubuntu\home\keenan\github\repo");
+        assert_eq!(to_linux(&path), "/home/keenan/github/repo");
+        let path = PathBuf::from(r"\\wsl$\Ubuntu\home\keenan\github\repo");
+        assert_eq!(to_linux(&path), "/home/keenan/github/repo");
+        // Not a WSL path: unchanged
+        assert_eq!(to_linux(Path::new("/home/keenan/repo")), "/home/keenan/repo");
+    }
+
+    #[test]
+    fn distro_root_keeps_the_server() {
+        assert_eq!(
+            distro_root(Path::new(r"\\wsl.localhost\Ubuntu\home\keenan\repo")),
+            Some(r"\\wsl.localhost\Ubuntu".to_string())
+        );
+        assert_eq!(
+            distro_root(Path::new("//wsl$/Debian/srv/repo")),
+            Some(r"\\wsl$\Debian".to_string())
+        );
+        assert_eq!(distro_root(Path::new("/home/keenan/repo")), None);
+    }
+
+    #[test]
+    fn round_trips_through_unc() {
+        let unc = to_unc(r"\\wsl.localhost\Ubuntu", "/tmp/poietai-1.sh");
+        assert_eq!(unc, PathBuf::from(r"\\wsl.localhost\Ubuntu\tmp\poietai-1.sh"));
+        assert_eq!(to_linux(&unc), "/tmp/poietai-1.sh");
+        assert_eq!(to_unc(r"\\wsl$\Ubuntu\", "/"), PathBuf::from(r"\\wsl$\Ubuntu"));
+
+        let original = r"\\wsl.localhost\Ubuntu\home\keenan\repo";
+        let wsl = parse_wsl(original).unwrap();
+        assert_eq!(to_unc(&wsl.distro_root(), &wsl.linux), PathBuf::from(original));
+    }
+
+    #[test]
+    fn normalizes_windows_spellings() {
+        assert_eq!(
+            normalize_windows("//wsl.localhost/Ubuntu/home/keenan/repo/"),
+            r"\\wsl.localhost\Ubuntu\home\keenan\repo"
+        );
+        assert_eq!(normalize_windows(r"C:\Users\\keenan\repo\"), r"C:\Users\keenan\repo");
+        assert_eq!(normalize_windows("C:/"), r"C:\");
+        assert_eq!(normalize_windows(r"\temp\x"), r"\temp\x");
+        assert_eq!(normalize_windows(r"src\lib.rs"), r"src\lib.rs");
+    }
+
+    #[test]
+    fn normalizes_posix_spellings() {
+        assert_eq!(normalize_posix("/home//keenan/repo/"), "/home/keenan/repo");
+        assert_eq!(normalize_posix("/"), "/");
+        assert_eq!(normalize_posix("src/lib.rs"), "src/lib.rs");
+        assert_eq!(normalize_posix("./"), ".");
+        assert_eq!(normalize_posix(""), ".");
+    }
+
+    #[test]
+    fn normalize_uses_the_host_spelling() {
+        if cfg!(target_os = "windows") {
+            assert_eq!(
+                normalize(Path::new("//wsl$/Ubuntu/repo/")),
+                PathBuf::from(r"\\wsl$\Ubuntu\repo")
+            );
+        } else {
+            assert_eq!(normalize(Path::new("/repo//a/")), PathBuf::from("/repo/a"));
+        }
+        assert_eq!(normalize(Path::new("")), PathBuf::new());
+    }
+
+    #[test]
+    fn relative_paths_use_forward_slashes() {
+        assert_eq!(to_slash(Path::new(r"src\agent\guard.rs")), "src/agent/guard.rs");
+        assert_eq!(to_slash(Path::new("src/lib.rs")), "src/lib.rs");
+    }
+}
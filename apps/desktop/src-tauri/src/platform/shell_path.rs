@@ -0,0 +1,171 @@
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::project::store;
+
+// Apps started from Finder, the Dock or a desktop launcher inherit a bare
+// PATH (`/usr/bin:/bin:...`), not the one the user's shell profile builds, so
+// `claude`, `gh` and a Homebrew `git` aren't found even though they work in a
+// terminal. At startup we put the login shell's PATH in front of ours, so
+// every git, gh and claude spawn inherits it. Asking the shell can take
+// seconds, so the answer is kept for the next launch.
+
+/// Marks the shell's output, so anything a profile prints is skipped.
+const MARKER: &str = "__POIETAI_PATH__";
+
+/// A profile that takes longer than this is given up on.
+const SHELL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The user's login shell: `$SHELL`, else the platform default.
+fn login_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(target_os = "macos") {
+                "/bin/zsh".to_string()
+            } else {
+                "/bin/bash".to_string()
+            }
+        })
+}
+
+/// The PATH in the shell's output, if it printed one.
+fn parse_output(output: &str) -> Option<String> {
+    let start = output.find(MARKER)? + MARKER.len();
+    let path = output[start..].lines().next()?.trim();
+    Some(path.to_string()).filter(|p| !p.is_empty())
+}
+
+/// Run the login shell interactively, as a terminal would, and read its
+/// PATH. None when the shell fails, hangs or prints nothing usable.
+fn read_login_path() -> Option<String> {
+    let shell = login_shell();
+    // Quoted $PATH is colon-joined in bash, zsh and fish alike
+    let script = format!("printf '\\n%s%s\\n' {} \"$PATH\"", MARKER);
+    let mut child = Command::new(&shell)
+        .args(["-ilc", &script])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| log::warn!("[shell_path] failed to start {}: {}", shell, e))
+        .ok()?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < SHELL_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(25))
+            }
+            _ => {
+                log::warn!(
+                    "[shell_path] {} took over {:?}, keeping the inherited PATH",
+                    shell,
+                    SHELL_TIMEOUT
+                );
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+    let output = child.wait_with_output().ok()?;
+    parse_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The login shell's PATH as of the last launch.
+fn cache_path() -> PathBuf {
+    store::app_dir().join("login-path")
+}
+
+fn read_cache() -> Option<String> {
+    let path = std::fs::read_to_string(cache_path()).ok()?;
+    Some(path.trim().to_string()).filter(|p| !p.is_empty())
+}
+
+fn write_cache(path: &str) {
+    if let Err(e) = store::write_atomic(&cache_path(), path) {
+        log::warn!("[shell_path] failed to save the login PATH: {:#}", e);
+    }
+}
+
+/// `login` entries first, then any of `current` it lacks.
+fn merge(login: &str, current: &str) -> String {
+    let mut entries: Vec<&str> = Vec::new();
+    for entry in login.split(':').chain(current.split(':')) {
+        if !entry.is_empty() && !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+    entries.join(":")
+}
+
+/// Give this process the login shell's PATH. Call first thing in `run()`,
+/// while this is the only thread: changing the environment once others
+/// exist races their reads of it.
+///
+/// Uses the PATH saved by the last launch and refreshes it in the
+/// background, so only a first launch waits on the shell. Does nothing on
+/// Windows, where the tools run inside WSL.
+pub fn apply() {
+    if cfg!(target_os = "windows") {
+        return;
+    }
+    let cached = read_cache();
+    let login = match cached {
+        Some(ref path) => Some(path.clone()),
+        None => read_login_path(),
+    };
+    if let Some(ref login) = login {
+        let current = std::env::var("PATH").unwrap_or_default();
+        let merged = merge(login, &current);
+        if merged != current {
+            std::env::set_var("PATH", merged);
+        }
+    }
+    // Only after the environment is settled may other threads start
+    match cached {
+        Some(_) => {
+            std::thread::spawn(|| {
+                if let Some(login) = read_login_path() {
+                    write_cache(&login);
+                }
+            });
+        }
+        None => {
+            if let Some(ref login) = login {
+                write_cache(login);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_path_past_profile_noise() {
+        let output = format!(
+            "Last login: today\n\n{}/opt/homebrew/bin:/usr/bin\n",
+            MARKER
+        );
+        assert_eq!(
+            parse_output(&output).as_deref(),
+            Some("/opt/homebrew/bin:/usr/bin")
+        );
+        assert_eq!(parse_output(&format!("\n{}\n", MARKER)), None);
+        assert_eq!(parse_output("no marker here"), None);
+    }
+
+    #[test]
+    fn login_entries_come_first_without_duplicates() {
+        assert_eq!(
+            merge("/opt/homebrew/bin:/usr/bin", "/usr/bin:/bin:"),
+            "/opt/homebrew/bin:/usr/bin:/bin"
+        );
+        assert_eq!(merge("", "/usr/bin"), "/usr/bin");
+    }
+}
@@ -0,0 +1,460 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::{exec, paths};
+
+/// CLIs an agent run needs inside the distro.
+const REQUIRED_TOOLS: &[&str] = &["claude", "gh", "git"];
+
+/// Marks the probe's output, so anything a profile prints is skipped.
+const PROBE_MARKER: &str = "__POIETAI_PROBE__";
+
+/// Shells asked where claude is, each loading its own profile. zsh needs -i
+/// for `.zshrc`, where most installers put their PATH lines.
+const PROBE_SHELLS: &[(&str, &[&str])] = &[
+    ("/bin/bash", &["-lc"]),
+    ("zsh", &["-lic"]),
+    ("fish", &["-lc"]),
+    ("/bin/sh", &["-lc"]),
+];
+
+/// Where installers put claude, tried when no shell finds it.
+const KNOWN_LOCATIONS: &[&str] = &[
+    "$HOME/.local/bin/claude",
+    "$HOME/.claude/local/claude",
+    "$HOME/.npm-global/bin/claude",
+    "$HOME/.volta/bin/claude",
+    "$HOME/.bun/bin/claude",
+    "$HOME/.nvm/versions/node/*/bin/claude",
+    "/usr/local/bin/claude",
+    "/usr/bin/claude",
+];
+
+/// PATH for a claude found outside any profile: its own directory (where
+/// nvm also keeps node) ahead of the system defaults.
+const BASE_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// A CLI as found, or not, inside a distro.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct WslToolStatus {
+    pub tool: String,
+    /// First line of `<tool> --version`, when it ran.
+    pub version: Option<String>,
+    /// Why it didn't, when it didn't.
+    pub error: Option<String>,
+}
+
+/// Whether a distro can host agent runs.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct WslHealth {
+    pub distro: String,
+    pub tools: Vec<WslToolStatus>,
+}
+
+impl WslHealth {
+    pub fn healthy(&self) -> bool {
+        self.tools.iter().all(|t| t.version.is_some())
+    }
+
+    /// One line per missing tool, e.g. "gh: command not found".
+    pub fn problems(&self) -> Vec<String> {
+        self.tools
+            .iter()
+            .filter(|t| t.version.is_none())
+            .map(|t| format!("{}: {}", t.tool, t.error.as_deref().unwrap_or("not found")))
+            .collect()
+    }
+}
+
+/// How to start claude inside a distro without relying on a login shell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaudeInstall {
+    /// Absolute path of the claude binary.
+    pub binary: String,
+    /// PATH to run it, and the gh/git it calls, under.
+    pub path: String,
+}
+
+// Distros that passed a health check this session, so runs don't re-probe.
+static READY: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+// Where claude was found in each distro, keyed by lowercased name.
+static INSTALLS: Mutex<Option<HashMap<String, ClaudeInstall>>> = Mutex::new(None);
+
+/// `wsl -l -q` output as distro names. wsl.exe writes UTF-16LE unless
+/// `WSL_UTF8` is set, so both encodings are accepted.
+pub fn parse_list(output: &[u8]) -> Vec<String> {
+    let utf16 = output.len() >= 2 && (output.starts_with(&[0xFF, 0xFE]) || output[1] == 0);
+    let text = if utf16 {
+        let units: Vec<u16> = output
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(output).into_owned()
+    };
+    text.lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}' || c == '\0'))
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Installed WSL distros, default first. Blocking.
+pub fn list_distros() -> Result<Vec<String>> {
+    if !cfg!(target_os = "windows") {
+        anyhow::bail!("WSL distros only apply on Windows");
+    }
+    let output = exec::output_blocking(Command::new("wsl").args(["-l", "-q"]), exec::LOCAL)
+        .context("failed to run wsl — is WSL installed?")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "wsl -l -q failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_list(&output.stdout))
+}
+
+/// The distro a run in `working_dir` uses: the project's choice when set,
+/// else the one the worktree's UNC path is in. The worktree must be inside a
+/// distro either way, and a choice that disagrees with where it lives is an
+/// error — the agent couldn't see its files.
+pub fn choose(setting: Option<&str>, working_dir: &Path) -> Result<String> {
+    let from_path = paths::parse_wsl(&working_dir.to_string_lossy()).map(|wsl| wsl.distro);
+    match (setting.filter(|s| !s.trim().is_empty()), from_path) {
+        (Some(chosen), Some(found)) if !chosen.eq_ignore_ascii_case(&found) => anyhow::bail!(
+            "the project runs agents in WSL distro '{}', but {} is in '{}' — \
+             move the repo or change the project's distro",
+            chosen,
+            working_dir.display(),
+            found
+        ),
+        (Some(chosen), Some(_)) => Ok(chosen.to_string()),
+        (Some(chosen), None) => anyhow::bail!(
+            "the project runs agents in WSL distro '{}', but {} isn't inside it — \
+             open the repo through \\\\wsl.localhost\\{}\\",
+            chosen,
+            working_dir.display(),
+            chosen
+        ),
+        (None, Some(found)) => Ok(found),
+        (None, None) => anyhow::bail!(
+            "{} isn't inside a WSL distro — open the repo through \\\\wsl.localhost\\<distro>\\",
+            working_dir.display()
+        ),
+    }
+}
+
+/// The probe script for `shell`: prints the marker, claude's path and PATH.
+fn probe_script(shell: &str) -> String {
+    if shell == "fish" {
+        // fish has no $(...) before 3.4; a quoted path variable joins with ':'
+        return format!(
+            "printf '\\n%s%s\\n%s\\n' {} (command -v claude) \"$PATH\"",
+            PROBE_MARKER
+        );
+    }
+    format!(
+        "printf '\\n%s%s\\n%s\\n' {} \"$(command -v claude)\" \"$PATH\"",
+        PROBE_MARKER
+    )
+}
+
+/// The install a probe printed, if it found an absolute claude path.
+pub fn parse_probe(output: &str) -> Option<ClaudeInstall> {
+    let mut lines = output.lines().skip_while(|l| !l.starts_with(PROBE_MARKER));
+    let binary = lines.next()?.trim_start_matches(PROBE_MARKER).trim();
+    let path = lines.next().map(str::trim).unwrap_or_default();
+    if !binary.starts_with('/') {
+        // Empty, or an alias/function name rather than a file we can exec
+        return None;
+    }
+    let path = if path.is_empty() { BASE_PATH } else { path };
+    Some(ClaudeInstall {
+        binary: binary.to_string(),
+        path: path.to_string(),
+    })
+}
+
+/// `wsl -d <distro> --exec <program> <args>`, stdout on success.
+fn wsl_exec(distro: &str, program: &str, args: &[&str]) -> Option<String> {
+    let output = exec::output_blocking(
+        Command::new("wsl")
+            .args(["-d", distro, "--exec", program])
+            .args(args),
+        exec::LOCAL,
+    )
+    .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Look for claude in `distro`: first through each shell's profile, then at
+/// the usual install locations. Blocking.
+fn probe(distro: &str) -> Result<ClaudeInstall> {
+    for (shell, flags) in PROBE_SHELLS {
+        let script = probe_script(shell);
+        let mut args = flags.to_vec();
+        args.push(&script);
+        if let Some(install) = wsl_exec(distro, shell, &args)
+            .as_deref()
+            .and_then(parse_probe)
+        {
+            log::info!(
+                "[wsl::probe] {} found claude at {} via {}",
+                distro,
+                install.binary,
+                shell
+            );
+            return Ok(install);
+        }
+    }
+    let search = format!(
+        "for f in {}; do [ -x \"$f\" ] && {{ printf '\\n%s%s\\n\\n' {} \"$f\"; exit 0; }}; done; exit 1",
+        KNOWN_LOCATIONS.join(" "),
+        PROBE_MARKER
+    );
+    let found = wsl_exec(distro, "/bin/sh", &["-c", &search])
+        .as_deref()
+        .and_then(parse_probe);
+    let Some(mut install) = found else {
+        anyhow::bail!(
+            "claude isn't installed in WSL distro '{}' — no shell profile puts it on PATH \
+             and it isn't in any usual install location",
+            distro
+        );
+    };
+    if let Some((dir, _)) = install.binary.rsplit_once('/') {
+        install.path = format!("{}:{}", dir, BASE_PATH);
+    }
+    log::info!("[wsl::probe] {} found claude at {}", distro, install.binary);
+    Ok(install)
+}
+
+/// How to start claude in `distro`, probed once per session. Blocking.
+pub fn resolve_claude(distro: &str) -> Result<ClaudeInstall> {
+    let key = distro.to_lowercase();
+    let cached = INSTALLS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|installs| installs.get(&key).cloned());
+    if let Some(install) = cached {
+        return Ok(install);
+    }
+    let install = probe(distro)?;
+    INSTALLS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(key, install.clone());
+    Ok(install)
+}
+
+/// Run `<program> --version` under `path`, as a run would.
+fn tool_status(distro: &str, tool: &str, program: &str, path: &str) -> WslToolStatus {
+    let path_var = format!("PATH={}", path);
+    let output = exec::output_blocking(
+        Command::new("wsl").args([
+            "-d",
+            distro,
+            "--exec",
+            "/usr/bin/env",
+            &path_var,
+            program,
+            "--version",
+        ]),
+        exec::LOCAL,
+    );
+    let (version, error) = match output {
+        Ok(o) if o.status.success() => (
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .next()
+                .map(|l| l.trim().to_string()),
+            None,
+        ),
+        Ok(o) => {
+            let stderr = String::from_utf8_lossy(&o.stderr).trim().to_string();
+            (
+                None,
+                Some(if stderr.is_empty() {
+                    "not found".to_string()
+                } else {
+                    stderr
+                }),
+            )
+        }
+        Err(e) => (None, Some(e.to_string())),
+    };
+    WslToolStatus {
+        tool: tool.to_string(),
+        version,
+        error,
+    }
+}
+
+/// Check that `distro` is installed and has claude, gh and git. Blocking.
+pub fn check(distro: &str) -> Result<WslHealth> {
+    let installed = list_distros()?;
+    if !installed.iter().any(|d| d.eq_ignore_ascii_case(distro)) {
+        anyhow::bail!(
+            "no WSL distro named '{}' (installed: {})",
+            distro,
+            if installed.is_empty() {
+                "none".to_string()
+            } else {
+                installed.join(", ")
+            }
+        );
+    }
+    // A fresh probe, so a check after installing claude sees it
+    INSTALLS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .remove(&distro.to_lowercase());
+    let install = resolve_claude(distro);
+    let path = install.as_ref().map_or(BASE_PATH, |i| i.path.as_str());
+    let tools = REQUIRED_TOOLS
+        .iter()
+        .map(|&tool| match (tool, &install) {
+            ("claude", Ok(install)) => tool_status(distro, tool, &install.binary, path),
+            ("claude", Err(e)) => WslToolStatus {
+                tool: tool.to_string(),
+                version: None,
+                error: Some(format!("{:#}", e)),
+            },
+            _ => tool_status(distro, tool, tool, path),
+        })
+        .collect();
+    let health = WslHealth {
+        distro: distro.to_string(),
+        tools,
+    };
+    if health.healthy() {
+        READY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(HashSet::new)
+            .insert(distro.to_lowercase());
+    }
+    Ok(health)
+}
+
+/// Fail with what's missing unless `distro` can host a run. Remembers a
+/// healthy distro for the rest of the session. Blocking.
+pub fn ensure_ready(distro: &str) -> Result<()> {
+    let known = READY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .is_some_and(|ready| ready.contains(&distro.to_lowercase()));
+    if known {
+        return Ok(());
+    }
+    let health = check(distro)?;
+    if !health.healthy() {
+        anyhow::bail!(
+            "WSL distro '{}' can't run agents yet:\n{}",
+            distro,
+            health.problems().join("\n")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn parses_distro_list_in_either_encoding() {
+        let expected = vec!["Ubuntu".to_string(), "Debian".to_string()];
+        assert_eq!(parse_list(&utf16("Ubuntu\r\nDebian\r\n\r\n")), expected);
+        assert_eq!(parse_list(b"Ubuntu\nDebian\n"), expected);
+        assert!(parse_list(b"").is_empty());
+    }
+
+    #[test]
+    fn chosen_distro_must_hold_the_worktree() {
+        let worktree = Path::new(r"\\wsl.localhost\Ubuntu\home\u\repo\.worktrees\t-1");
+        assert_eq!(choose(None, worktree).unwrap(), "Ubuntu");
+        assert_eq!(choose(Some("ubuntu"), worktree).unwrap(), "ubuntu");
+        assert_eq!(choose(Some(" "), worktree).unwrap(), "Ubuntu");
+        let err = choose(Some("Debian"), worktree).unwrap_err().to_string();
+        assert!(err.contains("'Debian'") && err.contains("'Ubuntu'"));
+        // Runs reach the worktree through the distro's UNC root, so a chosen
+        // distro doesn't help a repo on a Windows drive
+        let err = choose(Some("Debian"), Path::new(r"C:\repo"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(r"\\wsl.localhost\Debian\"));
+        assert!(choose(None, Path::new(r"C:\repo")).is_err());
+    }
+
+    #[test]
+    fn probe_output_skips_profile_noise() {
+        let output = format!("Welcome!\n\n{m}/home/u/.nvm/versions/node/v20/bin/claude\n/home/u/.nvm/versions/node/v20/bin:/usr/bin\n", m = PROBE_MARKER);
+        let install = parse_probe(&output).unwrap();
+        assert_eq!(install.binary, "/home/u/.nvm/versions/node/v20/bin/claude");
+        assert_eq!(install.path, "/home/u/.nvm/versions/node/v20/bin:/usr/bin");
+        // Not found, or only an alias
+        assert_eq!(
+            parse_probe(&format!("\n{}\n/usr/bin\n", PROBE_MARKER)),
+            None
+        );
+        assert_eq!(
+            parse_probe(&format!("\n{}claude\n/usr/bin\n", PROBE_MARKER)),
+            None
+        );
+        assert_eq!(parse_probe("no marker"), None);
+        // Known-location hits carry no PATH of their own
+        assert_eq!(
+            parse_probe(&format!("\n{}/usr/local/bin/claude\n\n", PROBE_MARKER))
+                .unwrap()
+                .path,
+            BASE_PATH
+        );
+    }
+
+    #[test]
+    fn problems_name_the_missing_tools() {
+        let health = WslHealth {
+            distro: "Ubuntu".to_string(),
+            tools: vec![
+                WslToolStatus {
+                    tool: "claude".to_string(),
+                    version: Some("1.0.0".to_string()),
+                    error: None,
+                },
+                WslToolStatus {
+                    tool: "gh".to_string(),
+                    version: None,
+                    error: Some("gh: command not found".to_string()),
+                },
+            ],
+        };
+        assert!(!health.healthy());
+        assert_eq!(
+            health.problems(),
+            vec!["gh: gh: command not found".to_string()]
+        );
+    }
+}
@@ -0,0 +1,676 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::deps::tool_output;
+use super::store;
+use super::templates::TicketDraft;
+
+/// A published vulnerability affecting an installed dependency.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct Advisory {
+    /// RUSTSEC or GHSA id.
+    pub id: String,
+    /// "cargo" or "npm".
+    pub ecosystem: String,
+    pub package: String,
+    /// Installed versions, comma-separated when the tree has several copies.
+    pub installed: String,
+    pub title: String,
+    /// npm's "low" to "critical"; None when the feed doesn't rate it.
+    pub severity: Option<String>,
+    pub url: Option<String>,
+    /// CVE ids for the same vulnerability.
+    pub cves: Vec<String>,
+    /// Version ranges with the fix, e.g. ">=0.25.1".
+    pub patched: Vec<String>,
+}
+
+/// Untracked advisories on one package, with a ticket to fix them.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct AdvisoryFinding {
+    pub advisories: Vec<Advisory>,
+    pub ticket: TicketDraft,
+}
+
+/// An advisory whose ticket is on the board, and how far its fix has got.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct TrackedAdvisory {
+    pub advisory: Advisory,
+    pub ticket_id: String,
+    pub pr_number: Option<u32>,
+    /// Set once an audit no longer reports it.
+    pub resolved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct AuditReport {
+    /// New advisories, grouped by package, for the frontend to add as tickets
+    /// and then [`track`].
+    pub findings: Vec<AdvisoryFinding>,
+    pub tracked: Vec<TrackedAdvisory>,
+}
+
+#[derive(Deserialize)]
+struct CargoAudit {
+    vulnerabilities: CargoVulnerabilities,
+}
+
+#[derive(Deserialize)]
+struct CargoVulnerabilities {
+    list: Vec<CargoVulnerability>,
+}
+
+#[derive(Deserialize)]
+struct CargoVulnerability {
+    advisory: CargoAdvisory,
+    versions: CargoVersions,
+    package: CargoPackage,
+}
+
+#[derive(Deserialize)]
+struct CargoAdvisory {
+    id: String,
+    title: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CargoVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CargoPackage {
+    name: String,
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct NpmAudit {
+    #[serde(default)]
+    vulnerabilities: BTreeMap<String, NpmVulnerability>,
+}
+
+#[derive(Deserialize)]
+struct NpmVulnerability {
+    /// Advisory objects, or names of the packages a transitive hit comes through.
+    via: Vec<serde_json::Value>,
+    range: String,
+    /// Where the package sits in the install tree, e.g. "node_modules/lodash".
+    #[serde(default)]
+    nodes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct NpmAdvisory {
+    title: String,
+    url: String,
+    severity: String,
+    range: String,
+}
+
+/// `package-lock.json`, for the versions npm's audit doesn't report.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NpmLock {
+    /// Lockfile v2+: install path → package.
+    packages: BTreeMap<String, NpmLocked>,
+    /// Lockfile v1: name → package.
+    dependencies: BTreeMap<String, NpmLocked>,
+}
+
+#[derive(Deserialize)]
+struct NpmLocked {
+    #[serde(default)]
+    version: String,
+}
+
+impl NpmLock {
+    /// Installed versions at `nodes`, falling back to the top-level copy of
+    /// `package`. Empty when the lockfile doesn't list it.
+    fn versions(&self, package: &str, nodes: &[String]) -> Vec<String> {
+        let top = format!("node_modules/{}", package);
+        let mut versions: Vec<String> = nodes
+            .iter()
+            .chain(nodes.is_empty().then_some(&top))
+            .filter_map(|node| self.packages.get(node))
+            .map(|p| p.version.clone())
+            .filter(|v| !v.is_empty())
+            .collect();
+        if versions.is_empty() {
+            versions.extend(self.dependencies.get(package).map(|p| p.version.clone()));
+        }
+        versions.sort();
+        versions.dedup();
+        versions
+    }
+}
+
+/// One advisory in the npm v6 report shape that `pnpm audit` and yarn 1's
+/// `auditAdvisory` lines still use.
+#[derive(Deserialize)]
+struct LegacyAdvisory {
+    module_name: String,
+    title: String,
+    url: String,
+    severity: String,
+    #[serde(default)]
+    patched_versions: String,
+    #[serde(default)]
+    cves: Vec<String>,
+    #[serde(default)]
+    findings: Vec<LegacyFinding>,
+}
+
+#[derive(Deserialize)]
+struct LegacyFinding {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct PnpmAudit {
+    #[serde(default)]
+    advisories: BTreeMap<String, LegacyAdvisory>,
+}
+
+#[derive(Deserialize)]
+struct YarnLine {
+    #[serde(rename = "type")]
+    kind: String,
+    data: serde_json::Value,
+}
+
+/// One `yarn npm audit --json` line (yarn 2+).
+#[derive(Deserialize)]
+struct BerryLine {
+    value: String,
+    children: BerryAdvisory,
+}
+
+#[derive(Deserialize)]
+struct BerryAdvisory {
+    #[serde(rename = "Issue")]
+    issue: String,
+    #[serde(rename = "URL")]
+    url: String,
+    #[serde(rename = "Severity")]
+    severity: String,
+    #[serde(rename = "Vulnerable Versions")]
+    vulnerable: String,
+    #[serde(rename = "Tree Versions", default)]
+    tree_versions: Vec<String>,
+}
+
+/// The GHSA id at the end of an advisory URL.
+fn id_from_url(url: &str) -> String {
+    url.rsplit('/').next().unwrap_or_default().to_string()
+}
+
+fn from_legacy(advisory: LegacyAdvisory) -> Advisory {
+    let mut installed: Vec<String> = advisory.findings.into_iter().map(|f| f.version).collect();
+    installed.sort();
+    installed.dedup();
+    Advisory {
+        id: id_from_url(&advisory.url),
+        ecosystem: "npm".to_string(),
+        package: advisory.module_name,
+        installed: installed.join(", "),
+        title: advisory.title,
+        severity: Some(advisory.severity),
+        url: Some(advisory.url),
+        cves: advisory.cves,
+        // "<0.0.0" is how the feed says there's no fix yet
+        patched: Some(advisory.patched_versions)
+            .filter(|v| !v.is_empty() && v != "<0.0.0")
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn parse_cargo(stdout: &str) -> Result<Vec<Advisory>> {
+    let report: CargoAudit =
+        serde_json::from_str(stdout).context("failed to parse cargo audit output")?;
+    Ok(report
+        .vulnerabilities
+        .list
+        .into_iter()
+        .map(|v| Advisory {
+            id: v.advisory.id,
+            ecosystem: "cargo".to_string(),
+            package: v.package.name,
+            installed: v.package.version,
+            title: v.advisory.title,
+            severity: None,
+            url: v.advisory.url,
+            cves: v
+                .advisory
+                .aliases
+                .into_iter()
+                .filter(|a| a.starts_with("CVE-"))
+                .collect(),
+            patched: v.versions.patched,
+        })
+        .collect())
+}
+
+/// Parse `npm audit --json` (v7+), taking installed versions from `lock`.
+/// Only packages with their own advisory are kept; ones listed because a
+/// dependency is vulnerable are fixed with it.
+fn parse_npm(stdout: &str, lock: &NpmLock) -> Result<Vec<Advisory>> {
+    let report: NpmAudit =
+        serde_json::from_str(stdout).context("failed to parse npm audit output")?;
+    let mut advisories = Vec::new();
+    for (package, vulnerability) in report.vulnerabilities {
+        let versions = lock.versions(&package, &vulnerability.nodes);
+        // Without the lockfile entry, the affected range is the best we have
+        let installed = if versions.is_empty() {
+            vulnerability.range.clone()
+        } else {
+            versions.join(", ")
+        };
+        for via in vulnerability.via {
+            let Ok(advisory) = serde_json::from_value::<NpmAdvisory>(via) else {
+                continue;
+            };
+            advisories.push(Advisory {
+                id: id_from_url(&advisory.url),
+                ecosystem: "npm".to_string(),
+                package: package.clone(),
+                installed: installed.clone(),
+                title: advisory.title,
+                severity: Some(advisory.severity),
+                url: Some(advisory.url),
+                cves: vec![],
+                patched: vec![format!("outside {}", advisory.range)],
+            });
+        }
+    }
+    Ok(advisories)
+}
+
+fn parse_pnpm(stdout: &str) -> Result<Vec<Advisory>> {
+    let report: PnpmAudit =
+        serde_json::from_str(stdout).context("failed to parse pnpm audit output")?;
+    Ok(report.advisories.into_values().map(from_legacy).collect())
+}
+
+/// Parse yarn 1's `yarn audit --json`: one JSON object per line, advisories
+/// among progress and summary lines.
+fn parse_yarn(stdout: &str) -> Result<Vec<Advisory>> {
+    let mut advisories = Vec::new();
+    for line in stdout.lines().filter(|l| !l.trim().is_empty()) {
+        let line: YarnLine =
+            serde_json::from_str(line).context("failed to parse yarn audit output")?;
+        if line.kind != "auditAdvisory" {
+            continue;
+        }
+        let advisory: LegacyAdvisory = serde_json::from_value(line.data["advisory"].clone())
+            .context("failed to parse yarn audit advisory")?;
+        advisories.push(from_legacy(advisory));
+    }
+    Ok(advisories)
+}
+
+/// Parse yarn 2+'s `yarn npm audit --json`: one package per line.
+fn parse_berry(stdout: &str) -> Result<Vec<Advisory>> {
+    stdout
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|line| {
+            let line: BerryLine =
+                serde_json::from_str(line).context("failed to parse yarn npm audit output")?;
+            let advisory = line.children;
+            Ok(Advisory {
+                id: id_from_url(&advisory.url),
+                ecosystem: "npm".to_string(),
+                package: line.value,
+                installed: advisory.tree_versions.join(", "),
+                title: advisory.issue,
+                severity: Some(advisory.severity),
+                url: Some(advisory.url),
+                cves: vec![],
+                patched: vec![format!("outside {}", advisory.vulnerable)],
+            })
+        })
+        .collect()
+}
+
+/// Audit the JavaScript dependencies with whichever package manager's
+/// lockfile the repo has. None when it has none.
+fn audit_npm(repo_root: &Path) -> Result<Option<Vec<Advisory>>> {
+    if repo_root.join("pnpm-lock.yaml").is_file() {
+        return Ok(Some(parse_pnpm(&tool_output(
+            repo_root,
+            "pnpm",
+            &["audit", "--json"],
+        )?)?));
+    }
+    let yarn_lock = repo_root.join("yarn.lock");
+    if yarn_lock.is_file() {
+        // yarn 2+ lockfiles open with a __metadata block and drop `yarn audit`
+        let berry = std::fs::read_to_string(&yarn_lock)
+            .with_context(|| format!("failed to read {}", yarn_lock.display()))?
+            .contains("__metadata:");
+        let advisories = if berry {
+            parse_berry(&tool_output(
+                repo_root,
+                "yarn",
+                &["npm", "audit", "--json", "--recursive"],
+            )?)?
+        } else {
+            parse_yarn(&tool_output(repo_root, "yarn", &["audit", "--json"])?)?
+        };
+        return Ok(Some(advisories));
+    }
+    let lock_path = repo_root.join("package-lock.json");
+    if lock_path.is_file() {
+        let lock: NpmLock = store::read_json(&lock_path)?;
+        return Ok(Some(parse_npm(
+            &tool_output(repo_root, "npm", &["audit", "--json"])?,
+            &lock,
+        )?));
+    }
+    Ok(None)
+}
+
+fn describe(advisory: &Advisory) -> String {
+    let mut line = format!(
+        "{} in `{}` {}: {}",
+        advisory.id, advisory.package, advisory.installed, advisory.title
+    );
+    if !advisory.cves.is_empty() {
+        line.push_str(&format!(" ({})", advisory.cves.join(", ")));
+    }
+    if let Some(ref severity) = advisory.severity {
+        line.push_str(&format!(" [{}]", severity));
+    }
+    line
+}
+
+fn draft(package: &str, advisories: &[Advisory]) -> TicketDraft {
+    let details = advisories
+        .iter()
+        .map(|a| {
+            let mut text = format!("- {}", describe(a));
+            if !a.patched.is_empty() {
+                text.push_str(&format!("\n  Patched: {}", a.patched.join(", ")));
+            }
+            if let Some(ref url) = a.url {
+                text.push_str(&format!("\n  {}", url));
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let ids = advisories
+        .iter()
+        .map(|a| a.id.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let critical = advisories
+        .iter()
+        .any(|a| matches!(a.severity.as_deref(), Some("high" | "critical")));
+    TicketDraft {
+        title: format!("Fix {} in {}", ids, package),
+        description: format!(
+            "## Advisories\n\n{}\n\n## Remediation\n\nUpgrade `{}` to a patched version, \
+             or replace it if there is none. Check whether our code reaches the vulnerable \
+             path and say so in the PR.",
+            details, package
+        ),
+        complexity: if critical { 5 } else { 3 },
+        acceptance_criteria: vec![
+            format!("The audit no longer reports {}", ids),
+            "No advisories are ignored or suppressed to get there".to_string(),
+            "Existing tests pass".to_string(),
+        ],
+        tags: vec!["security".to_string()],
+    }
+}
+
+fn tracking_path(repo_root: &Path) -> PathBuf {
+    store::project_dir(repo_root).join("advisories.json")
+}
+
+pub fn load_tracked(repo_root: &Path) -> Result<Vec<TrackedAdvisory>> {
+    store::read_json(&tracking_path(repo_root))
+}
+
+/// Mark tracked advisories the audit no longer reports as resolved, and group
+/// the untracked ones by package into findings. Only advisories in an
+/// `audited` ecosystem are settled; the others keep their status.
+fn reconcile(
+    tracked: &mut [TrackedAdvisory],
+    found: Vec<Advisory>,
+    audited: &[&str],
+) -> Vec<AdvisoryFinding> {
+    for entry in tracked
+        .iter_mut()
+        .filter(|t| audited.contains(&t.advisory.ecosystem.as_str()))
+    {
+        entry.resolved = !found.iter().any(|a| a.id == entry.advisory.id);
+    }
+    let mut by_package: BTreeMap<(String, String), Vec<Advisory>> = BTreeMap::new();
+    for advisory in found {
+        if tracked.iter().any(|t| t.advisory.id == advisory.id) {
+            continue;
+        }
+        by_package
+            .entry((advisory.ecosystem.clone(), advisory.package.clone()))
+            .or_default()
+            .push(advisory);
+    }
+    by_package
+        .into_iter()
+        .map(|((_, package), advisories)| AdvisoryFinding {
+            ticket: draft(&package, &advisories),
+            advisories,
+        })
+        .collect()
+}
+
+/// Run `cargo audit` and the npm, pnpm or yarn audit in the repo and update
+/// the tracked advisories. Blocking; the tools fetch their advisory databases.
+pub fn audit(repo_root: &Path) -> Result<AuditReport> {
+    let mut found = Vec::new();
+    let mut audited = Vec::new();
+    if repo_root.join("Cargo.lock").is_file() {
+        found.extend(parse_cargo(&tool_output(
+            repo_root,
+            "cargo",
+            &["audit", "--json"],
+        )?)?);
+        audited.push("cargo");
+    }
+    if let Some(advisories) = audit_npm(repo_root)? {
+        found.extend(advisories);
+        audited.push("npm");
+    }
+    let mut tracked = load_tracked(repo_root)?;
+    let findings = reconcile(&mut tracked, found, &audited);
+    store::write_json(&tracking_path(repo_root), &tracked)?;
+    Ok(AuditReport { findings, tracked })
+}
+
+/// Record that `ticket_id` remediates `advisories`.
+pub fn track(repo_root: &Path, advisories: Vec<Advisory>, ticket_id: &str) -> Result<()> {
+    let mut tracked = load_tracked(repo_root)?;
+    tracked.retain(|t| !advisories.iter().any(|a| a.id == t.advisory.id));
+    tracked.extend(advisories.into_iter().map(|advisory| TrackedAdvisory {
+        advisory,
+        ticket_id: ticket_id.to_string(),
+        pr_number: None,
+        resolved: false,
+    }));
+    store::write_json(&tracking_path(repo_root), &tracked)
+}
+
+/// Attach a ticket's PR to the advisories it remediates. Returns how many.
+pub fn record_pr(repo_root: &Path, ticket_id: &str, pr_number: u32) -> Result<usize> {
+    let mut tracked = load_tracked(repo_root)?;
+    let mut count = 0;
+    for entry in tracked.iter_mut().filter(|t| t.ticket_id == ticket_id) {
+        entry.pr_number = Some(pr_number);
+        count += 1;
+    }
+    if count > 0 {
+        store::write_json(&tracking_path(repo_root), &tracked)?;
+    }
+    Ok(count)
+}
+
+/// System prompt section for a run on a remediation ticket; empty when the
+/// ticket tracks no open advisories.
+pub fn prompt_section(tracked: &[TrackedAdvisory], ticket_id: &str) -> String {
+    let open: Vec<String> = tracked
+        .iter()
+        .filter(|t| t.ticket_id == ticket_id && !t.resolved)
+        .map(|t| format!("- {}", describe(&t.advisory)))
+        .collect();
+    if open.is_empty() {
+        return String::new();
+    }
+    format!(
+        "## Security remediation\n\n\
+         This ticket fixes published vulnerabilities:\n{}\n\n\
+         - Prefer upgrading to a patched release over patching around the bug.\n\
+         - Never silence the audit: no ignore lists, no pinning to skip the check.\n\
+         - Keep the change minimal and name every advisory id in the PR title or body.\n\
+         - If our code reaches the vulnerable path, add a test that would have caught it.",
+        open.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO: &str = r#"{"database":{},"lockfile":{},"settings":{},
+      "vulnerabilities":{"found":true,"count":1,"list":[{
+        "advisory":{"id":"RUSTSEC-2022-0090","package":"libsqlite3-sys","title":"Memory corruption in bundled SQLite",
+                    "aliases":["CVE-2022-35737","GHSA-jw36-hf63-69r9"],"url":null},
+        "versions":{"patched":[">=0.25.1"],"unaffected":[]},
+        "package":{"name":"libsqlite3-sys","version":"0.24.2"}}]},"warnings":{}}"#;
+
+    const NPM: &str = r#"{"auditReportVersion":2,"vulnerabilities":{
+      "lodash":{"name":"lodash","severity":"high","via":[{"source":1065,"name":"lodash","title":"Prototype Pollution",
+                "url":"https://github.com/advisories/GHSA-p6mc-m468-83gw","severity":"high","range":"<4.17.21"}],
+                "range":"<=4.17.20","nodes":["node_modules/lodash"],"fixAvailable":true},
+      "grunt":{"name":"grunt","severity":"high","via":["lodash"],"range":"0.4.0 - 1.0.4","fixAvailable":true}}}"#;
+
+    const NPM_LOCK: &str = r#"{"lockfileVersion":3,"packages":{"":{"name":"app"},
+      "node_modules/lodash":{"version":"4.17.20"}}}"#;
+
+    const LEGACY: &str = r#"{"module_name":"lodash","title":"Prototype Pollution",
+      "url":"https://github.com/advisories/GHSA-p6mc-m468-83gw","severity":"high",
+      "vulnerable_versions":"<4.17.21","patched_versions":">=4.17.21","cves":["CVE-2020-8203"],
+      "findings":[{"version":"4.17.20","paths":["grunt>lodash"]},{"version":"4.17.19","paths":["lodash"]}]}"#;
+
+    const BERRY: &str = r#"{"value":"lodash","children":{"ID":1065,"Issue":"Prototype Pollution",
+      "URL":"https://github.com/advisories/GHSA-p6mc-m468-83gw","Severity":"high",
+      "Vulnerable Versions":"<4.17.21","Tree Versions":["4.17.20"],"Dependents":["app@workspace:."]}}"#;
+
+    #[test]
+    fn parses_both_audit_feeds() {
+        let cargo = parse_cargo(CARGO).unwrap();
+        assert_eq!(cargo[0].id, "RUSTSEC-2022-0090");
+        assert_eq!(cargo[0].cves, ["CVE-2022-35737"]);
+        assert_eq!(cargo[0].patched, [">=0.25.1"]);
+
+        let lock: NpmLock = serde_json::from_str(NPM_LOCK).unwrap();
+        let npm = parse_npm(NPM, &lock).unwrap();
+        assert_eq!(npm.len(), 1, "grunt is only vulnerable through lodash");
+        assert_eq!(
+            (npm[0].id.as_str(), npm[0].package.as_str()),
+            ("GHSA-p6mc-m468-83gw", "lodash")
+        );
+        assert_eq!(npm[0].severity.as_deref(), Some("high"));
+        assert_eq!(npm[0].installed, "4.17.20");
+        let unlocked = parse_npm(NPM, &NpmLock::default()).unwrap();
+        assert_eq!(unlocked[0].installed, "<=4.17.20");
+    }
+
+    #[test]
+    fn parses_pnpm_and_yarn_feeds() {
+        let pnpm = parse_pnpm(&format!(r#"{{"advisories":{{"1065":{}}}}}"#, LEGACY)).unwrap();
+        let yarn = parse_yarn(&format!(
+            "{}\n{}\n",
+            r#"{"type":"info","data":"starting audit"}"#,
+            format!(
+                r#"{{"type":"auditAdvisory","data":{{"advisory":{}}}}}"#,
+                LEGACY.replace('\n', "")
+            )
+        ))
+        .unwrap();
+        for advisories in [pnpm, yarn] {
+            assert_eq!(advisories.len(), 1);
+            let lodash = &advisories[0];
+            assert_eq!(lodash.id, "GHSA-p6mc-m468-83gw");
+            assert_eq!(lodash.installed, "4.17.19, 4.17.20");
+            assert_eq!(lodash.cves, ["CVE-2020-8203"]);
+            assert_eq!(lodash.patched, [">=4.17.21"]);
+        }
+
+        let berry = parse_berry(&BERRY.replace('\n', "")).unwrap();
+        assert_eq!(
+            (berry[0].package.as_str(), berry[0].installed.as_str()),
+            ("lodash", "4.17.20")
+        );
+    }
+
+    #[test]
+    fn reconcile_resolves_fixed_and_skips_tracked() {
+        let sqlite = parse_cargo(CARGO).unwrap().remove(0);
+        let lodash = parse_npm(NPM, &NpmLock::default()).unwrap().remove(0);
+        let mut tracked = vec![TrackedAdvisory {
+            advisory: sqlite.clone(),
+            ticket_id: "t-1".to_string(),
+            pr_number: Some(4),
+            resolved: false,
+        }];
+
+        let findings = reconcile(
+            &mut tracked,
+            vec![sqlite, lodash.clone()],
+            &["cargo", "npm"],
+        );
+        assert!(!tracked[0].resolved);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].ticket.title,
+            "Fix GHSA-p6mc-m468-83gw in lodash"
+        );
+        assert_eq!(findings[0].ticket.complexity, 5);
+
+        // Cargo didn't run, so its advisory can't have been fixed
+        reconcile(&mut tracked, vec![lodash.clone()], &["npm"]);
+        assert!(!tracked[0].resolved);
+
+        reconcile(&mut tracked, vec![lodash], &["cargo", "npm"]);
+        assert!(tracked[0].resolved);
+    }
+
+    #[test]
+    fn prompt_section_lists_open_advisories_for_the_ticket() {
+        let tracked = vec![TrackedAdvisory {
+            advisory: parse_cargo(CARGO).unwrap().remove(0),
+            ticket_id: "t-1".to_string(),
+            pr_number: None,
+            resolved: false,
+        }];
+        let section = prompt_section(&tracked, "t-1");
+        assert!(section.contains("RUSTSEC-2022-0090 in `libsqlite3-sys` 0.24.2"));
+        assert!(section.contains("(CVE-2022-35737)"));
+        assert_eq!(prompt_section(&tracked, "t-2"), "");
+    }
+}
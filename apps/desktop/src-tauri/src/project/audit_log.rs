@@ -0,0 +1,246 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use ts_rs::TS;
+
+use super::store;
+
+/// Most entries a query returns when the filter sets no limit.
+const DEFAULT_LIMIT: usize = 500;
+
+/// Longest argument summary kept in a command entry's detail.
+const MAX_ARGS_LEN: usize = 500;
+
+/// Words in an argument name that mark its value as a credential.
+const SECRET_WORDS: &[&str] = &["token", "tokens", "secret", "password", "pem", "keys"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Actor {
+    /// Someone using the app.
+    User,
+    /// The backend acting on its own: orchestrator, schedulers, agent runs.
+    Automation,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditKind {
+    /// A state-changing Tauri command.
+    Command,
+    /// A human approving or rejecting something an agent asked for.
+    Approval,
+    /// A credential handed to a run.
+    TokenAccess,
+    Merge,
+}
+
+/// One privileged action, appended to `$HOME/.poietai/audit.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct AuditEntry {
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub at: i64,
+    pub actor: Actor,
+    pub kind: AuditKind,
+    /// Command name or what was done, e.g. "approve_checkpoint".
+    pub action: String,
+    pub agent_id: Option<String>,
+    pub ticket_id: Option<String>,
+    /// Never a secret: token entries name the credential, not its value.
+    pub detail: String,
+}
+
+impl AuditEntry {
+    pub fn new(actor: Actor, kind: AuditKind, action: &str) -> Self {
+        AuditEntry {
+            at: chrono::Utc::now().timestamp(),
+            actor,
+            kind,
+            action: action.to_string(),
+            agent_id: None,
+            ticket_id: None,
+            detail: String::new(),
+        }
+    }
+
+    pub fn agent(mut self, agent_id: &str, ticket_id: &str) -> Self {
+        self.agent_id = Some(agent_id.to_string());
+        self.ticket_id = Some(ticket_id.to_string()).filter(|t| !t.is_empty());
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+}
+
+/// What `get_audit_log` returns. Every set field must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub actor: Option<Actor>,
+    pub kind: Option<AuditKind>,
+    pub agent_id: Option<String>,
+    pub ticket_id: Option<String>,
+    /// Unix seconds, inclusive.
+    #[ts(type = "number | null")]
+    pub since: Option<i64>,
+    /// Unix seconds, exclusive.
+    #[ts(type = "number | null")]
+    pub until: Option<i64>,
+    /// Case-insensitive text in the action or detail.
+    pub text: Option<String>,
+    /// Newest entries kept; 500 when unset.
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        let text = self.text.as_ref().map(|t| t.to_lowercase());
+        self.actor.map_or(true, |a| a == entry.actor)
+            && self.kind.map_or(true, |k| k == entry.kind)
+            && self.agent_id.as_ref().map_or(true, |a| entry.agent_id.as_ref() == Some(a))
+            && self.ticket_id.as_ref().map_or(true, |t| entry.ticket_id.as_ref() == Some(t))
+            && self.since.map_or(true, |s| entry.at >= s)
+            && self.until.map_or(true, |u| entry.at < u)
+            && text.map_or(true, |t| {
+                entry.action.to_lowercase().contains(&t) || entry.detail.to_lowercase().contains(&t)
+            })
+    }
+}
+
+fn path() -> PathBuf {
+    store::app_dir().join("audit.jsonl")
+}
+
+/// Append an entry. A failed write is logged, never surfaced: the action
+/// it describes has already happened.
+pub fn record(entry: AuditEntry) {
+    if let Err(e) = store::append_jsonl(&path(), &entry) {
+        log::warn!("[audit_log::record] failed to record {}: {:#}", entry.action, e);
+    }
+}
+
+/// Record a state-changing Tauri command once it has run: the arguments it
+/// was given, with credentials blanked, and whether it failed.
+/// `agent_id` and `ticket_id` are taken from the arguments, at the top level
+/// or inside a `payload` object.
+pub fn command(actor: Actor, command: &str, args: serde_json::Value, outcome: Result<(), &str>) {
+    record(command_entry(actor, command, args, outcome));
+}
+
+fn command_entry(actor: Actor, command: &str, mut args: serde_json::Value, outcome: Result<(), &str>) -> AuditEntry {
+    redact(&mut args);
+    let field = |name: &str| {
+        args.get(name)
+            .or_else(|| args.get("payload").and_then(|p| p.get(name)))
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+    };
+    let mut entry = AuditEntry::new(actor, AuditKind::Command, command);
+    entry.agent_id = field("agent_id");
+    entry.ticket_id = field("ticket_id");
+    let mut args = args.to_string();
+    if args.len() > MAX_ARGS_LEN {
+        let end = (0..=MAX_ARGS_LEN).rev().find(|&i| args.is_char_boundary(i)).unwrap_or(0);
+        args.truncate(end);
+        args.push('…');
+    }
+    entry.detail = match outcome {
+        Ok(()) => format!("ok {}", args),
+        Err(e) => format!("failed: {} {}", e, args),
+    };
+    entry
+}
+
+/// Blank every value whose name marks it as a credential, e.g. `gh_token`,
+/// `api_keys` or `private_key`.
+fn redact(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (name, value) in map.iter_mut() {
+                let secret = name.ends_with("_key")
+                    || name.split('_').any(|word| SECRET_WORDS.contains(&word));
+                if secret && !value.is_null() {
+                    *value = serde_json::Value::String("[redacted]".to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Entries matching `filter`, newest first. Unparseable lines are skipped.
+pub fn query(filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+    let contents = match std::fs::read_to_string(path()) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).context("failed to read audit log"),
+    };
+    Ok(contents
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| filter.matches(entry))
+        .take(filter.limit.unwrap_or(DEFAULT_LIMIT))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_entries_carry_args_outcome_and_target() {
+        let args = serde_json::json!({
+            "payload": {
+                "agent_id": "a-1",
+                "ticket_id": "t-7",
+                "gh_token": "ghp_secret",
+                "api_keys": [{ "key": "sk-ant" }],
+                "github_app": { "app_id": 4, "private_key": "-----BEGIN" },
+                "gitlab_token": null,
+            }
+        });
+        let entry = command_entry(Actor::User, "start_agent", args, Err("no free slot"));
+        assert_eq!(entry.agent_id.as_deref(), Some("a-1"));
+        assert_eq!(entry.ticket_id.as_deref(), Some("t-7"));
+        assert!(entry.detail.starts_with("failed: no free slot "));
+        for secret in ["ghp_secret", "sk-ant", "BEGIN"] {
+            assert!(!entry.detail.contains(secret), "{} leaked", secret);
+        }
+        assert!(entry.detail.contains("\"app_id\":4"));
+
+        let entry = command_entry(Actor::Automation, "set_feature_flag", serde_json::json!({ "key": "pair" }), Ok(()));
+        assert_eq!(entry.detail, r#"ok {"key":"pair"}"#);
+        assert_eq!(entry.agent_id, None);
+    }
+
+    #[test]
+    fn filter_matches_every_set_field() {
+        let entry = AuditEntry::new(Actor::Automation, AuditKind::Merge, "merge_group_branch")
+            .agent("a-1", "t-7")
+            .detail("feat/t-7-g1 into feat/t-7");
+        assert!(AuditFilter::default().matches(&entry));
+        let filter = AuditFilter {
+            actor: Some(Actor::Automation),
+            ticket_id: Some("t-7".to_string()),
+            text: Some("G1".to_string()),
+            since: Some(entry.at),
+            ..Default::default()
+        };
+        assert!(filter.matches(&entry));
+        assert!(!AuditFilter { actor: Some(Actor::User), ..Default::default() }.matches(&entry));
+        assert!(!AuditFilter { until: Some(entry.at), ..Default::default() }.matches(&entry));
+    }
+}
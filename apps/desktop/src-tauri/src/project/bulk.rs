@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+use super::tickets::TicketRecord;
+use crate::agent::scheduler::Priority;
+
+/// Fields to set on every selected ticket. Unset fields are left alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TicketChanges {
+    pub status: Option<String>,
+    pub complexity: Option<u32>,
+    pub priority: Option<Priority>,
+    pub tags: Option<Vec<String>>,
+    pub labels: Option<Vec<String>>,
+    pub milestone: Option<String>,
+}
+
+/// Which of the requested tickets a bulk operation applies to.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct BulkUpdate {
+    pub updated: Vec<String>,
+    /// Ids not on the board — deleted since the selection was made.
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct BulkAssignment {
+    pub ticket_id: String,
+    pub agent_id: String,
+}
+
+/// Split `ids` into tickets on the board and ids that aren't.
+pub fn partition(board: &[TicketRecord], ids: &[String]) -> BulkUpdate {
+    let (updated, missing) = ids
+        .iter()
+        .cloned()
+        .partition(|id| board.iter().any(|t| &t.id == id));
+    BulkUpdate { updated, missing }
+}
+
+/// Open tickets already assigned to each agent.
+pub fn open_load(board: &[TicketRecord]) -> HashMap<String, usize> {
+    let mut load = HashMap::new();
+    for ticket in board.iter().filter(|t| t.status != "shipped") {
+        for assignment in &ticket.assignments {
+            *load.entry(assignment.agent_id.clone()).or_default() += 1;
+        }
+    }
+    load
+}
+
+/// Spread tickets over `agents`, each going to whoever has the fewest open
+/// tickets at that point. Ties go to the earlier agent. Agents already on a
+/// ticket are passed over, and a ticket every agent is already on is skipped.
+pub fn auto_assign(board: &[TicketRecord], ids: &[String], agents: &[String]) -> Vec<BulkAssignment> {
+    let open = open_load(board);
+    let mut load: Vec<(String, usize)> = agents
+        .iter()
+        .map(|a| (a.clone(), open.get(a).copied().unwrap_or(0)))
+        .collect();
+    ids.iter()
+        .filter_map(|id| {
+            let held = board
+                .iter()
+                .find(|t| &t.id == id)
+                .map(|t| t.assignments.as_slice())
+                .unwrap_or_default();
+            let (agent, count) = load
+                .iter_mut()
+                .filter(|(agent, _)| !held.iter().any(|a| &a.agent_id == agent))
+                .min_by_key(|(_, count)| *count)?;
+            *count += 1;
+            Some(BulkAssignment {
+                ticket_id: id.clone(),
+                agent_id: agent.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::tickets::TicketAssignment;
+
+    fn ticket(id: &str, status: &str, agent: Option<&str>) -> TicketRecord {
+        TicketRecord {
+            id: id.to_string(),
+            status: status.to_string(),
+            assignments: agent
+                .map(|a| vec![TicketAssignment { agent_id: a.to_string() }])
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn auto_assign_fills_the_least_loaded_agent_first() {
+        let board = [
+            ticket("t-1", "in_progress", Some("a")),
+            ticket("t-2", "in_progress", Some("a")),
+            ticket("t-3", "shipped", Some("b")),
+        ];
+        let ids: Vec<String> = ["n-1", "n-2", "n-3", "n-4"].iter().map(|s| s.to_string()).collect();
+        let agents = ["a".to_string(), "b".to_string()];
+        let assignments = auto_assign(&board, &ids, &agents);
+        let picked: Vec<&str> = assignments.iter().map(|a| a.agent_id.as_str()).collect();
+        assert_eq!(picked, ["b", "b", "a", "b"]);
+        assert!(auto_assign(&board, &ids, &[]).is_empty());
+    }
+
+    #[test]
+    fn auto_assign_passes_over_agents_already_on_the_ticket() {
+        let board = [ticket("t-1", "in_progress", Some("a")), ticket("t-2", "backlog", None)];
+        let ids = ["t-1".to_string(), "t-2".to_string()];
+        let assignments = auto_assign(&board, &ids, &["a".to_string(), "b".to_string()]);
+        let picked: Vec<(&str, &str)> = assignments
+            .iter()
+            .map(|a| (a.ticket_id.as_str(), a.agent_id.as_str()))
+            .collect();
+        assert_eq!(picked, [("t-1", "b"), ("t-2", "b")]);
+        assert!(auto_assign(&board, &ids[..1], &["a".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn partition_reports_missing_ids() {
+        let board = [ticket("t-1", "backlog", None)];
+        let result = partition(&board, &["t-1".to_string(), "t-9".to_string()]);
+        assert_eq!(result.updated, ["t-1"]);
+        assert_eq!(result.missing, ["t-9"]);
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::store;
+use ts_rs::TS;
+
+/// Build caches shared by every worktree of a project, injected into the agent
+/// environment so each ticket doesn't start from a cold build.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Wrap rustc with `sccache`. Safe with concurrent builds; requires
+    /// `sccache` on PATH or every cargo invocation fails.
+    pub sccache: bool,
+    /// Point every worktree at one `CARGO_TARGET_DIR`. Big win for monorepos,
+    /// but concurrent agents serialise on cargo's build lock and `cargo clean`
+    /// in one worktree wipes the others.
+    pub shared_cargo_target: bool,
+    /// Share one pnpm content store and npm download cache. Both are
+    /// content-addressed, so concurrent installs are safe.
+    pub shared_node_store: bool,
+}
+
+/// Root of the project's shared caches: `<project_root>/.poietai/cache/`.
+pub fn cache_dir(project_root: &Path) -> PathBuf {
+    store::project_dir(project_root).join("cache")
+}
+
+pub fn pnpm_store_dir(project_root: &Path) -> PathBuf {
+    cache_dir(project_root).join("pnpm-store")
+}
+
+fn path_var(name: &str, path: PathBuf) -> (String, String) {
+    (name.to_string(), path.to_string_lossy().to_string())
+}
+
+/// Environment variables that enable the configured cache sharing.
+pub fn cache_env(config: &CacheConfig, project_root: &Path) -> Vec<(String, String)> {
+    let mut env = Vec::new();
+    if config.sccache {
+        env.push(("RUSTC_WRAPPER".to_string(), "sccache".to_string()));
+        // App-wide: sccache keys on compiler inputs, so projects can share it.
+        env.push(path_var("SCCACHE_DIR", store::app_dir().join("cache").join("sccache")));
+    }
+    if config.shared_cargo_target {
+        env.push(path_var("CARGO_TARGET_DIR", cache_dir(project_root).join("cargo-target")));
+    }
+    if config.shared_node_store {
+        env.push(path_var("npm_config_store_dir", pnpm_store_dir(project_root)));
+        env.push(path_var("npm_config_cache", cache_dir(project_root).join("npm")));
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_cache_sets_nothing() {
+        assert!(cache_env(&CacheConfig::default(), Path::new("/repo")).is_empty());
+    }
+
+    #[test]
+    fn shared_target_lives_under_project_cache() {
+        let config = CacheConfig {
+            shared_cargo_target: true,
+            ..Default::default()
+        };
+        let env = cache_env(&config, Path::new("/repo"));
+        assert_eq!(env.len(), 1);
+        assert_eq!(env[0].0, "CARGO_TARGET_DIR");
+        assert!(env[0].1.ends_with("cargo-target"));
+        assert!(env[0].1.starts_with("/repo"));
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::Result;
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::tickets::{self, TicketRecord};
+use crate::agent::estimate::{self, TicketEstimate};
+use crate::agent::history::{self, Outcome, RunRecord};
+use crate::github::latency::{self, PrTiming};
+
+/// Agent time per complexity point until finished tickets say otherwise.
+const DEFAULT_SECS_PER_POINT: i64 = 1800;
+/// Finished, estimated tickets needed before their pace replaces the default.
+const MIN_SAMPLES: usize = 3;
+/// How long a PR may wait for its first review.
+const REVIEW_SLA_SECS: i64 = 24 * 3600;
+const ACTIVE: [&str; 3] = ["assigned", "in_progress", "in_review"];
+
+#[derive(Debug, Clone, PartialEq)]
+struct CalendarEvent {
+    uid: String,
+    start: i64,
+    end: i64,
+    summary: String,
+    description: String,
+}
+
+/// Median agent seconds per complexity point over finished, estimated tickets.
+fn secs_per_point(runs: &[RunRecord], estimates: &HashMap<String, TicketEstimate>) -> i64 {
+    let mut work: HashMap<&str, (i64, bool)> = HashMap::new();
+    for run in runs {
+        let entry = work.entry(&run.ticket_id).or_default();
+        entry.0 += (run.finished_at - run.started_at).max(0);
+        entry.1 |= run.outcome == Outcome::Succeeded;
+    }
+    let mut paces: Vec<i64> = work
+        .into_iter()
+        .filter(|(_, (_, done))| *done)
+        .filter_map(|(id, (secs, _))| Some(secs / i64::from(estimates.get(id)?.complexity.max(1))))
+        .collect();
+    if paces.len() < MIN_SAMPLES {
+        return DEFAULT_SECS_PER_POINT;
+    }
+    paces.sort_unstable();
+    paces[paces.len() / 2]
+}
+
+fn events(
+    tickets: &[TicketRecord],
+    estimates: &HashMap<String, TicketEstimate>,
+    runs: &[RunRecord],
+    prs: &[PrTiming],
+    now: i64,
+) -> Vec<CalendarEvent> {
+    let pace = secs_per_point(runs, estimates);
+    let mut events = Vec::new();
+    for ticket in tickets.iter().filter(|t| ACTIVE.contains(&t.status.as_str())) {
+        let label = format!("#{} {}", ticket.number, ticket.title);
+        if ticket.status != "in_review" {
+            if let Some(estimate) = estimates.get(&ticket.id) {
+                let started = runs
+                    .iter()
+                    .filter(|r| r.ticket_id == ticket.id)
+                    .map(|r| r.started_at)
+                    .min()
+                    .unwrap_or(now);
+                let expected = pace * i64::from(estimate.complexity.max(1));
+                // Never show a window that's already behind us
+                let start = (started + expected * 3 / 4).max(now);
+                events.push(CalendarEvent {
+                    uid: format!("eta-{}@poietai.ai", ticket.id),
+                    start,
+                    end: start.max(started + expected * 3 / 2),
+                    summary: format!("ETA: {}", label),
+                    description: format!("Complexity {}/10. {}", estimate.complexity, estimate.summary),
+                });
+            }
+        }
+        let awaiting = prs
+            .iter()
+            .filter(|p| p.ticket_id == ticket.id && p.first_review_at.is_none() && p.merged_at.is_none() && !p.closed);
+        for pr in awaiting {
+            let Some(opened) = pr
+                .opened_at
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            else {
+                continue;
+            };
+            let due = opened.timestamp() + REVIEW_SLA_SECS;
+            events.push(CalendarEvent {
+                uid: format!("review-{}-{}@poietai.ai", pr.repo.replace('/', "-"), pr.pr_number),
+                start: due,
+                end: due + 1800,
+                summary: format!("Review due: {} (PR #{})", label, pr.pr_number),
+                description: format!("https://github.com/{}/pull/{}", pr.repo, pr.pr_number),
+            });
+        }
+    }
+    events
+}
+
+fn ics_time(at: i64) -> String {
+    Utc.timestamp_opt(at, 0)
+        .single()
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets, per RFC 5545, without splitting a character.
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn render(events: &[CalendarEvent], now: i64) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//poietai.ai//Agent milestones//EN".to_string(),
+        "X-WR-CALNAME:Agent milestones".to_string(),
+    ];
+    for event in events {
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", event.uid),
+            format!("DTSTAMP:{}", ics_time(now)),
+            format!("DTSTART:{}", ics_time(event.start)),
+            format!("DTEND:{}", ics_time(event.end)),
+            format!("SUMMARY:{}", ics_text(&event.summary)),
+            format!("DESCRIPTION:{}", ics_text(&event.description)),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold(l)).collect()
+}
+
+/// An ICS calendar of expected completion windows and review deadlines for
+/// the project's active tickets.
+pub fn export(project_root: &Path) -> Result<String> {
+    let now = Utc::now().timestamp();
+    let events = events(
+        &tickets::load_all(project_root)?,
+        &estimate::load_all(project_root)?,
+        &history::load()?,
+        &latency::load()?,
+        now,
+    );
+    Ok(render(&events, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket(id: &str, status: &str) -> TicketRecord {
+        TicketRecord {
+            id: id.to_string(),
+            number: 7,
+            title: "Fix login, again".to_string(),
+            status: status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn estimated(id: &str, complexity: u32) -> (String, TicketEstimate) {
+        let estimate = TicketEstimate {
+            ticket_id: id.to_string(),
+            complexity,
+            files: vec![],
+            risks: vec![],
+            summary: String::new(),
+            estimated_at: 0,
+        };
+        (id.to_string(), estimate)
+    }
+
+    #[test]
+    fn eta_window_scales_with_complexity_from_first_run() {
+        let estimates = HashMap::from([estimated("t-1", 2)]);
+        let runs = [RunRecord {
+            started_at: 1_000,
+            finished_at: 2_000,
+            agent_id: "a".to_string(),
+            ticket_id: "t-1".to_string(),
+            cost_usd: 0.0,
+            outcome: Outcome::Failed,
+        }];
+        let all = events(&[ticket("t-1", "in_progress"), ticket("t-2", "backlog")], &estimates, &runs, &[], 1_000);
+        assert_eq!(all.len(), 1);
+        assert_eq!((all[0].start, all[0].end), (1_000 + 2700, 1_000 + 5400));
+    }
+
+    #[test]
+    fn review_deadline_follows_pr_opening() {
+        let pr = PrTiming {
+            repo: "acme/web".to_string(),
+            pr_number: 12,
+            ticket_id: "t-1".to_string(),
+            opened_at: Some("2026-03-02T09:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let all = events(&[ticket("t-1", "in_review")], &HashMap::new(), &[], &[pr], 0);
+        assert_eq!(ics_time(all[0].start), "20260303T090000Z");
+        assert_eq!(all[0].uid, "review-acme-web-12@poietai.ai");
+    }
+
+    #[test]
+    fn renders_escaped_folded_lines() {
+        let event = CalendarEvent {
+            uid: "eta-t-1@poietai.ai".to_string(),
+            start: 0,
+            end: 60,
+            summary: "ETA: #7 Fix login, again".to_string(),
+            description: "a;b\n".to_string() + &"x".repeat(100),
+        };
+        let ics = render(&[event], 0);
+        assert!(ics.contains("SUMMARY:ETA: #7 Fix login\\, again\r\n"));
+        assert!(ics.contains("DESCRIPTION:a\\;b\\n"));
+        assert!(ics.lines().all(|l| l.len() <= 75));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
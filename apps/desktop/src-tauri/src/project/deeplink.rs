@@ -0,0 +1,86 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use ts_rs::TS;
+
+pub const SCHEME: &str = "poietai";
+
+/// Where a `poietai://` link points. Emitted to the frontend as `deep-link`.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeepLink {
+    /// `poietai://ticket/<ticket_id>`
+    Ticket { ticket_id: String },
+    /// `poietai://agent/<agent_id>`
+    Agent { agent_id: String },
+    /// `poietai://agent/<agent_id>/question/<question_id>`
+    Question { agent_id: String, question_id: String },
+}
+
+/// A link that arrived before the frontend was listening, e.g. the one the
+/// app was launched with.
+pub type PendingLink = Arc<Mutex<Option<DeepLink>>>;
+
+pub fn parse(url: &str) -> Result<DeepLink> {
+    let rest = url
+        .strip_prefix(SCHEME)
+        .and_then(|r| r.strip_prefix("://"))
+        .ok_or_else(|| anyhow::anyhow!("not a {}:// link: {}", SCHEME, url))?;
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let link = match segments.as_slice() {
+        ["ticket", ticket_id] => DeepLink::Ticket {
+            ticket_id: ticket_id.to_string(),
+        },
+        ["agent", agent_id] => DeepLink::Agent {
+            agent_id: agent_id.to_string(),
+        },
+        ["agent", agent_id, "question", question_id] => DeepLink::Question {
+            agent_id: agent_id.to_string(),
+            question_id: question_id.to_string(),
+        },
+        _ => anyhow::bail!("unrecognised link: {}", url),
+    };
+    Ok(link)
+}
+
+/// The first `poietai://` link among the process arguments — how the OS
+/// hands a clicked link to a freshly launched app.
+pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<DeepLink> {
+    args.into_iter()
+        .filter(|a| a.starts_with(SCHEME))
+        .find_map(|a| parse(&a).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_tickets_agents_and_questions() {
+        assert_eq!(
+            parse("poietai://ticket/t-42").unwrap(),
+            DeepLink::Ticket { ticket_id: "t-42".to_string() }
+        );
+        assert_eq!(
+            parse("poietai://agent/a-1/question/q-9?from=slack").unwrap(),
+            DeepLink::Question {
+                agent_id: "a-1".to_string(),
+                question_id: "q-9".to_string()
+            }
+        );
+        assert_eq!(
+            parse("poietai://agent/a-1/").unwrap(),
+            DeepLink::Agent { agent_id: "a-1".to_string() }
+        );
+        assert!(parse("poietai://ticket").is_err());
+        assert!(parse("https://ticket/t-42").is_err());
+    }
+
+    #[test]
+    fn finds_the_link_among_launch_args() {
+        let args = ["/usr/bin/poietai", "--flag", "poietai://ticket/t-1"].map(String::from);
+        assert_eq!(from_args(args), Some(DeepLink::Ticket { ticket_id: "t-1".to_string() }));
+    }
+}
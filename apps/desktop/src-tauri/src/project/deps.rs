@@ -0,0 +1,326 @@
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use ts_rs::TS;
+
+use super::templates::TicketDraft;
+use crate::platform::exec;
+
+/// A dependency with a newer release than the one the lockfile pins.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct OutdatedDependency {
+    pub name: String,
+    pub current: String,
+    pub latest: String,
+}
+
+/// One ticket's worth of upgrades: an ecosystem's breaking or non-breaking
+/// updates, with the ticket to add and the command gating its PR.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct DependencyUpdate {
+    /// "cargo" or "npm".
+    pub ecosystem: String,
+    /// Major bumps (minor bumps below 1.0), which may need code changes.
+    pub breaking: bool,
+    pub dependencies: Vec<OutdatedDependency>,
+    pub ticket: TicketDraft,
+    /// Must pass before the agent may open the PR; pass it as `pr_gate`.
+    pub test_command: String,
+}
+
+#[derive(Deserialize)]
+struct CargoOutdated {
+    dependencies: Vec<CargoDependency>,
+}
+
+#[derive(Deserialize)]
+struct CargoDependency {
+    name: String,
+    project: String,
+    latest: String,
+}
+
+#[derive(Deserialize)]
+struct NpmDependency {
+    current: Option<String>,
+    latest: String,
+}
+
+/// Parse `cargo outdated --root-deps-only --format json`, which prints one
+/// object per workspace member.
+fn parse_cargo(stdout: &str) -> Result<Vec<OutdatedDependency>> {
+    let mut found: BTreeMap<String, OutdatedDependency> = BTreeMap::new();
+    for line in stdout.lines().filter(|l| l.trim_start().starts_with('{')) {
+        let report: CargoOutdated =
+            serde_json::from_str(line).context("failed to parse cargo outdated output")?;
+        // "---" / "Removed" mark crates that no longer resolve, not upgrades
+        for dep in report
+            .dependencies
+            .into_iter()
+            .filter(|d| d.latest.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            if dep.project != dep.latest {
+                found.insert(
+                    dep.name.clone(),
+                    OutdatedDependency {
+                        name: dep.name,
+                        current: dep.project,
+                        latest: dep.latest,
+                    },
+                );
+            }
+        }
+    }
+    Ok(found.into_values().collect())
+}
+
+/// Parse `npm outdated --json`. Packages missing from `node_modules` have no
+/// current version and are skipped: installing them isn't an upgrade.
+fn parse_npm(stdout: &str) -> Result<Vec<OutdatedDependency>> {
+    if stdout.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let report: BTreeMap<String, NpmDependency> =
+        serde_json::from_str(stdout).context("failed to parse npm outdated output")?;
+    Ok(report
+        .into_iter()
+        .filter_map(|(name, dep)| {
+            let current = dep.current?;
+            (current != dep.latest).then_some(OutdatedDependency {
+                name,
+                current,
+                latest: dep.latest,
+            })
+        })
+        .collect())
+}
+
+/// Whether going from `current` to `latest` crosses a semver-breaking line:
+/// the major version, or the minor version while still below 1.0.
+fn is_breaking(current: &str, latest: &str) -> bool {
+    let parts = |v: &str| -> (u64, u64) {
+        let mut nums = v
+            .trim_start_matches(['^', '~', '=', 'v'])
+            .split('.')
+            .map(|n| {
+                n.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            });
+        (nums.next().unwrap_or(0), nums.next().unwrap_or(0))
+    };
+    let (cur, new) = (parts(current), parts(latest));
+    cur.0 != new.0 || (cur.0 == 0 && cur.1 != new.1)
+}
+
+fn draft(
+    ecosystem: &str,
+    breaking: bool,
+    deps: &[OutdatedDependency],
+    test_command: &str,
+) -> TicketDraft {
+    let kind = if breaking {
+        "major versions"
+    } else {
+        "minor and patch"
+    };
+    let noun = if deps.len() == 1 {
+        "dependency"
+    } else {
+        "dependencies"
+    };
+    let list = deps
+        .iter()
+        .map(|d| format!("- `{}` {} → {}", d.name, d.current, d.latest))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let approach = if breaking {
+        "These are breaking releases. Read each changelog or migration guide, \
+         upgrade one dependency at a time, and fix call sites as you go. If an \
+         upgrade needs a larger redesign, leave it out and say why in the PR."
+    } else {
+        "These should be drop-in updates. Bump them together, then look for \
+         deprecation warnings in the build output and fix what's cheap."
+    };
+    let mut criteria = vec![
+        "Manifest and lockfile are updated together; nothing else in the lockfile churns"
+            .to_string(),
+        format!("`{}` passes before the PR is opened", test_command),
+        "The PR lists each upgrade with a link to its changelog".to_string(),
+    ];
+    if breaking {
+        criteria.push("Breaking changes that touched code are called out in the PR".to_string());
+    }
+    TicketDraft {
+        title: format!("Update {} {} {} ({})", deps.len(), ecosystem, noun, kind),
+        description: format!(
+            "## Outdated\n\n{}\n\n## Approach\n\n{}\n\nChange only what the upgrades \
+             require; don't refactor or reformat unrelated code.",
+            list, approach
+        ),
+        complexity: if breaking { 5 } else { 2 },
+        acceptance_criteria: criteria,
+        tags: vec!["dependencies".to_string()],
+    }
+}
+
+/// Split an ecosystem's outdated dependencies into breaking and
+/// non-breaking updates, one ticket each.
+fn group(
+    ecosystem: &str,
+    test_command: &str,
+    deps: Vec<OutdatedDependency>,
+) -> Vec<DependencyUpdate> {
+    let (breaking, routine): (Vec<_>, Vec<_>) = deps
+        .into_iter()
+        .partition(|d| is_breaking(&d.current, &d.latest));
+    [(false, routine), (true, breaking)]
+        .into_iter()
+        .filter(|(_, deps)| !deps.is_empty())
+        .map(|(breaking, dependencies)| DependencyUpdate {
+            ecosystem: ecosystem.to_string(),
+            breaking,
+            ticket: draft(ecosystem, breaking, &dependencies, test_command),
+            dependencies,
+            test_command: test_command.to_string(),
+        })
+        .collect()
+}
+
+/// Run a dependency tool in the project. `outdated` and `audit` commands
+/// exit non-zero when they find something, so only missing output counts as
+/// failure.
+pub(crate) fn tool_output(project_root: &Path, program: &str, args: &[&str]) -> Result<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args).current_dir(project_root);
+    let output = exec::output_blocking(&mut cmd, exec::NETWORK)?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() && stdout.trim().is_empty() {
+        anyhow::bail!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(stdout)
+}
+
+/// Whether a cargo plugin such as `cargo-outdated` is installed.
+fn has_cargo_subcommand(name: &str) -> bool {
+    let mut cmd = Command::new("cargo");
+    cmd.arg("--list");
+    exec::output_blocking(&mut cmd, exec::LOCAL)
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .any(|line| line.split_whitespace().next() == Some(name))
+        })
+        .unwrap_or(false)
+}
+
+/// Find outdated Cargo and npm dependencies in the project and group them
+/// into tickets. Blocking; both tools hit their registries. Cargo is skipped
+/// when `cargo-outdated` isn't installed.
+pub fn find_updates(project_root: &Path) -> Result<Vec<DependencyUpdate>> {
+    let mut updates = Vec::new();
+    if project_root.join("Cargo.toml").is_file() {
+        if has_cargo_subcommand("outdated") {
+            let stdout = tool_output(
+                project_root,
+                "cargo",
+                &["outdated", "--root-deps-only", "--format", "json"],
+            )?;
+            updates.extend(group("cargo", "cargo test", parse_cargo(&stdout)?));
+        } else {
+            warn!("[deps] cargo-outdated is not installed; skipping Cargo dependencies");
+        }
+    }
+    if project_root.join("package.json").is_file() {
+        let stdout = tool_output(project_root, "npm", &["outdated", "--json"])?;
+        updates.extend(group("npm", "npm test", parse_npm(&stdout)?));
+    }
+    Ok(updates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(name: &str, current: &str, latest: &str) -> OutdatedDependency {
+        OutdatedDependency {
+            name: name.to_string(),
+            current: current.to_string(),
+            latest: latest.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_cargo_workspace_output() {
+        let stdout = r#"{"crate_name":"app","dependencies":[{"name":"serde","project":"1.0.190","compat":"1.0.210","latest":"1.0.210","kind":"Normal","platform":null},{"name":"old","project":"0.3.0","compat":"---","latest":"Removed","kind":"Normal","platform":null}]}
+{"crate_name":"cli","dependencies":[{"name":"clap","project":"3.2.0","compat":"3.2.25","latest":"4.5.0","kind":"Normal","platform":null}]}"#;
+        assert_eq!(
+            parse_cargo(stdout).unwrap(),
+            vec![
+                dep("clap", "3.2.0", "4.5.0"),
+                dep("serde", "1.0.190", "1.0.210")
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_npm_output_and_skips_uninstalled() {
+        let stdout = r#"{"react":{"current":"18.2.0","wanted":"18.3.1","latest":"19.0.0","dependent":"app"},
+                         "left-pad":{"wanted":"1.3.0","latest":"1.3.0","dependent":"app"}}"#;
+        assert_eq!(
+            parse_npm(stdout).unwrap(),
+            vec![dep("react", "18.2.0", "19.0.0")]
+        );
+        assert!(parse_npm("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn groups_by_breaking_change() {
+        assert!(is_breaking("3.2.0", "4.0.0"));
+        assert!(is_breaking("0.11.2", "0.12.0"));
+        assert!(!is_breaking("0.11.2", "0.11.9"));
+        assert!(!is_breaking("1.0.190", "1.4.0"));
+
+        let updates = group(
+            "cargo",
+            "cargo test",
+            vec![
+                dep("clap", "3.2.0", "4.5.0"),
+                dep("serde", "1.0.190", "1.0.210"),
+                dep("tokio", "1.30.0", "1.40.0"),
+            ],
+        );
+        assert_eq!(updates.len(), 2);
+        assert!(!updates[0].breaking);
+        assert_eq!(
+            updates[0].ticket.title,
+            "Update 2 cargo dependencies (minor and patch)"
+        );
+        assert!(updates[1].breaking);
+        assert_eq!(
+            updates[1].ticket.title,
+            "Update 1 cargo dependency (major versions)"
+        );
+        assert!(updates[1]
+            .ticket
+            .description
+            .contains("- `clap` 3.2.0 → 4.5.0"));
+        assert!(updates[1]
+            .ticket
+            .acceptance_criteria
+            .iter()
+            .any(|c| c.contains("`cargo test` passes")));
+    }
+}
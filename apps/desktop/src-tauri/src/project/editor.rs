@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum EditorKind {
+    #[default]
+    VsCode,
+    Cursor,
+    /// Any JetBrains IDE via its launcher script (`idea`, `webstorm`, …).
+    JetBrains,
+}
+
+/// The editor `open_in_editor` launches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct EditorConfig {
+    pub kind: EditorKind,
+    /// CLI to run instead of the kind's default ("code", "cursor", "idea"),
+    /// e.g. "code-insiders" or a full path.
+    pub command: Option<String>,
+}
+
+impl EditorConfig {
+    fn program(&self) -> &str {
+        match (&self.command, self.kind) {
+            (Some(command), _) if !command.trim().is_empty() => command.trim(),
+            (_, EditorKind::VsCode) => "code",
+            (_, EditorKind::Cursor) => "cursor",
+            (_, EditorKind::JetBrains) => "idea",
+        }
+    }
+
+    /// Arguments opening `worktree` as the project, and `file` (relative to
+    /// it) in that window when given.
+    pub fn args(&self, worktree: &Path, file: Option<&str>) -> Vec<String> {
+        let root = worktree.to_string_lossy().to_string();
+        let mut args = vec![root];
+        if let Some(file) = file {
+            let file = worktree.join(file).to_string_lossy().to_string();
+            match self.kind {
+                // Reuse the window if the folder is already open
+                EditorKind::VsCode | EditorKind::Cursor => {
+                    args.insert(0, "--reuse-window".to_string());
+                    args.push(file);
+                }
+                EditorKind::JetBrains => args.push(file),
+            }
+        }
+        args
+    }
+}
+
+/// Launch the editor without waiting for it to exit.
+pub fn open(config: &EditorConfig, worktree: &Path, file: Option<&str>) -> Result<()> {
+    let program = config.program();
+    let mut cmd = if cfg!(target_os = "windows") {
+        // Editor CLIs are .cmd shims on Windows, which need cmd to run
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", program]);
+        cmd
+    } else {
+        Command::new(program)
+    };
+    cmd.args(config.args(worktree, file))
+        .spawn()
+        .with_context(|| format!("failed to launch '{}' — is it on your PATH?", program))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vscode_opens_file_in_worktree_window() {
+        let config = EditorConfig::default();
+        let args = config.args(Path::new("/wt"), Some("src/lib.rs"));
+        assert_eq!(config.program(), "code");
+        assert_eq!(args, vec!["--reuse-window", "/wt", "/wt/src/lib.rs"]);
+    }
+
+    #[test]
+    fn command_override_and_folder_only() {
+        let config = EditorConfig {
+            kind: EditorKind::JetBrains,
+            command: Some("webstorm".to_string()),
+        };
+        assert_eq!(config.program(), "webstorm");
+        assert_eq!(config.args(Path::new("/wt"), None), vec!["/wt"]);
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::store;
+
+/// Release track this install follows. Riskier capabilities reach nightly
+/// first, then beta, then stable.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl Channel {
+    /// The channel a build belongs to, from its version's pre-release tag.
+    pub fn of_version(version: &str) -> Channel {
+        match version.split_once('-').map(|(_, pre)| pre) {
+            Some(pre) if pre.starts_with("nightly") || pre.starts_with("alpha") => Channel::Nightly,
+            Some(pre) if pre.starts_with("beta") || pre.starts_with("rc") => Channel::Beta,
+            _ => Channel::Stable,
+        }
+    }
+}
+
+struct FlagDef {
+    key: &'static str,
+    description: &'static str,
+    /// On by default from this channel onwards (toward nightly).
+    rollout: Channel,
+}
+
+/// Capabilities that ship dark. Remove a flag once its feature is on for
+/// everyone.
+const FLAGS: &[FlagDef] = &[
+    FlagDef {
+        key: "ci_triage",
+        description: "Resume the agent with the failing logs when its PR fails CI",
+        rollout: Channel::Beta,
+    },
+    FlagDef {
+        key: "tournaments",
+        description: "Run several attempts at a ticket and keep the best one",
+        rollout: Channel::Nightly,
+    },
+];
+
+/// Serializes read-modify-writes of `flags.json`.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Per-install flag state, at `$HOME/.poietai/flags.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+struct FlagStore {
+    /// Chosen channel; follows the build's version when unset.
+    channel: Option<Channel>,
+    overrides: BTreeMap<String, bool>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct FeatureFlag {
+    pub key: String,
+    pub description: String,
+    pub enabled: bool,
+    /// Whether the user set this flag rather than getting the channel default.
+    pub overridden: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct FeatureFlags {
+    pub channel: Channel,
+    pub flags: Vec<FeatureFlag>,
+}
+
+fn path() -> PathBuf {
+    store::app_dir().join("flags.json")
+}
+
+fn resolve(store: &FlagStore, version: &str) -> FeatureFlags {
+    let channel = store.channel.unwrap_or_else(|| Channel::of_version(version));
+    let flags = FLAGS
+        .iter()
+        .map(|def| {
+            let overridden = store.overrides.get(def.key).copied();
+            FeatureFlag {
+                key: def.key.to_string(),
+                description: def.description.to_string(),
+                enabled: overridden.unwrap_or(channel >= def.rollout),
+                overridden: overridden.is_some(),
+            }
+        })
+        .collect();
+    FeatureFlags { channel, flags }
+}
+
+pub fn load() -> Result<FeatureFlags> {
+    let store: FlagStore = store::read_json(&path())?;
+    Ok(resolve(&store, env!("CARGO_PKG_VERSION")))
+}
+
+/// Whether `key` is on for this install. Unknown flags, and a store that
+/// can't be read, count as off.
+pub fn is_enabled(key: &str) -> bool {
+    load()
+        .map(|flags| flags.flags.iter().any(|f| f.key == key && f.enabled))
+        .unwrap_or(false)
+}
+
+/// Force a flag on or off for this install, or back to its channel default
+/// with None.
+pub fn set(key: &str, enabled: Option<bool>) -> Result<FeatureFlags> {
+    if !FLAGS.iter().any(|f| f.key == key) {
+        anyhow::bail!("unknown feature flag '{}'", key);
+    }
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut flags: FlagStore = store::read_json(&path())?;
+    match enabled {
+        Some(enabled) => flags.overrides.insert(key.to_string(), enabled),
+        None => flags.overrides.remove(key),
+    };
+    store::write_json(&path(), &flags)?;
+    load()
+}
+
+/// Follow `channel`, or the build's own channel with None.
+pub fn set_channel(channel: Option<Channel>) -> Result<FeatureFlags> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut flags: FlagStore = store::read_json(&path())?;
+    flags.channel = channel;
+    store::write_json(&path(), &flags)?;
+    load()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_comes_from_the_pre_release_tag() {
+        assert_eq!(Channel::of_version("0.4.0"), Channel::Stable);
+        assert_eq!(Channel::of_version("0.4.0-beta.2"), Channel::Beta);
+        assert_eq!(Channel::of_version("0.4.0-nightly.20260301"), Channel::Nightly);
+    }
+
+    #[test]
+    fn overrides_beat_channel_defaults() {
+        let enabled = |flags: &FeatureFlags, key: &str| flags.flags.iter().find(|f| f.key == key).unwrap().enabled;
+        let mut store = FlagStore::default();
+        assert!(!enabled(&resolve(&store, "0.4.0"), "ci_triage"));
+        assert!(enabled(&resolve(&store, "0.4.0-beta.1"), "ci_triage"));
+        assert!(!enabled(&resolve(&store, "0.4.0-beta.1"), "tournaments"));
+
+        store.overrides.insert("tournaments".to_string(), true);
+        let flags = resolve(&store, "0.4.0");
+        assert!(enabled(&flags, "tournaments"));
+        assert!(flags.flags.iter().find(|f| f.key == "tournaments").unwrap().overridden);
+
+        store.channel = Some(Channel::Nightly);
+        store.overrides.insert("ci_triage".to_string(), false);
+        assert!(!enabled(&resolve(&store, "0.4.0"), "ci_triage"));
+    }
+}
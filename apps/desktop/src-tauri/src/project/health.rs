@@ -0,0 +1,294 @@
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use ts_rs::TS;
+
+use super::settings::ProjectSettings;
+use crate::git::drift;
+use crate::mcp::listen;
+use crate::platform::{exec, wsl};
+
+/// Commits the local base branch may trail its remote before it's flagged.
+const STALE_AFTER_COMMITS: u32 = 0;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCheck {
+    Repo,
+    RemoteAuth,
+    BaseBranch,
+    WorktreeDir,
+    Bootstrap,
+    Mcp,
+    Wsl,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Agent runs will fail until this is fixed.
+    Error,
+    /// Runs work, but worse than they should.
+    Warning,
+}
+
+/// What would fix a problem, for the UI to offer as a button.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HealthFix {
+    /// Run this command in the project root.
+    Run { program: String, args: Vec<String> },
+    /// Create this directory.
+    CreateDir { path: String },
+    /// Change this key of the project's settings.
+    EditSetting { key: String },
+    /// Restart the app so its services come back up.
+    RestartApp,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct HealthProblem {
+    pub check: HealthCheck,
+    pub severity: Severity,
+    pub message: String,
+    pub fix: Option<HealthFix>,
+}
+
+fn problem(check: HealthCheck, severity: Severity, message: String, fix: Option<HealthFix>) -> HealthProblem {
+    HealthProblem {
+        check,
+        severity,
+        message,
+        fix,
+    }
+}
+
+fn run_fix(program: &str, args: &[&str]) -> Option<HealthFix> {
+    Some(HealthFix::Run {
+        program: program.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+    })
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.args(args)
+        .current_dir(root)
+        // Never hang on a credential or host-key prompt
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    let output = exec::output_blocking(&mut cmd, exec::NETWORK).map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Reachable remote, and a checkout no older than the remote's default
+/// branch: new worktrees branch from HEAD.
+fn remote_problems(root: &Path) -> Vec<HealthProblem> {
+    if let Err(e) = git(root, &["ls-remote", "--heads", "origin"]) {
+        let fix = if e.contains("No such remote") || e.contains("does not appear to be a git repository") {
+            Some(HealthFix::EditSetting { key: "remote".to_string() })
+        } else {
+            run_fix("gh", &["auth", "login"])
+        };
+        return vec![problem(
+            HealthCheck::RemoteAuth,
+            Severity::Error,
+            format!("Can't reach origin: {}", e),
+            fix,
+        )];
+    }
+    let base = drift::origin_default(root);
+    let local = base.trim_start_matches("origin/");
+    let _ = git(root, &["fetch", "--quiet", "origin", local]);
+    let head =
+        git(root, &["rev-parse", "--abbrev-ref", "HEAD"]).unwrap_or_else(|_| "HEAD".to_string());
+    match git(root, &["rev-list", "--count", &format!("HEAD..{}", base)]) {
+        Ok(count) if count.parse::<u32>().unwrap_or(0) > STALE_AFTER_COMMITS => {
+            // Only the checked-out base branch can be brought up to date for
+            // the user; any other branch needs them to merge or switch.
+            let (message, fix) = if head == local {
+                (
+                    format!(
+                        "{} is {} commits behind {} — new worktrees start from old code",
+                        local, count, base
+                    ),
+                    run_fix("git", &["pull", "--ff-only", "origin", local]),
+                )
+            } else {
+                (
+                    format!(
+                        "The checkout is on {}, {} commits behind {} — new worktrees start from it",
+                        head, count, base
+                    ),
+                    None,
+                )
+            };
+            vec![problem(HealthCheck::BaseBranch, Severity::Warning, message, fix)]
+        }
+        Ok(_) => vec![],
+        Err(e) => vec![problem(
+            HealthCheck::BaseBranch,
+            Severity::Warning,
+            format!("Can't compare {} with {}: {}", head, base, e),
+            run_fix("git", &["fetch", "origin"]),
+        )],
+    }
+}
+
+fn worktree_dir_problem(root: &Path) -> Option<HealthProblem> {
+    let dir = root.join(".worktrees");
+    if !dir.exists() {
+        // Created on first run — only a problem if the repo root itself is read-only
+        let writable = std::fs::metadata(root).is_ok_and(|m| !m.permissions().readonly());
+        return (!writable).then(|| {
+            problem(
+                HealthCheck::WorktreeDir,
+                Severity::Error,
+                format!("{} isn't writable, so agent worktrees can't be created", root.display()),
+                Some(HealthFix::CreateDir { path: dir.to_string_lossy().to_string() }),
+            )
+        });
+    }
+    let probe = dir.join(format!(".poietai-health-{}", uuid::Uuid::new_v4()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            None
+        }
+        Err(e) => Some(problem(
+            HealthCheck::WorktreeDir,
+            Severity::Error,
+            format!("Can't write to {}: {}", dir.display(), e),
+            None,
+        )),
+    }
+}
+
+fn bootstrap_problem(command: &str) -> Option<HealthProblem> {
+    let invalid = |message: String| {
+        Some(problem(
+            HealthCheck::Bootstrap,
+            Severity::Error,
+            message,
+            Some(HealthFix::EditSetting { key: "bootstrap.command".to_string() }),
+        ))
+    };
+    if command.trim().is_empty() {
+        return invalid("Bootstrap command is empty".to_string());
+    }
+    if cfg!(target_os = "windows") {
+        return None;
+    }
+    let syntax =
+        exec::output_blocking(Command::new("sh").args(["-n", "-c", command]), exec::LOCAL).ok();
+    if let Some(output) = syntax.filter(|o| !o.status.success()) {
+        return invalid(format!(
+            "Bootstrap command doesn't parse: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let program = command.split_whitespace().find(|w| !w.contains('='))?;
+    let found = exec::output_blocking(
+        Command::new("sh").args(["-c", "command -v \"$1\"", "sh", program]),
+        exec::LOCAL,
+    )
+    .is_ok_and(|o| o.status.success());
+    if !found {
+        return invalid(format!("Bootstrap command needs '{}', which isn't on PATH", program));
+    }
+    None
+}
+
+fn mcp_problem(port: u16) -> Option<HealthProblem> {
+    if listen::answers(port) {
+        return None;
+    }
+    Some(problem(
+        HealthCheck::Mcp,
+        Severity::Error,
+        format!("The MCP server isn't answering on port {} — agents can't ask questions or report status", port),
+        Some(HealthFix::RestartApp),
+    ))
+}
+
+/// The project's WSL distro is installed and has the CLIs agents need.
+fn wsl_problems(root: &Path, distro: Option<&str>) -> Vec<HealthProblem> {
+    let fix = || Some(HealthFix::EditSetting { key: "wsl_distro".to_string() });
+    let health = wsl::choose(distro, root).and_then(|distro| wsl::check(&distro));
+    match health {
+        Ok(health) => health
+            .problems()
+            .into_iter()
+            .map(|p| {
+                let message = format!("WSL distro '{}' is missing a tool agents need — {}", health.distro, p);
+                problem(HealthCheck::Wsl, Severity::Error, message, fix())
+            })
+            .collect(),
+        Err(e) => vec![problem(HealthCheck::Wsl, Severity::Error, format!("{:#}", e), fix())],
+    }
+}
+
+/// Everything standing in the way of agent runs on the project at `root`.
+/// Empty when healthy. Blocking — talks to the remote.
+pub fn check(root: &Path, settings: &ProjectSettings, mcp_port: u16) -> Vec<HealthProblem> {
+    if let Err(e) = git(root, &["rev-parse", "--git-dir"]) {
+        return vec![problem(
+            HealthCheck::Repo,
+            Severity::Error,
+            format!("{} isn't a git repository: {}", root.display(), e),
+            None,
+        )];
+    }
+    let mut problems = remote_problems(root);
+    problems.extend(worktree_dir_problem(root));
+    if let Some(ref bootstrap) = settings.bootstrap {
+        problems.extend(bootstrap_problem(&bootstrap.command));
+    }
+    problems.extend(mcp_problem(mcp_port));
+    if cfg!(target_os = "windows") {
+        problems.extend(wsl_problems(root, settings.wsl_distro.as_deref()));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn bootstrap_commands_must_parse_and_exist() {
+        assert!(bootstrap_problem("sh -c true").is_none());
+        assert!(bootstrap_problem("  ").is_some());
+        let broken = bootstrap_problem("if then fi (").unwrap();
+        assert!(broken.message.contains("doesn't parse"));
+        let missing = bootstrap_problem("CI=1 poietai-no-such-tool install").unwrap();
+        assert!(missing.message.contains("'poietai-no-such-tool'"));
+    }
+
+    #[test]
+    fn unanswered_mcp_port_suggests_a_restart() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(mcp_problem(port).is_none());
+        drop(listener);
+        assert_eq!(mcp_problem(port).unwrap().fix, Some(HealthFix::RestartApp));
+    }
+
+    #[test]
+    fn non_repos_stop_at_the_first_check() {
+        let dir = std::env::temp_dir().join(format!("poietai-health-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let problems = check(&dir, &ProjectSettings::default(), 1);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].check, HealthCheck::Repo);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::AbortHandle;
+use ts_rs::TS;
+
+/// When agents may start work unattended. Runs requested outside the window
+/// are deferred until it opens, unless started with "run now anyway".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct WorkingHours {
+    /// ISO weekdays the window applies to: 1 = Monday … 7 = Sunday.
+    pub days: Vec<u32>,
+    /// Local "HH:MM" the window opens.
+    pub start: String,
+    /// Local "HH:MM" the window closes. Earlier than `start` wraps past midnight.
+    pub end: String,
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        WorkingHours {
+            days: vec![1, 2, 3, 4, 5],
+            start: "09:00".to_string(),
+            end: "18:00".to_string(),
+        }
+    }
+}
+
+/// Runs waiting for their project's working hours, keyed by ticket id, so
+/// a deferral can be called off.
+pub type DeferredStore = Arc<Mutex<HashMap<String, Deferred>>>;
+
+pub fn new_store() -> DeferredStore {
+    Arc::new(Mutex::new(HashMap::new()))
+}
+
+/// A run sleeping until the window opens.
+pub struct Deferred {
+    pub id: String,
+    pub abort: AbortHandle,
+}
+
+fn parse_time(s: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M")
+        .with_context(|| format!("'{}' is not a time of day (HH:MM)", s))
+}
+
+impl WorkingHours {
+    /// Check the window can ever open: at least one valid weekday, and
+    /// start and end times that parse.
+    pub fn validate(&self) -> Result<()> {
+        self.window().map(|_| ())
+    }
+
+    /// The parsed (start, end) times.
+    fn window(&self) -> Result<(NaiveTime, NaiveTime)> {
+        if self.days.is_empty() {
+            anyhow::bail!("working hours need at least one day");
+        }
+        if let Some(day) = self.days.iter().find(|d| !(1..=7).contains(*d)) {
+            anyhow::bail!("{} is not a weekday (1 = Monday … 7 = Sunday)", day);
+        }
+        Ok((parse_time(&self.start)?, parse_time(&self.end)?))
+    }
+
+    /// Whether `now` (local time) is inside the window. A window that wraps
+    /// midnight belongs to the day it opened on.
+    pub fn is_open(&self, now: NaiveDateTime) -> Result<bool> {
+        let (start, end) = self.window()?;
+        let time = now.time();
+        let on_day = |at: NaiveDateTime| self.days.contains(&at.weekday().number_from_monday());
+        Ok(if start == end {
+            // Degenerate window: open all day on the listed days
+            on_day(now)
+        } else if start < end {
+            on_day(now) && time >= start && time < end
+        } else if time >= start {
+            on_day(now)
+        } else if time < end {
+            on_day(now - Duration::days(1))
+        } else {
+            false
+        })
+    }
+
+    /// Whether a poller should idle at `now`. A malformed window can never
+    /// open, so it doesn't hold polling either.
+    pub fn is_closed(&self, now: NaiveDateTime) -> bool {
+        matches!(self.is_open(now), Ok(false))
+    }
+
+    /// The next moment the window opens, or `now` if it's already open.
+    pub fn next_open(&self, now: NaiveDateTime) -> Result<NaiveDateTime> {
+        if self.is_open(now)? {
+            return Ok(now);
+        }
+        let (start, _) = self.window()?;
+        // A valid window opens on one of its days within the week
+        (0..=7)
+            .map(|offset| (now.date() + Duration::days(offset)).and_time(start))
+            .find(|at| *at > now && matches!(self.is_open(*at), Ok(true)))
+            .context("working hours never open")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    // 2026-03-02 is a Monday
+    fn at(day: u32, h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn weekday_office_hours() {
+        let hours = WorkingHours::default();
+        assert!(hours.is_open(at(2, 10, 0)).unwrap());
+        assert!(!hours.is_open(at(2, 18, 0)).unwrap());
+        assert!(!hours.is_open(at(7, 10, 0)).unwrap()); // Saturday
+    }
+
+    #[test]
+    fn next_open_skips_weekend() {
+        let hours = WorkingHours::default();
+        assert_eq!(hours.next_open(at(6, 20, 0)).unwrap(), at(9, 9, 0)); // Fri evening → Mon
+        assert_eq!(hours.next_open(at(3, 11, 0)).unwrap(), at(3, 11, 0));
+    }
+
+    #[test]
+    fn overnight_window_wraps() {
+        let hours = WorkingHours {
+            days: vec![1],
+            start: "22:00".to_string(),
+            end: "06:00".to_string(),
+        };
+        assert!(hours.is_open(at(2, 23, 0)).unwrap());
+        assert!(hours.is_open(at(3, 5, 0)).unwrap()); // Tuesday morning, Monday's window
+        assert!(!hours.is_open(at(2, 5, 0)).unwrap());
+        assert_eq!(hours.next_open(at(3, 12, 0)).unwrap(), at(9, 22, 0));
+    }
+
+    #[test]
+    fn malformed_windows_are_errors() {
+        let no_days = WorkingHours { days: vec![], ..WorkingHours::default() };
+        assert!(no_days.next_open(at(2, 10, 0)).is_err());
+        assert!(!no_days.is_closed(at(2, 10, 0)));
+        let bad_time = WorkingHours { start: "9am".to_string(), ..WorkingHours::default() };
+        assert!(bad_time.validate().unwrap_err().to_string().contains("9am"));
+        let bad_day = WorkingHours { days: vec![0], ..WorkingHours::default() };
+        assert!(bad_day.validate().is_err());
+        assert!(WorkingHours::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,19 @@
+pub mod audit;
+pub mod audit_log;
+pub mod bulk;
+pub mod cache;
+pub mod calendar;
+pub mod deeplink;
+pub mod deps;
+pub mod editor;
+pub mod flags;
+pub mod health;
+pub mod hours;
+pub mod onboarding;
+pub mod qa;
+pub mod settings;
+pub mod store;
+pub mod sync;
+pub mod telemetry;
+pub mod templates;
+pub mod tickets;
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::process::Command;
+use ts_rs::TS;
+
+use super::store;
+use crate::github::gh;
+use crate::platform::exec;
+
+/// First-run setup, in the order the wizard walks it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    DetectClaude,
+    ConnectGithub,
+    ScanProject,
+    CreateAgent,
+    Done,
+}
+
+const STEPS: [OnboardingStep; 4] = [
+    OnboardingStep::DetectClaude,
+    OnboardingStep::ConnectGithub,
+    OnboardingStep::ScanProject,
+    OnboardingStep::CreateAgent,
+];
+
+/// What the user has done that the environment can't show, at
+/// `$HOME/.poietai/onboarding.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+struct Progress {
+    /// Steps finished outside our view (a vault token saved). Holding
+    /// `Done` means the wizard was finished.
+    marked: BTreeSet<OnboardingStep>,
+    /// Steps the user chose to leave for later.
+    skipped: BTreeSet<OnboardingStep>,
+    project_scanned: bool,
+}
+
+/// What's actually set up on this machine.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Environment {
+    pub claude_version: Option<String>,
+    pub gh_authed: bool,
+    pub agents: usize,
+}
+
+/// Payload of `get_onboarding_state` and `onboarding-state-changed`.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct OnboardingState {
+    /// The first step neither done nor skipped.
+    pub step: OnboardingStep,
+    pub claude_version: Option<String>,
+    pub gh_authed: bool,
+    pub project_scanned: bool,
+    pub agent_created: bool,
+    pub skipped: Vec<OnboardingStep>,
+}
+
+fn path() -> PathBuf {
+    store::app_dir().join("onboarding.json")
+}
+
+/// Probe for the claude and gh CLIs. Blocking.
+pub fn probe(agents: usize) -> Environment {
+    let claude_version =
+        exec::output_blocking(Command::new("claude").arg("--version"), exec::LOCAL)
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    let gh_authed = gh::run_blocking(&["auth", "status"], None).is_ok();
+    Environment {
+        claude_version,
+        gh_authed,
+        agents,
+    }
+}
+
+fn resolve(env: &Environment, progress: &Progress) -> OnboardingState {
+    let observed = |step: OnboardingStep| match step {
+        OnboardingStep::DetectClaude => env.claude_version.is_some(),
+        OnboardingStep::ConnectGithub => env.gh_authed,
+        OnboardingStep::ScanProject => progress.project_scanned,
+        OnboardingStep::CreateAgent => env.agents > 0,
+        OnboardingStep::Done => progress.marked.contains(&OnboardingStep::Done),
+    };
+    let step = if observed(OnboardingStep::Done) {
+        OnboardingStep::Done
+    } else {
+        STEPS
+            .into_iter()
+            .find(|s| {
+                !observed(*s) && !progress.marked.contains(s) && !progress.skipped.contains(s)
+            })
+            .unwrap_or(OnboardingStep::Done)
+    };
+    OnboardingState {
+        step,
+        claude_version: env.claude_version.clone(),
+        gh_authed: env.gh_authed || progress.marked.contains(&OnboardingStep::ConnectGithub),
+        project_scanned: progress.project_scanned,
+        agent_created: env.agents > 0,
+        skipped: STEPS
+            .into_iter()
+            .filter(|s| {
+                !observed(*s) && !progress.marked.contains(s) && progress.skipped.contains(s)
+            })
+            .collect(),
+    }
+}
+
+/// Onboarding as of `env`.
+pub fn state(env: &Environment) -> Result<OnboardingState> {
+    Ok(resolve(env, &store::read_json(&path())?))
+}
+
+/// Count `step` as done — for steps finished where the backend can't see,
+/// and `Done` when the user leaves the wizard.
+pub fn mark(step: OnboardingStep) -> Result<()> {
+    let mut progress: Progress = store::read_json(&path())?;
+    if progress.marked.insert(step) {
+        store::write_json(&path(), &progress)?;
+    }
+    Ok(())
+}
+
+/// Move past `step` without counting it as done.
+pub fn skip(step: OnboardingStep) -> Result<()> {
+    let mut progress: Progress = store::read_json(&path())?;
+    if progress.skipped.insert(step) {
+        store::write_json(&path(), &progress)?;
+    }
+    Ok(())
+}
+
+/// Note that a folder has been scanned. Returns whether this was the first.
+pub fn project_scanned() -> Result<bool> {
+    let mut progress: Progress = store::read_json(&path())?;
+    if progress.project_scanned {
+        return Ok(false);
+    }
+    progress.project_scanned = true;
+    store::write_json(&path(), &progress)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walks_steps_in_order_from_what_the_machine_shows() {
+        let mut env = Environment::default();
+        let mut progress = Progress::default();
+        assert_eq!(resolve(&env, &progress).step, OnboardingStep::DetectClaude);
+
+        env.claude_version = Some("2.1.0 (Claude Code)".to_string());
+        assert_eq!(resolve(&env, &progress).step, OnboardingStep::ConnectGithub);
+
+        // A vault token counts as connected even though gh itself isn't authed
+        progress.marked.insert(OnboardingStep::ConnectGithub);
+        let state = resolve(&env, &progress);
+        assert_eq!(state.step, OnboardingStep::ScanProject);
+        assert!(state.gh_authed);
+
+        progress.project_scanned = true;
+        env.agents = 1;
+        assert_eq!(resolve(&env, &progress).step, OnboardingStep::Done);
+    }
+
+    #[test]
+    fn skipped_steps_are_reported_until_done_for_real() {
+        let env = Environment {
+            claude_version: Some("2.1.0".to_string()),
+            gh_authed: true,
+            agents: 0,
+        };
+        let mut progress = Progress::default();
+        progress.skipped.insert(OnboardingStep::ScanProject);
+        let state = resolve(&env, &progress);
+        assert_eq!(state.step, OnboardingStep::CreateAgent);
+        assert_eq!(state.skipped, [OnboardingStep::ScanProject]);
+
+        progress.marked.insert(OnboardingStep::Done);
+        assert_eq!(resolve(&env, &progress).step, OnboardingStep::Done);
+    }
+
+    #[test]
+    fn skipping_github_does_not_count_as_connected() {
+        let env = Environment {
+            claude_version: Some("2.1.0".to_string()),
+            ..Environment::default()
+        };
+        let mut progress = Progress::default();
+        progress.skipped.insert(OnboardingStep::ConnectGithub);
+        let state = resolve(&env, &progress);
+        assert_eq!(state.step, OnboardingStep::ScanProject);
+        assert!(!state.gh_authed);
+        assert_eq!(state.skipped, [OnboardingStep::ConnectGithub]);
+    }
+}
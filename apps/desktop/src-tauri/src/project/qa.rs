@@ -0,0 +1,211 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::store;
+
+/// One question an agent asked while working a ticket, and how it was answered.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct QaEntry {
+    /// Unix seconds the question was settled.
+    #[ts(type = "number")]
+    pub at: i64,
+    pub agent_id: String,
+    pub question: String,
+    /// None when the question timed out or the app closed before a reply.
+    pub answer: Option<String>,
+    /// "human", or the id of the senior agent that answered in its place.
+    pub answered_by: Option<String>,
+    /// Root of the project that owns the ticket. None for
+    /// entries recorded before this was kept.
+    #[serde(default)]
+    pub repo_root: Option<String>,
+}
+
+/// An earlier answer to a question like the one being asked, offered so it
+/// can be reused instead of typed again.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SuggestedAnswer {
+    pub question: String,
+    pub answer: String,
+    /// Ticket the earlier question was asked on.
+    pub ticket_id: String,
+    /// Word overlap between the two questions, 0 to 1.
+    pub similarity: f32,
+}
+
+/// Questions sharing less than this share of their words aren't suggested.
+const MIN_SIMILARITY: f32 = 0.5;
+
+/// Words too common to say whether two questions are about the same thing.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "for", "from", "how",
+    "i", "if", "in", "is", "it", "me", "my", "of", "on", "or", "should", "so", "that", "the",
+    "this", "to", "use", "we", "what", "when", "which", "will", "with", "you",
+];
+
+/// `$HOME/.poietai/qa/<ticket_id>.jsonl`, oldest first.
+pub fn path_for(ticket_id: &str) -> PathBuf {
+    store::app_dir()
+        .join("qa")
+        .join(format!("{}.jsonl", crate::agent::transcript::sanitize(ticket_id)))
+}
+
+pub fn record(ticket_id: &str, entry: &QaEntry) -> Result<()> {
+    store::append_jsonl(&path_for(ticket_id), entry)
+}
+
+/// Every question asked on a ticket. Unparseable lines are skipped.
+pub fn load(ticket_id: &str) -> Result<Vec<QaEntry>> {
+    read(&path_for(ticket_id))
+}
+
+fn read(path: &Path) -> Result<Vec<QaEntry>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Answered questions as a prompt section, for agents picking up the
+/// ticket — or a related one — so they don't ask again.
+pub fn as_prompt(entries: &[QaEntry]) -> Option<String> {
+    let answered: Vec<String> = entries
+        .iter()
+        .filter_map(|e| Some(format!("- Q: {}\n  A: {}", e.question.trim(), e.answer.as_deref()?.trim())))
+        .collect();
+    if answered.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "## Questions Already Answered\n\nDon't ask these again.\n\n{}",
+        answered.join("\n")
+    ))
+}
+
+/// The distinctive words of a question, lowercased.
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| w.chars().count() > 1 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Share of the two questions' words they have in common.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// The answered question in `history` (ticket id, entry) most like
+/// `question`, if any is close enough. Ties go to the most recent.
+fn best_match(history: &[(String, QaEntry)], question: &str) -> Option<SuggestedAnswer> {
+    let asked = words(question);
+    history
+        .iter()
+        .filter_map(|(ticket_id, entry)| {
+            let answer = entry.answer.as_deref()?.trim();
+            if answer.is_empty() {
+                return None;
+            }
+            let score = similarity(&asked, &words(&entry.question));
+            (score >= MIN_SIMILARITY).then_some((score, entry.at, ticket_id, entry, answer))
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+        .map(|(similarity, _, ticket_id, entry, answer)| SuggestedAnswer {
+            question: entry.question.clone(),
+            answer: answer.to_string(),
+            ticket_id: ticket_id.clone(),
+            similarity,
+        })
+}
+
+/// An earlier answer on the project at `repo_root` to a question like
+/// `question`, across all its tickets. Blocking: reads every ticket's history.
+pub fn suggest(repo_root: &Path, question: &str) -> Result<Option<SuggestedAnswer>> {
+    let dir = store::app_dir().join("qa");
+    let files = match std::fs::read_dir(&dir) {
+        Ok(files) => files,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let repo_root = repo_root.to_string_lossy();
+    let mut history = Vec::new();
+    for file in files.flatten() {
+        let path = file.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let Some(ticket_id) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        history.extend(
+            read(&path)?
+                .into_iter()
+                .filter(|e| e.repo_root.as_deref() == Some(&*repo_root))
+                .map(|e| (ticket_id.clone(), e)),
+        );
+    }
+    Ok(best_match(&history, question))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_lists_only_answered_questions() {
+        let entry = |question: &str, answer: Option<&str>| QaEntry {
+            at: 0,
+            agent_id: "a1".to_string(),
+            question: question.to_string(),
+            answer: answer.map(String::from),
+            answered_by: answer.map(|_| "human".to_string()),
+            repo_root: None,
+        };
+        let prompt = as_prompt(&[entry("Use sqlx?", Some("Yes")), entry("Ship Friday?", None)]).unwrap();
+        assert!(prompt.contains("- Q: Use sqlx?\n  A: Yes"));
+        assert!(!prompt.contains("Friday"));
+        assert_eq!(as_prompt(&[entry("Ship Friday?", None)]), None);
+    }
+
+    #[test]
+    fn suggests_the_closest_answered_question() {
+        let entry = |at: i64, question: &str, answer: Option<&str>| {
+            let entry = QaEntry {
+                at,
+                agent_id: "a1".to_string(),
+                question: question.to_string(),
+                answer: answer.map(String::from),
+                answered_by: answer.map(|_| "human".to_string()),
+                repo_root: None,
+            };
+            ("t1".to_string(), entry)
+        };
+        let history = [
+            entry(1, "Should the retry backoff be exponential?", Some("Yes, capped at 30s")),
+            entry(2, "Should retry backoff be exponential or linear?", None),
+            entry(3, "Which database driver do we use?", Some("sqlx")),
+        ];
+
+        let hit = best_match(&history, "Is the retry backoff exponential?").unwrap();
+        assert_eq!(hit.answer, "Yes, capped at 30s");
+        assert_eq!(hit.ticket_id, "t1");
+        assert!(hit.similarity >= MIN_SIMILARITY);
+
+        assert!(best_match(&history, "What should the login page look like?").is_none());
+        assert!(best_match(&[], "Which database driver?").is_none());
+    }
+}
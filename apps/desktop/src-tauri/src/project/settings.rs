@@ -0,0 +1,223 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::cache::CacheConfig;
+use super::editor::EditorConfig;
+use super::hours::WorkingHours;
+use crate::agent::backend::ExecutionBackend;
+use crate::agent::checkpoints::Checkpoint;
+use crate::agent::coalesce::CoalesceConfig;
+use crate::agent::costs::BudgetConfig;
+use crate::agent::delegate::DelegationConfig;
+use crate::agent::filter::EventFilter;
+use crate::agent::gateway::GatewayConfig;
+use crate::agent::guard::RolePaths;
+use crate::agent::limits::ResourceLimits;
+use crate::agent::policy::DodPolicy;
+use crate::agent::risk::RiskConfig;
+use crate::agent::scheduler::SchedulerConfig;
+use crate::agent::standup::StandupConfig;
+use crate::agent::verify::VerifyConfig;
+use crate::agent::voice::TranscriptionConfig;
+use crate::git::drift::DriftConfig;
+use crate::github::ci::CiTriageConfig;
+use crate::github::projects::ProjectsSyncConfig;
+use crate::mcp::listen::McpListenConfig;
+use crate::mcp::relay::RelayConfig;
+use super::store;
+use super::sync::SyncConfig;
+use super::telemetry::TelemetryConfig;
+use ts_rs::TS;
+
+/// A user-defined mapping from a git host to a provider, for self-hosted
+/// instances that `detect_provider` can't recognise from the URL alone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct ProviderHost {
+    /// Hostname as it appears in remote URLs, e.g. "git.example.com".
+    pub host: String,
+    /// Provider key: "github", "gitlab", "bitbucket", or "azure".
+    pub provider: String,
+}
+
+/// App-wide settings persisted at `$HOME/.poietai/settings.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct AppSettings {
+    pub provider_hosts: Vec<ProviderHost>,
+    /// Scheduled agent check-ins; off when unset.
+    pub standup: Option<StandupConfig>,
+    /// Daily/weekly spending caps; unlimited when unset.
+    pub budget: Option<BudgetConfig>,
+    /// Editor for "open in editor"; VS Code when unset.
+    pub editor: Option<EditorConfig>,
+    /// Merge bursts of Text/Thinking events into one node; off when unset.
+    pub coalesce_events: Option<CoalesceConfig>,
+    /// Event kinds to keep off the canvas and out of transcripts.
+    pub event_filter: EventFilter,
+    /// Concurrent ticket runs, queued by priority; unlimited when unset.
+    pub scheduler: Option<SchedulerConfig>,
+    /// Anonymous feature usage counts; off when unset.
+    pub telemetry: Option<TelemetryConfig>,
+    /// Turns voice-memo answers into text; voice answers off when unset.
+    pub transcription: Option<TranscriptionConfig>,
+    /// Push agent questions to a phone with reply links; off when unset.
+    pub relay: Option<RelayConfig>,
+    /// Share roster, boards and transcripts with other machines; off when unset.
+    pub sync: Option<SyncConfig>,
+    /// Where the MCP server listens; an OS-assigned loopback port by default.
+    pub mcp: McpListenConfig,
+}
+
+/// Connection details for a (possibly self-hosted) GitLab instance.
+/// The token itself lives in the vault and is passed in per run.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GitLabConfig {
+    /// Base URL of the instance, e.g. "https://git.example.com".
+    pub base_url: String,
+}
+
+impl GitLabConfig {
+    /// The bare hostname, as `glab` expects in GITLAB_HOST.
+    pub fn host(&self) -> &str {
+        let without_scheme = self
+            .base_url
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(&self.base_url);
+        without_scheme.split('/').next().unwrap_or(without_scheme)
+    }
+}
+
+/// How to prepare a fresh worktree before the agent starts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct BootstrapConfig {
+    /// Shell command run in the worktree, e.g. "pnpm install --frozen-lockfile".
+    pub command: String,
+    /// Share one pnpm content store across all worktrees of the project.
+    pub share_pnpm_store: bool,
+    /// Symlink the worktree's `target/` to the main checkout's, so cargo reuses
+    /// built dependencies. Concurrent builds will block on cargo's lock.
+    pub link_target_dir: bool,
+}
+
+/// Per-project settings persisted at `<project_root>/.poietai/settings.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct ProjectSettings {
+    pub gitlab: Option<GitLabConfig>,
+    /// Always work from a fork, even when the token can push upstream.
+    pub fork_mode: bool,
+    /// Leave submodules uninitialised in new worktrees (e.g. huge vendored repos).
+    pub skip_submodule_init: bool,
+    pub bootstrap: Option<BootstrapConfig>,
+    pub cache: CacheConfig,
+    /// Where agents run: on the host, or in the project's dev environment.
+    pub execution: ExecutionBackend,
+    /// CPU/memory caps for agent runs (host and Docker backends).
+    pub limits: ResourceLimits,
+    /// Let a senior agent try ask_human questions before they reach the user.
+    pub delegate_questions: Option<DelegationConfig>,
+    /// Defer runs and slow PR polling outside these hours; always on when unset.
+    pub working_hours: Option<WorkingHours>,
+    /// Check acceptance criteria after a successful build; off when unset.
+    pub verify_acceptance: Option<VerifyConfig>,
+    /// Rules every build must meet, stated to the agent and checked after.
+    pub definition_of_done: Option<DodPolicy>,
+    /// Feed failing CI logs back to the agent; off when unset.
+    pub ci_triage: Option<CiTriageConfig>,
+    /// GitHub Projects board mirroring ticket status; off when unset.
+    pub github_project: Option<ProjectsSyncConfig>,
+    /// Per-role path allow/deny globs, enforced before each write, Bash included.
+    pub role_paths: Option<RolePaths>,
+    /// Paths no agent may write without a human's approval, e.g. `/.github/workflows`.
+    pub protected_paths: Vec<String>,
+    /// Hold agents' `gh pr create` until the user approves the PR.
+    pub require_pr_approval: bool,
+    /// Tool calls that pause the run until the user signs off.
+    pub checkpoints: Vec<Checkpoint>,
+    /// Route agents' model traffic through this gateway; direct when unset.
+    pub gateway: Option<GatewayConfig>,
+    /// Critical paths and other inputs to agent diff risk scores.
+    pub risk: RiskConfig,
+    /// How far behind origin a worktree may fall before `worktree-stale`.
+    pub drift: DriftConfig,
+    /// WSL distro agents run in on Windows; the one holding the repo when unset.
+    pub wsl_distro: Option<String>,
+}
+
+pub fn app_settings_path() -> PathBuf {
+    store::app_dir().join("settings.json")
+}
+
+pub fn project_settings_path(project_root: &Path) -> PathBuf {
+    store::project_dir(project_root).join("settings.json")
+}
+
+pub fn load_app() -> Result<AppSettings> {
+    store::read_json(&app_settings_path())
+}
+
+pub fn save_app(settings: &AppSettings) -> Result<()> {
+    store::write_json(&app_settings_path(), settings)
+}
+
+pub fn load_project(project_root: &Path) -> Result<ProjectSettings> {
+    store::read_json(&project_settings_path(project_root))
+}
+
+pub fn save_project(project_root: &Path, settings: &ProjectSettings) -> Result<()> {
+    store::write_json(&project_settings_path(project_root), settings)
+}
+
+/// Environment variables that point `glab` at the project's GitLab instance.
+/// Empty when the project has no GitLab config or no token was supplied.
+pub fn gitlab_env(settings: &ProjectSettings, token: Option<&str>) -> Vec<(String, String)> {
+    match (&settings.gitlab, token) {
+        (Some(gitlab), Some(token)) if !token.is_empty() => vec![
+            ("GITLAB_HOST".to_string(), gitlab.host().to_string()),
+            ("GITLAB_TOKEN".to_string(), token.to_string()),
+        ],
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gitlab_host_strips_scheme_and_path() {
+        let config = GitLabConfig {
+            base_url: "https://git.example.com/gitlab/".to_string(),
+        };
+        assert_eq!(config.host(), "git.example.com");
+    }
+
+    #[test]
+    fn gitlab_env_requires_config_and_token() {
+        let mut settings = ProjectSettings::default();
+        assert!(gitlab_env(&settings, Some("tok")).is_empty());
+
+        settings.gitlab = Some(GitLabConfig {
+            base_url: "https://git.example.com".to_string(),
+        });
+        assert!(gitlab_env(&settings, None).is_empty());
+
+        let env = gitlab_env(&settings, Some("tok"));
+        assert!(env.contains(&("GITLAB_HOST".to_string(), "git.example.com".to_string())));
+        assert!(env.contains(&("GITLAB_TOKEN".to_string(), "tok".to_string())));
+    }
+
+    #[test]
+    fn settings_tolerate_missing_fields() {
+        let settings: ProjectSettings = serde_json::from_str("{}").unwrap();
+        assert!(settings.gitlab.is_none());
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The app-wide data directory: `$HOME/.poietai/`.
+/// Falls back to /tmp when HOME is unset (e.g. stripped GUI environments).
+pub fn app_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home).join(".poietai")
+}
+
+/// The project-scoped data directory: `<project_root>/.poietai/`.
+pub fn project_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".poietai")
+}
+
+/// Read a JSON file, returning `T::default()` if it doesn't exist yet.
+pub fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Write a string to `path` atomically.
+/// Creates the parent directory if needed and uses write-to-tmp + rename to
+/// prevent corruption on crash.
+pub fn write_atomic(path: &Path, data: &str) -> Result<()> {
+    let dir = path
+        .parent()
+        .with_context(|| format!("{} has no parent directory", path.display()))?;
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = dir.join(format!("{}.tmp", file_name));
+
+    let mut file = std::fs::File::create(&tmp).context("failed to create tmp file")?;
+    file.write_all(data.as_bytes())
+        .context("failed to write tmp file")?;
+    file.sync_all().context("failed to sync tmp file")?;
+    drop(file);
+
+    std::fs::rename(&tmp, path).context("failed to rename tmp → target")?;
+    Ok(())
+}
+
+/// Serialize `value` as pretty JSON and write it atomically.
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let data = serde_json::to_string_pretty(value).context("failed to serialize JSON")?;
+    write_atomic(path, &data)
+}
+
+/// Append `value` as one JSON line to `path`, creating the file if needed.
+/// For append-only logs where rewriting the whole file on each entry would be wasteful.
+pub fn append_jsonl<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let line = serde_json::to_string(value).context("failed to serialize JSON")?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    writeln!(file, "{}", line).with_context(|| format!("failed to append to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_file_reads_as_default() {
+        let path = std::env::temp_dir().join(format!("poietai-missing-{}.json", uuid::Uuid::new_v4()));
+        let value: Vec<String> = read_json(&path).unwrap();
+        assert!(value.is_empty());
+    }
+
+    #[test]
+    fn write_then_read_round_trips() {
+        let dir = std::env::temp_dir().join(format!("poietai-store-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("nested").join("data.json");
+        write_json(&path, &vec!["a".to_string(), "b".to_string()]).unwrap();
+        let value: Vec<String> = read_json(&path).unwrap();
+        assert_eq!(value, vec!["a", "b"]);
+        assert!(!dir.join("nested").join("data.json.tmp").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn append_jsonl_adds_lines() {
+        let dir = std::env::temp_dir().join(format!("poietai-store-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("log.jsonl");
+        append_jsonl(&path, &"one").unwrap();
+        append_jsonl(&path, &"two").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "\"one\"\n\"two\"\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+use super::store;
+use crate::agent::state::AgentState;
+use crate::agent::transcript::{self, sanitize, TranscriptEntry};
+use crate::git::scan;
+use crate::platform::exec;
+
+/// A folder every machine sees (Syncthing, Dropbox, a network mount) that
+/// carries each machine's roster, boards and transcripts to the others.
+/// Each machine only writes under its own id, so files never conflict; the
+/// frontend folds other machines' boards into its own by ticket.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub folder: String,
+    /// How the other machines label this one; the hostname when empty.
+    pub machine_name: String,
+}
+
+/// A project's board as one machine last saved it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+pub struct SyncedProject {
+    /// The origin remote URL, or the folder name for repos without one, so
+    /// both machines' clones of a repo share a key.
+    pub key: String,
+    /// The project's `.poietai/tickets.json` as the frontend wrote it.
+    #[ts(type = "unknown")]
+    pub tickets: serde_json::Value,
+}
+
+/// What one machine last published.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MachineSnapshot {
+    pub machine_id: String,
+    pub machine_name: String,
+    /// Unix seconds.
+    #[ts(type = "number")]
+    pub updated_at: i64,
+    /// The roster, with whatever each agent is running there.
+    pub agents: Vec<AgentState>,
+    pub projects: Vec<SyncedProject>,
+}
+
+/// This install's id, generated on first use at `$HOME/.poietai/machine-id`.
+pub fn machine_id() -> Result<String> {
+    let path = store::app_dir().join("machine-id");
+    match std::fs::read_to_string(&path) {
+        Ok(id) if !id.trim().is_empty() => return Ok(id.trim().to_string()),
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    store::write_atomic(&path, &id)?;
+    Ok(id)
+}
+
+fn hostname() -> String {
+    exec::output_blocking(&mut std::process::Command::new("hostname"), exec::LOCAL)
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn snapshot_path(folder: &Path, machine_id: &str) -> PathBuf {
+    folder.join("machines").join(format!("{}.json", sanitize(machine_id)))
+}
+
+fn transcripts_dir(folder: &Path, machine_id: &str) -> PathBuf {
+    folder.join("transcripts").join(sanitize(machine_id))
+}
+
+fn project_key(project_root: &Path) -> String {
+    scan::get_remote_url(project_root).unwrap_or_else(|| {
+        project_root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Bring the copy of `source` at `target` up to date. Transcripts only grow,
+/// so a shorter copy gets just the new tail; a longer one means the source
+/// was replaced, and is copied over whole.
+fn sync_file(source: &Path, target: &Path, copied_len: Option<u64>, source_len: u64) -> Result<()> {
+    let Some(copied_len) = copied_len.filter(|len| *len < source_len) else {
+        std::fs::copy(source, target).with_context(|| format!("failed to copy {}", source.display()))?;
+        return Ok(());
+    };
+    let mut from = std::fs::File::open(source).with_context(|| format!("failed to open {}", source.display()))?;
+    from.seek(SeekFrom::Start(copied_len))
+        .with_context(|| format!("failed to read {}", source.display()))?;
+    let mut to = std::fs::OpenOptions::new()
+        .append(true)
+        .open(target)
+        .with_context(|| format!("failed to open {}", target.display()))?;
+    std::io::copy(&mut from.take(source_len - copied_len), &mut to)
+        .with_context(|| format!("failed to append to {}", target.display()))?;
+    Ok(())
+}
+
+/// Bring `<ticket>/<agent>.jsonl` transcripts in `to` up to date with `from`
+/// where their sizes differ. Returns how many files were written.
+fn mirror(from: &Path, to: &Path) -> Result<usize> {
+    let Ok(tickets) = std::fs::read_dir(from) else {
+        return Ok(0);
+    };
+    let mut copied = 0;
+    for ticket in tickets.filter_map(|e| e.ok()).filter(|e| e.path().is_dir()) {
+        for file in std::fs::read_dir(ticket.path())?.filter_map(|e| e.ok()) {
+            let source = file.path();
+            if source.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+            let target = to.join(ticket.file_name()).join(file.file_name());
+            let len = |p: &Path| std::fs::metadata(p).map(|m| m.len()).ok();
+            let (Some(source_len), copied_len) = (len(&source), len(&target)) else {
+                continue;
+            };
+            if copied_len == Some(source_len) {
+                continue;
+            }
+            if let Some(dir) = target.parent() {
+                std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+            }
+            sync_file(&source, &target, copied_len, source_len)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// Write this machine's roster, the given projects' boards and its
+/// transcripts to the sync folder. Blocking.
+pub fn publish(config: &SyncConfig, agents: Vec<AgentState>, project_roots: &[PathBuf]) -> Result<()> {
+    let folder = Path::new(&config.folder);
+    if !folder.is_dir() {
+        anyhow::bail!("sync folder {} doesn't exist", folder.display());
+    }
+    let id = machine_id()?;
+    let projects = project_roots
+        .iter()
+        .map(|root| {
+            let tickets = store::read_json(&store::project_dir(root).join("tickets.json"))?;
+            Ok(SyncedProject {
+                key: project_key(root),
+                tickets,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let snapshot = MachineSnapshot {
+        machine_id: id.clone(),
+        machine_name: if config.machine_name.trim().is_empty() {
+            hostname()
+        } else {
+            config.machine_name.trim().to_string()
+        },
+        updated_at: chrono::Utc::now().timestamp(),
+        agents,
+        projects,
+    };
+    store::write_json(&snapshot_path(folder, &id), &snapshot)?;
+    mirror(&store::app_dir().join("transcripts"), &transcripts_dir(folder, &id))?;
+    Ok(())
+}
+
+fn read_peers(folder: &Path, own_id: &str) -> Result<Vec<MachineSnapshot>> {
+    let dir = folder.join("machines");
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let mut peers: Vec<MachineSnapshot> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| match store::read_json::<Option<MachineSnapshot>>(&p) {
+            Ok(snapshot) => snapshot,
+            // A sync tool may be midway through writing it
+            Err(e) => {
+                warn!("[sync::peers] skipping {}: {:#}", p.display(), e);
+                None
+            }
+        })
+        .filter(|s| s.machine_id != own_id)
+        .collect();
+    peers.sort_by(|a, b| a.machine_name.cmp(&b.machine_name));
+    Ok(peers)
+}
+
+/// What every other machine last published, by name. Blocking.
+pub fn peers(config: &SyncConfig) -> Result<Vec<MachineSnapshot>> {
+    read_peers(Path::new(&config.folder), &machine_id()?)
+}
+
+/// Every ticket the other machines have on their boards for the project at
+/// `project_root`. A ticket on several boards appears once per board.
+pub fn board_tickets(peers: &[MachineSnapshot], project_root: &Path) -> Vec<serde_json::Value> {
+    let key = project_key(project_root);
+    peers
+        .iter()
+        .flat_map(|peer| &peer.projects)
+        .filter(|project| project.key == key)
+        .filter_map(|project| project.tickets.get("tickets")?.as_array())
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+/// An agent's transcript on a ticket as another machine last published it.
+pub fn peer_transcript(
+    config: &SyncConfig,
+    machine_id: &str,
+    agent_id: &str,
+    ticket_id: &str,
+) -> Result<Vec<TranscriptEntry>> {
+    let path = transcripts_dir(Path::new(&config.folder), machine_id)
+        .join(sanitize(ticket_id))
+        .join(format!("{}.jsonl", sanitize(agent_id)));
+    transcript::load_from(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("poietai-sync-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn mirror_copies_only_changed_transcripts() {
+        let dir = temp_dir();
+        let (from, to) = (dir.join("from"), dir.join("to"));
+        std::fs::create_dir_all(from.join("t-1")).unwrap();
+        std::fs::write(from.join("t-1").join("a.jsonl"), "{}\n").unwrap();
+        std::fs::write(from.join("t-1").join("notes.txt"), "skip").unwrap();
+
+        assert_eq!(mirror(&from, &to).unwrap(), 1);
+        assert_eq!(mirror(&from, &to).unwrap(), 0);
+        std::fs::write(from.join("t-1").join("a.jsonl"), "{}\n{}\n").unwrap();
+        assert_eq!(mirror(&from, &to).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(to.join("t-1").join("a.jsonl")).unwrap(), "{}\n{}\n");
+        // A replaced transcript is copied whole rather than appended to
+        std::fs::write(from.join("t-1").join("a.jsonl"), "[]\n").unwrap();
+        assert_eq!(mirror(&from, &to).unwrap(), 1);
+        assert_eq!(std::fs::read_to_string(to.join("t-1").join("a.jsonl")).unwrap(), "[]\n");
+        assert!(!to.join("t-1").join("notes.txt").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn peers_leave_out_this_machine() {
+        let dir = temp_dir();
+        for (id, name) in [("me", "desktop"), ("other", "laptop")] {
+            let snapshot = MachineSnapshot {
+                machine_id: id.to_string(),
+                machine_name: name.to_string(),
+                updated_at: 0,
+                agents: vec![],
+                projects: vec![],
+            };
+            store::write_json(&snapshot_path(&dir, id), &snapshot).unwrap();
+        }
+        std::fs::write(dir.join("machines").join("partial.json"), "{\"machine_").unwrap();
+
+        let peers = read_peers(&dir, "me").unwrap();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].machine_name, "laptop");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn board_tickets_come_from_the_same_project() {
+        let dir = temp_dir();
+        let root = dir.join("poietai");
+        let peer = MachineSnapshot {
+            machine_id: "laptop".to_string(),
+            machine_name: "laptop".to_string(),
+            updated_at: 0,
+            agents: vec![],
+            projects: vec![
+                SyncedProject {
+                    key: "poietai".to_string(),
+                    tickets: serde_json::json!({ "tickets": [{ "id": "t-1" }, { "id": "t-2" }] }),
+                },
+                SyncedProject {
+                    key: "other".to_string(),
+                    tickets: serde_json::json!({ "tickets": [{ "id": "t-9" }] }),
+                },
+            ],
+        };
+        let ids: Vec<_> = board_tickets(&[peer], &root).iter().map(|t| t["id"].clone()).collect();
+        assert_eq!(ids, ["t-1", "t-2"]);
+    }
+}
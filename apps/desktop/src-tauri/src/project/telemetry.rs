@@ -0,0 +1,221 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use ts_rs::TS;
+
+use super::{settings, store};
+
+/// How often a running app offers finished days to the upload URL.
+const UPLOAD_INTERVAL_SECS: u64 = 24 * 3600;
+
+/// Opt-in usage counting. Nothing is recorded while this is unset.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// Where to POST daily aggregates; counts stay local when unset.
+    pub upload_url: Option<String>,
+}
+
+/// A feature use worth counting. Only the kind is kept — never agents,
+/// tickets, repos or text.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    RunStarted,
+    RunResumed,
+    QuestionAnswered,
+}
+
+/// Everything telemetry keeps, at `$HOME/.poietai/metrics/telemetry.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(default)]
+pub struct Usage {
+    /// Random, generated on opt-in, tying uploads from one install together.
+    pub install_id: String,
+    /// Feature counts per local day (`YYYY-MM-DD`).
+    pub days: BTreeMap<String, BTreeMap<Feature, u32>>,
+    /// Last day already uploaded.
+    pub uploaded_through: Option<String>,
+}
+
+/// One day's counts, as uploaded.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct DayUsage {
+    pub date: String,
+    pub counts: BTreeMap<Feature, u32>,
+}
+
+/// What `get_telemetry` shows: the stored data and the next upload, verbatim.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+pub struct TelemetryReport {
+    pub enabled: bool,
+    pub upload_url: Option<String>,
+    pub usage: Usage,
+    pub pending_upload: Vec<DayUsage>,
+}
+
+static USAGE: Mutex<()> = Mutex::new(());
+
+fn path() -> PathBuf {
+    store::app_dir().join("metrics").join("telemetry.json")
+}
+
+fn config() -> Option<TelemetryConfig> {
+    settings::load_app().ok().and_then(|s| s.telemetry)
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+impl Usage {
+    fn bump(&mut self, day: &str, feature: Feature) {
+        if self.install_id.is_empty() {
+            self.install_id = uuid::Uuid::new_v4().to_string();
+        }
+        *self.days.entry(day.to_string()).or_default().entry(feature).or_default() += 1;
+    }
+
+    /// Finished days not uploaded yet. Today is still counting.
+    fn pending(&self, today: &str) -> Vec<DayUsage> {
+        self.days
+            .iter()
+            .filter(|(date, _)| date.as_str() < today)
+            .filter(|(date, _)| self.uploaded_through.as_ref().is_none_or(|u| *date > u))
+            .map(|(date, counts)| DayUsage {
+                date: date.clone(),
+                counts: counts.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Count one use of `feature`, if the user opted in. The file work runs on
+/// the blocking pool, so async commands can call this directly.
+pub fn record(feature: Feature) {
+    tauri::async_runtime::spawn_blocking(move || {
+        if config().is_none() {
+            return;
+        }
+        let _guard = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+        let result = store::read_json::<Usage>(&path()).and_then(|mut usage| {
+            usage.bump(&today(), feature);
+            store::write_json(&path(), &usage)
+        });
+        if let Err(e) = result {
+            warn!("[telemetry::record] failed to record usage: {:#}", e);
+        }
+    });
+}
+
+pub fn report() -> Result<TelemetryReport> {
+    let config = config();
+    let usage: Usage = store::read_json(&path())?;
+    Ok(TelemetryReport {
+        enabled: config.is_some(),
+        upload_url: config.and_then(|c| c.upload_url),
+        pending_upload: usage.pending(&today()),
+        usage,
+    })
+}
+
+/// Forget everything recorded, including the install id.
+pub fn clear() -> Result<()> {
+    let _guard = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+    match std::fs::remove_file(path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).context("failed to delete telemetry")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// POST finished days to the configured URL. Blocking. The lock is only
+/// held to read and to mark days uploaded, not across the request.
+fn upload() -> Result<()> {
+    let Some(url) = config().and_then(|c| c.upload_url) else {
+        return Ok(());
+    };
+    let usage: Usage = {
+        let _guard = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+        store::read_json(&path())?
+    };
+    let pending = usage.pending(&today());
+    let Some(last) = pending.last().map(|d| d.date.clone()) else {
+        return Ok(());
+    };
+    let body = serde_json::json!({ "install_id": usage.install_id, "days": pending });
+
+    let mut child = Command::new("curl")
+        .args(["-sSf", "--max-time", "30", "-H", "Content-Type: application/json", "--data-binary", "@-", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.to_string().as_bytes()).context("failed to send telemetry")?;
+    }
+    let output = child.wait_with_output().context("failed to run curl")?;
+    if !output.status.success() {
+        anyhow::bail!("upload failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    info!("[telemetry::upload] uploaded {} days", pending.len());
+
+    let _guard = USAGE.lock().unwrap_or_else(|e| e.into_inner());
+    // Counts recorded meanwhile stay; a clear meanwhile means nothing to mark
+    if !path().exists() {
+        return Ok(());
+    }
+    let mut usage: Usage = store::read_json(&path())?;
+    if usage.uploaded_through.as_ref().is_none_or(|u| *u < last) {
+        usage.uploaded_through = Some(last);
+    }
+    store::write_json(&path(), &usage)
+}
+
+/// Upload finished days once a day for the app's lifetime.
+pub async fn schedule() {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(UPLOAD_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        match tokio::task::spawn_blocking(upload).await {
+            Ok(Err(e)) => warn!("[telemetry::schedule] {:#}", e),
+            Err(e) => warn!("[telemetry::schedule] upload task panicked: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_skips_today_and_uploaded_days() {
+        let mut usage = Usage::default();
+        usage.bump("2026-03-01", Feature::RunStarted);
+        usage.bump("2026-03-02", Feature::RunStarted);
+        usage.bump("2026-03-02", Feature::RunStarted);
+        usage.bump("2026-03-02", Feature::QuestionAnswered);
+        usage.bump("2026-03-03", Feature::RunResumed);
+        assert!(!usage.install_id.is_empty());
+        usage.uploaded_through = Some("2026-03-01".to_string());
+
+        let pending = usage.pending("2026-03-03");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].date, "2026-03-02");
+        assert_eq!(pending[0].counts[&Feature::RunStarted], 2);
+        assert_eq!(pending[0].counts[&Feature::QuestionAnswered], 1);
+    }
+}
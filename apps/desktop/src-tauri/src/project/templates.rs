@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Starting points for a new ticket.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketTemplate {
+    Bugfix,
+    Feature,
+    Refactor,
+    Spike,
+}
+
+/// A ticket ready for the board, shaped like the frontend's `addTicket` input.
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketDraft {
+    pub title: String,
+    pub description: String,
+    pub complexity: u32,
+    pub acceptance_criteria: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl TicketTemplate {
+    /// Description scaffold, with headings for the author to fill in.
+    fn scaffold(self) -> &'static str {
+        match self {
+            TicketTemplate::Bugfix => "## What happens\n\n\n## What should happen\n\n\n## Steps to reproduce\n1. \n",
+            TicketTemplate::Feature => "## Problem\n\n\n## Proposed behavior\n\n\n## Out of scope\n",
+            TicketTemplate::Refactor => "## Current shape\n\n\n## Target shape\n\n\n## Why now\n",
+            TicketTemplate::Spike => "## Question\n\n\n## Options to evaluate\n\n\n## Timebox\n",
+        }
+    }
+
+    fn criteria(self) -> &'static [&'static str] {
+        match self {
+            TicketTemplate::Bugfix => &[
+                "A regression test reproduces the bug and passes with the fix",
+                "Root cause is explained in the PR description",
+                "Existing tests pass",
+            ],
+            TicketTemplate::Feature => &[
+                "New behavior is covered by tests",
+                "User-facing changes are documented",
+                "Existing tests pass",
+            ],
+            TicketTemplate::Refactor => &[
+                "No change in observable behavior",
+                "Existing tests pass without modification",
+            ],
+            TicketTemplate::Spike => &[
+                "Findings and a recommendation are written up in the PR or ticket",
+                "No production code is merged",
+            ],
+        }
+    }
+
+    fn complexity(self) -> u32 {
+        match self {
+            TicketTemplate::Bugfix | TicketTemplate::Spike => 3,
+            TicketTemplate::Feature | TicketTemplate::Refactor => 5,
+        }
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            TicketTemplate::Bugfix => "bug",
+            TicketTemplate::Feature => "feature",
+            TicketTemplate::Refactor => "refactor",
+            TicketTemplate::Spike => "spike",
+        }
+    }
+
+    pub fn draft(self, title: &str) -> TicketDraft {
+        TicketDraft {
+            title: title.trim().to_string(),
+            description: self.scaffold().to_string(),
+            complexity: self.complexity(),
+            acceptance_criteria: self.criteria().iter().map(|c| c.to_string()).collect(),
+            tags: vec![self.tag().to_string()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drafts_carry_the_template_defaults() {
+        let draft = TicketTemplate::Bugfix.draft("  Cart total ignores qty ");
+        assert_eq!(draft.title, "Cart total ignores qty");
+        assert!(draft.description.contains("## Steps to reproduce"));
+        assert!(draft.acceptance_criteria[0].contains("regression test"));
+        assert_eq!(draft.tags, ["bug"]);
+        let json = serde_json::to_value(&draft).unwrap();
+        assert!(json.get("acceptanceCriteria").is_some());
+    }
+}
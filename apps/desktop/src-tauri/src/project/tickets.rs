@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use super::store;
+use crate::agent::scheduler::Priority;
+
+/// The fields of a board ticket the backend reads. The frontend owns
+/// `.poietai/tickets.json` and rewrites it whole, so this side never writes it.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TicketRecord {
+    pub id: String,
+    pub number: u32,
+    pub title: String,
+    pub description: String,
+    pub complexity: u32,
+    pub status: String,
+    pub acceptance_criteria: Vec<String>,
+    pub tags: Vec<String>,
+    /// GitHub labels to put on the ticket's PR.
+    pub labels: Vec<String>,
+    pub milestone: Option<String>,
+    pub assignments: Vec<TicketAssignment>,
+    pub priority: Priority,
+    /// The issue the ticket was imported from.
+    pub source_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TicketAssignment {
+    pub agent_id: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct TicketsFile {
+    tickets: Vec<TicketRecord>,
+}
+
+pub fn load_all(project_root: &Path) -> Result<Vec<TicketRecord>> {
+    let file: TicketsFile =
+        store::read_json(&store::project_dir(project_root).join("tickets.json"))?;
+    Ok(file.tickets)
+}
+
+pub fn find(project_root: &Path, ticket_id: &str) -> Result<TicketRecord> {
+    load_all(project_root)?
+        .into_iter()
+        .find(|t| t.id == ticket_id)
+        .with_context(|| format!("ticket '{}' not found", ticket_id))
+}
+
+/// Find the project holding `ticket_id` from a repo inside it: repos of a
+/// multi-repo project sit below the project root that owns the board.
+/// Returns the project root with the ticket.
+pub fn locate(repo_root: &Path, ticket_id: &str) -> Result<(PathBuf, TicketRecord)> {
+    repo_root
+        .ancestors()
+        .find_map(|root| {
+            let ticket = load_all(root).ok()?.into_iter().find(|t| t.id == ticket_id)?;
+            Some((root.to_path_buf(), ticket))
+        })
+        .with_context(|| format!("ticket '{}' not found", ticket_id))
+}
+
+impl TicketRecord {
+    /// Title, description and acceptance criteria as prompt text.
+    pub fn as_prompt(&self) -> String {
+        let mut text = format!("# {}\n\n{}", self.title, self.description);
+        if !self.acceptance_criteria.is_empty() {
+            text.push_str("\n\nAcceptance criteria:\n");
+            for (i, criterion) in self.acceptance_criteria.iter().enumerate() {
+                text.push_str(&format!("{}. {}\n", i + 1, criterion));
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_frontend_ticket_file() {
+        let root = std::env::temp_dir().join(format!("poietai-tickets-{}", uuid::Uuid::new_v4()));
+        store::write_atomic(
+            &store::project_dir(&root).join("tickets.json"),
+            r#"{"tickets":[{"id":"t-1","number":1,"title":"Retry billing","description":"",
+               "complexity":3,"status":"backlog","assignments":[],
+               "acceptanceCriteria":["retries 5 times"],"tags":[],"phases":[],"artifacts":{}}],
+               "nextTicketNumber":2}"#,
+        )
+        .unwrap();
+        let ticket = find(&root, "t-1").unwrap();
+        assert_eq!(ticket.acceptance_criteria, vec!["retries 5 times"]);
+        assert!(ticket.as_prompt().contains("1. retries 5 times"));
+        assert!(find(&root, "t-2").is_err());
+        let (found, _) = locate(&root.join("services").join("api"), "t-1").unwrap();
+        assert_eq!(found, root);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
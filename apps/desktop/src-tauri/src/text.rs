@@ -0,0 +1,61 @@
+/// The longest prefix of `text` at most `max` bytes long that doesn't split
+/// a character.
+pub fn truncate_bytes(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// The first `max` characters of `text`.
+pub fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// `text` cut to `max` characters, with "…" when anything was cut.
+pub fn ellipsize(text: &str, max: usize) -> String {
+    let head = truncate_chars(text, max);
+    if head.len() < text.len() {
+        format!("{}…", head)
+    } else {
+        head.to_string()
+    }
+}
+
+/// Bytes from a tool as text, with anything that isn't UTF-8 replaced
+/// rather than rejected. Build tools print in the console's code page,
+/// which on Windows often isn't UTF-8.
+pub fn lossy(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_never_split_characters() {
+        // "é" is two bytes, "🦀" four
+        assert_eq!(truncate_bytes("héllo", 2), "h");
+        assert_eq!(truncate_bytes("🦀🦀", 5), "🦀");
+        assert_eq!(truncate_bytes("abc", 10), "abc");
+        assert_eq!(truncate_chars("🦀🦀🦀", 2), "🦀🦀");
+        assert_eq!(truncate_chars("日本", 5), "日本");
+        assert_eq!(ellipsize("日本語のテキスト", 3), "日本語…");
+        assert_eq!(ellipsize("short", 5), "short");
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced() {
+        // "café" in Latin-1, as a Windows build tool might print it
+        assert_eq!(lossy(b"caf\xe9".to_vec()), "caf\u{fffd}");
+        assert_eq!(lossy("ok ✓".as_bytes().to_vec()), "ok ✓");
+    }
+}
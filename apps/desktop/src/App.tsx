@@ -3,26 +3,31 @@ import { AppShell } from './components/layout/AppShell';
 import { useAgentStore } from './store/agentStore';
 import { useSecretsStore } from './store/secretsStore';
 import { useSettingsStore } from './store/settingsStore';
+import { useFeatureFlagStore } from './store/featureFlagStore';
 import { useProjectStore } from './store/projectStore';
 import { useTicketStore } from './store/ticketStore';
 import { useMessageStore } from './store/messageStore';
+import { useSyncStore } from './store/syncStore';
 
 function App() {
   const { startPolling, stopPolling, restoreAgents } = useAgentStore();
   const { loadToken } = useSecretsStore();
   const { loadSettings } = useSettingsStore();
+  const { loadFlags } = useFeatureFlagStore();
   const { loadFromDisk: loadProjects } = useProjectStore();
   const { loadFromDisk: loadTickets } = useTicketStore();
   const { loadFromDisk: loadMessages } = useMessageStore();
+  const { startSync, stopSync } = useSyncStore();
 
   useEffect(() => {
     restoreAgents().then(() => startPolling());
     loadToken();
     loadSettings();
-    // Load projects first, then project-scoped stores
-    loadProjects().then(() => { loadTickets(); loadMessages(); });
-    return () => stopPolling();
-  }, [startPolling, stopPolling, loadToken, loadSettings, restoreAgents, loadProjects, loadTickets, loadMessages]);
+    loadFlags();
+    // Load projects first, then project-scoped stores and what other machines publish
+    loadProjects().then(() => { loadTickets(); loadMessages(); startSync(); });
+    return () => { stopPolling(); stopSync(); };
+  }, [startPolling, stopPolling, startSync, stopSync, loadToken, loadSettings, loadFlags, restoreAgents, loadProjects, loadTickets, loadMessages]);
 
   return <AppShell />;
 }
@@ -18,7 +18,9 @@ function StatusIcon({ status }: { status: Agent['status'] }) {
 
 function statusLabel(agent: Agent): string {
   if (agent.status === 'idle') return 'Available';
-  if (agent.status === 'working') return 'Busy (will queue)';
+  if (agent.status === 'working') {
+    return agent.status_note ? `Busy (will queue) · ${agent.status_note}` : 'Busy (will queue)';
+  }
   return agent.status.replace(/_/g, ' ');
 }
 
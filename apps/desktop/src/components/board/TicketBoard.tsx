@@ -9,7 +9,7 @@ import {
   type DragStartEvent,
   type DragEndEvent,
 } from '@dnd-kit/core';
-import { Plus } from 'lucide-react';
+import { Download, Plus } from 'lucide-react';
 import { useTicketStore, type Ticket, type TicketStatus } from '../../store/ticketStore';
 import { TicketCard } from './TicketCard';
 import { TicketDetailPanel } from './TicketDetailPanel';
@@ -60,11 +60,13 @@ function DroppableColumn({
 }
 
 export function TicketBoard() {
-  const { tickets, updateTicketStatus } = useTicketStore();
+  const { tickets, updateTicketStatus, importGitHubIssues } = useTicketStore();
   const setSelectedTicketId = useNavigationStore((s) => s.setSelectedTicketId);
   const [activeTicket, setActiveTicket] = useState<Ticket | null>(null);
   const [showCreateModal, setShowCreateModal] = useState(false);
   const [detailTicketId, setDetailTicketId] = useState<string | null>(null);
+  const [importing, setImporting] = useState(false);
+  const [importNote, setImportNote] = useState<string | null>(null);
 
   const detailTicket = detailTicketId ? tickets.find((t) => t.id === detailTicketId) ?? null : null;
 
@@ -72,6 +74,19 @@ export function TicketBoard() {
     useSensor(PointerSensor, { activationConstraint: { distance: 5 } }),
   );
 
+  async function handleImport() {
+    setImporting(true);
+    setImportNote(null);
+    try {
+      const added = await importGitHubIssues();
+      setImportNote(added === 0 ? 'No new issues' : `Imported ${added} issue${added === 1 ? '' : 's'}`);
+    } catch (e) {
+      setImportNote(`Import failed: ${e}`);
+    } finally {
+      setImporting(false);
+    }
+  }
+
   function handleDragStart(event: DragStartEvent) {
     const ticket = event.active.data.current?.ticket as Ticket | undefined;
     setActiveTicket(ticket ?? null);
@@ -100,12 +115,22 @@ export function TicketBoard() {
           {/* Board header */}
           <div className="flex items-center justify-between px-4 pt-4 pb-2">
             <h2 className="text-zinc-300 text-sm font-semibold uppercase tracking-wider">Board</h2>
-            <button
-              onClick={() => setShowCreateModal(true)}
-              className="flex items-center gap-1.5 text-xs text-indigo-400 hover:text-indigo-300 bg-indigo-600/10 hover:bg-indigo-600/20 border border-indigo-500/30 rounded-lg px-3 py-1.5 transition-colors"
-            >
-              <Plus size={14} /> New ticket
-            </button>
+            <div className="flex items-center gap-2">
+              {importNote && <span className="text-neutral-500 text-xs">{importNote}</span>}
+              <button
+                onClick={handleImport}
+                disabled={importing}
+                className="flex items-center gap-1.5 text-xs text-zinc-400 hover:text-zinc-300 border border-zinc-700 hover:border-zinc-600 rounded-lg px-3 py-1.5 transition-colors disabled:opacity-50"
+              >
+                <Download size={14} /> {importing ? 'Importing…' : 'Import issues'}
+              </button>
+              <button
+                onClick={() => setShowCreateModal(true)}
+                className="flex items-center gap-1.5 text-xs text-indigo-400 hover:text-indigo-300 bg-indigo-600/10 hover:bg-indigo-600/20 border border-indigo-500/30 rounded-lg px-3 py-1.5 transition-colors"
+              >
+                <Plus size={14} /> New ticket
+              </button>
+            </div>
           </div>
 
           {/* Columns */}
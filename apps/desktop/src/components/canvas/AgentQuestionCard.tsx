@@ -12,12 +12,10 @@ export function AgentQuestionCard({ payload, onAnswered }: Props) {
   const [reply, setReply] = useState('');
   const [sending, setSending] = useState(false);
 
-  const handleSubmit = async (e: React.FormEvent) => {
-    e.preventDefault();
-    if (!reply.trim()) return;
+  const send = async (text: string | null) => {
     setSending(true);
     try {
-      await invoke('answer_agent', { agentId: payload.agent_id, reply: reply.trim() });
+      await invoke('answer_agent', { agentId: payload.agent_id, reply: text });
       onAnswered(payload.agent_id);
     } catch (err) {
       console.error('Failed to deliver reply:', err);
@@ -25,12 +23,37 @@ export function AgentQuestionCard({ payload, onAnswered }: Props) {
     }
   };
 
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!reply.trim()) return;
+    await send(reply.trim());
+  };
+
+  const suggestion = payload.suggested_answer;
+
   return (
     <div className="border border-violet-400 bg-violet-50 rounded-lg p-4 shadow-md">
       <p className="text-xs font-semibold text-violet-600 uppercase tracking-wide mb-1">
         Agent needs input
       </p>
       <p className="text-sm text-zinc-800 mb-3">{payload.question}</p>
+      {suggestion && (
+        <div className="border border-violet-200 bg-white rounded p-2 mb-3">
+          <p className="text-xs text-zinc-500 mb-1" title={suggestion.question}>
+            Answered before on {suggestion.ticket_id}
+          </p>
+          <p className="text-sm text-zinc-700 mb-2 whitespace-pre-wrap">{suggestion.answer}</p>
+          <button
+            type="button"
+            onClick={() => send(null)}
+            disabled={sending}
+            className="text-xs text-violet-700 border border-violet-300 px-2 py-1 rounded
+                       hover:bg-violet-100 disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            Reuse previous answer
+          </button>
+        </div>
+      )}
       <form onSubmit={handleSubmit} className="flex gap-2">
         <input
           type="text"
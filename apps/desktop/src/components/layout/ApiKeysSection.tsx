@@ -0,0 +1,138 @@
+// apps/desktop/src/components/layout/ApiKeysSection.tsx
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Plus, Trash2 } from 'lucide-react';
+import { useSecretsStore } from '../../store/secretsStore';
+import type { ApiKey } from '../../types/generated/ApiKey';
+import type { KeyUsage } from '../../types/generated/KeyUsage';
+
+const inputClass = `bg-zinc-800 border border-zinc-600 rounded-lg px-2 py-1.5 text-xs text-white
+  placeholder-zinc-500 focus:outline-none focus:border-violet-500`;
+
+/** Anthropic keys and gateways agent runs rotate among, with what each has billed. */
+export function ApiKeysSection() {
+  const { apiKeys, saveApiKeys } = useSecretsStore();
+  const [drafts, setDrafts] = useState<ApiKey[]>(apiKeys);
+  const [usage, setUsage] = useState<KeyUsage[]>([]);
+  const [saving, setSaving] = useState(false);
+  const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => setDrafts(apiKeys), [apiKeys]);
+
+  useEffect(() => {
+    invoke<KeyUsage[]>('get_api_key_usage')
+      .then(setUsage)
+      .catch((e) => console.warn('failed to load API key usage:', e));
+  }, []);
+
+  useEffect(() => {
+    if (!saved) return;
+    const id = setTimeout(() => setSaved(false), 2000);
+    return () => clearTimeout(id);
+  }, [saved]);
+
+  const update = (index: number, patch: Partial<ApiKey>) =>
+    setDrafts((keys) => keys.map((k, i) => (i === index ? { ...k, ...patch } : k)));
+
+  const ids = drafts.map((k) => k.id.trim());
+  const invalid = ids.some((id) => !id) || new Set(ids).size !== ids.length;
+
+  const handleSave = async () => {
+    setError(null);
+    setSaving(true);
+    try {
+      await saveApiKeys(
+        drafts.map((k) => ({
+          id: k.id.trim(),
+          key: k.key.trim(),
+          base_url: k.base_url?.trim() || null,
+        })),
+      );
+      setSaved(true);
+    } catch (e) {
+      console.error('failed to save API keys:', e);
+      setError('Failed to save keys. Please try again.');
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <div className="mb-4 border-t border-zinc-800 pt-4">
+      <div className="flex items-center justify-between mb-1">
+        <h3 className="text-zinc-300 text-sm font-medium">Anthropic API keys</h3>
+        <button
+          type="button"
+          onClick={() => setDrafts((keys) => [...keys, { id: '', key: '', base_url: null }])}
+          className="text-violet-400 text-xs hover:text-violet-300 flex items-center gap-1"
+        >
+          <Plus size={12} /> Add key
+        </button>
+      </div>
+      <p className="text-zinc-500 text-xs mb-3">
+        Each agent run takes the least recently used key. With none, runs use the claude CLI's own login.
+      </p>
+
+      {drafts.map((k, i) => {
+        const used = usage.find((u) => u.key_id === k.id);
+        return (
+          <div key={i} className="mb-2">
+            <div className="flex gap-2">
+              <input
+                aria-label="Key label"
+                value={k.id}
+                onChange={(e) => update(i, { id: e.target.value })}
+                placeholder="team-payments"
+                className={`${inputClass} w-28`}
+              />
+              <input
+                aria-label="API key"
+                type="password"
+                value={k.key}
+                onChange={(e) => update(i, { key: e.target.value })}
+                placeholder="sk-ant-..."
+                className={`${inputClass} flex-1 font-mono`}
+              />
+              <button
+                type="button"
+                onClick={() => setDrafts((keys) => keys.filter((_, j) => j !== i))}
+                aria-label={`Remove key ${k.id}`}
+                className="text-zinc-500 hover:text-red-400 transition-colors"
+              >
+                <Trash2 size={14} />
+              </button>
+            </div>
+            <input
+              aria-label="Gateway URL"
+              value={k.base_url ?? ''}
+              onChange={(e) => update(i, { base_url: e.target.value })}
+              placeholder="Gateway URL (optional)"
+              className={`${inputClass} w-full mt-1`}
+            />
+            {used && (
+              <p className="text-zinc-500 text-xs mt-1">
+                {used.runs} run{used.runs === 1 ? '' : 's'} · ${used.cost_usd.toFixed(2)}
+              </p>
+            )}
+          </div>
+        );
+      })}
+
+      {invalid && <p className="text-amber-400 text-xs mb-2">Every key needs a unique label.</p>}
+      {error && <p className="text-red-400 text-xs mb-2">{error}</p>}
+
+      <div className="flex justify-end">
+        <button
+          type="button"
+          onClick={handleSave}
+          disabled={saving || invalid}
+          className="text-xs bg-zinc-700 hover:bg-zinc-600 disabled:opacity-50
+                     text-white px-3 py-1.5 rounded-lg transition-colors"
+        >
+          {saved ? 'Saved!' : saving ? 'Saving…' : 'Save keys'}
+        </button>
+      </div>
+    </div>
+  );
+}
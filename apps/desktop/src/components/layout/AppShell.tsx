@@ -23,7 +23,9 @@ import { buildChatPrompt } from '../../lib/chatPromptBuilder';
 import { resolveInitiative, type InitiativeLevel } from '../../lib/initiativeResolver';
 import { resumeStalledTickets } from '../../lib/resumeOnStartup';
 import { checkAgentMessageRate, checkConversationDepth } from '../../lib/agentMessageRateLimit';
+import { listenForDeepLinks } from '../../lib/deepLinks';
 import type { CanvasNodePayload, AgentQuestionPayload, AgentChoicesPayload, AgentStatusPayload, AgentConfirmPayload } from '../../types/canvas';
+import type { PollStoppedPayload } from '../../types/generated/PollStoppedPayload';
 
 export function AppShell() {
   const { activeView, setActiveView } = useNavigationStore();
@@ -102,6 +104,9 @@ export function AppShell() {
     return () => { unlisten.then((fn) => fn()); };
   }, [handleAgentEvent]);
 
+  // poietai:// links, e.g. from Slack notifications
+  useEffect(() => listenForDeepLinks(), []);
+
   // Route agent-event to canvas store (always-on, regardless of active view)
   useEffect(() => {
     const unlisten = listen<CanvasNodePayload>('agent-event', (e) => {
@@ -143,7 +148,7 @@ export function AppShell() {
   // so questions appear directly in the DM. User replies via answer_agent.
   useEffect(() => {
     const unlisten = listen<AgentQuestionPayload>('agent-question', (event) => {
-      const { agent_id, question } = event.payload;
+      const { agent_id, question_id, question } = event.payload;
       const agent = useAgentStore.getState().agents.find((a) => a.id === agent_id);
       const agentName = agent?.name ?? agent_id;
 
@@ -165,6 +170,7 @@ export function AppShell() {
         agentName,
         content,
         type: 'question',
+        questionId: question_id,
         timestamp: Date.now(),
         resolved: false,
       });
@@ -340,6 +346,7 @@ export function AppShell() {
             message: wakeMessage,
             system_prompt: systemPrompt,
             context_update: contextUpdate,
+            automated: true,
           },
         }).catch((err) => {
           console.warn(`[agent-message] failed to wake ${recipientId}:`, err);
@@ -656,6 +663,7 @@ export function AppShell() {
               gh_token: ghToken,
               resume_session_id: null,
               phase: ticket.activePhase ?? 'build',
+              automated: true,
             },
           });
           useTicketStore.getState().assignTicket(ticket.id, { agentId: agent.id, repoId: repo.id });
@@ -908,6 +916,27 @@ export function AppShell() {
     return () => { unlisten.then((fn) => fn()); };
   }, [showToast]);
 
+  // A PR or MR watch gave up while it was still open — say so in the DM
+  useEffect(() => {
+    const unlisten = listen<PollStoppedPayload>('pr-poll-stopped', (event) => {
+      const { agent_id, ticket_id, pr_number } = event.payload;
+      const agent = useAgentStore.getState().agents.find((a) => a.id === agent_id);
+      useMessageStore.getState().addMessage({
+        id: `dm-poll-stopped-${agent_id}-${Date.now()}`,
+        threadId: agent_id,
+        threadType: 'dm',
+        from: 'system',
+        agentId: agent_id,
+        agentName: agent?.name ?? agent_id,
+        content: `Stopped watching #${pr_number} — later reviews and CI failures won't reach this agent.`,
+        type: 'status',
+        ticketId: ticket_id,
+        timestamp: Date.now(),
+      });
+    });
+    return () => { unlisten.then((fn) => fn()); };
+  }, []);
+
   // Orchestrator: question — route to DM
   useEffect(() => {
     const unlisten = listen<{
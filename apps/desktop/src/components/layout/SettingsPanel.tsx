@@ -2,6 +2,7 @@
 import { useState, useEffect, useRef } from 'react';
 import { X, ChevronDown, ChevronRight } from 'lucide-react';
 import { useSecretsStore } from '../../store/secretsStore';
+import { ApiKeysSection } from './ApiKeysSection';
 
 interface Props {
   onClose: () => void;
@@ -175,6 +176,8 @@ export function SettingsPanel({ onClose }: Props) {
           {error && <p className="text-red-400 text-xs mb-2">{error}</p>}
         </div>
 
+        <ApiKeysSection />
+
         <div className="flex gap-2 justify-end border-t border-zinc-800 pt-4">
           <button type="button" onClick={onClose}
             className="text-sm text-zinc-400 hover:text-zinc-200 px-3 py-1.5">
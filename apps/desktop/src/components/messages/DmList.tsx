@@ -13,6 +13,7 @@ import { parseTokens } from '../../lib/tokenParser';
 import { MCP_TOOLS } from '../../lib/mcpTools';
 import { TokenChip } from './TokenChip';
 import type { DmMessage, Conversation } from '../../types/message';
+import type { ResumeInclude } from '../../types/generated/ResumeInclude';
 
 /** Format time like Slack: "9:41 AM" */
 function fmtTime(ts: number) {
@@ -782,11 +783,19 @@ export function DmList() {
 
     try {
       if (msg.sessionId) {
-        // End-of-session question — resume the agent with --resume
+        // End-of-session question — resume the agent with --resume, the reply
+        // leading whatever its PR has picked up since
+        const include: ResumeInclude = {
+          reviews: true,
+          failed_checks: true,
+          new_commits_on_main: false,
+          user_reply: finalReply,
+        };
+        const prompt = await invoke<string>('compose_resume_prompt', { agentId: msg.agentId, include });
         await invoke('resume_agent', {
           agentId: msg.agentId,
           sessionId: msg.sessionId,
-          prompt: finalReply,
+          prompt,
         });
       } else {
         // MCP ask_human question — answer via the MCP server
@@ -1065,6 +1074,9 @@ export function DmList() {
               ? agentNameFor(conv.participants[0])
               : conv.participants.map(agentNameFor).join(', ');
             const avatarName = agentNameFor(conv.participants[0]);
+            const statusNote = conv.participants.length === 1
+              ? agents.find((a) => a.id === conv.participants[0])?.status_note
+              : null;
 
             return (
               <button
@@ -1090,7 +1102,14 @@ export function DmList() {
                     {conv.participants.length}
                   </div>
                 )}
-                <span className="flex-1 truncate">{displayName}</span>
+                <span className="flex-1 min-w-0">
+                  <span className="block truncate">{displayName}</span>
+                  {statusNote && (
+                    <span className="block truncate text-xs text-zinc-500" title={statusNote}>
+                      {statusNote}
+                    </span>
+                  )}
+                </span>
                 {unread > 0 && (
                   <span className="bg-violet-600 text-white text-xs rounded-full px-1.5 py-0.5">
                     {unread}
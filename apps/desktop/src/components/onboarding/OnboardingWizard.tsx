@@ -1,5 +1,9 @@
 // apps/desktop/src/components/onboarding/OnboardingWizard.tsx
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useSettingsStore } from '../../store/settingsStore';
+import type { OnboardingState } from '../../types/generated/OnboardingState';
+import type { OnboardingStep } from '../../types/generated/OnboardingStep';
 import { StepConnectGitHub } from './StepConnectGitHub';
 import { StepAddProject } from './StepAddProject';
 import { StepCreateAgent } from './StepCreateAgent';
@@ -8,8 +12,33 @@ interface Props {
   onComplete: () => void;
 }
 
+// Wizard screens by the backend step they cover; Claude detection has no screen.
+const SCREEN_FOR: Record<OnboardingStep, number> = {
+  detect_claude: 1,
+  connect_github: 1,
+  scan_project: 2,
+  create_agent: 3,
+  done: 3,
+};
+
 export function OnboardingWizard({ onComplete }: Props) {
-  const [step, setStep] = useState(1);
+  const { onboarding, setOnboarding, markOnboardingStep, skipOnboardingStep } = useSettingsStore();
+  const [step, setStep] = useState(() => SCREEN_FOR[onboarding?.step ?? 'connect_github']);
+
+  useEffect(() => {
+    const unlisten = listen<OnboardingState>('onboarding-state-changed', (e) => setOnboarding(e.payload));
+    return () => { unlisten.then((fn) => fn()); };
+  }, [setOnboarding]);
+
+  const advance = (done: OnboardingStep, next: number) => {
+    markOnboardingStep(done);
+    setStep(next);
+  };
+
+  const skip = (skipped: OnboardingStep, next: number) => {
+    skipOnboardingStep(skipped);
+    setStep(next);
+  };
 
   const steps = ['Connect GitHub', 'Add project', 'Create agent'];
 
@@ -41,17 +70,23 @@ export function OnboardingWizard({ onComplete }: Props) {
           ))}
         </div>
 
+        {onboarding && !onboarding.claude_version && (
+          <p className="mb-6 text-xs text-amber-400">
+            The claude CLI wasn't found on your PATH — agents can't run until it's installed.
+          </p>
+        )}
+
         {/* Step content */}
         {step === 1 && (
           <StepConnectGitHub
-            onNext={() => setStep(2)}
-            onSkip={() => setStep(2)}
+            onNext={() => advance('connect_github', 2)}
+            onSkip={() => skip('connect_github', 2)}
           />
         )}
         {step === 2 && (
           <StepAddProject
             onNext={() => setStep(3)}
-            onSkip={() => setStep(3)}
+            onSkip={() => skip('scan_project', 3)}
           />
         )}
         {step === 3 && (
@@ -3,18 +3,9 @@ import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import { useProjectStore, type Project, type Repo } from '../../store/projectStore';
+import type { FolderScanResult } from '../../types/generated/FolderScanResult';
 
-interface RepoInfo {
-  name: string;
-  repo_root: string;
-  remote_url?: string;
-  provider?: string;
-}
-
-type ScanResult =
-  | { type: 'single_repo'; name: string; repo_root: string; remote_url?: string; provider?: string }
-  | { type: 'multi_repo'; repos: RepoInfo[]; suggested_name: string }
-  | { type: 'no_repo' };
+type ScanResult = FolderScanResult;
 
 interface Props {
   onNext: () => void;
@@ -68,7 +59,7 @@ export function StepAddProject({ onNext, onSkip }: Props) {
           id: crypto.randomUUID(),
           name: scanResult.name,
           repoRoot: scanResult.repo_root,
-          remoteUrl: scanResult.remote_url,
+          remoteUrl: scanResult.remote_url ?? undefined,
           provider: (scanResult.provider ?? 'github') as Repo['provider'],
         }];
       } else if (scanResult?.type === 'multi_repo') {
@@ -78,7 +69,7 @@ export function StepAddProject({ onNext, onSkip }: Props) {
             id: crypto.randomUUID(),
             name: r.name,
             repoRoot: r.repo_root,
-            remoteUrl: r.remote_url,
+            remoteUrl: r.remote_url ?? undefined,
             provider: (r.provider ?? 'github') as Repo['provider'],
           }));
       }
@@ -0,0 +1,46 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { DeepLink } from '../types/generated/DeepLink';
+import { useNavigationStore } from '../store/navigationStore';
+import { useMessageStore } from '../store/messageStore';
+
+/** Focus the UI on the entity a poietai:// link points at. */
+export function routeDeepLink(link: DeepLink) {
+  switch (link.kind) {
+    case 'ticket':
+      useNavigationStore.getState().setSelectedTicketId(link.ticket_id);
+      break;
+    case 'agent': {
+      const messages = useMessageStore.getState();
+      messages.setActiveThread(messages.findOrCreateDm([link.agent_id]).id);
+      useNavigationStore.getState().setActiveView('messages');
+      break;
+    }
+    case 'question': {
+      // Open the question itself; one that's gone falls back to the agent's DM
+      const messages = useMessageStore.getState();
+      const asked = Object.values(messages.threads)
+        .flat()
+        .find((m) => m.questionId === link.question_id && m.agentId === link.agent_id);
+      messages.setActiveThread(asked?.threadId ?? messages.findOrCreateDm([link.agent_id]).id);
+      messages.setOpenThread(asked?.id ?? null);
+      useNavigationStore.getState().setActiveView('messages');
+      break;
+    }
+  }
+}
+
+/**
+ * Route the link the app was opened with, then every one after.
+ * Returns the unlisten function.
+ */
+export function listenForDeepLinks(): () => void {
+  const unlisten = listen<DeepLink>('deep-link', () => {
+    // Take it so a reload doesn't route the same link twice
+    invoke<DeepLink | null>('take_deep_link').then((link) => link && routeDeepLink(link));
+  });
+  invoke<DeepLink | null>('take_deep_link')
+    .then((link) => link && routeDeepLink(link))
+    .catch((e) => console.warn('failed to read launch link:', e));
+  return () => { unlisten.then((fn) => fn()); };
+}
@@ -103,6 +103,7 @@ export async function resumeStalledTickets(): Promise<void> {
           gh_token: ghToken,
           resume_session_id: null,
           phase: ticket.activePhase ?? 'build',
+          automated: true,
         },
       });
     } catch (err) {
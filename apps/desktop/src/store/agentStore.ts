@@ -17,6 +17,7 @@ export interface Agent {
   chat_session_id?: string;
   chatting?: boolean;
   initiative?: string | null;
+  status_note?: string | null;
 }
 
 type AgentIdentity = Pick<Agent, 'id' | 'name' | 'role' | 'personality' | 'chat_session_id' | 'initiative'>;
@@ -80,7 +81,7 @@ export const useAgentStore = create<AgentStore>((set, get) => ({
     const saved = (await store.get<AgentIdentity[]>('agents')) ?? [];
     for (const { id, name, role, personality, chat_session_id, initiative } of saved) {
       try {
-        await invoke('create_agent', { id, name, role, personality, chatSessionId: chat_session_id ?? null, initiative: initiative ?? null });
+        await invoke('create_agent', { id, name, role, personality, chatSessionId: chat_session_id ?? null, initiative: initiative ?? null, restored: true });
       } catch {
         // Already exists in this session — skip.
       }
@@ -78,20 +78,13 @@ function contentFromEvent(event: AgentEventKind): string {
 
 function filePathFromEvent(event: AgentEventKind): string | undefined {
   if (event.type !== 'tool_use') return undefined;
-  const raw = event.tool_input['file_path'] ?? event.tool_input['path'];
-  return typeof raw === 'string' ? raw : undefined;
+  return event.summary?.file_path ?? undefined;
 }
 
 /** Human-readable label for a tool call — used as the item label in grouped nodes. */
 function itemLabelFromEvent(event: AgentEventKind): string {
   if (event.type !== 'tool_use') return '';
-  const fp = filePathFromEvent(event);
-  if (fp) return fp;
-  const cmd = event.tool_input['command'];
-  if (typeof cmd === 'string') return cmd;
-  const pattern = event.tool_input['pattern'];
-  if (typeof pattern === 'string') return pattern;
-  return JSON.stringify(event.tool_input).slice(0, 80);
+  return event.summary?.label ?? event.tool_name;
 }
 
 function textFromToolResult(content: unknown): string | undefined {
@@ -265,9 +258,7 @@ export const useCanvasStore = create<CanvasStore>((set, get) => ({
         // Still run ghost activation below
         const isFileEdit = mappedType === 'file_edit' || mappedType === 'file_write';
         if (isFileEdit && payload.kind.type === 'tool_use') {
-          const editedPath = String(
-            (payload.kind.tool_input['file_path'] ?? payload.kind.tool_input['path']) ?? ''
-          );
+          const editedPath = filePathFromEvent(payload.kind) ?? '';
           if (editedPath) {
             newNodes = newNodes.map((n) => {
               if (n.data.isGhost && n.data.filePath) {
@@ -333,9 +324,7 @@ export const useCanvasStore = create<CanvasStore>((set, get) => ({
     // Activate matching ghost nodes when a file is edited or written
     const isFileEdit = mappedType === 'file_edit' || mappedType === 'file_write';
     if (isFileEdit && payload.kind.type === 'tool_use') {
-      const editedPath = String(
-        (payload.kind.tool_input['file_path'] ?? payload.kind.tool_input['path']) ?? ''
-      );
+      const editedPath = filePathFromEvent(payload.kind) ?? '';
       if (editedPath) {
         newNodes = newNodes.map((n) => {
           if (n.data.isGhost && n.data.filePath) {
@@ -0,0 +1,51 @@
+// apps/desktop/src/store/featureFlagStore.ts
+import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
+import type { Channel } from '../types/generated/Channel';
+import type { FeatureFlags } from '../types/generated/FeatureFlags';
+
+interface FeatureFlagStore {
+  channel: Channel;
+  flags: Record<string, boolean>;
+  loaded: boolean;
+
+  loadFlags: () => Promise<void>;
+  /** Force a flag on/off for this install; null restores the channel default. */
+  setFlag: (key: string, enabled: boolean | null) => Promise<void>;
+  /** Follow a release channel; null follows the installed build's channel. */
+  setChannel: (channel: Channel | null) => Promise<void>;
+  isEnabled: (key: string) => boolean;
+}
+
+function toState(result: FeatureFlags) {
+  return {
+    channel: result.channel,
+    flags: Object.fromEntries(result.flags.map((f) => [f.key, f.enabled])),
+    loaded: true,
+  };
+}
+
+export const useFeatureFlagStore = create<FeatureFlagStore>((set, get) => ({
+  channel: 'stable',
+  flags: {},
+  loaded: false,
+
+  loadFlags: async () => {
+    try {
+      set(toState(await invoke<FeatureFlags>('get_feature_flags')));
+    } catch (e) {
+      console.warn('failed to load feature flags:', e);
+      set({ loaded: true });
+    }
+  },
+
+  setFlag: async (key, enabled) => {
+    set(toState(await invoke<FeatureFlags>('set_feature_flag', { key, enabled })));
+  },
+
+  setChannel: async (channel) => {
+    set(toState(await invoke<FeatureFlags>('set_update_channel', { channel })));
+  },
+
+  isEnabled: (key) => get().flags[key] ?? false,
+}));
@@ -1,12 +1,15 @@
 // apps/desktop/src/store/secretsStore.ts
 import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
 import { Stronghold } from '@tauri-apps/plugin-stronghold';
 import { appDataDir, join } from '@tauri-apps/api/path';
 import { readTextFile, writeTextFile, exists, mkdir } from '@tauri-apps/plugin-fs';
+import type { ApiKey } from '../types/generated/ApiKey';
 
 export type GitProvider = 'github' | 'gitlab' | 'bitbucket' | 'azure';
 
 const CLIENT_NAME = 'poietai';
+const API_KEYS_KEY = 'anthropic:api-keys';
 
 function tokenKey(provider: GitProvider): string {
   return `token:${provider}`;
@@ -35,6 +38,25 @@ async function writeFallbackTokens(tokens: Partial<Record<GitProvider, string>>)
   await writeTextFile(path, JSON.stringify(tokens, null, 2));
 }
 
+async function getFallbackApiKeysPath(): Promise<string> {
+  const dir = await appDataDir();
+  return join(dir, 'api-keys.json');
+}
+
+function parseApiKeys(raw: string): ApiKey[] {
+  try {
+    const keys = JSON.parse(raw);
+    return Array.isArray(keys) ? keys : [];
+  } catch {
+    return [];
+  }
+}
+
+/** Hand the keys to the backend, which rotates runs among them. */
+function pushApiKeys(keys: ApiKey[]) {
+  invoke('set_api_keys', { keys }).catch((e) => console.warn('failed to hand API keys to the backend:', e));
+}
+
 async function getInstallKey(): Promise<string> {
   const dir = await appDataDir();
   await mkdir(dir, { recursive: true });
@@ -78,9 +100,11 @@ interface SecretsStore {
   loaded: boolean;
   isLoading: boolean;
   usingFallback: boolean;   // true when Stronghold is unavailable
+  apiKeys: ApiKey[];        // Anthropic keys/gateways, rotated per agent run
 
   loadToken: () => Promise<void>;
   saveToken: (token: string) => Promise<void>;
+  saveApiKeys: (keys: ApiKey[]) => Promise<void>;
 }
 
 export const useSecretsStore = create<SecretsStore>((set, get) => ({
@@ -88,6 +112,7 @@ export const useSecretsStore = create<SecretsStore>((set, get) => ({
   loaded: false,
   isLoading: false,
   usingFallback: false,
+  apiKeys: [],
 
   loadToken: async () => {
     if (get().loaded || get().isLoading) return;
@@ -113,6 +138,13 @@ export const useSecretsStore = create<SecretsStore>((set, get) => ({
         }
       }
 
+      const rawKeys = await store.get(API_KEYS_KEY);
+      if (rawKeys) {
+        const apiKeys = parseApiKeys(new TextDecoder().decode(rawKeys));
+        set({ apiKeys });
+        pushApiKeys(apiKeys);
+      }
+
       if (raw) {
         const token = new TextDecoder().decode(raw);
         set({ ghToken: token, loaded: true, isLoading: false });
@@ -128,7 +160,10 @@ export const useSecretsStore = create<SecretsStore>((set, get) => ({
     try {
       const tokens = await readFallbackTokens();
       const token = tokens['github'] ?? null;
-      set({ ghToken: token, loaded: true, isLoading: false, usingFallback: true });
+      const keysPath = await getFallbackApiKeysPath();
+      const apiKeys = (await exists(keysPath)) ? parseApiKeys(await readTextFile(keysPath)) : [];
+      set({ ghToken: token, apiKeys, loaded: true, isLoading: false, usingFallback: true });
+      pushApiKeys(apiKeys);
     } catch (e) {
       console.warn('Plaintext fallback also failed:', e);
       set({ loaded: true, isLoading: false, usingFallback: true });
@@ -156,4 +191,26 @@ export const useSecretsStore = create<SecretsStore>((set, get) => ({
     await writeFallbackTokens(tokens);
     set({ ghToken: token, usingFallback: true });
   },
+
+  saveApiKeys: async (keys: ApiKey[]) => {
+    const json = JSON.stringify(keys);
+    try {
+      const { stronghold, client } = await openVault();
+      const store = client.getStore();
+      try { await store.remove(API_KEYS_KEY); } catch { /* may not exist */ }
+      await store.insert(API_KEYS_KEY, Array.from(new TextEncoder().encode(json)));
+      await stronghold.save();
+      set({ apiKeys: keys, usingFallback: false });
+      pushApiKeys(keys);
+      return;
+    } catch (e) {
+      console.warn('Stronghold save failed — using plaintext fallback:', e);
+    }
+
+    const dir = await appDataDir();
+    await mkdir(dir, { recursive: true });
+    await writeTextFile(await getFallbackApiKeysPath(), JSON.stringify(keys, null, 2));
+    set({ apiKeys: keys, usingFallback: true });
+    pushApiKeys(keys);
+  },
 }));
@@ -1,6 +1,9 @@
 // apps/desktop/src/store/settingsStore.ts
 import { create } from 'zustand';
 import { load } from '@tauri-apps/plugin-store';
+import { invoke } from '@tauri-apps/api/core';
+import type { OnboardingState } from '../types/generated/OnboardingState';
+import type { OnboardingStep } from '../types/generated/OnboardingStep';
 
 /** Categories that map to one or more canvas node types */
 export const NODE_CATEGORIES = {
@@ -15,10 +18,17 @@ export type NodeCategory = keyof typeof NODE_CATEGORIES;
 
 interface SettingsStore {
   onboardingComplete: boolean;
+  /** Backend view of first-run setup; null until loaded. */
+  onboarding: OnboardingState | null;
   hiddenNodeCategories: Set<NodeCategory>;
   loaded: boolean;
 
   loadSettings: () => Promise<void>;
+  setOnboarding: (state: OnboardingState) => void;
+  /** Count a step as done in the backend. */
+  markOnboardingStep: (step: OnboardingStep) => Promise<void>;
+  /** Leave a step for later without counting it as done. */
+  skipOnboardingStep: (step: OnboardingStep) => Promise<void>;
   completeOnboarding: () => Promise<void>;
   toggleNodeCategory: (category: NodeCategory) => void;
 }
@@ -29,25 +39,52 @@ async function getStore() {
 
 export const useSettingsStore = create<SettingsStore>((set, get) => ({
   onboardingComplete: false,
+  onboarding: null,
   hiddenNodeCategories: new Set(),
   loaded: false,
 
   loadSettings: async () => {
     if (get().loaded) return;
     const store = await getStore();
-    const onboardingComplete = (await store.get<boolean>('onboardingComplete')) ?? false;
     const hiddenArr = (await store.get<string[]>('hiddenNodeCategories')) ?? [];
     const hiddenNodeCategories = new Set(hiddenArr as NodeCategory[]);
-    set({ onboardingComplete, hiddenNodeCategories, loaded: true });
+    // Installs that finished the wizard before the backend tracked it
+    const legacyComplete = (await store.get<boolean>('onboardingComplete')) ?? false;
+    let onboarding: OnboardingState | null = null;
+    try {
+      onboarding = await invoke<OnboardingState>(
+        legacyComplete ? 'mark_onboarding_step' : 'get_onboarding_state',
+        legacyComplete ? { step: 'done' } : {},
+      );
+    } catch (e) {
+      console.warn('failed to load onboarding state:', e);
+    }
+    const onboardingComplete = legacyComplete || onboarding?.step === 'done';
+    set({ onboardingComplete, onboarding, hiddenNodeCategories, loaded: true });
   },
 
-  completeOnboarding: async () => {
+  setOnboarding: (state: OnboardingState) => {
+    set({ onboarding: state, onboardingComplete: get().onboardingComplete || state.step === 'done' });
+  },
+
+  markOnboardingStep: async (step: OnboardingStep) => {
+    try {
+      get().setOnboarding(await invoke<OnboardingState>('mark_onboarding_step', { step }));
+    } catch (e) {
+      console.warn('failed to record onboarding step:', e);
+    }
+  },
+
+  skipOnboardingStep: async (step: OnboardingStep) => {
     try {
-      const store = await getStore();
-      await store.set('onboardingComplete', true);
+      get().setOnboarding(await invoke<OnboardingState>('skip_onboarding_step', { step }));
     } catch (e) {
-      console.warn('failed to persist onboardingComplete:', e);
+      console.warn('failed to skip onboarding step:', e);
     }
+  },
+
+  completeOnboarding: async () => {
+    await get().markOnboardingStep('done');
     set({ onboardingComplete: true });
   },
 
@@ -0,0 +1,54 @@
+// apps/desktop/src/store/syncStore.ts
+import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
+import { getActiveProjectRoot, useProjectStore } from './projectStore';
+import { useTicketStore, type Ticket } from './ticketStore';
+import type { MachineSnapshot } from '../types/generated/MachineSnapshot';
+
+/** How often this machine publishes to the sync folder and reads the others back. */
+const SYNC_INTERVAL_MS = 60_000;
+
+interface SyncStore {
+  /** What the other machines last published; empty when sync is off. */
+  peers: MachineSnapshot[];
+  _intervalId: ReturnType<typeof setInterval> | null;
+
+  sync: () => Promise<void>;
+  startSync: () => void;
+  stopSync: () => void;
+}
+
+export const useSyncStore = create<SyncStore>((set, get) => ({
+  peers: [],
+  _intervalId: null,
+
+  sync: async () => {
+    const projectRoots = useProjectStore
+      .getState()
+      .projects.flatMap((p) => (p.repos.length > 0 ? [p.repos[0].repoRoot] : []));
+    try {
+      const peers = await invoke<MachineSnapshot[]>('sync_machines', { projectRoots });
+      set({ peers });
+      const root = getActiveProjectRoot();
+      if (!root || peers.length === 0 || !useTicketStore.getState().loaded) return;
+      const tickets = await invoke<Ticket[]>('get_peer_tickets', { projectRoot: root });
+      // The project may have been switched while the folder was read
+      if (getActiveProjectRoot() === root) useTicketStore.getState().mergePeerTickets(tickets);
+    } catch (e) {
+      console.warn('failed to sync with other machines:', e);
+    }
+  },
+
+  startSync: () => {
+    if (get()._intervalId) return;
+    get().sync();
+    const id = setInterval(() => get().sync(), SYNC_INTERVAL_MS);
+    set({ _intervalId: id });
+  },
+
+  stopSync: () => {
+    const id = get()._intervalId;
+    if (id) clearInterval(id);
+    set({ _intervalId: null });
+  },
+}));
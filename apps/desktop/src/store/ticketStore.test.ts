@@ -1,5 +1,5 @@
 import { describe, it, expect, beforeEach } from 'vitest';
-import { useTicketStore } from './ticketStore';
+import { useTicketStore, type Ticket } from './ticketStore';
 
 beforeEach(() => {
   useTicketStore.setState({ tickets: [], nextTicketNumber: 1 });
@@ -236,3 +236,33 @@ describe('updateTicketStatus', () => {
     expect(old).toBeUndefined();
   });
 });
+
+describe('mergePeerTickets', () => {
+  const peer = (patch: Partial<Ticket>): Ticket => ({
+    id: 'p1', number: 1, title: 'From laptop', description: '', complexity: 2, status: 'backlog',
+    assignments: [], acceptanceCriteria: [], tags: [], phases: ['plan'], artifacts: {}, ...patch,
+  });
+
+  it('adds tickets this board lacks, renumbering on a clash', () => {
+    useTicketStore.getState().addTicket({ title: 'Local', description: '', complexity: 1, acceptanceCriteria: [] });
+    useTicketStore.getState().mergePeerTickets([peer({})]);
+    const tickets = useTicketStore.getState().tickets;
+    expect(tickets.map((t) => t.number)).toEqual([1, 2]);
+    expect(useTicketStore.getState().nextTicketNumber).toBe(3);
+  });
+
+  it('takes the newer of two copies', () => {
+    useTicketStore.setState({ tickets: [peer({ updatedAt: '2026-01-02T00:00:00Z' })] });
+    useTicketStore.getState().mergePeerTickets([peer({ title: 'Stale', updatedAt: '2026-01-01T00:00:00Z' })]);
+    expect(useTicketStore.getState().tickets[0].title).toBe('From laptop');
+    useTicketStore.getState().mergePeerTickets([peer({ title: 'Edited', updatedAt: '2026-01-03T00:00:00Z' })]);
+    expect(useTicketStore.getState().tickets[0].title).toBe('Edited');
+  });
+
+  it('does not bring back a ticket deleted here', () => {
+    useTicketStore.setState({ tickets: [peer({})] });
+    useTicketStore.getState().deleteTicket('p1');
+    useTicketStore.getState().mergePeerTickets([peer({ updatedAt: '2026-01-03T00:00:00Z' })]);
+    expect(useTicketStore.getState().tickets).toHaveLength(0);
+  });
+});
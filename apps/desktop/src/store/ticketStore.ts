@@ -1,10 +1,19 @@
 // apps/desktop/src/store/ticketStore.ts
 import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
 import { readProjectStore, writeProjectStore } from '../lib/projectFileIO';
-import { getActiveProjectRoot } from './projectStore';
+import { getActiveProjectRoot, useProjectStore } from './projectStore';
 import { phasesForComplexity, nextPhase } from '../lib/phaseRouter';
 import { useCanvasStore } from './canvasStore';
 import { useMessageStore } from './messageStore';
+import { useSecretsStore } from './secretsStore';
+import type { BulkAssignment } from '../types/generated/BulkAssignment';
+import type { BulkUpdate } from '../types/generated/BulkUpdate';
+import type { GitHubIssue } from '../types/generated/GitHubIssue';
+import type { Priority } from '../types/generated/Priority';
+import type { TicketChanges } from '../types/generated/TicketChanges';
+import type { TicketDraft } from '../types/generated/TicketDraft';
+import type { TicketTemplate } from '../types/generated/TicketTemplate';
 
 export type TicketStatus =
   | 'backlog' | 'refined' | 'assigned'
@@ -36,9 +45,18 @@ export interface Ticket {
   assignments: Assignment[];
   acceptanceCriteria: string[];
   tags: string[];
+  /** GitHub labels and milestone applied to the agent's PR. */
+  labels?: string[];
+  milestone?: string;
+  /** Queue order when the scheduler caps concurrent runs; normal when unset. */
+  priority?: Priority;
+  /** The issue the ticket was imported from. */
+  sourceUrl?: string;
   phases: TicketPhase[];
   activePhase?: TicketPhase;
   artifacts: Partial<Record<TicketPhase, Artifact>>;
+  /** When the ticket last changed on this or another synced machine. */
+  updatedAt?: string;
 }
 
 interface TicketStore {
@@ -49,9 +67,16 @@ interface TicketStore {
   isLoading: boolean;
 
   loadFromDisk: () => Promise<void>;
-  addTicket: (input: { title: string; description: string; complexity: number; acceptanceCriteria: string[]; tags?: string[] }) => void;
-  updateTicket: (id: string, patch: Partial<Pick<Ticket, 'title' | 'description' | 'complexity' | 'acceptanceCriteria' | 'tags'>>) => void;
+  addTicket: (input: { title: string; description: string; complexity: number; acceptanceCriteria: string[]; tags?: string[]; labels?: string[]; milestone?: string; sourceUrl?: string }) => void;
+  addTicketFromTemplate: (template: TicketTemplate, title: string) => Promise<void>;
+  /** Add the active project's open GitHub issues that aren't on the board yet; resolves to how many were added. */
+  importGitHubIssues: () => Promise<number>;
+  updateTicket: (id: string, patch: Partial<Pick<Ticket, 'title' | 'description' | 'complexity' | 'acceptanceCriteria' | 'tags' | 'labels' | 'milestone' | 'priority'>>) => void;
   updateTicketStatus: (id: string, status: TicketStatus) => TicketStatus | undefined;
+  /** Apply one edit to many tickets with a single write and board sync. */
+  bulkUpdateTickets: (ids: string[], changes: TicketChanges) => Promise<BulkUpdate>;
+  /** Assign many tickets to one agent, or spread them over the roster when `agentId` is null. */
+  bulkAssign: (ids: string[], agentId: string | null) => Promise<BulkAssignment[]>;
   assignTicket: (ticketId: string, assignment: Assignment) => void;
   selectTicket: (id: string | null) => void;
   advanceTicketPhase: (id: string) => void;
@@ -59,6 +84,8 @@ interface TicketStore {
   blockTicket: (id: string) => void;
   resetTicket: (id: string) => void;
   deleteTicket: (id: string) => void;
+  /** Fold in other machines' copies of this board: new tickets are added, newer edits win. */
+  mergePeerTickets: (peerTickets: Ticket[]) => void;
   resetForProjectSwitch: () => void;
 }
 
@@ -75,14 +102,62 @@ async function clearPersistedCanvas(ticketId: string) {
   }
 }
 
-async function persistTickets(get: () => TicketStore) {
+/** Mirror the status to the project's GitHub Projects board, if configured. */
+async function syncTicketStatus(ticket: Ticket) {
   const root = getActiveProjectRoot();
   if (!root) return;
+  try {
+    await invoke('sync_ticket_status', {
+      projectRoot: root,
+      ticketId: ticket.id,
+      title: ticket.title,
+      sourceUrl: ticket.sourceUrl ?? null,
+      status: ticket.status,
+    });
+  } catch (e) {
+    console.warn('failed to sync ticket status to GitHub Projects:', e);
+  }
+}
+
+/** Each ticket's content as last loaded or written, to spot which ones changed. */
+const persisted = new Map<string, string>();
+/** Tickets deleted here, so other machines' copies don't bring them back. */
+const deleted = new Set<string>();
+/** Each ticket's status as the GitHub Projects board last heard it. */
+const mirrored = new Map<string, TicketStatus>();
+
+function markMirrored(tickets: Ticket[]) {
+  for (const t of tickets) mirrored.set(t.id, t.status);
+}
+
+function contentOf({ updatedAt: _, ...ticket }: Ticket) {
+  return JSON.stringify(ticket);
+}
+
+function remember(tickets: Ticket[]) {
+  persisted.clear();
+  for (const t of tickets) persisted.set(t.id, contentOf(t));
+}
+
+async function persistTickets(get: () => TicketStore, set: (patch: Partial<TicketStore>) => void) {
+  const root = getActiveProjectRoot();
+  if (!root) return;
+  // Stamp edits so synced machines can tell whose copy of a ticket is newer
+  const now = new Date().toISOString();
+  const stamped = get().tickets.map((t) => (persisted.get(t.id) === contentOf(t) ? t : { ...t, updatedAt: now }));
+  if (stamped.some((t, i) => t !== get().tickets[i])) set({ tickets: stamped });
+  remember(stamped);
+  // Every status change reaches the board, whichever action made it
+  for (const t of stamped) {
+    if (mirrored.has(t.id) && mirrored.get(t.id) !== t.status) syncTicketStatus(t);
+  }
+  markMirrored(stamped);
   try {
     await writeProjectStore(root, 'tickets.json', {
       tickets: get().tickets,
       selectedTicketId: get().selectedTicketId,
       nextTicketNumber: get().nextTicketNumber,
+      deletedTicketIds: [...deleted],
     });
   } catch (e) {
     console.warn('failed to persist tickets:', e);
@@ -109,6 +184,7 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
         tickets: Ticket[];
         selectedTicketId: string | null;
         nextTicketNumber: number;
+        deletedTicketIds?: string[];
       }>(root, 'tickets.json');
 
       const tickets = saved?.tickets ?? [];
@@ -134,6 +210,11 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
           ? Math.max(...migratedTickets.map((t: Ticket) => t.number)) + 1
           : 1;
       }
+      remember(migratedTickets);
+      mirrored.clear();
+      markMirrored(migratedTickets);
+      deleted.clear();
+      for (const id of saved?.deletedTicketIds ?? []) deleted.add(id);
       set({ tickets: migratedTickets, selectedTicketId, nextTicketNumber, loaded: true, isLoading: false });
     } catch (e) {
       console.warn('failed to load tickets:', e);
@@ -154,20 +235,51 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
         assignments: [],
         acceptanceCriteria: input.acceptanceCriteria,
         tags: input.tags ?? [],
+        labels: input.labels,
+        milestone: input.milestone,
+        sourceUrl: input.sourceUrl,
         phases,
         activePhase: phases[0],
         artifacts: {},
       };
       return { tickets: [...state.tickets, ticket], nextTicketNumber: state.nextTicketNumber + 1 };
     });
-    persistTickets(get);
+    persistTickets(get, set);
+  },
+
+  addTicketFromTemplate: async (template, title) => {
+    const draft = await invoke<TicketDraft>('create_ticket_from_template', { template, title });
+    get().addTicket(draft);
+  },
+
+  importGitHubIssues: async () => {
+    const root = getActiveProjectRoot();
+    if (!root) return 0;
+    const issues = await invoke<GitHubIssue[]>('list_github_issues', {
+      repoRoot: root,
+      ghToken: useSecretsStore.getState().ghToken ?? null,
+    });
+    const imported = new Set(get().tickets.map((t) => t.sourceUrl).filter(Boolean));
+    const fresh = issues.filter((issue) => !imported.has(issue.url));
+    for (const issue of fresh) {
+      get().addTicket({
+        title: issue.title,
+        description: `${issue.body}\n\nImported from ${issue.url}`.trim(),
+        complexity: 3,
+        acceptanceCriteria: [],
+        labels: issue.labels,
+        milestone: issue.milestone ?? undefined,
+        sourceUrl: issue.url,
+      });
+    }
+    return fresh.length;
   },
 
   updateTicket: (id, patch) => {
     set((s) => ({
       tickets: s.tickets.map((t) => (t.id === id ? { ...t, ...patch } : t)),
     }));
-    persistTickets(get);
+    persistTickets(get, set);
   },
 
   updateTicketStatus: (id, status) => {
@@ -175,10 +287,56 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
     set((s) => ({
       tickets: s.tickets.map((t) => (t.id === id ? { ...t, status } : t)),
     }));
-    persistTickets(get);
+    persistTickets(get, set);
     return oldStatus;
   },
 
+  bulkUpdateTickets: async (ids, changes) => {
+    const root = getActiveProjectRoot();
+    if (!root) return { updated: [], missing: ids };
+    // Persist first so the backend sees every selected ticket
+    await persistTickets(get, set);
+    const result = await invoke<BulkUpdate>('bulk_update_tickets', { projectRoot: root, ids, changes });
+    const patch: Partial<Ticket> = {};
+    if (changes.status != null) patch.status = changes.status as TicketStatus;
+    if (changes.complexity != null) patch.complexity = changes.complexity;
+    if (changes.priority != null) patch.priority = changes.priority;
+    if (changes.tags != null) patch.tags = changes.tags;
+    if (changes.labels != null) patch.labels = changes.labels;
+    if (changes.milestone != null) patch.milestone = changes.milestone;
+    set((s) => ({
+      tickets: s.tickets.map((t) => (result.updated.includes(t.id) ? { ...t, ...patch } : t)),
+    }));
+    // The backend already moved these on the board
+    markMirrored(get().tickets.filter((t) => result.updated.includes(t.id)));
+    persistTickets(get, set);
+    return result;
+  },
+
+  bulkAssign: async (ids, agentId) => {
+    const { projects, activeProjectId } = useProjectStore.getState();
+    const repo = projects.find((p) => p.id === activeProjectId)?.repos[0];
+    if (!repo) return [];
+    await persistTickets(get, set);
+    const assignments = await invoke<BulkAssignment[]>('bulk_assign', {
+      projectRoot: repo.repoRoot,
+      ids,
+      agentId,
+    });
+    const byTicket = new Map(assignments.map((a) => [a.ticket_id, a.agent_id]));
+    set((s) => ({
+      tickets: s.tickets.map((t) => {
+        const assignee = byTicket.get(t.id);
+        if (!assignee || t.assignments.some((a) => a.agentId === assignee)) return t;
+        // Only tickets not yet picked up move to assigned; work in flight keeps its status
+        const status: TicketStatus = t.status === 'backlog' || t.status === 'refined' ? 'assigned' : t.status;
+        return { ...t, assignments: [...t.assignments, { agentId: assignee, repoId: repo.id }], status };
+      }),
+    }));
+    persistTickets(get, set);
+    return assignments;
+  },
+
   assignTicket: (ticketId, assignment) => {
     set((s) => ({
       tickets: s.tickets.map((t) =>
@@ -187,10 +345,10 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
           : t
       ),
     }));
-    persistTickets(get);
+    persistTickets(get, set);
   },
 
-  selectTicket: (id) => { set({ selectedTicketId: id }); persistTickets(get); },
+  selectTicket: (id) => { set({ selectedTicketId: id }); persistTickets(get, set); },
 
   advanceTicketPhase: (id) => {
     set((state) => ({
@@ -205,7 +363,7 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
         };
       }),
     }));
-    persistTickets(get);
+    persistTickets(get, set);
   },
 
   setPhaseArtifact: (id, artifact) => {
@@ -214,14 +372,14 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
         t.id !== id ? t : { ...t, artifacts: { ...t.artifacts, [artifact.phase]: artifact } }
       ),
     }));
-    persistTickets(get);
+    persistTickets(get, set);
   },
 
   blockTicket: (id) => {
     set((s) => ({
       tickets: s.tickets.map((t) => (t.id === id ? { ...t, status: 'blocked' as TicketStatus } : t)),
     }));
-    persistTickets(get);
+    persistTickets(get, set);
   },
 
   resetTicket: (id) => {
@@ -237,7 +395,7 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
         };
       }),
     }));
-    persistTickets(get);
+    persistTickets(get, set);
     // Clear canvas nodes for this ticket (in-memory + persisted)
     const canvas = useCanvasStore.getState();
     if (canvas.activeTicketId === id) canvas.clearCanvas();
@@ -247,18 +405,50 @@ export const useTicketStore = create<TicketStore>((set, get) => ({
   },
 
   deleteTicket: (id) => {
+    deleted.add(id);
     set((s) => ({
       tickets: s.tickets.filter((t) => t.id !== id),
       selectedTicketId: s.selectedTicketId === id ? null : s.selectedTicketId,
     }));
-    persistTickets(get);
+    persistTickets(get, set);
     // Clear canvas nodes for this ticket (in-memory + persisted)
     const canvas = useCanvasStore.getState();
     if (canvas.activeTicketId === id) canvas.clearCanvas();
     clearPersistedCanvas(id);
   },
 
+  mergePeerTickets: (peerTickets) => {
+    const newer = (a?: string, b?: string) => !!a && (!b || a > b);
+    let { tickets, nextTicketNumber } = get();
+    let changed = false;
+    for (const peer of peerTickets) {
+      if (deleted.has(peer.id)) continue;
+      const index = tickets.findIndex((t) => t.id === peer.id);
+      if (index === -1) {
+        // Both machines may have handed out the same number meanwhile
+        const taken = tickets.some((t) => t.number === peer.number);
+        const ticket = taken ? { ...peer, number: nextTicketNumber } : peer;
+        tickets = [...tickets, ticket];
+        nextTicketNumber = Math.max(nextTicketNumber, ticket.number) + 1;
+      } else if (newer(peer.updatedAt, tickets[index].updatedAt)) {
+        tickets = tickets.map((t, i) => (i === index ? { ...peer, number: t.number } : t));
+      } else {
+        continue;
+      }
+      changed = true;
+    }
+    if (!changed) return;
+    set({ tickets, nextTicketNumber });
+    // Adopted copies keep their peer's timestamp, and their machine mirrors them
+    for (const t of tickets) persisted.set(t.id, contentOf(t));
+    markMirrored(tickets);
+    persistTickets(get, set);
+  },
+
   resetForProjectSwitch: () => {
+    persisted.clear();
+    deleted.clear();
+    mirrored.clear();
     set({ tickets: [], nextTicketNumber: 1, selectedTicketId: null, loaded: false, isLoading: false });
   },
 }));
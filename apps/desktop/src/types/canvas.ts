@@ -1,14 +1,26 @@
 import type { Node } from '@xyflow/react';
+import type { AgentEvent } from './generated/AgentEvent';
+import type { SuggestedAnswer } from './generated/SuggestedAnswer';
+import type { ToolSummary } from './generated/ToolSummary';
 
 // These mirror the Rust AgentEvent enum exactly.
 // When Tauri emits "agent-event", the payload has this shape.
+// The generated binding (src/types/generated, written by `cargo test`) is the
+// source of truth; AgentEventInSync below fails typecheck if the two drift.
 
 export type AgentEventKind =
   | { type: 'thinking'; thinking: string }
   | { type: 'text'; text: string }
-  | { type: 'tool_use'; id: string; tool_name: string; tool_input: Record<string, unknown> }
-  | { type: 'tool_result'; tool_use_id: string; content: unknown; is_error?: boolean }
-  | { type: 'result'; result?: string; session_id?: string };
+  | { type: 'tool_use'; id: string; tool_name: string; tool_input: Record<string, unknown>; summary?: ToolSummary | null }
+  | { type: 'tool_result'; tool_use_id: string; content: unknown; is_error?: boolean; full_size?: number | null }
+  | { type: 'result'; result?: string; session_id?: string; cost_usd?: number };
+
+type Assert<T extends true> = T;
+export type AgentEventInSync = Assert<
+  [AgentEvent['type']] extends [AgentEventKind['type']]
+    ? [AgentEventKind['type']] extends [AgentEvent['type']] ? true : false
+    : false
+>;
 
 export interface CanvasNodePayload {
   /** Optional explicit node id; derived from kind.id for tool_use, or auto-generated. */
@@ -90,10 +102,14 @@ export interface CanvasNodeData extends Record<string, unknown> {
 export type CanvasNode = Node<CanvasNodeData>;
 
 /// Emitted by Tauri when Claude calls ask_human mid-task.
-/// Agent stays running — answer via invoke('answer_agent', { agentId, reply }).
+/// Agent stays running — answer via invoke('answer_agent', { agentId, reply }),
+/// or with reply: null to send the suggested answer.
 export interface AgentQuestionPayload {
   agent_id: string;
+  /** What `poietai://agent/<agent_id>/question/<question_id>` links point at. */
+  question_id: string;
   question: string;
+  suggested_answer?: SuggestedAnswer | null;
 }
 
 /// Emitted by MCP server when agent calls present_choices.
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CriterionResult } from "./CriterionResult";
+
+/**
+ * Per-criterion verdicts for a ticket. Emitted as `acceptance-verified`
+ * and stored in `<project>/.poietai/acceptance.json`.
+ */
+export type AcceptanceReport = { ticket_id: string, criteria: Array<CriterionResult>, 
+/**
+ * None when no test command is configured.
+ */
+tests_passed: boolean | null, 
+/**
+ * Unix seconds.
+ */
+verified_at: number, };
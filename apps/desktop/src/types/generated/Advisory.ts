@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A published vulnerability affecting an installed dependency.
+ */
+export type Advisory = { 
+/**
+ * RUSTSEC or GHSA id.
+ */
+id: string, 
+/**
+ * "cargo" or "npm".
+ */
+ecosystem: string, package: string, 
+/**
+ * Installed versions, comma-separated when the tree has several copies.
+ */
+installed: string, title: string, 
+/**
+ * npm's "low" to "critical"; None when the feed doesn't rate it.
+ */
+severity: string | null, url: string | null, 
+/**
+ * CVE ids for the same vulnerability.
+ */
+cves: Array<string>, 
+/**
+ * Version ranges with the fix, e.g. ">=0.25.1".
+ */
+patched: Array<string>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Advisory } from "./Advisory";
+import type { TicketDraft } from "./TicketDraft";
+
+/**
+ * Untracked advisories on one package, with a ticket to fix them.
+ */
+export type AdvisoryFinding = { advisories: Array<Advisory>, ticket: TicketDraft, };
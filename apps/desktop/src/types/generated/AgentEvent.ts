@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { ToolSummary } from "./ToolSummary";
+
+/**
+ * The semantic events we surface to the React canvas.
+ * These are extracted from the nested stream-json wire format.
+ */
+export type AgentEvent = { "type": "thinking", thinking: string, } | { "type": "text", text: string, } | { "type": "tool_use", id: string, tool_name: string, tool_input: JsonValue, 
+/**
+ * What the call does, from [`summarize`]. None in transcripts
+ * recorded before summaries existed.
+ */
+summary: ToolSummary | null, } | { "type": "tool_result", tool_use_id: string, content: JsonValue, is_error: boolean | null, 
+/**
+ * Set when the content was too large to carry: its full size in
+ * bytes. `content` is then a text preview; `fetch_full_content`
+ * returns the original.
+ */
+full_size: number | null, } | { "type": "result", result: string | null, session_id: string | null, 
+/**
+ * What the run cost, as reported by claude.
+ */
+cost_usd: number | null, };
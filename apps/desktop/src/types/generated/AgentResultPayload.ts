@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload emitted when the agent run completes.
+ */
+export type AgentResultPayload = { agent_id: string, ticket_id: string, session_id: string | null, };
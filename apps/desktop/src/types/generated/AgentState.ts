@@ -0,0 +1,40 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentStatus } from "./AgentStatus";
+
+/**
+ * Everything we know about a running (or idle) agent.
+ */
+export type AgentState = { id: string, name: string, role: string, personality: string, status: AgentStatus, 
+/**
+ * The ticket this agent is currently working on, if any.
+ */
+current_ticket_id: string | null, 
+/**
+ * The Claude Code session ID, used for --resume.
+ */
+session_id: string | null, 
+/**
+ * Path to the git worktree, if one is active.
+ */
+worktree_path: string | null, 
+/**
+ * The open PR number, if one exists.
+ */
+pr_number: number | null, 
+/**
+ * Persistent chat session ID (separate from ticket session_id).
+ */
+chat_session_id: string | null, 
+/**
+ * True while processing a chat message.
+ */
+chatting: boolean, 
+/**
+ * Per-agent initiative override: "auto", "ask", "suggest", or "off".
+ */
+initiative: string | null, 
+/**
+ * One-line "currently doing X" the agent set through `set_status_note`.
+ * Cleared when it goes idle.
+ */
+status_note: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentState } from "./AgentState";
+
+/**
+ * Payload for `agent-state-changed`. `agent` is None once removed.
+ */
+export type AgentStateChangedPayload = { agent_id: string, agent: AgentState | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The statuses an agent can be in.
+ */
+export type AgentStatus = "idle" | "working" | "waiting_for_user" | "reviewing" | "blocked";
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How one agent's time split over a window.
+ */
+export type AgentUtilization = { agent_id: string, 
+/**
+ * Distinct tickets with a successful run in the window.
+ */
+tickets_completed: number, 
+/**
+ * Working or reviewing.
+ */
+working_secs: number, waiting_secs: number, idle_secs: number, blocked_secs: number, 
+/**
+ * This agent's fraction of all agents' working time, 0–1.
+ */
+work_share: number, };
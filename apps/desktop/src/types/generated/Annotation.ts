@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A human note attached to one canvas node, e.g. "this assumption was wrong".
+ */
+export type Annotation = { id: string, node_id: string, text: string, 
+/**
+ * Optional marker shown on the node: "wrong", "important", "question", …
+ */
+flag: string | null, 
+/**
+ * Unix milliseconds, last edit.
+ */
+updated_at: number, };
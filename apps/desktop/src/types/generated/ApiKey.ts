@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An Anthropic API key, or an enterprise gateway, from the vault. Held in
+ * memory and never written to disk by the backend.
+ */
+export type ApiKey = { 
+/**
+ * Label usage is accounted under, e.g. "team-payments".
+ */
+id: string, 
+/**
+ * May be empty for gateways that authenticate some other way.
+ */
+key: string, 
+/**
+ * Gateway to send requests through instead of the Anthropic API.
+ */
+base_url: string | null, };
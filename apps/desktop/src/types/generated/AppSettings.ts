@@ -0,0 +1,62 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BudgetConfig } from "./BudgetConfig";
+import type { CoalesceConfig } from "./CoalesceConfig";
+import type { EditorConfig } from "./EditorConfig";
+import type { EventFilter } from "./EventFilter";
+import type { McpListenConfig } from "./McpListenConfig";
+import type { ProviderHost } from "./ProviderHost";
+import type { RelayConfig } from "./RelayConfig";
+import type { SchedulerConfig } from "./SchedulerConfig";
+import type { StandupConfig } from "./StandupConfig";
+import type { SyncConfig } from "./SyncConfig";
+import type { TelemetryConfig } from "./TelemetryConfig";
+import type { TranscriptionConfig } from "./TranscriptionConfig";
+
+/**
+ * App-wide settings persisted at `$HOME/.poietai/settings.json`.
+ */
+export type AppSettings = { provider_hosts: Array<ProviderHost>, 
+/**
+ * Scheduled agent check-ins; off when unset.
+ */
+standup: StandupConfig | null, 
+/**
+ * Daily/weekly spending caps; unlimited when unset.
+ */
+budget: BudgetConfig | null, 
+/**
+ * Editor for "open in editor"; VS Code when unset.
+ */
+editor: EditorConfig | null, 
+/**
+ * Merge bursts of Text/Thinking events into one node; off when unset.
+ */
+coalesce_events: CoalesceConfig | null, 
+/**
+ * Event kinds to keep off the canvas and out of transcripts.
+ */
+event_filter: EventFilter, 
+/**
+ * Concurrent ticket runs, queued by priority; unlimited when unset.
+ */
+scheduler: SchedulerConfig | null, 
+/**
+ * Anonymous feature usage counts; off when unset.
+ */
+telemetry: TelemetryConfig | null, 
+/**
+ * Turns voice-memo answers into text; voice answers off when unset.
+ */
+transcription: TranscriptionConfig | null, 
+/**
+ * Push agent questions to a phone with reply links; off when unset.
+ */
+relay: RelayConfig | null, 
+/**
+ * Share roster, boards and transcripts with other machines; off when unset.
+ */
+sync: SyncConfig | null, 
+/**
+ * Where the MCP server listens; an OS-assigned loopback port by default.
+ */
+mcp: McpListenConfig, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Actor } from "./Actor";
+import type { AuditKind } from "./AuditKind";
+
+/**
+ * One privileged action, appended to `$HOME/.poietai/audit.jsonl`.
+ */
+export type AuditEntry = { 
+/**
+ * Unix seconds.
+ */
+at: number, actor: Actor, kind: AuditKind, 
+/**
+ * Command name or what was done, e.g. "approve_checkpoint".
+ */
+action: string, agent_id: string | null, ticket_id: string | null, 
+/**
+ * Never a secret: token entries name the credential, not its value.
+ */
+detail: string, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Actor } from "./Actor";
+import type { AuditKind } from "./AuditKind";
+
+/**
+ * What `get_audit_log` returns. Every set field must match.
+ */
+export type AuditFilter = { actor: Actor | null, kind: AuditKind | null, agent_id: string | null, ticket_id: string | null, 
+/**
+ * Unix seconds, inclusive.
+ */
+since: number | null, 
+/**
+ * Unix seconds, exclusive.
+ */
+until: number | null, 
+/**
+ * Case-insensitive text in the action or detail.
+ */
+text: string | null, 
+/**
+ * Newest entries kept; 500 when unset.
+ */
+limit: number | null, };
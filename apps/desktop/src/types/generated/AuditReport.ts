@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AdvisoryFinding } from "./AdvisoryFinding";
+import type { TrackedAdvisory } from "./TrackedAdvisory";
+
+export type AuditReport = { 
+/**
+ * New advisories, grouped by package, for the frontend to add as tickets
+ * and then [`track`].
+ */
+findings: Array<AdvisoryFinding>, tracked: Array<TrackedAdvisory>, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How to prepare a fresh worktree before the agent starts.
+ */
+export type BootstrapConfig = { 
+/**
+ * Shell command run in the worktree, e.g. "pnpm install --frozen-lockfile".
+ */
+command: string, 
+/**
+ * Share one pnpm content store across all worktrees of the project.
+ */
+share_pnpm_store: boolean, 
+/**
+ * Symlink the worktree's `target/` to the main checkout's, so cargo reuses
+ * built dependencies. Concurrent builds will block on cargo's lock.
+ */
+link_target_dir: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Spending caps across all projects.
+ */
+export type BudgetConfig = { daily_usd: number | null, 
+/**
+ * Weeks start Monday, local time.
+ */
+weekly_usd: number | null, 
+/**
+ * Also stop multi-phase runs at their next phase boundary once over
+ * budget, instead of letting them finish the ticket.
+ */
+pause_running: boolean, };
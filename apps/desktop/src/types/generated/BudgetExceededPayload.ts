@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `budget-exceeded`.
+ */
+export type BudgetExceededPayload = { 
+/**
+ * "daily" or "weekly".
+ */
+period: string, spent_usd: number, cap_usd: number, };
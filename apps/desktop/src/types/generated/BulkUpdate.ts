@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which of the requested tickets a bulk operation applies to.
+ */
+export type BulkUpdate = { updated: Array<string>, 
+/**
+ * Ids not on the board — deleted since the selection was made.
+ */
+missing: Array<string>, };
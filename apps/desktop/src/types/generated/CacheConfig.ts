@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Build caches shared by every worktree of a project, injected into the agent
+ * environment so each ticket doesn't start from a cold build.
+ */
+export type CacheConfig = { 
+/**
+ * Wrap rustc with `sccache`. Safe with concurrent builds; requires
+ * `sccache` on PATH or every cargo invocation fails.
+ */
+sccache: boolean, 
+/**
+ * Point every worktree at one `CARGO_TARGET_DIR`. Big win for monorepos,
+ * but concurrent agents serialise on cargo's build lock and `cargo clean`
+ * in one worktree wipes the others.
+ */
+shared_cargo_target: boolean, 
+/**
+ * Share one pnpm content store and npm download cache. Both are
+ * content-addressed, so concurrent installs are safe.
+ */
+shared_node_store: boolean, };
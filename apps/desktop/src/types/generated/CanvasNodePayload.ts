@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentEvent } from "./AgentEvent";
+
+/**
+ * Payload sent to the React frontend for each canvas node.
+ */
+export type CanvasNodePayload = { node_id: string, agent_id: string, ticket_id: string, kind: AgentEvent, group_id: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Release track this install follows. Riskier capabilities reach nightly
+ * first, then beta, then stable.
+ */
+export type Channel = "stable" | "beta" | "nightly";
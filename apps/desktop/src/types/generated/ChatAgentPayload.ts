@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload from React to start a chat session with an agent.
+ */
+export type ChatAgentPayload = { agent_id: string, message: string, 
+/**
+ * Full system prompt — only used on cold start (no existing chat_session_id).
+ */
+system_prompt: string, 
+/**
+ * State deltas injected via --append-system-prompt on resume.
+ */
+context_update: string, 
+/**
+ * Sent by the app on its own, e.g. waking an agent messaged by another,
+ * rather than by the user. Only changes how the message is audited.
+ */
+automated: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A completed check on the PR's head commit.
+ */
+export type CheckStatus = { name: string, url: string, 
+/**
+ * Actions run id, when the check is a GitHub Actions job.
+ */
+run_id: number | null, failed: boolean, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A kind of tool call a human signs off on before the agent may make it.
+ */
+export type Checkpoint = { 
+/**
+ * Shown to the user, e.g. "Database migrations".
+ */
+name: string, 
+/**
+ * Tools it applies to, e.g. ["Bash"] or ["Edit", "Write"].
+ */
+tools: Array<string>, 
+/**
+ * Text that, found in the call's command or the path it writes, stops
+ * the run.
+ */
+patterns: Array<string>, };
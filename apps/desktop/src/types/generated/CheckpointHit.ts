@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A run stopped at a checkpoint, awaiting sign-off.
+ */
+export type CheckpointHit = { id: string, agent_id: string, ticket_id: string, 
+/**
+ * The checkpoint's name.
+ */
+checkpoint: string, tool_name: string, 
+/**
+ * The command or path that matched.
+ */
+detail: string, 
+/**
+ * The session an approval resumes.
+ */
+session_id: string | null, 
+/**
+ * Unix seconds.
+ */
+reached_at: number, 
+/**
+ * Approved checkpoints don't stop the agent again on this ticket.
+ */
+approved: boolean, };
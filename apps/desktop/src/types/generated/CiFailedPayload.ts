@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CheckStatus } from "./CheckStatus";
+
+/**
+ * Payload for `ci-failed`.
+ */
+export type CiFailedPayload = { agent_id: string, ticket_id: string, pr_number: number, 
+/**
+ * The failed checks.
+ */
+checks: Array<CheckStatus>, 
+/**
+ * 1-based auto-fix attempt, or None once attempts are used up.
+ */
+attempt: number | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Resume the agent with failing CI logs when its PR's checks fail.
+ */
+export type CiTriageConfig = { 
+/**
+ * Auto-fix attempts per PR before leaving it to a human.
+ */
+max_attempts: number, 
+/**
+ * Lines kept from the end of each failing run's log.
+ */
+log_tail_lines: number, };
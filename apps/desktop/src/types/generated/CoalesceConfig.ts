@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Merge runs of Text (or Thinking) events into one canvas node.
+ */
+export type CoalesceConfig = { 
+/**
+ * How long to hold an event for followers to merge into it. Also the
+ * most a merged event is delayed.
+ */
+window_ms: number, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TourStop } from "./TourStop";
+
+/**
+ * A structured orientation to a repository, written once by a read-only
+ * agent and given to later runs that work in the code so they don't start
+ * cold.
+ */
+export type CodebaseTour = { summary: string, entry_points: Array<TourStop>, modules: Array<TourStop>, data_flow: string, conventions: Array<string>, 
+/**
+ * Commit the tour was written at; later commits may have moved things.
+ */
+commit: string | null, 
+/**
+ * Unix seconds.
+ */
+generated_at: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One finished run's spend, appended to `$HOME/.poietai/costs.jsonl`.
+ */
+export type CostEntry = { 
+/**
+ * Unix seconds the run finished.
+ */
+at: number, agent_id: string, ticket_id: string, cost_usd: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CriterionResult = { criterion: string, passed: boolean, 
+/**
+ * Where in the diff or test output the verdict comes from.
+ */
+evidence: string, };
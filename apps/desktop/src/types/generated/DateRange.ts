@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Local calendar days, both ends included, as `YYYY-MM-DD`.
+ */
+export type DateRange = { from: string, to: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One day of activity, for a heatmap cell.
+ */
+export type DayBucket = { date: string, runs: number, succeeded: number, failed: number, duration_secs: number, cost_usd: number, };
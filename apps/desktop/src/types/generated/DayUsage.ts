@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Feature } from "./Feature";
+
+/**
+ * One day's counts, as uploaded.
+ */
+export type DayUsage = { date: string, counts: { [key in Feature]?: number }, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a `poietai://` link points. Emitted to the frontend as `deep-link`.
+ */
+export type DeepLink = { "kind": "ticket", ticket_id: string, } | { "kind": "agent", agent_id: string, } | { "kind": "question", agent_id: string, question_id: string, };
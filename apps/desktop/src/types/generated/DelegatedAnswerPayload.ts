@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `agent-question-delegated`, emitted when a senior agent
+ * answered in place of the human. Also appended to the project's
+ * `.poietai/delegated-answers.jsonl`.
+ */
+export type DelegatedAnswerPayload = { agent_id: string, senior_agent_id: string, question: string, answer: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Offer `ask_human` questions to a senior agent before interrupting the human.
+ */
+export type DelegationConfig = { 
+/**
+ * The agent (normally a staff engineer) that gets first look at questions.
+ */
+senior_agent_id: string, 
+/**
+ * Turn cap for the consult run.
+ */
+max_turns: number, 
+/**
+ * Give up and escalate to the human after this long.
+ */
+timeout_secs: number, };
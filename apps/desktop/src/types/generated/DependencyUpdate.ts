@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutdatedDependency } from "./OutdatedDependency";
+import type { TicketDraft } from "./TicketDraft";
+
+/**
+ * One ticket's worth of upgrades: an ecosystem's breaking or non-breaking
+ * updates, with the ticket to add and the command gating its PR.
+ */
+export type DependencyUpdate = { 
+/**
+ * "cargo" or "npm".
+ */
+ecosystem: string, 
+/**
+ * Major bumps (minor bumps below 1.0), which may need code changes.
+ */
+breaking: boolean, dependencies: Array<OutdatedDependency>, ticket: TicketDraft, 
+/**
+ * Must pass before the agent may open the PR; pass it as `pr_gate`.
+ */
+test_command: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffLine } from "./DiffLine";
+
+export type DiffHunk = { old_start: number, old_lines: number, new_start: number, new_lines: number, 
+/**
+ * Text after the `@@ … @@` marker, usually the enclosing function.
+ */
+section: string, lines: Array<DiffLine>, };
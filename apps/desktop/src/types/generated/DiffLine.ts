@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffLineKind } from "./DiffLineKind";
+
+export type DiffLine = { kind: DiffLineKind, content: string, 
+/**
+ * Line number on the base side (None for added lines).
+ */
+old_line: number | null, 
+/**
+ * Line number in the worktree (None for removed lines).
+ */
+new_line: number | null, };
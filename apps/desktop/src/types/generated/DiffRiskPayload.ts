@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RiskReport } from "./RiskReport";
+
+/**
+ * Payload for `diff-risk-scored`, emitted once a build finishes.
+ */
+export type DiffRiskPayload = { agent_id: string, ticket_id: string, report: RiskReport, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A project's definition of done for build runs. Each rule is both stated
+ * in the agent's prompt and checked against the worktree afterwards.
+ */
+export type DodPolicy = { 
+/**
+ * The change must add or modify tests.
+ */
+tests_added: boolean, 
+/**
+ * The change must touch documentation.
+ */
+docs_updated: boolean, 
+/**
+ * The change must not add TODO/FIXME comments.
+ */
+no_new_todos: boolean, 
+/**
+ * Must exit 0 in the worktree, e.g. "cargo clippy -- -D warnings".
+ */
+lint_command: string | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When a worktree counts as too far behind origin's default branch.
+ */
+export type DriftConfig = { 
+/**
+ * Commits on origin the worktree's branch doesn't have.
+ */
+max_commits: number, 
+/**
+ * Days since the oldest of those commits landed.
+ */
+max_days: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EditorKind } from "./EditorKind";
+
+/**
+ * The editor `open_in_editor` launches.
+ */
+export type EditorConfig = { kind: EditorKind, 
+/**
+ * CLI to run instead of the kind's default ("code", "cursor", "idea"),
+ * e.g. "code-insiders" or a full path.
+ */
+command: string | null, };
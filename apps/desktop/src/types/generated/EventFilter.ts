@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which agent events reach the canvas and the transcript. Everything is
+ * kept by default; `result` always is, since resume depends on it.
+ */
+export type EventFilter = { hide_thinking: boolean, hide_text: boolean, hide_tool_use: boolean, hide_tool_results: boolean, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TranscriptEntry } from "./TranscriptEntry";
+
+/**
+ * A window of a transcript, for virtualized canvases that load older
+ * nodes on demand.
+ */
+export type EventPage = { entries: Array<TranscriptEntry>, 
+/**
+ * Pass back as `cursor` for the entries before these; None at the start.
+ */
+cursor: number | null, 
+/**
+ * Entries in the whole transcript.
+ */
+total: number, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the claude CLI runs for a project's agents.
+ */
+export type ExecutionBackend = { "kind": "host" } | { "kind": "devcontainer" } | { "kind": "docker", 
+/**
+ * Image with claude and the project toolchain installed.
+ */
+image: string, 
+/**
+ * Extra `docker run` flags, e.g. `["--cpus", "4"]`.
+ */
+run_args: Array<string>, } | { "kind": "ssh", 
+/**
+ * `user@host`, or an alias from `~/.ssh/config`.
+ */
+host: string, port: number | null, 
+/**
+ * Private key to authenticate with, if not the ssh default.
+ */
+identity_file: string | null, 
+/**
+ * Absolute path of the repo's clone on the remote host.
+ */
+remote_repo_root: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A feature use worth counting. Only the kind is kept — never agents,
+ * tickets, repos or text.
+ */
+export type Feature = "run_started" | "run_resumed" | "question_answered";
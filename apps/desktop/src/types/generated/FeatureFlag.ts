@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FeatureFlag = { key: string, description: string, enabled: boolean, 
+/**
+ * Whether the user set this flag rather than getting the channel default.
+ */
+overridden: boolean, };
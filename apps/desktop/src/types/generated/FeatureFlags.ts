@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Channel } from "./Channel";
+import type { FeatureFlag } from "./FeatureFlag";
+
+export type FeatureFlags = { channel: Channel, flags: Array<FeatureFlag>, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiffHunk } from "./DiffHunk";
+
+/**
+ * One file's changes between the base branch and the worktree, including
+ * uncommitted edits.
+ */
+export type FileDiff = { path: string, binary: boolean, hunks: Array<DiffHunk>, };
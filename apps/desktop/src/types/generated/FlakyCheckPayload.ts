@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `flaky-check`.
+ */
+export type FlakyCheckPayload = { repo: string, pr_number: number, agent_id: string, ticket_id: string, check_name: string, };
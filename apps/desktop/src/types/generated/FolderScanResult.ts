@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RepoInfo } from "./RepoInfo";
+
+export type FolderScanResult = { "type": "single_repo", name: string, repo_root: string, remote_url: string | null, provider: string | null, 
+/**
+ * Current branch; None when HEAD is detached.
+ */
+branch: string | null, 
+/**
+ * Upstream tracking branch, e.g. "origin/main".
+ */
+upstream: string | null, 
+/**
+ * Commits on the branch not yet on upstream.
+ */
+ahead: number, 
+/**
+ * Commits on upstream not yet on the branch.
+ */
+behind: number, 
+/**
+ * Modified, staged, or conflicted tracked files.
+ */
+has_uncommitted_changes: boolean, has_untracked_files: boolean, 
+/**
+ * Submodule paths declared in `.gitmodules`. Fresh worktrees need
+ * `git submodule update --init` before these contain any code.
+ */
+submodules: Array<string>, 
+/**
+ * Top-level directories that are independent git repos but not
+ * submodules. These never appear in worktrees.
+ */
+nested_repos: Array<string>, 
+/**
+ * Tracks files with Git LFS — worktrees need a `git lfs pull`.
+ */
+uses_lfs: boolean, } | { "type": "multi_repo", repos: Array<RepoInfo>, suggested_name: string, } | { "type": "no_repo" };
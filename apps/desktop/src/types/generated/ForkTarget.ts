@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where an agent pushes and opens its PR when working from a fork.
+ */
+export type ForkTarget = { 
+/**
+ * The upstream `owner/repo` the PR targets.
+ */
+upstream: string, 
+/**
+ * The fork's `owner/repo`.
+ */
+full_name: string, 
+/**
+ * Login of the fork owner — used as the `--head owner:branch` prefix.
+ */
+owner: string, clone_url: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of `test_gateway`.
+ */
+export type GatewayCheck = { 
+/**
+ * The gateway answered and accepted the credentials.
+ */
+ok: boolean, 
+/**
+ * HTTP status, when the gateway answered at all.
+ */
+status: number | null, message: string, };
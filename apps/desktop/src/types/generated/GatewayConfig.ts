@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An LLM gateway (LiteLLM, an internal proxy) the project's model traffic
+ * must go through.
+ */
+export type GatewayConfig = { 
+/**
+ * Sent as ANTHROPIC_BASE_URL, e.g. "https://llm.internal.example.com".
+ */
+base_url: string, 
+/**
+ * Extra request headers as "Name: value", e.g. a tenant or cost-center tag.
+ */
+headers: Array<string>, 
+/**
+ * Further env for the claude process, e.g. ANTHROPIC_MODEL pinned to a
+ * model the gateway serves.
+ */
+env: { [key in string]?: string }, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * GitHub App credentials, read from the vault by the frontend and passed in
+ * per run. The private key never touches disk on the backend side.
+ */
+export type GitHubAppCredentials = { app_id: string, installation_id: string, 
+/**
+ * PEM-encoded RSA private key downloaded from the App settings page.
+ */
+private_key_pem: string, };
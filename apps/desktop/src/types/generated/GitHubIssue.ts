@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An open issue, for importing as a ticket.
+ */
+export type GitHubIssue = { number: number, title: string, body: string, labels: Array<string>, milestone: string | null, url: string, };
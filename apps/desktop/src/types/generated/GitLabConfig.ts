@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Connection details for a (possibly self-hosted) GitLab instance.
+ * The token itself lives in the vault and is passed in per run.
+ */
+export type GitLabConfig = { 
+/**
+ * Base URL of the instance, e.g. "https://git.example.com".
+ */
+base_url: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An open issue, for importing as a ticket. Shaped like a GitHub issue so
+ * both import the same way.
+ */
+export type GitLabIssue = { 
+/**
+ * The project-scoped issue number (`iid`).
+ */
+number: number, title: string, body: string, labels: Array<string>, milestone: string | null, url: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for the `agent-handoff` event and the `handoff_ticket` result.
+ */
+export type HandoffPayload = { from_agent_id: string, to_agent_id: string, ticket_id: string, worktree_path: string, 
+/**
+ * Handoff note generated from the outgoing agent's session — include it
+ * in the prompt when starting the receiving agent.
+ */
+summary: string, };
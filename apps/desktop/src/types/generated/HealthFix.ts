@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What would fix a problem, for the UI to offer as a button.
+ */
+export type HealthFix = { "kind": "run", program: string, args: Array<string>, } | { "kind": "create_dir", path: string, } | { "kind": "edit_setting", key: string, } | { "kind": "restart_app" };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HealthCheck } from "./HealthCheck";
+import type { HealthFix } from "./HealthFix";
+import type { Severity } from "./Severity";
+
+export type HealthProblem = { check: HealthCheck, severity: Severity, message: string, fix: HealthFix | null, };
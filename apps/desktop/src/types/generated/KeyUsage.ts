@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Runs and spend billed to one key.
+ */
+export type KeyUsage = { key_id: string, runs: number, cost_usd: number, 
+/**
+ * Unix seconds the key was last handed to a run.
+ */
+last_used_at: number | null, };
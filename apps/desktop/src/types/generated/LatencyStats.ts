@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PrTiming } from "./PrTiming";
+
+/**
+ * Review latency across tracked PRs.
+ */
+export type LatencyStats = { median_to_first_review_secs: number | null, p90_to_first_review_secs: number | null, median_to_merge_secs: number | null, p90_to_merge_secs: number | null, 
+/**
+ * Open PRs nobody has reviewed yet.
+ */
+awaiting_review: number, prs: Array<PrTiming>, };
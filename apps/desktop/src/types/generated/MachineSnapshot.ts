@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentState } from "./AgentState";
+import type { SyncedProject } from "./SyncedProject";
+
+/**
+ * What one machine last published.
+ */
+export type MachineSnapshot = { machine_id: string, machine_name: string, 
+/**
+ * Unix seconds.
+ */
+updated_at: number, 
+/**
+ * The roster, with whatever each agent is running there.
+ */
+agents: Array<AgentState>, projects: Array<SyncedProject>, };
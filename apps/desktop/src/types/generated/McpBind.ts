@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Interfaces the MCP server listens on. Agents on this machine connect
+ * through 127.0.0.1 either way.
+ */
+export type McpBind = "loopback" | "all_interfaces";
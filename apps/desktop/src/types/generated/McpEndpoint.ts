@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { McpBind } from "./McpBind";
+
+/**
+ * Where the MCP server ended up listening, for diagnostics.
+ */
+export type McpEndpoint = { 
+/**
+ * SSE endpoint agents on this machine connect to. Each run adds its own
+ * `?token=`; the server refuses this bare URL.
+ */
+url: string, port: number, bind: McpBind, 
+/**
+ * The configured port, when it was taken and another was used.
+ */
+requested_port: number | null, 
+/**
+ * The server answered an HTTP request on `/sse` just now.
+ */
+reachable: boolean, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { McpBind } from "./McpBind";
+
+/**
+ * Where the MCP server listens. Read at startup; changes take effect after
+ * a restart.
+ */
+export type McpListenConfig = { bind: McpBind, 
+/**
+ * Fixed port to listen on; OS-assigned when unset.
+ */
+port: number | null, 
+/**
+ * Ports after `port` to try when it's taken, before falling back to an
+ * OS-assigned one.
+ */
+fallback_ports: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OnboardingStep } from "./OnboardingStep";
+
+/**
+ * Payload of `get_onboarding_state` and `onboarding-state-changed`.
+ */
+export type OnboardingState = { 
+/**
+ * The first step neither done nor skipped.
+ */
+step: OnboardingStep, claude_version: string | null, gh_authed: boolean, project_scanned: boolean, agent_created: boolean, skipped: Array<OnboardingStep>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * First-run setup, in the order the wizard walks it.
+ */
+export type OnboardingStep = "detect_claude" | "connect_github" | "scan_project" | "create_agent" | "done";
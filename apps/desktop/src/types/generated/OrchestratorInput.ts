@@ -0,0 +1,54 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ForkTarget } from "./ForkTarget";
+import type { GitHubAppCredentials } from "./GitHubAppCredentials";
+import type { PairConfig } from "./PairConfig";
+import type { TestFirstConfig } from "./TestFirstConfig";
+import type { TournamentConfig } from "./TournamentConfig";
+
+/**
+ * Configuration passed from the frontend/Tauri command when starting a ticket run.
+ */
+export type OrchestratorInput = { agent_id: string, ticket_id: string, ticket_slug: string, prompt: string, system_prompt: string, repo_root: string, gh_token: string, 
+/**
+ * When set, each phase mints a fresh App installation token in place of
+ * `gh_token`, so long tickets outlive the token's hour.
+ */
+github_app: GitHubAppCredentials | null, phase: string, worktree_path_override: string | null, plan_artifact: string | null, 
+/**
+ * Optional group ID — set during fan-out builds so agent events carry the
+ * group identifier through to the frontend.
+ */
+group_id: string | null, 
+/**
+ * GitLab token for projects configured against a GitLab instance.
+ */
+gitlab_token: string | null, 
+/**
+ * Set when agents push to a fork and open cross-repo PRs.
+ */
+fork: ForkTarget | null, 
+/**
+ * Continue this session instead of starting a fresh one.
+ */
+resume_session_id: string | null, 
+/**
+ * Cap on agentic turns for the run.
+ */
+max_turns: number | null, 
+/**
+ * Build in pair-programming mode: this agent drives, another reviews.
+ */
+pair: PairConfig | null, 
+/**
+ * Build with several agents in parallel and let the user pick a winner.
+ */
+tournament: TournamentConfig | null, 
+/**
+ * Build test-first: a QA agent writes failing tests, then this agent
+ * makes them pass.
+ */
+test_first: TestFirstConfig | null, 
+/**
+ * Shell command that must pass before the agent may open a PR.
+ */
+pr_gate: string | null, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OrchestratorQuestionPayload = { ticket_id: string, agent_id: string, content: string, session_id: string, };
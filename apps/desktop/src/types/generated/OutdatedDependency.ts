@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A dependency with a newer release than the one the lockfile pins.
+ */
+export type OutdatedDependency = { name: string, current: string, latest: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Pair-programming settings for a build.
+ */
+export type PairConfig = { 
+/**
+ * The agent that critiques each driver turn.
+ */
+reviewer_agent_id: string, 
+/**
+ * Driver/reviewer exchanges before the pair gives up without approval.
+ */
+max_rounds: number, 
+/**
+ * Agentic turns each side gets per round.
+ */
+turns_per_round: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Emitted after each driver or reviewer turn in pair mode.
+ */
+export type PairTurnPayload = { ticket_id: string, round: number, agent_id: string, 
+/**
+ * "driver" or "reviewer".
+ */
+role: string, message: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Paths a role may edit, as globs relative to the repo root
+ * (`*` within a segment, `**` across segments, `?` one character).
+ */
+export type PathRule = { 
+/**
+ * When non-empty, edits must match one of these.
+ */
+allow: Array<string>, 
+/**
+ * Edits matching any of these are forbidden, even when allowed.
+ */
+deny: Array<string>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `path-violation`.
+ */
+export type PathViolationPayload = { agent_id: string, ticket_id: string, role: string, tool_name: string, path: string, 
+/**
+ * Whether the run was stopped.
+ */
+interrupted: boolean, };
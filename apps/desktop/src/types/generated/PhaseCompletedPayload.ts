@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+import type { PolicyReport } from "./PolicyReport";
+
+export type PhaseCompletedPayload = { ticket_id: string, phase: string, artifact_content: string | null, result_summary: JsonValue | null, blocked: boolean, 
+/**
+ * Definition-of-done results for build phases, when the project has a policy.
+ */
+policy_report: PolicyReport | null, };
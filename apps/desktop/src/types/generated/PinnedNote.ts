@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A standing correction for one agent, e.g. "never touch the legacy module".
+ */
+export type PinnedNote = { id: string, text: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PolicyCheck = { 
+/**
+ * "tests_added", "docs_updated", "no_new_todos" or "lint_clean".
+ */
+rule: string, passed: boolean, detail: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PolicyCheck } from "./PolicyCheck";
+
+/**
+ * How a build run measured up to the project's definition of done.
+ */
+export type PolicyReport = { checks: Array<PolicyCheck>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `pr-poll-stopped`: a PR or MR is no longer watched although
+ * it's still open, so later reviews and CI failures won't reach the agent.
+ */
+export type PollStoppedPayload = { agent_id: string, ticket_id: string, pr_number: number, polls: number, };
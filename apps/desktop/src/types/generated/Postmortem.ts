@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PostmortemChange } from "./PostmortemChange";
+
+/**
+ * What went wrong on a failed run or a PR closed unmerged, and what to
+ * change before trying again.
+ */
+export type Postmortem = { ticket_id: string, agent_id: string, summary: string, causes: Array<string>, changes: Array<PostmortemChange>, 
+/**
+ * The PR the review history came from, if any.
+ */
+pr_number: number | null, 
+/**
+ * Unix seconds.
+ */
+generated_at: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Something to do differently on the next attempt.
+ */
+export type PostmortemChange = { 
+/**
+ * "prompt", "scope", "tooling" or "other".
+ */
+area: string, suggestion: string, };
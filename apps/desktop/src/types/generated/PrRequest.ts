@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An agent's request to open a PR, held until the user decides.
+ */
+export type PrRequest = { id: string, agent_id: string, ticket_id: string, worktree_path: string, 
+/**
+ * The session approval resumes.
+ */
+session_id: string | null, 
+/**
+ * From the agent's `gh pr create --title`, when it gave one.
+ */
+title: string | null, 
+/**
+ * `git diff --stat` against the worktree's base.
+ */
+diff_summary: string, 
+/**
+ * Unix seconds.
+ */
+requested_at: number, 
+/**
+ * Approved requests let the agent open its PR for the rest of the ticket.
+ */
+approved: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A single PR review from GitHub.
+ */
+export type PrReview = { author: string, body: string, state: string, submitted_at: string, };
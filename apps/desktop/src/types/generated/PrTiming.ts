@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How long an agent PR waited on people. Times are RFC 3339, from GitHub.
+ */
+export type PrTiming = { repo: string, pr_number: number, agent_id: string, ticket_id: string, opened_at: string | null, 
+/**
+ * The first review by someone other than a bot.
+ */
+first_review_at: string | null, merged_at: string | null, 
+/**
+ * Closed without merging; no longer refreshed.
+ */
+closed: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How soon a ticket should get a free agent slot.
+ */
+export type Priority = "low" | "normal" | "high" | "urgent";
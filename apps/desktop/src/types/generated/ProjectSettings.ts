@@ -0,0 +1,94 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BootstrapConfig } from "./BootstrapConfig";
+import type { CacheConfig } from "./CacheConfig";
+import type { Checkpoint } from "./Checkpoint";
+import type { CiTriageConfig } from "./CiTriageConfig";
+import type { DelegationConfig } from "./DelegationConfig";
+import type { DodPolicy } from "./DodPolicy";
+import type { DriftConfig } from "./DriftConfig";
+import type { ExecutionBackend } from "./ExecutionBackend";
+import type { GatewayConfig } from "./GatewayConfig";
+import type { GitLabConfig } from "./GitLabConfig";
+import type { ProjectsSyncConfig } from "./ProjectsSyncConfig";
+import type { ResourceLimits } from "./ResourceLimits";
+import type { RiskConfig } from "./RiskConfig";
+import type { RolePaths } from "./RolePaths";
+import type { VerifyConfig } from "./VerifyConfig";
+import type { WorkingHours } from "./WorkingHours";
+
+/**
+ * Per-project settings persisted at `<project_root>/.poietai/settings.json`.
+ */
+export type ProjectSettings = { gitlab: GitLabConfig | null, 
+/**
+ * Always work from a fork, even when the token can push upstream.
+ */
+fork_mode: boolean, 
+/**
+ * Leave submodules uninitialised in new worktrees (e.g. huge vendored repos).
+ */
+skip_submodule_init: boolean, bootstrap: BootstrapConfig | null, cache: CacheConfig, 
+/**
+ * Where agents run: on the host, or in the project's dev environment.
+ */
+execution: ExecutionBackend, 
+/**
+ * CPU/memory caps for agent runs (host and Docker backends).
+ */
+limits: ResourceLimits, 
+/**
+ * Let a senior agent try ask_human questions before they reach the user.
+ */
+delegate_questions: DelegationConfig | null, 
+/**
+ * Defer runs and slow PR polling outside these hours; always on when unset.
+ */
+working_hours: WorkingHours | null, 
+/**
+ * Check acceptance criteria after a successful build; off when unset.
+ */
+verify_acceptance: VerifyConfig | null, 
+/**
+ * Rules every build must meet, stated to the agent and checked after.
+ */
+definition_of_done: DodPolicy | null, 
+/**
+ * Feed failing CI logs back to the agent; off when unset.
+ */
+ci_triage: CiTriageConfig | null, 
+/**
+ * GitHub Projects board mirroring ticket status; off when unset.
+ */
+github_project: ProjectsSyncConfig | null, 
+/**
+ * Per-role path allow/deny globs, enforced before each write, Bash included.
+ */
+role_paths: RolePaths | null, 
+/**
+ * Paths no agent may write without a human's approval, e.g. `/.github/workflows`.
+ */
+protected_paths: Array<string>, 
+/**
+ * Hold agents' `gh pr create` until the user approves the PR.
+ */
+require_pr_approval: boolean, 
+/**
+ * Tool calls that pause the run until the user signs off.
+ */
+checkpoints: Array<Checkpoint>, 
+/**
+ * Route agents' model traffic through this gateway; direct when unset.
+ */
+gateway: GatewayConfig | null, 
+/**
+ * Critical paths and other inputs to agent diff risk scores.
+ */
+risk: RiskConfig, 
+/**
+ * How far behind origin a worktree may fall before `worktree-stale`.
+ */
+drift: DriftConfig, 
+/**
+ * WSL distro agents run in on Windows; the one holding the repo when unset.
+ */
+wsl_distro: string | null, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Mirror ticket status changes to a GitHub Projects (v2) board.
+ */
+export type ProjectsSyncConfig = { 
+/**
+ * User or organization that owns the board.
+ */
+owner: string, 
+/**
+ * The number in the board's URL: github.com/orgs/<owner>/projects/<number>.
+ */
+number: number, 
+/**
+ * Single-select field holding the column.
+ */
+status_field: string, 
+/**
+ * Ticket status → option name, overriding the Todo/In Progress/Done defaults.
+ */
+status_map: { [key in string]?: string }, };
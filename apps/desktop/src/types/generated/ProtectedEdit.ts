@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A write to a protected path, refused before it happened and awaiting a
+ * human.
+ */
+export type ProtectedEdit = { id: string, agent_id: string, ticket_id: string, tool_name: string, 
+/**
+ * Repo-relative path the agent tried to write.
+ */
+path: string, 
+/**
+ * The session an approval resumes.
+ */
+session_id: string | null, 
+/**
+ * Unix seconds.
+ */
+requested_at: number, 
+/**
+ * Approved edits let the agent write the path for the rest of the ticket.
+ */
+approved: boolean, };
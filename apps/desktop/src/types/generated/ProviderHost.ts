@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A user-defined mapping from a git host to a provider, for self-hosted
+ * instances that `detect_provider` can't recognise from the URL alone.
+ */
+export type ProviderHost = { 
+/**
+ * Hostname as it appears in remote URLs, e.g. "git.example.com".
+ */
+host: string, 
+/**
+ * Provider key: "github", "gitlab", "bitbucket", or "azure".
+ */
+provider: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One question an agent asked while working a ticket, and how it was answered.
+ */
+export type QaEntry = { 
+/**
+ * Unix seconds the question was settled.
+ */
+at: number, agent_id: string, question: string, 
+/**
+ * None when the question timed out or the app closed before a reply.
+ */
+answer: string | null, 
+/**
+ * "human", or the id of the senior agent that answered in its place.
+ */
+answered_by: string | null, 
+/**
+ * Root of the project that owns the ticket. None for
+ * entries recorded before this was kept.
+ */
+repo_root: string | null, };
@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Priority } from "./Priority";
+import type { QuestionKind } from "./QuestionKind";
+import type { SuggestedAnswer } from "./SuggestedAnswer";
+
+/**
+ * A waiting question as shown in the inbox.
+ */
+export type QuestionInfo = { id: string, agent_id: string, kind: QuestionKind, question: string, 
+/**
+ * Choices (present_choices) or action details (confirm_action).
+ */
+details: unknown, 
+/**
+ * The priority the agent asked with.
+ */
+priority: Priority, 
+/**
+ * Priority after aging — what the inbox sorts by.
+ */
+effective_priority: Priority, 
+/**
+ * Unix seconds when the question was asked.
+ */
+asked_at: number, waiting_secs: number, 
+/**
+ * An earlier answer to a similar question on the same project.
+ */
+suggested_answer: SuggestedAnswer | null, };
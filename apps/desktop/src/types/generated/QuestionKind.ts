@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which tool asked — decides how the UI renders the answer controls.
+ */
+export type QuestionKind = "question" | "choices" | "confirm";
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where agent questions are pushed, and how the phone reaches this machine.
+ * Read at startup; changes take effect after a restart.
+ */
+export type RelayConfig = { 
+/**
+ * A ntfy topic URL, e.g. "https://ntfy.sh/poietai-7f3a" or a self-hosted one.
+ * Anyone who knows the topic sees the questions, so pick an unguessable name.
+ */
+topic_url: string, 
+/**
+ * This machine as the phone sees it, e.g. "http://192.168.1.20:7878".
+ */
+public_url: string, 
+/**
+ * Port the reply form is served on, on all interfaces.
+ */
+port: number, };
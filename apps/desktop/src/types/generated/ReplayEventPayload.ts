@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CanvasNodePayload } from "./CanvasNodePayload";
+
+/**
+ * Payload for `replay-event`: a recorded canvas node, re-emitted on its
+ * original schedule. Kept apart from `agent-event` so replays never touch
+ * live run state.
+ */
+export type ReplayEventPayload = { replay_id: string, node: CanvasNodePayload, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `replay-finished`, sent after the last event or when stopped.
+ */
+export type ReplayFinishedPayload = { replay_id: string, stopped: boolean, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PushMode } from "./PushMode";
+
+/**
+ * Result of the repository permission pre-flight.
+ */
+export type RepoAccess = { repo: string, default_branch: string, 
+/**
+ * True when the default branch has protection rules (required reviews,
+ * status checks, no direct pushes, ...).
+ */
+default_branch_protected: boolean, can_push: boolean, push_mode: PushMode, 
+/**
+ * Things the user should know before the run starts.
+ */
+warnings: Array<string>, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Repositories-within-the-repository that need special handling.
+ */
+export type RepoLayout = { 
+/**
+ * Submodule paths declared in `.gitmodules`. Fresh worktrees need
+ * `git submodule update --init` before these contain any code.
+ */
+submodules: Array<string>, 
+/**
+ * Top-level directories that are independent git repos but not
+ * submodules. These never appear in worktrees.
+ */
+nested_repos: Array<string>, 
+/**
+ * Tracks files with Git LFS — worktrees need a `git lfs pull`.
+ */
+uses_lfs: boolean, };
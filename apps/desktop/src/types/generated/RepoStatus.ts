@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Working-copy state, so the UI can warn about dirty or stale repos before
+ * agents branch from them.
+ */
+export type RepoStatus = { 
+/**
+ * Current branch; None when HEAD is detached.
+ */
+branch: string | null, 
+/**
+ * Upstream tracking branch, e.g. "origin/main".
+ */
+upstream: string | null, 
+/**
+ * Commits on the branch not yet on upstream.
+ */
+ahead: number, 
+/**
+ * Commits on upstream not yet on the branch.
+ */
+behind: number, 
+/**
+ * Modified, staged, or conflicted tracked files.
+ */
+has_uncommitted_changes: boolean, has_untracked_files: boolean, };
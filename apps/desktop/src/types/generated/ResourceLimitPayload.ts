@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `agent-resource-limit`, emitted when a run hits its caps.
+ */
+export type ResourceLimitPayload = { agent_id: string, ticket_id: string, 
+/**
+ * "throttled" or "oom_killed".
+ */
+kind: string, detail: string, };
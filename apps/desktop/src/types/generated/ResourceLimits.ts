@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * CPU and memory caps for an agent's process tree, so a runaway build can't
+ * lock up the machine.
+ */
+export type ResourceLimits = { 
+/**
+ * Maximum CPU cores, e.g. 2.0 or 0.5.
+ */
+cpus: number | null, 
+/**
+ * Maximum memory in MiB. The process tree is killed when it exceeds this.
+ */
+memory_mb: number | null, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What to put in a resume prompt besides the human's reply.
+ */
+export type ResumeInclude = { 
+/**
+ * Reviews on the agent's PR that ask for changes or comment.
+ */
+reviews: boolean, 
+/**
+ * Checks failing on the PR's head commit.
+ */
+failed_checks: boolean, 
+/**
+ * Commits that landed on the default branch since the worktree's base.
+ */
+new_commits_on_main: boolean, 
+/**
+ * The human's reply, leading the prompt.
+ */
+user_reply: string | null, };
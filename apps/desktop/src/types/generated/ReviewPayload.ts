@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PrReview } from "./PrReview";
+
+/**
+ * Payload emitted to React when a new CI review arrives.
+ */
+export type ReviewPayload = { agent_id: string, ticket_id: string, pr_number: number, review: PrReview, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What makes a change risky in this project.
+ */
+export type RiskConfig = { 
+/**
+ * Globs relative to the repo root, e.g. "src/auth/" or "**/payments/**".
+ */
+critical_paths: Array<string>, };
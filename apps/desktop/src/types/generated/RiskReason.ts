@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type RiskReason = { 
+/**
+ * "critical_path", "size", "deleted_tests" or "migration".
+ */
+kind: string, detail: string, points: number, };
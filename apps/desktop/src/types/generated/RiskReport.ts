@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RiskReason } from "./RiskReason";
+
+/**
+ * How closely reviewers should look at an agent's change, and where.
+ */
+export type RiskReport = { 
+/**
+ * 0 (routine) to 100.
+ */
+score: number, 
+/**
+ * "low", "medium" or "high".
+ */
+level: string, reasons: Array<RiskReason>, files_changed: number, lines_changed: number, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PathRule } from "./PathRule";
+import type { Strictness } from "./Strictness";
+
+/**
+ * Per-role path restrictions for a project.
+ */
+export type RolePaths = { 
+/**
+ * Role (e.g. "frontend-engineer") → its rule. Roles without one are unrestricted.
+ */
+roles: { [key in string]?: PathRule }, strictness: Strictness, };
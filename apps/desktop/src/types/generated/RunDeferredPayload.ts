@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Emitted when a run is held until the project's working hours open.
+ */
+export type RunDeferredPayload = { agent_id: string, ticket_id: string, 
+/**
+ * Local RFC 3339 time the run will start.
+ */
+until: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A run that was queued or in flight, journaled so a crash doesn't lose it.
+ */
+export type RunMarker = { run_id: string, agent_id: string, ticket_id: string, worktree_path: string, 
+/**
+ * Unix seconds the run was started or queued.
+ */
+started_at: number, 
+/**
+ * The claude session, once it reported one — what a resume continues.
+ */
+session_id: string | null, 
+/**
+ * Local RFC 3339 time a deferred run was waiting for. None once running.
+ */
+queued_until: string | null, 
+/**
+ * The ticket run this is part of — a pair reviewer, fan-out group or
+ * tournament entry. Journaled once it reports a session.
+ */
+parent_run_id: string | null, };
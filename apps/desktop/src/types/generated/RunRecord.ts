@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Outcome } from "./Outcome";
+
+/**
+ * One finished agent process, appended to `$HOME/.poietai/metrics/runs.jsonl`.
+ */
+export type RunRecord = { 
+/**
+ * Unix seconds.
+ */
+started_at: number, finished_at: number, agent_id: string, ticket_id: string, cost_usd: number, outcome: Outcome, };
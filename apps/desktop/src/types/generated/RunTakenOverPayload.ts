@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `run-taken-over`.
+ */
+export type RunTakenOverPayload = { ticket_id: string, previous_agent_id: string, agent_id: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Cap on simultaneous ticket runs.
+ */
+export type SchedulerConfig = { max_concurrent: number, 
+/**
+ * Pause the lowest-priority run, keeping its session, when an urgent
+ * ticket finds every slot taken.
+ */
+preempt: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Narrow a transcript search. Empty/None fields don't filter.
+ */
+export type SearchFilters = { agent_id: string | null, ticket_id: string | null, 
+/**
+ * Event kinds to include: "text", "thinking", "tool_use", "tool_result", "result".
+ */
+kinds: Array<string>, 
+/**
+ * Unix milliseconds, inclusive.
+ */
+since: number | null, until: number | null, limit: number | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One matching event, best matches first.
+ */
+export type SearchHit = { agent_id: string, ticket_id: string, node_id: string, at: number, kind: string, 
+/**
+ * Matching excerpt with hits wrapped in `[` `]`.
+ */
+snippet: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GitHubAppCredentials } from "./GitHubAppCredentials";
+
+/**
+ * Tokens for replaying a journaled run, from the vault.
+ */
+export type Secrets = { gh_token: string, gitlab_token: string | null, github_app: GitHubAppCredentials | null, };
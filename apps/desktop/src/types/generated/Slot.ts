@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Priority } from "./Priority";
+
+/**
+ * A run holding, or waiting for, a slot.
+ */
+export type Slot = { 
+/**
+ * The run holding or waiting for the slot; its lock and journal entry
+ * share the id.
+ */
+run_id: string, ticket_id: string, agent_id: string, priority: Priority, };
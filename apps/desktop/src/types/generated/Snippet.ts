@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A reusable block of instructions, e.g. "security checklist".
+ */
+export type Snippet = { 
+/**
+ * Assigned on first save.
+ */
+id: string, name: string, body: string, 
+/**
+ * Roles whose runs always get this snippet.
+ */
+roles: Array<string>, 
+/**
+ * Tickets whose runs get this snippet, whatever the role.
+ */
+tickets: Array<string>, };
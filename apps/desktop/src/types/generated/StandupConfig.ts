@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When to run check-ins and which model to spend on them.
+ */
+export type StandupConfig = { 
+/**
+ * Local times of day, "HH:MM".
+ */
+times: Array<string>, 
+/**
+ * A cheap model — check-ins only summarise what's already in the session.
+ */
+model: string, };
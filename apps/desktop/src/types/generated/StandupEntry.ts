@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One agent's check-in.
+ */
+export type StandupEntry = { agent_id: string, agent_name: string, ticket_id: string | null, summary: string, blockers: Array<string>, next_step: string, 
+/**
+ * Set when the check-in itself failed.
+ */
+error: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StandupEntry } from "./StandupEntry";
+
+/**
+ * Payload for `standup-report`: every active agent's check-in in one place.
+ */
+export type StandupReport = { 
+/**
+ * RFC 3339 local time the report was generated.
+ */
+generated_at: string, entries: Array<StandupEntry>, };
@@ -0,0 +1,65 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GitHubAppCredentials } from "./GitHubAppCredentials";
+import type { PairConfig } from "./PairConfig";
+import type { TestFirstConfig } from "./TestFirstConfig";
+import type { TournamentConfig } from "./TournamentConfig";
+
+/**
+ * Payload from React to start an agent on a ticket.
+ * Matches the shape React sends via invoke("start_agent", { payload: { ... } }).
+ */
+export type StartAgentPayload = { agent_id: string, ticket_id: string, ticket_slug: string, prompt: string, system_prompt: string, repo_root: string, gh_token: string, 
+/**
+ * When set, mint a GitHub App installation token and use it instead of
+ * `gh_token`, so PRs are attributed to the App's bot identity.
+ */
+github_app: GitHubAppCredentials | null, resume_session_id: string | null, 
+/**
+ * The current ticket phase (e.g. "brief", "design", "plan", "build", etc.).
+ * Defaults to Build if absent or unrecognised.
+ */
+phase: string | null, 
+/**
+ * When set, skip worktree creation and run in this directory instead.
+ * Used by VALIDATE phase to reuse the BUILD agent's worktree.
+ */
+worktree_path_override: string | null, 
+/**
+ * Optional JSON plan artifact from the Plan phase — used by the orchestrator
+ * to determine whether to fan-out the Build phase across parallel task groups.
+ */
+plan_artifact: string | null, 
+/**
+ * GitLab token from the vault — only used when the project has a GitLab config.
+ */
+gitlab_token: string | null, 
+/**
+ * Build in pair-programming mode with a reviewer agent.
+ */
+pair: PairConfig | null, 
+/**
+ * Build with several agents in parallel and compare the results.
+ */
+tournament: TournamentConfig | null, 
+/**
+ * Build test-first: a QA agent writes failing tests before the build.
+ */
+test_first: TestFirstConfig | null, 
+/**
+ * Shell command that must pass before the agent may open a PR, e.g. the
+ * `test_command` of a dependency update.
+ */
+pr_gate: string | null, 
+/**
+ * Start immediately even outside the project's working hours.
+ */
+run_now: boolean, 
+/**
+ * Stop whichever run holds the ticket's worktree instead of failing.
+ */
+force_takeover: boolean, 
+/**
+ * Started by the app on its own, e.g. resuming after a restart, rather
+ * than by the user. Only changes how the start is audited.
+ */
+automated: boolean, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An earlier answer to a question like the one being asked, offered so it
+ * can be reused instead of typed again.
+ */
+export type SuggestedAnswer = { question: string, answer: string, 
+/**
+ * Ticket the earlier question was asked on.
+ */
+ticket_id: string, 
+/**
+ * Word overlap between the two questions, 0 to 1.
+ */
+similarity: number, };
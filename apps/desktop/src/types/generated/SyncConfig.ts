@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A folder every machine sees (Syncthing, Dropbox, a network mount) that
+ * carries each machine's roster, boards and transcripts to the others.
+ * Each machine only writes under its own id, so nothing is ever merged.
+ */
+export type SyncConfig = { folder: string, 
+/**
+ * How the other machines label this one; the hostname when empty.
+ */
+machine_name: string, };
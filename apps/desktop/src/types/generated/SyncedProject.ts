@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A project's board as one machine last saved it.
+ */
+export type SyncedProject = { 
+/**
+ * The origin remote URL, or the folder name for repos without one, so
+ * both machines' clones of a repo share a key.
+ */
+key: string, 
+/**
+ * The project's `.poietai/tickets.json` as the frontend wrote it.
+ */
+tickets: unknown, };
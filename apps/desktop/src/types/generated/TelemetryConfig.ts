@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Opt-in usage counting. Nothing is recorded while this is unset.
+ */
+export type TelemetryConfig = { 
+/**
+ * Where to POST daily aggregates; counts stay local when unset.
+ */
+upload_url: string | null, };
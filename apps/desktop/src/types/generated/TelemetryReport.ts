@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayUsage } from "./DayUsage";
+import type { Usage } from "./Usage";
+
+/**
+ * What `get_telemetry` shows: the stored data and the next upload, verbatim.
+ */
+export type TelemetryReport = { enabled: boolean, upload_url: string | null, usage: Usage, pending_upload: Array<DayUsage>, };
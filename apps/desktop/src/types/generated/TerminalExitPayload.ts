@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `terminal-exit`, sent once the shell has exited.
+ */
+export type TerminalExitPayload = { session_id: string, exit_code: number | null, };
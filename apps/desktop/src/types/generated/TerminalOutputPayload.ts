@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `terminal-output`.
+ */
+export type TerminalOutputPayload = { session_id: string, data: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Test-first settings: who writes the tests and how they're run.
+ */
+export type TestFirstConfig = { 
+/**
+ * Writes failing tests for the acceptance criteria before any code.
+ */
+qa_agent_id: string, 
+/**
+ * Shell command run in the worktree between phases, e.g. "cargo test".
+ */
+test_command: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Emitted after each phase of a test-first build with the suite's result.
+ */
+export type TestFirstStatusPayload = { ticket_id: string, 
+/**
+ * "tests_written" after the QA run, "implemented" after the build.
+ */
+stage: string, passed: boolean, 
+/**
+ * The last lines of the test command's output.
+ */
+output: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Priority } from "./Priority";
+
+/**
+ * Fields to set on every selected ticket. Unset fields are left alone.
+ */
+export type TicketChanges = { status: string | null, complexity: number | null, priority: Priority | null, tags: Array<string> | null, labels: Array<string> | null, milestone: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A ticket ready for the board, shaped like the frontend's `addTicket` input.
+ */
+export type TicketDraft = { title: string, description: string, complexity: number, acceptanceCriteria: Array<string>, tags: Array<string>, };
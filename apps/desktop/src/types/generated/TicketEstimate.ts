@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of a quick read-only analysis of a ticket, shown on the board.
+ */
+export type TicketEstimate = { ticket_id: string, 
+/**
+ * 1 (trivial) to 10 (very large).
+ */
+complexity: number, files: Array<string>, risks: Array<string>, summary: string, 
+/**
+ * Unix seconds.
+ */
+estimated_at: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Starting points for a new ticket.
+ */
+export type TicketTemplate = "bugfix" | "feature" | "refactor" | "spike";
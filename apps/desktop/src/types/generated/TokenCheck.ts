@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a token is able to do against one repository.
+ */
+export type TokenCheck = { 
+/**
+ * Classic token scopes from `X-OAuth-Scopes`; `None` for fine-grained
+ * tokens and GitHub App tokens, which don't report scopes.
+ */
+scopes: Array<string> | null, 
+/**
+ * Whether the token can push branches (and therefore open PRs from them).
+ */
+can_push: boolean, 
+/**
+ * Human-readable scope problems that will make the run fail. Empty = OK.
+ */
+problems: Array<string>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The gist of a tool call, so the frontend needn't parse each tool's input.
+ */
+export type ToolSummary = { 
+/**
+ * One line for the canvas: a path, a command, a pattern.
+ */
+label: string, file_path: string | null, 
+/**
+ * 1-based line range read, when the call was limited to one.
+ */
+lines: [number, number | null] | null, command: string | null, };
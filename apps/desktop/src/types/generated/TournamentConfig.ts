@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Tournament settings: who competes and how entries are compared.
+ */
+export type TournamentConfig = { 
+/**
+ * One attempt per agent, each in its own worktree.
+ */
+agent_ids: Array<string>, 
+/**
+ * Shell command run in each worktree afterwards, e.g. "cargo test".
+ */
+test_command: string | null, 
+/**
+ * Agent that reads every diff and ranks the attempts.
+ */
+judge_agent_id: string | null, };
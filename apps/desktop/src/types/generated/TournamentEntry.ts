@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One contestant's result.
+ */
+export type TournamentEntry = { agent_id: string, worktree_path: string, branch: string, files_changed: number, insertions: number, deletions: number, 
+/**
+ * None when no test command is configured.
+ */
+tests_passed: boolean | null, 
+/**
+ * Tail of the test command's output.
+ */
+test_output: string, 
+/**
+ * Set when the attempt itself failed.
+ */
+error: string | null, 
+/**
+ * 1 is best.
+ */
+rank: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TournamentEntry } from "./TournamentEntry";
+
+/**
+ * Payload for `tournament-results`.
+ */
+export type TournamentPayload = { ticket_id: string, entries: Array<TournamentEntry>, judge_rationale: string | null, };
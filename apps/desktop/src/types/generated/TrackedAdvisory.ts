@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Advisory } from "./Advisory";
+
+/**
+ * An advisory whose ticket is on the board, and how far its fix has got.
+ */
+export type TrackedAdvisory = { advisory: Advisory, ticket_id: string, pr_number: number | null, 
+/**
+ * Set once an audit no longer reports it.
+ */
+resolved: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgentEvent } from "./AgentEvent";
+
+/**
+ * One persisted canvas event.
+ */
+export type TranscriptEntry = { 
+/**
+ * Unix milliseconds the event was received.
+ */
+at: number, node_id: string, kind: AgentEvent, group_id: string | null, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How voice-memo answers to agent questions are turned into text.
+ */
+export type TranscriptionConfig = { "kind": "whisper", 
+/**
+ * The `whisper-cli` binary; looked up on PATH when bare.
+ */
+binary: string, 
+/**
+ * Absolute path of a ggml model, e.g. "/opt/whisper/ggml-base.en.bin".
+ */
+model: string, 
+/**
+ * Spoken language, e.g. "en"; auto-detected when unset.
+ */
+language: string | null, } | { "kind": "api", 
+/**
+ * e.g. "https://api.openai.com/v1".
+ */
+base_url: string, 
+/**
+ * e.g. "whisper-1".
+ */
+model: string, };
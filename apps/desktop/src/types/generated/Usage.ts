@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Feature } from "./Feature";
+
+/**
+ * Everything telemetry keeps, at `$HOME/.poietai/metrics/telemetry.json`.
+ */
+export type Usage = { 
+/**
+ * Random, generated on opt-in, tying uploads from one install together.
+ */
+install_id: string, 
+/**
+ * Feature counts per local day (`YYYY-MM-DD`).
+ */
+days: { [key in string]?: { [key in Feature]?: number } }, 
+/**
+ * Last day already uploaded.
+ */
+uploaded_through: string | null, };
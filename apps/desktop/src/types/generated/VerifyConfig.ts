@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Check acceptance criteria after a successful build, before the PR.
+ */
+export type VerifyConfig = { 
+/**
+ * Run in the worktree first; its result and output tail go to the checker.
+ */
+test_command: string | null, max_turns: number, timeout_secs: number, };
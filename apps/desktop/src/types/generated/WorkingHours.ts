@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When agents may start work unattended. Runs requested outside the window
+ * are deferred until it opens, unless started with "run now anyway".
+ */
+export type WorkingHours = { 
+/**
+ * ISO weekdays the window applies to: 1 = Monday … 7 = Sunday.
+ */
+days: Array<number>, 
+/**
+ * Local "HH:MM" the window opens.
+ */
+start: string, 
+/**
+ * Local "HH:MM" the window closes. Earlier than `start` wraps past midnight.
+ */
+end: string, };
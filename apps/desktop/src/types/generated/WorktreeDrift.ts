@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a worktree stands against origin's default branch.
+ */
+export type WorktreeDrift = { 
+/**
+ * e.g. "origin/main".
+ */
+base: string, behind: number, days_behind: number, stale: boolean, 
+/**
+ * Shell command that brings the worktree up to date.
+ */
+rebase_command: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for `worktree-file-changed`.
+ */
+export type WorktreeFileChangedPayload = { agent_id: string, worktree_path: string, 
+/**
+ * Paths relative to the worktree, sorted and deduplicated.
+ */
+paths: Array<string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Progress from post-creation worktree setup (LFS pull, bootstrap, ...).
+ */
+export type WorktreeSetupPayload = { ticket_id: string, agent_id: string, step: string, message: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorktreeDrift } from "./WorktreeDrift";
+
+/**
+ * Payload for `worktree-stale`.
+ */
+export type WorktreeStalePayload = { agent_id: string, ticket_id: string | null, worktree_path: string, drift: WorktreeDrift, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WslToolStatus } from "./WslToolStatus";
+
+/**
+ * Whether a distro can host agent runs.
+ */
+export type WslHealth = { distro: string, tools: Array<WslToolStatus>, };
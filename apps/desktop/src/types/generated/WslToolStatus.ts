@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A CLI as found, or not, inside a distro.
+ */
+export type WslToolStatus = { tool: string, 
+/**
+ * First line of `<tool> --version`, when it ran.
+ */
+version: string | null, 
+/**
+ * Why it didn't, when it didn't.
+ */
+error: string | null, };
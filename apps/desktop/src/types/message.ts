@@ -27,6 +27,7 @@ export interface DmMessage {
   replyCount?: number;
   lastReplyAt?: number;
   sessionId?: string;
+  questionId?: string;           // the backend's id for a question, for deep links
 }
 
 export interface Channel {